@@ -1,13 +1,14 @@
-use prometheus_exporter::run_prometheus_exporter;
+use prometheus_exporter::{run_prometheus_exporter, PrometheusTransport};
 use structopt::StructOpt;
 use tokio::{sync::oneshot, sync::watch};
 
 use zksync_config::configs::{
-    AlertsConfig, CircuitSynthesizerConfig, PrometheusConfig, ProverGroupConfig,
+    AlertsConfig, CircuitSynthesizerConfig, PrometheusConfig, PrometheusExporterMode,
+    ProverGroupConfig,
 };
 use zksync_dal::connection::DbVariant;
 use zksync_dal::ConnectionPool;
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{BlobKind, ObjectStoreFactory};
 use zksync_queued_job_processor::JobProcessor;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
@@ -29,11 +30,12 @@ async fn main() {
     let opt = Opt::from_args();
     let config: CircuitSynthesizerConfig = CircuitSynthesizerConfig::from_env();
     let pool = ConnectionPool::new(None, DbVariant::Prover).await;
+    let store_router = ObjectStoreFactory::from_env_multi();
 
     let circuit_synthesizer = CircuitSynthesizer::new(
         config.clone(),
         ProverGroupConfig::from_env(),
-        &ObjectStoreFactory::from_env(),
+        store_router.factory(BlobKind::WitnessInputs),
         pool,
     )
     .await
@@ -58,9 +60,13 @@ async fn main() {
         listener_port: config.prometheus_listener_port,
         pushgateway_url: config.prometheus_pushgateway_url,
         push_interval_ms: config.prometheus_push_interval_ms,
+        exporter_mode: PrometheusExporterMode::Pull,
+        collect_runtime_metrics: None,
     };
     let tasks = vec![
-        run_prometheus_exporter(prometheus_config.listener_port, None),
+        run_prometheus_exporter(PrometheusTransport::Pull {
+            port: prometheus_config.listener_port,
+        }),
         tokio::spawn(circuit_synthesizer.run(stop_receiver, opt.number_of_iterations)),
     ];
 
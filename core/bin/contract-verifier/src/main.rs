@@ -163,7 +163,11 @@ async fn main() {
         // The prover connection pool is not used by the contract verifier, but we need to pass it
         // since `JobProcessor` trait requires it.
         tokio::spawn(contract_verifier.run(stop_receiver, opt.jobs_number)),
-        prometheus_exporter::run_prometheus_exporter(prometheus_config.listener_port, None),
+        prometheus_exporter::run_prometheus_exporter(
+            prometheus_exporter::PrometheusTransport::Pull {
+                port: prometheus_config.listener_port,
+            },
+        ),
     ];
 
     let particular_crypto_alerts = None;
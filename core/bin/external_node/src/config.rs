@@ -3,9 +3,12 @@ use serde::Deserialize;
 use std::{env, time::Duration};
 use url::Url;
 
-use zksync_basic_types::{Address, L1ChainId, L2ChainId, H256};
+use zksync_basic_types::{Address, L1BatchNumber, L1ChainId, L2ChainId, H256};
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_core::api_server::{tx_sender::TxSenderConfig, web3::state::InternalApiConfig};
+use zksync_core::{
+    api_server::{tx_sender::TxSenderConfig, web3::state::InternalApiConfig},
+    sync_layer::ActionQueue,
+};
 use zksync_types::api::BridgeAddresses;
 
 use zksync_web3_decl::{
@@ -110,6 +113,28 @@ pub struct OptionalENConfig {
     vm_concurrency_limit: Option<usize>,
     /// Smart contract source code cache size for the API server.
     factory_deps_cache_size_mb: Option<usize>,
+    /// Maximum number of actions in the sync layer action queue. If the queue is full,
+    /// the fetcher waits until the state keeper processes some of the queued actions.
+    action_queue_capacity: Option<usize>,
+    /// Whether to resume syncing after the node state is reverted because of a detected batch reorg.
+    /// If disabled, the node exits after the revert, so that it can be restarted by the operator.
+    reorg_detector_auto_revert: Option<bool>,
+    /// Maximum number of L1 batches that may be reverted automatically. If a reorg requires reverting
+    /// more batches, the node halts. Only applies if `reorg_detector_auto_revert` is enabled.
+    reorg_detector_max_revert_depth: Option<u32>,
+    /// L1 batch to recover the node state from if the node database is empty. If not set, the node
+    /// performs genesis instead. Note that the Merkle tree is not recovered from the snapshot, so the metadata
+    /// calculator fails on start if its RocksDB is empty.
+    snapshot_recovery_l1_batch: Option<u32>,
+    /// Max number of block hashes returned for a block filter by a single `eth_getFilterChanges` call.
+    max_block_hash_batch: Option<usize>,
+    /// Max number of blocks that may be scanned by a single `eth_getLogs`-style query.
+    max_getlogs_block_range: Option<u32>,
+    /// Min base fee per gas returned for blocks by the API. Lower base fees are raised to this value
+    /// in API responses. If not set, base fees are returned as is.
+    pub min_base_fee_per_gas: Option<u64>,
+    /// Max number of entries in the cache mapping miniblocks to L1 batches including them.
+    l1_batch_number_cache_size: Option<u64>,
 }
 
 impl OptionalENConfig {
@@ -125,6 +150,18 @@ impl OptionalENConfig {
         self.filters_limit.unwrap_or(10000)
     }
 
+    pub fn max_block_hash_batch(&self) -> usize {
+        self.max_block_hash_batch.unwrap_or(1024)
+    }
+
+    pub fn max_getlogs_block_range(&self) -> u32 {
+        self.max_getlogs_block_range.unwrap_or(100_000)
+    }
+
+    pub fn l1_batch_number_cache_size(&self) -> u64 {
+        self.l1_batch_number_cache_size.unwrap_or(100_000)
+    }
+
     pub fn subscriptions_limit(&self) -> usize {
         self.subscriptions_limit.unwrap_or(10000)
     }
@@ -169,6 +206,23 @@ impl OptionalENConfig {
         // 128MB is the default smart contract code cache size.
         self.factory_deps_cache_size_mb.unwrap_or(128)
     }
+
+    pub fn action_queue_capacity(&self) -> usize {
+        self.action_queue_capacity
+            .unwrap_or(ActionQueue::DEFAULT_CAPACITY)
+    }
+
+    pub fn reorg_detector_auto_revert(&self) -> bool {
+        self.reorg_detector_auto_revert.unwrap_or(false)
+    }
+
+    pub fn reorg_detector_max_revert_depth(&self) -> u32 {
+        self.reorg_detector_max_revert_depth.unwrap_or(10)
+    }
+
+    pub fn snapshot_recovery_l1_batch(&self) -> Option<L1BatchNumber> {
+        self.snapshot_recovery_l1_batch.map(L1BatchNumber)
+    }
 }
 
 /// This part of the external node config is required for its operation.
@@ -329,6 +383,13 @@ impl From<ExternalNodeConfig> for InternalApiConfig {
             diamond_proxy_addr: config.remote.diamond_proxy_addr,
             l2_testnet_paymaster_addr: config.remote.l2_testnet_paymaster_addr,
             req_entities_limit: config.optional.req_entities_limit(),
+            max_block_hash_batch: config.optional.max_block_hash_batch(),
+            max_getlogs_block_range: config.optional.max_getlogs_block_range(),
+            min_base_fee_per_gas: config.optional.min_base_fee_per_gas,
+            l1_batch_number_cache_size: config.optional.l1_batch_number_cache_size(),
+            // The external node proxies transactions to the main node and has no mempool,
+            // so an assembled pending block would never contain transactions.
+            assemble_pending_block: None,
         }
     }
 }
@@ -345,6 +406,8 @@ impl From<ExternalNodeConfig> for TxSenderConfig {
             validation_computational_gas_limit: config.required.validation_computational_gas_limit,
             default_aa: config.required.default_aa_hash,
             bootloader: config.required.bootloader_hash,
+            // The external node doesn't cap the L1 gas price, since it's fetched from the main node.
+            max_l1_gas_price_for_estimation: None,
         }
     }
 }
@@ -1,5 +1,10 @@
-use prometheus_exporter::run_prometheus_exporter;
-use tokio::{sync::watch, task, time::sleep};
+use futures::future;
+use prometheus_exporter::{run_prometheus_exporter, PrometheusTransport};
+use tokio::{
+    sync::watch,
+    task,
+    time::{sleep, Instant},
+};
 use zksync_state::FactoryDepsCache;
 
 use config::ExternalNodeConfig;
@@ -7,7 +12,9 @@ use std::{sync::Arc, time::Duration};
 use zksync_basic_types::Address;
 use zksync_config::DBConfig;
 
-use zksync_core::api_server::healthcheck::HealthCheckHandle;
+use zksync_core::api_server::{
+    healthcheck::HealthCheckHandle, web3::api_health_check::ApiHealthCheck,
+};
 use zksync_core::{
     api_server::{
         execution_sandbox::VmConcurrencyLimiter, healthcheck, tx_sender::TxSenderBuilder,
@@ -17,24 +24,31 @@ use zksync_core::{
     consistency_checker::ConsistencyChecker,
     l1_gas_price::MainNodeGasPriceFetcher,
     metadata_calculator::{
-        MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig,
+        MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig, TreeHealthCheck,
     },
     reorg_detector::ReorgDetector,
     setup_sigint_handler,
+    shutdown::{shutdown_reason_channel, ShutdownReason},
     state_keeper::{MainBatchExecutorBuilder, SealManager, ZkSyncStateKeeper},
     sync_layer::{
-        batch_status_updater::BatchStatusUpdater, external_io::ExternalIO,
-        fetcher::MainNodeFetcher, genesis::perform_genesis_if_needed, ActionQueue,
-        ExternalNodeSealer, SyncState,
+        batch_status_updater::BatchStatusUpdater,
+        external_io::ExternalIO,
+        fetcher::MainNodeFetcher,
+        genesis::{perform_genesis_if_needed, recover_from_snapshot},
+        ActionQueue, ExternalNodeSealer, SyncState,
     },
 };
 use zksync_dal::{connection::DbVariant, healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
 use zksync_health_check::CheckHealth;
+use zksync_object_store::{BlobKind, ObjectStoreFactory};
 use zksync_storage::RocksDB;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
 mod config;
 
+/// Time given to the sync components to stop before they are aborted.
+const SYNC_TASKS_STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Creates the state keeper configured to work in the external node mode.
 async fn build_state_keeper(
     action_queue: ActionQueue,
@@ -84,79 +98,19 @@ async fn build_state_keeper(
     ZkSyncStateKeeper::new(stop_receiver, io, batch_executor_base, sealer)
 }
 
+/// Initializes the components that keep running for the entire lifetime of the node: the API servers,
+/// the gas price fetcher and the Prometheus exporter.
 async fn init_tasks(
-    config: ExternalNodeConfig,
+    config: &ExternalNodeConfig,
     connection_pool: ConnectionPool,
-) -> (
-    Vec<task::JoinHandle<()>>,
-    watch::Sender<bool>,
-    HealthCheckHandle,
-) {
+    sync_state: SyncState,
+    stop_receiver: watch::Receiver<bool>,
+) -> (Vec<task::JoinHandle<()>>, Vec<ApiHealthCheck>) {
     let main_node_url = config
         .required
         .main_node_url()
         .expect("Main node URL is incorrect");
-    let (stop_sender, stop_receiver) = watch::channel::<bool>(false);
-    let mut healthchecks: Vec<Box<dyn CheckHealth>> = Vec::new();
-    // Create components.
     let gas_adjuster = Arc::new(MainNodeGasPriceFetcher::new(&main_node_url));
-
-    let sync_state = SyncState::new();
-    let action_queue = ActionQueue::new();
-    let state_keeper = build_state_keeper(
-        action_queue.clone(),
-        config.required.state_cache_path.clone(),
-        main_node_url.to_string(),
-        connection_pool.clone(),
-        sync_state.clone(),
-        config.remote.l2_erc20_bridge_addr,
-        stop_receiver.clone(),
-    )
-    .await;
-    let fetcher = MainNodeFetcher::new(
-        ConnectionPool::new(Some(1), DbVariant::Master).await,
-        &main_node_url,
-        action_queue.clone(),
-        sync_state.clone(),
-        stop_receiver.clone(),
-    )
-    .await;
-
-    let metadata_calculator = MetadataCalculator::new(&MetadataCalculatorConfig {
-        db_path: &config.required.merkle_tree_path,
-        mode: MetadataCalculatorModeConfig::Lightweight,
-        delay_interval: config.optional.metadata_calculator_delay(),
-        max_block_batch: config.optional.max_blocks_per_tree_batch(),
-        throttle_interval: config.optional.merkle_tree_throttle(),
-    })
-    .await;
-    healthchecks.push(Box::new(metadata_calculator.tree_health_check()));
-
-    let consistency_checker = ConsistencyChecker::new(
-        &config
-            .required
-            .eth_client_url()
-            .expect("L1 client URL is incorrect"),
-        10,
-        ConnectionPool::new(Some(1), DbVariant::Master).await,
-    );
-
-    let batch_status_updater = BatchStatusUpdater::new(
-        &main_node_url,
-        ConnectionPool::new(Some(1), DbVariant::Master).await,
-    )
-    .await;
-
-    // Run the components.
-    let tree_stop_receiver = stop_receiver.clone();
-    let tree_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
-    let prover_tree_pool = ConnectionPool::new(Some(1), DbVariant::Prover).await;
-    let tree_handle =
-        task::spawn(metadata_calculator.run(tree_pool, prover_tree_pool, tree_stop_receiver));
-    let consistency_checker_handle = tokio::spawn(consistency_checker.run(stop_receiver.clone()));
-    let updater_handle = task::spawn(batch_status_updater.run(stop_receiver.clone()));
-    let sk_handle = task::spawn(state_keeper.run());
-    let fetcher_handle = tokio::spawn(fetcher.run());
     let gas_adjuster_handle = tokio::spawn(gas_adjuster.clone().run(stop_receiver.clone()));
 
     let tx_sender = {
@@ -207,42 +161,158 @@ async fn init_tasks(
             .with_threads(config.required.threads_per_server)
             .with_tx_sender(tx_sender)
             .with_sync_state(sync_state)
-            .build(stop_receiver.clone())
+            .build(stop_receiver)
             .await;
 
-    healthchecks.push(Box::new(ws_api_healthcheck));
-    healthchecks.push(Box::new(http_api_healthcheck));
-    healthchecks.push(Box::new(ConnectionPoolHealthCheck::new(
-        ConnectionPool::new(Some(1), DbVariant::Master).await,
-    )));
-    let healthcheck_handle = healthcheck::start_server_thread_detached(
-        ([0, 0, 0, 0], config.required.healthcheck_port).into(),
-        healthchecks,
-    );
     if let Some(port) = config.optional.prometheus_port {
-        let prometheus_task = run_prometheus_exporter(port, None);
+        let prometheus_task = run_prometheus_exporter(PrometheusTransport::Pull { port });
         task_handles.push(prometheus_task);
     }
-
     task_handles.extend(http_api_handle);
-    task_handles.extend([
-        sk_handle,
-        fetcher_handle,
-        updater_handle,
-        tree_handle,
-        gas_adjuster_handle,
-        consistency_checker_handle,
-    ]);
-
-    (task_handles, stop_sender, healthcheck_handle)
+    task_handles.push(gas_adjuster_handle);
+
+    (task_handles, vec![ws_api_healthcheck, http_api_healthcheck])
+}
+
+/// Components syncing the node state with the main node. Unlike the components started in [`init_tasks()`],
+/// these are stopped and started anew when the node state is rolled back because of a reorg.
+struct SyncTasks {
+    task_handles: Vec<task::JoinHandle<()>>,
+    stop_sender: watch::Sender<bool>,
+    tree_healthcheck: TreeHealthCheck,
+}
+
+impl SyncTasks {
+    async fn new(
+        config: &ExternalNodeConfig,
+        connection_pool: ConnectionPool,
+        sync_state: SyncState,
+    ) -> Self {
+        let main_node_url = config
+            .required
+            .main_node_url()
+            .expect("Main node URL is incorrect");
+        let (stop_sender, stop_receiver) = watch::channel::<bool>(false);
+
+        let action_queue = ActionQueue::with_capacity(config.optional.action_queue_capacity());
+        let state_keeper = build_state_keeper(
+            action_queue.clone(),
+            config.required.state_cache_path.clone(),
+            main_node_url.to_string(),
+            connection_pool,
+            sync_state.clone(),
+            config.remote.l2_erc20_bridge_addr,
+            stop_receiver.clone(),
+        )
+        .await;
+        let fetcher = MainNodeFetcher::new(
+            ConnectionPool::new(Some(1), DbVariant::Master).await,
+            &main_node_url,
+            action_queue,
+            sync_state,
+            stop_receiver.clone(),
+        )
+        .await;
+
+        let metadata_calculator = MetadataCalculator::new(&MetadataCalculatorConfig {
+            db_path: &config.required.merkle_tree_path,
+            mode: MetadataCalculatorModeConfig::Lightweight,
+            delay_interval: config.optional.metadata_calculator_delay(),
+            max_block_batch: config.optional.max_blocks_per_tree_batch(),
+            throttle_interval: config.optional.merkle_tree_throttle(),
+        })
+        .await;
+        let tree_healthcheck = metadata_calculator.tree_health_check();
+
+        let consistency_checker = ConsistencyChecker::new(
+            &config
+                .required
+                .eth_client_url()
+                .expect("L1 client URL is incorrect"),
+            10,
+            ConnectionPool::new(Some(1), DbVariant::Master).await,
+        );
+
+        let batch_status_updater = BatchStatusUpdater::new(
+            &main_node_url,
+            ConnectionPool::new(Some(1), DbVariant::Master).await,
+        )
+        .await;
+
+        let tree_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
+        let prover_tree_pool = ConnectionPool::new(Some(1), DbVariant::Prover).await;
+        let task_handles = vec![
+            task::spawn(state_keeper.run()),
+            tokio::spawn(fetcher.run()),
+            task::spawn(batch_status_updater.run(stop_receiver.clone())),
+            task::spawn(metadata_calculator.run(
+                tree_pool,
+                prover_tree_pool,
+                stop_receiver.clone(),
+            )),
+            tokio::spawn(consistency_checker.run(stop_receiver)),
+        ];
+
+        Self {
+            task_handles,
+            stop_sender,
+            tree_healthcheck,
+        }
+    }
+
+    /// Stops all sync components and waits until they terminate, so that the node state can be safely
+    /// modified afterwards. Components that don't stop in time are aborted.
+    async fn stop(self) {
+        let _ = self.stop_sender.send(true);
+        let deadline = Instant::now() + SYNC_TASKS_STOP_TIMEOUT;
+        for mut handle in self.task_handles {
+            // Handles of the finished tasks may have already been polled to completion.
+            if handle.is_finished() {
+                continue;
+            }
+            if tokio::time::timeout_at(deadline, &mut handle)
+                .await
+                .is_err()
+            {
+                vlog::warn!(
+                    "Sync component didn't stop in {SYNC_TASKS_STOP_TIMEOUT:?}, aborting it"
+                );
+                handle.abort();
+            }
+        }
+        RocksDB::await_rocksdb_termination();
+    }
+}
+
+/// Starts the health check server reporting the health of the long-running and the sync components.
+fn start_healthcheck_server(
+    config: &ExternalNodeConfig,
+    api_healthchecks: &[ApiHealthCheck],
+    pool_healthcheck: &ConnectionPoolHealthCheck,
+    tree_healthcheck: TreeHealthCheck,
+) -> HealthCheckHandle {
+    let mut healthchecks: Vec<Box<dyn CheckHealth>> = vec![Box::new(tree_healthcheck)];
+    for healthcheck in api_healthchecks {
+        healthchecks.push(Box::new(healthcheck.clone()));
+    }
+    healthchecks.push(Box::new(pool_healthcheck.clone()));
+    let node_info =
+        healthcheck::NodeInfo::new(vec!["external_node".to_owned()]).with_tree_mode("lightweight");
+    healthcheck::start_server_thread_detached(
+        ([0, 0, 0, 0], config.required.healthcheck_port).into(),
+        healthchecks,
+        node_info,
+        healthcheck::LivenessPolicy::default(),
+    )
 }
 
 async fn shutdown_components(
     stop_sender: watch::Sender<bool>,
+    sync_tasks: SyncTasks,
     healthcheck_handle: HealthCheckHandle,
 ) {
     let _ = stop_sender.send(true);
-    RocksDB::await_rocksdb_termination();
+    sync_tasks.stop().await;
     // Sleep for some time to let components gracefully stop.
     sleep(Duration::from_secs(10)).await;
     healthcheck_handle.stop().await;
@@ -253,7 +323,7 @@ async fn main() -> anyhow::Result<()> {
     // Initial setup.
 
     vlog::init();
-    let _sentry_guard = vlog::init_sentry();
+    let sentry_guard = vlog::init_sentry();
     let config = ExternalNodeConfig::collect()
         .await
         .expect("Failed to load external node config");
@@ -263,59 +333,165 @@ async fn main() -> anyhow::Result<()> {
         .expect("Main node URL is incorrect");
 
     let connection_pool = ConnectionPool::new(None, DbVariant::Master).await;
-    let sigint_receiver = setup_sigint_handler();
+    let (shutdown_sender, mut shutdown_receiver) = shutdown_reason_channel();
+    setup_sigint_handler(shutdown_sender);
 
     vlog::warn!("The external node is in the alpha phase, and should be used with caution.");
 
     vlog::info!("Started the external node");
     vlog::info!("Main node URL is: {}", main_node_url);
 
-    // Make sure that genesis is performed.
-    perform_genesis_if_needed(
-        &mut connection_pool.access_storage().await,
-        config.remote.l2_chain_id,
-        config.base_system_contracts_hashes(),
-        main_node_url.clone(),
-    )
-    .await;
-
-    let (task_handles, stop_sender, health_check_handle) =
-        init_tasks(config.clone(), connection_pool.clone()).await;
-
-    let reorg_detector = ReorgDetector::new(&main_node_url, connection_pool.clone());
-    let reorg_detector_handle = tokio::spawn(reorg_detector.run());
+    // Make sure that the storage is initialized either from a snapshot or by performing genesis.
+    let mut storage = connection_pool.access_storage().await;
+    let snapshot_recovery_l1_batch = match config.optional.snapshot_recovery_l1_batch() {
+        Some(l1_batch_number) if storage.blocks_dal().is_genesis_needed().await => {
+            Some(l1_batch_number)
+        }
+        _ => None,
+    };
+    match snapshot_recovery_l1_batch {
+        Some(l1_batch_number) => {
+            vlog::info!("Recovering storage from snapshot for L1 batch #{l1_batch_number}");
+            let store_router = ObjectStoreFactory::from_env_multi();
+            recover_from_snapshot(
+                &mut storage,
+                store_router.factory(BlobKind::Snapshots),
+                l1_batch_number,
+                &main_node_url,
+            )
+            .await
+            .expect("Failed recovering storage from snapshot");
+        }
+        _ => {
+            perform_genesis_if_needed(
+                &mut storage,
+                config.remote.l2_chain_id,
+                config.base_system_contracts_hashes(),
+                main_node_url.clone(),
+            )
+            .await;
+        }
+    }
+    drop(storage);
 
     let reverter_config = DBConfig {
         state_keeper_db_path: config.required.state_cache_path.clone(),
         new_merkle_tree_ssd_path: config.required.merkle_tree_path.clone(),
         ..Default::default()
     };
+    let auto_revert = config.optional.reorg_detector_auto_revert();
+    let max_revert_depth = auto_revert.then(|| config.optional.reorg_detector_max_revert_depth());
 
+    let sync_state = SyncState::new();
+    let (stop_sender, stop_receiver) = watch::channel::<bool>(false);
+    let (task_handles, api_healthchecks) = init_tasks(
+        &config,
+        connection_pool.clone(),
+        sync_state.clone(),
+        stop_receiver,
+    )
+    .await;
+    let pool_healthcheck =
+        ConnectionPoolHealthCheck::new(ConnectionPool::new(Some(1), DbVariant::Master).await);
     let particular_crypto_alerts = None;
-    let graceful_shutdown = None::<futures::future::Ready<()>>;
+    let graceful_shutdown = None::<future::Ready<()>>;
     let tasks_allowed_to_finish = false;
-    tokio::select! {
-        _ = wait_for_tasks(task_handles, particular_crypto_alerts, graceful_shutdown, tasks_allowed_to_finish) => {},
-        _ = sigint_receiver => {
-            vlog::info!("Stop signal received, shutting down");
-        },
-        last_correct_batch = reorg_detector_handle => {
-            if let Ok(last_correct_batch) = last_correct_batch {
-                vlog::info!("Performing rollback to block {}", last_correct_batch);
-                shutdown_components(stop_sender, health_check_handle).await;
-                BlockReverter::new(reverter_config, None, connection_pool, L1ExecutedBatchesRevert::Allowed)
-                    .rollback_db(last_correct_batch, BlockReverterFlags::all())
-                    .await;
-                vlog::info!("Rollback successfully completed, the node has to restart to continue working");
-                return Ok(());
-            } else {
+    let tasks_finished = wait_for_tasks(
+        task_handles,
+        particular_crypto_alerts,
+        graceful_shutdown,
+        tasks_allowed_to_finish,
+    );
+    tokio::pin!(tasks_finished);
+
+    let (sync_tasks, health_check_handle, shutdown_reason) = loop {
+        let mut sync_tasks =
+            SyncTasks::new(&config, connection_pool.clone(), sync_state.clone()).await;
+        let health_check_handle = start_healthcheck_server(
+            &config,
+            &api_healthchecks,
+            &pool_healthcheck,
+            sync_tasks.tree_healthcheck.clone(),
+        );
+
+        let reorg_detector = ReorgDetector::new(&main_node_url, connection_pool.clone())
+            .with_max_revert_depth(max_revert_depth);
+        let mut reorg_detector_handle = tokio::spawn(reorg_detector.run());
+
+        // `Err(_)` means that the node should shut down (with an optional reason).
+        let reorg_detector_result = tokio::select! {
+            _ = &mut tasks_finished => Err(None),
+            _ = future::select_all(sync_tasks.task_handles.iter_mut()) => {
+                vlog::error!("One of the sync components finished its run, while it wasn't expected to do it");
+                Err(None)
+            }
+            reason = shutdown_receiver.recv() => Err(Some(reason)),
+            result = &mut reorg_detector_handle => Ok(result),
+        };
+        reorg_detector_handle.abort();
+
+        let last_correct_batch = match reorg_detector_result {
+            Ok(Ok(Ok(last_correct_batch))) => last_correct_batch,
+            Ok(Ok(Err(err))) => {
+                vlog::error!("Reorg cannot be reverted automatically: {err:#}");
+                break (
+                    sync_tasks,
+                    health_check_handle,
+                    ShutdownReason::ComponentTerminated,
+                );
+            }
+            Ok(Err(_)) => {
                 vlog::error!("Reorg detector actor failed");
+                break (
+                    sync_tasks,
+                    health_check_handle,
+                    ShutdownReason::ComponentTerminated,
+                );
+            }
+            Err(reason) => {
+                let reason = reason
+                    .or_else(|| shutdown_receiver.try_recv())
+                    .unwrap_or(ShutdownReason::ComponentTerminated);
+                break (sync_tasks, health_check_handle, reason);
             }
+        };
+
+        vlog::info!("Performing rollback to block {}", last_correct_batch);
+        // Only the sync components are stopped; the API servers keep serving requests.
+        health_check_handle.stop().await;
+        sync_tasks.stop().await;
+        BlockReverter::new(
+            reverter_config.clone(),
+            None,
+            connection_pool.clone(),
+            L1ExecutedBatchesRevert::Allowed,
+        )
+        .rollback_db(last_correct_batch, BlockReverterFlags::all())
+        .await;
+        if auto_revert {
+            vlog::info!("Rollback successfully completed, resuming sync from the main node");
+            continue;
         }
-    }
+        vlog::info!("Rollback successfully completed, the node has to restart to continue working");
+        let _ = stop_sender.send(true);
+        let reason = ShutdownReason::Reorg(last_correct_batch);
+        vlog::info!("Stopped, reason: {reason}");
+        return Ok(());
+    };
 
     // Reaching this point means that either some actor exited unexpectedly or we received a stop signal.
     // Broadcast the stop signal to all actors and exit.
-    shutdown_components(stop_sender, health_check_handle).await;
+    if shutdown_reason.exit_code() == 0 {
+        vlog::info!("Shutting down, reason: {shutdown_reason}");
+    } else {
+        vlog::warn!("Shutting down, reason: {shutdown_reason}");
+    }
+    shutdown_components(stop_sender, sync_tasks, health_check_handle).await;
+    let exit_code = shutdown_reason.exit_code();
+    if exit_code != 0 {
+        // `process::exit()` doesn't run destructors, so Sentry needs to be flushed explicitly.
+        drop(sentry_guard);
+        std::process::exit(exit_code);
+    }
     Ok(())
 }
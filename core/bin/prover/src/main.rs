@@ -19,7 +19,7 @@ use zksync_dal::{
     ConnectionPool,
 };
 use zksync_eth_client::clients::http::QueryClient;
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{BlobKind, ObjectStoreFactory};
 use zksync_prover_utils::region_fetcher::{get_region, get_zone};
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
@@ -153,8 +153,9 @@ async fn main() {
     let mut tasks: Vec<JoinHandle<()>> = vec![];
 
     tasks.push(prometheus_exporter::run_prometheus_exporter(
-        prometheus_config.listener_port,
-        None,
+        prometheus_exporter::PrometheusTransport::Pull {
+            port: prometheus_config.listener_port,
+        },
     ));
     tasks.push(tokio::spawn(
         circuit_breaker_checker.run(cb_sender, stop_receiver),
@@ -183,7 +184,8 @@ async fn main() {
     )));
 
     let params = ProverParams::new(&prover_config);
-    let store_factory = ObjectStoreFactory::from_env();
+    // Prover jobs are produced by witness generators, so they are stored in the same object store.
+    let store_router = ObjectStoreFactory::from_env_multi();
 
     let circuit_provider_pool = ConnectionPool::new(Some(1), DbVariant::Prover).await;
     tasks.push(tokio::task::spawn_blocking(move || {
@@ -196,7 +198,11 @@ async fn main() {
             zone,
             rt_handle.clone(),
         );
-        let prover_job_reporter = ProverReporter::new(prover_config, &store_factory, rt_handle);
+        let prover_job_reporter = ProverReporter::new(
+            prover_config,
+            store_router.factory(BlobKind::WitnessInputs),
+            rt_handle,
+        );
         run_prover_with_remote_synthesizer(
             synthesized_circuit_provider,
             ProverArtifactProvider,
@@ -10,7 +10,7 @@ use zksync_config::configs::{FriProverConfig, PrometheusConfig};
 use zksync_config::{ApiConfig, ObjectStoreConfig};
 use zksync_dal::connection::DbVariant;
 use zksync_dal::ConnectionPool;
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{BlobKind, ObjectStoreFactory};
 use zksync_queued_job_processor::JobProcessor;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
@@ -46,7 +46,10 @@ async fn main() {
     .expect("Error setting Ctrl+C handler");
 
     let (stop_sender, stop_receiver) = tokio::sync::watch::channel(false);
-    let blob_store = ObjectStoreFactory::from_env().create_store().await;
+    let blob_store = ObjectStoreFactory::from_env_multi()
+        .factory(BlobKind::WitnessInputs)
+        .create_store()
+        .await;
     let public_blob_store = ObjectStoreFactory::new(ObjectStoreConfig::public_from_env())
         .create_store()
         .await;
@@ -67,7 +70,11 @@ async fn main() {
         circuit_ids_for_round_to_be_proven,
     );
     let tasks = vec![
-        prometheus_exporter::run_prometheus_exporter(prometheus_config.listener_port, None),
+        prometheus_exporter::run_prometheus_exporter(
+            prometheus_exporter::PrometheusTransport::Pull {
+                port: prometheus_config.listener_port,
+            },
+        ),
         tokio::spawn(prover.run(stop_receiver, None)),
     ];
 
@@ -28,6 +28,13 @@ impl ZksNamespaceT for ZkMockNamespaceImpl {
         })
     }
 
+    fn estimate_fee_detailed(
+        &self,
+        _req: zksync_types::transaction_request::CallRequest,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_types::fee::FeeBreakdown>> {
+        not_implemented!()
+    }
+
     fn get_raw_block_transactions(
         &self,
         _block_number: MiniblockNumber,
@@ -149,6 +156,13 @@ impl ZksNamespaceT for ZkMockNamespaceImpl {
         not_implemented!()
     }
 
+    fn get_transaction_age(
+        &self,
+        _hash: zksync_basic_types::H256,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<Option<zksync_basic_types::U64>>> {
+        not_implemented!()
+    }
+
     fn get_l1_batch_details(
         &self,
         _batch: zksync_basic_types::L1BatchNumber,
@@ -170,4 +184,10 @@ impl ZksNamespaceT for ZkMockNamespaceImpl {
     ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_basic_types::U64>> {
         not_implemented!()
     }
+
+    fn get_protocol_version(
+        &self,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_types::api::ProtocolVersionInfo>> {
+        not_implemented!()
+    }
 }
@@ -1,13 +1,13 @@
 #![feature(generic_const_exprs)]
 
-use prometheus_exporter::run_prometheus_exporter;
+use prometheus_exporter::{run_prometheus_exporter, PrometheusTransport};
 use std::time::Instant;
 use structopt::StructOpt;
 use tokio::sync::watch;
 use zksync_config::configs::{AlertsConfig, FriWitnessGeneratorConfig, PrometheusConfig};
 use zksync_config::ObjectStoreConfig;
 use zksync_dal::{connection::DbVariant, ConnectionPool};
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{BlobKind, ObjectStoreFactory};
 use zksync_prover_utils::get_stop_signal_receiver;
 use zksync_queued_job_processor::JobProcessor;
 use zksync_types::proofs::AggregationRound;
@@ -61,7 +61,8 @@ async fn main() {
     );
     let use_push_gateway = opt.batch_size.is_some();
 
-    let store_factory = ObjectStoreFactory::from_env();
+    let store_router = ObjectStoreFactory::from_env_multi();
+    let store_factory = store_router.factory(BlobKind::WitnessInputs);
     let config = FriWitnessGeneratorConfig::from_env();
     let prometheus_config = PrometheusConfig::from_env();
     let connection_pool = ConnectionPool::new(None, DbVariant::Master).await;
@@ -75,7 +76,7 @@ async fn main() {
                 .await;
             let generator = BasicWitnessGenerator::new(
                 config,
-                &store_factory,
+                store_factory,
                 public_blob_store,
                 connection_pool,
                 prover_connection_pool,
@@ -84,35 +85,33 @@ async fn main() {
             generator.run(stop_receiver, opt.batch_size)
         }
         AggregationRound::LeafAggregation => {
-            let generator = LeafAggregationWitnessGenerator::new(
-                config,
-                &store_factory,
-                prover_connection_pool,
-            )
-            .await;
+            let generator =
+                LeafAggregationWitnessGenerator::new(config, store_factory, prover_connection_pool)
+                    .await;
             generator.run(stop_receiver, opt.batch_size)
         }
         AggregationRound::NodeAggregation => {
             let generator =
-                NodeAggregationWitnessGenerator::new(&store_factory, prover_connection_pool).await;
+                NodeAggregationWitnessGenerator::new(store_factory, prover_connection_pool).await;
             generator.run(stop_receiver, opt.batch_size)
         }
         AggregationRound::Scheduler => {
             let generator =
-                SchedulerWitnessGenerator::new(&store_factory, prover_connection_pool).await;
+                SchedulerWitnessGenerator::new(store_factory, prover_connection_pool).await;
             generator.run(stop_receiver, opt.batch_size)
         }
     };
     let tasks = vec![
-        run_prometheus_exporter(
-            prometheus_config.listener_port,
-            use_push_gateway.then(|| {
-                (
-                    prometheus_config.pushgateway_url.clone(),
-                    prometheus_config.push_interval(),
-                )
-            }),
-        ),
+        run_prometheus_exporter(if use_push_gateway {
+            PrometheusTransport::Push {
+                url: prometheus_config.pushgateway_url.clone(),
+                interval: prometheus_config.push_interval(),
+            }
+        } else {
+            PrometheusTransport::Pull {
+                port: prometheus_config.listener_port,
+            }
+        }),
         tokio::spawn(witness_generator_task),
     ];
     vlog::info!(
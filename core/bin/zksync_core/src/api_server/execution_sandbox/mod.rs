@@ -4,7 +4,7 @@ use tokio::runtime::{Handle, Runtime};
 use vm::vm_with_bootloader::derive_base_fee_and_gas_per_pubdata;
 use zksync_config::constants::PUBLISH_BYTECODE_OVERHEAD;
 use zksync_contracts::BaseSystemContracts;
-use zksync_dal::{ConnectionPool, SqlxError, StorageProcessor};
+use zksync_dal::{blocks_web3_dal::BlocksWeb3DalError, ConnectionPool, StorageProcessor};
 use zksync_state::{FactoryDepsCache, PostgresStorage, ReadStorage, StorageView};
 use zksync_types::{api, AccountTreeId, MiniblockNumber, U256};
 use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
@@ -233,7 +233,7 @@ impl BlockArgs {
     pub async fn new(
         connection: &mut StorageProcessor<'_>,
         block_id: api::BlockId,
-    ) -> Result<Option<Self>, SqlxError> {
+    ) -> Result<Option<Self>, BlocksWeb3DalError> {
         let resolved_block_number = connection
             .blocks_web3_dal()
             .resolve_block_id(block_id)
@@ -1,4 +1,6 @@
+use std::future::Future;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use zksync_config::configs::api::ExplorerApiConfig;
@@ -7,9 +9,11 @@ use zksync_types::Address;
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 
 use actix_cors::Cors;
-use actix_web::dev::Server;
+use actix_web::body::MessageBody;
+use actix_web::dev::{Server, Service, ServiceRequest, ServiceResponse};
+use actix_web::http::header;
 use actix_web::{web, App, HttpResponse, HttpServer};
-use tokio::sync::watch;
+use tokio::sync::{watch, Semaphore};
 use tokio::task::JoinHandle;
 
 use api_decl::RestApi;
@@ -18,10 +22,64 @@ pub mod api_decl;
 pub mod api_impl;
 pub mod network_stats;
 
-fn start_server(api: RestApi, bind_to: SocketAddr, threads: usize) -> Server {
+/// Limits the number of requests concurrently processed by the server. Requests exceeding the limit
+/// are rejected with 503 Service Unavailable and a `Retry-After` header, so that a burst
+/// of expensive queries cannot exhaust the connection pool shared with other components.
+#[derive(Debug, Clone)]
+struct RequestLimiter {
+    semaphore: Arc<Semaphore>,
+}
+
+impl RequestLimiter {
+    const RETRY_AFTER: Duration = Duration::from_secs(1);
+
+    fn new(max_concurrent_requests: Option<usize>) -> Self {
+        let permits = max_concurrent_requests.unwrap_or(Semaphore::MAX_PERMITS);
+        Self {
+            semaphore: Arc::new(Semaphore::new(permits)),
+        }
+    }
+
+    fn handle<S, B>(
+        &self,
+        request: ServiceRequest,
+        service: &S,
+    ) -> impl Future<Output = Result<ServiceResponse, actix_web::Error>>
+    where
+        S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+        B: MessageBody + 'static,
+    {
+        let call = match self.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => Ok((permit, service.call(request))),
+            Err(_) => Err(request),
+        };
+
+        async move {
+            match call {
+                Ok((_permit, call)) => call.await.map(ServiceResponse::map_into_boxed_body),
+                Err(request) => {
+                    metrics::increment_counter!("api.explorer.rejected_requests");
+                    let response = HttpResponse::ServiceUnavailable()
+                        .insert_header((header::RETRY_AFTER, Self::RETRY_AFTER.as_secs()))
+                        .finish();
+                    Ok(request.into_response(response))
+                }
+            }
+        }
+    }
+}
+
+fn start_server(
+    api: RestApi,
+    bind_to: SocketAddr,
+    threads: usize,
+    limiter: RequestLimiter,
+) -> Server {
     HttpServer::new(move || {
         let api = api.clone();
+        let limiter = limiter.clone();
         App::new()
+            .wrap_fn(move |request, service| limiter.handle(request, service))
             .wrap(
                 Cors::default()
                     .send_wildcard()
@@ -65,6 +123,7 @@ pub fn start_server_thread_detached(
             actix_rt::System::new().block_on(async move {
                 let bind_address = api_config.bind_addr();
                 let threads = api_config.threads_per_server as usize;
+                let limiter = RequestLimiter::new(api_config.max_concurrent_requests);
                 let api = RestApi::new(
                     master_connection_pool,
                     replica_connection_pool,
@@ -74,7 +133,7 @@ pub fn start_server_thread_detached(
                 );
                 api.spawn_network_stats_updater(panic_sender, stop_receiver.clone());
 
-                let server = start_server(api, bind_address, threads);
+                let server = start_server(api, bind_address, threads, limiter);
                 let close_handle = server.handle();
                 actix_rt::spawn(async move {
                     if stop_receiver.changed().await.is_ok() {
@@ -89,3 +148,55 @@ pub fn start_server_thread_detached(
 
     handler
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test};
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn excess_requests_are_rejected() {
+        const LIMIT: usize = 2;
+
+        let limiter = RequestLimiter::new(Some(LIMIT));
+        // Requests are blocked until the gate is opened.
+        let gate = Arc::new(Semaphore::new(0));
+        let handler_gate = gate.clone();
+        let app = App::new()
+            .wrap_fn(move |request, service| limiter.handle(request, service))
+            .route(
+                "/slow",
+                web::get().to(move || {
+                    let gate = handler_gate.clone();
+                    async move {
+                        let _permit = gate.acquire().await.unwrap();
+                        HttpResponse::Ok().finish()
+                    }
+                }),
+            );
+        let app = test::init_service(app).await;
+        let request = || test::TestRequest::get().uri("/slow").to_request();
+
+        let mut in_flight: Vec<_> = (0..LIMIT)
+            .map(|_| Box::pin(test::call_service(&app, request())))
+            .collect();
+        for response in &mut in_flight {
+            assert!(futures::poll!(response).is_pending());
+        }
+
+        for _ in 0..3 {
+            let response = test::call_service(&app, request()).await;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "1");
+        }
+
+        gate.add_permits(1);
+        for response in in_flight {
+            assert_eq!(response.await.status(), StatusCode::OK);
+        }
+        // Permits are released once requests are processed.
+        let response = test::call_service(&app, request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -1,41 +1,237 @@
 use actix_web::dev::Server;
 use actix_web::{get, web, App, HttpResponse, HttpServer, Responder};
-use serde::Serialize;
-use std::{net::SocketAddr, sync::Arc};
+use serde::{Deserialize, Serialize};
+use std::{
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::watch;
-use zksync_health_check::{CheckHealth, CheckHealthStatus};
+use zksync_health_check::{async_trait, CheckHealth, CheckHealthStatus};
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct Response {
     pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<serde_json::Value>,
+}
+
+/// Information about the node process exposed via the `/info` endpoint.
+#[derive(Debug, Clone)]
+pub struct NodeInfo {
+    components: Vec<String>,
+    tree_mode: Option<String>,
+    started_at: Instant,
+}
+
+impl NodeInfo {
+    /// Creates information for a process running the specified components.
+    pub fn new(components: Vec<String>) -> Self {
+        Self {
+            components,
+            tree_mode: None,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Sets the mode of the Merkle tree run by the process.
+    pub fn with_tree_mode(mut self, tree_mode: &str) -> Self {
+        self.tree_mode = Some(tree_mode.to_owned());
+        self
+    }
+
+    pub fn components(&self) -> &[String] {
+        &self.components
+    }
+
+    pub fn tree_mode(&self) -> Option<&str> {
+        self.tree_mode.as_deref()
+    }
+
+    fn response(&self) -> NodeInfoResponse {
+        NodeInfoResponse {
+            components: self.components.clone(),
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            uptime_sec: self.started_at.elapsed().as_secs(),
+            tree_mode: self.tree_mode.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodeInfoResponse {
+    pub components: Vec<String>,
+    pub version: String,
+    pub uptime_sec: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree_mode: Option<String>,
+}
+
+/// Defines how health checks are aggregated by the `/live` endpoint. The `/health` endpoint
+/// always considers degraded checks as failed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LivenessPolicy {
+    /// Only hard failures ([`CheckHealthStatus::NotReady`]) fail liveness.
+    #[default]
+    IgnoreDegraded,
+    /// Degraded checks fail liveness as well.
+    FailOnDegraded,
+}
+
+impl LivenessPolicy {
+    pub fn new(degraded_fails_liveness: bool) -> Self {
+        if degraded_fails_liveness {
+            Self::FailOnDegraded
+        } else {
+            Self::IgnoreDegraded
+        }
+    }
+}
+
+/// Checks all provided health checks. If some of them fail, the returned status
+/// contains messages of all failed checks. Degraded checks are considered failed
+/// only if `fail_on_degraded` is set.
+async fn check_all<'a>(
+    healthchecks: impl IntoIterator<Item = &'a Box<dyn CheckHealth>>,
+    fail_on_degraded: bool,
+) -> CheckHealthStatus {
+    let mut failure_messages = vec![];
+    for healthcheck in healthchecks {
+        match healthcheck.check_health().await {
+            CheckHealthStatus::Ready => { /* do nothing */ }
+            CheckHealthStatus::Degraded(message) => {
+                if fail_on_degraded {
+                    failure_messages.push(message);
+                }
+            }
+            CheckHealthStatus::NotReady(message) => failure_messages.push(message),
+        }
+    }
+
+    if failure_messages.is_empty() {
+        CheckHealthStatus::Ready
+    } else {
+        CheckHealthStatus::NotReady(failure_messages.join("; "))
+    }
 }
 
 #[get("/health")]
 async fn healthcheck(healthchecks: web::Data<[Box<dyn CheckHealth>]>) -> impl Responder {
-    for healthcheck in healthchecks.iter() {
-        match healthcheck.check_health().await {
-            CheckHealthStatus::NotReady(message) => {
-                let response = Response { message };
-                return HttpResponse::ServiceUnavailable().json(response);
+    let details = healthchecks
+        .iter()
+        .filter_map(|healthcheck| healthcheck.details())
+        .collect();
+    match check_all(healthchecks.iter(), true).await {
+        CheckHealthStatus::Degraded(message) | CheckHealthStatus::NotReady(message) => {
+            let response = Response { message, details };
+            HttpResponse::ServiceUnavailable().json(response)
+        }
+        CheckHealthStatus::Ready => {
+            let response = Response {
+                message: "Everything is working fine".to_string(),
+                details,
+            };
+            HttpResponse::Ok().json(response)
+        }
+    }
+}
+
+/// Lightweight liveness endpoint. Unlike `/health`, it doesn't report check details, skips checks
+/// that opt out via [`CheckHealth::affects_liveness()`], and it may tolerate degraded checks
+/// depending on the [`LivenessPolicy`]. Startup states (e.g., genesis not being performed yet)
+/// are reported as degraded, so they don't fail liveness under the default policy.
+#[get("/live")]
+async fn liveness(
+    healthchecks: web::Data<[Box<dyn CheckHealth>]>,
+    policy: web::Data<LivenessPolicy>,
+) -> impl Responder {
+    let fail_on_degraded = **policy == LivenessPolicy::FailOnDegraded;
+    let liveness_checks = healthchecks
+        .iter()
+        .filter(|healthcheck| healthcheck.affects_liveness());
+    match check_all(liveness_checks, fail_on_degraded).await {
+        CheckHealthStatus::Ready | CheckHealthStatus::Degraded(_) => HttpResponse::Ok().finish(),
+        CheckHealthStatus::NotReady(message) => HttpResponse::ServiceUnavailable().body(message),
+    }
+}
+
+#[get("/info")]
+async fn info(node_info: web::Data<NodeInfo>) -> impl Responder {
+    HttpResponse::Ok().json(node_info.response())
+}
+
+/// Health check of a component running in a separate process, which queries the `/health` endpoint
+/// of the component. If the component is unreachable, it is considered not ready.
+#[derive(Debug)]
+pub struct HttpRemoteHealthCheck {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HttpRemoteHealthCheck {
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn new(url: String) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Self::REQUEST_TIMEOUT)
+            .build()
+            .expect("failed building HTTP client for remote health check");
+        Self { url, client }
+    }
+}
+
+#[async_trait]
+impl CheckHealth for HttpRemoteHealthCheck {
+    async fn check_health(&self) -> CheckHealthStatus {
+        let response = match self.client.get(&self.url).send().await {
+            Ok(response) => response,
+            Err(err) => {
+                return CheckHealthStatus::NotReady(format!(
+                    "Remote component at {} is unreachable: {err}",
+                    self.url
+                ));
             }
-            CheckHealthStatus::Ready => (),
+        };
+        let status = response.status();
+        if status.is_success() {
+            return CheckHealthStatus::Ready;
         }
+
+        let message = match response.json::<Response>().await {
+            Ok(response) => response.message,
+            Err(_) => status.to_string(),
+        };
+        CheckHealthStatus::NotReady(format!(
+            "Remote component at {} is not ready: {message}",
+            self.url
+        ))
     }
-    let response = Response {
-        message: "Everything is working fine".to_string(),
-    };
-    HttpResponse::Ok().json(response)
 }
 
-fn run_server(bind_address: SocketAddr, healthchecks: Vec<Box<dyn CheckHealth>>) -> Server {
+fn run_server(
+    bind_address: SocketAddr,
+    healthchecks: Vec<Box<dyn CheckHealth>>,
+    node_info: NodeInfo,
+    liveness_policy: LivenessPolicy,
+) -> Server {
     let healthchecks: Arc<[Box<dyn CheckHealth>]> = healthchecks.into();
     let data = web::Data::from(healthchecks);
-    HttpServer::new(move || App::new().service(healthcheck).app_data(data.clone()))
-        .workers(1)
-        .bind(bind_address)
-        .unwrap()
-        .run()
+    let node_info = web::Data::new(node_info);
+    let liveness_policy = web::Data::new(liveness_policy);
+    HttpServer::new(move || {
+        App::new()
+            .service(healthcheck)
+            .service(liveness)
+            .service(info)
+            .app_data(data.clone())
+            .app_data(node_info.clone())
+            .app_data(liveness_policy.clone())
+    })
+    .workers(1)
+    .bind(bind_address)
+    .unwrap()
+    .run()
 }
 
 pub struct HealthCheckHandle {
@@ -54,6 +250,8 @@ impl HealthCheckHandle {
 pub fn start_server_thread_detached(
     addr: SocketAddr,
     healthchecks: Vec<Box<dyn CheckHealth>>,
+    node_info: NodeInfo,
+    liveness_policy: LivenessPolicy,
 ) -> HealthCheckHandle {
     let (handler, panic_sender) = spawn_panic_handler();
     let (stop_sender, mut stop_receiver) = watch::channel(false);
@@ -63,7 +261,7 @@ pub fn start_server_thread_detached(
             let _panic_sentinel = ThreadPanicNotify(panic_sender.clone());
 
             actix_rt::System::new().block_on(async move {
-                let server = run_server(addr, healthchecks);
+                let server = run_server(addr, healthchecks, node_info, liveness_policy);
                 let close_handle = server.handle();
                 actix_rt::spawn(async move {
                     if stop_receiver.changed().await.is_ok() {
@@ -81,3 +279,192 @@ pub fn start_server_thread_detached(
         stop_sender,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        http::StatusCode,
+        test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Spawns a mock remote health endpoint responding with the specified status and message.
+    async fn spawn_remote_endpoint(status_line: &'static str, message: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let body = serde_json::json!({ "message": message }).to_string();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0_u8; 1_024];
+                let bytes_read = stream.read(&mut request).await.unwrap();
+                assert!(bytes_read > 0);
+                let response = format!(
+                    "HTTP/1.1 {status_line}\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{local_addr}/health")
+    }
+
+    #[tokio::test]
+    async fn aggregating_remote_health_checks() {
+        let healthy_url = spawn_remote_endpoint("200 OK", "Everything is working fine").await;
+        let failing_url =
+            spawn_remote_endpoint("503 Service Unavailable", "State keeper is not ready").await;
+
+        let healthy_check = HttpRemoteHealthCheck::new(healthy_url);
+        assert_eq!(healthy_check.check_health().await, CheckHealthStatus::Ready);
+        let failing_check = HttpRemoteHealthCheck::new(failing_url.clone());
+        let CheckHealthStatus::NotReady(message) = failing_check.check_health().await else {
+            panic!("Failing remote component reported as ready");
+        };
+        assert!(message.contains(&failing_url), "{message}");
+        assert!(message.contains("State keeper is not ready"), "{message}");
+
+        let healthchecks: Vec<Box<dyn CheckHealth>> =
+            vec![Box::new(healthy_check), Box::new(failing_check)];
+        let combined_status = check_all(&healthchecks, true).await;
+        let CheckHealthStatus::NotReady(combined_message) = combined_status else {
+            panic!("Combined status should be not ready");
+        };
+        assert_eq!(combined_message, message);
+        assert_eq!(
+            check_all(&healthchecks[..1], true).await,
+            CheckHealthStatus::Ready
+        );
+    }
+
+    #[tokio::test]
+    async fn unreachable_remote_is_reported_as_not_ready() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        drop(listener);
+
+        let health_check = HttpRemoteHealthCheck::new(url.clone());
+        let CheckHealthStatus::NotReady(message) = health_check.check_health().await else {
+            panic!("Unreachable remote component reported as ready");
+        };
+        assert!(message.contains("unreachable"), "{message}");
+        assert!(message.contains(&url), "{message}");
+    }
+
+    #[actix_rt::test]
+    async fn node_info_endpoint() {
+        let components = vec!["http_api".to_owned(), "tree".to_owned()];
+        let node_info = NodeInfo::new(components.clone()).with_tree_mode("full");
+        let app = App::new().service(info).app_data(web::Data::new(node_info));
+        let app = init_service(app).await;
+
+        let request = TestRequest::get().uri("/info").to_request();
+        let response: NodeInfoResponse = call_and_read_body_json(&app, request).await;
+        assert_eq!(response.components, components);
+        assert_eq!(response.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(response.tree_mode.as_deref(), Some("full"));
+    }
+
+    /// Health check with a status that can be changed from the test.
+    #[derive(Debug, Clone)]
+    struct MockHealthCheck(Arc<std::sync::Mutex<CheckHealthStatus>>);
+
+    /// Health check opting out of liveness checks.
+    #[derive(Debug)]
+    struct ExpensiveHealthCheck;
+
+    #[async_trait]
+    impl CheckHealth for ExpensiveHealthCheck {
+        async fn check_health(&self) -> CheckHealthStatus {
+            CheckHealthStatus::NotReady("database is unreachable".to_owned())
+        }
+
+        fn affects_liveness(&self) -> bool {
+            false
+        }
+    }
+
+    #[async_trait]
+    impl CheckHealth for MockHealthCheck {
+        async fn check_health(&self) -> CheckHealthStatus {
+            match &*self.0.lock().unwrap() {
+                CheckHealthStatus::Ready => CheckHealthStatus::Ready,
+                CheckHealthStatus::Degraded(message) => {
+                    CheckHealthStatus::Degraded(message.clone())
+                }
+                CheckHealthStatus::NotReady(message) => {
+                    CheckHealthStatus::NotReady(message.clone())
+                }
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn liveness_endpoint_ignores_degraded_checks() {
+        let status = Arc::new(std::sync::Mutex::new(CheckHealthStatus::Degraded(
+            "tree is lagging".to_owned(),
+        )));
+        let healthchecks: Vec<Box<dyn CheckHealth>> =
+            vec![Box::new(MockHealthCheck(status.clone()))];
+        let healthchecks: Arc<[Box<dyn CheckHealth>]> = healthchecks.into();
+        let app = App::new()
+            .service(healthcheck)
+            .service(liveness)
+            .app_data(web::Data::from(healthchecks))
+            .app_data(web::Data::new(LivenessPolicy::IgnoreDegraded));
+        let app = init_service(app).await;
+
+        let request = TestRequest::get().uri("/live").to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        // Degraded checks still fail readiness.
+        let request = TestRequest::get().uri("/health").to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        *status.lock().unwrap() = CheckHealthStatus::NotReady("tree has crashed".to_owned());
+        let request = TestRequest::get().uri("/live").to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[actix_rt::test]
+    async fn liveness_endpoint_skips_opted_out_checks() {
+        let healthchecks: Vec<Box<dyn CheckHealth>> = vec![Box::new(ExpensiveHealthCheck)];
+        let healthchecks: Arc<[Box<dyn CheckHealth>]> = healthchecks.into();
+        let app = App::new()
+            .service(healthcheck)
+            .service(liveness)
+            .app_data(web::Data::from(healthchecks))
+            .app_data(web::Data::new(LivenessPolicy::FailOnDegraded));
+        let app = init_service(app).await;
+
+        let request = TestRequest::get().uri("/live").to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let request = TestRequest::get().uri("/health").to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn degraded_checks_can_fail_liveness() {
+        let healthchecks: Vec<Box<dyn CheckHealth>> = vec![Box::new(MockHealthCheck(Arc::new(
+            std::sync::Mutex::new(CheckHealthStatus::Degraded("tree is lagging".to_owned())),
+        )))];
+        assert_eq!(
+            check_all(&healthchecks, false).await,
+            CheckHealthStatus::Ready
+        );
+        assert_eq!(
+            check_all(&healthchecks, true).await,
+            CheckHealthStatus::NotReady("tree is lagging".to_owned())
+        );
+    }
+}
@@ -18,7 +18,9 @@ use vm::{
     zk_evm::zkevm_opcode_defs::system_params::MAX_PUBDATA_PER_BLOCK,
     VmExecutionResult,
 };
-use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
+use zksync_config::configs::{
+    api::Web3JsonRpcConfig, chain::StateKeeperConfig, eth_sender::GasAdjusterConfig,
+};
 use zksync_contracts::{
     BaseSystemContracts, SystemContractCode, ESTIMATE_FEE_BLOCK_CODE,
     PLAYGROUND_BLOCK_BOOTLOADER_CODE,
@@ -26,7 +28,7 @@ use zksync_contracts::{
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
 use zksync_state::FactoryDepsCache;
 use zksync_types::{
-    fee::{Fee, TransactionExecutionMetrics},
+    fee::{Fee, FeeBreakdown, TransactionExecutionMetrics},
     get_code_key, get_intrinsic_constants,
     l2::error::TxCheckError::TxDuplication,
     l2::L2Tx,
@@ -42,6 +44,10 @@ use crate::api_server::execution_sandbox::{
     get_pubdata_for_factory_deps, BlockArgs, SandboxExecutionError, TxExecutionArgs, TxSharedArgs,
     VmConcurrencyLimiter, VmPermit,
 };
+use crate::fee_ticker::{
+    policy::{DefaultFeePolicy, FeePolicy},
+    FeeTicker,
+};
 use crate::l1_gas_price::L1GasPriceProvider;
 use crate::state_keeper::seal_criteria::{ConditionalSealer, SealData};
 
@@ -70,6 +76,8 @@ pub struct TxSenderBuilder {
     /// Actual state keeper configuration, required for tx verification.
     /// If not set, transactions would not be checked against seal criteria.
     state_keeper_config: Option<StateKeeperConfig>,
+    /// Policy scaling estimated fees. If not set, fees are left unchanged.
+    fee_policy: Option<Box<dyn FeePolicy>>,
 }
 
 impl TxSenderBuilder {
@@ -81,6 +89,7 @@ impl TxSenderBuilder {
             rate_limiter: None,
             proxy: None,
             state_keeper_config: None,
+            fee_policy: None,
         }
     }
 
@@ -110,6 +119,11 @@ impl TxSenderBuilder {
         self
     }
 
+    pub fn with_fee_policy(mut self, fee_policy: Box<dyn FeePolicy>) -> Self {
+        self.fee_policy = Some(fee_policy);
+        self
+    }
+
     pub async fn build<G: L1GasPriceProvider>(
         self,
         l1_gas_price_source: Arc<G>,
@@ -158,6 +172,9 @@ impl TxSenderBuilder {
             rate_limiter: self.rate_limiter,
             proxy: self.proxy,
             state_keeper_config: self.state_keeper_config,
+            fee_policy: self
+                .fee_policy
+                .unwrap_or_else(|| Box::new(DefaultFeePolicy)),
             vm_concurrency_limiter,
             factory_deps_cache,
         }))
@@ -179,12 +196,15 @@ pub struct TxSenderConfig {
     pub validation_computational_gas_limit: u32,
     pub default_aa: H256,
     pub bootloader: H256,
+    /// Cap for the L1 gas price used in fee estimation.
+    pub max_l1_gas_price_for_estimation: Option<u64>,
 }
 
 impl TxSenderConfig {
     pub fn new(
         state_keeper_config: &StateKeeperConfig,
         web3_json_config: &Web3JsonRpcConfig,
+        gas_adjuster_config: &GasAdjusterConfig,
     ) -> Self {
         Self {
             fee_account_addr: state_keeper_config.fee_account_addr,
@@ -197,6 +217,7 @@ impl TxSenderConfig {
                 .validation_computational_gas_limit,
             default_aa: state_keeper_config.default_aa_hash,
             bootloader: state_keeper_config.bootloader_hash,
+            max_l1_gas_price_for_estimation: gas_adjuster_config.max_l1_gas_price_for_estimation,
         }
     }
 }
@@ -217,6 +238,8 @@ pub struct TxSenderInner<G> {
     /// This field may be omitted on the external node, since the configuration may change unexpectedly.
     /// If this field is set to `None`, `TxSender` will assume that any transaction is executable.
     state_keeper_config: Option<StateKeeperConfig>,
+    /// Policy consulted to scale the estimated fees, e.g. to sponsor calls to specific contracts.
+    fee_policy: Box<dyn FeePolicy>,
     /// Used to limit the amount of VMs that can be executed simultaneously.
     pub(super) vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     // Smart contract source code cache.
@@ -592,13 +615,27 @@ impl<G: L1GasPriceProvider> TxSender<G> {
 
     pub async fn get_txs_fee_in_wei(
         &self,
-        mut tx: Transaction,
+        tx: Transaction,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u32,
     ) -> Result<Fee, SubmitTxError> {
+        let (fee, _) = self
+            .get_txs_fee_breakdown(tx, estimated_fee_scale_factor, acceptable_overestimation)
+            .await?;
+        Ok(fee)
+    }
+
+    /// Same as [`Self::get_txs_fee_in_wei()`], but additionally returns the breakdown
+    /// of the estimated fee into the L2 gas, L1 pubdata and overhead components.
+    pub async fn get_txs_fee_breakdown(
+        &self,
+        mut tx: Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+    ) -> Result<(Fee, FeeBreakdown), SubmitTxError> {
         let estimation_started_at = Instant::now();
         let l1_gas_price = {
-            let effective_gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
+            let effective_gas_price = self.estimation_l1_gas_price();
             let current_l1_gas_price =
                 ((effective_gas_price as f64) * self.0.sender_config.gas_price_scale_factor) as u64;
 
@@ -763,31 +800,27 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         drop(vm_permit); // Unblock other VMs to enter.
         match result {
             Err(err) => Err(err.into()),
-            Ok(_) => {
+            Ok(result) => {
                 let overhead = derive_overhead(
                     suggested_gas_limit,
                     gas_per_pubdata_byte as u32,
                     tx.encoding_len(),
                     OverheadCoeficients::from_tx_type(tx.tx_format() as u8),
                 );
-
-                let full_gas_limit =
-                    match tx_body_gas_limit.overflowing_add(gas_for_bytecodes_pubdata + overhead) {
-                        (value, false) => value,
-                        (_, true) => {
-                            return Err(SubmitTxError::ExecutionReverted(
-                                "exceeds block gas limit".to_string(),
-                                vec![],
-                            ));
-                        }
-                    };
-
-                Ok(Fee {
-                    max_fee_per_gas: base_fee.into(),
-                    max_priority_fee_per_gas: 0u32.into(),
-                    gas_limit: full_gas_limit.into(),
-                    gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
-                })
+                // The policy only affects the quoted fee; the transaction is executed with the full base fee.
+                let fee_per_gas = FeeTicker::apply_fee_policy(
+                    self.0.fee_policy.as_ref(),
+                    tx.initiator_account(),
+                    tx.execute.contract_address,
+                    base_fee.into(),
+                );
+                fee_with_breakdown(
+                    fee_per_gas.as_u64(),
+                    gas_per_pubdata_byte,
+                    suggested_gas_limit,
+                    &result,
+                    overhead,
+                )
             }
         }
     }
@@ -821,8 +854,17 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         })
     }
 
+    /// Returns the L1 gas price to be used for fee estimation, capped according to the config.
+    fn estimation_l1_gas_price(&self) -> u64 {
+        let l1_gas_price_source = &self.0.l1_gas_price_source;
+        match self.0.sender_config.max_l1_gas_price_for_estimation {
+            Some(cap) => l1_gas_price_source.bounded_gas_price_with_cap(cap),
+            None => l1_gas_price_source.estimate_effective_gas_price(),
+        }
+    }
+
     pub fn gas_price(&self) -> u64 {
-        let gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
+        let gas_price = self.estimation_l1_gas_price();
         let l1_gas_price = (gas_price as f64 * self.0.sender_config.gas_price_scale_factor).round();
         let (base_fee, _) = derive_base_fee_and_gas_per_pubdata(
             l1_gas_price as u64,
@@ -865,3 +907,117 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         Ok(())
     }
 }
+
+/// Assembles the fee estimate from the gas limit components, and splits the total fee accordingly.
+/// The split between L2 execution and L1 pubdata is based on `execution_result` of the transaction
+/// executed with `gas_limit`: all gas not spent on computations is spent on publishing pubdata.
+fn fee_with_breakdown(
+    base_fee: u64,
+    gas_per_pubdata_byte: u64,
+    gas_limit: u32,
+    execution_result: &VmExecutionResult,
+    overhead: u32,
+) -> Result<(Fee, FeeBreakdown), SubmitTxError> {
+    let full_gas_limit = match gas_limit.overflowing_add(overhead) {
+        (value, false) => value,
+        (_, true) => {
+            return Err(SubmitTxError::ExecutionReverted(
+                "exceeds block gas limit".to_string(),
+                vec![],
+            ));
+        }
+    };
+
+    let fee = Fee {
+        max_fee_per_gas: base_fee.into(),
+        max_priority_fee_per_gas: 0u32.into(),
+        gas_limit: full_gas_limit.into(),
+        gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
+    };
+    let pubdata_gas = execution_result
+        .gas_used
+        .saturating_sub(execution_result.computational_gas_used)
+        .min(gas_limit);
+    let base_fee = U256::from(base_fee);
+    let breakdown = FeeBreakdown {
+        l2_gas_cost: U256::from(gas_limit - pubdata_gas) * base_fee,
+        l1_pubdata_cost: U256::from(pubdata_gas) * base_fee,
+        overhead: U256::from(overhead) * base_fee,
+    };
+    Ok((fee, breakdown))
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::vm_trace::VmTrace;
+
+    use super::*;
+
+    /// Creates an execution result for a transaction that spends `computational_gas`
+    /// on computations and publishes `pubdata_bytes` of pubdata.
+    fn execution_result(
+        computational_gas: u32,
+        pubdata_bytes: u32,
+        gas_per_pubdata_byte: u64,
+    ) -> VmExecutionResult {
+        VmExecutionResult {
+            events: vec![],
+            storage_log_queries: vec![],
+            used_contract_hashes: vec![],
+            l2_to_l1_logs: vec![],
+            return_data: vec![],
+            gas_used: computational_gas + pubdata_bytes * gas_per_pubdata_byte as u32,
+            computational_gas_used: computational_gas,
+            contracts_used: 0,
+            revert_reason: None,
+            trace: VmTrace::ExecutionTrace(Default::default()),
+            total_log_queries: 0,
+            cycles_used: 0,
+        }
+    }
+
+    #[test]
+    fn fee_breakdown_sums_up_to_total_fee() {
+        let (base_fee, gas_per_pubdata_byte) =
+            derive_base_fee_and_gas_per_pubdata(10_000_000_000, 250_000_000);
+        let computational_gas = 500_000;
+        let gas_limit = 100_000_000;
+        let overhead = 20_000;
+
+        let mut pubdata_costs = vec![];
+        for pubdata_bytes in [1_000_u32, 2_000] {
+            let result = execution_result(computational_gas, pubdata_bytes, gas_per_pubdata_byte);
+            let (fee, breakdown) =
+                fee_with_breakdown(base_fee, gas_per_pubdata_byte, gas_limit, &result, overhead)
+                    .unwrap();
+
+            assert_eq!(
+                breakdown.l2_gas_cost + breakdown.l1_pubdata_cost + breakdown.overhead,
+                fee.max_total_fee()
+            );
+            assert_eq!(
+                breakdown.l1_pubdata_cost,
+                U256::from(pubdata_bytes) * U256::from(gas_per_pubdata_byte) * U256::from(base_fee)
+            );
+            assert_eq!(
+                breakdown.overhead,
+                U256::from(overhead) * U256::from(base_fee)
+            );
+            pubdata_costs.push(breakdown.l1_pubdata_cost);
+        }
+
+        // The pubdata component scales linearly with the pubdata size.
+        assert!(!pubdata_costs[0].is_zero());
+        assert_eq!(pubdata_costs[1], pubdata_costs[0] * 2);
+    }
+
+    #[test]
+    fn fee_breakdown_errors_on_gas_limit_overflow() {
+        let result = execution_result(0, 0, 1);
+        let err = fee_with_breakdown(1, 1, u32::MAX, &result, 1).unwrap_err();
+        assert!(
+            matches!(err, SubmitTxError::ExecutionReverted(..)),
+            "{err:?}"
+        );
+    }
+}
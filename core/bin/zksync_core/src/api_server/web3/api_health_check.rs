@@ -20,6 +20,7 @@ impl CheckHealth for ApiHealthCheck {
     async fn check_health(&self) -> CheckHealthStatus {
         match *self.receiver.borrow() {
             CheckHealthStatus::Ready => CheckHealthStatus::Ready,
+            CheckHealthStatus::Degraded(ref msg) => CheckHealthStatus::Degraded(msg.clone()),
             CheckHealthStatus::NotReady(ref error) => CheckHealthStatus::NotReady(error.clone()),
         }
     }
@@ -1,4 +1,5 @@
 use jsonrpc_core::{Error, ErrorCode};
+use zksync_dal::blocks_web3_dal::BlocksWeb3DalError;
 use zksync_web3_decl::error::Web3Error;
 
 pub fn into_jsrpc_error(err: Web3Error) -> Error {
@@ -13,6 +14,7 @@ pub fn into_jsrpc_error(err: Web3Error) -> Error {
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
             | Web3Error::LogsLimitExceeded(_, _, _)
+            | Web3Error::LogsBlockRangeTooWide(_)
             | Web3Error::InvalidFilterBlockHash => ErrorCode::InvalidParams,
             Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3.into(),
             Web3Error::PubSubTimeout => 4.into(),
@@ -41,3 +43,12 @@ pub fn internal_error(method_name: &str, error: impl ToString) -> Web3Error {
 
     Web3Error::InternalError
 }
+
+/// Maps an error returned by `BlocksWeb3Dal` to a Web3 error. Missing entities are reported
+/// to the caller; all other errors are internal.
+pub fn blocks_dal_error(method_name: &str, error: BlocksWeb3DalError) -> Web3Error {
+    match error {
+        BlocksWeb3DalError::NotFound => Web3Error::NoBlock,
+        _ => internal_error(method_name, error),
+    }
+}
@@ -0,0 +1,103 @@
+//! Middleware for the `jsonrpc` backend.
+
+use jsonrpc_core::{
+    futures::future::{Either, Future},
+    Call, Failure, FutureOutput, FutureResponse, Metadata, Middleware, Output,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use std::time::Duration;
+
+use super::error::into_jsrpc_error;
+
+/// Middleware cancelling method calls that run longer than the specified timeout. Timed out calls
+/// are answered with [`Web3Error::RequestTimeout`].
+///
+/// Cancelling a call drops its handler future, so all resources held by the handler
+/// (e.g., a `VmConcurrencyLimiter` permit) are released.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestTimeoutMiddleware {
+    timeout: Option<Duration>,
+}
+
+impl RequestTimeoutMiddleware {
+    /// Creates a middleware with the specified timeout. If the timeout is not set,
+    /// the middleware is a no-op.
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self { timeout }
+    }
+}
+
+impl<M: Metadata> Middleware<M> for RequestTimeoutMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = FutureOutput;
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let (Some(timeout), Call::MethodCall(method_call)) = (self.timeout, &call) else {
+            return Either::Right(next(call, meta));
+        };
+        let method = method_call.method.clone();
+        let jsonrpc = method_call.jsonrpc;
+        let id = method_call.id.clone();
+
+        let call_future = next(call, meta);
+        Either::Left(Box::pin(async move {
+            if let Ok(output) = tokio::time::timeout(timeout, call_future).await {
+                return output;
+            }
+            vlog::warn!("Call to `{method}` timed out after {timeout:?}");
+            metrics::counter!("api.web3.request_timeouts", 1, "method" => method);
+            let error = into_jsrpc_error(Web3Error::RequestTimeout);
+            Some(Output::Failure(Failure { jsonrpc, error, id }))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpc_core::{MetaIoHandler, Params, Value};
+    use tokio::sync::Semaphore;
+
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn slow_call_is_timed_out() {
+        let middleware = RequestTimeoutMiddleware::new(Some(Duration::from_millis(50)));
+        let mut io = MetaIoHandler::<(), _>::with_middleware(middleware);
+        // Emulates a handler holding a `VmConcurrencyLimiter` permit.
+        let limiter = Arc::new(Semaphore::new(1));
+        let handler_limiter = limiter.clone();
+        io.add_method("slow", move |_: Params| {
+            let limiter = handler_limiter.clone();
+            async move {
+                let _permit = limiter.acquire().await.unwrap();
+                tokio::time::sleep(Duration::from_secs(3_600)).await;
+                Ok(Value::Null)
+            }
+        });
+        io.add_method("fast", |_: Params| async { Ok(Value::Bool(true)) });
+
+        let response = io
+            .handle_request(r#"{"jsonrpc":"2.0","method":"slow","id":1}"#, ())
+            .await
+            .unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["error"]["code"], 5, "{response}");
+        // The permit held by the cancelled handler must be released.
+        assert_eq!(limiter.available_permits(), 1);
+
+        let response = io
+            .handle_request(r#"{"jsonrpc":"2.0","method":"fast","id":2}"#, ())
+            .await
+            .unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["result"], true, "{response}");
+    }
+}
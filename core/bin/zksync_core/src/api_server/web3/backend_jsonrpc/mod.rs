@@ -1,3 +1,5 @@
 pub mod error;
+pub mod middleware;
 pub mod namespaces;
 pub mod pub_sub;
+pub(crate) mod ws_server;
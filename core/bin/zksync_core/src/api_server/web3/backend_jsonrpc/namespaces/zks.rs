@@ -8,9 +8,9 @@ use jsonrpc_derive::rpc;
 
 // Workspace uses
 use zksync_types::{
-    api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails},
+    api::{BridgeAddresses, L2ToL1LogProof, ProtocolVersionInfo, TransactionDetails},
     explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
+    fee::{Fee, FeeBreakdown},
     transaction_request::CallRequest,
     Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
@@ -26,6 +26,9 @@ pub trait ZksNamespaceT {
     #[rpc(name = "zks_estimateFee")]
     fn estimate_fee(&self, req: CallRequest) -> BoxFuture<Result<Fee>>;
 
+    #[rpc(name = "zks_estimateFeeDetailed")]
+    fn estimate_fee_detailed(&self, req: CallRequest) -> BoxFuture<Result<FeeBreakdown>>;
+
     #[rpc(name = "zks_estimateGasL1ToL2")]
     fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> BoxFuture<Result<U256>>;
 
@@ -87,6 +90,9 @@ pub trait ZksNamespaceT {
     #[rpc(name = "zks_getTransactionDetails")]
     fn get_transaction_details(&self, hash: H256) -> BoxFuture<Result<Option<TransactionDetails>>>;
 
+    #[rpc(name = "zks_getTransactionAge")]
+    fn get_transaction_age(&self, hash: H256) -> BoxFuture<Result<Option<U64>>>;
+
     #[rpc(name = "zks_getRawBlockTransactions")]
     fn get_raw_block_transactions(
         &self,
@@ -104,6 +110,9 @@ pub trait ZksNamespaceT {
 
     #[rpc(name = "zks_getL1GasPrice")]
     fn get_l1_gas_price(&self) -> BoxFuture<Result<U64>>;
+
+    #[rpc(name = "zks_getProtocolVersion")]
+    fn get_protocol_version(&self) -> BoxFuture<Result<ProtocolVersionInfo>>;
 }
 
 impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespace<G> {
@@ -112,6 +121,16 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
         Box::pin(async move { self_.estimate_fee_impl(req).await.map_err(into_jsrpc_error) })
     }
 
+    fn estimate_fee_detailed(&self, req: CallRequest) -> BoxFuture<Result<FeeBreakdown>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .estimate_fee_detailed_impl(req)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
     fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> BoxFuture<Result<U256>> {
         let self_ = self.clone();
         Box::pin(async move {
@@ -248,6 +267,16 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
         })
     }
 
+    fn get_transaction_age(&self, hash: H256) -> BoxFuture<Result<Option<U64>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_transaction_age_impl(hash)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
     fn set_known_bytecode(&self, _bytecode: Bytes) -> BoxFuture<Result<bool>> {
         #[cfg(feature = "openzeppelin_tests")]
         let self_ = self.clone();
@@ -295,4 +324,14 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
         let self_ = self.clone();
         Box::pin(async move { Ok(self_.get_l1_gas_price_impl()) })
     }
+
+    fn get_protocol_version(&self) -> BoxFuture<Result<ProtocolVersionInfo>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_protocol_version_impl()
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
 }
@@ -7,7 +7,12 @@ use jsonrpc_pubsub::{Session, SubscriptionId};
 
 use zksync_web3_decl::types::PubSubResult;
 
-use super::super::namespaces::EthSubscribe;
+use super::super::namespaces::{ConnectionId, EthSubscribe};
+
+/// Returns the identifier of the WebSocket connection that the session belongs to.
+pub(crate) fn connection_id(session: &Arc<Session>) -> ConnectionId {
+    Arc::as_ptr(session) as ConnectionId
+}
 
 #[rpc]
 pub trait Web3PubSub {
@@ -39,15 +44,16 @@ impl Web3PubSub for EthSubscribe {
 
     fn subscribe(
         &self,
-        _meta: Self::Metadata,
+        meta: Self::Metadata,
         subscriber: typed::Subscriber<PubSubResult>,
         sub_type: String,
         params: Option<serde_json::Value>,
     ) {
         let self_ = self.clone();
+        let connection_id = connection_id(&meta);
         // Fire and forget is OK here.
         self.runtime_handle
-            .spawn(async move { self_.sub(subscriber, sub_type, params).await });
+            .spawn(async move { self_.sub(connection_id, subscriber, sub_type, params).await });
     }
 
     fn unsubscribe(
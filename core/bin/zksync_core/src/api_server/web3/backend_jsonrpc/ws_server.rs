@@ -0,0 +1,561 @@
+//! WebSocket transport for the `jsonrpc` backend.
+//!
+//! The transport replaces the one from `jsonrpc_ws_server` in order to apply backpressure
+//! to subscription notifications. Outbound messages of the `jsonrpc_ws_server` transport
+//! are queued into an unbounded channel, so a client that doesn't read notifications
+//! makes the server buffer them indefinitely.
+//!
+//! Here, each session tracks notifications that were sent to the client, but were not acknowledged
+//! by it. Acknowledgements are piggybacked on WebSocket pings: after sending a batch of notifications,
+//! the server pings the client with the number of notifications sent so far as the ping payload,
+//! and the client echoes the payload in a pong (as required by RFC 6455). If the number of
+//! unacknowledged notifications exceeds the configured limit, the client is disconnected.
+//!
+//! Additionally, the server can periodically ping clients to detect dead connections. A client that
+//! doesn't respond to a keepalive ping until the next one is due is disconnected.
+
+use futures::{channel::mpsc, StreamExt};
+use jsonrpc_core::MetaIoHandler;
+use jsonrpc_pubsub::{PubSubHandler, Session};
+use jsonrpc_ws_server::ws;
+
+use std::{
+    io,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use super::middleware::RequestTimeoutMiddleware;
+
+type IoHandler = MetaIoHandler<Arc<Session>, RequestTimeoutMiddleware>;
+
+/// Timeout token used to disconnect a client from outside the event loop.
+const SLOW_CONSUMER_TOKEN: ws::util::Token = ws::util::Token(1);
+/// Timeout token used for keepalive pings.
+const PING_TOKEN: ws::util::Token = ws::util::Token(2);
+/// Hard limit for the input and output buffers of a connection (the same as in `jsonrpc_ws_server`).
+const MAX_BUFFER_CAPACITY: usize = 10 * 1024 * 1024;
+/// Default maximum number of connections (the same as in `jsonrpc_ws_server`).
+const DEFAULT_MAX_CONNECTIONS: usize = 100;
+/// Maximum request payload size (the same as in `jsonrpc_ws_server`).
+const MAX_PAYLOAD_BYTES: usize = 5 * 1024 * 1024;
+
+/// Builder for the WebSocket server of the `jsonrpc` backend.
+pub(crate) struct WsServerBuilder {
+    io: Arc<IoHandler>,
+    runtime_handle: tokio::runtime::Handle,
+    max_connections: usize,
+    max_unacknowledged_notifications: Option<usize>,
+    ping_interval: Option<Duration>,
+    on_session: Arc<dyn Fn(&Arc<Session>) + Send + Sync>,
+}
+
+impl WsServerBuilder {
+    /// Creates a builder for the server handling requests with `io`. Requests and outbound
+    /// notifications are processed on the runtime with the specified handle.
+    pub fn new(
+        io: PubSubHandler<Arc<Session>, RequestTimeoutMiddleware>,
+        runtime_handle: tokio::runtime::Handle,
+    ) -> Self {
+        Self {
+            io: Arc::new(io.into()),
+            runtime_handle,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            max_unacknowledged_notifications: None,
+            ping_interval: None,
+            on_session: Arc::new(|_| { /* do nothing */ }),
+        }
+    }
+
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    /// Sets the maximum number of notifications not acknowledged by a client. Clients exceeding
+    /// the limit are disconnected. If not set, notifications are not tracked.
+    pub fn max_unacknowledged_notifications(mut self, limit: usize) -> Self {
+        self.max_unacknowledged_notifications = Some(limit);
+        self
+    }
+
+    /// Sets the interval between keepalive pings sent to each client.
+    pub fn ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    /// Sets a hook called for each established session.
+    pub fn on_session(mut self, hook: impl Fn(&Arc<Session>) + Send + Sync + 'static) -> Self {
+        self.on_session = Arc::new(hook);
+        self
+    }
+
+    /// Binds the server to the specified address. The returned server should be started
+    /// with [`ws::WebSocket::run()`]; it can be stopped using its [`broadcaster`](ws::WebSocket::broadcaster()).
+    pub fn bind(self, addr: SocketAddr) -> ws::Result<ws::WebSocket<SessionFactory>> {
+        // `ws::Settings` is non-exhaustive, so it cannot be initialized with a struct expression.
+        let mut settings = ws::Settings::default();
+        settings.max_connections = self.max_connections;
+        settings.max_fragment_size = MAX_PAYLOAD_BYTES;
+        settings.in_buffer_capacity_hard_limit = MAX_BUFFER_CAPACITY;
+        settings.out_buffer_capacity_hard_limit = MAX_BUFFER_CAPACITY;
+        // Don't grow non-final fragments (to prevent DoS).
+        settings.fragments_grow = false;
+        settings.fragments_capacity = (MAX_PAYLOAD_BYTES / settings.fragment_size).max(1);
+        // Accept only handshakes beginning with `GET`.
+        settings.method_strict = true;
+        // Require masking.
+        settings.masking_strict = true;
+        settings.shutdown_on_interrupt = false;
+
+        let factory = SessionFactory {
+            io: self.io,
+            runtime_handle: self.runtime_handle,
+            max_unacknowledged_notifications: self.max_unacknowledged_notifications,
+            ping_interval: self.ping_interval,
+            on_session: self.on_session,
+        };
+        ws::Builder::new()
+            .with_settings(settings)
+            .build(factory)?
+            .bind(addr)
+    }
+}
+
+pub(crate) struct SessionFactory {
+    io: Arc<IoHandler>,
+    runtime_handle: tokio::runtime::Handle,
+    max_unacknowledged_notifications: Option<usize>,
+    ping_interval: Option<Duration>,
+    on_session: Arc<dyn Fn(&Arc<Session>) + Send + Sync>,
+}
+
+impl ws::Factory for SessionFactory {
+    type Handler = SessionHandler;
+
+    fn connection_made(&mut self, out: ws::Sender) -> Self::Handler {
+        SessionHandler {
+            out,
+            io: self.io.clone(),
+            runtime_handle: self.runtime_handle.clone(),
+            max_unacknowledged_notifications: self.max_unacknowledged_notifications,
+            ping_interval: self.ping_interval,
+            on_session: self.on_session.clone(),
+            acknowledgements: Arc::default(),
+            last_ping: None,
+            last_pong: Instant::now(),
+            session: None,
+        }
+    }
+}
+
+/// Numbers of notifications sent to the client and acknowledged by it.
+#[derive(Debug, Default)]
+struct Acknowledgements {
+    sent: AtomicU64,
+    acknowledged: AtomicU64,
+}
+
+impl Acknowledgements {
+    fn acknowledge(&self, count: u64) {
+        let sent = self.sent.load(Ordering::SeqCst);
+        // Do not trust the client blindly; it cannot acknowledge notifications that were not sent.
+        self.acknowledged
+            .fetch_max(count.min(sent), Ordering::SeqCst);
+    }
+
+    fn unacknowledged(&self) -> u64 {
+        let acknowledged = self.acknowledged.load(Ordering::SeqCst);
+        self.sent.load(Ordering::SeqCst) - acknowledged
+    }
+}
+
+pub(crate) struct SessionHandler {
+    out: ws::Sender,
+    io: Arc<IoHandler>,
+    runtime_handle: tokio::runtime::Handle,
+    max_unacknowledged_notifications: Option<usize>,
+    ping_interval: Option<Duration>,
+    on_session: Arc<dyn Fn(&Arc<Session>) + Send + Sync>,
+    acknowledgements: Arc<Acknowledgements>,
+    /// Time when the last keepalive ping was sent.
+    last_ping: Option<Instant>,
+    /// Time when the last pong was received (or when the connection was established).
+    last_pong: Instant,
+    /// Set once the WebSocket handshake is completed.
+    session: Option<Arc<Session>>,
+}
+
+impl Drop for SessionHandler {
+    fn drop(&mut self) {
+        if self.session.is_some() {
+            metrics::decrement_gauge!("api.ws.open_sessions", 1.0);
+        }
+    }
+}
+
+impl SessionHandler {
+    fn schedule_ping(&self) -> ws::Result<()> {
+        if let Some(interval) = self.ping_interval {
+            self.out.timeout(interval.as_millis() as u64, PING_TOKEN)?;
+        }
+        Ok(())
+    }
+
+    fn ping(&mut self) -> ws::Result<()> {
+        let is_dead = matches!(self.last_ping, Some(last_ping) if self.last_pong < last_ping);
+        if is_dead {
+            vlog::info!(
+                "Closing WS connection #{}: client didn't respond to ping in {:?}",
+                self.out.connection_id(),
+                self.ping_interval.unwrap_or_default()
+            );
+            metrics::counter!("api.ws.dead_connections_closed", 1);
+            // See `on_timeout()` for the slow consumer case as to why an I/O error is returned.
+            return Err(
+                io::Error::new(io::ErrorKind::TimedOut, "client doesn't respond to pings").into(),
+            );
+        }
+
+        // Pings carry the number of sent notifications, so they acknowledge notifications as well.
+        let sent = self.acknowledgements.sent.load(Ordering::SeqCst);
+        self.out.ping(sent.to_be_bytes().to_vec())?;
+        self.last_ping = Some(Instant::now());
+        self.schedule_ping()
+    }
+
+    /// Forwards messages sent to the session (i.e., subscription notifications) to the client.
+    async fn forward_notifications(
+        mut receiver: mpsc::UnboundedReceiver<String>,
+        out: ws::Sender,
+        acknowledgements: Arc<Acknowledgements>,
+        limit: Option<usize>,
+    ) {
+        while let Some(message) = receiver.next().await {
+            let mut batch = vec![message];
+            while let Ok(Some(message)) = receiver.try_next() {
+                batch.push(message);
+            }
+
+            let batch_len = batch.len() as u64;
+            for message in batch {
+                if out.send(message).is_err() {
+                    return; // The event loop is stopped
+                }
+            }
+            let sent = acknowledgements.sent.fetch_add(batch_len, Ordering::SeqCst) + batch_len;
+            let Some(limit) = limit else {
+                continue;
+            };
+
+            let unacknowledged = acknowledgements.unacknowledged();
+            metrics::histogram!("api.ws.unacknowledged_notifications", unacknowledged as f64);
+            if unacknowledged > limit as u64 {
+                // Disconnect the client from the event loop, which will drop the session.
+                out.timeout(0, SLOW_CONSUMER_TOKEN).ok();
+                return;
+            }
+            if out.ping(sent.to_be_bytes().to_vec()).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl ws::Handler for SessionHandler {
+    fn on_open(&mut self, _shake: ws::Handshake) -> ws::Result<()> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.runtime_handle.spawn(Self::forward_notifications(
+            receiver,
+            self.out.clone(),
+            self.acknowledgements.clone(),
+            self.max_unacknowledged_notifications,
+        ));
+
+        let session = Arc::new(Session::new(sender));
+        (self.on_session)(&session);
+        self.session = Some(session);
+        metrics::increment_gauge!("api.ws.open_sessions", 1.0);
+        self.schedule_ping()
+    }
+
+    fn on_message(&mut self, message: ws::Message) -> ws::Result<()> {
+        let request = message.as_text()?;
+        let session = self
+            .session
+            .clone()
+            .expect("Session is always set in `on_open`");
+        let response = self.io.handle_request(request, session);
+        let out = self.out.clone();
+        self.runtime_handle.spawn(async move {
+            if let Some(response) = response.await {
+                out.send(response).ok();
+            }
+        });
+        Ok(())
+    }
+
+    fn on_frame(&mut self, frame: ws::Frame) -> ws::Result<Option<ws::Frame>> {
+        if frame.has_rsv1() || frame.has_rsv2() || frame.has_rsv3() {
+            return Err(ws::Error::new(
+                ws::ErrorKind::Protocol,
+                "Encountered frame with reserved bits set.",
+            ));
+        }
+        if frame.opcode() == ws::OpCode::Pong {
+            self.last_pong = Instant::now();
+            if let Ok(count) = <[u8; 8]>::try_from(frame.payload().as_slice()) {
+                self.acknowledgements.acknowledge(u64::from_be_bytes(count));
+            }
+        }
+        Ok(Some(frame))
+    }
+
+    fn on_timeout(&mut self, event: ws::util::Token) -> ws::Result<()> {
+        if event == PING_TOKEN {
+            return self.ping();
+        }
+        if event != SLOW_CONSUMER_TOKEN {
+            return Ok(());
+        }
+        let unacknowledged = self.acknowledgements.unacknowledged();
+        vlog::info!(
+            "Closing WS connection #{}: client doesn't read notifications, \
+             {unacknowledged} notifications are not acknowledged",
+            self.out.connection_id()
+        );
+        metrics::counter!("api.ws.slow_consumer_dropped", 1);
+        // Returning an I/O error makes the event loop drop the connection immediately, without
+        // a closing handshake (which the client is unlikely to respond to).
+        Err(io::Error::new(io::ErrorKind::TimedOut, "client doesn't read notifications").into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        sync::atomic::AtomicBool,
+        thread,
+    };
+
+    use tokio::sync::mpsc as tokio_mpsc;
+
+    use super::*;
+    use crate::testonly::counter_value;
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    struct TestServer {
+        addr: SocketAddr,
+        broadcaster: ws::Sender,
+        server_thread: thread::JoinHandle<()>,
+        sessions: tokio_mpsc::UnboundedReceiver<(mpsc::UnboundedSender<String>, Arc<AtomicBool>)>,
+    }
+
+    impl TestServer {
+        fn new(configure: impl FnOnce(WsServerBuilder) -> WsServerBuilder) -> Self {
+            let (sessions_sender, sessions) = tokio_mpsc::unbounded_channel();
+            let io = PubSubHandler::new(MetaIoHandler::with_middleware(
+                RequestTimeoutMiddleware::default(),
+            ));
+            let builder = WsServerBuilder::new(io, tokio::runtime::Handle::current());
+            let server = configure(builder)
+                .on_session(move |session| {
+                    let is_dropped = Arc::new(AtomicBool::new(false));
+                    let is_dropped_flag = is_dropped.clone();
+                    session.on_drop(move || is_dropped_flag.store(true, Ordering::SeqCst));
+                    sessions_sender.send((session.sender(), is_dropped)).ok();
+                })
+                .bind("127.0.0.1:0".parse().unwrap())
+                .unwrap();
+            let addr = server.local_addr().unwrap();
+            let broadcaster = server.broadcaster();
+            let server_thread = thread::spawn(move || {
+                server.run().unwrap();
+            });
+            Self {
+                addr,
+                broadcaster,
+                server_thread,
+                sessions,
+            }
+        }
+
+        fn stop(self) {
+            self.broadcaster.shutdown().unwrap();
+            self.server_thread.join().unwrap();
+        }
+    }
+
+    /// Connects to the server and performs the WebSocket handshake.
+    fn connect(addr: SocketAddr) -> TcpStream {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let handshake = format!(
+            "GET / HTTP/1.1\r\nHost: {addr}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n"
+        );
+        stream.write_all(handshake.as_bytes()).unwrap();
+
+        let mut response = vec![];
+        while !response.ends_with(b"\r\n\r\n") {
+            let mut byte = [0_u8];
+            stream.read_exact(&mut byte).unwrap();
+            response.push(byte[0]);
+        }
+        assert!(response.starts_with(b"HTTP/1.1 101"), "{response:?}");
+        stream
+    }
+
+    /// Reads an (unmasked) server frame, returning its opcode and payload.
+    fn read_frame(stream: &mut TcpStream) -> io::Result<(u8, Vec<u8>)> {
+        let mut header = [0_u8; 2];
+        stream.read_exact(&mut header)?;
+        let opcode = header[0] & 0x0f;
+        let len = match header[1] & 0x7f {
+            126 => {
+                let mut len = [0_u8; 2];
+                stream.read_exact(&mut len)?;
+                u16::from_be_bytes(len) as usize
+            }
+            127 => {
+                let mut len = [0_u8; 8];
+                stream.read_exact(&mut len)?;
+                u64::from_be_bytes(len) as usize
+            }
+            len => len as usize,
+        };
+        let mut payload = vec![0_u8; len];
+        stream.read_exact(&mut payload)?;
+        Ok((opcode, payload))
+    }
+
+    /// Writes a pong frame. Client frames must be masked; we use the zero mask for simplicity.
+    fn write_pong(stream: &mut TcpStream, payload: &[u8]) -> io::Result<()> {
+        let mut frame = vec![0x8a, 0x80 | payload.len() as u8, 0, 0, 0, 0];
+        frame.extend_from_slice(payload);
+        stream.write_all(&frame)
+    }
+
+    async fn wait_until(condition: impl Fn() -> bool) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !condition() {
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .expect("timed out waiting for condition");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn non_draining_client_is_dropped() {
+        const LIMIT: usize = 5;
+
+        let mut server = TestServer::new(|builder| builder.max_unacknowledged_notifications(LIMIT));
+        let dropped_count = counter_value("api_ws_slow_consumer_dropped");
+        // The client completes the handshake, but never reads notifications and never responds to pings.
+        let _stream = connect(server.addr);
+        let (sender, is_dropped) = server.sessions.recv().await.unwrap();
+
+        for i in 0..=LIMIT {
+            sender.unbounded_send(format!("notification #{i}")).unwrap();
+        }
+        wait_until(|| is_dropped.load(Ordering::SeqCst)).await;
+        assert_eq!(
+            counter_value("api_ws_slow_consumer_dropped"),
+            dropped_count + 1
+        );
+        server.stop();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn draining_client_is_not_dropped() {
+        const LIMIT: usize = 2;
+        const NOTIFICATION_COUNT: usize = LIMIT * 10;
+
+        let mut server = TestServer::new(|builder| builder.max_unacknowledged_notifications(LIMIT));
+        let mut stream = connect(server.addr);
+        let (sender, is_dropped) = server.sessions.recv().await.unwrap();
+
+        // The client reads all notifications and responds to pings, reporting acknowledged notifications.
+        let (ack_sender, mut ack_receiver) = tokio_mpsc::unbounded_channel();
+        let client_thread = thread::spawn(move || {
+            let mut received = 0;
+            loop {
+                let (opcode, payload) = read_frame(&mut stream).unwrap();
+                match opcode {
+                    0x1 => {
+                        assert_eq!(payload, format!("notification #{received}").into_bytes());
+                        received += 1;
+                    }
+                    0x9 => {
+                        write_pong(&mut stream, &payload).unwrap();
+                        ack_sender.send(received).ok();
+                        if received == NOTIFICATION_COUNT {
+                            break;
+                        }
+                    }
+                    _ => panic!("unexpected frame opcode: {opcode}"),
+                }
+            }
+            stream
+        });
+
+        for i in 0..NOTIFICATION_COUNT {
+            sender.unbounded_send(format!("notification #{i}")).unwrap();
+            while ack_receiver.recv().await.unwrap() <= i {
+                // Wait until the notification is acknowledged.
+            }
+        }
+        let _stream = client_thread.join().unwrap();
+        assert!(!is_dropped.load(Ordering::SeqCst));
+        server.stop();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn non_responsive_client_is_dropped() {
+        let mut server =
+            TestServer::new(|builder| builder.ping_interval(Duration::from_millis(50)));
+        let closed_count = counter_value("api_ws_dead_connections_closed");
+        // The client completes the handshake, but never responds to pings.
+        let _stream = connect(server.addr);
+        let (_sender, is_dropped) = server.sessions.recv().await.unwrap();
+
+        wait_until(|| is_dropped.load(Ordering::SeqCst)).await;
+        assert_eq!(
+            counter_value("api_ws_dead_connections_closed"),
+            closed_count + 1
+        );
+        server.stop();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn responsive_client_is_not_dropped() {
+        const PING_COUNT: usize = 5;
+
+        let mut server =
+            TestServer::new(|builder| builder.ping_interval(Duration::from_millis(50)));
+        let mut stream = connect(server.addr);
+        let (_sender, is_dropped) = server.sessions.recv().await.unwrap();
+
+        let client_thread = thread::spawn(move || {
+            for _ in 0..PING_COUNT {
+                let (opcode, payload) = read_frame(&mut stream).unwrap();
+                assert_eq!(opcode, 0x9);
+                write_pong(&mut stream, &payload).unwrap();
+            }
+            stream
+        });
+        let _stream = tokio::task::spawn_blocking(|| client_thread.join().unwrap())
+            .await
+            .unwrap();
+        assert!(!is_dropped.load(Ordering::SeqCst));
+        server.stop();
+    }
+}
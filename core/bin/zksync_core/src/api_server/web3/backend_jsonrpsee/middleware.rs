@@ -0,0 +1,381 @@
+//! Middleware for the `jsonrpsee` backend.
+
+use futures::future::BoxFuture;
+use hyper::{body::HttpBody, header, Body};
+use tower::{Layer, Service};
+use tower_http::{
+    compression::{predicate::SizeAbove, Compression, CompressionLayer},
+    cors::{AllowOrigin, CorsLayer},
+};
+use zksync_web3_decl::error::Web3Error;
+
+use std::{
+    fmt,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use super::into_jsrpc_error;
+
+/// Creates a CORS layer for the HTTP transport. Requests (including preflight ones) are allowed
+/// from `allowed_origins`; `*` in the list allows any origin. If `allowed_origins` is not specified,
+/// any origin is allowed as well. Origins that are not valid header values are ignored.
+pub fn cors_layer(allowed_origins: Option<&[String]>) -> CorsLayer {
+    let allow_origin = match allowed_origins {
+        Some(origins) if !origins.iter().any(|origin| origin == "*") => {
+            let origins = origins.iter().filter_map(|origin| {
+                let value = header::HeaderValue::from_str(origin);
+                if value.is_err() {
+                    vlog::warn!("Ignoring invalid CORS origin: {origin:?}");
+                }
+                value.ok()
+            });
+            AllowOrigin::list(origins)
+        }
+        _ => AllowOrigin::any(),
+    };
+
+    CorsLayer::new()
+        // Allow `POST` when accessing the resource
+        .allow_methods([hyper::Method::POST])
+        .allow_origin(allow_origin)
+        .allow_headers([header::CONTENT_TYPE])
+}
+
+/// Layer cancelling HTTP requests that are processed longer than the specified timeout.
+/// Timed out requests are answered with a JSON-RPC [`Web3Error::RequestTimeout`] error;
+/// since the request ID is unknown at this level, the error has a `null` ID.
+///
+/// Cancelling a request drops its handler future, so all resources held by the handler
+/// (e.g., a `VmConcurrencyLimiter` permit) are released.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestTimeoutLayer {
+    timeout: Option<Duration>,
+}
+
+impl RequestTimeoutLayer {
+    /// Creates a layer with the specified timeout. If the timeout is not set, the layer is a no-op.
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self { timeout }
+    }
+}
+
+impl<S> Layer<S> for RequestTimeoutLayer {
+    type Service = RequestTimeout<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestTimeout {
+            inner,
+            timeout: self.timeout,
+        }
+    }
+}
+
+/// Service produced by [`RequestTimeoutLayer`].
+#[derive(Debug, Clone)]
+pub struct RequestTimeout<S> {
+    inner: S,
+    timeout: Option<Duration>,
+}
+
+fn timeout_response() -> hyper::Response<Body> {
+    let error = into_jsrpc_error(Web3Error::RequestTimeout);
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": error,
+        "id": null,
+    });
+    hyper::Response::builder()
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+impl<S> Service<hyper::Request<Body>> for RequestTimeout<S>
+where
+    S: Service<hyper::Request<Body>, Response = hyper::Response<Body>>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+{
+    type Response = hyper::Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: hyper::Request<Body>) -> Self::Future {
+        let timeout = self.timeout;
+        let call_future = self.inner.call(request);
+        Box::pin(async move {
+            let Some(timeout) = timeout else {
+                return call_future.await;
+            };
+            if let Ok(response) = tokio::time::timeout(timeout, call_future).await {
+                return response;
+            }
+            vlog::warn!("HTTP JSON-RPC request timed out after {timeout:?}");
+            metrics::counter!("api.web3.request_timeouts", 1, "method" => "unknown");
+            Ok(timeout_response())
+        })
+    }
+}
+
+/// Layer compressing HTTP responses, depending on the `Accept-Encoding` header of the request. Responses
+/// smaller than the specified min size are sent uncompressed, since compressing them saves little bandwidth.
+///
+/// Compression itself is performed by [`CompressionLayer`]; this layer additionally reports the number
+/// of bytes saved by compression.
+#[derive(Debug, Clone, Copy)]
+pub struct ResponseCompressionLayer {
+    min_size: u16,
+}
+
+impl ResponseCompressionLayer {
+    /// Creates a layer with the specified min size of compressed responses (in bytes).
+    /// Min sizes exceeding `u16::MAX` are capped.
+    pub fn new(min_size: usize) -> Self {
+        Self {
+            min_size: u16::try_from(min_size).unwrap_or(u16::MAX),
+        }
+    }
+}
+
+impl<S> Layer<S> for ResponseCompressionLayer {
+    type Service = ResponseCompression<Compression<RecordUncompressedSize<S>, SizeAbove>>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let compression = CompressionLayer::new().compress_when(SizeAbove::new(self.min_size));
+        ResponseCompression {
+            inner: compression.layer(RecordUncompressedSize { inner }),
+        }
+    }
+}
+
+/// Size of a response body before compression.
+#[derive(Debug, Clone, Copy)]
+struct UncompressedSize(u64);
+
+/// Service recording [`UncompressedSize`] in the response extensions, which are retained by [`Compression`].
+#[derive(Debug, Clone)]
+pub struct RecordUncompressedSize<S> {
+    inner: S,
+}
+
+impl<S> Service<hyper::Request<Body>> for RecordUncompressedSize<S>
+where
+    S: Service<hyper::Request<Body>, Response = hyper::Response<Body>>,
+    S::Future: Send + 'static,
+{
+    type Response = hyper::Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: hyper::Request<Body>) -> Self::Future {
+        let call_future = self.inner.call(request);
+        Box::pin(async move {
+            let mut response = call_future.await?;
+            if let Some(size) = response.body().size_hint().exact() {
+                response.extensions_mut().insert(UncompressedSize(size));
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Service produced by [`ResponseCompressionLayer`].
+#[derive(Debug, Clone)]
+pub struct ResponseCompression<S> {
+    inner: S,
+}
+
+async fn report_bytes_saved<B>(response: hyper::Response<B>) -> hyper::Response<Body>
+where
+    B: HttpBody,
+    B::Error: fmt::Display,
+{
+    let (parts, body) = response.into_parts();
+    // JSON-RPC responses are buffered by the server anyway, so buffering them here is cheap.
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(err) => {
+            vlog::warn!("Failed reading HTTP JSON-RPC response body: {err}");
+            return hyper::Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let encoding = parts
+        .headers
+        .get(header::CONTENT_ENCODING)
+        .and_then(|encoding| encoding.to_str().ok());
+    let uncompressed_size = parts.extensions.get::<UncompressedSize>();
+    if let (Some(encoding), Some(UncompressedSize(uncompressed_size))) =
+        (encoding, uncompressed_size)
+    {
+        metrics::counter!(
+            "api.web3.compression.bytes_saved",
+            uncompressed_size.saturating_sub(body.len() as u64),
+            "encoding" => encoding.to_owned()
+        );
+    }
+    hyper::Response::from_parts(parts, Body::from(body))
+}
+
+impl<S, B> Service<hyper::Request<Body>> for ResponseCompression<S>
+where
+    S: Service<hyper::Request<Body>, Response = hyper::Response<B>>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: fmt::Display,
+{
+    type Response = hyper::Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: hyper::Request<Body>) -> Self::Future {
+        let call_future = self.inner.call(request);
+        Box::pin(async move {
+            let response = call_future.await?;
+            Ok(report_bytes_saved(response).await)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::Semaphore;
+    use tower::ServiceExt;
+
+    use std::{convert::Infallible, io, sync::Arc};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn slow_request_is_timed_out() {
+        // Emulates a handler holding a `VmConcurrencyLimiter` permit.
+        let limiter = Arc::new(Semaphore::new(1));
+        let handler_limiter = limiter.clone();
+        let service = tower::service_fn(move |_: hyper::Request<Body>| {
+            let limiter = handler_limiter.clone();
+            async move {
+                let _permit = limiter.acquire().await.unwrap();
+                tokio::time::sleep(Duration::from_secs(3_600)).await;
+                Ok::<_, Infallible>(hyper::Response::new(Body::empty()))
+            }
+        });
+        let service = RequestTimeoutLayer::new(Some(Duration::from_millis(50))).layer(service);
+
+        let response = service.oneshot(hyper::Request::default()).await.unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], 5, "{body}");
+        assert!(body["id"].is_null(), "{body}");
+        // The permit held by the cancelled handler must be released.
+        assert_eq!(limiter.available_permits(), 1);
+    }
+
+    async fn send_preflight_request(cors: CorsLayer, origin: &str) -> hyper::Response<Body> {
+        let service = tower::service_fn(|_: hyper::Request<Body>| async {
+            Ok::<_, Infallible>(hyper::Response::new(Body::empty()))
+        });
+        let request = hyper::Request::builder()
+            .method(hyper::Method::OPTIONS)
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .body(Body::empty())
+            .unwrap();
+        cors.layer(service).oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn preflight_request_respects_cors_origins() {
+        let origins = ["https://app.example.com".to_owned()];
+        let response =
+            send_preflight_request(cors_layer(Some(&origins)), "https://app.example.com").await;
+        let headers = response.headers();
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_METHODS], "POST");
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_HEADERS],
+            "content-type"
+        );
+
+        let response =
+            send_preflight_request(cors_layer(Some(&origins)), "https://evil.example.com").await;
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let any_origin = ["*".to_owned()];
+        for cors in [cors_layer(Some(&any_origin)), cors_layer(None)] {
+            let response = send_preflight_request(cors, "https://evil.example.com").await;
+            assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        }
+    }
+
+    async fn send_request_with_encoding(
+        response_body: String,
+        accept_encoding: Option<&str>,
+    ) -> hyper::Response<Body> {
+        let service = tower::service_fn(move |_: hyper::Request<Body>| {
+            let response_body = response_body.clone();
+            async move { Ok::<_, Infallible>(hyper::Response::new(Body::from(response_body))) }
+        });
+        let service = ResponseCompressionLayer::new(1_024).layer(service);
+
+        let mut request = hyper::Request::builder().method(hyper::Method::POST);
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header(header::ACCEPT_ENCODING, accept_encoding);
+        }
+        let request = request.body(Body::empty()).unwrap();
+        service.oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn large_response_is_compressed() {
+        let large_body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "result": vec!["0x0000000000000000000000000000000000000000"; 100],
+            "id": 1,
+        })
+        .to_string();
+
+        let response = send_request_with_encoding(large_body.clone(), Some("gzip")).await;
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(body.len() < large_body.len());
+        let mut decompressed_body = String::new();
+        io::Read::read_to_string(
+            &mut flate2::read::GzDecoder::new(&body[..]),
+            &mut decompressed_body,
+        )
+        .unwrap();
+        assert_eq!(decompressed_body, large_body);
+
+        let response = send_request_with_encoding(large_body.clone(), None).await;
+        assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, large_body.as_bytes());
+
+        let small_body = r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#.to_owned();
+        let response = send_request_with_encoding(small_body.clone(), Some("gzip")).await;
+        assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, small_body.as_bytes());
+    }
+}
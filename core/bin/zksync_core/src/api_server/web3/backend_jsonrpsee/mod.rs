@@ -6,6 +6,7 @@ use std::error::Error;
 use zksync_web3_decl::error::Web3Error;
 use zksync_web3_decl::jsonrpsee::types::{error::ErrorCode, ErrorObjectOwned};
 
+pub mod middleware;
 pub mod namespaces;
 
 pub fn from_std_error(e: impl Error) -> ErrorObjectOwned {
@@ -24,7 +25,8 @@ pub fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
             | Web3Error::InvalidFilterBlockHash
-            | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
+            | Web3Error::LogsLimitExceeded(_, _, _)
+            | Web3Error::LogsBlockRangeTooWide(_) => ErrorCode::InvalidParams.code(),
             Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3,
             Web3Error::PubSubTimeout => 4,
             Web3Error::RequestTimeout => 5,
@@ -3,9 +3,9 @@ use bigdecimal::BigDecimal;
 use std::collections::HashMap;
 
 use zksync_types::{
-    api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails, U64},
+    api::{BridgeAddresses, L2ToL1LogProof, ProtocolVersionInfo, TransactionDetails, U64},
     explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
+    fee::{Fee, FeeBreakdown},
     transaction_request::CallRequest,
     Address, L1BatchNumber, MiniblockNumber, H256, U256,
 };
@@ -26,6 +26,12 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
         self.estimate_fee_impl(req).await.map_err(into_jsrpc_error)
     }
 
+    async fn estimate_fee_detailed(&self, req: CallRequest) -> RpcResult<FeeBreakdown> {
+        self.estimate_fee_detailed_impl(req)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256> {
         self.estimate_l1_to_l2_gas_impl(req)
             .await
@@ -118,6 +124,12 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_transaction_age(&self, hash: H256) -> RpcResult<Option<U64>> {
+        self.get_transaction_age_impl(hash)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_raw_block_transactions(
         &self,
         block_number: MiniblockNumber,
@@ -143,4 +155,10 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
     async fn get_l1_gas_price(&self) -> RpcResult<U64> {
         Ok(self.get_l1_gas_price_impl())
     }
+
+    async fn get_protocol_version(&self) -> RpcResult<ProtocolVersionInfo> {
+        self.get_protocol_version_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
 }
@@ -0,0 +1,123 @@
+//! Cache for the mapping of miniblocks to L1 batches.
+
+use zksync_dal::{blocks_web3_dal::BlocksWeb3DalError, StorageProcessor};
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+
+/// LRU cache for numbers of L1 batches including miniblocks. Since the mapping is immutable
+/// once a miniblock is included into a sealed L1 batch, only such mappings are cached;
+/// pending miniblocks are always looked up in Postgres.
+///
+/// Clones of the cache share the underlying storage.
+#[derive(Debug, Clone)]
+pub struct L1BatchNumberCache {
+    cache: mini_moka::sync::Cache<MiniblockNumber, L1BatchNumber>,
+}
+
+impl L1BatchNumberCache {
+    /// Creates a cache holding up to `capacity` entries.
+    pub fn new(capacity: u64) -> Self {
+        Self {
+            cache: mini_moka::sync::Cache::new(capacity),
+        }
+    }
+
+    /// Returns the number of the L1 batch including the specified miniblock, or `None`
+    /// if the miniblock is not included into an L1 batch yet.
+    pub async fn get_l1_batch_number_of_miniblock(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+    ) -> Result<Option<L1BatchNumber>, BlocksWeb3DalError> {
+        let cached = self.cache.get(&miniblock_number);
+        metrics::increment_counter!(
+            "api.web3.l1_batch_number_cache.requests",
+            "kind" => if cached.is_some() { "hit" } else { "miss" }
+        );
+        if cached.is_some() {
+            return Ok(cached);
+        }
+
+        let l1_batch_number = storage
+            .blocks_web3_dal()
+            .get_l1_batch_number_of_miniblock(miniblock_number)
+            .await?;
+        if let Some(l1_batch_number) = l1_batch_number {
+            self.cache.insert(miniblock_number, l1_batch_number);
+        }
+        Ok(l1_batch_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_dal::ConnectionPool;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
+        Address,
+    };
+    use zksync_utils::miniblock_hash;
+
+    use super::*;
+
+    #[db_test]
+    async fn l1_batch_numbers_are_cached_for_sealed_miniblocks(pool: ConnectionPool) {
+        let mut storage = pool.access_storage().await;
+        storage.blocks_dal().delete_genesis().await;
+        let miniblock_header = MiniblockHeader {
+            number: MiniblockNumber(1),
+            timestamp: 1,
+            hash: miniblock_hash(MiniblockNumber(1)),
+            l1_tx_count: 0,
+            l2_tx_count: 0,
+            base_fee_per_gas: 100,
+            l1_gas_price: 100,
+            l2_fair_gas_price: 100,
+            base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        };
+        storage
+            .blocks_dal()
+            .insert_miniblock(&miniblock_header)
+            .await;
+
+        let cache = L1BatchNumberCache::new(16);
+        let l1_batch_number = cache
+            .get_l1_batch_number_of_miniblock(&mut storage, MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(l1_batch_number, None);
+        // Pending miniblocks must not be cached.
+        assert!(!cache.cache.contains_key(&MiniblockNumber(1)));
+
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&l1_batch_header, BlockGasCount::default())
+            .await;
+        storage
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await;
+
+        let l1_batch_number = cache
+            .get_l1_batch_number_of_miniblock(&mut storage, MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(l1_batch_number, Some(L1BatchNumber(1)));
+        assert!(cache.cache.contains_key(&MiniblockNumber(1)));
+
+        // The second lookup should be served from the cache even if Postgres no longer has the data.
+        storage.blocks_dal().delete_genesis().await;
+        let l1_batch_number = cache
+            .get_l1_batch_number_of_miniblock(&mut storage, MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(l1_batch_number, Some(L1BatchNumber(1)));
+    }
+}
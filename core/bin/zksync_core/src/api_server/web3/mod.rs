@@ -7,11 +7,10 @@ use std::time::Duration;
 // External uses
 use futures::channel::oneshot;
 use futures::FutureExt;
-use jsonrpc_core::IoHandler;
-use jsonrpc_http_server::hyper;
+use jsonrpc_core::MetaIoHandler;
 use jsonrpc_pubsub::PubSubHandler;
 use tokio::sync::{watch, RwLock};
-use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
+use tower_http::metrics::InFlightRequestsLayer;
 
 // Workspace uses
 use zksync_contracts::BaseSystemContractsHashes;
@@ -35,17 +34,23 @@ use crate::sync_layer::SyncState;
 use super::tx_sender::TxSender;
 use crate::api_server::web3::api_health_check::ApiHealthCheck;
 use backend_jsonrpc::{
-    error::internal_error,
+    error::blocks_dal_error,
+    middleware::RequestTimeoutMiddleware,
     namespaces::{
         debug::DebugNamespaceT, en::EnNamespaceT, eth::EthNamespaceT, net::NetNamespaceT,
         web3::Web3NamespaceT, zks::ZksNamespaceT,
     },
-    pub_sub::Web3PubSub,
+    pub_sub::{connection_id, Web3PubSub},
+    ws_server::WsServerBuilder,
 };
+use backend_jsonrpsee::middleware::{cors_layer, RequestTimeoutLayer, ResponseCompressionLayer};
+use l1_batch_number_cache::L1BatchNumberCache;
 use namespaces::{
     DebugNamespace, EnNamespace, EthNamespace, EthSubscribe, NetNamespace, Web3Namespace,
     ZksNamespace,
 };
+
+pub use namespaces::SubscriptionLimits;
 use pubsub_notifier::{notify_blocks, notify_logs, notify_txs};
 use state::{Filters, RpcState};
 use zksync_health_check::CheckHealthStatus;
@@ -53,6 +58,7 @@ use zksync_health_check::CheckHealthStatus;
 pub mod api_health_check;
 pub mod backend_jsonrpc;
 pub mod backend_jsonrpsee;
+pub mod l1_batch_number_cache;
 pub mod namespaces;
 mod pubsub_notifier;
 pub mod state;
@@ -78,10 +84,16 @@ pub struct ApiBuilder<G> {
     tx_sender: Option<TxSender<G>>,
     filters_limit: Option<usize>,
     subscriptions_limit: Option<usize>,
+    subscription_type_limits: SubscriptionLimits,
     sync_state: Option<SyncState>,
     threads: Option<usize>,
     vm_concurrency_limit: Option<usize>,
     polling_interval: Option<Duration>,
+    websocket_ping_interval: Option<Duration>,
+    max_buffered_notifications: Option<usize>,
+    request_timeout: Option<Duration>,
+    cors_origins: Option<Vec<String>>,
+    response_compression_min_size: Option<usize>,
     accounts: HashMap<Address, PrivateKeySigner>,
     debug_namespace_config: Option<(BaseSystemContractsHashes, u64, Option<usize>)>,
 }
@@ -96,9 +108,15 @@ impl<G> ApiBuilder<G> {
             tx_sender: None,
             filters_limit: None,
             subscriptions_limit: None,
+            subscription_type_limits: SubscriptionLimits::default(),
             threads: None,
             vm_concurrency_limit: None,
             polling_interval: None,
+            websocket_ping_interval: None,
+            max_buffered_notifications: None,
+            request_timeout: None,
+            cors_origins: None,
+            response_compression_min_size: None,
             debug_namespace_config: None,
             accounts: Default::default(),
             config,
@@ -114,9 +132,15 @@ impl<G> ApiBuilder<G> {
             tx_sender: None,
             filters_limit: None,
             subscriptions_limit: None,
+            subscription_type_limits: SubscriptionLimits::default(),
             threads: None,
             vm_concurrency_limit: None,
             polling_interval: None,
+            websocket_ping_interval: None,
+            max_buffered_notifications: None,
+            request_timeout: None,
+            cors_origins: None,
+            response_compression_min_size: None,
             debug_namespace_config: None,
             accounts: Default::default(),
             config,
@@ -148,6 +172,13 @@ impl<G> ApiBuilder<G> {
         self
     }
 
+    /// Sets limits on the number of subscriptions of each type per connection. These limits
+    /// are applied in addition to the `subscriptions_limit`.
+    pub fn with_subscription_type_limits(mut self, limits: SubscriptionLimits) -> Self {
+        self.subscription_type_limits = limits;
+        self
+    }
+
     pub fn with_sync_state(mut self, sync_state: SyncState) -> Self {
         self.sync_state = Some(sync_state);
         self
@@ -163,6 +194,47 @@ impl<G> ApiBuilder<G> {
         self
     }
 
+    /// Sets the interval between pings sent to WebSocket clients. Clients not responding to a ping
+    /// until the next one is due are disconnected; this allows detecting half-open connections,
+    /// which otherwise would consume connection slots indefinitely. The jsonrpsee backend only sends
+    /// pings and doesn't disconnect non-responsive clients.
+    pub fn with_websocket_ping_interval(mut self, ping_interval: Duration) -> Self {
+        self.websocket_ping_interval = Some(ping_interval);
+        self
+    }
+
+    /// Sets the max number of notifications sent to a WebSocket client, but not acknowledged by it.
+    /// If a client doesn't read notifications fast enough and exceeds the limit, the connection
+    /// is closed. Only supported for the jsonrpc backend.
+    pub fn with_max_buffered_notifications(mut self, limit: usize) -> Self {
+        self.max_buffered_notifications = Some(limit);
+        self
+    }
+
+    /// Sets the max duration of processing a single request. Requests exceeding this timeout
+    /// are cancelled and answered with a timeout error; cancellation releases resources held
+    /// by the request handler, such as VM permits.
+    /// Not supported for the jsonrpsee backend with WebSocket transport.
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = Some(request_timeout);
+        self
+    }
+
+    /// Sets origins allowed to access the HTTP API from browsers; `*` allows any origin.
+    /// If not set, any origin is allowed. Only supported for the jsonrpsee backend with HTTP transport.
+    pub fn with_cors(mut self, origins: Vec<String>) -> Self {
+        self.cors_origins = Some(origins);
+        self
+    }
+
+    /// Enables compression of responses with size at least `min_size` bytes; the encoding
+    /// is negotiated via the `Accept-Encoding` request header. Only supported for the jsonrpsee backend
+    /// with HTTP transport.
+    pub fn with_response_compression(mut self, min_size: usize) -> Self {
+        self.response_compression_min_size = Some(min_size);
+        self
+    }
+
     pub fn with_vm_concurrency_limit(mut self, vm_concurrency_limit: usize) -> Self {
         self.vm_concurrency_limit = Some(vm_concurrency_limit);
         self
@@ -211,6 +283,7 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             sync_state: self.sync_state.clone(),
             api_config: self.config.clone(),
             accounts: self.accounts.clone(),
+            l1_batch_number_cache: L1BatchNumberCache::new(self.config.l1_batch_number_cache_size),
             #[cfg(feature = "openzeppelin_tests")]
             known_bytecodes: Arc::new(RwLock::new(Default::default())),
         }
@@ -275,6 +348,63 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             }
             _ => {}
         }
+        if self.websocket_ping_interval.is_some()
+            && matches!(self.transport, Some(ApiTransport::Http(_)))
+        {
+            vlog::warn!("`websocket_ping_interval` is ignored for HTTP transport");
+        }
+        if self.max_buffered_notifications.is_some() {
+            match (self.backend, &self.transport) {
+                (_, Some(ApiTransport::Http(_))) => {
+                    vlog::warn!("`max_buffered_notifications` is ignored for HTTP transport");
+                }
+                (ApiBackend::Jsonrpsee, _) => {
+                    vlog::warn!(
+                        "`max_buffered_notifications` is not supported for jsonrpsee backend, \
+                         use jsonrpc instead"
+                    );
+                }
+                _ => {}
+            }
+        }
+        if self.request_timeout.is_some() {
+            if let (ApiBackend::Jsonrpsee, Some(ApiTransport::WebSocket(_))) =
+                (self.backend, &self.transport)
+            {
+                vlog::warn!(
+                    "`request_timeout` is not supported for jsonrpsee backend with WebSocket \
+                     transport, use jsonrpc instead"
+                );
+            }
+        }
+
+        if self.cors_origins.is_some() {
+            match (self.backend, &self.transport) {
+                (_, Some(ApiTransport::WebSocket(_))) => {
+                    vlog::warn!("`cors_origins` is ignored for WebSocket transport");
+                }
+                (ApiBackend::Jsonrpc, _) => {
+                    vlog::warn!(
+                        "`cors_origins` is not supported for jsonrpc backend, use jsonrpsee instead"
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        if self.response_compression_min_size.is_some() {
+            match (self.backend, &self.transport) {
+                (_, Some(ApiTransport::WebSocket(_))) => {
+                    vlog::warn!("Response compression is ignored for WebSocket transport");
+                }
+                (ApiBackend::Jsonrpc, _) => {
+                    vlog::warn!(
+                        "Response compression is not supported for jsonrpc backend, use jsonrpsee instead"
+                    );
+                }
+                _ => {}
+            }
+        }
 
         match (self.backend, self.transport.take()) {
             (ApiBackend::Jsonrpc, Some(ApiTransport::Http(addr))) => {
@@ -320,7 +450,7 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
 
     fn create_health_check(&self) -> (ApiHealthCheck, watch::Sender<CheckHealthStatus>) {
         let (status_sender, receiver) =
-            watch::channel(CheckHealthStatus::NotReady("Api is not ready".into()));
+            watch::channel(CheckHealthStatus::Degraded("Api is not ready".into()));
         (ApiHealthCheck::new(receiver), status_sender)
     }
 
@@ -333,7 +463,8 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
         let io_handler = {
             let zksync_network_id = self.config.l2_chain_id;
             let rpc_state = self.build_rpc_state();
-            let mut io = IoHandler::new();
+            let middleware = RequestTimeoutMiddleware::new(self.request_timeout);
+            let mut io = MetaIoHandler::<(), _>::with_middleware(middleware);
             io.extend_with(EthNamespace::new(rpc_state.clone()).to_delegate());
             io.extend_with(ZksNamespace::new(rpc_state.clone()).to_delegate());
             io.extend_with(EnNamespace::new(rpc_state.clone()).to_delegate());
@@ -409,12 +540,7 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
                 .unwrap();
 
             // Setup CORS.
-            let cors = CorsLayer::new()
-                // Allow `POST` when accessing the resource
-                .allow_methods([hyper::Method::POST])
-                // Allow requests from any origin
-                .allow_origin(tower_http::cors::Any)
-                .allow_headers([hyper::header::CONTENT_TYPE]);
+            let cors = cors_layer(self.cors_origins.as_deref());
 
             // Setup metrics for the number of in-flight txs.
             let (in_flight_requests_layer, counter) = InFlightRequestsLayer::pair();
@@ -425,7 +551,12 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             // Prepare middleware.
             let middleware = tower::ServiceBuilder::new()
                 .layer(in_flight_requests_layer)
-                .layer(cors);
+                .layer(cors)
+                .option_layer(
+                    self.response_compression_min_size
+                        .map(ResponseCompressionLayer::new),
+                )
+                .layer(RequestTimeoutLayer::new(self.request_timeout));
 
             runtime.block_on(async move {
                 let server = ServerBuilder::default()
@@ -483,8 +614,11 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
                 .unwrap();
 
             runtime.block_on(async move {
-                let server = ServerBuilder::default()
-                    .ws_only()
+                let mut server_builder = ServerBuilder::default().ws_only();
+                if let Some(ping_interval) = self.websocket_ping_interval {
+                    server_builder = server_builder.ping_interval(ping_interval);
+                }
+                let server = server_builder
                     .build(addr)
                     .await
                     .expect("Can't start the WS JSON RPC server");
@@ -523,7 +657,8 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             .build()
             .unwrap();
 
-        let pub_sub = EthSubscribe::new(jsonrpc_runtime.handle().clone());
+        let pub_sub = EthSubscribe::new(jsonrpc_runtime.handle().clone())
+            .with_limits(self.subscription_type_limits);
         let polling_interval = self.polling_interval.expect("Polling interval is not set");
 
         let mut notify_handles = vec![
@@ -551,8 +686,9 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
         let io = {
             let zksync_network_id = self.config.l2_chain_id;
             let rpc_state = self.build_rpc_state();
-            let mut io = PubSubHandler::default();
-            io.extend_with(pub_sub.to_delegate());
+            let middleware = RequestTimeoutMiddleware::new(self.request_timeout);
+            let mut io = PubSubHandler::new(MetaIoHandler::with_middleware(middleware));
+            io.extend_with(pub_sub.clone().to_delegate());
             io.extend_with(EthNamespace::new(rpc_state.clone()).to_delegate());
             io.extend_with(ZksNamespace::new(rpc_state.clone()).to_delegate());
             io.extend_with(EnNamespace::new(rpc_state).to_delegate());
@@ -561,30 +697,36 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             io
         };
 
+        let mut server_builder = WsServerBuilder::new(io, jsonrpc_runtime.handle().clone())
+            .on_session(move |session| {
+                let connection_id = connection_id(session);
+                let pub_sub = pub_sub.clone();
+                session.on_drop(move || pub_sub.close_connection(connection_id));
+            });
+        if let Some(max_connections) = self.subscriptions_limit {
+            server_builder = server_builder.max_connections(max_connections);
+        }
+        if let Some(limit) = self.max_buffered_notifications {
+            server_builder = server_builder.max_unacknowledged_notifications(limit);
+        }
+        if let Some(ping_interval) = self.websocket_ping_interval {
+            server_builder = server_builder.ping_interval(ping_interval);
+        }
+
         std::thread::spawn(move || {
-            let server = jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
-                io,
-                |context: &jsonrpc_ws_server::RequestContext| {
-                    Arc::new(jsonrpc_pubsub::Session::new(context.sender()))
-                },
-            )
-            .event_loop_executor(jsonrpc_runtime.handle().clone())
-            .max_connections(self.subscriptions_limit.unwrap_or(usize::MAX))
-            .session_stats(TrackOpenWsConnections)
-            .start(&addr)
-            .unwrap();
-            let close_handler = server.close_handle();
+            let server = server_builder.bind(addr).unwrap();
+            let broadcaster = server.broadcaster();
 
             std::thread::spawn(move || {
                 let stop_signal = futures::executor::block_on(stop_receiver.changed());
                 if stop_signal.is_ok() {
-                    close_handler.close();
+                    broadcaster.shutdown().ok();
                     vlog::info!("Stop signal received, WS JSON RPC API is shutting down");
                 }
             });
 
             api_health_check.send(CheckHealthStatus::Ready).unwrap();
-            server.wait().unwrap();
+            server.run().unwrap();
             jsonrpc_runtime.shutdown_timeout(Duration::from_secs(10));
             let _ = sender;
         });
@@ -594,18 +736,6 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
     }
 }
 
-struct TrackOpenWsConnections;
-
-impl jsonrpc_ws_server::SessionStats for TrackOpenWsConnections {
-    fn open_session(&self, _id: jsonrpc_ws_server::SessionId) {
-        metrics::increment_gauge!("api.ws.open_sessions", 1.0);
-    }
-
-    fn close_session(&self, _id: jsonrpc_ws_server::SessionId) {
-        metrics::decrement_gauge!("api.ws.open_sessions", 1.0);
-    }
-}
-
 async fn resolve_block(
     connection: &mut StorageProcessor<'_>,
     block: api::BlockId,
@@ -613,6 +743,6 @@ async fn resolve_block(
 ) -> Result<MiniblockNumber, Web3Error> {
     let result = connection.blocks_web3_dal().resolve_block_id(block).await;
     result
-        .map_err(|err| internal_error(method_name, err))?
+        .map_err(|err| blocks_dal_error(method_name, err))?
         .ok_or(Web3Error::NoBlock)
 }
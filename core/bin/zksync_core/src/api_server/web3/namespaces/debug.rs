@@ -16,7 +16,7 @@ use zksync_web3_decl::error::Web3Error;
 use crate::api_server::{
     execution_sandbox::{execute_tx_eth_call, BlockArgs, TxSharedArgs, VmConcurrencyLimiter},
     tx_sender::SubmitTxError,
-    web3::{backend_jsonrpc::error::internal_error, resolve_block},
+    web3::{backend_jsonrpc::error::blocks_dal_error, resolve_block},
 };
 
 #[derive(Debug, Clone)]
@@ -132,7 +132,7 @@ impl DebugNamespace {
         let mut connection = self.connection_pool.access_storage_tagged("api").await;
         let block_args = BlockArgs::new(&mut connection, block)
             .await
-            .map_err(|err| internal_error("debug_trace_call", err))?
+            .map_err(|err| blocks_dal_error("debug_trace_call", err))?
             .ok_or(Web3Error::NoBlock)?;
         drop(connection);
 
@@ -33,7 +33,11 @@ use {
 use crate::{
     api_server::{
         execution_sandbox::BlockArgs,
-        web3::{backend_jsonrpc::error::internal_error, resolve_block, state::RpcState},
+        web3::{
+            backend_jsonrpc::error::{blocks_dal_error, internal_error},
+            resolve_block,
+            state::RpcState,
+        },
     },
     l1_gas_price::L1GasPriceProvider,
 };
@@ -73,7 +77,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             .get_sealed_miniblock_number()
             .await
             .map(|n| U64::from(n.0))
-            .map_err(|err| internal_error(METHOD_NAME, err));
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err));
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
         block_number
@@ -95,7 +99,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             .await;
         let block_args = BlockArgs::new(&mut connection, block)
             .await
-            .map_err(|err| internal_error("eth_call", err))?
+            .map_err(|err| blocks_dal_error("eth_call", err))?
             .ok_or(Web3Error::NoBlock)?;
         drop(connection);
 
@@ -291,15 +295,38 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             "get_block"
         };
 
-        let block = self
+        let api_config = &self.state.api_config;
+        let min_base_fee_per_gas = api_config.min_base_fee_per_gas.map(U256::from);
+        let mut storage = self
             .state
             .connection_pool
             .access_storage_tagged("api")
-            .await
-            .blocks_web3_dal()
-            .get_block_by_web3_block_id(block, full_transactions, self.state.api_config.l2_chain_id)
-            .await
-            .map_err(|err| internal_error(method_name, err));
+            .await;
+        let mut blocks_dal = storage.blocks_web3_dal();
+        let pending_block_ordering = api_config
+            .assemble_pending_block
+            .filter(|_| block == BlockId::Number(BlockNumber::Pending));
+        let block = if let Some(ordering) = pending_block_ordering {
+            blocks_dal
+                .get_pending_block(
+                    full_transactions,
+                    api_config.l2_chain_id,
+                    api_config.req_entities_limit,
+                    ordering,
+                    min_base_fee_per_gas,
+                )
+                .await
+        } else {
+            blocks_dal
+                .get_block_by_web3_block_id(
+                    block,
+                    full_transactions,
+                    api_config.l2_chain_id,
+                    min_base_fee_per_gas,
+                )
+                .await
+        };
+        let block = block.map_err(|err| blocks_dal_error(method_name, err));
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => method_name);
         block
@@ -321,7 +348,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             .blocks_web3_dal()
             .get_block_tx_count(block)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err));
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err));
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
         tx_count
@@ -378,7 +405,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let block_number = resolve_block(&mut connection, block, METHOD_NAME).await?;
         let value = connection
             .storage_web3_dal()
-            .get_historical_value_unchecked(&storage_key, block_number)
+            .get_historical_storage_value(storage_key, block_number)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
 
@@ -533,7 +560,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             .blocks_web3_dal()
             .get_sealed_miniblock_number()
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?;
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err))?;
 
         let idx = self
             .state
@@ -607,7 +634,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
                     .update(idx, updated_filter);
                 Ok(changes)
             }
-            Err(Web3Error::LogsLimitExceeded(_, _, _)) => {
+            Err(Web3Error::LogsLimitExceeded(_, _, _) | Web3Error::LogsBlockRangeTooWide(_)) => {
                 // The filter was not being polled for a long time, so we remove it.
                 self.state.installed_filters.write().await.remove(idx);
                 Err(Web3Error::FilterNotFound)
@@ -688,15 +715,24 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
 
         let res = match typed_filter {
             TypedFilter::Blocks(from_block) => {
-                let (block_hashes, last_block_number) = self
+                let api_config = &self.state.api_config;
+                let limit = api_config
+                    .req_entities_limit
+                    .min(api_config.max_block_hash_batch);
+                let (block_hashes, last_block_number, is_truncated) = self
                     .state
                     .connection_pool
                     .access_storage_tagged("api")
                     .await
                     .blocks_web3_dal()
-                    .get_block_hashes_after(from_block, self.state.api_config.req_entities_limit)
+                    .get_block_hashes_after(from_block, limit)
                     .await
-                    .map_err(|err| internal_error(METHOD_NAME, err))?;
+                    .map_err(|err| blocks_dal_error(METHOD_NAME, err))?;
+                if is_truncated {
+                    // The filter is advanced to the last returned block, so the remaining hashes
+                    // will be returned on the following calls.
+                    metrics::counter!("api.web3.filter_block_hashes_truncated", 1);
+                }
                 (
                     FilterChanges::Hashes(block_hashes),
                     TypedFilter::Blocks(last_block_number.unwrap_or(from_block)),
@@ -750,6 +786,11 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
                     .state
                     .resolve_filter_block_number(filter.to_block)
                     .await?;
+                check_logs_block_range(
+                    from_block,
+                    to_block,
+                    self.state.api_config.max_getlogs_block_range,
+                )?;
 
                 let mut storage = self
                     .state
@@ -959,3 +1000,38 @@ impl<E> EthNamespace<E> {
     // - `compile_solidity`.
     // - `compile_serpent`.
 }
+
+/// Rejects logs queries spanning more than `max_range` blocks, so that they don't result
+/// in scanning a large part of the `events` table.
+fn check_logs_block_range(
+    from_block: MiniblockNumber,
+    to_block: MiniblockNumber,
+    max_range: u32,
+) -> Result<(), Web3Error> {
+    if to_block >= from_block && to_block.0 - from_block.0 >= max_range {
+        Err(Web3Error::LogsBlockRangeTooWide(max_range))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logs_block_range_is_checked() {
+        let from_block = MiniblockNumber(100);
+        // The range is inclusive, so it spans exactly 10 blocks.
+        check_logs_block_range(from_block, MiniblockNumber(109), 10).unwrap();
+        check_logs_block_range(from_block, from_block, 1).unwrap();
+        // Empty ranges are never rejected.
+        check_logs_block_range(from_block, MiniblockNumber(50), 10).unwrap();
+
+        let err = check_logs_block_range(from_block, MiniblockNumber(110), 10).unwrap_err();
+        assert!(
+            matches!(err, Web3Error::LogsBlockRangeTooWide(10)),
+            "{err:?}"
+        );
+    }
+}
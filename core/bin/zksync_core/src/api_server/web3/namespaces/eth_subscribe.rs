@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use jsonrpc_core::error::{Error, ErrorCode};
 use jsonrpc_pubsub::typed;
@@ -13,7 +13,13 @@ use super::eth::EVENT_TOPIC_NUMBER_LIMIT;
 
 pub type SubscriptionMap<T> = Arc<RwLock<HashMap<SubscriptionId, T>>>;
 
-#[derive(Debug, Clone, Copy)]
+/// Identifier of a WebSocket connection used to enforce per-connection subscription limits.
+pub type ConnectionId = usize;
+
+/// JSON-RPC error code returned when a subscription limit is reached.
+const SUBSCRIPTION_LIMIT_ERROR_CODE: i64 = -32005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum SubscriptionType {
     Blocks,
     Txs,
@@ -21,6 +27,15 @@ enum SubscriptionType {
 }
 
 impl SubscriptionType {
+    fn from_rpc_name(name: &str) -> Option<Self> {
+        match name {
+            "newHeads" => Some(Self::Blocks),
+            "newPendingTransactions" => Some(Self::Txs),
+            "logs" => Some(Self::Logs),
+            _ => None,
+        }
+    }
+
     fn as_str(&self) -> &'static str {
         match self {
             Self::Blocks => "blocks",
@@ -30,6 +45,71 @@ impl SubscriptionType {
     }
 }
 
+/// Limits on the number of active subscriptions of each type per WebSocket connection.
+/// `None` means that subscriptions of the corresponding type are not limited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SubscriptionLimits {
+    pub new_heads: Option<usize>,
+    pub pending_txs: Option<usize>,
+    pub logs: Option<usize>,
+}
+
+impl SubscriptionLimits {
+    fn get(&self, sub_type: SubscriptionType) -> Option<usize> {
+        match sub_type {
+            SubscriptionType::Blocks => self.new_heads,
+            SubscriptionType::Txs => self.pending_txs,
+            SubscriptionType::Logs => self.logs,
+        }
+    }
+}
+
+/// Active subscriptions grouped by WebSocket connection.
+#[derive(Debug, Default)]
+struct ConnectionSubscriptions {
+    counts: HashMap<(ConnectionId, SubscriptionType), usize>,
+    owners: HashMap<SubscriptionId, (ConnectionId, SubscriptionType)>,
+}
+
+impl ConnectionSubscriptions {
+    /// Reserves a slot for a subscription of the specified type. Returns `false` if the limit
+    /// for the connection is reached.
+    fn try_reserve(
+        &mut self,
+        connection_id: ConnectionId,
+        sub_type: SubscriptionType,
+        limit: Option<usize>,
+    ) -> bool {
+        let count = self.counts.entry((connection_id, sub_type)).or_default();
+        if limit.map_or(false, |limit| *count >= limit) {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+
+    fn release(&mut self, connection_id: ConnectionId, sub_type: SubscriptionType) {
+        let key = (connection_id, sub_type);
+        if let Some(count) = self.counts.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&key);
+            }
+        }
+    }
+
+    fn remove_subscription(&mut self, id: &SubscriptionId) {
+        if let Some((connection_id, sub_type)) = self.owners.remove(id) {
+            self.release(connection_id, sub_type);
+        }
+    }
+
+    fn remove_connection(&mut self, connection_id: ConnectionId) {
+        self.counts.retain(|&(id, _), _| id != connection_id);
+        self.owners.retain(|_, &mut (id, _)| id != connection_id);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EthSubscribe {
     // `jsonrpc` backend executes task subscription on a separate thread that has no tokio context.
@@ -37,6 +117,8 @@ pub struct EthSubscribe {
     pub active_block_subs: SubscriptionMap<typed::Sink<PubSubResult>>,
     pub active_tx_subs: SubscriptionMap<typed::Sink<PubSubResult>>,
     pub active_log_subs: SubscriptionMap<(typed::Sink<PubSubResult>, PubSubFilter)>,
+    limits: SubscriptionLimits,
+    connection_subs: Arc<Mutex<ConnectionSubscriptions>>,
 }
 
 impl EthSubscribe {
@@ -46,9 +128,23 @@ impl EthSubscribe {
             active_block_subs: SubscriptionMap::default(),
             active_tx_subs: SubscriptionMap::default(),
             active_log_subs: SubscriptionMap::default(),
+            limits: SubscriptionLimits::default(),
+            connection_subs: Arc::default(),
         }
     }
 
+    pub fn with_limits(mut self, limits: SubscriptionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Forgets about subscriptions of a closed connection, so that they don't count
+    /// towards subscription limits.
+    pub fn close_connection(&self, connection_id: ConnectionId) {
+        let mut connection_subs = self.connection_subs.lock().unwrap();
+        connection_subs.remove_connection(connection_id);
+    }
+
     fn assign_id(
         subscriber: typed::Subscriber<PubSubResult>,
     ) -> (typed::Sink<PubSubResult>, SubscriptionId) {
@@ -68,25 +164,55 @@ impl EthSubscribe {
             .unwrap();
     }
 
+    fn reject_on_limit(
+        subscriber: typed::Subscriber<PubSubResult>,
+        sub_type: &str,
+        limit: Option<usize>,
+    ) {
+        let limit = limit.unwrap_or_default();
+        subscriber
+            .reject(Error {
+                code: ErrorCode::ServerError(SUBSCRIPTION_LIMIT_ERROR_CODE),
+                message: format!(
+                    "Rejecting subscription - limit of {limit} `{sub_type}` subscriptions \
+                     per connection is reached."
+                ),
+                data: None,
+            })
+            .unwrap();
+    }
+
     #[tracing::instrument(skip(self, subscriber, params))]
     pub async fn sub(
         &self,
+        connection_id: ConnectionId,
         subscriber: typed::Subscriber<PubSubResult>,
         sub_type: String,
         params: Option<serde_json::Value>,
     ) {
-        let sub_type = match sub_type.as_str() {
+        let requested_type = SubscriptionType::from_rpc_name(&sub_type);
+        if let Some(requested_type) = requested_type {
+            let limit = self.limits.get(requested_type);
+            let mut connection_subs = self.connection_subs.lock().unwrap();
+            if !connection_subs.try_reserve(connection_id, requested_type, limit) {
+                drop(connection_subs);
+                Self::reject_on_limit(subscriber, &sub_type, limit);
+                return;
+            }
+        }
+
+        let new_sub = match sub_type.as_str() {
             "newHeads" => {
                 let mut block_subs = self.active_block_subs.write().await;
                 let (sink, id) = Self::assign_id(subscriber);
-                block_subs.insert(id, sink);
-                Some(SubscriptionType::Blocks)
+                block_subs.insert(id.clone(), sink);
+                Some((SubscriptionType::Blocks, id))
             }
             "newPendingTransactions" => {
                 let mut tx_subs = self.active_tx_subs.write().await;
                 let (sink, id) = Self::assign_id(subscriber);
-                tx_subs.insert(id, sink);
-                Some(SubscriptionType::Txs)
+                tx_subs.insert(id.clone(), sink);
+                Some((SubscriptionType::Txs, id))
             }
             "logs" => {
                 let filter = params.map(serde_json::from_value).transpose();
@@ -105,8 +231,8 @@ impl EthSubscribe {
                         } else {
                             let mut log_subs = self.active_log_subs.write().await;
                             let (sink, id) = Self::assign_id(subscriber);
-                            log_subs.insert(id, (sink, filter));
-                            Some(SubscriptionType::Logs)
+                            log_subs.insert(id.clone(), (sink, filter));
+                            Some((SubscriptionType::Logs, id))
                         }
                     }
                     Err(_) => {
@@ -126,8 +252,13 @@ impl EthSubscribe {
             }
         };
 
-        if let Some(sub_type) = sub_type {
+        let mut connection_subs = self.connection_subs.lock().unwrap();
+        if let Some((sub_type, id)) = new_sub {
+            connection_subs.owners.insert(id, (connection_id, sub_type));
             metrics::increment_gauge!("api.web3.pubsub.active_subscribers", 1f64, "subscription_type" => sub_type.as_str());
+        } else if let Some(requested_type) = requested_type {
+            // The subscription was rejected, so the reserved slot should be freed.
+            connection_subs.release(connection_id, requested_type);
         }
     }
 
@@ -143,6 +274,8 @@ impl EthSubscribe {
             None
         };
         if let Some(sub_type) = removed {
+            let mut connection_subs = self.connection_subs.lock().unwrap();
+            connection_subs.remove_subscription(&id);
             metrics::decrement_gauge!("api.web3.pubsub.active_subscribers", 1f64, "subscription_type" => sub_type.as_str());
             Ok(true)
         } else {
@@ -154,3 +287,62 @@ impl EthSubscribe {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn subscribe(
+        pub_sub: &EthSubscribe,
+        connection_id: ConnectionId,
+        sub_type: &str,
+    ) -> Result<SubscriptionId, Error> {
+        let (subscriber, id_receiver, _) = typed::Subscriber::new_test("eth_subscribe");
+        pub_sub
+            .sub(connection_id, subscriber, sub_type.to_owned(), None)
+            .await;
+        id_receiver.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn subscriptions_are_limited_per_type() {
+        let limits = SubscriptionLimits {
+            new_heads: None,
+            pending_txs: Some(1),
+            logs: Some(2),
+        };
+        let pub_sub = EthSubscribe::new(tokio::runtime::Handle::current()).with_limits(limits);
+
+        for _ in 0..2 {
+            subscribe(&pub_sub, 1, "logs").await.unwrap();
+        }
+        let err = subscribe(&pub_sub, 1, "logs").await.unwrap_err();
+        assert_eq!(
+            err.code,
+            ErrorCode::ServerError(SUBSCRIPTION_LIMIT_ERROR_CODE)
+        );
+        assert!(err.message.contains("`logs`"), "{}", err.message);
+
+        // Subscriptions of other types and from other connections are not affected.
+        for _ in 0..5 {
+            subscribe(&pub_sub, 1, "newHeads").await.unwrap();
+        }
+        let tx_sub_id = subscribe(&pub_sub, 1, "newPendingTransactions")
+            .await
+            .unwrap();
+        subscribe(&pub_sub, 1, "newPendingTransactions")
+            .await
+            .unwrap_err();
+        subscribe(&pub_sub, 2, "logs").await.unwrap();
+
+        // Unsubscribing frees a slot.
+        pub_sub.unsub(tx_sub_id).await.unwrap();
+        subscribe(&pub_sub, 1, "newPendingTransactions")
+            .await
+            .unwrap();
+
+        // Closing a connection frees all its slots.
+        pub_sub.close_connection(1);
+        subscribe(&pub_sub, 1, "logs").await.unwrap();
+    }
+}
@@ -18,7 +18,7 @@ pub use self::{
     debug::DebugNamespace,
     en::EnNamespace,
     eth::EthNamespace,
-    eth_subscribe::{EthSubscribe, SubscriptionMap},
+    eth_subscribe::{ConnectionId, EthSubscribe, SubscriptionLimits, SubscriptionMap},
     net::NetNamespace,
     web3::Web3Namespace,
     zks::ZksNamespace,
@@ -3,29 +3,37 @@ use std::{collections::HashMap, convert::TryInto};
 
 use bigdecimal::{BigDecimal, Zero};
 
+use zksync_dal::StorageProcessor;
 use zksync_mini_merkle_tree::MiniMerkleTree;
 
 #[cfg(feature = "openzeppelin_tests")]
 use zksync_types::Bytes;
 use zksync_types::{
-    api::{BridgeAddresses, GetLogsFilter, L2ToL1LogProof, TransactionDetails, U64},
+    api::{
+        BridgeAddresses, GetLogsFilter, L2ToL1LogProof, ProtocolVersionInfo, TransactionDetails,
+        U64,
+    },
     commitment::SerializeCommitment,
     explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
+    fee::{Fee, FeeBreakdown},
     l1::L1Tx,
+    l2::L2Tx,
     l2_to_l1_log::L2ToL1Log,
     tokens::ETHEREUM_ADDRESS,
     transaction_request::{l2_tx_from_call_req, CallRequest},
     L1BatchNumber, MiniblockNumber, Transaction, L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS,
     MAX_GAS_PER_PUBDATA_BYTE, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
 };
-use zksync_utils::address_to_h256;
+use zksync_utils::{address_to_h256, time::millis_since_epoch};
 use zksync_web3_decl::{
     error::Web3Error,
     types::{Address, Token, H256},
 };
 
-use crate::api_server::web3::{backend_jsonrpc::error::internal_error, RpcState};
+use crate::api_server::web3::{
+    backend_jsonrpc::error::{blocks_dal_error, internal_error},
+    RpcState,
+};
 use crate::fee_ticker::FeeTicker;
 use crate::fee_ticker::{error::TickerError, TokenPriceRequestType};
 use crate::l1_gas_price::L1GasPriceProvider;
@@ -51,6 +59,36 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
     #[tracing::instrument(skip(self, request))]
     pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<Fee, Web3Error> {
         let start = Instant::now();
+        let tx = self.l2_tx_for_fee_estimation(request).await?;
+        let fee = self.estimate_fee(tx.into()).await?;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => "estimate_fee");
+        Ok(fee)
+    }
+
+    #[tracing::instrument(skip(self, request))]
+    pub async fn estimate_fee_detailed_impl(
+        &self,
+        request: CallRequest,
+    ) -> Result<FeeBreakdown, Web3Error> {
+        let start = Instant::now();
+        let tx = self.l2_tx_for_fee_estimation(request).await?;
+        let scale_factor = self.state.api_config.estimate_gas_scale_factor;
+        let acceptable_overestimation =
+            self.state.api_config.estimate_gas_acceptable_overestimation;
+
+        let (_, breakdown) = self
+            .state
+            .tx_sender
+            .get_txs_fee_breakdown(tx.into(), scale_factor, acceptable_overestimation)
+            .await
+            .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => "estimate_fee_detailed");
+        Ok(breakdown)
+    }
+
+    async fn l2_tx_for_fee_estimation(&self, request: CallRequest) -> Result<L2Tx, Web3Error> {
         let mut request_with_gas_per_pubdata_overridden = request;
 
         self.state
@@ -70,11 +108,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         // not consider provided ones.
         tx.common_data.fee.max_priority_fee_per_gas = 0u64.into();
         tx.common_data.fee.gas_per_pubdata_limit = MAX_GAS_PER_PUBDATA_BYTE.into();
-
-        let fee = self.estimate_fee(tx.into()).await?;
-
-        metrics::histogram!("api.web3.call", start.elapsed(), "method" => "estimate_fee");
-        Ok(fee)
+        Ok(tx)
     }
 
     #[tracing::instrument(skip(self, request))]
@@ -249,11 +283,12 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             .connection_pool
             .access_storage_tagged("api")
             .await;
-        let l1_batch_number = match storage
-            .blocks_web3_dal()
-            .get_l1_batch_number_of_miniblock(block_number)
+        let l1_batch_number = match self
+            .state
+            .l1_batch_number_cache
+            .get_l1_batch_number_of_miniblock(&mut storage, block_number)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err))?
         {
             Some(number) => number,
             None => return Ok(None),
@@ -262,14 +297,14 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             .blocks_web3_dal()
             .get_miniblock_range_of_l1_batch(l1_batch_number)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err))?
             .expect("L1 batch should contain at least one miniblock");
 
         let all_l1_logs_in_block = storage
             .blocks_web3_dal()
             .get_l2_to_l1_logs(l1_batch_number)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?;
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err))?;
 
         // Position of l1 log in block relative to logs with identical data
         let l1_log_relative_position = if let Some(l2_log_position) = l2_log_position {
@@ -347,7 +382,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             .blocks_web3_dal()
             .get_l1_batch_info_for_tx(tx_hash)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err))?
         {
             Some(x) => x,
             None => return Ok(None),
@@ -357,7 +392,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             .blocks_web3_dal()
             .get_l2_to_l1_logs(l1_batch_number)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?;
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err))?;
 
         let l1_log_index = match all_l1_logs_in_block
             .iter()
@@ -398,7 +433,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             .get_sealed_l1_batch_number()
             .await
             .map(|n| U64::from(n.0))
-            .map_err(|err| internal_error(METHOD_NAME, err));
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err));
 
         metrics::histogram!("api.web3.call", start.elapsed(), "endpoint" => METHOD_NAME);
         l1_batch_number
@@ -421,7 +456,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             .get_miniblock_range_of_l1_batch(batch)
             .await
             .map(|minmax| minmax.map(|(min, max)| (U64::from(min.0), U64::from(max.0))))
-            .map_err(|err| internal_error(METHOD_NAME, err));
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err));
 
         metrics::histogram!("api.web3.call", start.elapsed(), "endpoint" => METHOD_NAME);
         minmax
@@ -509,6 +544,31 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         tx_details
     }
 
+    /// Returns the number of seconds that a pending transaction has been waiting in the mempool,
+    /// or `None` if the transaction is unknown or is already included into a miniblock.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_transaction_age_impl(&self, hash: H256) -> Result<Option<U64>, Web3Error> {
+        const METHOD_NAME: &str = "get_transaction_age";
+
+        let start = Instant::now();
+        let received_at = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .transactions_web3_dal()
+            .get_transaction_received_at(hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let age = received_at.map(|received_at| {
+            let now = millis_since_epoch() as u64;
+            U64::from(now.saturating_sub(received_at) / 1_000)
+        });
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(age)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_l1_batch_details_impl(
         &self,
@@ -566,6 +626,24 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         gas_price.into()
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_protocol_version_impl(&self) -> Result<ProtocolVersionInfo, Web3Error> {
+        const METHOD_NAME: &str = "get_protocol_version";
+
+        let start = Instant::now();
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await;
+        let version_info = load_protocol_version_info(&mut storage)
+            .await
+            .ok_or(Web3Error::NoBlock);
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        version_info
+    }
+
     #[cfg(feature = "openzeppelin_tests")]
     /// Saves contract bytecode to memory.
     pub fn set_known_bytecode_impl(&self, bytecode: Bytes) -> bool {
@@ -574,3 +652,105 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         true
     }
 }
+
+/// Returns protocol version information for the latest sealed L1 batch, or `None` if there are no batches yet.
+async fn load_protocol_version_info(
+    storage: &mut StorageProcessor<'_>,
+) -> Option<ProtocolVersionInfo> {
+    let (l1_batch_number, hashes) = storage
+        .blocks_dal()
+        .get_newest_block_base_system_contracts_hashes()
+        .await?;
+    let version_id = storage
+        .blocks_dal()
+        .get_base_system_contracts_version_count(l1_batch_number)
+        .await;
+    Some(ProtocolVersionInfo {
+        version_id,
+        l1_batch_number,
+        bootloader_code_hash: hashes.bootloader,
+        default_account_code_hash: hashes.default_aa,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use zksync_config::{configs::chain::StateKeeperConfig, ApiConfig, GasAdjusterConfig};
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_dal::ConnectionPool;
+    use zksync_types::block::{BlockGasCount, L1BatchHeader};
+
+    use super::*;
+    use crate::api_server::tx_sender::TxSenderConfig;
+
+    #[db_test]
+    async fn protocol_version_reflects_latest_sealed_batch(pool: ConnectionPool) {
+        let tx_sender_config = TxSenderConfig::new(
+            &StateKeeperConfig::from_env(),
+            &ApiConfig::from_env().web3_json_rpc,
+            &GasAdjusterConfig::from_env(),
+        );
+        let configured_hashes = BaseSystemContractsHashes {
+            bootloader: tx_sender_config.bootloader,
+            default_aa: tx_sender_config.default_aa,
+        };
+
+        let mut storage = pool.access_storage().await;
+        storage
+            .blocks_dal()
+            .delete_l1_batches(L1BatchNumber(0))
+            .await;
+        let header =
+            L1BatchHeader::new(L1BatchNumber(1), 100, Address::default(), configured_hashes);
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&header, BlockGasCount::default())
+            .await;
+
+        let version_info = load_protocol_version_info(&mut storage).await.unwrap();
+        assert_eq!(version_info.version_id, 1);
+        assert_eq!(version_info.l1_batch_number, L1BatchNumber(1));
+        assert_eq!(
+            version_info.bootloader_code_hash,
+            configured_hashes.bootloader
+        );
+        assert_eq!(
+            version_info.default_account_code_hash,
+            configured_hashes.default_aa
+        );
+
+        // A batch executed with the same contracts doesn't change the version.
+        let header =
+            L1BatchHeader::new(L1BatchNumber(2), 200, Address::default(), configured_hashes);
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&header, BlockGasCount::default())
+            .await;
+        let version_info = load_protocol_version_info(&mut storage).await.unwrap();
+        assert_eq!(version_info.version_id, 1);
+        assert_eq!(version_info.l1_batch_number, L1BatchNumber(2));
+
+        // Upgrading the bootloader starts a new version.
+        let upgraded_hashes = BaseSystemContractsHashes {
+            bootloader: H256::repeat_byte(0x42),
+            ..configured_hashes
+        };
+        let header = L1BatchHeader::new(L1BatchNumber(3), 300, Address::default(), upgraded_hashes);
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&header, BlockGasCount::default())
+            .await;
+        let version_info = load_protocol_version_info(&mut storage).await.unwrap();
+        assert_eq!(version_info.version_id, 2);
+        assert_eq!(version_info.l1_batch_number, L1BatchNumber(3));
+        assert_eq!(
+            version_info.bootloader_code_hash,
+            upgraded_hashes.bootloader
+        );
+        assert_eq!(
+            version_info.default_account_code_hash,
+            configured_hashes.default_aa
+        );
+    }
+}
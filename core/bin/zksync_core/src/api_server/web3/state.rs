@@ -5,10 +5,18 @@ use std::convert::TryInto;
 use std::sync::Arc;
 
 use tokio::sync::RwLock;
-use zksync_config::configs::{api::Web3JsonRpcConfig, chain::NetworkConfig, ContractsConfig};
+use zksync_config::configs::{
+    api::Web3JsonRpcConfig,
+    chain::{MempoolConfig, MempoolOrderingStrategy, NetworkConfig},
+    ContractsConfig,
+};
 
 use crate::api_server::tx_sender::TxSender;
-use crate::api_server::web3::{backend_jsonrpc::error::internal_error, resolve_block};
+use crate::api_server::web3::{
+    backend_jsonrpc::error::{blocks_dal_error, internal_error},
+    l1_batch_number_cache::L1BatchNumberCache,
+    resolve_block,
+};
 use crate::sync_layer::SyncState;
 
 use zksync_dal::ConnectionPool;
@@ -38,6 +46,13 @@ pub struct InternalApiConfig {
     pub diamond_proxy_addr: Address,
     pub l2_testnet_paymaster_addr: Option<Address>,
     pub req_entities_limit: usize,
+    pub max_block_hash_batch: usize,
+    pub max_getlogs_block_range: u32,
+    pub min_base_fee_per_gas: Option<u64>,
+    pub l1_batch_number_cache_size: u64,
+    /// Ordering of mempool transactions in the assembled pending block. If `None`, the pending block
+    /// is not assembled.
+    pub assemble_pending_block: Option<MempoolOrderingStrategy>,
 }
 
 impl InternalApiConfig {
@@ -45,6 +60,7 @@ impl InternalApiConfig {
         eth_config: &NetworkConfig,
         web3_config: &Web3JsonRpcConfig,
         contracts_config: &ContractsConfig,
+        mempool_config: &MempoolConfig,
     ) -> Self {
         Self {
             l1_chain_id: eth_config.network.chain_id(),
@@ -62,6 +78,13 @@ impl InternalApiConfig {
             diamond_proxy_addr: contracts_config.diamond_proxy_addr,
             l2_testnet_paymaster_addr: contracts_config.l2_testnet_paymaster_addr,
             req_entities_limit: web3_config.req_entities_limit(),
+            max_block_hash_batch: web3_config.max_block_hash_batch(),
+            max_getlogs_block_range: web3_config.max_getlogs_block_range(),
+            min_base_fee_per_gas: web3_config.min_base_fee_per_gas,
+            l1_batch_number_cache_size: web3_config.l1_batch_number_cache_size(),
+            assemble_pending_block: web3_config
+                .assemble_pending_block()
+                .then(|| mempool_config.ordering_strategy()),
         }
     }
 }
@@ -75,6 +98,7 @@ pub struct RpcState<E> {
     pub sync_state: Option<SyncState>,
     pub(super) api_config: InternalApiConfig,
     pub accounts: HashMap<Address, PrivateKeySigner>,
+    pub l1_batch_number_cache: L1BatchNumberCache,
     #[cfg(feature = "openzeppelin_tests")]
     pub known_bytecodes: Arc<RwLock<HashSet<Vec<u8>>>>,
 }
@@ -91,6 +115,7 @@ impl<E> Clone for RpcState<E> {
             sync_state: self.sync_state.clone(),
             api_config: self.api_config.clone(),
             accounts: self.accounts.clone(),
+            l1_batch_number_cache: self.l1_batch_number_cache.clone(),
             #[cfg(feature = "openzeppelin_tests")]
             known_bytecodes: self.known_bytecodes.clone(),
         }
@@ -131,7 +156,7 @@ impl<E> RpcState<E> {
             .blocks_web3_dal()
             .resolve_block_id(block_id)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err))?
             .unwrap())
         // ^ `unwrap()` is safe: `resolve_block_id(api::BlockId::Number(_))` can only return `None`
         // if called with an explicit number, and we've handled this case earlier.
@@ -157,7 +182,7 @@ impl<E> RpcState<E> {
                     .blocks_web3_dal()
                     .resolve_block_id(api::BlockId::Hash(block_hash))
                     .await
-                    .map_err(|err| internal_error("resolve_filter_block_hash", err))?
+                    .map_err(|err| blocks_dal_error("resolve_filter_block_hash", err))?
                     .ok_or(Web3Error::NoBlock)?;
 
                 filter.from_block = Some(api::BlockNumber::Number(block_number.0.into()));
@@ -184,7 +209,7 @@ impl<E> RpcState<E> {
             .blocks_web3_dal()
             .resolve_block_id(api::BlockId::Number(api::BlockNumber::Pending))
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .map_err(|err| blocks_dal_error(METHOD_NAME, err))?
             .expect("Pending block number shouldn't be None");
         let block_number = match filter.from_block {
             Some(api::BlockNumber::Number(number)) => {
@@ -56,6 +56,13 @@ enum Command {
         /// Flag that specifies if RocksDB with state keeper cache should be rolled back.
         #[arg(long)]
         rollback_sk_cache: bool,
+        /// Maximum number of L1 batches reverted in a single Postgres transaction. If specified,
+        /// an interrupted rollback can be resumed by running the same command again.
+        #[arg(long)]
+        chunk_size: Option<u32>,
+        /// Only prints the data that would be removed from Postgres, without reverting anything.
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Clears failed L1 transactions.
@@ -76,7 +83,7 @@ async fn main() -> anyhow::Result<()> {
     let config = BlockReverterEthConfig::new(eth_sender, contracts, eth_client.web3_url.clone());
 
     let connection_pool = ConnectionPool::new(None, DbVariant::Master).await;
-    let block_reverter = BlockReverter::new(
+    let mut block_reverter = BlockReverter::new(
         db_config,
         Some(config),
         connection_pool,
@@ -112,7 +119,15 @@ async fn main() -> anyhow::Result<()> {
             rollback_postgres,
             rollback_tree,
             rollback_sk_cache,
+            chunk_size,
+            dry_run,
         } => {
+            if dry_run {
+                let summary = block_reverter.dry_run(L1BatchNumber(l1_batch_number)).await;
+                println!("Data to be removed by rollback: {:#?}", summary);
+                return Ok(());
+            }
+
             if !rollback_tree && rollback_postgres {
                 println!("You want to rollback Postgres DB without rolling back tree.");
                 println!(
@@ -138,6 +153,9 @@ async fn main() -> anyhow::Result<()> {
             if rollback_sk_cache {
                 flags |= BlockReverterFlags::SK_CACHE;
             }
+            if let Some(chunk_size) = chunk_size {
+                block_reverter = block_reverter.with_postgres_chunk_size(chunk_size);
+            }
             block_reverter
                 .rollback_db(L1BatchNumber(l1_batch_number), flags)
                 .await
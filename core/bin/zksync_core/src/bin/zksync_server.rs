@@ -1,12 +1,13 @@
 use clap::Parser;
 
 use std::{env, str::FromStr, time::Duration};
-use zksync_config::configs::chain::NetworkConfig;
+use zksync_config::configs::{chain::NetworkConfig, PrometheusExporterMode};
 
 use zksync_config::ETHSenderConfig;
 use zksync_core::{
-    genesis_init, initialize_components, is_genesis_needed, setup_sigint_handler, Component,
-    Components,
+    genesis_init, initialize_components, is_genesis_needed, setup_sigint_handler,
+    shutdown::{shutdown_reason_channel, ShutdownReason},
+    Component, ComponentFailurePolicy, Components,
 };
 use zksync_storage::RocksDB;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
@@ -26,6 +27,10 @@ struct Cli {
         default_value = "api,tree,eth,data_fetcher,state_keeper,witness_generator,housekeeper"
     )]
     components: ComponentsToRun,
+    /// Start all components that can be started instead of exiting on the first component
+    /// that fails to initialize. Intended for development environments.
+    #[arg(long)]
+    best_effort_startup: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -83,34 +88,71 @@ async fn main() -> anyhow::Result<()> {
         [Component::WitnessGenerator(Some(_), _)]
     );
 
+    let failure_policy = if opt.best_effort_startup {
+        ComponentFailurePolicy::BestEffort
+    } else {
+        ComponentFailurePolicy::FailFast
+    };
+
     // Run core actors.
-    let (core_task_handles, stop_sender, cb_receiver, health_check_handle) =
-        initialize_components(components, is_only_oneshot_witness_generator_task)
-            .await
-            .expect("Unable to start Core actors");
+    let (shutdown_sender, mut shutdown_receiver) = shutdown_reason_channel();
+    // One-shot runs may finish before Prometheus scrapes their metrics, so metrics are pushed instead.
+    let prometheus_exporter_mode =
+        is_only_oneshot_witness_generator_task.then_some(PrometheusExporterMode::Push);
+    let (core_task_handles, stop_sender, health_check_handle, failures) = initialize_components(
+        components,
+        prometheus_exporter_mode,
+        failure_policy,
+        shutdown_sender.clone(),
+    )
+    .await
+    .expect("Unable to start Core actors");
+    for failure in &failures {
+        vlog::warn!(
+            "Component {:?} is not running: {:#}",
+            failure.component,
+            failure.error
+        );
+    }
 
     vlog::info!("Running {} core task handlers", core_task_handles.len());
-    let sigint_receiver = setup_sigint_handler();
+    setup_sigint_handler(shutdown_sender);
 
     let particular_crypto_alerts = None::<Vec<String>>;
     let graceful_shutdown = None::<futures::future::Ready<()>>;
     let tasks_allowed_to_finish = is_only_oneshot_witness_generator_task;
-    tokio::select! {
-        _ = wait_for_tasks(core_task_handles, particular_crypto_alerts, graceful_shutdown, tasks_allowed_to_finish) => {},
-        _ = sigint_receiver => {
-            vlog::info!("Stop signal received, shutting down");
-        },
-        error = cb_receiver => {
-            if let Ok(error_msg) = error {
-                vlog::warn!("Circuit breaker received, shutting down. Reason: {}", error_msg);
+    let shutdown_reason = tokio::select! {
+        _ = wait_for_tasks(core_task_handles, particular_crypto_alerts, graceful_shutdown, tasks_allowed_to_finish) => {
+            // A terminated task may have reported a more specific reason (e.g., a circuit breaker).
+            match shutdown_receiver.try_recv() {
+                Some(reason) => Some(reason),
+                None if tasks_allowed_to_finish => None,
+                None => Some(ShutdownReason::ComponentTerminated),
             }
         },
+        reason = shutdown_receiver.recv() => Some(reason),
     };
+    match &shutdown_reason {
+        Some(reason) if reason.exit_code() != 0 => {
+            vlog::warn!("Shutting down, reason: {reason}");
+        }
+        Some(reason) => vlog::info!("Shutting down, reason: {reason}"),
+        None => vlog::info!("All tasks finished, shutting down"),
+    }
     stop_sender.send(true).ok();
     RocksDB::await_rocksdb_termination();
     // Sleep for some time to let some components gracefully stop.
     tokio::time::sleep(Duration::from_secs(5)).await;
-    health_check_handle.stop().await;
+    if let Some(health_check_handle) = health_check_handle {
+        health_check_handle.stop().await;
+    }
     vlog::info!("Stopped");
+
+    let exit_code = shutdown_reason.map_or(0, |reason| reason.exit_code());
+    if exit_code != 0 {
+        // `process::exit()` doesn't run destructors, so Sentry needs to be flushed explicitly.
+        drop(sentry_guard);
+        std::process::exit(exit_code);
+    }
     Ok(())
 }
@@ -19,10 +19,13 @@ use zksync_types::web3::{
     types::{BlockId, BlockNumber},
     Web3,
 };
-use zksync_types::{L1BatchNumber, PackedEthSignature, H160, H256, U256};
+use zksync_types::{L1BatchNumber, MiniblockNumber, PackedEthSignature, H160, H256, U256};
 
 use zksync_eth_signer::{EthereumSigner, PrivateKeySigner, TransactionParameters};
 
+#[cfg(test)]
+mod tests;
+
 bitflags! {
     pub struct BlockReverterFlags: u32 {
         const POSTGRES = 0b_0001;
@@ -92,6 +95,7 @@ pub struct BlockReverter {
     eth_config: Option<BlockReverterEthConfig>,
     connection_pool: ConnectionPool,
     executed_batches_revert_mode: L1ExecutedBatchesRevert,
+    postgres_chunk_size: Option<u32>,
 }
 
 impl BlockReverter {
@@ -106,9 +110,18 @@ impl BlockReverter {
             db_config,
             connection_pool,
             executed_batches_revert_mode,
+            postgres_chunk_size: None,
         }
     }
 
+    /// Makes the reverter roll back Postgres data in chunks of at most `chunk_size` L1 batches.
+    /// See [`Self::rollback_postgres_in_chunks()`] for details.
+    pub fn with_postgres_chunk_size(mut self, chunk_size: u32) -> Self {
+        assert!(chunk_size > 0, "Chunk size must be positive");
+        self.postgres_chunk_size = Some(chunk_size);
+        self
+    }
+
     /// Rolls back DBs (Postgres + RocksDB) to a previous state.
     pub async fn rollback_db(
         &self,
@@ -139,7 +152,12 @@ impl BlockReverter {
         self.rollback_rocks_dbs(last_l1_batch_to_keep, rollback_tree, rollback_sk_cache)
             .await;
         if rollback_postgres {
-            self.rollback_postgres(last_l1_batch_to_keep).await;
+            if let Some(chunk_size) = self.postgres_chunk_size {
+                self.rollback_postgres_in_chunks(last_l1_batch_to_keep, chunk_size)
+                    .await;
+            } else {
+                self.rollback_postgres(last_l1_batch_to_keep).await;
+            }
         }
     }
 
@@ -218,6 +236,80 @@ impl BlockReverter {
         }
     }
 
+    /// Computes the data that would be removed from Postgres by reverting to the specified L1 batch,
+    /// without modifying the database.
+    pub async fn dry_run(&self, last_l1_batch_to_keep: L1BatchNumber) -> RollbackSummary {
+        let mut storage = self.connection_pool.access_storage().await;
+        let (_, last_miniblock_to_keep) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch_to_keep)
+            .await
+            .expect("L1 batch should contain at least one miniblock");
+
+        let l1_batches_to_remove = storage
+            .blocks_dal()
+            .get_l1_batch_count_after(last_l1_batch_to_keep)
+            .await;
+        let miniblocks_to_remove = storage
+            .blocks_dal()
+            .get_miniblock_count_after(last_miniblock_to_keep)
+            .await;
+        let storage_logs_to_remove = storage
+            .storage_logs_dal()
+            .get_storage_logs_count_after(last_miniblock_to_keep)
+            .await;
+
+        RollbackSummary {
+            last_l1_batch_to_keep,
+            last_miniblock_to_keep,
+            l1_batches_to_remove,
+            miniblocks_to_remove,
+            storage_logs_to_remove,
+        }
+    }
+
+    /// Reverts data in the Postgres database in chunks of at most `chunk_size` L1 batches, starting
+    /// from the newest batch. Each chunk is reverted in a separate DB transaction, so that after
+    /// each chunk Postgres is consistent and corresponds to an intermediate L1 batch. Thus, the progress
+    /// is recorded in Postgres itself, and an interrupted revert can be resumed by calling this method again.
+    pub async fn rollback_postgres_in_chunks(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        chunk_size: u32,
+    ) {
+        while self
+            .rollback_postgres_chunk(last_l1_batch_to_keep, chunk_size)
+            .await
+        {}
+    }
+
+    /// Reverts a single chunk of L1 batches. Returns `true` if there are more batches to revert.
+    async fn rollback_postgres_chunk(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        chunk_size: u32,
+    ) -> bool {
+        let last_l1_batch = self
+            .connection_pool
+            .access_storage()
+            .await
+            .blocks_dal()
+            .get_newest_block_header()
+            .await
+            .number;
+        let chunk_l1_batch_to_keep =
+            L1BatchNumber(last_l1_batch.0.saturating_sub(chunk_size)).max(last_l1_batch_to_keep);
+        vlog::info!(
+            "rolling back L1 batches after #{chunk_l1_batch_to_keep} (last L1 batch: #{last_l1_batch}, \
+             target: #{last_l1_batch_to_keep})"
+        );
+        self.rollback_postgres(chunk_l1_batch_to_keep).await;
+
+        let remaining_blocks = chunk_l1_batch_to_keep.0 - last_l1_batch_to_keep.0;
+        metrics::gauge!("block_reverter.remaining_blocks", remaining_blocks as f64);
+        remaining_blocks > 0
+    }
+
     /// Reverts data in the Postgres database.
     async fn rollback_postgres(&self, last_l1_batch_to_keep: L1BatchNumber) {
         vlog::info!("rolling back postgres data...");
@@ -419,6 +511,17 @@ impl BlockReverter {
     }
 }
 
+/// Summary of the data removed from Postgres by reverting to a certain L1 batch.
+/// Returned by [`BlockReverter::dry_run()`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RollbackSummary {
+    pub last_l1_batch_to_keep: L1BatchNumber,
+    pub last_miniblock_to_keep: MiniblockNumber,
+    pub l1_batches_to_remove: usize,
+    pub miniblocks_to_remove: usize,
+    pub storage_logs_to_remove: usize,
+}
+
 #[derive(Debug, Serialize)]
 pub struct SuggestedRollbackValues {
     pub last_executed_l1_batch_number: L1BatchNumber,
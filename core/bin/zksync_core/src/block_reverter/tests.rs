@@ -0,0 +1,157 @@
+use db_test_macro::db_test;
+
+use zksync_config::DBConfig;
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
+    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey, StorageLog, H256,
+};
+use zksync_utils::{miniblock_hash, u32_to_h256};
+
+use super::{BlockReverter, L1ExecutedBatchesRevert, RollbackSummary};
+
+/// Seeds `l1_batch_count` L1 batches, each consisting of 2 miniblocks with 3 storage logs per miniblock.
+async fn seed_blocks(storage: &mut StorageProcessor<'_>, l1_batch_count: u32) {
+    let account = AccountTreeId::new(Address::repeat_byte(1));
+    for batch_number in 0..l1_batch_count {
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(batch_number),
+            0,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        header.is_finished = true;
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&header, BlockGasCount::default())
+            .await;
+
+        for miniblock_number in [batch_number * 2, batch_number * 2 + 1] {
+            let miniblock_number = MiniblockNumber(miniblock_number);
+            let miniblock_header = MiniblockHeader {
+                number: miniblock_number,
+                timestamp: 0,
+                hash: miniblock_hash(miniblock_number),
+                l1_tx_count: 0,
+                l2_tx_count: 0,
+                base_fee_per_gas: 0,
+                l1_gas_price: 0,
+                l2_fair_gas_price: 0,
+                base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+            };
+            storage
+                .blocks_dal()
+                .insert_miniblock(&miniblock_header)
+                .await;
+
+            let logs: Vec<_> = (0..3)
+                .map(|i| {
+                    let key = StorageKey::new(account, u32_to_h256(miniblock_number.0 * 3 + i));
+                    StorageLog::new_write_log(key, H256::repeat_byte(0xff))
+                })
+                .collect();
+            storage
+                .storage_logs_dal()
+                .insert_storage_logs(miniblock_number, &[(H256::zero(), logs)])
+                .await;
+        }
+        storage
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(batch_number))
+            .await;
+    }
+}
+
+/// Returns the total number of L1 batches, miniblocks and storage logs after genesis.
+async fn count_data_after_genesis(pool: &ConnectionPool) -> (usize, usize, usize) {
+    let mut storage = pool.access_storage().await;
+    let l1_batch_count = storage
+        .blocks_dal()
+        .get_l1_batch_count_after(L1BatchNumber(0))
+        .await;
+    let miniblock_count = storage
+        .blocks_dal()
+        .get_miniblock_count_after(MiniblockNumber(0))
+        .await;
+    let storage_log_count = storage
+        .storage_logs_dal()
+        .get_storage_logs_count_after(MiniblockNumber(0))
+        .await;
+    (l1_batch_count, miniblock_count, storage_log_count)
+}
+
+#[db_test]
+async fn dry_run_reports_data_removed_by_rollback(pool: ConnectionPool) {
+    seed_blocks(&mut pool.access_storage().await, 5).await;
+
+    let block_reverter = BlockReverter::new(
+        DBConfig::default(),
+        None,
+        pool.clone(),
+        L1ExecutedBatchesRevert::Allowed,
+    );
+    let summary = block_reverter.dry_run(L1BatchNumber(2)).await;
+    assert_eq!(
+        summary,
+        RollbackSummary {
+            last_l1_batch_to_keep: L1BatchNumber(2),
+            last_miniblock_to_keep: MiniblockNumber(5),
+            l1_batches_to_remove: 2,
+            miniblocks_to_remove: 4,
+            storage_logs_to_remove: 12,
+        }
+    );
+
+    // The dry run must not modify the database.
+    let counts_before_rollback = count_data_after_genesis(&pool).await;
+    assert_eq!(counts_before_rollback, (4, 8, 24));
+
+    block_reverter.rollback_postgres(L1BatchNumber(2)).await;
+
+    let counts_after_rollback = count_data_after_genesis(&pool).await;
+    assert_eq!(
+        counts_before_rollback.0 - counts_after_rollback.0,
+        summary.l1_batches_to_remove
+    );
+    assert_eq!(
+        counts_before_rollback.1 - counts_after_rollback.1,
+        summary.miniblocks_to_remove
+    );
+    assert_eq!(
+        counts_before_rollback.2 - counts_after_rollback.2,
+        summary.storage_logs_to_remove
+    );
+}
+
+#[db_test]
+async fn interrupted_chunked_rollback_is_resumed(pool: ConnectionPool) {
+    seed_blocks(&mut pool.access_storage().await, 6).await;
+
+    let block_reverter = BlockReverter::new(
+        DBConfig::default(),
+        None,
+        pool.clone(),
+        L1ExecutedBatchesRevert::Allowed,
+    );
+    // Simulate an interruption after the first chunk.
+    let has_more_chunks = block_reverter
+        .rollback_postgres_chunk(L1BatchNumber(1), 2)
+        .await;
+    assert!(has_more_chunks);
+    // Postgres should be consistent with L1 batch #3.
+    assert_eq!(count_data_after_genesis(&pool).await, (3, 7, 21));
+
+    block_reverter
+        .rollback_postgres_in_chunks(L1BatchNumber(1), 2)
+        .await;
+    assert_eq!(count_data_after_genesis(&pool).await, (1, 3, 9));
+    let last_l1_batch = pool
+        .access_storage()
+        .await
+        .blocks_dal()
+        .get_newest_block_header()
+        .await
+        .number;
+    assert_eq!(last_l1_batch, L1BatchNumber(1));
+}
@@ -0,0 +1,123 @@
+//! Circuit breaker for requests to third-party APIs.
+//!
+//! After a configured number of consecutive failures the circuit opens, and requests to the API
+//! are not made until the cooldown passes. Then the circuit becomes half-open: a single request
+//! is allowed, and depending on its outcome the circuit either closes or opens again.
+//! While the circuit is closed, failed requests are retried with an exponential backoff.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug)]
+pub struct ApiCircuitBreaker {
+    fetcher: &'static str,
+    failure_threshold: u32,
+    cooldown: Duration,
+    base_backoff: Duration,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    state: CircuitState,
+}
+
+impl ApiCircuitBreaker {
+    pub fn new(
+        fetcher: &'static str,
+        failure_threshold: u32,
+        cooldown: Duration,
+        base_backoff: Duration,
+    ) -> Self {
+        assert!(failure_threshold > 0, "Failure threshold must be positive");
+        let this = Self {
+            fetcher,
+            failure_threshold,
+            cooldown,
+            base_backoff,
+            consecutive_failures: 0,
+            opened_at: None,
+            state: CircuitState::Closed,
+        };
+        this.report_state();
+        this
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.state
+    }
+
+    /// Checks whether a request to the API may be made at the specified moment.
+    pub fn allows_request(&mut self, now: Instant) -> bool {
+        match self.state {
+            CircuitState::Closed | CircuitState::HalfOpen => true,
+            CircuitState::Open => {
+                let opened_at = self.opened_at.expect("open circuit without opening time");
+                if now.saturating_duration_since(opened_at) < self.cooldown {
+                    return false;
+                }
+                vlog::info!(
+                    "[{}] Circuit is half-open, probing the remote API",
+                    self.fetcher
+                );
+                self.state = CircuitState::HalfOpen;
+                true
+            }
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        if self.state != CircuitState::Closed {
+            vlog::info!("[{}] Remote API recovered, closing circuit", self.fetcher);
+            self.state = CircuitState::Closed;
+            self.opened_at = None;
+            self.report_state();
+        }
+    }
+
+    pub fn record_failure(&mut self, now: Instant) {
+        self.consecutive_failures += 1;
+        let should_open = match self.state {
+            CircuitState::Closed => self.consecutive_failures >= self.failure_threshold,
+            CircuitState::HalfOpen => true,
+            CircuitState::Open => false,
+        };
+        if should_open {
+            vlog::warn!(
+                "[{}] {} requests to the remote API failed in a row, opening circuit for {:?}",
+                self.fetcher,
+                self.consecutive_failures,
+                self.cooldown
+            );
+            self.state = CircuitState::Open;
+            self.opened_at = Some(now);
+            self.report_state();
+        }
+    }
+
+    /// Returns the delay before retrying a failed request. The delay grows exponentially with
+    /// the number of consecutive failures and is capped by the cooldown. If the circuit isn't closed,
+    /// the delay is zero since retries are governed by the cooldown instead.
+    pub fn backoff(&self) -> Duration {
+        if self.state != CircuitState::Closed || self.consecutive_failures == 0 {
+            return Duration::ZERO;
+        }
+        let exponent = (self.consecutive_failures - 1).min(16);
+        self.base_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.cooldown)
+    }
+
+    fn report_state(&self) {
+        let is_open = if self.state == CircuitState::Open {
+            1.0
+        } else {
+            0.0
+        };
+        metrics::gauge!("data_fetcher.circuit.open", is_open, "fetcher" => self.fetcher);
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use zksync_health_check::{CheckHealth, CheckHealthStatus};
+
+/// Health check for the data fetchers. Tracks the time of the last successful update
+/// of every data feed and reports not ready if any feed wasn't updated for too long.
+#[derive(Debug, Clone)]
+pub struct DataFetcherHealthCheck {
+    max_feed_staleness: Duration,
+    /// Last update time per feed; `None` means that the feed was explicitly marked as stale.
+    last_updates: Arc<Mutex<HashMap<&'static str, Option<Instant>>>>,
+}
+
+impl DataFetcherHealthCheck {
+    pub fn new(max_feed_staleness: Duration) -> Self {
+        Self {
+            max_feed_staleness,
+            last_updates: Arc::default(),
+        }
+    }
+
+    /// Starts tracking the specified feed. The feed is considered updated at the moment
+    /// of registration, so that it has time to perform the first fetch.
+    pub fn register_feed(&self, feed: &'static str) {
+        self.report_success(feed);
+    }
+
+    /// Records a successful update of the specified feed.
+    pub fn report_success(&self, feed: &'static str) {
+        self.report_success_at(feed, Instant::now());
+    }
+
+    fn report_success_at(&self, feed: &'static str, updated_at: Instant) {
+        let mut last_updates = self.last_updates.lock().unwrap();
+        last_updates.insert(feed, Some(updated_at));
+    }
+
+    /// Marks the specified feed as stale regardless of the time of its last update, e.g.
+    /// if the fetcher knows that the upstream API is unavailable.
+    pub fn mark_stale(&self, feed: &'static str) {
+        let mut last_updates = self.last_updates.lock().unwrap();
+        last_updates.insert(feed, None);
+    }
+
+    #[cfg(test)]
+    pub(super) fn registered_feeds(&self) -> Vec<&'static str> {
+        let mut feeds: Vec<_> = self.last_updates.lock().unwrap().keys().copied().collect();
+        feeds.sort_unstable();
+        feeds
+    }
+
+    fn check_health_at(&self, now: Instant) -> CheckHealthStatus {
+        let last_updates = self.last_updates.lock().unwrap();
+        let mut stale_feeds: Vec<_> = last_updates
+            .iter()
+            .filter(|(_, &updated_at)| {
+                updated_at.map_or(true, |updated_at| {
+                    now.saturating_duration_since(updated_at) > self.max_feed_staleness
+                })
+            })
+            .map(|(&feed, _)| feed)
+            .collect();
+        if stale_feeds.is_empty() {
+            return CheckHealthStatus::Ready;
+        }
+
+        stale_feeds.sort_unstable();
+        CheckHealthStatus::NotReady(format!(
+            "Data feeds {:?} are stale or weren't updated for more than {:?}",
+            stale_feeds, self.max_feed_staleness
+        ))
+    }
+}
+
+#[async_trait]
+impl CheckHealth for DataFetcherHealthCheck {
+    async fn check_health(&self) -> CheckHealthStatus {
+        self.check_health_at(Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_feed_is_reported() {
+        let staleness = Duration::from_secs(60);
+        let start = Instant::now();
+        let health_check = DataFetcherHealthCheck::new(staleness);
+        health_check.report_success_at("token_list", start);
+        health_check.report_success_at("token_price", start);
+        assert_eq!(
+            health_check.check_health_at(start),
+            CheckHealthStatus::Ready
+        );
+
+        // Only the token list feed is updated, so the token price feed becomes stale.
+        health_check.report_success_at("token_list", start + Duration::from_secs(50));
+        let now = start + Duration::from_secs(90);
+        let CheckHealthStatus::NotReady(message) = health_check.check_health_at(now) else {
+            panic!("Stale feed should make the health check fail");
+        };
+        assert!(message.contains("token_price"), "{message}");
+        assert!(!message.contains("token_list"), "{message}");
+
+        health_check.report_success_at("token_price", now);
+        assert_eq!(health_check.check_health_at(now), CheckHealthStatus::Ready);
+
+        health_check.mark_stale("token_price");
+        let CheckHealthStatus::NotReady(message) = health_check.check_health_at(now) else {
+            panic!("Feed marked as stale should make the health check fail");
+        };
+        assert!(message.contains("token_price"), "{message}");
+    }
+}
@@ -11,27 +11,115 @@
 
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
-use zksync_config::FetcherConfig;
+use zksync_config::{configs::fetcher::DataFetcherKind, FetcherConfig};
 use zksync_dal::ConnectionPool;
 
+use self::health_check::DataFetcherHealthCheck;
+
+pub mod circuit_breaker;
 pub mod error;
+pub mod health_check;
 pub mod token_list;
 pub mod token_price;
 pub mod token_trading_volume;
 
+/// Spawns the data fetchers enabled in the `config`. Returns handles of the spawned tasks.
 pub fn run_data_fetchers(
     config: &FetcherConfig,
     network: zksync_types::network::Network,
     pool: ConnectionPool,
+    health_check: DataFetcherHealthCheck,
     stop_receiver: watch::Receiver<bool>,
 ) -> Vec<JoinHandle<()>> {
-    let list_fetcher = token_list::TokenListFetcher::new(config.clone(), network);
-    let price_fetcher = token_price::TokenPriceFetcher::new(config.clone());
-    let volume_fetcher = token_trading_volume::TradingVolumeFetcher::new(config.clone());
-
-    vec![
-        tokio::spawn(list_fetcher.run(pool.clone(), stop_receiver.clone())),
-        tokio::spawn(price_fetcher.run(pool.clone(), stop_receiver.clone())),
-        tokio::spawn(volume_fetcher.run(pool, stop_receiver)),
-    ]
+    let mut handles = vec![];
+    if config.is_fetcher_enabled(DataFetcherKind::TokenList) {
+        let list_fetcher = token_list::TokenListFetcher::new(config.clone(), network);
+        health_check.register_feed(token_list::TokenListFetcher::FEED_NAME);
+        handles.push(tokio::spawn(list_fetcher.run(
+            pool.clone(),
+            health_check.clone(),
+            stop_receiver.clone(),
+        )));
+    }
+    if config.is_fetcher_enabled(DataFetcherKind::TokenPrice) {
+        let price_fetcher = token_price::TokenPriceFetcher::new(config.clone());
+        health_check.register_feed(token_price::TokenPriceFetcher::FEED_NAME);
+        handles.push(tokio::spawn(price_fetcher.run(
+            pool.clone(),
+            health_check.clone(),
+            stop_receiver.clone(),
+        )));
+    }
+    if config.is_fetcher_enabled(DataFetcherKind::TokenTradingVolume) {
+        let volume_fetcher = token_trading_volume::TradingVolumeFetcher::new(config.clone());
+        health_check.register_feed(token_trading_volume::TradingVolumeFetcher::FEED_NAME);
+        handles.push(tokio::spawn(volume_fetcher.run(
+            pool,
+            health_check,
+            stop_receiver,
+        )));
+    }
+    handles
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, time::Duration};
+
+    use db_test_macro::db_test;
+    use zksync_config::configs::fetcher::{
+        SingleFetcherConfig, TokenListSource, TokenPriceSource, TokenTradingVolumeSource,
+    };
+    use zksync_types::network::Network;
+
+    use super::*;
+
+    fn mock_single_fetcher_config<T>(source: T) -> SingleFetcherConfig<T> {
+        SingleFetcherConfig {
+            source,
+            url: String::new(),
+            fetching_interval: 1,
+        }
+    }
+
+    /// Returns config with all fetchers enabled and using mock sources.
+    pub(super) fn mock_fetcher_config() -> FetcherConfig {
+        FetcherConfig {
+            token_list: mock_single_fetcher_config(TokenListSource::Mock),
+            token_price: mock_single_fetcher_config(TokenPriceSource::Mock),
+            token_trading_volume: mock_single_fetcher_config(TokenTradingVolumeSource::Mock),
+            max_feed_staleness: 60,
+            enabled_fetchers: DataFetcherKind::ALL.iter().copied().collect(),
+        }
+    }
+
+    #[db_test]
+    async fn running_only_price_fetcher(connection_pool: ConnectionPool) {
+        let config = FetcherConfig {
+            enabled_fetchers: HashSet::from([DataFetcherKind::TokenPrice]),
+            ..mock_fetcher_config()
+        };
+        let health_check = DataFetcherHealthCheck::new(config.max_feed_staleness());
+        // Signal the fetchers to stop right away; we're only interested in what's spawned.
+        let (_stop_sender, stop_receiver) = watch::channel(true);
+
+        let handles = run_data_fetchers(
+            &config,
+            Network::Localhost,
+            connection_pool,
+            health_check.clone(),
+            stop_receiver,
+        );
+        assert_eq!(handles.len(), 1);
+        assert_eq!(
+            health_check.registered_feeds(),
+            [token_price::TokenPriceFetcher::FEED_NAME]
+        );
+        for handle in handles {
+            tokio::time::timeout(Duration::from_secs(5), handle)
+                .await
+                .expect("fetcher didn't stop")
+                .unwrap();
+        }
+    }
 }
@@ -22,6 +22,7 @@ use zksync_types::network::Network;
 use zksync_types::{tokens::TokenMetadata, Address};
 
 use super::error::{ApiFetchError, ErrorAnalyzer};
+use super::health_check::DataFetcherHealthCheck;
 
 mod mock;
 mod one_inch;
@@ -40,6 +41,9 @@ pub struct TokenListFetcher {
 }
 
 impl TokenListFetcher {
+    /// Name of the data feed reported to [`DataFetcherHealthCheck`].
+    pub const FEED_NAME: &'static str = "token_list";
+
     fn create_fetcher(config: &FetcherConfig, network: Network) -> Box<dyn FetcherImpl> {
         let token_list_config = &config.token_list;
         match token_list_config.source {
@@ -62,7 +66,12 @@ impl TokenListFetcher {
         }
     }
 
-    pub async fn run(mut self, pool: ConnectionPool, stop_receiver: watch::Receiver<bool>) {
+    pub async fn run(
+        mut self,
+        pool: ConnectionPool,
+        health_check: DataFetcherHealthCheck,
+        stop_receiver: watch::Receiver<bool>,
+    ) {
         let mut fetching_interval =
             tokio::time::interval(self.config.token_list.fetching_interval());
 
@@ -92,6 +101,7 @@ impl TokenListFetcher {
             token_list.retain(|token, _data| unknown_tokens.contains(token));
 
             self.update_tokens(&mut storage, token_list).await;
+            health_check.report_success(Self::FEED_NAME);
         }
     }
 
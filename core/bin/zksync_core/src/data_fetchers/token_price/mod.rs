@@ -1,6 +1,9 @@
 //! Token price fetcher is responsible for maintaining actual prices for tokens that are used in zkSync.
 
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
 
@@ -8,7 +11,9 @@ use zksync_config::{configs::fetcher::TokenPriceSource, FetcherConfig};
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_types::{tokens::TokenPrice, Address};
 
+use super::circuit_breaker::ApiCircuitBreaker;
 use super::error::{ApiFetchError, ErrorAnalyzer};
+use super::health_check::DataFetcherHealthCheck;
 use bigdecimal::FromPrimitive;
 use num::{rational::Ratio, BigUint};
 use tokio::sync::watch;
@@ -32,9 +37,18 @@ pub struct TokenPriceFetcher {
     config: FetcherConfig,
     fetcher: Box<dyn FetcherImpl>,
     error_handler: ErrorAnalyzer,
+    circuit_breaker: ApiCircuitBreaker,
 }
 
 impl TokenPriceFetcher {
+    /// Name of the data feed reported to [`DataFetcherHealthCheck`].
+    pub const FEED_NAME: &'static str = "token_price";
+    /// Number of consecutive failed requests after which requests to the API are suspended.
+    const CIRCUIT_FAILURE_THRESHOLD: u32 = 5;
+    /// Time for which requests to the API are suspended once the circuit is open.
+    const CIRCUIT_COOLDOWN: Duration = Duration::from_secs(60);
+    const BASE_BACKOFF: Duration = Duration::from_secs(1);
+
     fn create_fetcher(config: &FetcherConfig) -> Box<dyn FetcherImpl> {
         let token_price_config = &config.token_price;
         match token_price_config.source {
@@ -53,6 +67,12 @@ impl TokenPriceFetcher {
     pub fn new(config: FetcherConfig) -> Self {
         let fetcher = Self::create_fetcher(&config);
         let error_handler = ErrorAnalyzer::new("TokenPriceFetcher");
+        let circuit_breaker = ApiCircuitBreaker::new(
+            "TokenPriceFetcher",
+            Self::CIRCUIT_FAILURE_THRESHOLD,
+            Self::CIRCUIT_COOLDOWN,
+            Self::BASE_BACKOFF,
+        );
         Self {
             minimum_required_liquidity: Ratio::from_integer(
                 BigUint::from_u64(0).unwrap(), // We don't use minimum required liquidity in the server anymore.
@@ -60,10 +80,16 @@ impl TokenPriceFetcher {
             config,
             fetcher,
             error_handler,
+            circuit_breaker,
         }
     }
 
-    pub async fn run(mut self, pool: ConnectionPool, stop_receiver: watch::Receiver<bool>) {
+    pub async fn run(
+        mut self,
+        pool: ConnectionPool,
+        health_check: DataFetcherHealthCheck,
+        stop_receiver: watch::Receiver<bool>,
+    ) {
         let mut fetching_interval =
             tokio::time::interval(self.config.token_price.fetching_interval());
 
@@ -76,6 +102,12 @@ impl TokenPriceFetcher {
             fetching_interval.tick().await;
             self.error_handler.update().await;
 
+            if !self.circuit_breaker.allows_request(Instant::now()) {
+                // Previously fetched prices remain in the database, but we signal that they aren't fresh.
+                health_check.mark_stale(Self::FEED_NAME);
+                continue;
+            }
+
             // We refresh token list in case new tokens were added.
             let mut storage = pool.access_storage().await;
             let tokens = self.get_tokens(&mut storage).await;
@@ -88,24 +120,32 @@ impl TokenPriceFetcher {
                 }
                 Err(err) => {
                     self.error_handler.process_error(err);
+                    tokio::time::sleep(self.circuit_breaker.backoff()).await;
                     continue;
                 }
             };
             self.store_token_prices(&mut storage, token_prices).await;
+            health_check.report_success(Self::FEED_NAME);
         }
     }
 
     async fn fetch_token_price(
-        &self,
+        &mut self,
         tokens: &[Address],
     ) -> Result<HashMap<Address, TokenPrice>, ApiFetchError> {
         const AWAITING_TIMEOUT: Duration = Duration::from_secs(2);
 
         let fetch_future = self.fetcher.fetch_token_price(tokens);
 
-        tokio::time::timeout(AWAITING_TIMEOUT, fetch_future)
+        let result = tokio::time::timeout(AWAITING_TIMEOUT, fetch_future)
             .await
-            .map_err(|_| ApiFetchError::RequestTimeout)?
+            .map_err(|_| ApiFetchError::RequestTimeout)
+            .and_then(|result| result);
+        match &result {
+            Ok(_) => self.circuit_breaker.record_success(),
+            Err(_) => self.circuit_breaker.record_failure(Instant::now()),
+        }
+        result
     }
 
     async fn store_token_prices(
@@ -128,3 +168,73 @@ impl TokenPriceFetcher {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::data_fetchers::{circuit_breaker::CircuitState, tests::mock_fetcher_config};
+
+    #[derive(Debug)]
+    struct FlakyPriceFetcher {
+        is_failing: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl FetcherImpl for FlakyPriceFetcher {
+        async fn fetch_token_price(
+            &self,
+            tokens: &[Address],
+        ) -> Result<HashMap<Address, TokenPrice>, ApiFetchError> {
+            if self.is_failing.load(Ordering::SeqCst) {
+                return Err(ApiFetchError::ApiUnavailable("mock".to_owned()));
+            }
+            mock::MockPriceFetcher::new()
+                .fetch_token_price(tokens)
+                .await
+        }
+    }
+
+    #[tokio::test]
+    async fn circuit_opens_and_recovers() {
+        let is_failing = Arc::new(AtomicBool::new(true));
+        let mut fetcher = TokenPriceFetcher::new(mock_fetcher_config());
+        fetcher.fetcher = Box::new(FlakyPriceFetcher {
+            is_failing: is_failing.clone(),
+        });
+        let tokens = [Address::repeat_byte(1)];
+
+        let mut prev_backoff = Duration::ZERO;
+        for _ in 1..TokenPriceFetcher::CIRCUIT_FAILURE_THRESHOLD {
+            assert!(fetcher.circuit_breaker.allows_request(Instant::now()));
+            fetcher.fetch_token_price(&tokens).await.unwrap_err();
+            assert_eq!(fetcher.circuit_breaker.state(), CircuitState::Closed);
+            let backoff = fetcher.circuit_breaker.backoff();
+            assert!(backoff > prev_backoff, "{backoff:?}");
+            prev_backoff = backoff;
+        }
+        fetcher.fetch_token_price(&tokens).await.unwrap_err();
+        assert_eq!(fetcher.circuit_breaker.state(), CircuitState::Open);
+        assert!(!fetcher.circuit_breaker.allows_request(Instant::now()));
+
+        // After the cooldown, a single failed probe opens the circuit again.
+        let after_cooldown = Instant::now() + TokenPriceFetcher::CIRCUIT_COOLDOWN;
+        assert!(fetcher.circuit_breaker.allows_request(after_cooldown));
+        assert_eq!(fetcher.circuit_breaker.state(), CircuitState::HalfOpen);
+        fetcher.fetch_token_price(&tokens).await.unwrap_err();
+        assert_eq!(fetcher.circuit_breaker.state(), CircuitState::Open);
+
+        // Once the API recovers, a successful probe closes the circuit.
+        is_failing.store(false, Ordering::SeqCst);
+        let after_cooldown = Instant::now() + TokenPriceFetcher::CIRCUIT_COOLDOWN;
+        assert!(fetcher.circuit_breaker.allows_request(after_cooldown));
+        let prices = fetcher.fetch_token_price(&tokens).await.unwrap();
+        assert_eq!(prices.len(), 1);
+        assert_eq!(fetcher.circuit_breaker.state(), CircuitState::Closed);
+        assert_eq!(fetcher.circuit_breaker.backoff(), Duration::ZERO);
+    }
+}
@@ -12,6 +12,7 @@ use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_types::{tokens::TokenMarketVolume, Address};
 
 use super::error::{ApiFetchError, ErrorAnalyzer};
+use super::health_check::DataFetcherHealthCheck;
 
 mod mock;
 mod uniswap;
@@ -33,6 +34,9 @@ pub struct TradingVolumeFetcher {
 }
 
 impl TradingVolumeFetcher {
+    /// Name of the data feed reported to [`DataFetcherHealthCheck`].
+    pub const FEED_NAME: &'static str = "token_trading_volume";
+
     fn create_fetcher(config: &FetcherConfig) -> Box<dyn FetcherImpl> {
         let token_trading_volume_config = &config.token_trading_volume;
         match token_trading_volume_config.source {
@@ -55,7 +59,12 @@ impl TradingVolumeFetcher {
         }
     }
 
-    pub async fn run(mut self, pool: ConnectionPool, stop_receiver: watch::Receiver<bool>) {
+    pub async fn run(
+        mut self,
+        pool: ConnectionPool,
+        health_check: DataFetcherHealthCheck,
+        stop_receiver: watch::Receiver<bool>,
+    ) {
         let mut fetching_interval =
             tokio::time::interval(self.config.token_trading_volume.fetching_interval());
         loop {
@@ -83,6 +92,7 @@ impl TradingVolumeFetcher {
 
             self.store_market_volumes(&mut storage, trading_volumes)
                 .await;
+            health_check.report_success(Self::FEED_NAME);
         }
     }
 
@@ -13,8 +13,9 @@ use zksync_types::{
 
 use crate::eth_sender::block_publish_criterion::{
     BlockNumberCriterion, BlockPublishCriterion, DataSizeCriterion, GasCriterion,
-    TimestampDeadlineCriterion,
+    MaxWaitDeadlineCriterion, TimestampDeadlineCriterion,
 };
+use crate::gas_tracker::{agg_block_base_cost, project_l1_batch_gas};
 
 #[derive(Debug)]
 pub struct Aggregator {
@@ -26,26 +27,35 @@ pub struct Aggregator {
 
 impl Aggregator {
     pub fn new(config: SenderConfig) -> Self {
+        let mut commit_criterion: Vec<Box<dyn BlockPublishCriterion>> = vec![
+            Box::from(BlockNumberCriterion {
+                op: AggregatedActionType::CommitBlocks,
+                limit: config.max_aggregated_blocks_to_commit,
+            }),
+            Box::from(GasCriterion::new(
+                AggregatedActionType::CommitBlocks,
+                config.max_aggregated_tx_gas,
+            )),
+            Box::from(DataSizeCriterion {
+                op: AggregatedActionType::CommitBlocks,
+                data_limit: config.commit_data_size_limit(),
+                pubdata_in_blobs: config.blob_operations().contains(&"CommitBlocks"),
+            }),
+            Box::from(TimestampDeadlineCriterion {
+                op: AggregatedActionType::CommitBlocks,
+                deadline_seconds: config.aggregated_block_commit_deadline,
+                max_allowed_lag: Some(config.timestamp_criteria_max_allowed_lag),
+            }),
+        ];
+        if let Some(max_batch_wait) = config.max_batch_wait {
+            commit_criterion.push(Box::from(MaxWaitDeadlineCriterion::new(
+                AggregatedActionType::CommitBlocks,
+                max_batch_wait,
+            )));
+        }
+
         Self {
-            commit_criterion: vec![
-                Box::from(BlockNumberCriterion {
-                    op: AggregatedActionType::CommitBlocks,
-                    limit: config.max_aggregated_blocks_to_commit,
-                }),
-                Box::from(GasCriterion::new(
-                    AggregatedActionType::CommitBlocks,
-                    config.max_aggregated_tx_gas,
-                )),
-                Box::from(DataSizeCriterion {
-                    op: AggregatedActionType::CommitBlocks,
-                    data_limit: config.max_eth_tx_data_size,
-                }),
-                Box::from(TimestampDeadlineCriterion {
-                    op: AggregatedActionType::CommitBlocks,
-                    deadline_seconds: config.aggregated_block_commit_deadline,
-                    max_allowed_lag: Some(config.timestamp_criteria_max_allowed_lag),
-                }),
-            ],
+            commit_criterion,
             proof_criterion: vec![
                 Box::from(BlockNumberCriterion {
                     op: AggregatedActionType::PublishProofBlocksOnchain,
@@ -143,6 +153,9 @@ impl Aggregator {
         )
         .await;
 
+        if let Some(blocks) = &blocks {
+            Self::report_projected_gas(AggregatedActionType::ExecuteBlocks, blocks);
+        }
         blocks.map(|blocks| BlocksExecuteOperation { blocks })
     }
 
@@ -183,12 +196,39 @@ impl Aggregator {
             last_sealed_block,
         )
         .await;
+        if let Some(blocks) = &blocks {
+            Self::report_projected_gas(AggregatedActionType::CommitBlocks, blocks);
+        }
         blocks.map(|blocks| BlocksCommitOperation {
             last_committed_block: last_block,
             blocks,
         })
     }
 
+    /// Reports the projected L1 gas cost of the aggregated operation, which allows to evaluate
+    /// how well the aggregation overhead is amortized over the published batches.
+    fn report_projected_gas(op: AggregatedActionType, blocks: &[BlockWithMetadata]) {
+        let batches_gas: u32 = blocks
+            .iter()
+            .map(|block| project_l1_batch_gas(&block.header).for_operation(op))
+            .sum();
+        let projected_gas = agg_block_base_cost(op) + batches_gas;
+        vlog::debug!(
+            "Projected L1 gas for {op:?} of {} L1 batches: {projected_gas}",
+            blocks.len()
+        );
+        metrics::histogram!(
+            "server.eth_sender.projected_gas",
+            projected_gas as f64,
+            "op" => op.to_string()
+        );
+        metrics::histogram!(
+            "server.eth_sender.projected_gas_per_batch",
+            projected_gas as f64 / blocks.len().max(1) as f64,
+            "op" => op.to_string()
+        );
+    }
+
     async fn load_real_proof_operation(
         storage: &mut StorageProcessor<'_>,
         prover_storage: &mut StorageProcessor<'_>,
@@ -0,0 +1,98 @@
+//! Encoding of L1 transaction data into EIP-4844 blobs.
+
+use c_kzg::{Blob, KzgCommitment, KzgProof, BYTES_PER_BLOB};
+
+use zksync_eth_signer::BlobTxSidecar;
+
+use crate::eth_sender::ETHSenderError;
+
+/// Size of a single field element in a blob.
+const BYTES_PER_FIELD_ELEMENT: usize = 32;
+/// Number of data bytes packed into a single field element. The first byte of each element is left
+/// zeroed so that the element is guaranteed to be less than the BLS modulus.
+const DATA_BYTES_PER_FIELD_ELEMENT: usize = 31;
+/// Number of data bytes that fit into a single blob.
+const DATA_BYTES_PER_BLOB: usize =
+    BYTES_PER_BLOB / BYTES_PER_FIELD_ELEMENT * DATA_BYTES_PER_FIELD_ELEMENT;
+/// Maximum number of blobs that can be attached to a single transaction.
+const MAX_BLOBS_PER_TX: usize = 6;
+/// Maximum number of data bytes that can be posted in blobs of a single transaction.
+pub(super) const MAX_BLOB_DATA_BYTES: usize = MAX_BLOBS_PER_TX * DATA_BYTES_PER_BLOB;
+
+/// Splits `data` into blobs and computes KZG commitments and proofs for them. Returns an error
+/// if `data` is longer than [`MAX_BLOB_DATA_BYTES`].
+pub(super) fn build_blob_sidecar(data: &[u8]) -> Result<BlobTxSidecar, ETHSenderError> {
+    if data.len() > MAX_BLOB_DATA_BYTES {
+        return Err(ETHSenderError::BlobDataTooLarge(data.len()));
+    }
+    let blobs = encode_blobs(data);
+
+    let settings = c_kzg::ethereum_kzg_settings();
+    let mut commitments = Vec::with_capacity(blobs.len());
+    let mut proofs = Vec::with_capacity(blobs.len());
+    for blob_bytes in &blobs {
+        let blob = Blob::from_bytes(blob_bytes).expect("Invalid blob size");
+        let commitment = KzgCommitment::blob_to_kzg_commitment(&blob, settings)
+            .expect("Failed to compute KZG commitment");
+        let proof = KzgProof::compute_blob_kzg_proof(&blob, &commitment.to_bytes(), settings)
+            .expect("Failed to compute KZG proof");
+        commitments.push(commitment.to_bytes().into_inner());
+        proofs.push(proof.to_bytes().into_inner());
+    }
+
+    Ok(BlobTxSidecar {
+        blobs,
+        commitments,
+        proofs,
+    })
+}
+
+fn encode_blobs(data: &[u8]) -> Vec<Vec<u8>> {
+    let blob_count = ((data.len() + DATA_BYTES_PER_BLOB - 1) / DATA_BYTES_PER_BLOB).max(1);
+    let mut blobs = vec![vec![0_u8; BYTES_PER_BLOB]; blob_count];
+    for (i, chunk) in data.chunks(DATA_BYTES_PER_FIELD_ELEMENT).enumerate() {
+        let blob = &mut blobs[i * DATA_BYTES_PER_FIELD_ELEMENT / DATA_BYTES_PER_BLOB];
+        let element_start =
+            (i % (BYTES_PER_BLOB / BYTES_PER_FIELD_ELEMENT)) * BYTES_PER_FIELD_ELEMENT;
+        blob[element_start + 1..element_start + 1 + chunk.len()].copy_from_slice(chunk);
+    }
+    blobs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_data_into_blobs() {
+        let data: Vec<u8> = (0..DATA_BYTES_PER_BLOB + 40).map(|i| i as u8).collect();
+        let blobs = encode_blobs(&data);
+        assert_eq!(blobs.len(), 2);
+
+        for (i, element) in blobs[0].chunks(BYTES_PER_FIELD_ELEMENT).enumerate() {
+            assert_eq!(element[0], 0);
+            let offset = i * DATA_BYTES_PER_FIELD_ELEMENT;
+            assert_eq!(
+                element[1..],
+                data[offset..offset + DATA_BYTES_PER_FIELD_ELEMENT]
+            );
+        }
+        let tail = &data[DATA_BYTES_PER_BLOB..];
+        assert_eq!(blobs[1][1..32], tail[..31]);
+        assert_eq!(blobs[1][32], 0);
+        assert_eq!(blobs[1][33..42], tail[31..]);
+        assert!(blobs[1][42..].iter().all(|&byte| byte == 0));
+
+        assert_eq!(encode_blobs(&[]).len(), 1);
+    }
+
+    #[test]
+    fn building_sidecar_for_oversized_data() {
+        let data = vec![1_u8; MAX_BLOB_DATA_BYTES + 1];
+        let err = build_blob_sidecar(&data).unwrap_err();
+        assert!(
+            matches!(err, ETHSenderError::BlobDataTooLarge(size) if size == data.len()),
+            "{err}"
+        );
+    }
+}
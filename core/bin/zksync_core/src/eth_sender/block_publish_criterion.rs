@@ -1,4 +1,4 @@
-use crate::gas_tracker::agg_block_base_cost;
+use crate::gas_tracker::{agg_block_base_cost, project_l1_batch_gas};
 use async_trait::async_trait;
 use chrono::Utc;
 use zksync_dal::StorageProcessor;
@@ -135,15 +135,21 @@ impl GasCriterion {
         GasCriterion { op, gas_limit }
     }
 
+    /// Returns the gas amount for `block`. Gas predicted by the state keeper is stored in the DB;
+    /// it is cross-checked with the projection based on the block header, since the projection
+    /// is a lower bound for the actual gas cost.
     async fn get_gas_amount(
         &mut self,
         storage: &mut StorageProcessor<'_>,
-        block_number: L1BatchNumber,
+        block: &BlockWithMetadata,
     ) -> u32 {
-        storage
+        let block_number = block.header.number;
+        let predicted_gas = storage
             .blocks_dal()
             .get_blocks_predicted_gas(block_number, block_number, self.op)
-            .await
+            .await;
+        let projected_gas = project_l1_batch_gas(&block.header).for_operation(self.op);
+        predicted_gas.max(projected_gas)
     }
 }
 
@@ -165,7 +171,7 @@ impl BlockPublishCriterion for GasCriterion {
 
         let mut last_block: Option<L1BatchNumber> = None;
         for (index, block) in consecutive_blocks.iter().enumerate() {
-            let block_gas = self.get_gas_amount(storage, block.header.number).await;
+            let block_gas = self.get_gas_amount(storage, block).await;
             if block_gas >= gas_left {
                 if index == 0 {
                     panic!(
@@ -206,6 +212,18 @@ impl BlockPublishCriterion for GasCriterion {
 pub struct DataSizeCriterion {
     pub op: AggregatedActionType,
     pub data_limit: usize,
+    /// Whether pubdata of the blocks is posted in blobs, so that it doesn't count towards the calldata size.
+    pub pubdata_in_blobs: bool,
+}
+
+impl DataSizeCriterion {
+    fn block_data_size(&self, block: &BlockWithMetadata) -> usize {
+        if self.pubdata_in_blobs {
+            block.l1_commit_data_without_pubdata_size()
+        } else {
+            block.l1_commit_data_size()
+        }
+    }
 }
 
 #[async_trait]
@@ -220,13 +238,12 @@ impl BlockPublishCriterion for DataSizeCriterion {
         let mut data_size_left = self.data_limit - STORED_BLOCK_INFO_SIZE;
 
         for (index, block) in consecutive_blocks.iter().enumerate() {
-            if data_size_left < block.l1_commit_data_size() {
+            let block_data_size = self.block_data_size(block);
+            if data_size_left < block_data_size {
                 if index == 0 {
                     panic!(
                         "block {} requires {} data, which is more than the range limit of {}",
-                        block.header.number,
-                        block.l1_commit_data_size(),
-                        self.data_limit
+                        block.header.number, block_data_size, self.data_limit
                     )
                 }
                 vlog::debug!(
@@ -242,9 +259,10 @@ impl BlockPublishCriterion for DataSizeCriterion {
                     "type" => "data_size",
                     "op" => self.op.to_string()
                 );
+                metrics::counter!("aggregator.size_limited_seals", 1, "op" => self.op.to_string());
                 return Some(block.header.number - 1);
             }
-            data_size_left -= block.l1_commit_data_size();
+            data_size_left -= block_data_size;
         }
 
         None
@@ -254,3 +272,61 @@ impl BlockPublishCriterion for DataSizeCriterion {
         "data_size"
     }
 }
+
+/// Publishes all the available blocks once the oldest of them waits for longer than `max_wait_seconds`.
+/// Unlike [`TimestampDeadlineCriterion`], this criterion is applied even if the sender lags behind,
+/// so it bounds the time to finality on low-traffic chains.
+#[derive(Debug)]
+pub struct MaxWaitDeadlineCriterion {
+    pub op: AggregatedActionType,
+    pub max_wait_seconds: u64,
+    /// Returns the current UNIX timestamp in seconds.
+    pub clock: fn() -> u64,
+}
+
+impl MaxWaitDeadlineCriterion {
+    pub fn new(op: AggregatedActionType, max_wait_seconds: u64) -> Self {
+        Self {
+            op,
+            max_wait_seconds,
+            clock: || Utc::now().timestamp() as u64,
+        }
+    }
+}
+
+#[async_trait]
+impl BlockPublishCriterion for MaxWaitDeadlineCriterion {
+    async fn last_block_to_publish(
+        &mut self,
+        _storage: &mut StorageProcessor<'_>,
+        consecutive_blocks: &[BlockWithMetadata],
+        _last_sealed_block: L1BatchNumber,
+    ) -> Option<L1BatchNumber> {
+        let first_block = consecutive_blocks.first()?;
+        let oldest_block_age_seconds = (self.clock)().saturating_sub(first_block.header.timestamp);
+        if oldest_block_age_seconds < self.max_wait_seconds {
+            return None;
+        }
+
+        let result = consecutive_blocks.last().unwrap().header.number;
+        vlog::debug!(
+            "{} block range {}-{}: MAX WAIT {} triggered",
+            self.op.to_string(),
+            first_block.header.number.0,
+            result.0,
+            self.max_wait_seconds
+        );
+        metrics::counter!(
+            "server.eth_sender.block_aggregation_reason",
+            1,
+            "type" => "max_wait",
+            "op" => self.op.to_string()
+        );
+        metrics::counter!("aggregator.deadline_seals", 1, "op" => self.op.to_string());
+        Some(result)
+    }
+
+    fn name(&self) -> &'static str {
+        "max_wait"
+    }
+}
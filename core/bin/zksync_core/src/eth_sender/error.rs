@@ -4,4 +4,8 @@ use zksync_eth_client::types::Error;
 pub enum ETHSenderError {
     #[error("Ethereum gateway Error {0}")]
     EthereumGateWayError(#[from] Error),
+    #[error("Blob base fee is unknown; cannot send blob transaction")]
+    UnknownBlobBaseFee,
+    #[error("Data of {0} bytes doesn't fit into blobs of a single transaction")]
+    BlobDataTooLarge(usize),
 }
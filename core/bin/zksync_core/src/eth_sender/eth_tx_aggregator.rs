@@ -1,14 +1,19 @@
+use crate::eth_sender::blobs::MAX_BLOB_DATA_BYTES;
 use crate::eth_sender::grafana_metrics::track_eth_tx_metrics;
 use crate::eth_sender::zksync_functions::ZkSyncFunctions;
 use crate::eth_sender::{zksync_functions, Aggregator, ETHSenderError};
 use crate::gas_tracker::agg_block_base_cost;
-use std::cmp::max;
+use std::{cmp::max, str::FromStr};
 use tokio::sync::watch;
 use zksync_config::configs::eth_sender::SenderConfig;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::BoundEthInterface;
-use zksync_types::{aggregated_operations::AggregatedOperation, eth_sender::EthTx, Address, H256};
+use zksync_types::{
+    aggregated_operations::{AggregatedActionType, AggregatedOperation},
+    eth_sender::EthTx,
+    Address, H256,
+};
 
 /// The component is responsible for aggregating l1 batches into eth_txs:
 /// Such as CommitBlocks, PublishProofBlocksOnchain and ExecuteBlock
@@ -20,6 +25,8 @@ pub struct EthTxAggregator {
     contract_address: Address,
     functions: ZkSyncFunctions,
     base_nonce: u64,
+    /// Operations sent as blob transactions, with their pubdata posted in blobs rather than in calldata.
+    blob_operations: Vec<AggregatedActionType>,
 }
 
 impl EthTxAggregator {
@@ -30,12 +37,29 @@ impl EthTxAggregator {
         base_nonce: u64,
     ) -> Self {
         let functions = zksync_functions::get_zksync_functions();
+        let blob_operations = config
+            .blob_operations()
+            .into_iter()
+            .map(|operation| {
+                let operation = AggregatedActionType::from_str(operation).unwrap_or_else(|err| {
+                    panic!("Invalid blob operation `{}`: {}", operation, err)
+                });
+                // Only commit operations carry pubdata that can be moved to blobs.
+                assert_eq!(
+                    operation,
+                    AggregatedActionType::CommitBlocks,
+                    "Only commit operations can be sent as blob transactions"
+                );
+                operation
+            })
+            .collect();
         Self {
             base_nonce,
             aggregator,
             config,
             contract_address,
             functions,
+            blob_operations,
         }
     }
 
@@ -158,8 +182,31 @@ impl EthTxAggregator {
         track_eth_tx_metrics(storage, "save", tx).await;
     }
 
-    fn encode_aggregated_op(&self, op: &AggregatedOperation) -> Vec<u8> {
-        match &op {
+    /// Returns the calldata for the operation and, if the operation is sent as a blob transaction,
+    /// the data posted in blobs. Pubdata is omitted from the calldata in the latter case.
+    /// If pubdata doesn't fit into blobs of a single transaction, it's posted in calldata instead.
+    fn encode_aggregated_op(&self, op: &AggregatedOperation) -> (Vec<u8>, Option<Vec<u8>>) {
+        let use_blobs = self.blob_operations.contains(&op.get_action_type());
+        let mut blob_data = None;
+        let calldata = match &op {
+            AggregatedOperation::CommitBlocks(commit_blocks) if use_blobs => {
+                let pubdata = commit_blocks.pubdata();
+                if pubdata.len() > MAX_BLOB_DATA_BYTES {
+                    vlog::warn!(
+                        "Pubdata of {} bytes for blocks {:?} doesn't fit into blobs; posting it in calldata",
+                        pubdata.len(),
+                        op.get_block_range()
+                    );
+                    self.functions
+                        .commit_blocks
+                        .encode_input(&commit_blocks.get_eth_tx_args())
+                } else {
+                    blob_data = Some(pubdata);
+                    self.functions
+                        .commit_blocks
+                        .encode_input(&commit_blocks.get_eth_tx_args_without_pubdata())
+                }
+            }
             AggregatedOperation::CommitBlocks(commit_blocks) => self
                 .functions
                 .commit_blocks
@@ -174,7 +221,8 @@ impl EthTxAggregator {
                 .encode_input(&execute_blocks.get_eth_tx_args()),
         }
         .expect("Failed to encode transaction data.")
-        .to_vec()
+        .to_vec();
+        (calldata, blob_data)
     }
 
     pub(super) async fn save_eth_tx(
@@ -184,7 +232,7 @@ impl EthTxAggregator {
     ) -> Result<EthTx, ETHSenderError> {
         let mut transaction = storage.start_transaction().await;
         let nonce = self.get_next_nonce(&mut transaction).await?;
-        let calldata = self.encode_aggregated_op(aggregated_op);
+        let (calldata, blob_data) = self.encode_aggregated_op(aggregated_op);
         let (first_block, last_block) = aggregated_op.get_block_range();
         let op_type = aggregated_op.get_action_type();
 
@@ -199,6 +247,7 @@ impl EthTxAggregator {
             .save_eth_tx(
                 nonce,
                 calldata,
+                blob_data,
                 op_type,
                 self.contract_address,
                 eth_tx_predicted_gas,
@@ -4,7 +4,7 @@ use tokio::sync::watch;
 use zksync_config::configs::eth_sender::SenderConfig;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{
-    types::{Error, ExecutedTxStatus, SignedCallResult},
+    types::{BlobTxParams, Error, ExecutedTxStatus, SignedCallResult},
     BoundEthInterface,
 };
 use zksync_types::{
@@ -15,7 +15,10 @@ use zksync_types::{
 use zksync_utils::time::seconds_since_epoch;
 
 use crate::eth_sender::ETHSenderError;
-use crate::{eth_sender::grafana_metrics::track_eth_tx_metrics, l1_gas_price::L1TxParamsProvider};
+use crate::{
+    eth_sender::{blobs::build_blob_sidecar, grafana_metrics::track_eth_tx_metrics},
+    l1_gas_price::{FeeParams, L1TxParamsProvider},
+};
 
 #[derive(Debug)]
 struct EthFee {
@@ -72,6 +75,21 @@ where
             .map_err(Into::into)
     }
 
+    /// Returns the number of L1 blocks mined on top of the block that includes the transaction
+    /// with the specified hash, or 0 if the transaction is not mined yet.
+    pub async fn confirmations_for(&self, tx_hash: H256) -> anyhow::Result<u64> {
+        let tx_status = self.get_tx_status(tx_hash).await?;
+        let tx_block_number = match tx_status.and_then(|status| status.receipt.block_number) {
+            Some(number) => number,
+            None => return Ok(0),
+        };
+
+        let current_block_number = self.ethereum_gateway.block_number("eth_tx_manager").await?;
+        Ok(current_block_number
+            .as_u64()
+            .saturating_sub(tx_block_number.as_u64()))
+    }
+
     async fn check_all_sending_attempts(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -108,7 +126,7 @@ where
         let base_fee_per_gas = self.gas_adjuster.get_base_fee(time_in_mempool);
 
         let priority_fee_per_gas = if time_in_mempool != 0 {
-            metrics::increment_counter!("server.eth_sender.transaction_resent");
+            metrics::increment_counter!("server.eth_sender.transaction_resent", "type" => tx.tx_type.to_string());
             let priority_fee_per_gas = self
                 .increase_priority_fee(storage, tx.id, base_fee_per_gas)
                 .await?;
@@ -168,9 +186,9 @@ where
             return Err(ETHSenderError::from(Error::from(Web3Error::Internal)));
         }
 
-        // Increase `priority_fee_per_gas` by at least 20% to prevent "replacement transaction underpriced" error.
-        Ok((previous_priority_fee + (previous_priority_fee / 5) + 1)
-            .max(self.gas_adjuster.get_priority_fee()))
+        // Increase `priority_fee_per_gas` by the configured bump to prevent "replacement transaction underpriced" error.
+        let fee_bump = previous_priority_fee * self.config.resend_fee_bump_percent() / 100;
+        Ok((previous_priority_fee + fee_bump + 1).max(self.gas_adjuster.get_priority_fee()))
     }
 
     pub(crate) async fn send_eth_tx(
@@ -195,9 +213,16 @@ where
             priority_fee_per_gas as f64
         );
 
-        let signed_tx = self
-            .sign_tx(tx, base_fee_per_gas, priority_fee_per_gas)
-            .await;
+        let fee_params = self
+            .gas_adjuster
+            .get_fee_params(base_fee_per_gas, priority_fee_per_gas);
+        let signed_tx = match self.sign_tx(tx, fee_params, time_in_mempool).await {
+            Ok(signed_tx) => signed_tx,
+            Err(error) => {
+                vlog::warn!("Failed signing tx {}: {}", tx.id, error);
+                return Err(error);
+            }
+        };
 
         if let Some(tx_history_id) = storage
             .eth_sender_dal()
@@ -384,23 +409,62 @@ where
     async fn sign_tx(
         &self,
         tx: &EthTx,
-        base_fee_per_gas: u64,
-        priority_fee_per_gas: u64,
-    ) -> SignedCallResult {
-        self.ethereum_gateway
-            .sign_prepared_tx_for_addr(
-                tx.raw_tx.clone(),
-                tx.contract_address,
-                Options::with(|opt| {
-                    opt.gas = Some(self.config.max_aggregated_tx_gas.into());
-                    opt.max_fee_per_gas = Some(U256::from(base_fee_per_gas + priority_fee_per_gas));
-                    opt.max_priority_fee_per_gas = Some(U256::from(priority_fee_per_gas));
-                    opt.nonce = Some(tx.nonce.0.into());
-                }),
-                "eth_tx_manager",
-            )
-            .await
-            .expect("Failed to sign transaction")
+        fee_params: FeeParams,
+        time_in_mempool: u32,
+    ) -> Result<SignedCallResult, ETHSenderError> {
+        let options = self.tx_options(tx, fee_params);
+        let signed_tx = if let Some(blob_data) = &tx.blob_data {
+            let max_fee_per_blob_gas = self
+                .gas_adjuster
+                .get_blob_base_fee(time_in_mempool)
+                .ok_or(ETHSenderError::UnknownBlobBaseFee)?;
+            metrics::histogram!(
+                "server.eth_sender.used_blob_base_fee",
+                max_fee_per_blob_gas.low_u128() as f64
+            );
+            let blob_params = BlobTxParams {
+                max_fee_per_blob_gas,
+                sidecar: build_blob_sidecar(blob_data)?,
+            };
+            self.ethereum_gateway
+                .sign_prepared_blob_tx_for_addr(
+                    tx.raw_tx.clone(),
+                    tx.contract_address,
+                    options,
+                    blob_params,
+                    "eth_tx_manager",
+                )
+                .await
+        } else {
+            self.ethereum_gateway
+                .sign_prepared_tx_for_addr(
+                    tx.raw_tx.clone(),
+                    tx.contract_address,
+                    options,
+                    "eth_tx_manager",
+                )
+                .await
+        };
+        Ok(signed_tx.expect("Failed to sign transaction"))
+    }
+
+    fn tx_options(&self, tx: &EthTx, fee_params: FeeParams) -> Options {
+        Options::with(|opt| {
+            opt.gas = Some(self.config.max_aggregated_tx_gas.into());
+            match fee_params {
+                FeeParams::Legacy(gas_price) => {
+                    opt.gas_price = Some(gas_price);
+                }
+                FeeParams::Eip1559 {
+                    max_fee,
+                    max_priority,
+                } => {
+                    opt.max_fee_per_gas = Some(max_fee);
+                    opt.max_priority_fee_per_gas = Some(max_priority);
+                }
+            }
+            opt.nonce = Some(tx.nonce.0.into());
+        })
     }
 
     async fn send_unsent_txs(
@@ -504,10 +568,11 @@ where
             .receipt
             .gas_used
             .expect("light ETH clients are not supported");
+        let confirmed_at_block = tx_status.receipt.block_number.map(|number| number.as_u32());
 
         storage
             .eth_sender_dal()
-            .confirm_tx(tx_status.tx_hash, gas_used)
+            .confirm_tx(tx_status.tx_hash, gas_used, confirmed_at_block)
             .await;
 
         track_eth_tx_metrics(storage, "mined", tx).await;
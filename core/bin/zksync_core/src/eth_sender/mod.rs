@@ -1,4 +1,5 @@
 mod aggregator;
+mod blobs;
 mod block_publish_criterion;
 
 mod error;
@@ -1,4 +1,7 @@
-use std::sync::{atomic::Ordering, Arc};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
 use db_test_macro::db_test;
 use zksync_config::{
@@ -10,7 +13,8 @@ use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{clients::mock::MockEthereum, EthInterface};
 use zksync_types::{
     aggregated_operations::{
-        AggregatedOperation, BlocksCommitOperation, BlocksExecuteOperation, BlocksProofOperation,
+        AggregatedActionType, AggregatedOperation, BlocksCommitOperation, BlocksExecuteOperation,
+        BlocksProofOperation,
     },
     block::L1BatchHeader,
     commitment::{BlockMetaParameters, BlockMetadata, BlockWithMetadata},
@@ -19,8 +23,14 @@ use zksync_types::{
 };
 
 use crate::eth_sender::{
-    eth_tx_manager::L1BlockNumbers, Aggregator, EthTxAggregator, EthTxManager,
+    blobs::MAX_BLOB_DATA_BYTES,
+    block_publish_criterion::{
+        BlockPublishCriterion, DataSizeCriterion, GasCriterion, MaxWaitDeadlineCriterion,
+    },
+    eth_tx_manager::L1BlockNumbers,
+    Aggregator, EthTxAggregator, EthTxManager,
 };
+use crate::gas_tracker::{agg_block_base_cost, project_l1_batch_gas};
 use crate::l1_gas_price::GasAdjuster;
 
 // Alias to conveniently call static methods of ETHSender.
@@ -266,6 +276,89 @@ async fn resend_each_block(connection_pool: ConnectionPool) -> anyhow::Result<()
     Ok(())
 }
 
+/// Sends a transaction and resends it with the specified fee bump. Returns priority fees of both transactions.
+async fn resend_with_fee_bump(
+    connection_pool: ConnectionPool,
+    resend_fee_bump_percent: u64,
+) -> anyhow::Result<(u64, u64)> {
+    let mut tester = EthSenderTester::new(connection_pool, vec![7, 6, 5, 5, 5, 2, 1], false).await;
+    tester.manager = EthTxManager::new(
+        SenderConfig {
+            resend_fee_bump_percent: Some(resend_fee_bump_percent),
+            ..ETHSenderConfig::from_env().sender
+        },
+        tester.gas_adjuster.clone(),
+        tester.gateway.clone(),
+    );
+    tester.gateway.advance_block_number(3);
+    tester.gas_adjuster.keep_updated().await?;
+
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    let tx = tester
+        .aggregator
+        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION)
+        .await?;
+    tester
+        .manager
+        .send_eth_tx(&mut tester.storage().await, &tx, 0, block)
+        .await?;
+    let original_fee = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_last_sent_eth_tx(tx.id)
+        .await
+        .unwrap()
+        .priority_fee_per_gas;
+
+    tester.gateway.advance_block_number(2);
+    tester.gas_adjuster.keep_updated().await?;
+    let block_numbers = tester.get_block_numbers().await;
+    let (to_resend, _) = tester
+        .manager
+        .monitor_inflight_transactions(&mut tester.storage().await, block_numbers)
+        .await?
+        .unwrap();
+    tester
+        .manager
+        .send_eth_tx(
+            &mut tester.storage().await,
+            &to_resend,
+            1,
+            block_numbers.latest,
+        )
+        .await?;
+    let resent_fee = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_last_sent_eth_tx(tx.id)
+        .await
+        .unwrap()
+        .priority_fee_per_gas;
+
+    Ok((original_fee, resent_fee))
+}
+
+// Tests that the priority fee of a resent transaction is raised by the configured bump.
+#[db_test]
+async fn resend_uses_configured_fee_bump(connection_pool: ConnectionPool) -> anyhow::Result<()> {
+    let (original_fee, resent_fee) = resend_with_fee_bump(connection_pool, 50).await?;
+    assert!(resent_fee >= original_fee + original_fee / 2);
+    Ok(())
+}
+
+// Tests that the fee bump for a resent transaction isn't lower than the minimum valid one.
+#[db_test]
+async fn resend_fee_bump_is_not_below_minimum(
+    connection_pool: ConnectionPool,
+) -> anyhow::Result<()> {
+    let (original_fee, resent_fee) = resend_with_fee_bump(connection_pool, 1).await?;
+    let min_bump = original_fee * SenderConfig::MIN_RESEND_FEE_BUMP_PERCENT / 100;
+    assert!(resent_fee >= original_fee + min_bump);
+    Ok(())
+}
+
 // Tests that if transaction was mined, but not enough blocks has been mined since,
 // we won't mark it as confirmed but also won't resend it.
 #[db_test]
@@ -715,3 +808,293 @@ async fn confirm_tx(tester: &mut EthSenderTester, hash: H256) {
         .await
         .unwrap();
 }
+
+// Tests that the number of confirmations is counted from the block including the transaction.
+#[db_test]
+async fn confirmations_for_tx(connection_pool: ConnectionPool) -> anyhow::Result<()> {
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+
+    let tx = tester
+        .aggregator
+        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION)
+        .await?;
+    let hash = tester
+        .manager
+        .send_eth_tx(
+            &mut tester.storage().await,
+            &tx,
+            0,
+            L1BlockNumber(tester.gateway.block_number("").await?.as_u32()),
+        )
+        .await?;
+
+    // The transaction isn't mined yet.
+    assert_eq!(tester.manager.confirmations_for(hash).await?, 0);
+
+    tester
+        .gateway
+        .execute_tx(hash, true, EthSenderTester::WAIT_CONFIRMATIONS)?;
+    assert_eq!(
+        tester.manager.confirmations_for(hash).await?,
+        EthSenderTester::WAIT_CONFIRMATIONS
+    );
+
+    tester.gateway.advance_block_number(3);
+    assert_eq!(
+        tester.manager.confirmations_for(hash).await?,
+        EthSenderTester::WAIT_CONFIRMATIONS + 3
+    );
+
+    // Unknown transactions are reported as unmined.
+    assert_eq!(tester.manager.confirmations_for(H256::random()).await?, 0);
+
+    Ok(())
+}
+
+// Tests that only commit operations are sent as blob transactions if blobs are enabled,
+// and that their pubdata is moved from calldata to blobs.
+#[db_test]
+async fn commit_operations_sent_as_blob_txs(connection_pool: ConnectionPool) {
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+    let sender_config = ETHSenderConfig::from_env().sender;
+    tester.aggregator = EthTxAggregator::new(
+        SenderConfig {
+            use_blobs: Some(true),
+            blob_operations: None,
+            proof_sending_mode: ProofSendingMode::SkipEveryProof,
+            ..sender_config.clone()
+        },
+        Aggregator::new(sender_config),
+        Address::random(),
+        0,
+    );
+
+    let genesis_block = insert_block(&mut tester, L1BatchNumber(0)).await;
+    let mut first_block = block_metadata(&insert_block(&mut tester, L1BatchNumber(1)).await);
+    first_block.metadata.initial_writes_compressed = vec![1; 64];
+    first_block.metadata.repeated_writes_compressed = vec![2; 32];
+    let commit_operation = BlocksCommitOperation {
+        last_committed_block: block_metadata(&genesis_block),
+        blocks: vec![first_block.clone()],
+    };
+
+    let commit_tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.storage().await,
+            &AggregatedOperation::CommitBlocks(commit_operation.clone()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(commit_tx.blob_data, Some(commit_operation.pubdata()));
+    let pubdata_chunks = [vec![1_u8; 64], vec![2; 32]];
+    for pubdata in &pubdata_chunks {
+        assert!(!commit_tx
+            .raw_tx
+            .windows(pubdata.len())
+            .any(|window| window == pubdata.as_slice()));
+    }
+
+    let commit_hash = tester
+        .manager
+        .send_eth_tx(
+            &mut tester.storage().await,
+            &commit_tx,
+            0,
+            L1BlockNumber(tester.gateway.block_number("").await.unwrap().as_u32()),
+        )
+        .await
+        .unwrap();
+    confirm_tx(&mut tester, commit_hash).await;
+    let execute_hash = execute_blocks(&mut tester, vec![first_block.header], false).await;
+
+    let signed_blob_txs = tester.gateway.signed_blob_txs.read().unwrap();
+    let blob_params = &signed_blob_txs[&commit_hash];
+    assert_eq!(blob_params.sidecar.blobs.len(), 1);
+    assert_eq!(blob_params.sidecar.commitments.len(), 1);
+    assert_eq!(blob_params.sidecar.proofs.len(), 1);
+    assert!(!signed_blob_txs.contains_key(&execute_hash));
+}
+
+// Tests that a commit operation whose pubdata doesn't fit into blobs is sent with pubdata in calldata.
+#[db_test]
+async fn oversized_commit_operation_sent_with_calldata(connection_pool: ConnectionPool) {
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+    let sender_config = ETHSenderConfig::from_env().sender;
+    tester.aggregator = EthTxAggregator::new(
+        SenderConfig {
+            use_blobs: Some(true),
+            blob_operations: None,
+            proof_sending_mode: ProofSendingMode::SkipEveryProof,
+            ..sender_config.clone()
+        },
+        Aggregator::new(sender_config),
+        Address::random(),
+        0,
+    );
+
+    let genesis_block = insert_block(&mut tester, L1BatchNumber(0)).await;
+    let mut first_block = block_metadata(&insert_block(&mut tester, L1BatchNumber(1)).await);
+    first_block.metadata.initial_writes_compressed = vec![1; MAX_BLOB_DATA_BYTES + 1];
+    let commit_operation = BlocksCommitOperation {
+        last_committed_block: block_metadata(&genesis_block),
+        blocks: vec![first_block],
+    };
+
+    let commit_tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.storage().await,
+            &AggregatedOperation::CommitBlocks(commit_operation),
+        )
+        .await
+        .unwrap();
+    assert_eq!(commit_tx.blob_data, None);
+    assert!(commit_tx.raw_tx.len() > MAX_BLOB_DATA_BYTES);
+
+    let commit_hash = tester
+        .manager
+        .send_eth_tx(
+            &mut tester.storage().await,
+            &commit_tx,
+            0,
+            L1BlockNumber(tester.gateway.block_number("").await.unwrap().as_u32()),
+        )
+        .await
+        .unwrap();
+    let signed_blob_txs = tester.gateway.signed_blob_txs.read().unwrap();
+    assert!(!signed_blob_txs.contains_key(&commit_hash));
+}
+
+#[db_test]
+async fn max_wait_deadline_seals_sparse_batch(connection_pool: ConnectionPool) {
+    static NOW: AtomicU64 = AtomicU64::new(0);
+
+    const BATCH_TIMESTAMP: u64 = 1_000;
+    const MAX_WAIT: u64 = 60;
+
+    let mut storage = connection_pool.access_test_storage().await;
+    let mut criterion = MaxWaitDeadlineCriterion {
+        op: AggregatedActionType::CommitBlocks,
+        max_wait_seconds: MAX_WAIT,
+        clock: || NOW.load(Ordering::SeqCst),
+    };
+    // A single sparse batch that wouldn't trigger any of the fullness criteria.
+    let header = L1BatchHeader::new(
+        L1BatchNumber(1),
+        BATCH_TIMESTAMP,
+        Address::default(),
+        BaseSystemContractsHashes::default(),
+    );
+    let blocks = [block_metadata(&header)];
+
+    NOW.store(BATCH_TIMESTAMP + MAX_WAIT - 1, Ordering::SeqCst);
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &blocks, L1BatchNumber(1))
+        .await;
+    assert_eq!(last_block, None);
+
+    NOW.store(BATCH_TIMESTAMP + MAX_WAIT, Ordering::SeqCst);
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &blocks, L1BatchNumber(1))
+        .await;
+    assert_eq!(last_block, Some(L1BatchNumber(1)));
+
+    // The deadline isn't suppressed if the sender lags behind.
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &blocks, L1BatchNumber(1_000))
+        .await;
+    assert_eq!(last_block, Some(L1BatchNumber(1)));
+}
+
+#[db_test]
+async fn commit_size_limit_forces_seal(connection_pool: ConnectionPool) {
+    const STORED_BLOCK_INFO_SIZE: usize = 96;
+
+    let mut storage = connection_pool.access_test_storage().await;
+    let blocks: Vec<_> = (1..=3)
+        .map(|number| {
+            let mut header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                number.into(),
+                Address::default(),
+                BaseSystemContractsHashes::default(),
+            );
+            header.l2_to_l1_messages = vec![vec![0; 1_000]];
+            block_metadata(&header)
+        })
+        .collect();
+
+    // The limit allows to commit exactly 2 blocks.
+    let data_limit = STORED_BLOCK_INFO_SIZE
+        + blocks[..2]
+            .iter()
+            .map(BlockWithMetadata::l1_commit_data_size)
+            .sum::<usize>();
+    let mut criterion = DataSizeCriterion {
+        op: AggregatedActionType::CommitBlocks,
+        data_limit,
+        pubdata_in_blobs: false,
+    };
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &blocks, L1BatchNumber(3))
+        .await;
+    assert_eq!(last_block, Some(L1BatchNumber(2)));
+
+    // Blocks fitting into the limit don't force a seal.
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &blocks[..2], L1BatchNumber(3))
+        .await;
+    assert_eq!(last_block, None);
+
+    // If pubdata is posted in blobs, it doesn't count towards the limit.
+    let mut criterion = DataSizeCriterion {
+        pubdata_in_blobs: true,
+        ..criterion
+    };
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &blocks, L1BatchNumber(3))
+        .await;
+    assert_eq!(last_block, None);
+}
+
+#[db_test]
+async fn projected_gas_forces_seal(connection_pool: ConnectionPool) {
+    let mut storage = connection_pool.access_test_storage().await;
+    // Blocks are not persisted, so the gas predicted by the state keeper is zero for them,
+    // and the range is sealed based on the projected gas only.
+    let blocks: Vec<_> = (1..=3)
+        .map(|number| {
+            let mut header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                number.into(),
+                Address::default(),
+                BaseSystemContractsHashes::default(),
+            );
+            header.l1_tx_count = 10;
+            header.l2_tx_count = 100;
+            block_metadata(&header)
+        })
+        .collect();
+
+    let ops = [
+        AggregatedActionType::CommitBlocks,
+        AggregatedActionType::PublishProofBlocksOnchain,
+        AggregatedActionType::ExecuteBlocks,
+    ];
+    for op in ops {
+        let block_gas = project_l1_batch_gas(&blocks[0].header).for_operation(op);
+        // The gas limit (lowered by 10% by the criterion) allows to publish 2.5 blocks.
+        let gas_limit = (agg_block_base_cost(op) + block_gas * 5 / 2) as f64 / 0.9;
+        let mut criterion = GasCriterion::new(op, gas_limit.round() as u32);
+        let last_block = criterion
+            .last_block_to_publish(&mut storage, &blocks, L1BatchNumber(3))
+            .await;
+        assert_eq!(last_block, Some(L1BatchNumber(2)), "{op:?}");
+
+        let last_block = criterion
+            .last_block_to_publish(&mut storage, &blocks[..2], L1BatchNumber(3))
+            .await;
+        assert_eq!(last_block, None, "{op:?}");
+    }
+}
@@ -1,8 +1,10 @@
 use itertools::Itertools;
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use tokio::time::Instant;
+use tokio::{sync::Semaphore, time::Instant};
 
 use zksync_eth_client::{types::Error as EthClientError, EthInterface};
 use zksync_types::ethabi::{Contract, Hash};
@@ -51,6 +53,9 @@ pub trait EthClient {
         to: BlockNumber,
         retries_left: usize,
     ) -> Result<Vec<L1Tx>, Error>;
+    /// Returns the number of the latest block on L1.
+    async fn block_number(&self) -> Result<u64, Error>;
+    /// Returns the number of the latest block marked as finalized on L1.
     async fn finalized_block_number(&self) -> Result<u64, Error>;
 }
 
@@ -63,22 +68,16 @@ pub struct EthHttpQueryClient<E> {
     client: E,
     topics: ContractTopics,
     zksync_contract_addr: H160,
-    confirmations_for_eth_event: Option<u64>,
 }
 
 impl<E: EthInterface> EthHttpQueryClient<E> {
-    pub fn new(
-        client: E,
-        zksync_contract_addr: H160,
-        confirmations_for_eth_event: Option<u64>,
-    ) -> Self {
+    pub fn new(client: E, zksync_contract_addr: H160) -> Self {
         vlog::debug!("New eth client, contract addr: {:x}", zksync_contract_addr);
         let topics = ContractTopics::new(&zksync_contract());
         Self {
             client,
             topics,
             zksync_contract_addr,
-            confirmations_for_eth_event,
         }
     }
 
@@ -200,21 +199,99 @@ impl<E: EthInterface + Send + Sync + 'static> EthClient for EthHttpQueryClient<E
         Ok(events)
     }
 
+    async fn block_number(&self) -> Result<u64, Error> {
+        Ok(self.client.block_number("watch").await?.as_u64())
+    }
+
     async fn finalized_block_number(&self) -> Result<u64, Error> {
-        if let Some(confirmations) = self.confirmations_for_eth_event {
-            let latest_block_number = self.client.block_number("watch").await?.as_u64();
-            Ok(latest_block_number.saturating_sub(confirmations))
-        } else {
-            self.client
-                .block("finalized".to_string(), "watch")
-                .await
-                .map_err(Into::into)
-                .map(|res| {
-                    res.expect("Finalized block must be present on L1")
-                        .number
-                        .expect("Finalized block must contain number")
-                        .as_u64()
-                })
+        self.client
+            .block("finalized".to_string(), "watch")
+            .await
+            .map_err(Into::into)
+            .map(|res| {
+                res.expect("Finalized block must be present on L1")
+                    .number
+                    .expect("Finalized block must contain number")
+                    .as_u64()
+            })
+    }
+}
+
+/// Guard for an in-flight request to the Ethereum node. Updates the `eth_watcher.in_flight_l1_requests`
+/// gauge on creation and on drop, so that the gauge stays correct if the request is cancelled or panics.
+#[derive(Debug)]
+struct InFlightRequestGuard<'a> {
+    in_flight_requests: &'a AtomicUsize,
+}
+
+impl<'a> InFlightRequestGuard<'a> {
+    fn new(in_flight_requests: &'a AtomicUsize) -> Self {
+        let count = in_flight_requests.fetch_add(1, Ordering::SeqCst) + 1;
+        metrics::gauge!("eth_watcher.in_flight_l1_requests", count as f64);
+        Self { in_flight_requests }
+    }
+}
+
+impl Drop for InFlightRequestGuard<'_> {
+    fn drop(&mut self) {
+        let count = self.in_flight_requests.fetch_sub(1, Ordering::SeqCst) - 1;
+        metrics::gauge!("eth_watcher.in_flight_l1_requests", count as f64);
+    }
+}
+
+/// [`EthClient`] wrapper limiting the number of concurrent requests to the Ethereum node.
+/// The number of in-flight requests is exported as the `eth_watcher.in_flight_l1_requests` gauge.
+#[derive(Debug)]
+pub struct LimitedEthClient<W> {
+    inner: W,
+    semaphore: Semaphore,
+    in_flight_requests: AtomicUsize,
+}
+
+impl<W: EthClient> LimitedEthClient<W> {
+    /// Creates a client allowing at most `max_concurrent_requests` concurrent requests
+    /// to the `inner` client. If the limit is not specified, requests are issued one at a time.
+    pub fn new(inner: W, max_concurrent_requests: Option<usize>) -> Self {
+        let max_concurrent_requests = max_concurrent_requests.unwrap_or(1);
+        assert!(
+            max_concurrent_requests > 0,
+            "max number of concurrent L1 requests must be positive"
+        );
+        Self {
+            inner,
+            semaphore: Semaphore::new(max_concurrent_requests),
+            in_flight_requests: AtomicUsize::new(0),
         }
     }
+
+    async fn limit<T>(&self, request: impl Future<Output = T>) -> T {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .expect("semaphore is never closed");
+        let _guard = InFlightRequestGuard::new(&self.in_flight_requests);
+        request.await
+    }
+}
+
+#[async_trait::async_trait]
+impl<W: EthClient + Send + Sync> EthClient for LimitedEthClient<W> {
+    async fn get_priority_op_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        retries_left: usize,
+    ) -> Result<Vec<L1Tx>, Error> {
+        self.limit(self.inner.get_priority_op_events(from, to, retries_left))
+            .await
+    }
+
+    async fn block_number(&self) -> Result<u64, Error> {
+        self.limit(self.inner.block_number()).await
+    }
+
+    async fn finalized_block_number(&self) -> Result<u64, Error> {
+        self.limit(self.inner.finalized_block_number()).await
+    }
 }
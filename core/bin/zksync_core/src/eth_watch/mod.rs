@@ -3,11 +3,17 @@
 //!
 //! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
 //! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
+//! Events in blocks with fewer confirmations are not requested, so an L1 reorg within the confirmations
+//! window cannot lead to processing reverted events.
 
 // Built-in deps
-use std::time::{Duration, Instant};
+use std::{
+    cmp,
+    time::{Duration, Instant},
+};
 
 // External uses
+use futures::future;
 use tokio::{sync::watch, task::JoinHandle};
 
 // Workspace deps
@@ -21,7 +27,7 @@ use self::client::{Error, EthClient};
 
 use zksync_config::ETHWatchConfig;
 
-use crate::eth_watch::client::{EthHttpQueryClient, RETRY_LIMIT};
+use crate::eth_watch::client::{EthHttpQueryClient, LimitedEthClient, RETRY_LIMIT};
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::EthInterface;
 
@@ -30,6 +36,11 @@ mod client;
 #[cfg(test)]
 mod tests;
 
+/// Max number of L1 blocks covered by a single request for priority op events. Larger block ranges
+/// (e.g., when catching up after a restart) are split into chunks; the number of chunks requested
+/// concurrently is bounded by [`LimitedEthClient`].
+const MAX_BLOCKS_PER_REQUEST: u64 = 1_000;
+
 #[derive(Debug)]
 struct EthWatchState {
     next_expected_priority_id: PriorityOpId,
@@ -40,25 +51,55 @@ struct EthWatchState {
 pub struct EthWatch<W: EthClient> {
     client: W,
     poll_interval: Duration,
+    /// Number of L1 blocks a priority operation must be buried under before it's processed.
+    /// If not set, operations are processed once their block is finalized.
+    confirmations_for_eth_event: Option<u64>,
 
     state: EthWatchState,
 }
 
 impl<W: EthClient> EthWatch<W> {
-    pub async fn new(client: W, pool: &ConnectionPool, poll_interval: Duration) -> Self {
+    pub async fn new(
+        client: W,
+        pool: &ConnectionPool,
+        poll_interval: Duration,
+        confirmations_for_eth_event: Option<u64>,
+    ) -> Self {
         let mut storage = pool.access_storage_tagged("eth_watch").await;
 
-        let state = Self::initialize_state(&client, &mut storage).await;
+        let state =
+            Self::initialize_state(&client, confirmations_for_eth_event, &mut storage).await;
 
         vlog::info!("initialized state: {:?}", state);
         Self {
             client,
             poll_interval,
+            confirmations_for_eth_event,
             state,
         }
     }
 
-    async fn initialize_state(client: &W, storage: &mut StorageProcessor<'_>) -> EthWatchState {
+    /// Returns the latest L1 block that is safe to process, i.e. the one that either has
+    /// the configured number of confirmations or is finalized. Events in later blocks
+    /// may still be reverted by an L1 reorg, so they are not requested at all; they will
+    /// be fetched again once they are buried deep enough.
+    async fn last_safe_block_number(
+        client: &W,
+        confirmations_for_eth_event: Option<u64>,
+    ) -> Result<u64, Error> {
+        if let Some(confirmations) = confirmations_for_eth_event {
+            let latest_block_number = client.block_number().await?;
+            Ok(latest_block_number.saturating_sub(confirmations))
+        } else {
+            client.finalized_block_number().await
+        }
+    }
+
+    async fn initialize_state(
+        client: &W,
+        confirmations_for_eth_event: Option<u64>,
+        storage: &mut StorageProcessor<'_>,
+    ) -> EthWatchState {
         let next_expected_priority_id: PriorityOpId = storage
             .transactions_dal()
             .last_priority_id()
@@ -74,8 +115,7 @@ impl<W: EthClient> EthWatch<W> {
             // but subtract 1 in case the server stopped mid-block.
             Some(block) => block.0.saturating_sub(1).into(),
             // There are no priority ops processed - to be safe, scan the last 50k blocks.
-            None => client
-                .finalized_block_number()
+            None => Self::last_safe_block_number(client, confirmations_for_eth_event)
                 .await
                 .expect("cannot initialize eth watch: cannot get current ETH block")
                 .saturating_sub(PRIORITY_EXPIRATION),
@@ -104,7 +144,12 @@ impl<W: EthClient> EthWatch<W> {
                 // This is an error because otherwise we could potentially miss a priority operation
                 // thus entering priority mode, which is not desired.
                 vlog::error!("Failed to process new blocks {}", error);
-                self.state = Self::initialize_state(&self.client, &mut storage).await;
+                self.state = Self::initialize_state(
+                    &self.client,
+                    self.confirmations_for_eth_event,
+                    &mut storage,
+                )
+                .await;
             }
         }
     }
@@ -112,7 +157,8 @@ impl<W: EthClient> EthWatch<W> {
     #[tracing::instrument(skip(self, storage))]
     async fn loop_iteration(&mut self, storage: &mut StorageProcessor<'_>) -> Result<(), Error> {
         let mut stage_start = Instant::now();
-        let to_block = self.client.finalized_block_number().await?;
+        let to_block =
+            Self::last_safe_block_number(&self.client, self.confirmations_for_eth_event).await?;
 
         if to_block <= self.state.last_processed_ethereum_block {
             return Ok(());
@@ -161,16 +207,22 @@ impl<W: EthClient> EthWatch<W> {
         from_block: u64,
         to_block: u64,
     ) -> Result<Vec<(L1BlockNumber, L1Tx)>, Error> {
-        let priority_ops: Vec<L1Tx> = self
-            .client
-            .get_priority_op_events(
-                Web3BlockNumber::Number(from_block.into()),
-                Web3BlockNumber::Number(to_block.into()),
-                RETRY_LIMIT,
-            )
+        let chunk_requests = (from_block..=to_block)
+            .step_by(MAX_BLOCKS_PER_REQUEST as usize)
+            .map(|chunk_start| {
+                let chunk_end = cmp::min(chunk_start + MAX_BLOCKS_PER_REQUEST - 1, to_block);
+                self.client.get_priority_op_events(
+                    Web3BlockNumber::Number(chunk_start.into()),
+                    Web3BlockNumber::Number(chunk_end.into()),
+                    RETRY_LIMIT,
+                )
+            });
+        // Chunks are returned in the order of requests, so priority ops remain sorted.
+        let priority_ops: Vec<L1Tx> = future::try_join_all(chunk_requests)
             .await?
             .into_iter()
-            .collect::<Vec<_>>();
+            .flatten()
+            .collect();
 
         if !priority_ops.is_empty() {
             let first = &priority_ops[0];
@@ -204,13 +256,16 @@ pub async fn start_eth_watch<E: EthInterface + Send + Sync + 'static>(
     stop_receiver: watch::Receiver<bool>,
 ) -> JoinHandle<()> {
     let eth_watch = ETHWatchConfig::from_env();
-    let eth_client = EthHttpQueryClient::new(
-        eth_gateway,
-        diamond_proxy_addr,
-        eth_watch.confirmations_for_eth_event,
-    );
+    let eth_client = EthHttpQueryClient::new(eth_gateway, diamond_proxy_addr);
+    let eth_client = LimitedEthClient::new(eth_client, eth_watch.max_concurrent_l1_requests);
 
-    let mut eth_watch = EthWatch::new(eth_client, &pool, eth_watch.poll_interval()).await;
+    let mut eth_watch = EthWatch::new(
+        eth_client,
+        &pool,
+        eth_watch.poll_interval(),
+        eth_watch.confirmations_for_eth_event,
+    )
+    .await;
 
     tokio::spawn(async move {
         eth_watch.run(pool, stop_receiver).await;
@@ -1,10 +1,13 @@
 use std::collections::HashMap;
 use std::convert::TryInto;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::sync::RwLock;
 
 use db_test_macro::db_test;
+use zksync_config::configs::chain::MempoolOrderingStrategy;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_types::web3::types::{Address, BlockNumber};
 use zksync_types::{
@@ -12,11 +15,12 @@ use zksync_types::{
     Execute, L1TxCommonData, PriorityOpId, Transaction, H256, U256,
 };
 
-use super::client::Error;
-use crate::eth_watch::{client::EthClient, EthWatch};
+use super::client::{Error, LimitedEthClient};
+use crate::eth_watch::{client::EthClient, EthWatch, MAX_BLOCKS_PER_REQUEST};
 
 struct FakeEthClientData {
     transactions: HashMap<u64, Vec<L1Tx>>,
+    last_block_number: u64,
     last_finalized_block_number: u64,
 }
 
@@ -24,6 +28,7 @@ impl FakeEthClientData {
     fn new() -> Self {
         Self {
             transactions: Default::default(),
+            last_block_number: 0,
             last_finalized_block_number: 0,
         }
     }
@@ -37,27 +42,51 @@ impl FakeEthClientData {
                 .push(transaction.clone());
         }
     }
+    fn set_last_block_number(&mut self, number: u64) {
+        self.last_block_number = number;
+    }
+
     fn set_last_finalized_block_number(&mut self, number: u64) {
         self.last_finalized_block_number = number;
     }
+
+    /// Emulates an L1 reorg reverting all blocks starting from `first_reverted_block`.
+    fn revert_blocks(&mut self, first_reverted_block: u64) {
+        self.transactions
+            .retain(|&eth_block, _| eth_block < first_reverted_block);
+        self.last_block_number = first_reverted_block.saturating_sub(1);
+    }
 }
 
 #[derive(Clone)]
 struct FakeEthClient {
     inner: Arc<RwLock<FakeEthClientData>>,
+    in_flight_requests: Arc<AtomicUsize>,
+    max_in_flight_requests: Arc<AtomicUsize>,
 }
 
 impl FakeEthClient {
     fn new() -> Self {
         Self {
             inner: Arc::new(RwLock::new(FakeEthClientData::new())),
+            in_flight_requests: Arc::default(),
+            max_in_flight_requests: Arc::default(),
         }
     }
 
+    /// Returns the max number of concurrent `get_priority_op_events()` calls observed so far.
+    fn max_in_flight_requests(&self) -> usize {
+        self.max_in_flight_requests.load(Ordering::SeqCst)
+    }
+
     async fn add_transactions(&mut self, transactions: &[L1Tx]) {
         self.inner.write().await.add_transactions(transactions);
     }
 
+    async fn set_last_block_number(&mut self, number: u64) {
+        self.inner.write().await.set_last_block_number(number);
+    }
+
     async fn set_last_finalized_block_number(&mut self, number: u64) {
         self.inner
             .write()
@@ -65,6 +94,10 @@ impl FakeEthClient {
             .set_last_finalized_block_number(number);
     }
 
+    async fn revert_blocks(&mut self, first_reverted_block: u64) {
+        self.inner.write().await.revert_blocks(first_reverted_block);
+    }
+
     async fn block_to_number(&self, block: BlockNumber) -> u64 {
         match block {
             BlockNumber::Latest => unreachable!(),
@@ -83,6 +116,13 @@ impl EthClient for FakeEthClient {
         to: BlockNumber,
         _retries_left: usize,
     ) -> Result<Vec<L1Tx>, Error> {
+        let in_flight_requests = self.in_flight_requests.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight_requests
+            .fetch_max(in_flight_requests, Ordering::SeqCst);
+        // Emulate network latency so that concurrent requests overlap.
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        self.in_flight_requests.fetch_sub(1, Ordering::SeqCst);
+
         let from = self.block_to_number(from).await;
         let to = self.block_to_number(to).await;
         let mut transactions = vec![];
@@ -94,6 +134,10 @@ impl EthClient for FakeEthClient {
         Ok(transactions)
     }
 
+    async fn block_number(&self) -> Result<u64, Error> {
+        Ok(self.inner.read().await.last_block_number)
+    }
+
     async fn finalized_block_number(&self) -> Result<u64, Error> {
         Ok(self.inner.read().await.last_finalized_block_number)
     }
@@ -135,6 +179,7 @@ async fn test_normal_operation(connection_pool: ConnectionPool) {
         client.clone(),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        None,
     )
     .await;
 
@@ -169,6 +214,7 @@ async fn test_gap_in_single_batch(connection_pool: ConnectionPool) {
         client.clone(),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        None,
     )
     .await;
 
@@ -194,6 +240,7 @@ async fn test_gap_between_batches(connection_pool: ConnectionPool) {
         client.clone(),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        None,
     )
     .await;
 
@@ -224,6 +271,7 @@ async fn test_overlapping_batches(connection_pool: ConnectionPool) {
         client.clone(),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        None,
     )
     .await;
 
@@ -255,11 +303,101 @@ async fn test_overlapping_batches(connection_pool: ConnectionPool) {
     assert_eq!(tx.common_data.serial_id.0, 4);
 }
 
+#[db_test]
+async fn test_reorg_within_confirmations_window(connection_pool: ConnectionPool) {
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+        Some(5),
+    )
+    .await;
+
+    let mut storage = connection_pool.access_test_storage().await;
+    client
+        .add_transactions(&[build_tx(0, 10), build_tx(1, 14)])
+        .await;
+    client.set_last_block_number(18).await;
+    // Only the first tx has enough confirmations.
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let db_txs = get_all_db_txs(&mut storage).await;
+    assert_eq!(db_txs.len(), 1);
+
+    // The block with the second tx is reverted, and the tx is included into another block.
+    client.revert_blocks(12).await;
+    client.add_transactions(&[build_tx(1, 15)]).await;
+    client.set_last_block_number(19).await;
+    // The new block doesn't have enough confirmations yet.
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let db_txs = get_all_db_txs(&mut storage).await;
+    assert_eq!(db_txs.len(), 1);
+
+    client.set_last_block_number(25).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let db_txs = get_all_db_txs(&mut storage).await;
+    assert_eq!(db_txs.len(), 2);
+    let tx: L1Tx = db_txs[0].clone().try_into().unwrap();
+    assert_eq!((tx.common_data.serial_id.0, tx.eth_block()), (0, 10));
+    let tx: L1Tx = db_txs[1].clone().try_into().unwrap();
+    assert_eq!((tx.common_data.serial_id.0, tx.eth_block()), (1, 15));
+}
+
+#[db_test]
+async fn test_concurrent_l1_requests_are_limited(connection_pool: ConnectionPool) {
+    const MAX_CONCURRENT_REQUESTS: usize = 2;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        LimitedEthClient::new(client.clone(), Some(MAX_CONCURRENT_REQUESTS)),
+        &connection_pool,
+        Duration::from_nanos(1),
+        Some(0),
+    )
+    .await;
+
+    let mut storage = connection_pool.access_test_storage().await;
+    // Emulate a backfill spanning many block chunks.
+    let txs: Vec<_> = (0..10)
+        .map(|serial_id| build_tx(serial_id, serial_id * MAX_BLOCKS_PER_REQUEST + 1))
+        .collect();
+    client.add_transactions(&txs).await;
+    client
+        .set_last_block_number(10 * MAX_BLOCKS_PER_REQUEST)
+        .await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let db_txs = get_all_db_txs(&mut storage).await;
+    assert_eq!(db_txs.len(), 10);
+    for (serial_id, db_tx) in db_txs.into_iter().enumerate() {
+        let db_tx: L1Tx = db_tx.try_into().unwrap();
+        assert_eq!(db_tx.common_data.serial_id.0, serial_id as u64);
+    }
+    assert_eq!(client.max_in_flight_requests(), MAX_CONCURRENT_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_l1_requests_are_sequential_without_limit() {
+    let client = FakeEthClient::new();
+    let limited_client = LimitedEthClient::new(client.clone(), None);
+    let requests = (0..10).map(|_| {
+        limited_client.get_priority_op_events(
+            BlockNumber::Number(0.into()),
+            BlockNumber::Number(10.into()),
+            0,
+        )
+    });
+    for result in futures::future::join_all(requests).await {
+        result.unwrap();
+    }
+    assert_eq!(client.max_in_flight_requests(), 1);
+}
+
 async fn get_all_db_txs(storage: &mut StorageProcessor<'_>) -> Vec<Transaction> {
     storage.transactions_dal().reset_mempool().await;
     storage
         .transactions_dal()
-        .sync_mempool(vec![], vec![], 0, 0, 1000)
+        .sync_mempool(vec![], vec![], 0, 0, 1000, MempoolOrderingStrategy::Fifo)
         .await
         .0
 }
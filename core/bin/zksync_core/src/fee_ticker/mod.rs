@@ -5,14 +5,15 @@ use core::fmt::Debug;
 use bigdecimal::BigDecimal;
 use num::{rational::Ratio, BigUint};
 use vm::vm_with_bootloader::base_fee_to_gas_per_pubdata;
-use zksync_types::Address;
+use zksync_types::{Address, U256};
 use zksync_utils::ratio_to_big_decimal_normalized;
 
-use self::error::TickerError;
+use self::{error::TickerError, policy::FeePolicy};
 use zksync_dal::tokens_web3_dal::TokensWeb3Dal;
 
 pub mod error;
 mod gas_price;
+pub mod policy;
 pub mod types;
 
 /// Amount of possible symbols after the decimal dot in the USD.
@@ -23,6 +24,9 @@ pub const USD_PRECISION: usize = 100;
 /// Used to convert `Ratio<BigUint>` to `BigDecimal`.
 pub const MIN_PRECISION: usize = 2;
 
+/// Precision of the fee multipliers returned by [`FeePolicy`].
+const FEE_MULTIPLIER_PRECISION: u64 = 1_000_000;
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum TokenPriceRequestType {
     USDForOneWei,
@@ -46,6 +50,21 @@ impl FeeTicker {
             })
     }
 
+    /// Scales the `fee` of a transaction sent by `initiator` to `contract` according to the fee policy.
+    pub fn apply_fee_policy(
+        policy: &dyn FeePolicy,
+        initiator: Address,
+        contract: Address,
+        fee: U256,
+    ) -> U256 {
+        let multiplier = policy.fee_multiplier(initiator, contract);
+        if multiplier == 1.0 {
+            return fee;
+        }
+        let scaled_multiplier = (multiplier * FEE_MULTIPLIER_PRECISION as f64).round() as u64;
+        fee.saturating_mul(scaled_multiplier.into()) / FEE_MULTIPLIER_PRECISION
+    }
+
     /// Returns the acceptable `gas_per_pubdata_byte` based on the current gas price.
     pub fn gas_per_pubdata_byte(gas_price_wei: u64, base_fee: u64) -> u32 {
         base_fee_to_gas_per_pubdata(gas_price_wei, base_fee) as u32
@@ -90,3 +109,41 @@ impl FeeTicker {
         Ok(final_price)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::fee_ticker::policy::{DefaultFeePolicy, SponsoredContractsPolicy};
+
+    #[test]
+    fn default_fee_policy_keeps_fees() {
+        let fee = U256::from(123_456_789_u64);
+        let adjusted_fee = FeeTicker::apply_fee_policy(
+            &DefaultFeePolicy,
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            fee,
+        );
+        assert_eq!(adjusted_fee, fee);
+    }
+
+    #[test]
+    fn sponsored_contracts_policy() {
+        let sponsored_contract = Address::repeat_byte(0x5);
+        let other_contract = Address::repeat_byte(0x6);
+        let initiator = Address::repeat_byte(1);
+        let fee = U256::from(1_000_000_u64);
+
+        let policy = SponsoredContractsPolicy::new(HashSet::from([sponsored_contract]), 0.0);
+        let adjusted_fee = FeeTicker::apply_fee_policy(&policy, initiator, sponsored_contract, fee);
+        assert_eq!(adjusted_fee, U256::zero());
+        let adjusted_fee = FeeTicker::apply_fee_policy(&policy, initiator, other_contract, fee);
+        assert_eq!(adjusted_fee, fee);
+
+        let policy = SponsoredContractsPolicy::new(HashSet::from([sponsored_contract]), 0.25);
+        let adjusted_fee = FeeTicker::apply_fee_policy(&policy, initiator, sponsored_contract, fee);
+        assert_eq!(adjusted_fee, U256::from(250_000_u64));
+    }
+}
@@ -0,0 +1,68 @@
+//! Fee policies allow to reduce fees for certain transactions, e.g. to sponsor calls to specific contracts.
+
+use std::{collections::HashSet, fmt::Debug};
+
+use zksync_config::configs::FeePolicyConfig;
+use zksync_types::Address;
+
+/// Policy consulted by the fee ticker to scale the fee of a transaction.
+pub trait FeePolicy: Debug + Send + Sync {
+    /// Returns the multiplier for the fee of a transaction sent by `initiator` to `contract`.
+    /// The multiplier is non-negative; `0.0` means that the transaction is free.
+    fn fee_multiplier(&self, initiator: Address, contract: Address) -> f64;
+}
+
+/// Policy leaving all the fees unchanged.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultFeePolicy;
+
+impl FeePolicy for DefaultFeePolicy {
+    fn fee_multiplier(&self, _initiator: Address, _contract: Address) -> f64 {
+        1.0
+    }
+}
+
+/// Policy applying a fixed multiplier to fees of transactions calling one of the sponsored contracts.
+#[derive(Debug, Clone)]
+pub struct SponsoredContractsPolicy {
+    sponsored_contracts: HashSet<Address>,
+    sponsored_fee_multiplier: f64,
+}
+
+impl SponsoredContractsPolicy {
+    pub fn new(sponsored_contracts: HashSet<Address>, sponsored_fee_multiplier: f64) -> Self {
+        assert!(
+            sponsored_fee_multiplier.is_finite() && sponsored_fee_multiplier >= 0.0,
+            "Sponsored fee multiplier must be a non-negative number, got {}",
+            sponsored_fee_multiplier
+        );
+        Self {
+            sponsored_contracts,
+            sponsored_fee_multiplier,
+        }
+    }
+}
+
+impl FeePolicy for SponsoredContractsPolicy {
+    fn fee_multiplier(&self, _initiator: Address, contract: Address) -> f64 {
+        if self.sponsored_contracts.contains(&contract) {
+            self.sponsored_fee_multiplier
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Creates a fee policy based on the provided config. If there are no sponsored contracts,
+/// returns the default policy.
+pub fn fee_policy_from_config(config: &FeePolicyConfig) -> Box<dyn FeePolicy> {
+    if config.sponsored_contracts.is_empty() {
+        Box::new(DefaultFeePolicy)
+    } else {
+        let sponsored_contracts = config.sponsored_contracts.iter().copied().collect();
+        Box::new(SponsoredContractsPolicy::new(
+            sponsored_contracts,
+            config.sponsored_fee_multiplier,
+        ))
+    }
+}
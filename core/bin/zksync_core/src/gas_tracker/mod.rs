@@ -2,8 +2,9 @@
 
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
-    block::BlockGasCount,
-    commitment::BlockWithMetadata,
+    block::{BlockGasCount, L1BatchHeader},
+    commitment::{BlockWithMetadata, SerializeCommitment},
+    l2_to_l1_log::L2ToL1Log,
     tx::tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics},
     ExecuteTransactionCommon, Transaction,
 };
@@ -108,3 +109,111 @@ pub fn commit_gas_count_for_block(block: &BlockWithMetadata) -> u32 {
     let additional_cost = additional_calldata_bytes * GAS_PER_BYTE;
     base_cost + additional_cost
 }
+
+/// Projected L1 gas costs of committing, proving and executing an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasProjection {
+    pub commit: u32,
+    pub prove: u32,
+    pub execute: u32,
+}
+
+impl GasProjection {
+    pub fn for_operation(&self, op: AggregatedActionType) -> u32 {
+        match op {
+            AggregatedActionType::CommitBlocks => self.commit,
+            AggregatedActionType::PublishProofBlocksOnchain => self.prove,
+            AggregatedActionType::ExecuteBlocks => self.execute,
+        }
+    }
+}
+
+/// Projects L1 gas costs of the operations for the batch based on its header, which is available
+/// before the batch metadata is computed. Unlike [`commit_gas_count_for_block()`], the projection
+/// doesn't account for compressed storage writes and factory deps, so it should be treated as a lower bound.
+pub fn project_l1_batch_gas(batch: &L1BatchHeader) -> GasProjection {
+    let l1_tx_count = u32::from(batch.l1_tx_count);
+    let l2_tx_count = u32::from(batch.l2_tx_count);
+    let pubdata_bytes = batch.l2_to_l1_logs.len() * L2ToL1Log::SERIALIZED_SIZE
+        + batch.l2_to_l1_messages.iter().map(Vec::len).sum::<usize>();
+
+    GasProjection {
+        commit: block_base_cost(AggregatedActionType::CommitBlocks)
+            + (l1_tx_count + l2_tx_count) * EXECUTE_COMMIT_COST
+            + pubdata_bytes as u32 * GAS_PER_BYTE,
+        prove: block_base_cost(AggregatedActionType::PublishProofBlocksOnchain),
+        execute: block_base_cost(AggregatedActionType::ExecuteBlocks)
+            + l1_tx_count * L1_OPERATION_EXECUTE_COST
+            + l2_tx_count * EXECUTE_EXECUTE_COST,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{Address, L1BatchNumber};
+
+    use super::*;
+
+    fn mock_batch(l1_tx_count: u16, l2_tx_count: u16) -> L1BatchHeader {
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            0,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        header.l1_tx_count = l1_tx_count;
+        header.l2_tx_count = l2_tx_count;
+        // Each transaction emits a single L2-to-L1 message.
+        let tx_count = usize::from(l1_tx_count + l2_tx_count);
+        header.l2_to_l1_logs = vec![L2ToL1Log::default(); tx_count];
+        header.l2_to_l1_messages = vec![vec![0; 32]; tx_count];
+        header
+    }
+
+    #[test]
+    fn empty_batch_projection() {
+        let projection = project_l1_batch_gas(&mock_batch(0, 0));
+        let base_cost = new_block_gas_count();
+        assert_eq!(projection.commit, base_cost.commit);
+        assert_eq!(projection.prove, base_cost.prove);
+        assert_eq!(projection.execute, base_cost.execute);
+    }
+
+    #[test]
+    fn projection_is_monotonic_in_tx_count() {
+        let ops = [
+            AggregatedActionType::CommitBlocks,
+            AggregatedActionType::PublishProofBlocksOnchain,
+            AggregatedActionType::ExecuteBlocks,
+        ];
+        let tx_counts = [
+            (0, 0),
+            (0, 1),
+            (1, 1),
+            (1, 10),
+            (5, 10),
+            (5, 100),
+            (50, 500),
+        ];
+        let projections: Vec<_> = tx_counts
+            .iter()
+            .map(|&(l1_tx_count, l2_tx_count)| {
+                project_l1_batch_gas(&mock_batch(l1_tx_count, l2_tx_count))
+            })
+            .collect();
+
+        for pair in projections.windows(2) {
+            let (prev, next) = (pair[0], pair[1]);
+            for op in ops {
+                assert!(
+                    next.for_operation(op) >= prev.for_operation(op),
+                    "{op:?}: {prev:?} -> {next:?}"
+                );
+            }
+            assert!(next.commit > prev.commit, "{prev:?} -> {next:?}");
+        }
+        // Executing priority operations costs extra gas.
+        assert!(projections[2].execute > projections[1].execute);
+    }
+}
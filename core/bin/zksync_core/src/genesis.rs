@@ -2,9 +2,14 @@
 //! It initializes the Merkle tree with the basic setup (such as fields of special service accounts),
 //! setups the required databases, and outputs the data required to initialize a smart contract.
 
+use async_trait::async_trait;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use vm::zk_evm::aux_structures::{LogQuery, Timestamp};
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
-use zksync_dal::StorageProcessor;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_health_check::{CheckHealth, CheckHealthStatus};
 use zksync_merkle_tree::domain::ZkSyncTree;
 use zksync_types::{
     block::DeployedContract,
@@ -34,21 +39,39 @@ pub enum GenesisParams {
     },
 }
 
+/// Performs genesis if it wasn't performed previously and returns the genesis root hash.
+///
+/// It's safe to call this function concurrently from multiple processes: genesis is guarded
+/// by a DB lock, so only one of the callers performs it, while others wait for it to complete.
 pub async fn ensure_genesis_state(
     storage: &mut StorageProcessor<'_>,
     zksync_chain_id: L2ChainId,
     genesis_params: &GenesisParams,
 ) -> H256 {
+    run_genesis_if_needed(storage, zksync_chain_id, genesis_params)
+        .await
+        .0
+}
+
+/// Returns the genesis root hash and a flag whether genesis was performed by this call.
+async fn run_genesis_if_needed(
+    storage: &mut StorageProcessor<'_>,
+    zksync_chain_id: L2ChainId,
+    genesis_params: &GenesisParams,
+) -> (H256, bool) {
     let mut transaction = storage.start_transaction().await;
+    // Serialize concurrent genesis attempts; the lock is held until the transaction is finished.
+    transaction.blocks_dal().lock_genesis().await;
 
     // return if genesis block was already processed
     if !transaction.blocks_dal().is_genesis_needed().await {
         vlog::debug!("genesis is not needed!");
-        return transaction
+        let root_hash = transaction
             .blocks_dal()
             .get_block_state_root(L1BatchNumber(0))
             .await
             .expect("genesis block hash is empty");
+        return (root_hash, false);
     }
 
     vlog::info!("running regenesis");
@@ -144,7 +167,7 @@ pub async fn ensure_genesis_state(
         base_system_contracts_hashes.default_aa
     );
 
-    genesis_root_hash
+    (genesis_root_hash, true)
 }
 
 // Default account and bootloader are not a regular system contracts
@@ -153,8 +176,9 @@ pub async fn ensure_genesis_state(
 // Both are rather parameters of a block and not system contracts.
 // The code of the bootloader should not be deployed anywhere anywhere in the kernel space (i.e. addresses below 2^16)
 // because in this case we will have to worry about protecting it.
-async fn insert_base_system_contracts_to_factory_deps(
+pub(crate) async fn insert_base_system_contracts_to_factory_deps(
     storage: &mut StorageProcessor<'_>,
+    miniblock_number: MiniblockNumber,
     contracts: BaseSystemContracts,
 ) {
     let factory_deps = [contracts.bootloader, contracts.default_aa]
@@ -164,7 +188,7 @@ async fn insert_base_system_contracts_to_factory_deps(
 
     storage
         .storage_dal()
-        .insert_factory_deps(MiniblockNumber(0), &factory_deps)
+        .insert_factory_deps(miniblock_number, &factory_deps)
         .await;
 }
 
@@ -299,7 +323,12 @@ pub(crate) async fn create_genesis_block(
         .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(0))
         .await;
 
-    insert_base_system_contracts_to_factory_deps(&mut transaction, base_system_contracts).await;
+    insert_base_system_contracts_to_factory_deps(
+        &mut transaction,
+        MiniblockNumber(0),
+        base_system_contracts,
+    )
+    .await;
 
     let contracts = get_system_smart_contracts();
     insert_system_contracts(&mut transaction, contracts, chain_id).await;
@@ -362,10 +391,46 @@ pub(crate) async fn save_genesis_block_metadata(
         .await;
 }
 
+/// Health check reporting the node as not ready until genesis is performed. Before that,
+/// the API cannot serve requests, so load balancers should not route traffic to the node.
+#[derive(Debug)]
+pub struct GenesisHealthCheck {
+    connection_pool: ConnectionPool,
+    is_genesis_performed: AtomicBool,
+}
+
+impl GenesisHealthCheck {
+    pub fn new(connection_pool: ConnectionPool) -> Self {
+        Self {
+            connection_pool,
+            is_genesis_performed: AtomicBool::new(false),
+        }
+    }
+}
+
+#[async_trait]
+impl CheckHealth for GenesisHealthCheck {
+    async fn check_health(&self) -> CheckHealthStatus {
+        // Genesis cannot be undone, so the DB doesn't need to be queried once it's performed.
+        if self.is_genesis_performed.load(Ordering::Relaxed) {
+            return CheckHealthStatus::Ready;
+        }
+        let mut storage = self.connection_pool.access_storage().await;
+        if storage.blocks_dal().is_genesis_needed().await {
+            return CheckHealthStatus::Degraded("genesis is not performed yet".to_owned());
+        }
+        self.is_genesis_performed.store(true, Ordering::Relaxed);
+        CheckHealthStatus::Ready
+    }
+
+    fn affects_liveness(&self) -> bool {
+        false
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use db_test_macro::db_test;
-    use zksync_dal::ConnectionPool;
 
     use super::*;
 
@@ -387,4 +452,44 @@ mod tests {
         // Check that `ensure_genesis_state()` doesn't panic on repeated runs.
         ensure_genesis_state(&mut conn, L2ChainId(270), &params).await;
     }
+
+    #[db_test]
+    async fn genesis_health_check(pool: ConnectionPool) {
+        let mut conn = pool.access_storage().await;
+        conn.blocks_dal().delete_genesis().await;
+        drop(conn);
+
+        let health_check = GenesisHealthCheck::new(pool.clone());
+        assert!(matches!(
+            health_check.check_health().await,
+            CheckHealthStatus::Degraded(_)
+        ));
+
+        let mut conn = pool.access_storage().await;
+        let params = GenesisParams::MainNode {
+            first_validator: Address::random(),
+        };
+        ensure_genesis_state(&mut conn, L2ChainId(270), &params).await;
+        drop(conn);
+        assert_eq!(health_check.check_health().await, CheckHealthStatus::Ready);
+    }
+
+    /// Concurrent callers are serialized by the genesis lock (tested in the DAL crate using real
+    /// connections), so here we only check that a caller observing completed genesis doesn't redo it.
+    #[db_test]
+    async fn running_genesis_after_it_is_performed(pool: ConnectionPool) {
+        let mut conn = pool.access_storage().await;
+        conn.blocks_dal().delete_genesis().await;
+
+        let params = GenesisParams::MainNode {
+            first_validator: Address::random(),
+        };
+        let (first_root_hash, first_performed) =
+            run_genesis_if_needed(&mut conn, L2ChainId(270), &params).await;
+        assert!(first_performed);
+        let (second_root_hash, second_performed) =
+            run_genesis_if_needed(&mut conn, L2ChainId(270), &params).await;
+        assert!(!second_performed);
+        assert_eq!(first_root_hash, second_root_hash);
+    }
 }
@@ -64,7 +64,35 @@ impl L1BatchMetricsReporter {
         let oldest_unexecuted_batch_timestamp =
             conn.blocks_dal().oldest_unexecuted_batch_timestamp().await;
 
+        match conn
+            .transactions_web3_dal()
+            .count_transactions_by_status()
+            .await
+        {
+            Ok(counts) => {
+                let statuses = [
+                    ("pending", counts.pending),
+                    ("included", counts.included),
+                    ("failed", counts.failed),
+                ];
+                for (status, count) in statuses {
+                    metrics::gauge!("server.transactions.count", count as f64, "status" => status);
+                }
+            }
+            Err(err) => vlog::warn!("Failed counting transactions by status: {err}"),
+        }
+
         let now = seconds_since_epoch();
+        match conn
+            .blocks_web3_dal()
+            .count_miniblocks_in_time_window(now.saturating_sub(60), now)
+            .await
+        {
+            Ok(count) => metrics::gauge!("server.miniblocks_per_minute", count as f64),
+            Err(err) => {
+                vlog::warn!("Failed counting miniblocks sealed within the last minute: {err}")
+            }
+        }
 
         if let Some(timestamp) = oldest_uncommitted_batch_timestamp {
             metrics::gauge!(
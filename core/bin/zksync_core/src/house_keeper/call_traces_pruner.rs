@@ -0,0 +1,182 @@
+use async_trait::async_trait;
+
+use zksync_dal::ConnectionPool;
+use zksync_types::MiniblockNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Max number of call traces removed in a single DB query.
+const PRUNING_CHUNK_SIZE: usize = 1_000;
+
+/// Removes call traces for transactions in miniblocks older than the configured retention.
+#[derive(Debug)]
+pub struct CallTracePruner {
+    retention_miniblocks: u32,
+    pruning_interval_ms: u64,
+    pool: ConnectionPool,
+}
+
+impl CallTracePruner {
+    pub fn new(retention_miniblocks: u32, pruning_interval_ms: u64, pool: ConnectionPool) -> Self {
+        Self {
+            retention_miniblocks,
+            pruning_interval_ms,
+            pool,
+        }
+    }
+
+    async fn prune_traces(&self, chunk_size: usize) -> usize {
+        let mut storage = self.pool.access_storage().await;
+        let sealed_miniblock = storage.blocks_dal().get_sealed_miniblock_number().await;
+        let Some(first_retained) = sealed_miniblock.0.checked_sub(self.retention_miniblocks) else {
+            return 0;
+        };
+        let first_retained = MiniblockNumber(first_retained);
+
+        let mut total_pruned = 0;
+        loop {
+            // Traces are removed in chunks so that each query holds locks only briefly.
+            let pruned = storage
+                .transactions_dal()
+                .prune_call_traces(first_retained, chunk_size)
+                .await;
+            total_pruned += pruned;
+            metrics::counter!("housekeeper.call_traces_pruned", pruned as u64);
+            if pruned < chunk_size {
+                break;
+            }
+        }
+
+        if total_pruned > 0 {
+            vlog::info!(
+                "Pruned {total_pruned} call traces for miniblocks before #{first_retained}"
+            );
+        }
+        total_pruned
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for CallTracePruner {
+    const SERVICE_NAME: &'static str = "CallTracePruner";
+
+    async fn run_routine_task(&mut self) {
+        self.prune_traces(PRUNING_CHUNK_SIZE).await;
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.pruning_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::MiniblockHeader,
+        fee::{Fee, TransactionExecutionMetrics},
+        l2::L2Tx,
+        tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
+        vm_trace::Call,
+        Address, L2ChainId, Nonce, H256, U256,
+    };
+    use zksync_utils::miniblock_hash;
+
+    use super::*;
+
+    fn create_l2_transaction() -> L2Tx {
+        let fee = Fee {
+            gas_limit: 1_000_u64.into(),
+            max_fee_per_gas: 1_u64.into(),
+            max_priority_fee_per_gas: 0_u64.into(),
+            gas_per_pubdata_limit: 800_u64.into(),
+        };
+        let mut tx = L2Tx::new_signed(
+            Address::random(),
+            vec![],
+            Nonce(0),
+            fee,
+            U256::zero(),
+            L2ChainId(270),
+            &H256::repeat_byte(0x11),
+            None,
+            Default::default(),
+        )
+        .unwrap();
+        // Randomize the input so that transaction hashes are unique.
+        tx.set_input(H256::random().0.to_vec(), H256::random());
+        tx
+    }
+
+    /// Seeds miniblocks `1..=miniblock_count`, each with a single transaction that has a call trace.
+    async fn seed_traces(pool: &ConnectionPool, miniblock_count: u32) -> Vec<H256> {
+        let mut storage = pool.access_storage().await;
+        storage
+            .blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+
+        let mut tx_hashes = vec![];
+        for number in 1..=miniblock_count {
+            let number = MiniblockNumber(number);
+            let header = MiniblockHeader {
+                number,
+                timestamp: 0,
+                hash: miniblock_hash(number),
+                l1_tx_count: 0,
+                l2_tx_count: 1,
+                base_fee_per_gas: 0,
+                l1_gas_price: 0,
+                l2_fair_gas_price: 0,
+                base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+            };
+            storage.blocks_dal().insert_miniblock(&header).await;
+
+            let tx = create_l2_transaction();
+            tx_hashes.push(tx.hash());
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+            let tx_result = TransactionExecutionResult {
+                hash: tx.hash(),
+                transaction: tx.into(),
+                execution_info: ExecutionMetrics::default(),
+                execution_status: TxExecutionStatus::Success,
+                refunded_gas: 0,
+                operator_suggested_refund: 0,
+                compressed_bytecodes: vec![],
+                call_traces: vec![Call::default()],
+                revert_reason: None,
+            };
+            storage
+                .transactions_dal()
+                .mark_txs_as_executed_in_miniblock(number, &[tx_result], U256::one())
+                .await;
+        }
+        tx_hashes
+    }
+
+    #[db_test]
+    async fn pruning_call_traces(pool: ConnectionPool) {
+        let tx_hashes = seed_traces(&pool, 5).await;
+
+        let pruner = CallTracePruner::new(2, 1_000, pool.clone());
+        // Use a small chunk size to check that pruning proceeds in multiple chunks.
+        let pruned = pruner.prune_traces(1).await;
+        assert_eq!(pruned, 2);
+
+        let mut storage = pool.access_storage().await;
+        for (i, &tx_hash) in tx_hashes.iter().enumerate() {
+            let trace = storage.transactions_dal().get_call_trace(tx_hash).await;
+            // Traces for miniblocks #1 and #2 should be pruned.
+            assert_eq!(trace.is_some(), i >= 2, "tx #{i}");
+        }
+        drop(storage);
+
+        // Repeated pruning is a no-op.
+        assert_eq!(pruner.prune_traces(1).await, 0);
+    }
+}
@@ -42,10 +42,15 @@ impl PeriodicJob for FriProverJobRetryManager {
             .requeue_stuck_jobs(self.processing_timeout, self.max_attempts)
             .await;
         let job_len = stuck_jobs.len();
+        let stuck_in_progress_len = stuck_jobs
+            .iter()
+            .filter(|job| job.status == "in_progress")
+            .count();
         for stuck_job in stuck_jobs {
             vlog::info!("re-queuing fri prover job {:?}", stuck_job);
         }
         metrics::counter!("server.prover_fri.requeued_jobs", job_len as u64);
+        metrics::counter!("fri_prover.requeued_stuck", stuck_in_progress_len as u64);
     }
 
     fn polling_interval_ms(&self) -> u64 {
@@ -1,6 +1,7 @@
 use crate::house_keeper::periodic_job::PeriodicJob;
 use async_trait::async_trait;
 use zksync_dal::ConnectionPool;
+use zksync_types::proofs::AggregationRound;
 
 #[derive(Debug)]
 pub struct FriProverStatsReporter {
@@ -55,6 +56,25 @@ impl PeriodicJob for FriProverStatsReporter {
               "circuit_id" => circuit_id.to_string(),
               "aggregation_round" => aggregation_round.to_string());
         }
+
+        let oldest_job_age_by_round = conn.fri_prover_jobs_dal().oldest_unpicked_job_age().await;
+        // Rounds without queued jobs are reported with zero age, so that the gauge doesn't get stuck
+        // at the last non-zero value once the queue is drained.
+        for aggregation_round in [
+            AggregationRound::BasicCircuits,
+            AggregationRound::LeafAggregation,
+            AggregationRound::NodeAggregation,
+            AggregationRound::Scheduler,
+        ] {
+            let aggregation_round = aggregation_round as u8;
+            let age = oldest_job_age_by_round
+                .get(&aggregation_round)
+                .copied()
+                .unwrap_or_default();
+            metrics::gauge!(
+              "fri_prover.oldest_unpicked_job_age_seconds", age.as_secs_f64(),
+              "aggregation_round" => aggregation_round.to_string());
+        }
     }
 
     fn polling_interval_ms(&self) -> u64 {
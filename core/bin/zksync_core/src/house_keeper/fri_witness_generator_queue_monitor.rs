@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use zksync_dal::ConnectionPool;
 use zksync_types::proofs::{AggregationRound, JobCountStatistics};
 
@@ -12,6 +13,8 @@ const FRI_WITNESS_GENERATOR_SERVICE_NAME: &str = "fri_witness_generator";
 pub struct FriWitnessGeneratorStatsReporter {
     reporting_interval_ms: u64,
     pool: ConnectionPool,
+    /// DB timestamps up to which completed jobs were reported for each round.
+    last_reported_at: HashMap<AggregationRound, NaiveDateTime>,
 }
 
 impl FriWitnessGeneratorStatsReporter {
@@ -19,6 +22,31 @@ impl FriWitnessGeneratorStatsReporter {
         Self {
             reporting_interval_ms,
             pool,
+            last_reported_at: HashMap::new(),
+        }
+    }
+
+    /// Reports the number of jobs completed since the previous invocation for each round.
+    /// On the first invocation, only the reporting watermarks are initialized.
+    async fn report_completed_jobs(&mut self) {
+        let mut conn = self.pool.access_storage().await;
+        for round in [
+            AggregationRound::BasicCircuits,
+            AggregationRound::LeafAggregation,
+            AggregationRound::NodeAggregation,
+            AggregationRound::Scheduler,
+        ] {
+            let since = self.last_reported_at.get(&round).copied();
+            let (completed_jobs, reported_at) = conn
+                .fri_witness_generator_dal()
+                .get_witness_jobs_completed_since(round, since)
+                .await;
+            self.last_reported_at.insert(round, reported_at);
+            metrics::counter!(
+                format!("{}.completed_jobs", FRI_WITNESS_GENERATOR_SERVICE_NAME),
+                completed_jobs as u64,
+                "round" => format!("{:?}", round)
+            );
         }
     }
 
@@ -111,6 +139,8 @@ impl PeriodicJob for FriWitnessGeneratorStatsReporter {
             aggregated.in_progress as f64,
             "type" => "in_progress"
         );
+
+        self.report_completed_jobs().await;
     }
 
     fn polling_interval_ms(&self) -> u64 {
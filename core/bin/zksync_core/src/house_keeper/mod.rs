@@ -1,4 +1,5 @@
 pub mod blocks_state_reporter;
+pub mod call_traces_pruner;
 pub mod fri_prover_job_retry_manager;
 pub mod fri_prover_queue_monitor;
 pub mod fri_scheduler_circuit_queuer;
@@ -1,8 +1,65 @@
-use std::time::Duration;
+use std::{
+    collections::HashMap,
+    panic::AssertUnwindSafe,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use async_trait::async_trait;
+use futures::FutureExt;
 use tokio::time::sleep;
 
+use zksync_health_check::{CheckHealth, CheckHealthStatus};
+
+/// Health check for periodic jobs. Tracks the number of consecutive failed ticks of every job
+/// and reports not ready if any job has failed too many ticks in a row.
+#[derive(Debug, Clone)]
+pub struct PeriodicJobHealthCheck {
+    max_consecutive_failures: u32,
+    consecutive_failures: Arc<Mutex<HashMap<&'static str, u32>>>,
+}
+
+impl PeriodicJobHealthCheck {
+    pub fn new(max_consecutive_failures: u32) -> Self {
+        Self {
+            max_consecutive_failures,
+            consecutive_failures: Arc::default(),
+        }
+    }
+
+    /// Returns the number of consecutive failed ticks of the specified job.
+    pub fn consecutive_failures(&self, job: &str) -> u32 {
+        let consecutive_failures = self.consecutive_failures.lock().unwrap();
+        consecutive_failures.get(job).copied().unwrap_or(0)
+    }
+
+    fn set_consecutive_failures(&self, job: &'static str, count: u32) {
+        let mut consecutive_failures = self.consecutive_failures.lock().unwrap();
+        consecutive_failures.insert(job, count);
+    }
+}
+
+#[async_trait]
+impl CheckHealth for PeriodicJobHealthCheck {
+    async fn check_health(&self) -> CheckHealthStatus {
+        let consecutive_failures = self.consecutive_failures.lock().unwrap();
+        let mut failing_jobs: Vec<_> = consecutive_failures
+            .iter()
+            .filter(|(_, &count)| count >= self.max_consecutive_failures)
+            .map(|(&job, _)| job)
+            .collect();
+        if failing_jobs.is_empty() {
+            return CheckHealthStatus::Ready;
+        }
+
+        failing_jobs.sort_unstable();
+        CheckHealthStatus::NotReady(format!(
+            "Periodic jobs {:?} failed at least {} times in a row",
+            failing_jobs, self.max_consecutive_failures
+        ))
+    }
+}
+
 #[async_trait]
 pub trait PeriodicJob: Sync + Send {
     const SERVICE_NAME: &'static str;
@@ -10,7 +67,18 @@ pub trait PeriodicJob: Sync + Send {
     /// Runs the routine task periodically in [`Self::polling_interval_ms()`] frequency.
     async fn run_routine_task(&mut self);
 
-    async fn run(mut self)
+    async fn run(self)
+    where
+        Self: Sized,
+    {
+        self.run_with_health_check(None).await;
+    }
+
+    /// Same as [`Self::run()`], additionally reporting failed ticks to `health_check`. A tick fails
+    /// if [`Self::run_routine_task()`] panics; the failure is logged, and the job keeps running.
+    /// The number of consecutive failed ticks is exported as the `housekeeper.job.consecutive_failures`
+    /// gauge regardless of `health_check`.
+    async fn run_with_health_check(mut self, health_check: Option<PeriodicJobHealthCheck>)
     where
         Self: Sized,
     {
@@ -19,11 +87,105 @@ pub trait PeriodicJob: Sync + Send {
             Self::SERVICE_NAME,
             self.polling_interval_ms()
         );
+        let mut consecutive_failures = 0_u32;
         loop {
-            self.run_routine_task().await;
+            let tick_result = AssertUnwindSafe(self.run_routine_task())
+                .catch_unwind()
+                .await;
+            if let Err(panic) = tick_result {
+                consecutive_failures += 1;
+                let message = panic
+                    .downcast_ref::<String>()
+                    .map(String::as_str)
+                    .or_else(|| panic.downcast_ref::<&str>().copied())
+                    .unwrap_or("unknown error");
+                vlog::error!(
+                    "Periodic job {} failed ({consecutive_failures} times in a row): {message}",
+                    Self::SERVICE_NAME
+                );
+            } else {
+                consecutive_failures = 0;
+            }
+
+            metrics::gauge!(
+                "housekeeper.job.consecutive_failures",
+                consecutive_failures as f64,
+                "job" => Self::SERVICE_NAME
+            );
+            if let Some(health_check) = &health_check {
+                health_check.set_consecutive_failures(Self::SERVICE_NAME, consecutive_failures);
+            }
             sleep(Duration::from_millis(self.polling_interval_ms())).await;
         }
     }
 
     fn polling_interval_ms(&self) -> u64;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct FailingJob {
+        should_fail: Arc<AtomicBool>,
+        ticks: Arc<AtomicU32>,
+    }
+
+    #[async_trait]
+    impl PeriodicJob for FailingJob {
+        const SERVICE_NAME: &'static str = "failing_job";
+
+        async fn run_routine_task(&mut self) {
+            self.ticks.fetch_add(1, Ordering::SeqCst);
+            if self.should_fail.load(Ordering::SeqCst) {
+                panic!("query failed");
+            }
+        }
+
+        fn polling_interval_ms(&self) -> u64 {
+            1
+        }
+    }
+
+    async fn wait_for_ticks(ticks: &AtomicU32, count: u32) {
+        let start = ticks.load(Ordering::SeqCst);
+        while ticks.load(Ordering::SeqCst) < start + count {
+            sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn failing_job_makes_health_check_fail() {
+        let should_fail = Arc::new(AtomicBool::new(true));
+        let ticks = Arc::new(AtomicU32::new(0));
+        let job = FailingJob {
+            should_fail: should_fail.clone(),
+            ticks: ticks.clone(),
+        };
+        let health_check = PeriodicJobHealthCheck::new(3);
+        let job_task = tokio::spawn(job.run_with_health_check(Some(health_check.clone())));
+
+        wait_for_ticks(&ticks, 5).await;
+        assert!(health_check.consecutive_failures(FailingJob::SERVICE_NAME) >= 3);
+        let CheckHealthStatus::NotReady(message) = health_check.check_health().await else {
+            panic!("Failing job should make the health check fail");
+        };
+        assert!(message.contains(FailingJob::SERVICE_NAME), "{message}");
+        // The job keeps running despite failures.
+        assert!(!job_task.is_finished());
+
+        should_fail.store(false, Ordering::SeqCst);
+        wait_for_ticks(&ticks, 2).await;
+        // Wait until the successful tick is recorded.
+        sleep(Duration::from_millis(10)).await;
+        assert_eq!(
+            health_check.consecutive_failures(FailingJob::SERVICE_NAME),
+            0
+        );
+        assert_eq!(health_check.check_health().await, CheckHealthStatus::Ready);
+        job_task.abort();
+    }
+}
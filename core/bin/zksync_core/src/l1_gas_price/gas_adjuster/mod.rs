@@ -7,19 +7,26 @@ use tokio::sync::watch::Receiver;
 
 use zksync_config::GasAdjusterConfig;
 use zksync_eth_client::{types::Error, EthInterface};
+use zksync_types::U256;
 
-use super::{L1GasPriceProvider, L1TxParamsProvider};
+use super::{FeeParams, L1GasPriceProvider, L1TxParamsProvider};
 
 pub mod bounded_gas_adjuster;
 #[cfg(test)]
 mod tests;
 
+/// Percentile of the base fee samples window that is reported alongside the median.
+const BASE_FEE_PERCENTILE: usize = 90;
+
 /// This component keeps track of the median base_fee from the last `max_base_fee_samples` blocks.
 /// It is used to adjust the base_fee of transactions sent to L1.
 #[derive(Debug)]
 pub struct GasAdjuster<E> {
     pub(super) statistics: GasStatistics,
     pub(super) config: GasAdjusterConfig,
+    /// Last observed base fee per blob gas (EIP-4844). `None` if it couldn't be fetched, e.g. because
+    /// the L1 doesn't support blobs.
+    blob_base_fee: RwLock<Option<U256>>,
     eth_client: E,
 }
 
@@ -36,11 +43,14 @@ impl<E: EthInterface> GasAdjuster<E> {
         let history = eth_client
             .base_fee_history(current_block, config.max_base_fee_samples, "gas_adjuster")
             .await?;
-        Ok(Self {
+        let adjuster = Self {
             statistics: GasStatistics::new(config.max_base_fee_samples, current_block, &history),
+            blob_base_fee: RwLock::new(None),
             eth_client,
             config,
-        })
+        };
+        adjuster.update_blob_base_fee().await;
+        Ok(adjuster)
     }
 
     /// Performs an actualization routine for `GasAdjuster`.
@@ -75,10 +85,55 @@ impl<E: EthInterface> GasAdjuster<E> {
             );
 
             self.statistics.add_samples(&history);
+            let (median, percentile) = self.statistics.median_and_percentile();
+            metrics::gauge!(
+                "server.gas_adjuster.smoothed_base_fee_per_gas",
+                median as f64
+            );
+            metrics::gauge!(
+                "server.gas_adjuster.base_fee_per_gas_percentile",
+                percentile as f64,
+                "percentile" => BASE_FEE_PERCENTILE.to_string()
+            );
+            self.update_blob_base_fee().await;
         }
         Ok(())
     }
 
+    async fn update_blob_base_fee(&self) {
+        // Not all L1 networks support blobs, so the error is only surfaced when sending a blob transaction.
+        let blob_base_fee = match self.eth_client.get_blob_base_fee("gas_adjuster").await {
+            Ok(blob_base_fee) => {
+                metrics::gauge!(
+                    "server.gas_adjuster.current_blob_base_fee",
+                    blob_base_fee.low_u128() as f64
+                );
+                Some(blob_base_fee)
+            }
+            Err(err) => {
+                vlog::debug!("Cannot get blob base fee: {err}");
+                None
+            }
+        };
+        *self.blob_base_fee.write().unwrap() = blob_base_fee;
+    }
+
+    /// Returns the `(block number, base fee)` samples the adjuster currently bases its decisions on,
+    /// ordered from the oldest to the newest block.
+    pub fn recent_samples(&self) -> Vec<(u64, U256)> {
+        self.statistics.recent_samples()
+    }
+
+    fn scale_factor(&self, time_in_mempool: u32) -> f64 {
+        let a = self.config.pricing_formula_parameter_a;
+        let b = self.config.pricing_formula_parameter_b;
+
+        // Currently we use an exponential formula.
+        // The alternative is a linear one:
+        // let scale_factor = a + b * time_in_mempool as f64;
+        a * b.powf(time_in_mempool as f64)
+    }
+
     pub async fn run(self: Arc<Self>, stop_receiver: Receiver<bool>) {
         loop {
             if *stop_receiver.borrow() {
@@ -117,13 +172,7 @@ impl<E: EthInterface> L1TxParamsProvider for GasAdjuster<E> {
     // In other words, in order to pay less fees, we are ready to wait longer.
     // But the longer we wait, the more we are ready to pay.
     fn get_base_fee(&self, time_in_mempool: u32) -> u64 {
-        let a = self.config.pricing_formula_parameter_a;
-        let b = self.config.pricing_formula_parameter_b;
-
-        // Currently we use an exponential formula.
-        // The alternative is a linear one:
-        // let scale_factor = a + b * time_in_mempool as f64;
-        let scale_factor = a * b.powf(time_in_mempool as f64);
+        let scale_factor = self.scale_factor(time_in_mempool);
         let median = self.statistics.median();
 
         metrics::gauge!("server.gas_adjuster.median_base_fee_per_gas", median as f64);
@@ -132,6 +181,17 @@ impl<E: EthInterface> L1TxParamsProvider for GasAdjuster<E> {
         new_fee as u64
     }
 
+    // The blob base fee is adjusted using the same formula as the base fee. Unlike the base fee,
+    // blob base fee history is not tracked, so the last observed value is used.
+    fn get_blob_base_fee(&self, time_in_mempool: u32) -> Option<U256> {
+        const SCALE_FACTOR_PRECISION: u64 = 1_000;
+
+        let blob_base_fee = (*self.blob_base_fee.read().unwrap())?;
+        let scale_factor =
+            (self.scale_factor(time_in_mempool) * SCALE_FACTOR_PRECISION as f64) as u64;
+        Some(blob_base_fee * scale_factor / SCALE_FACTOR_PRECISION)
+    }
+
     fn get_next_block_minimal_base_fee(&self) -> u64 {
         let last_block_base_fee = self.statistics.last_added_value();
 
@@ -149,6 +209,18 @@ impl<E: EthInterface> L1TxParamsProvider for GasAdjuster<E> {
     fn get_priority_fee(&self) -> u64 {
         self.config.default_priority_fee_per_gas
     }
+
+    fn get_fee_params(&self, base_fee_per_gas: u64, priority_fee_per_gas: u64) -> FeeParams {
+        let max_fee = U256::from(base_fee_per_gas) + U256::from(priority_fee_per_gas);
+        if self.config.use_legacy_gas_price {
+            FeeParams::Legacy(max_fee)
+        } else {
+            FeeParams::Eip1559 {
+                max_fee,
+                max_priority: priority_fee_per_gas.into(),
+            }
+        }
+    }
 }
 
 /// Helper structure responsible for collecting the data about recent transactions,
@@ -157,6 +229,7 @@ impl<E: EthInterface> L1TxParamsProvider for GasAdjuster<E> {
 pub(super) struct GasStatisticsInner {
     samples: VecDeque<u64>,
     median_cached: u64,
+    percentile_cached: u64,
     max_samples: usize,
     last_processed_block: usize,
 }
@@ -167,6 +240,7 @@ impl GasStatisticsInner {
             max_samples,
             samples: VecDeque::with_capacity(max_samples),
             median_cached: 0,
+            percentile_cached: 0,
             last_processed_block: 0,
         };
 
@@ -182,10 +256,24 @@ impl GasStatisticsInner {
         self.median_cached
     }
 
+    fn percentile(&self) -> u64 {
+        self.percentile_cached
+    }
+
     fn last_added_value(&self) -> u64 {
         self.samples.back().copied().unwrap_or(self.median_cached)
     }
 
+    /// Returns samples paired with the numbers of L1 blocks they were observed in.
+    fn samples_with_blocks(&self) -> Vec<(u64, U256)> {
+        let first_block = (self.last_processed_block + 1).saturating_sub(self.samples.len());
+        self.samples
+            .iter()
+            .enumerate()
+            .map(|(i, &fee)| ((first_block + i) as u64, U256::from(fee)))
+            .collect()
+    }
+
     fn add_samples(&mut self, fees: &[u64]) {
         self.samples.extend(fees);
         self.last_processed_block += fees.len();
@@ -195,8 +283,11 @@ impl GasStatisticsInner {
 
         let mut samples: Vec<_> = self.samples.iter().cloned().collect();
         let (_, &mut median, _) = samples.select_nth_unstable(self.samples.len() / 2);
+        let percentile_idx = (samples.len() * BASE_FEE_PERCENTILE / 100).min(samples.len() - 1);
+        let (_, &mut percentile, _) = samples.select_nth_unstable(percentile_idx);
 
         self.median_cached = median;
+        self.percentile_cached = percentile;
     }
 }
 
@@ -220,6 +311,17 @@ impl GasStatistics {
         self.0.read().unwrap().last_added_value()
     }
 
+    pub fn median_and_percentile(&self) -> (u64, u64) {
+        let inner = self.0.read().unwrap();
+        (inner.median(), inner.percentile())
+    }
+
+    /// The read lock is only held while copying the window, so callers never stall `add_samples`
+    /// for longer than that.
+    pub fn recent_samples(&self) -> Vec<(u64, U256)> {
+        self.0.read().unwrap().samples_with_blocks()
+    }
+
     pub fn add_samples(&self, fees: &[u64]) {
         self.0.write().unwrap().add_samples(fees)
     }
@@ -1,8 +1,10 @@
 use super::{GasAdjuster, GasStatisticsInner};
+use crate::l1_gas_price::{FeeParams, L1GasPriceProvider, L1TxParamsProvider};
 use std::collections::VecDeque;
 use std::sync::Arc;
 use zksync_config::GasAdjusterConfig;
 use zksync_eth_client::clients::mock::MockEthereum;
+use zksync_types::U256;
 
 /// Check that we compute the median correctly
 #[test]
@@ -43,6 +45,8 @@ async fn kept_updated() {
             internal_enforced_l1_gas_price: None,
             poll_period: 5,
             max_l1_gas_price: None,
+            max_l1_gas_price_for_estimation: None,
+            use_legacy_gas_price: false,
         },
     )
     .await
@@ -57,3 +61,112 @@ async fn kept_updated() {
     assert_eq!(adjuster.statistics.0.read().unwrap().samples.len(), 5);
     assert_eq!(adjuster.statistics.0.read().unwrap().median(), 7);
 }
+
+/// Check that the exposed samples window is aligned with L1 blocks and the smoothed values
+#[tokio::test]
+async fn recent_samples_window() {
+    let eth_client =
+        Arc::new(MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9]));
+    eth_client.advance_block_number(5);
+
+    let adjuster = GasAdjuster::new(
+        Arc::clone(&eth_client),
+        GasAdjusterConfig {
+            default_priority_fee_per_gas: 5,
+            max_base_fee_samples: 5,
+            pricing_formula_parameter_a: 1.5,
+            pricing_formula_parameter_b: 1.0005,
+            internal_l1_pricing_multiplier: 0.8,
+            internal_enforced_l1_gas_price: None,
+            poll_period: 5,
+            max_l1_gas_price: None,
+            max_l1_gas_price_for_estimation: None,
+            use_legacy_gas_price: false,
+        },
+    )
+    .await
+    .unwrap();
+
+    let expected: Vec<(u64, U256)> = [(0, 0), (1, 4), (2, 6), (3, 8), (4, 7)]
+        .iter()
+        .map(|&(block, fee): &(u64, u64)| (block, U256::from(fee)))
+        .collect();
+    assert_eq!(adjuster.recent_samples(), expected);
+
+    eth_client.advance_block_number(3);
+    adjuster.keep_updated().await.unwrap();
+
+    let expected: Vec<(u64, U256)> = [(3, 8), (4, 7), (5, 5), (6, 5), (7, 8)]
+        .iter()
+        .map(|&(block, fee): &(u64, u64)| (block, U256::from(fee)))
+        .collect();
+    assert_eq!(adjuster.recent_samples(), expected);
+    // sorted: 5 5 7 8 8
+    assert_eq!(adjuster.statistics.median_and_percentile(), (7, 8));
+}
+
+/// Check that fee params are produced according to the configured fee model
+#[tokio::test]
+async fn fee_params() {
+    let config = GasAdjusterConfig {
+        default_priority_fee_per_gas: 5,
+        max_base_fee_samples: 5,
+        pricing_formula_parameter_a: 1.5,
+        pricing_formula_parameter_b: 1.0005,
+        internal_l1_pricing_multiplier: 0.8,
+        internal_enforced_l1_gas_price: None,
+        poll_period: 5,
+        max_l1_gas_price: None,
+        max_l1_gas_price_for_estimation: None,
+        use_legacy_gas_price: false,
+    };
+    let eth_client = MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5]);
+    eth_client.advance_block_number(5);
+    let adjuster = GasAdjuster::new(eth_client, config).await.unwrap();
+    assert_eq!(
+        adjuster.get_fee_params(100, 10),
+        FeeParams::Eip1559 {
+            max_fee: 110.into(),
+            max_priority: 10.into(),
+        }
+    );
+
+    let eth_client = MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5]);
+    eth_client.advance_block_number(5);
+    let legacy_config = GasAdjusterConfig {
+        use_legacy_gas_price: true,
+        ..config
+    };
+    let adjuster = GasAdjuster::new(eth_client, legacy_config).await.unwrap();
+    assert_eq!(
+        adjuster.get_fee_params(100, 10),
+        FeeParams::Legacy(110.into())
+    );
+}
+
+/// Check that the spiked gas price is capped for fee estimation
+#[tokio::test]
+async fn gas_price_capped_for_estimation() {
+    let eth_client = MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5]);
+    eth_client.advance_block_number(5);
+    let adjuster = GasAdjuster::new(
+        eth_client,
+        GasAdjusterConfig {
+            default_priority_fee_per_gas: 5,
+            max_base_fee_samples: 5,
+            pricing_formula_parameter_a: 1.5,
+            pricing_formula_parameter_b: 1.0005,
+            internal_l1_pricing_multiplier: 0.8,
+            internal_enforced_l1_gas_price: Some(1_000_000),
+            poll_period: 5,
+            max_l1_gas_price: None,
+            max_l1_gas_price_for_estimation: None,
+            use_legacy_gas_price: false,
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(adjuster.bounded_gas_price_with_cap(1_000), 1_000);
+    assert_eq!(adjuster.bounded_gas_price_with_cap(10_000_000), 1_000_000);
+}
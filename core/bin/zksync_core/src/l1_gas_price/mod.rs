@@ -1,5 +1,7 @@
 //! This module determines the fees to pay in txs containing blocks submitted to the L1.
 
+use zksync_types::U256;
+
 pub use gas_adjuster::bounded_gas_adjuster::BoundedGasAdjuster;
 pub use gas_adjuster::GasAdjuster;
 pub use main_node_fetcher::MainNodeGasPriceFetcher;
@@ -9,12 +11,33 @@ mod gas_adjuster;
 mod main_node_fetcher;
 pub mod singleton;
 
+/// Fee parameters of an L1 transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeParams {
+    /// Legacy (pre-EIP1559) transaction with a single gas price.
+    Legacy(U256),
+    /// EIP1559 transaction.
+    Eip1559 { max_fee: U256, max_priority: U256 },
+}
+
 /// Abstraction that provides information about the L1 gas price currently
 /// observed by the application.
 pub trait L1GasPriceProvider {
     /// Returns a best guess of a realistic value for the L1 gas price.
     /// Return value is in wei.
     fn estimate_effective_gas_price(&self) -> u64;
+
+    /// Same as `estimate_effective_gas_price()`, but clamps the returned value to `cap` (in wei).
+    /// This is meant to be used only for fee estimation, so that L1 gas price spikes don't price out
+    /// all the users; it doesn't affect the fees the eth sender actually bids for L1 transactions.
+    fn bounded_gas_price_with_cap(&self, cap: u64) -> u64 {
+        let gas_price = self.estimate_effective_gas_price();
+        if gas_price > cap {
+            metrics::increment_counter!("server.gas_adjuster.capped");
+            return cap;
+        }
+        gas_price
+    }
 }
 
 /// Extended version of `L1GasPriceProvider` that can provide parameters
@@ -28,6 +51,14 @@ pub trait L1TxParamsProvider: L1GasPriceProvider {
     /// Returns the recommended `max_priority_fee_per_gas` value (EIP1559).
     fn get_priority_fee(&self) -> u64;
 
+    /// Returns the recommended `max_fee_per_blob_gas` value (EIP4844), or `None` if the blob base fee
+    /// couldn't be fetched from L1.
+    fn get_blob_base_fee(&self, time_in_mempool: u32) -> Option<U256>;
+
     /// Returns a lower bound for the `base_fee` value for the next L1 block.
     fn get_next_block_minimal_base_fee(&self) -> u64;
+
+    /// Packs the provided base and priority fees into parameters for an L1 transaction,
+    /// according to the fee model used by the provider.
+    fn get_fee_params(&self, base_fee_per_gas: u64, priority_fee_per_gas: u64) -> FeeParams;
 }
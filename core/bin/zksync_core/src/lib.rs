@@ -1,30 +1,43 @@
 #![allow(clippy::upper_case_acronyms, clippy::derive_partial_eq_without_eq)]
 
-use std::{str::FromStr, sync::Arc, time::Instant};
+use std::{
+    collections::BTreeMap,
+    env,
+    future::Future,
+    iter,
+    panic::AssertUnwindSafe,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use anyhow::Context as _;
 use api_server::execution_sandbox::VmConcurrencyLimiter;
-use futures::channel::oneshot;
+use futures::{future, FutureExt};
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
 use tokio::{sync::watch, task::JoinHandle};
 
-use house_keeper::periodic_job::PeriodicJob;
-use prometheus_exporter::run_prometheus_exporter;
+use house_keeper::periodic_job::{PeriodicJob, PeriodicJobHealthCheck};
+use prometheus_exporter::run_prometheus_exporter_from_config;
 use zksync_circuit_breaker::{
     facet_selectors::FacetSelectorsChecker, l1_txs::FailedL1TransactionChecker, vks::VksChecker,
-    CircuitBreaker, CircuitBreakerChecker, CircuitBreakerError,
+    CircuitBreaker, CircuitBreakerChecker,
 };
 use zksync_config::configs::{
-    api::{HealthCheckConfig, Web3JsonRpcConfig},
+    api::{ExplorerApiConfig, HealthCheckConfig, Web3JsonRpcConfig},
     chain::{
         self, CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
         StateKeeperConfig,
     },
     house_keeper::HouseKeeperConfig,
-    FriProverConfig, FriWitnessGeneratorConfig, PrometheusConfig, ProverGroupConfig,
+    FeePolicyConfig, FriProverConfig, FriWitnessGeneratorConfig, PrometheusConfig,
+    PrometheusExporterMode, ProverGroupConfig, SnapshotExporterConfig, SupervisorConfig,
     WitnessGeneratorConfig,
 };
 use zksync_config::{
-    ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, FetcherConfig,
-    ProverConfigs,
+    ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
+    FetcherConfig, GasAdjusterConfig, ObjectStoreConfig, ProverConfigs,
 };
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{
@@ -33,12 +46,13 @@ use zksync_dal::{
 use zksync_eth_client::clients::http::QueryClient;
 use zksync_eth_client::{clients::http::PKSigningClient, BoundEthInterface};
 use zksync_health_check::CheckHealth;
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{BlobKind, ObjectStoreError, ObjectStoreFactory, ObjectStoreRouter};
 use zksync_queued_job_processor::JobProcessor;
 use zksync_state::FactoryDepsCache;
 use zksync_types::{proofs::AggregationRound, L2ChainId, PackedEthSignature, H160};
+use zksync_utils::panic_extractor::extract_panic_message;
 
-use crate::api_server::healthcheck::HealthCheckHandle;
+use crate::api_server::healthcheck::{HealthCheckHandle, NodeInfo};
 use crate::api_server::tx_sender::TxSenderConfig;
 use crate::api_server::web3::api_health_check::ApiHealthCheck;
 use crate::api_server::web3::state::InternalApiConfig;
@@ -47,6 +61,9 @@ use crate::api_server::{
     tx_sender::{TxSender, TxSenderBuilder},
 };
 use crate::eth_sender::{Aggregator, EthTxManager};
+use crate::fee_ticker::policy::fee_policy_from_config;
+use crate::genesis::GenesisHealthCheck;
+use crate::house_keeper::call_traces_pruner::CallTracePruner;
 use crate::house_keeper::fri_prover_job_retry_manager::FriProverJobRetryManager;
 use crate::house_keeper::fri_prover_queue_monitor::FriProverStatsReporter;
 use crate::house_keeper::fri_scheduler_circuit_queuer::SchedulerCircuitQueuer;
@@ -62,16 +79,20 @@ use crate::house_keeper::{
 };
 use crate::l1_gas_price::{GasAdjusterSingleton, L1GasPriceProvider};
 use crate::metadata_calculator::{
-    MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig, TreeHealthCheck,
+    MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig,
+    TreeConsistencyHealthCheck, TreeHealthCheck,
 };
+use crate::shutdown::{run_circuit_breaker_checker, ShutdownReason, ShutdownReasonSender};
+use crate::snapshot_exporter::SnapshotExporter;
 use crate::state_keeper::{create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockSealer};
+use crate::supervisor::{spawn_supervised, RestartPolicy};
 use crate::witness_generator::{
     basic_circuits::BasicWitnessGenerator, leaf_aggregation::LeafAggregationWitnessGenerator,
     node_aggregation::NodeAggregationWitnessGenerator, scheduler::SchedulerWitnessGenerator,
 };
 use crate::{
     api_server::{explorer, web3},
-    data_fetchers::run_data_fetchers,
+    data_fetchers::{health_check::DataFetcherHealthCheck, run_data_fetchers},
     eth_sender::EthTxAggregator,
     eth_watch::start_eth_watch,
 };
@@ -89,8 +110,14 @@ pub mod house_keeper;
 pub mod l1_gas_price;
 pub mod metadata_calculator;
 pub mod reorg_detector;
+pub mod shutdown;
+pub mod snapshot_exporter;
+pub mod snapshot_importer;
 pub mod state_keeper;
+pub mod supervisor;
 pub mod sync_layer;
+#[cfg(test)]
+pub(crate) mod testonly;
 pub mod witness_generator;
 
 /// Inserts the initial information about zkSync tokens into the database.
@@ -120,22 +147,70 @@ pub async fn is_genesis_needed() -> bool {
     storage.blocks_dal().is_genesis_needed().await
 }
 
-/// Sets up an interrupt handler and returns a future that resolves once an interrupt signal
-/// is received.
-pub fn setup_sigint_handler() -> oneshot::Receiver<()> {
-    let (sigint_sender, sigint_receiver) = oneshot::channel();
-    let mut sigint_sender = Some(sigint_sender);
+/// Sets up an interrupt handler reporting [`ShutdownReason::StopSignal`] via `shutdown_sender`
+/// once an interrupt signal is received.
+pub fn setup_sigint_handler(shutdown_sender: ShutdownReasonSender) {
+    let mut shutdown_sender = Some(shutdown_sender);
     ctrlc::set_handler(move || {
-        if let Some(sigint_sender) = sigint_sender.take() {
-            sigint_sender.send(()).ok();
-            // ^ The send fails if `sigint_receiver` is dropped. We're OK with this,
-            // since at this point the node should be stopping anyway, or is not interested
-            // in listening to interrupt signals.
+        if let Some(shutdown_sender) = shutdown_sender.take() {
+            shutdown_sender.send(ShutdownReason::StopSignal);
         }
     })
     .expect("Error setting Ctrl+C handler");
+}
+
+/// Spawns a Tokio task with the specified name, so that the task can be attributed in runtime
+/// introspection tools such as `tokio-console`. Naming tasks requires the `tokio-console` feature
+/// and the `tokio_unstable` compiler flag; otherwise, the task is spawned without a name.
+pub fn spawn_named<F>(name: &str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(all(tokio_unstable, feature = "tokio-console"))]
+    {
+        tokio::task::Builder::new()
+            .name(name)
+            .spawn(future)
+            .unwrap_or_else(|err| panic!("Failed spawning task `{name}`: {err}"))
+    }
+    #[cfg(not(all(tokio_unstable, feature = "tokio-console")))]
+    {
+        let _ = name;
+        tokio::spawn(future)
+    }
+}
 
-    sigint_receiver
+/// Spawns a component future that is raced against the stop signal. Once the signal is received,
+/// the future is dropped even if it's in the middle of an operation (e.g., waiting on a long DB query),
+/// so that shutdown is bounded regardless of how often the component checks `stop_receiver` itself.
+///
+/// The component must be safe to drop at any `.await` point; e.g., it must not leave the persisted
+/// state inconsistent if its DB transaction is rolled back.
+pub fn spawn_cancellable<F>(
+    name: &str,
+    mut stop_receiver: watch::Receiver<bool>,
+    future: F,
+) -> JoinHandle<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    spawn_named(name, async move {
+        let stop_signal = async {
+            while !*stop_receiver.borrow() {
+                if stop_receiver.changed().await.is_err() {
+                    // The stop sender is dropped, so the signal will never be received.
+                    futures::future::pending::<()>().await;
+                }
+            }
+        };
+        tokio::select! {
+            () = future => {}
+            () = stop_signal => {
+                vlog::info!("Stop signal received, cancelling the component");
+            }
+        }
+    })
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -164,11 +239,144 @@ pub enum Component {
     WitnessGenerator(Option<usize>, AggregationRound),
     // Component for housekeeping task such as cleaning blobs from GCS, reporting metrics etc.
     Housekeeper,
+    // Health check aggregating health of components running in other processes.
+    HealthCheckAggregator,
+    // Periodic export of state snapshots to the object store.
+    SnapshotExporter,
 }
 
-#[derive(Debug)]
+impl Component {
+    /// Checks whether this component is an API server.
+    fn is_api(&self) -> bool {
+        matches!(self, Self::HttpApi | Self::WsApi | Self::ExplorerApi)
+    }
+
+    /// Checks whether this component can be relaunched by the supervisor. Only the ETH watcher
+    /// and data fetchers are supported; other components, including critical ones like the state keeper,
+    /// shut down the server if their tasks terminate.
+    fn is_restartable(&self) -> bool {
+        matches!(self, Self::EthWatcher | Self::DataFetcher)
+    }
+
+    /// Checks whether this component connects to the prover DB. Prover DB connection pools are created
+    /// only when launching such components, so that deployments without a prover DB (e.g., API-only ones)
+    /// don't need to configure it.
+    fn uses_prover_db(&self) -> bool {
+        matches!(
+            self,
+            Self::Tree
+                | Self::TreeLightweight
+                | Self::EthTxAggregator
+                | Self::WitnessGenerator(..)
+                | Self::Housekeeper
+        )
+    }
+
+    /// Returns the resource that this component exclusively owns, or `None` if the component
+    /// can share resources with other components. Distinct components owning the same resource
+    /// cannot run in the same process.
+    fn exclusive_resource(&self) -> Option<&'static str> {
+        match self {
+            // The storage layout of the tree is mode-independent, so the tree can be run
+            // in a single mode only.
+            Self::Tree | Self::TreeLightweight => Some("Merkle tree"),
+            _ => None,
+        }
+    }
+
+    /// Returns targets (i.e., module paths) of logs emitted by this component.
+    fn log_targets(&self) -> &'static [&'static str] {
+        match self {
+            Self::HttpApi | Self::WsApi | Self::ExplorerApi => &["zksync_core::api_server"],
+            Self::Tree | Self::TreeLightweight | Self::TreeBackup => {
+                &["zksync_core::metadata_calculator", "zksync_merkle_tree"]
+            }
+            Self::EthWatcher => &["zksync_core::eth_watch"],
+            Self::EthTxAggregator | Self::EthTxManager => &["zksync_core::eth_sender"],
+            Self::DataFetcher => &["zksync_core::data_fetchers"],
+            Self::StateKeeper => &["zksync_core::state_keeper", "zksync_mempool"],
+            Self::WitnessGenerator(..) => &["zksync_core::witness_generator"],
+            Self::Housekeeper => &["zksync_core::house_keeper"],
+            Self::HealthCheckAggregator => &["zksync_core::api_server::healthcheck"],
+            Self::SnapshotExporter => &["zksync_core::snapshot_exporter"],
+        }
+    }
+
+    /// Returns the token parsed by [`Components::from_str()`] into this component only, or `None`
+    /// if the component cannot be expressed as a token.
+    fn token(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::HttpApi => "http_api",
+            Self::WsApi => "ws_api",
+            Self::ExplorerApi => "explorer_api",
+            Self::Tree => "tree",
+            Self::TreeLightweight => "tree_lightweight",
+            Self::TreeBackup => "tree_backup",
+            Self::EthWatcher => "eth_watcher",
+            Self::EthTxAggregator => "eth_tx_aggregator",
+            Self::EthTxManager => "eth_tx_manager",
+            Self::DataFetcher => "data_fetcher",
+            Self::StateKeeper => "state_keeper",
+            Self::Housekeeper => "housekeeper",
+            Self::HealthCheckAggregator => "health_check_aggregator",
+            Self::SnapshotExporter => "snapshot_exporter",
+            Self::WitnessGenerator(None, round) => match round {
+                AggregationRound::BasicCircuits => "basic_witness_generator",
+                AggregationRound::LeafAggregation => "leaf_witness_generator",
+                AggregationRound::NodeAggregation => "node_witness_generator",
+                AggregationRound::Scheduler => "scheduler_witness_generator",
+            },
+            Self::WitnessGenerator(Some(1), round) => match round {
+                AggregationRound::BasicCircuits => "one_shot_basic_witness_generator",
+                AggregationRound::LeafAggregation => "one_shot_leaf_witness_generator",
+                AggregationRound::NodeAggregation => "one_shot_node_witness_generator",
+                AggregationRound::Scheduler => "one_shot_scheduler_witness_generator",
+            },
+            Self::WitnessGenerator(Some(_), _) => return None,
+        })
+    }
+}
+
+/// Serializes a component as its token, e.g. `"http_api"` or `"one_shot_leaf_witness_generator"`.
+impl Serialize for Component {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let token = self.token().ok_or_else(|| {
+            ser::Error::custom(format!("{:?} cannot be represented as a token", self))
+        })?;
+        serializer.serialize_str(token)
+    }
+}
+
+/// Deserializes a component from a token describing a single component.
+impl<'de> Deserialize<'de> for Component {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let token = String::deserialize(deserializer)?;
+        match Components::from_str(&token).map_err(de::Error::custom)?.0[..] {
+            [component] => Ok(component),
+            _ => Err(de::Error::custom(format!(
+                "{} describes multiple components",
+                token
+            ))),
+        }
+    }
+}
+
+/// Set of components. Serialized as a list of single-component tokens; deserialized from a list of
+/// any tokens accepted by [`Components::from_str()`], so that e.g. `["api", "eth"]` is supported.
+#[derive(Debug, PartialEq, Serialize)]
 pub struct Components(pub Vec<Component>);
 
+impl<'de> Deserialize<'de> for Components {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tokens = Vec::<String>::deserialize(deserializer)?;
+        let mut components = vec![];
+        for token in tokens {
+            components.extend(Components::from_str(&token).map_err(de::Error::custom)?.0);
+        }
+        Ok(Components(components))
+    }
+}
+
 impl FromStr for Components {
     type Err = String;
 
@@ -190,6 +398,8 @@ impl FromStr for Components {
             "data_fetcher" => Ok(Components(vec![Component::DataFetcher])),
             "state_keeper" => Ok(Components(vec![Component::StateKeeper])),
             "housekeeper" => Ok(Components(vec![Component::Housekeeper])),
+            "health_check_aggregator" => Ok(Components(vec![Component::HealthCheckAggregator])),
+            "snapshot_exporter" => Ok(Components(vec![Component::SnapshotExporter])),
             "witness_generator" => Ok(Components(vec![
                 Component::WitnessGenerator(None, AggregationRound::BasicCircuits),
                 Component::WitnessGenerator(None, AggregationRound::LeafAggregation),
@@ -202,6 +412,22 @@ impl FromStr for Components {
                 Component::WitnessGenerator(Some(1), AggregationRound::NodeAggregation),
                 Component::WitnessGenerator(Some(1), AggregationRound::Scheduler),
             ])),
+            "basic_witness_generator" => Ok(Components(vec![Component::WitnessGenerator(
+                None,
+                AggregationRound::BasicCircuits,
+            )])),
+            "leaf_witness_generator" => Ok(Components(vec![Component::WitnessGenerator(
+                None,
+                AggregationRound::LeafAggregation,
+            )])),
+            "node_witness_generator" => Ok(Components(vec![Component::WitnessGenerator(
+                None,
+                AggregationRound::NodeAggregation,
+            )])),
+            "scheduler_witness_generator" => Ok(Components(vec![Component::WitnessGenerator(
+                None,
+                AggregationRound::Scheduler,
+            )])),
             "one_shot_basic_witness_generator" => {
                 Ok(Components(vec![Component::WitnessGenerator(
                     Some(1),
@@ -235,18 +461,152 @@ impl FromStr for Components {
     }
 }
 
+/// Returns components listed in the supervisor `config` that can be relaunched by the supervisor.
+/// Invalid tokens and components not supported by the supervisor are logged and ignored.
+fn restartable_components(config: &SupervisorConfig) -> Vec<Component> {
+    let listed = config
+        .restartable_components
+        .iter()
+        .filter(|token| !token.is_empty())
+        .filter_map(|token| match Components::from_str(token) {
+            Ok(components) => Some(components.0),
+            Err(err) => {
+                vlog::warn!("Ignoring restartable component: {err}");
+                None
+            }
+        })
+        .flatten();
+    let (supported, unsupported): (Vec<_>, Vec<_>) =
+        listed.partition(|component| component.is_restartable());
+    if !unsupported.is_empty() {
+        vlog::warn!(
+            "Components {unsupported:?} cannot be relaunched by the supervisor and are not supervised"
+        );
+    }
+    supported
+}
+
+/// Returns the supervisor policy for `component`, or `None` if the component shouldn't be relaunched
+/// after an unexpected termination.
+fn restart_policy(
+    config: &SupervisorConfig,
+    restartable: &[Component],
+    component: Component,
+) -> Option<RestartPolicy> {
+    restartable.contains(&component).then(|| RestartPolicy {
+        max_restarts: config.max_restarts(),
+        window: config.restart_window(),
+    })
+}
+
+/// Defines how [`initialize_components()`] reacts to a component failing to initialize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentFailurePolicy {
+    /// Return the first initialization error immediately.
+    FailFast,
+    /// Start every component that can be started, and report the failed components
+    /// alongside the handles of the started ones.
+    BestEffort,
+}
+
+/// Component that has failed to initialize under [`ComponentFailurePolicy::BestEffort`].
+#[derive(Debug)]
+pub struct ComponentFailure {
+    pub component: Component,
+    pub error: anyhow::Error,
+}
+
+/// Applies [`ComponentFailurePolicy`] to component initialization results.
+#[derive(Debug)]
+struct ComponentFailures {
+    policy: ComponentFailurePolicy,
+    failures: Vec<ComponentFailure>,
+}
+
+impl ComponentFailures {
+    fn new(policy: ComponentFailurePolicy) -> Self {
+        Self {
+            policy,
+            failures: vec![],
+        }
+    }
+
+    /// Records the initialization result for `component`. Returns an error if the component
+    /// has failed and the policy requires failing fast.
+    fn record(&mut self, component: Component, result: anyhow::Result<()>) -> anyhow::Result<()> {
+        let Err(err) = result else {
+            return Ok(());
+        };
+        let err = err.context(format!("failed initializing component {component:?}"));
+        match self.policy {
+            ComponentFailurePolicy::FailFast => Err(err),
+            ComponentFailurePolicy::BestEffort => {
+                vlog::error!("{err:#}");
+                self.failures.push(ComponentFailure {
+                    component,
+                    error: err,
+                });
+                Ok(())
+            }
+        }
+    }
+
+    /// Runs initialization of `component` and records its result.
+    async fn initialize(
+        &mut self,
+        component: Component,
+        init: impl Future<Output = anyhow::Result<()>>,
+    ) -> anyhow::Result<()> {
+        let result = catch_init_panic(init).await;
+        self.record(component, result)
+    }
+}
+
+/// Runs component initialization, converting a panic into an error. Initialization code mostly panics
+/// on failures (e.g., if a DB pool or an L1 client cannot be created), so this allows to handle
+/// all failures according to [`ComponentFailurePolicy`].
+async fn catch_init_panic<T>(init: impl Future<Output = anyhow::Result<T>>) -> anyhow::Result<T> {
+    AssertUnwindSafe(init)
+        .catch_unwind()
+        .await
+        .unwrap_or_else(|panic| {
+            let message = extract_panic_message(panic);
+            Err(anyhow::anyhow!("panicked during initialization: {message}"))
+        })
+}
+
+/// Initializes and starts the specified components. Configuration is validated for all components
+/// upfront, so configuration errors are returned regardless of `failure_policy`; the same applies
+/// to resources shared by all components (e.g., the main DB pools). Under
+/// [`ComponentFailurePolicy::BestEffort`], components that have failed to initialize (including
+/// by panicking) are returned alongside the handles of the started tasks. If `prometheus_exporter_mode`
+/// is set, it overrides the configured Prometheus exporter mode.
 pub async fn initialize_components(
     components: Vec<Component>,
-    use_prometheus_pushgateway: bool,
+    prometheus_exporter_mode: Option<PrometheusExporterMode>,
+    failure_policy: ComponentFailurePolicy,
+    shutdown_sender: ShutdownReasonSender,
 ) -> anyhow::Result<(
     Vec<JoinHandle<()>>,
     watch::Sender<bool>,
-    oneshot::Receiver<CircuitBreakerError>,
-    HealthCheckHandle,
+    Option<HealthCheckHandle>,
+    Vec<ComponentFailure>,
 )> {
+    let log_overrides = std::env::var("MISC_LOG_OVERRIDES").unwrap_or_default();
+    if !log_overrides.is_empty() {
+        let directives = log_override_directives(&log_overrides)?;
+        vlog::set_log_directives(directives.iter().map(String::as_str))
+            .context("invalid log level override")?;
+        vlog::info!("Applied log level overrides: {log_overrides}");
+    }
+    let mut failures = ComponentFailures::new(failure_policy);
+    let components = dedup_components(components, &mut failures)?;
     vlog::info!("Starting the components: {components:?}");
+    if !components.iter().any(Component::uses_prover_db) {
+        vlog::info!("None of the components uses the prover DB; it will not be connected to");
+    }
+    preflight_validate(&components)?;
     let connection_pool = ConnectionPool::new(None, DbVariant::Master).await;
-    let prover_connection_pool = ConnectionPool::new(None, DbVariant::Prover).await;
     let replica_connection_pool = ConnectionPool::new(None, DbVariant::Replica).await;
     let mut healthchecks: Vec<Box<dyn CheckHealth>> = Vec::new();
     let contracts_config = ContractsConfig::from_env();
@@ -270,235 +630,438 @@ pub async fn initialize_components(
     let mut gas_adjuster = GasAdjusterSingleton::new();
 
     let (stop_sender, stop_receiver) = watch::channel(false);
-    let (cb_sender, cb_receiver) = oneshot::channel();
     // Prometheus exporter and circuit breaker checker should run for every component configuration.
-    let prom_config = PrometheusConfig::from_env();
-    let mut task_futures: Vec<JoinHandle<()>> = vec![
-        run_prometheus_exporter(
-            prom_config.listener_port,
-            use_prometheus_pushgateway.then(|| {
-                (
-                    prom_config.pushgateway_url.clone(),
-                    prom_config.push_interval(),
-                )
-            }),
+    let mut prom_config = PrometheusConfig::from_env();
+    if let Some(exporter_mode) = prometheus_exporter_mode {
+        prom_config.exporter_mode = exporter_mode;
+    }
+    let mut task_futures: Vec<JoinHandle<()>> = run_prometheus_exporter_from_config(&prom_config);
+    task_futures.push(spawn_cancellable(
+        "circuit_breaker_checker",
+        stop_receiver.clone(),
+        run_circuit_breaker_checker(
+            circuit_breaker_checker,
+            stop_receiver.clone(),
+            shutdown_sender,
         ),
-        tokio::spawn(circuit_breaker_checker.run(cb_sender, stop_receiver.clone())),
-    ];
+    ));
 
-    let factory_deps_cache = FactoryDepsCache::new(
+    let supervisor_config = SupervisorConfig::from_env();
+    let restartable = restartable_components(&supervisor_config);
+    // A misconfigured object store is only fatal for components that cannot work without it;
+    // e.g., the house keeper just doesn't start the blob cleaner.
+    let store_router = ObjectStoreFactory::try_from_env_multi();
+    // Witness inputs are produced by the Merkle tree, consumed by witness generators and cleaned up
+    // by the house keeper, so all these components must use the same store.
+    let witness_store_factory = store_router
+        .as_ref()
+        .map(|router| router.factory(BlobKind::WitnessInputs));
+    let web3_config = Web3JsonRpcConfig::from_env();
+    // Clones of the cache share the underlying storage, so all API servers use a single instance.
+    let mut factory_deps_cache = FactoryDepsCache::new(
         "factory_deps_cache",
-        Web3JsonRpcConfig::from_env().factory_deps_cache_size_mb(),
+        web3_config.factory_deps_cache_size_mb(),
     );
+    if web3_config.factory_deps_cache_object_store_tier() {
+        let store_factory = require_object_store(store_router.as_ref()).default_factory();
+        let object_store = store_factory.create_store().await;
+        factory_deps_cache = factory_deps_cache.with_object_store_tier(object_store.into());
+    }
 
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
         || components.contains(&Component::ExplorerApi)
     {
-        let api_config = ApiConfig::from_env();
+        // API servers cannot serve requests until genesis is performed.
+        healthchecks.push(Box::new(GenesisHealthCheck::new(connection_pool.clone())));
+        task_futures.push(spawn_named(
+            "factory_deps_cache_size_reporter",
+            report_factory_deps_cache_size(factory_deps_cache.clone(), stop_receiver.clone()),
+        ));
+        if web3_config.factory_deps_cache_persist_keys() {
+            let dump_path = PathBuf::from(web3_config.factory_deps_cache_dump_path());
+            let mut storage = connection_pool.access_storage_tagged("api").await;
+            match factory_deps_cache
+                .prewarm_from_file(&dump_path, &mut storage)
+                .await
+            {
+                Ok(count) => vlog::info!("Prewarmed factory deps cache with {count} bytecodes"),
+                Err(err) => vlog::warn!("Failed prewarming factory deps cache: {err}"),
+            }
+            drop(storage);
+            task_futures.push(spawn_named(
+                "factory_deps_cache_dumper",
+                dump_factory_deps_cache_on_stop(
+                    factory_deps_cache.clone(),
+                    dump_path,
+                    stop_receiver.clone(),
+                ),
+            ));
+        }
+        let mut api_config = ApiConfig::from_env();
+        let with_http_api = components.contains(&Component::HttpApi);
+        let with_ws_api = components.contains(&Component::WsApi);
+        api_config
+            .web3_json_rpc
+            .distribute_api_threads(with_http_api, with_ws_api);
+        if with_http_api && with_ws_api {
+            warn_on_api_threads_oversubscription(&api_config.web3_json_rpc);
+        }
+
         let state_keeper_config = StateKeeperConfig::from_env();
         let network_config = NetworkConfig::from_env();
-        let tx_sender_config = TxSenderConfig::new(&state_keeper_config, &api_config.web3_json_rpc);
+        let tx_sender_config = TxSenderConfig::new(
+            &state_keeper_config,
+            &api_config.web3_json_rpc,
+            &GasAdjusterConfig::from_env(),
+        );
         let internal_api_config = InternalApiConfig::new(
             &network_config,
             &api_config.web3_json_rpc,
             &contracts_config,
+            &MempoolConfig::from_env(),
         );
-        if components.contains(&Component::HttpApi) {
-            let started_at = Instant::now();
-            vlog::info!("initializing HTTP API");
-            let bounded_gas_adjuster = gas_adjuster.get_or_init_bounded().await;
-            let (futures, health_check) = run_http_api(
-                &tx_sender_config,
-                &state_keeper_config,
-                &internal_api_config,
-                &api_config,
-                connection_pool.clone(),
-                replica_connection_pool.clone(),
-                stop_receiver.clone(),
-                bounded_gas_adjuster.clone(),
-                state_keeper_config.save_call_traces,
-                factory_deps_cache.clone(),
-            )
-            .await;
-            task_futures.extend(futures);
-            healthchecks.push(Box::new(health_check));
-            vlog::info!("initialized HTTP API in {:?}", started_at.elapsed());
-            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "http_api");
+        if with_http_api {
+            let init = async {
+                let started_at = Instant::now();
+                vlog::info!("initializing HTTP API");
+                let bounded_gas_adjuster = gas_adjuster.get_or_init_bounded().await;
+                let (futures, health_check) = run_http_api(
+                    &tx_sender_config,
+                    &state_keeper_config,
+                    &internal_api_config,
+                    &api_config,
+                    connection_pool.clone(),
+                    replica_connection_pool.clone(),
+                    stop_receiver.clone(),
+                    bounded_gas_adjuster.clone(),
+                    state_keeper_config.save_call_traces,
+                    factory_deps_cache.clone(),
+                )
+                .await;
+                task_futures.extend(futures);
+                healthchecks.push(Box::new(health_check));
+                vlog::info!("initialized HTTP API in {:?}", started_at.elapsed());
+                metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "http_api");
+                Ok(())
+            };
+            failures.initialize(Component::HttpApi, init).await?;
         }
 
-        if components.contains(&Component::WsApi) {
-            let started_at = Instant::now();
-            vlog::info!("initializing WS API");
-            let bounded_gas_adjuster = gas_adjuster.get_or_init_bounded().await;
-            let (futures, health_check) = run_ws_api(
-                &tx_sender_config,
-                &state_keeper_config,
-                &internal_api_config,
-                &api_config,
-                bounded_gas_adjuster.clone(),
-                connection_pool.clone(),
-                replica_connection_pool.clone(),
-                stop_receiver.clone(),
-                factory_deps_cache.clone(),
-            )
-            .await;
-            task_futures.extend(futures);
-            healthchecks.push(Box::new(health_check));
-            vlog::info!("initialized WS API in {:?}", started_at.elapsed());
-            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "ws_api");
+        if with_ws_api {
+            let init = async {
+                let started_at = Instant::now();
+                vlog::info!("initializing WS API");
+                let bounded_gas_adjuster = gas_adjuster.get_or_init_bounded().await;
+                let (futures, health_check) = run_ws_api(
+                    &tx_sender_config,
+                    &state_keeper_config,
+                    &internal_api_config,
+                    &api_config,
+                    bounded_gas_adjuster.clone(),
+                    connection_pool.clone(),
+                    replica_connection_pool.clone(),
+                    stop_receiver.clone(),
+                    factory_deps_cache.clone(),
+                )
+                .await;
+                task_futures.extend(futures);
+                healthchecks.push(Box::new(health_check));
+                vlog::info!("initialized WS API in {:?}", started_at.elapsed());
+                metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "ws_api");
+                Ok(())
+            };
+            failures.initialize(Component::WsApi, init).await?;
         }
 
         if components.contains(&Component::ExplorerApi) {
-            let started_at = Instant::now();
-            vlog::info!("initializing explorer REST API");
-            task_futures.push(explorer::start_server_thread_detached(
-                api_config.explorer.clone(),
-                contracts_config.l2_erc20_bridge_addr,
-                state_keeper_config.fee_account_addr,
-                connection_pool.clone(),
-                replica_connection_pool.clone(),
-                stop_receiver.clone(),
-            ));
-            vlog::info!(
-                "initialized explorer REST API in {:?}",
-                started_at.elapsed()
-            );
-            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "explorer_api");
+            let init = async {
+                let started_at = Instant::now();
+                vlog::info!("initializing explorer REST API");
+                task_futures.push(explorer::start_server_thread_detached(
+                    api_config.explorer.clone(),
+                    contracts_config.l2_erc20_bridge_addr,
+                    state_keeper_config.fee_account_addr,
+                    connection_pool.clone(),
+                    replica_connection_pool.clone(),
+                    stop_receiver.clone(),
+                ));
+                vlog::info!(
+                    "initialized explorer REST API in {:?}",
+                    started_at.elapsed()
+                );
+                metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "explorer_api");
+                Ok(())
+            };
+            failures.initialize(Component::ExplorerApi, init).await?;
         }
     }
 
     if components.contains(&Component::StateKeeper) {
-        let started_at = Instant::now();
-        vlog::info!("initializing State Keeper");
-        let bounded_gas_adjuster = gas_adjuster.get_or_init_bounded().await;
-        add_state_keeper_to_task_futures(
-            &mut task_futures,
-            &contracts_config,
-            StateKeeperConfig::from_env(),
-            &DBConfig::from_env(),
-            &MempoolConfig::from_env(),
-            bounded_gas_adjuster,
-            stop_receiver.clone(),
-        )
-        .await;
-        vlog::info!("initialized State Keeper in {:?}", started_at.elapsed());
-        metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "state_keeper");
+        let init = async {
+            let started_at = Instant::now();
+            vlog::info!("initializing State Keeper");
+            let bounded_gas_adjuster = gas_adjuster.get_or_init_bounded().await;
+            add_state_keeper_to_task_futures(
+                &mut task_futures,
+                &contracts_config,
+                StateKeeperConfig::from_env(),
+                &DBConfig::from_env(),
+                &MempoolConfig::from_env(),
+                bounded_gas_adjuster,
+                stop_receiver.clone(),
+            )
+            .await;
+            vlog::info!("initialized State Keeper in {:?}", started_at.elapsed());
+            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "state_keeper");
+            Ok(())
+        };
+        failures.initialize(Component::StateKeeper, init).await?;
     }
 
     if components.contains(&Component::EthWatcher) {
-        let started_at = Instant::now();
-        vlog::info!("initializing ETH-Watcher");
-        let eth_watch_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
-        task_futures.push(
-            start_eth_watch(
-                eth_watch_pool,
-                query_client.clone(),
-                contracts_config.diamond_proxy_addr,
-                stop_receiver.clone(),
-            )
-            .await,
-        );
-        vlog::info!("initialized ETH-Watcher in {:?}", started_at.elapsed());
-        metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "eth_watcher");
+        let init = async {
+            let started_at = Instant::now();
+            vlog::info!("initializing ETH-Watcher");
+            let eth_watch_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
+            let diamond_proxy_addr = contracts_config.diamond_proxy_addr;
+            let policy = restart_policy(&supervisor_config, &restartable, Component::EthWatcher);
+            let task = match policy {
+                Some(policy) => {
+                    let query_client = query_client.clone();
+                    let stop_receiver = stop_receiver.clone();
+                    spawn_supervised("eth_watcher", policy, stop_receiver.clone(), move || {
+                        start_eth_watch(
+                            eth_watch_pool.clone(),
+                            query_client.clone(),
+                            diamond_proxy_addr,
+                            stop_receiver.clone(),
+                        )
+                        .map(|handle| vec![handle])
+                    })
+                }
+                None => {
+                    start_eth_watch(
+                        eth_watch_pool,
+                        query_client.clone(),
+                        diamond_proxy_addr,
+                        stop_receiver.clone(),
+                    )
+                    .await
+                }
+            };
+            task_futures.push(task);
+            vlog::info!("initialized ETH-Watcher in {:?}", started_at.elapsed());
+            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "eth_watcher");
+            Ok(())
+        };
+        failures.initialize(Component::EthWatcher, init).await?;
     }
 
     if components.contains(&Component::EthTxAggregator) {
-        let started_at = Instant::now();
-        vlog::info!("initializing ETH-TxAggregator");
-        let eth_sender_storage = ConnectionPool::new(Some(1), DbVariant::Master).await;
-        let eth_sender_prover_storage = ConnectionPool::new(Some(1), DbVariant::Prover).await;
-
-        let eth_sender = ETHSenderConfig::from_env();
-        let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
-        let nonce = eth_client.pending_nonce("eth_sender").await.unwrap();
-        let eth_tx_aggregator_actor = EthTxAggregator::new(
-            eth_sender.sender.clone(),
-            Aggregator::new(eth_sender.sender.clone()),
-            contracts_config.validator_timelock_addr,
-            nonce.as_u64(),
-        );
-        task_futures.push(tokio::spawn(eth_tx_aggregator_actor.run(
-            eth_sender_storage.clone(),
-            eth_sender_prover_storage.clone(),
-            eth_client,
-            stop_receiver.clone(),
-        )));
-        vlog::info!("initialized ETH-TxAggregator in {:?}", started_at.elapsed());
-        metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "eth_tx_aggregator");
+        let init = async {
+            let started_at = Instant::now();
+            vlog::info!("initializing ETH-TxAggregator");
+            let eth_sender_storage = ConnectionPool::new(Some(1), DbVariant::Master).await;
+            let eth_sender_prover_storage =
+                prover_connection_pool(Component::EthTxAggregator, 1).await;
+
+            let eth_sender = ETHSenderConfig::from_env();
+            let eth_client =
+                PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
+            let nonce = eth_client
+                .pending_nonce("eth_sender")
+                .await
+                .context("failed getting pending nonce for the operator")?;
+            let eth_tx_aggregator_actor = EthTxAggregator::new(
+                eth_sender.sender.clone(),
+                Aggregator::new(eth_sender.sender.clone()),
+                contracts_config.validator_timelock_addr,
+                nonce.as_u64(),
+            );
+            task_futures.push(spawn_cancellable(
+                "eth_tx_aggregator",
+                stop_receiver.clone(),
+                eth_tx_aggregator_actor.run(
+                    eth_sender_storage.clone(),
+                    eth_sender_prover_storage.clone(),
+                    eth_client,
+                    stop_receiver.clone(),
+                ),
+            ));
+            vlog::info!("initialized ETH-TxAggregator in {:?}", started_at.elapsed());
+            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "eth_tx_aggregator");
+            Ok::<_, anyhow::Error>(())
+        };
+        failures
+            .initialize(Component::EthTxAggregator, init)
+            .await?;
     }
 
     if components.contains(&Component::EthTxManager) {
-        let started_at = Instant::now();
-        vlog::info!("initializing ETH-TxManager");
-        let eth_sender_storage = ConnectionPool::new(Some(1), DbVariant::Master).await;
-        let eth_sender = ETHSenderConfig::from_env();
-        let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
-        let eth_tx_manager_actor = EthTxManager::new(
-            eth_sender.sender,
-            gas_adjuster.get_or_init().await,
-            eth_client,
-        );
-        task_futures.extend([tokio::spawn(
-            eth_tx_manager_actor.run(eth_sender_storage, stop_receiver.clone()),
-        )]);
-        vlog::info!("initialized ETH-TxManager in {:?}", started_at.elapsed());
-        metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "eth_tx_aggregator");
+        let init = async {
+            let started_at = Instant::now();
+            vlog::info!("initializing ETH-TxManager");
+            let eth_sender_storage = ConnectionPool::new(Some(1), DbVariant::Master).await;
+            let eth_sender = ETHSenderConfig::from_env();
+            let eth_client =
+                PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
+            let eth_tx_manager_actor = EthTxManager::new(
+                eth_sender.sender,
+                gas_adjuster.get_or_init().await,
+                eth_client,
+            );
+            task_futures.push(spawn_cancellable(
+                "eth_tx_manager",
+                stop_receiver.clone(),
+                eth_tx_manager_actor.run(eth_sender_storage, stop_receiver.clone()),
+            ));
+            vlog::info!("initialized ETH-TxManager in {:?}", started_at.elapsed());
+            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "eth_tx_aggregator");
+            Ok(())
+        };
+        failures.initialize(Component::EthTxManager, init).await?;
     }
 
     if components.contains(&Component::DataFetcher) {
-        let started_at = Instant::now();
-        let fetcher_config = FetcherConfig::from_env();
-        let eth_network = chain::NetworkConfig::from_env();
-        vlog::info!("initializing data fetchers");
-        task_futures.extend(run_data_fetchers(
-            &fetcher_config,
-            eth_network.network,
-            connection_pool.clone(),
-            stop_receiver.clone(),
-        ));
-        vlog::info!("initialized data fetchers in {:?}", started_at.elapsed());
-        metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "data_fetchers");
+        let init = async {
+            let started_at = Instant::now();
+            let fetcher_config = FetcherConfig::from_env();
+            let eth_network = chain::NetworkConfig::from_env();
+            vlog::info!("initializing data fetchers");
+            let health_check = DataFetcherHealthCheck::new(fetcher_config.max_feed_staleness());
+            match restart_policy(&supervisor_config, &restartable, Component::DataFetcher) {
+                Some(policy) => {
+                    let pool = connection_pool.clone();
+                    let health_check = health_check.clone();
+                    let stop_receiver = stop_receiver.clone();
+                    let supervisor = spawn_supervised(
+                        "data_fetcher",
+                        policy,
+                        stop_receiver.clone(),
+                        move || {
+                            future::ready(run_data_fetchers(
+                                &fetcher_config,
+                                eth_network.network,
+                                pool.clone(),
+                                health_check.clone(),
+                                stop_receiver.clone(),
+                            ))
+                        },
+                    );
+                    task_futures.push(supervisor);
+                }
+                None => task_futures.extend(run_data_fetchers(
+                    &fetcher_config,
+                    eth_network.network,
+                    connection_pool.clone(),
+                    health_check.clone(),
+                    stop_receiver.clone(),
+                )),
+            }
+            healthchecks.push(Box::new(health_check));
+            vlog::info!("initialized data fetchers in {:?}", started_at.elapsed());
+            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "data_fetchers");
+            Ok(())
+        };
+        failures.initialize(Component::DataFetcher, init).await?;
     }
 
-    let store_factory = ObjectStoreFactory::from_env();
     add_trees_to_task_futures(
         &mut task_futures,
         &mut healthchecks,
+        &mut failures,
         &components,
-        &store_factory,
+        witness_store_factory,
         &stop_receiver,
     )
-    .await;
+    .await?;
     add_witness_generator_to_task_futures(
         &mut task_futures,
+        &mut failures,
         &components,
-        &connection_pool,
-        &prover_connection_pool,
-        &store_factory,
+        store_router.as_ref(),
         &stop_receiver,
     )
-    .await;
+    .await?;
 
     if components.contains(&Component::Housekeeper) {
-        add_house_keeper_to_task_futures(&mut task_futures, &store_factory).await;
+        failures
+            .initialize(Component::Housekeeper, async {
+                add_house_keeper_to_task_futures(
+                    &mut task_futures,
+                    &mut healthchecks,
+                    witness_store_factory,
+                )
+                .await;
+                Ok(())
+            })
+            .await?;
     }
 
-    // Run healthcheck server for all components.
-    healthchecks.push(Box::new(ConnectionPoolHealthCheck::new(
-        replica_connection_pool,
-    )));
+    if components.contains(&Component::SnapshotExporter) {
+        let init = async {
+            let started_at = Instant::now();
+            vlog::info!("initializing snapshot exporter");
+            let config = SnapshotExporterConfig::from_env();
+            let pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
+            let store_router = require_object_store(store_router.as_ref());
+            let snapshot_exporter = SnapshotExporter::new(&config, store_router, pool).await;
+            task_futures.push(spawn_cancellable(
+                "snapshot_exporter",
+                stop_receiver.clone(),
+                snapshot_exporter.run(stop_receiver.clone()),
+            ));
+            vlog::info!(
+                "initialized snapshot exporter in {:?}",
+                started_at.elapsed()
+            );
+            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "snapshot_exporter");
+            Ok(())
+        };
+        failures
+            .initialize(Component::SnapshotExporter, init)
+            .await?;
+    }
 
+    // Run healthcheck server for all components.
     let healtcheck_api_config = HealthCheckConfig::from_env();
+    if needs_replica_pool_health_check(&components, healtcheck_api_config.scope_to_components()) {
+        healthchecks.push(Box::new(ConnectionPoolHealthCheck::new(
+            replica_connection_pool,
+        )));
+    }
+    if components.contains(&Component::HealthCheckAggregator) {
+        let remote_urls = healtcheck_api_config.remote_urls();
+        vlog::info!("Aggregating health of remote components: {remote_urls:?}");
+        for url in remote_urls {
+            healthchecks.push(Box::new(healthcheck::HttpRemoteHealthCheck::new(url)));
+        }
+    }
     let health_check_handle =
-        healthcheck::start_server_thread_detached(healtcheck_api_config.bind_addr(), healthchecks);
+        if needs_health_check_server(&components, healtcheck_api_config.scope_to_components()) {
+            Some(healthcheck::start_server_thread_detached(
+                healtcheck_api_config.bind_addr(),
+                healthchecks,
+                node_info(&components),
+                healthcheck::LivenessPolicy::new(healtcheck_api_config.degraded_fails_liveness()),
+            ))
+        } else {
+            vlog::info!(
+                "Health checks are scoped to API servers; not starting the health check server"
+            );
+            None
+        };
 
     if let Some(task) = gas_adjuster.run_if_initialized(stop_receiver.clone()) {
         task_futures.push(task);
     }
-    Ok((task_futures, stop_sender, cb_receiver, health_check_handle))
+    Ok((
+        task_futures,
+        stop_sender,
+        health_check_handle,
+        failures.failures,
+    ))
 }
 
 async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync + 'static>(
@@ -511,6 +1074,7 @@ async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync +
     stop_receiver: watch::Receiver<bool>,
 ) {
     let fair_l2_gas_price = state_keeper_config.fair_l2_gas_price;
+    let shadow_mode = state_keeper_config.shadow_mode();
     let state_keeper_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
     let next_priority_id = state_keeper_pool
         .access_storage()
@@ -518,14 +1082,22 @@ async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync +
         .transactions_dal()
         .next_priority_id()
         .await;
-    let mempool = MempoolGuard::new(next_priority_id, mempool_config.capacity);
+    let mempool = MempoolGuard::new(
+        next_priority_id,
+        mempool_config.capacity,
+        mempool_config.ordering_strategy(),
+    );
 
     let miniblock_sealer_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
     let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
         miniblock_sealer_pool,
         state_keeper_config.miniblock_seal_queue_capacity,
     );
-    task_futures.push(tokio::spawn(miniblock_sealer.run()));
+    if !shadow_mode {
+        // The shadow state keeper doesn't seal miniblocks, so the sealer would exit immediately
+        // once its handle is dropped.
+        task_futures.push(spawn_named("miniblock_sealer", miniblock_sealer.run()));
+    }
 
     let state_keeper = create_state_keeper(
         contracts_config,
@@ -539,46 +1111,359 @@ async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync +
         stop_receiver.clone(),
     )
     .await;
-    task_futures.push(tokio::spawn(state_keeper.run()));
+    task_futures.push(spawn_named("state_keeper", state_keeper.run()));
+    if shadow_mode {
+        // The shadow state keeper doesn't use the mempool, and it must not mutate transactions in Postgres.
+        return;
+    }
 
     let mempool_fetcher_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
     let mempool_fetcher = MempoolFetcher::new(mempool, gas_adjuster, mempool_config);
-    let mempool_fetcher_handle = tokio::spawn(mempool_fetcher.run(
-        mempool_fetcher_pool,
-        mempool_config.remove_stuck_txs,
-        mempool_config.stuck_tx_timeout(),
-        fair_l2_gas_price,
-        stop_receiver,
-    ));
+    let mempool_fetcher_handle = spawn_cancellable(
+        "mempool_fetcher",
+        stop_receiver.clone(),
+        mempool_fetcher.run(
+            mempool_fetcher_pool,
+            mempool_config.remove_stuck_txs,
+            mempool_config.stuck_tx_timeout(),
+            fair_l2_gas_price,
+            stop_receiver,
+        ),
+    );
     task_futures.push(mempool_fetcher_handle);
 }
 
+/// Checks whether the replica connection pool should be health-checked. If health checks
+/// are scoped to the running components, the pool is only checked if an API server is running,
+/// since API servers are the only users of the replica pool.
+fn needs_replica_pool_health_check(components: &[Component], scope_to_components: bool) -> bool {
+    !scope_to_components || components.iter().any(Component::is_api)
+}
+
+/// Checks whether the health check server should be started. If health checks are scoped
+/// to the running components, the server is co-located with the API servers, so it's only started
+/// if an API server or the health check aggregator is running.
+fn needs_health_check_server(components: &[Component], scope_to_components: bool) -> bool {
+    !scope_to_components
+        || components
+            .iter()
+            .any(|component| component.is_api() || *component == Component::HealthCheckAggregator)
+}
+
+/// Returns information about the node running the specified components, which is exposed
+/// via the `/info` health check endpoint.
+fn node_info(components: &[Component]) -> NodeInfo {
+    let component_names = components
+        .iter()
+        .map(|component| {
+            component
+                .token()
+                .map_or_else(|| format!("{component:?}"), str::to_owned)
+        })
+        .collect();
+    let node_info = NodeInfo::new(component_names);
+    // If both tree modes are requested, only the full tree is started; see `add_trees_to_task_futures()`.
+    if components.contains(&Component::Tree) {
+        node_info.with_tree_mode("full")
+    } else if components.contains(&Component::TreeLightweight) {
+        node_info.with_tree_mode("lightweight")
+    } else {
+        node_info
+    }
+}
+
+/// Loads a config from the provided environment variables.
+type ConfigLoader = fn(&[(String, String)]) -> anyhow::Result<()>;
+
+/// Returns loaders for the configs used by the specified components, keyed by the config name.
+/// Configs that cannot fail to load (e.g., `DBConfig`, which falls back to defaults) are not included.
+fn required_configs(components: &[Component]) -> BTreeMap<&'static str, ConfigLoader> {
+    let mut configs: BTreeMap<&'static str, ConfigLoader> = BTreeMap::new();
+    // Configs used regardless of the components.
+    configs.insert("ContractsConfig", |vars| {
+        ContractsConfig::try_from_vars(vars)?;
+        Ok(())
+    });
+    configs.insert("ETHClientConfig", |vars| {
+        ETHClientConfig::try_from_vars(vars)?;
+        Ok(())
+    });
+    configs.insert("CircuitBreakerConfig", |vars| {
+        CircuitBreakerConfig::try_from_vars(vars)?;
+        Ok(())
+    });
+    configs.insert("PrometheusConfig", |vars| {
+        PrometheusConfig::try_from_vars(vars)?;
+        Ok(())
+    });
+    configs.insert("ObjectStoreConfig", |vars| {
+        ObjectStoreConfig::try_from_vars(vars)?;
+        Ok(())
+    });
+    configs.insert("Web3JsonRpcConfig", |vars| {
+        Web3JsonRpcConfig::try_from_vars(vars)?;
+        Ok(())
+    });
+    configs.insert("HealthCheckConfig", |vars| {
+        HealthCheckConfig::try_from_vars(vars)?;
+        Ok(())
+    });
+    configs.insert("SupervisorConfig", |vars| {
+        SupervisorConfig::try_from_vars(vars)?;
+        Ok(())
+    });
+
+    for component in components {
+        match component {
+            Component::HttpApi | Component::WsApi | Component::ExplorerApi => {
+                configs.insert("ExplorerApiConfig", |vars| {
+                    ExplorerApiConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+                configs.insert("StateKeeperConfig", |vars| {
+                    StateKeeperConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+                configs.insert("NetworkConfig", |vars| {
+                    NetworkConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+                configs.insert("GasAdjusterConfig", |vars| {
+                    GasAdjusterConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+            }
+            Component::StateKeeper => {
+                configs.insert("StateKeeperConfig", |vars| {
+                    StateKeeperConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+                configs.insert("MempoolConfig", |vars| {
+                    MempoolConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+                configs.insert("GasAdjusterConfig", |vars| {
+                    GasAdjusterConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+            }
+            Component::EthWatcher => {
+                configs.insert("ETHWatchConfig", |vars| {
+                    ETHWatchConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+            }
+            Component::EthTxAggregator | Component::EthTxManager => {
+                configs.insert("ETHSenderConfig", |vars| {
+                    ETHSenderConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+            }
+            Component::DataFetcher => {
+                configs.insert("FetcherConfig", |vars| {
+                    FetcherConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+                configs.insert("NetworkConfig", |vars| {
+                    NetworkConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+            }
+            Component::Tree | Component::TreeLightweight | Component::TreeBackup => {
+                configs.insert("OperationsManagerConfig", |vars| {
+                    OperationsManagerConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+            }
+            Component::WitnessGenerator(..) => {
+                configs.insert("WitnessGeneratorConfig", |vars| {
+                    WitnessGeneratorConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+            }
+            Component::Housekeeper => {
+                configs.insert("HouseKeeperConfig", |vars| {
+                    HouseKeeperConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+                configs.insert("ProverGroupConfig", |vars| {
+                    ProverGroupConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+                configs.insert("ProverConfigs", |vars| {
+                    ProverConfigs::try_from_vars(vars)?;
+                    Ok(())
+                });
+                configs.insert("FriProverConfig", |vars| {
+                    FriProverConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+                configs.insert("FriWitnessGeneratorConfig", |vars| {
+                    FriWitnessGeneratorConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+            }
+            Component::SnapshotExporter => {
+                configs.insert("SnapshotExporterConfig", |vars| {
+                    SnapshotExporterConfig::try_from_vars(vars)?;
+                    Ok(())
+                });
+            }
+            Component::HealthCheckAggregator => {}
+        }
+    }
+    configs
+}
+
+/// Periodically reports the size of the factory deps cache shared by the API servers until the stop signal
+/// is received.
+async fn report_factory_deps_cache_size(
+    cache: FactoryDepsCache,
+    mut stop_receiver: watch::Receiver<bool>,
+) {
+    const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+    while !*stop_receiver.borrow() {
+        cache.report_size();
+        // Both the timeout and the stop signal are handled by the loop condition.
+        tokio::time::timeout(REPORT_INTERVAL, stop_receiver.changed())
+            .await
+            .ok();
+    }
+}
+
+/// Persists hashes of bytecodes in `cache` to `dump_path` once the stop signal is received,
+/// so that the cache can be prewarmed on the next start.
+async fn dump_factory_deps_cache_on_stop(
+    cache: FactoryDepsCache,
+    dump_path: PathBuf,
+    mut stop_receiver: watch::Receiver<bool>,
+) {
+    while !*stop_receiver.borrow() {
+        if stop_receiver.changed().await.is_err() {
+            break;
+        }
+    }
+    match cache.dump_keys(&dump_path) {
+        Ok(count) => vlog::info!(
+            "Persisted {count} factory deps cache keys to {}",
+            dump_path.display()
+        ),
+        Err(err) => vlog::warn!("Failed persisting factory deps cache keys: {err}"),
+    }
+}
+
+/// Converts per-component log level overrides (e.g., `tree=debug,eth_watcher=trace`) into log filter
+/// directives for the modules of the overridden components. Components are specified using
+/// the same tokens as in [`Components::from_str()`], so that e.g. `api=debug` is supported.
+fn log_override_directives(overrides: &str) -> anyhow::Result<Vec<String>> {
+    let mut directives = vec![];
+    for item in overrides.split(',').map(str::trim) {
+        if item.is_empty() {
+            continue;
+        }
+        let (token, level) = item
+            .split_once('=')
+            .with_context(|| format!("log level override `{item}` is not `component=level`"))?;
+        let components = Components::from_str(token.trim()).map_err(anyhow::Error::msg)?;
+        for component in components.0 {
+            for target in component.log_targets() {
+                let directive = format!("{target}={}", level.trim());
+                if !directives.contains(&directive) {
+                    directives.push(directive);
+                }
+            }
+        }
+    }
+    Ok(directives)
+}
+
+/// Removes duplicate components (e.g., `HttpApi` specified twice would try to bind the same port twice),
+/// warning about each removed duplicate. If distinct components contend for the same resource
+/// (e.g., both full and lightweight Merkle trees are requested), the latter component is recorded
+/// as failed in `failures` and is removed as well.
+fn dedup_components(
+    components: Vec<Component>,
+    failures: &mut ComponentFailures,
+) -> anyhow::Result<Vec<Component>> {
+    let mut deduped: Vec<Component> = Vec::with_capacity(components.len());
+    for component in components {
+        if deduped.contains(&component) {
+            vlog::warn!(
+                "Component {component:?} is specified multiple times; ignoring the duplicate"
+            );
+            continue;
+        }
+        if let Some(resource) = component.exclusive_resource() {
+            let contender = deduped
+                .iter()
+                .find(|other| other.exclusive_resource() == Some(resource));
+            if let Some(contender) = contender {
+                let err = anyhow::anyhow!(
+                    "Components {contender:?} and {component:?} cannot be run together \
+                     since both of them use the {resource}"
+                );
+                failures.record(component, Err(err))?;
+                continue;
+            }
+        }
+        deduped.push(component);
+    }
+    Ok(deduped)
+}
+
+/// Eagerly loads all configs required by the specified components, so that a misconfiguration
+/// is reported before any component is started rather than once the affected component starts.
+/// The returned error lists all configs that failed to load.
+pub fn preflight_validate(components: &[Component]) -> anyhow::Result<()> {
+    let vars: Vec<_> = env::vars().collect();
+    preflight_validate_vars(components, &vars)
+}
+
+fn preflight_validate_vars(
+    components: &[Component],
+    vars: &[(String, String)],
+) -> anyhow::Result<()> {
+    let errors: Vec<_> = required_configs(components)
+        .into_iter()
+        .filter_map(|(name, load)| {
+            let err = load(vars).err()?;
+            Some(format!("{name}: {err}"))
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        anyhow::bail!("Invalid configuration:\n{}", errors.join("\n"))
+    }
+}
+
 async fn add_trees_to_task_futures(
     task_futures: &mut Vec<JoinHandle<()>>,
     healthchecks: &mut Vec<Box<dyn CheckHealth>>,
+    failures: &mut ComponentFailures,
     components: &[Component],
-    store_factory: &ObjectStoreFactory,
+    store_factory: Result<&ObjectStoreFactory, &ObjectStoreError>,
     stop_receiver: &watch::Receiver<bool>,
-) {
+) -> anyhow::Result<()> {
     let db_config = DBConfig::from_env();
     let operation_config = OperationsManagerConfig::from_env();
     const COMPONENTS_TO_MODES: &[(Component, bool)] =
         &[(Component::Tree, true), (Component::TreeLightweight, false)];
 
     if components.contains(&Component::TreeBackup) {
-        panic!("Tree backup mode is disabled");
+        let err = anyhow::anyhow!("Tree backup mode is disabled");
+        failures.record(Component::TreeBackup, Err(err))?;
     }
-    if components.contains(&Component::Tree) && components.contains(&Component::TreeLightweight) {
-        panic!(
-            "Cannot start a node with a Merkle tree in both full and lightweight modes. \
-             Since the storage layout is mode-independent, choose either of modes and run \
-             the node with it."
-        );
-    }
-
+    // Requesting both tree modes is handled by `dedup_components()`.
     for &(component, is_full) in COMPONENTS_TO_MODES {
-        if components.contains(&component) {
+        if !components.contains(&component) {
+            continue;
+        }
+        let init = async {
             let mode = if is_full {
+                let store_factory = require_object_store(store_factory);
                 MetadataCalculatorModeConfig::Full { store_factory }
             } else {
                 MetadataCalculatorModeConfig::Lightweight
@@ -586,9 +1471,20 @@ async fn add_trees_to_task_futures(
             let (future, tree_health_check) =
                 run_tree(&db_config, &operation_config, mode, stop_receiver.clone()).await;
             task_futures.push(future);
+            if is_full {
+                // Comparing the tree with L1 only requires a single cheap query per health check.
+                let pool = ConnectionPool::new(Some(1), DbVariant::Replica).await;
+                healthchecks.push(Box::new(TreeConsistencyHealthCheck::new(
+                    tree_health_check.clone(),
+                    pool,
+                )));
+            }
             healthchecks.push(Box::new(tree_health_check));
-        }
+            Ok(())
+        };
+        failures.initialize(component, init).await?;
     }
+    Ok(())
 }
 
 async fn run_tree(
@@ -598,10 +1494,10 @@ async fn run_tree(
     stop_receiver: watch::Receiver<bool>,
 ) -> (JoinHandle<()>, TreeHealthCheck) {
     let started_at = Instant::now();
-    let mode_str = if matches!(mode, MetadataCalculatorModeConfig::Full { .. }) {
-        "full"
+    let (component, mode_str) = if matches!(mode, MetadataCalculatorModeConfig::Full { .. }) {
+        (Component::Tree, "full")
     } else {
-        "lightweight"
+        (Component::TreeLightweight, "lightweight")
     };
     vlog::info!("Initializing Merkle tree in {mode_str} mode");
 
@@ -610,8 +1506,11 @@ async fn run_tree(
     let tree_health_check = metadata_calculator.tree_health_check();
     let tree_tag = metadata_calculator.tree_tag();
     let pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
-    let prover_pool = ConnectionPool::new(Some(1), DbVariant::Prover).await;
-    let future = tokio::spawn(metadata_calculator.run(pool, prover_pool, stop_receiver));
+    let prover_pool = prover_connection_pool(component, 1).await;
+    let future = spawn_named(
+        &format!("{tree_tag}_tree"),
+        metadata_calculator.run(pool, prover_pool, stop_receiver),
+    );
 
     vlog::info!(
         "Initialized `{tree_tag}` tree in {:?}",
@@ -626,19 +1525,26 @@ async fn run_tree(
     (future, tree_health_check)
 }
 
-async fn add_witness_generator_to_task_futures(
-    task_futures: &mut Vec<JoinHandle<()>>,
-    components: &[Component],
-    connection_pool: &ConnectionPool,
-    prover_connection_pool: &ConnectionPool,
-    store_factory: &ObjectStoreFactory,
-    stop_receiver: &watch::Receiver<bool>,
-) {
-    // We don't want witness generator to run on local nodes, as it's CPU heavy.
-    if std::env::var("ZKSYNC_LOCAL_SETUP") == Ok("true".to_owned()) {
-        return;
-    }
+/// Returns the number of concurrent witness generator workers configured for `round`.
+fn witness_generator_concurrency(
+    config: &WitnessGeneratorConfig,
+    round: AggregationRound,
+) -> usize {
+    let concurrency = match round {
+        AggregationRound::BasicCircuits => config.basic_circuits_concurrency,
+        AggregationRound::LeafAggregation => config.leaf_aggregation_concurrency,
+        AggregationRound::NodeAggregation => config.node_aggregation_concurrency,
+        AggregationRound::Scheduler => config.scheduler_concurrency,
+    };
+    concurrency.unwrap_or(1).max(1)
+}
 
+/// Lists witness generator workers that should be spawned for the specified `components`.
+/// Each worker is represented by its batch size and aggregation round.
+fn witness_generator_workers(
+    components: &[Component],
+    config: &WitnessGeneratorConfig,
+) -> Vec<(Option<usize>, AggregationRound)> {
     let generator_params = components.iter().filter_map(|component| {
         if let Component::WitnessGenerator(batch_size, component_type) = component {
             Some((*batch_size, *component_type))
@@ -646,84 +1552,191 @@ async fn add_witness_generator_to_task_futures(
             None
         }
     });
+    generator_params
+        .flat_map(|(batch_size, round)| {
+            let concurrency = witness_generator_concurrency(config, round);
+            iter::repeat((batch_size, round)).take(concurrency)
+        })
+        .collect()
+}
 
-    for (batch_size, component_type) in generator_params {
-        let started_at = Instant::now();
-        vlog::info!(
-            "initializing the {component_type:?} witness generator, batch size: {batch_size:?}"
-        );
+async fn add_witness_generator_to_task_futures(
+    task_futures: &mut Vec<JoinHandle<()>>,
+    failures: &mut ComponentFailures,
+    components: &[Component],
+    store_router: Result<&ObjectStoreRouter, &ObjectStoreError>,
+    stop_receiver: &watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    // We don't want witness generator to run on local nodes, as it's CPU heavy.
+    if std::env::var("ZKSYNC_LOCAL_SETUP") == Ok("true".to_owned()) {
+        return Ok(());
+    }
 
-        let config = WitnessGeneratorConfig::from_env();
-        let task = match component_type {
-            AggregationRound::BasicCircuits => {
-                let witness_generator = BasicWitnessGenerator::new(
-                    config,
-                    store_factory,
-                    connection_pool.clone(),
-                    prover_connection_pool.clone(),
-                )
-                .await;
-                tokio::spawn(witness_generator.run(stop_receiver.clone(), batch_size))
-            }
-            AggregationRound::LeafAggregation => {
-                let witness_generator = LeafAggregationWitnessGenerator::new(
-                    config,
-                    store_factory,
-                    connection_pool.clone(),
-                    prover_connection_pool.clone(),
-                )
-                .await;
-                tokio::spawn(witness_generator.run(stop_receiver.clone(), batch_size))
-            }
-            AggregationRound::NodeAggregation => {
-                let witness_generator = NodeAggregationWitnessGenerator::new(
-                    config,
-                    store_factory,
-                    connection_pool.clone(),
-                    prover_connection_pool.clone(),
-                )
-                .await;
-                tokio::spawn(witness_generator.run(stop_receiver.clone(), batch_size))
-            }
-            AggregationRound::Scheduler => {
-                let witness_generator = SchedulerWitnessGenerator::new(
-                    config,
-                    store_factory,
-                    connection_pool.clone(),
-                    prover_connection_pool.clone(),
-                )
-                .await;
-                tokio::spawn(witness_generator.run(stop_receiver.clone(), batch_size))
+    let workers = witness_generator_workers(components, &WitnessGeneratorConfig::from_env());
+    if workers.is_empty() {
+        return Ok(());
+    }
+    // Pools are shared among all workers; each worker needs at most one connection to each DB at a time.
+    let pool_size = workers.len() as u32;
+    let init_shared = async {
+        let connection_pool = ConnectionPool::new(Some(pool_size), DbVariant::Master).await;
+        let (batch_size, round) = workers[0];
+        let component = Component::WitnessGenerator(batch_size, round);
+        let prover_connection_pool = prover_connection_pool(component, pool_size).await;
+        let store_factory = require_object_store(store_router).factory(BlobKind::WitnessInputs);
+        Ok((connection_pool, prover_connection_pool, store_factory))
+    };
+    let (connection_pool, prover_connection_pool, store_factory) =
+        match catch_init_panic(init_shared).await {
+            Ok(shared) => shared,
+            Err(err) => {
+                // Shared resources are required by all witness generators, so all of them fail.
+                let err = format!("{err:#}");
+                for &component in components {
+                    if matches!(component, Component::WitnessGenerator(..)) {
+                        let err = anyhow::anyhow!("failed initializing shared resources: {err}");
+                        failures.record(component, Err(err))?;
+                    }
+                }
+                return Ok(());
             }
         };
-        task_futures.push(task);
 
-        vlog::info!(
-            "initialized {component_type:?} witness generator in {:?}",
-            started_at.elapsed()
-        );
-        metrics::gauge!(
-            "server.init.latency",
-            started_at.elapsed(),
-            "stage" => format!("witness_generator_{component_type:?}")
-        );
+    for (batch_size, component_type) in workers {
+        let init = async {
+            let started_at = Instant::now();
+            vlog::info!(
+                "initializing the {component_type:?} witness generator, batch size: {batch_size:?}"
+            );
+
+            let config = WitnessGeneratorConfig::from_env();
+            // Witness generators are not cancelled on stop signal; instead, they finish the in-flight job
+            // (see `JobProcessor::run()`) so that it doesn't need to be retried.
+            let task = match component_type {
+                AggregationRound::BasicCircuits => {
+                    let witness_generator = BasicWitnessGenerator::new(
+                        config,
+                        store_factory,
+                        connection_pool.clone(),
+                        prover_connection_pool.clone(),
+                    )
+                    .await;
+                    spawn_named(
+                        "basic_witness_generator",
+                        witness_generator.run(stop_receiver.clone(), batch_size),
+                    )
+                }
+                AggregationRound::LeafAggregation => {
+                    let witness_generator = LeafAggregationWitnessGenerator::new(
+                        config,
+                        store_factory,
+                        connection_pool.clone(),
+                        prover_connection_pool.clone(),
+                    )
+                    .await;
+                    spawn_named(
+                        "leaf_witness_generator",
+                        witness_generator.run(stop_receiver.clone(), batch_size),
+                    )
+                }
+                AggregationRound::NodeAggregation => {
+                    let witness_generator = NodeAggregationWitnessGenerator::new(
+                        config,
+                        store_factory,
+                        connection_pool.clone(),
+                        prover_connection_pool.clone(),
+                    )
+                    .await;
+                    spawn_named(
+                        "node_witness_generator",
+                        witness_generator.run(stop_receiver.clone(), batch_size),
+                    )
+                }
+                AggregationRound::Scheduler => {
+                    let witness_generator = SchedulerWitnessGenerator::new(
+                        config,
+                        store_factory,
+                        connection_pool.clone(),
+                        prover_connection_pool.clone(),
+                    )
+                    .await;
+                    spawn_named(
+                        "scheduler_witness_generator",
+                        witness_generator.run(stop_receiver.clone(), batch_size),
+                    )
+                }
+            };
+            task_futures.push(task);
+
+            vlog::info!(
+                "initialized {component_type:?} witness generator in {:?}",
+                started_at.elapsed()
+            );
+            metrics::gauge!(
+                "server.init.latency",
+                started_at.elapsed(),
+                "stage" => format!("witness_generator_{component_type:?}")
+            );
+            Ok(())
+        };
+        let component = Component::WitnessGenerator(batch_size, component_type);
+        failures.initialize(component, init).await?;
     }
+    Ok(())
+}
+
+/// Creates a connection pool to the prover DB for `component`.
+///
+/// # Panics
+///
+/// Panics if `component` is not declared as using the prover DB (see [`Component::uses_prover_db()`]),
+/// so that the prover DB isn't required to be configured for components not using it.
+async fn prover_connection_pool(component: Component, pool_size: u32) -> ConnectionPool {
+    assert!(
+        component.uses_prover_db(),
+        "Component {component:?} is not declared as using the prover DB"
+    );
+    ConnectionPool::new(Some(pool_size), DbVariant::Prover).await
+}
+
+/// Unwraps the object store configuration for a component that cannot work without it.
+fn require_object_store<T>(store: Result<T, &ObjectStoreError>) -> T {
+    store.unwrap_or_else(|err| panic!("Object store is misconfigured: {err}"))
 }
 
 async fn add_house_keeper_to_task_futures(
     task_futures: &mut Vec<JoinHandle<()>>,
-    store_factory: &ObjectStoreFactory,
+    healthchecks: &mut Vec<Box<dyn CheckHealth>>,
+    store_factory: Result<&ObjectStoreFactory, &ObjectStoreError>,
 ) {
     let house_keeper_config = HouseKeeperConfig::from_env();
+    let job_health_check = house_keeper_config
+        .job_max_consecutive_failures
+        .map(PeriodicJobHealthCheck::new);
+    if let Some(health_check) = &job_health_check {
+        healthchecks.push(Box::new(health_check.clone()));
+    }
     let connection_pool = ConnectionPool::new(Some(1), DbVariant::Replica).await;
     let l1_batch_metrics_reporter = L1BatchMetricsReporter::new(
         house_keeper_config.l1_batch_metrics_reporting_interval_ms,
         connection_pool,
     );
+    if house_keeper_config.call_traces_pruning_enabled() {
+        let master_connection_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
+        let call_traces_pruner = CallTracePruner::new(
+            house_keeper_config.call_traces_retention_miniblocks(),
+            house_keeper_config.call_traces_pruning_interval_ms(),
+            master_connection_pool,
+        );
+        task_futures.push(spawn_named(
+            "call_traces_pruner",
+            call_traces_pruner.run_with_health_check(job_health_check.clone()),
+        ));
+    }
 
-    let prover_connection_pool = ConnectionPool::new(
-        Some(house_keeper_config.prover_db_pool_size),
-        DbVariant::Prover,
+    let prover_connection_pool = prover_connection_pool(
+        Component::Housekeeper,
+        house_keeper_config.prover_db_pool_size,
     )
     .await;
     let gpu_prover_queue = GpuProverQueueMonitor::new(
@@ -750,20 +1763,47 @@ async fn add_house_keeper_to_task_futures(
         house_keeper_config.witness_generator_stats_reporting_interval_ms,
         prover_connection_pool.clone(),
     );
-    let gcs_blob_cleaner = GcsBlobCleaner::new(
-        store_factory,
-        prover_connection_pool.clone(),
-        house_keeper_config.blob_cleaning_interval_ms,
-    )
-    .await;
-
-    task_futures.push(tokio::spawn(gcs_blob_cleaner.run()));
-    task_futures.push(tokio::spawn(witness_generator_stats_reporter.run()));
-    task_futures.push(tokio::spawn(gpu_prover_queue.run()));
-    task_futures.push(tokio::spawn(l1_batch_metrics_reporter.run()));
-    task_futures.push(tokio::spawn(prover_stats_reporter.run()));
-    task_futures.push(tokio::spawn(waiting_to_queued_witness_job_mover.run()));
-    task_futures.push(tokio::spawn(prover_job_retry_manager.run()));
+    match store_factory {
+        Ok(store_factory) => {
+            let gcs_blob_cleaner = GcsBlobCleaner::new(
+                store_factory,
+                prover_connection_pool.clone(),
+                house_keeper_config.blob_cleaning_interval_ms,
+            )
+            .await;
+            task_futures.push(spawn_named(
+                "gcs_blob_cleaner",
+                gcs_blob_cleaner.run_with_health_check(job_health_check.clone()),
+            ));
+        }
+        Err(err) => {
+            vlog::warn!("Object store is misconfigured ({err}); GCS blob cleaner is not started");
+        }
+    }
+    task_futures.push(spawn_named(
+        "witness_generator_stats_reporter",
+        witness_generator_stats_reporter.run_with_health_check(job_health_check.clone()),
+    ));
+    task_futures.push(spawn_named(
+        "gpu_prover_queue",
+        gpu_prover_queue.run_with_health_check(job_health_check.clone()),
+    ));
+    task_futures.push(spawn_named(
+        "l1_batch_metrics_reporter",
+        l1_batch_metrics_reporter.run_with_health_check(job_health_check.clone()),
+    ));
+    task_futures.push(spawn_named(
+        "prover_stats_reporter",
+        prover_stats_reporter.run_with_health_check(job_health_check.clone()),
+    ));
+    task_futures.push(spawn_named(
+        "waiting_to_queued_witness_job_mover",
+        waiting_to_queued_witness_job_mover.run_with_health_check(job_health_check.clone()),
+    ));
+    task_futures.push(spawn_named(
+        "prover_job_retry_manager",
+        prover_job_retry_manager.run_with_health_check(job_health_check.clone()),
+    ));
 
     // All FRI Prover related components are configured below.
     let fri_prover_config = FriProverConfig::from_env();
@@ -773,7 +1813,10 @@ async fn add_house_keeper_to_task_futures(
         house_keeper_config.fri_prover_job_retrying_interval_ms,
         prover_connection_pool.clone(),
     );
-    task_futures.push(tokio::spawn(fri_prover_job_retry_manager.run()));
+    task_futures.push(spawn_named(
+        "fri_prover_job_retry_manager",
+        fri_prover_job_retry_manager.run_with_health_check(job_health_check.clone()),
+    ));
 
     let fri_witness_gen_config = FriWitnessGeneratorConfig::from_env();
     let fri_witness_gen_job_retry_manager = FriWitnessGeneratorJobRetryManager::new(
@@ -782,31 +1825,46 @@ async fn add_house_keeper_to_task_futures(
         house_keeper_config.fri_witness_generator_job_retrying_interval_ms,
         prover_connection_pool.clone(),
     );
-    task_futures.push(tokio::spawn(fri_witness_gen_job_retry_manager.run()));
+    task_futures.push(spawn_named(
+        "fri_witness_gen_job_retry_manager",
+        fri_witness_gen_job_retry_manager.run_with_health_check(job_health_check.clone()),
+    ));
 
     let waiting_to_queued_fri_witness_job_mover = WaitingToQueuedFriWitnessJobMover::new(
         house_keeper_config.fri_witness_job_moving_interval_ms,
         prover_connection_pool.clone(),
     );
-    task_futures.push(tokio::spawn(waiting_to_queued_fri_witness_job_mover.run()));
+    task_futures.push(spawn_named(
+        "waiting_to_queued_fri_witness_job_mover",
+        waiting_to_queued_fri_witness_job_mover.run_with_health_check(job_health_check.clone()),
+    ));
 
     let scheduler_circuit_queuer = SchedulerCircuitQueuer::new(
         house_keeper_config.fri_witness_job_moving_interval_ms,
         prover_connection_pool.clone(),
     );
-    task_futures.push(tokio::spawn(scheduler_circuit_queuer.run()));
+    task_futures.push(spawn_named(
+        "scheduler_circuit_queuer",
+        scheduler_circuit_queuer.run_with_health_check(job_health_check.clone()),
+    ));
 
     let fri_witness_generator_stats_reporter = FriWitnessGeneratorStatsReporter::new(
         prover_connection_pool.clone(),
         house_keeper_config.witness_generator_stats_reporting_interval_ms,
     );
-    task_futures.push(tokio::spawn(fri_witness_generator_stats_reporter.run()));
+    task_futures.push(spawn_named(
+        "fri_witness_generator_stats_reporter",
+        fri_witness_generator_stats_reporter.run_with_health_check(job_health_check.clone()),
+    ));
 
     let fri_prover_stats_reporter = FriProverStatsReporter::new(
         house_keeper_config.fri_prover_stats_reporting_interval_ms,
         prover_connection_pool.clone(),
     );
-    task_futures.push(tokio::spawn(fri_prover_stats_reporter.run()));
+    task_futures.push(spawn_named(
+        "fri_prover_stats_reporter",
+        fri_prover_stats_reporter.run_with_health_check(job_health_check.clone()),
+    ));
 }
 
 async fn build_tx_sender<G: L1GasPriceProvider>(
@@ -820,7 +1878,8 @@ async fn build_tx_sender<G: L1GasPriceProvider>(
 ) -> TxSender<G> {
     let mut tx_sender_builder = TxSenderBuilder::new(tx_sender_config.clone(), replica_pool)
         .with_main_connection_pool(master_pool)
-        .with_state_keeper_config(state_keeper_config.clone());
+        .with_state_keeper_config(state_keeper_config.clone())
+        .with_fee_policy(fee_policy_from_config(&FeePolicyConfig::from_env()));
 
     // Add rate limiter if enabled.
     if let Some(transactions_per_sec_limit) = web3_json_config.transactions_per_sec_limit {
@@ -839,6 +1898,20 @@ async fn build_tx_sender<G: L1GasPriceProvider>(
         .await
 }
 
+fn warn_on_api_threads_oversubscription(config: &Web3JsonRpcConfig) {
+    let Ok(available_parallelism) = std::thread::available_parallelism() else {
+        return;
+    };
+    let total_threads = config.http_server_threads() + config.ws_server_threads();
+    if total_threads > available_parallelism.get() {
+        vlog::warn!(
+            "HTTP and WS API servers use {total_threads} threads in total, which exceeds \
+             available parallelism ({available_parallelism}); consider setting \
+             `API_WEB3_JSON_RPC_TOTAL_API_THREADS` to distribute threads between the servers"
+        );
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
     tx_sender_config: &TxSenderConfig,
@@ -859,7 +1932,7 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
         replica_connection_pool.clone(),
         master_connection_pool.clone(),
         gas_adjuster,
-        factory_deps_cache.clone(),
+        factory_deps_cache,
     )
     .await;
 
@@ -868,7 +1941,15 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
             .http(api_config.web3_json_rpc.http_port)
             .with_filter_limit(api_config.web3_json_rpc.filters_limit())
             .with_threads(api_config.web3_json_rpc.http_server_threads())
+            .with_request_timeout(api_config.web3_json_rpc.request_timeout())
             .with_tx_sender(tx_sender);
+    if let Some(cors_origins) = api_config.web3_json_rpc.cors_origins.clone() {
+        builder = builder.with_cors(cors_origins);
+    }
+    if api_config.web3_json_rpc.enable_response_compression() {
+        builder = builder
+            .with_response_compression(api_config.web3_json_rpc.response_compression_min_size());
+    }
 
     if with_debug_namespace {
         builder = builder.enable_debug_namespace(
@@ -903,19 +1984,31 @@ async fn run_ws_api<G: L1GasPriceProvider + Send + Sync + 'static>(
         replica_connection_pool.clone(),
         master_connection_pool.clone(),
         gas_adjuster,
-        factory_deps_cache.clone(),
+        factory_deps_cache,
     )
     .await;
 
-    web3::ApiBuilder::jsonrpc_backend(internal_api.clone(), replica_connection_pool)
-        .ws(api_config.web3_json_rpc.ws_port)
-        .with_filter_limit(api_config.web3_json_rpc.filters_limit())
-        .with_subscriptions_limit(api_config.web3_json_rpc.subscriptions_limit())
-        .with_polling_interval(api_config.web3_json_rpc.pubsub_interval())
-        .with_threads(api_config.web3_json_rpc.ws_server_threads())
-        .with_tx_sender(tx_sender)
-        .build(stop_receiver.clone())
-        .await
+    let mut builder =
+        web3::ApiBuilder::jsonrpc_backend(internal_api.clone(), replica_connection_pool)
+            .ws(api_config.web3_json_rpc.ws_port)
+            .with_filter_limit(api_config.web3_json_rpc.filters_limit())
+            .with_subscriptions_limit(api_config.web3_json_rpc.subscriptions_limit())
+            .with_subscription_type_limits(web3::SubscriptionLimits {
+                new_heads: api_config.web3_json_rpc.new_heads_subscriptions_limit,
+                pending_txs: api_config.web3_json_rpc.pending_txs_subscriptions_limit,
+                logs: api_config.web3_json_rpc.logs_subscriptions_limit,
+            })
+            .with_polling_interval(api_config.web3_json_rpc.pubsub_interval())
+            .with_threads(api_config.web3_json_rpc.ws_server_threads())
+            .with_request_timeout(api_config.web3_json_rpc.request_timeout())
+            .with_tx_sender(tx_sender);
+    if let Some(ping_interval) = api_config.web3_json_rpc.ws_ping_interval() {
+        builder = builder.with_websocket_ping_interval(ping_interval);
+    }
+    if let Some(limit) = api_config.web3_json_rpc.ws_max_buffered_notifications {
+        builder = builder.with_max_buffered_notifications(limit);
+    }
+    builder.build(stop_receiver.clone()).await
 }
 
 async fn circuit_breakers_for_components(
@@ -966,12 +2059,364 @@ async fn circuit_breakers_for_components(
     circuit_breakers
 }
 
-#[tokio::test]
-async fn test_house_keeper_components_get_added() {
-    let (core_task_handles, _, _, _) = initialize_components(vec![Component::Housekeeper], false)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_house_keeper_components_get_added() {
+        let (shutdown_sender, _shutdown_receiver) = shutdown::shutdown_reason_channel();
+        let (core_task_handles, _, _, failures) = initialize_components(
+            vec![Component::Housekeeper],
+            None,
+            ComponentFailurePolicy::FailFast,
+            shutdown_sender,
+        )
+        .await
+        .unwrap();
+        assert!(failures.is_empty());
+        // circuit-breaker, prometheus-exporter components are run, irrespective of other components.
+        let always_running_component_count = 2;
+        assert_eq!(13, core_task_handles.len() - always_running_component_count);
+    }
+
+    #[tokio::test]
+    async fn initializing_components_with_failure_policy() {
+        // Both tree modes cannot be run together, so the latter tree component always fails.
+        let components = vec![
+            Component::Housekeeper,
+            Component::TreeLightweight,
+            Component::Tree,
+        ];
+
+        let (shutdown_sender, _shutdown_receiver) = shutdown::shutdown_reason_channel();
+        let err = initialize_components(
+            components.clone(),
+            None,
+            ComponentFailurePolicy::FailFast,
+            shutdown_sender,
+        )
+        .await
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("Merkle tree"), "{err:#}");
+
+        let (shutdown_sender, _shutdown_receiver) = shutdown::shutdown_reason_channel();
+        let (core_task_handles, stop_sender, _, failures) = initialize_components(
+            components,
+            None,
+            ComponentFailurePolicy::BestEffort,
+            shutdown_sender,
+        )
         .await
         .unwrap();
-    // circuit-breaker, prometheus-exporter components are run, irrespective of other components.
-    let always_running_component_count = 2;
-    assert_eq!(13, core_task_handles.len() - always_running_component_count);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].component, Component::Tree);
+        // The house keeper (13 tasks) and the lightweight tree (1 task) are started together with
+        // the circuit breaker and Prometheus exporter.
+        assert_eq!(core_task_handles.len(), 2 + 13 + 1);
+        stop_sender.send(true).ok();
+    }
+
+    #[tokio::test]
+    async fn house_keeper_starts_without_object_store() {
+        let store_err = ObjectStoreError::Other("invalid object store config".into());
+        let mut task_futures = vec![];
+        let mut healthchecks = vec![];
+        add_house_keeper_to_task_futures(&mut task_futures, &mut healthchecks, Err(&store_err))
+            .await;
+        // All house keeper tasks except for the GCS blob cleaner are started.
+        assert_eq!(task_futures.len(), 12);
+    }
+
+    #[test]
+    fn replica_pool_health_check_is_scoped_to_api_components() {
+        let api_components = Components::from_str("api").unwrap().0;
+        assert!(needs_replica_pool_health_check(&api_components, true));
+        assert!(needs_replica_pool_health_check(&api_components, false));
+
+        let tree_components = Components::from_str("tree").unwrap().0;
+        assert!(!needs_replica_pool_health_check(&tree_components, true));
+        // Without scoping, the replica pool is checked for all components.
+        assert!(needs_replica_pool_health_check(&tree_components, false));
+    }
+
+    #[test]
+    fn health_check_server_is_colocated_with_api_components() {
+        let parse_components = |tokens: &[&str]| -> Vec<Component> {
+            tokens
+                .iter()
+                .flat_map(|token| Components::from_str(token).unwrap().0)
+                .collect()
+        };
+
+        let api_component_sets: [&[&str]; 4] = [
+            &["api"],
+            &["http_api"],
+            &["tree", "explorer_api"],
+            &["health_check_aggregator"],
+        ];
+        for tokens in api_component_sets {
+            let components = parse_components(tokens);
+            assert!(needs_health_check_server(&components, true), "{tokens:?}");
+            assert!(needs_health_check_server(&components, false), "{tokens:?}");
+        }
+
+        let components = parse_components(&["tree", "eth", "state_keeper"]);
+        assert!(!needs_health_check_server(&components, true));
+        assert!(needs_health_check_server(&components, false));
+    }
+
+    #[test]
+    fn components_serde_roundtrip() {
+        let config = r#"["api", "one_shot_leaf_witness_generator", "scheduler_witness_generator", "eth_tx_manager"]"#;
+        let components: Components = serde_json::from_str(config).unwrap();
+        let expected_components = vec![
+            Component::HttpApi,
+            Component::WsApi,
+            Component::ExplorerApi,
+            Component::WitnessGenerator(Some(1), AggregationRound::LeafAggregation),
+            Component::WitnessGenerator(None, AggregationRound::Scheduler),
+            Component::EthTxManager,
+        ];
+        assert_eq!(components.0, expected_components);
+
+        let serialized = serde_json::to_value(&components).unwrap();
+        assert_eq!(
+            serialized,
+            serde_json::json!([
+                "http_api",
+                "ws_api",
+                "explorer_api",
+                "one_shot_leaf_witness_generator",
+                "scheduler_witness_generator",
+                "eth_tx_manager"
+            ])
+        );
+        let deserialized: Components = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, components);
+
+        // Every component expressible as a token must round-trip through it.
+        let all_components = Components::from_str("witness_generator").unwrap().0;
+        let one_shot_components = Components::from_str("one_shot_witness_generator")
+            .unwrap()
+            .0;
+        for component in all_components.into_iter().chain(one_shot_components) {
+            let serialized = serde_json::to_string(&component).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Component>(&serialized).unwrap(),
+                component
+            );
+        }
+        let multi_job_component = Component::WitnessGenerator(Some(5), AggregationRound::Scheduler);
+        serde_json::to_string(&multi_job_component).unwrap_err();
+        serde_json::from_str::<Component>(r#""api""#).unwrap_err();
+    }
+
+    #[test]
+    fn duplicate_components_are_removed() {
+        let components = vec![
+            Component::HttpApi,
+            Component::StateKeeper,
+            Component::HttpApi,
+            Component::WsApi,
+            Component::StateKeeper,
+        ];
+        let mut failures = ComponentFailures::new(ComponentFailurePolicy::FailFast);
+        let components = dedup_components(components, &mut failures).unwrap();
+        assert_eq!(
+            components,
+            [Component::HttpApi, Component::StateKeeper, Component::WsApi]
+        );
+    }
+
+    #[test]
+    fn contending_components_are_recorded_as_failures() {
+        let components = vec![Component::Tree, Component::TreeLightweight];
+        let mut failures = ComponentFailures::new(ComponentFailurePolicy::FailFast);
+        let err = dedup_components(components.clone(), &mut failures).unwrap_err();
+        assert!(format!("{err:#}").contains("Merkle tree"), "{err:#}");
+
+        let mut failures = ComponentFailures::new(ComponentFailurePolicy::BestEffort);
+        let components = dedup_components(components, &mut failures).unwrap();
+        assert_eq!(components, [Component::Tree]);
+        assert_eq!(failures.failures.len(), 1);
+        assert_eq!(failures.failures[0].component, Component::TreeLightweight);
+    }
+
+    #[test]
+    fn api_components_do_not_use_prover_db() {
+        let api_components = Components::from_str("api").unwrap().0;
+        assert!(!api_components.iter().any(Component::uses_prover_db));
+        assert!(!Component::StateKeeper.uses_prover_db());
+
+        let witness_generators = Components::from_str("witness_generator").unwrap().0;
+        assert!(witness_generators.iter().all(Component::uses_prover_db));
+        assert!(Component::Housekeeper.uses_prover_db());
+        assert!(Component::EthTxAggregator.uses_prover_db());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "not declared as using the prover DB")]
+    async fn prover_pool_is_not_created_for_api_components() {
+        prover_connection_pool(Component::HttpApi, 1).await;
+    }
+
+    #[test]
+    fn parsing_log_override_directives() {
+        let directives =
+            log_override_directives("tree=debug, eth_watcher=trace,,api=info").unwrap();
+        assert_eq!(
+            directives,
+            [
+                "zksync_core::metadata_calculator=debug",
+                "zksync_merkle_tree=debug",
+                "zksync_core::eth_watch=trace",
+                "zksync_core::api_server=info",
+            ]
+        );
+        assert!(log_override_directives("").unwrap().is_empty());
+
+        let err = log_override_directives("tree").unwrap_err();
+        assert!(err.to_string().contains("component=level"), "{err}");
+        log_override_directives("unknown_component=debug").unwrap_err();
+    }
+
+    #[test]
+    fn preflight_reports_broken_config() {
+        let mut vars: Vec<_> = env::vars()
+            .filter(|(name, _)| name != "FRI_PROVER_MAX_ATTEMPTS")
+            .collect();
+        vars.push((
+            "FRI_PROVER_MAX_ATTEMPTS".to_owned(),
+            "not a number".to_owned(),
+        ));
+
+        let err = preflight_validate_vars(&[Component::Housekeeper], &vars)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("FriProverConfig: "), "{err}");
+        // The FRI prover config isn't used by the ETH watcher, so it isn't validated.
+        if let Err(err) = preflight_validate_vars(&[Component::EthWatcher], &vars) {
+            assert!(!err.to_string().contains("FriProverConfig"), "{err}");
+        }
+    }
+
+    #[test]
+    fn node_info_lists_components() {
+        let components = Components::from_str("api").unwrap().0;
+        let node_info = node_info(&components);
+        assert_eq!(
+            node_info.components(),
+            ["http_api", "ws_api", "explorer_api"]
+        );
+        assert_eq!(node_info.tree_mode(), None);
+
+        let components = vec![
+            Component::TreeLightweight,
+            Component::Tree,
+            Component::WitnessGenerator(Some(5), AggregationRound::Scheduler),
+        ];
+        let node_info = node_info(&components);
+        assert_eq!(
+            node_info.components(),
+            [
+                "tree_lightweight",
+                "tree",
+                "WitnessGenerator(Some(5), Scheduler)"
+            ]
+        );
+        assert_eq!(node_info.tree_mode(), Some("full"));
+    }
+
+    #[test]
+    fn component_failures_are_handled_according_to_policy() {
+        let failing_result = || Err(anyhow::anyhow!("no connection to L1"));
+
+        let mut failures = ComponentFailures::new(ComponentFailurePolicy::FailFast);
+        failures.record(Component::EthWatcher, Ok(())).unwrap();
+        let err = failures
+            .record(Component::EthTxAggregator, failing_result())
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("EthTxAggregator"), "{err:#}");
+        assert!(failures.failures.is_empty());
+
+        let mut failures = ComponentFailures::new(ComponentFailurePolicy::BestEffort);
+        failures.record(Component::EthWatcher, Ok(())).unwrap();
+        failures
+            .record(Component::EthTxAggregator, failing_result())
+            .unwrap();
+        failures.record(Component::EthTxManager, Ok(())).unwrap();
+        assert_eq!(failures.failures.len(), 1);
+        let failure = &failures.failures[0];
+        assert_eq!(failure.component, Component::EthTxAggregator);
+        assert!(
+            format!("{:#}", failure.error).contains("no connection to L1"),
+            "{:#}",
+            failure.error
+        );
+    }
+
+    #[tokio::test]
+    async fn named_task_is_spawned() {
+        let handle = spawn_named("test", async { 42 });
+        assert_eq!(handle.await.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn cancellable_component_stops_on_stop_signal() {
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let handle = spawn_cancellable("test", stop_receiver, futures::future::pending());
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert!(!handle.is_finished());
+
+        stop_sender.send_replace(true);
+        tokio::time::timeout(std::time::Duration::from_secs(5), handle)
+            .await
+            .expect("component did not stop on stop signal")
+            .unwrap();
+    }
+
+    #[test]
+    fn witness_generator_workers_respect_concurrency() {
+        let mut config = WitnessGeneratorConfig::from_env();
+        config.basic_circuits_concurrency = Some(3);
+        config.leaf_aggregation_concurrency = None;
+        config.scheduler_concurrency = Some(0);
+        let components = vec![
+            Component::WitnessGenerator(None, AggregationRound::BasicCircuits),
+            Component::WitnessGenerator(Some(1), AggregationRound::LeafAggregation),
+            Component::WitnessGenerator(None, AggregationRound::Scheduler),
+            Component::EthWatcher,
+        ];
+
+        let workers = witness_generator_workers(&components, &config);
+        assert_eq!(
+            workers,
+            [
+                (None, AggregationRound::BasicCircuits),
+                (None, AggregationRound::BasicCircuits),
+                (None, AggregationRound::BasicCircuits),
+                (Some(1), AggregationRound::LeafAggregation),
+                (None, AggregationRound::Scheduler),
+            ]
+        );
+    }
+
+    #[test]
+    fn restart_policy_is_applied_to_listed_supported_components() {
+        let config = SupervisorConfig {
+            restartable_components: vec!["eth".to_owned(), "state_keeper".to_owned()],
+            max_restarts: Some(5),
+            restart_window_sec: None,
+        };
+        let restartable = restartable_components(&config);
+        assert_eq!(restartable, [Component::EthWatcher]);
+        let policy = restart_policy(&config, &restartable, Component::EthWatcher).unwrap();
+        assert_eq!(policy.max_restarts, 5);
+        assert_eq!(policy.window, std::time::Duration::from_secs(600));
+        assert!(restart_policy(&config, &restartable, Component::DataFetcher).is_none());
+        // Components the supervisor cannot relaunch are not supervised, even if listed.
+        assert!(restart_policy(&config, &restartable, Component::EthTxManager).is_none());
+        assert!(restart_policy(&config, &restartable, Component::StateKeeper).is_none());
+    }
 }
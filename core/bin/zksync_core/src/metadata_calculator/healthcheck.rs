@@ -1,28 +1,41 @@
 use async_trait::async_trait;
 use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
 use zksync_health_check::{CheckHealth, CheckHealthStatus};
+use zksync_types::{ethabi, L1BatchNumber, H256, U256};
 
-use super::{MetadataCalculatorMode, MetadataCalculatorStatus};
+use super::{MetadataCalculatorMode, MetadataCalculatorStatus, TreeRootInfo};
 
 /// HealthCheck used to verify if the tree(MetadataCalculator) is ready.
 /// This guarantees that we mark a tree as ready only when it can start processing blocks.
 /// Used in the /health endpoint
+///
+/// Besides the readiness, the health check reports the latest tree root hash and the L1 batch
+/// it corresponds to, so that it can be compared with the root hash committed on L1.
 #[derive(Clone, Debug)]
 pub struct TreeHealthCheck {
     receiver: watch::Receiver<MetadataCalculatorStatus>,
+    root_info_receiver: watch::Receiver<Option<TreeRootInfo>>,
     tree_mode: MetadataCalculatorMode,
 }
 
 impl TreeHealthCheck {
     pub(super) fn new(
         receiver: watch::Receiver<MetadataCalculatorStatus>,
+        root_info_receiver: watch::Receiver<Option<TreeRootInfo>>,
         tree_mode: MetadataCalculatorMode,
     ) -> TreeHealthCheck {
         TreeHealthCheck {
             receiver,
+            root_info_receiver,
             tree_mode,
         }
     }
+
+    /// Returns the latest root hash of the tree, or `None` if the tree isn't initialized yet.
+    pub fn root_info(&self) -> Option<TreeRootInfo> {
+        *self.root_info_receiver.borrow()
+    }
 }
 
 #[async_trait]
@@ -30,10 +43,115 @@ impl CheckHealth for TreeHealthCheck {
     async fn check_health(&self) -> CheckHealthStatus {
         match *self.receiver.borrow() {
             MetadataCalculatorStatus::Ready => CheckHealthStatus::Ready,
-            MetadataCalculatorStatus::NotReady => CheckHealthStatus::NotReady(format!(
+            // The tree catching up is not a hard failure.
+            MetadataCalculatorStatus::NotReady => CheckHealthStatus::Degraded(format!(
                 "{} tree is not ready",
                 self.tree_mode.as_tag()
             )),
         }
     }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        let root_info = self.root_info()?;
+        Some(serde_json::json!({
+            "tree": self.tree_mode.as_tag(),
+            "l1_batch_number": root_info.l1_batch_number,
+            "root_hash": root_info.root_hash,
+        }))
+    }
+}
+
+/// HealthCheck detecting divergence between the tree and L1. It compares the latest root hash
+/// computed by the tree with the state root in the calldata of the confirmed transaction committing
+/// the same L1 batch, and reports the tree as unhealthy if they differ. Unlike `l1_batches.hash`,
+/// which is overwritten by the tree itself, commit calldata is immutable once the transaction is sent.
+///
+/// The check is only conclusive once the L1 batch last processed by the tree is committed by this node;
+/// until then (or if the commit transaction was sent by another node, e.g. for the external node),
+/// the tree is considered healthy.
+#[derive(Debug)]
+pub struct TreeConsistencyHealthCheck {
+    tree_health_check: TreeHealthCheck,
+    pool: ConnectionPool,
+    commit_function: ethabi::Function,
+}
+
+impl TreeConsistencyHealthCheck {
+    pub fn new(tree_health_check: TreeHealthCheck, pool: ConnectionPool) -> Self {
+        let commit_function = zksync_contracts::zksync_contract()
+            .function("commitBlocks")
+            .expect("commitBlocks function not found in the zkSync contract")
+            .clone();
+        Self {
+            tree_health_check,
+            pool,
+            commit_function,
+        }
+    }
+
+    /// Extracts the state root of the specified L1 batch from the calldata of a `commitBlocks` call.
+    fn committed_state_root(
+        &self,
+        calldata: &[u8],
+        l1_batch_number: L1BatchNumber,
+    ) -> Option<H256> {
+        let (selector, args) = (calldata.get(..4)?, calldata.get(4..)?);
+        if selector != self.commit_function.short_signature() {
+            return None;
+        }
+        let commitments = self
+            .commit_function
+            .decode_input(args)
+            .ok()?
+            .pop()?
+            .into_array()?;
+        commitments.into_iter().find_map(|commitment| {
+            // See `BlockWithMetadata::l1_commit_data()` for the layout of the commitment.
+            let ethabi::Token::Tuple(fields) = commitment else {
+                return None;
+            };
+            let mut fields = fields.into_iter();
+            let number = fields.next()?.into_uint()?;
+            if number != U256::from(l1_batch_number.0) {
+                return None;
+            }
+            let state_root = fields.nth(2)?.into_fixed_bytes()?;
+            (state_root.len() == 32).then(|| H256::from_slice(&state_root))
+        })
+    }
+}
+
+#[async_trait]
+impl CheckHealth for TreeConsistencyHealthCheck {
+    async fn check_health(&self) -> CheckHealthStatus {
+        let Some(root_info) = self.tree_health_check.root_info() else {
+            return CheckHealthStatus::Ready;
+        };
+        let commit_calldata = self
+            .pool
+            .access_storage_tagged("metadata_calculator")
+            .await
+            .blocks_dal()
+            .get_confirmed_commit_tx_calldata(root_info.l1_batch_number)
+            .await;
+        let committed_root_hash = commit_calldata
+            .and_then(|calldata| self.committed_state_root(&calldata, root_info.l1_batch_number));
+
+        match committed_root_hash {
+            Some(root_hash) if root_hash != root_info.root_hash => {
+                CheckHealthStatus::NotReady(format!(
+                    "{} tree root hash {:?} for L1 batch #{} differs from the root hash {:?} committed on L1",
+                    self.tree_health_check.tree_mode.as_tag(),
+                    root_info.root_hash,
+                    root_info.l1_batch_number,
+                    root_hash
+                ))
+            }
+            _ => CheckHealthStatus::Ready,
+        }
+    }
+
+    fn affects_liveness(&self) -> bool {
+        false
+    }
 }
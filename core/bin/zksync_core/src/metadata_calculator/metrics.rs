@@ -1,6 +1,9 @@
 //! Metrics for `MetadataCalculator`.
 
-use std::time::Instant;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
 use zksync_types::block::L1BatchHeader;
 use zksync_utils::time::seconds_since_epoch;
@@ -64,6 +67,88 @@ impl UpdateTreeLatency {
     }
 }
 
+/// Catch-up progress of the tree relative to the sealed L1 batches in Postgres.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct CatchupProgress {
+    /// Number of sealed L1 batches not yet processed by the tree.
+    pub blocks_behind: u32,
+    /// Tree throughput in L1 batches per second, averaged over the tracker window.
+    pub blocks_per_sec: f64,
+    /// Estimated time until the tree catches up; `None` if the tree makes no progress.
+    pub eta: Option<Duration>,
+}
+
+impl CatchupProgress {
+    pub fn report(&self, mode: MetadataCalculatorMode) {
+        let mode_tag = mode.as_tag();
+        metrics::gauge!("tree.blocks_behind", self.blocks_behind as f64, "tree" => mode_tag);
+        metrics::gauge!("tree.catchup_blocks_per_sec", self.blocks_per_sec, "tree" => mode_tag);
+        if let Some(eta) = self.eta {
+            metrics::gauge!("tree.catchup_eta_sec", eta.as_secs_f64(), "tree" => mode_tag);
+        }
+    }
+}
+
+/// Tracks the tree catch-up throughput over a sliding time window, so that spikes in processing
+/// (e.g., a large L1 batch) don't distort the estimated time to catch up.
+#[derive(Debug)]
+pub(super) struct CatchupTracker {
+    window: Duration,
+    /// Samples of the last processed L1 batch number, ordered by time.
+    samples: VecDeque<(Instant, u32)>,
+}
+
+impl CatchupTracker {
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(60);
+
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Records that by `now`, the tree has processed L1 batches up to `last_processed_block`
+    /// while the last sealed L1 batch is `last_sealed_block`.
+    pub fn observe(
+        &mut self,
+        now: Instant,
+        last_processed_block: u32,
+        last_sealed_block: u32,
+    ) -> CatchupProgress {
+        self.samples.push_back((now, last_processed_block));
+        // Retain a single sample outside the window, so that the throughput is computed
+        // over the entire window.
+        while self.samples.len() > 2 && now.duration_since(self.samples[1].0) >= self.window {
+            self.samples.pop_front();
+        }
+
+        let (first_time, first_block) = self.samples[0];
+        let elapsed = now.duration_since(first_time).as_secs_f64();
+        let processed_blocks = last_processed_block.saturating_sub(first_block);
+        let blocks_per_sec = if elapsed > 0.0 {
+            f64::from(processed_blocks) / elapsed
+        } else {
+            0.0
+        };
+        let blocks_behind = last_sealed_block.saturating_sub(last_processed_block);
+        let eta = if blocks_behind == 0 {
+            Some(Duration::ZERO)
+        } else if blocks_per_sec > 0.0 {
+            Some(Duration::from_secs_f64(
+                f64::from(blocks_behind) / blocks_per_sec,
+            ))
+        } else {
+            None
+        };
+        CatchupProgress {
+            blocks_behind,
+            blocks_per_sec,
+            eta,
+        }
+    }
+}
+
 impl MetadataCalculator {
     pub(super) fn update_metrics(
         mode: MetadataCalculatorMode,
@@ -1,6 +1,7 @@
 //! This module applies updates to the ZkSyncTree, calculates metadata for sealed blocks, and
 //! stores them in the DB.
 
+use serde::Serialize;
 use tokio::sync::watch;
 
 use std::time::Duration;
@@ -13,6 +14,7 @@ use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{
     block::L1BatchHeader,
     commitment::{BlockCommitment, BlockMetadata, BlockWithMetadata},
+    L1BatchNumber, H256,
 };
 
 mod healthcheck;
@@ -22,7 +24,7 @@ mod metrics;
 mod tests;
 mod updater;
 
-pub use self::healthcheck::TreeHealthCheck;
+pub use self::healthcheck::{TreeConsistencyHealthCheck, TreeHealthCheck};
 pub(crate) use self::helpers::get_logs_for_l1_batch;
 use self::{helpers::Delayer, metrics::TreeUpdateStage, updater::TreeUpdater};
 
@@ -47,6 +49,14 @@ pub enum MetadataCalculatorStatus {
     NotReady,
 }
 
+/// Root hash of the tree after processing a certain L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TreeRootInfo {
+    /// Number of the last L1 batch processed by the tree.
+    pub l1_batch_number: L1BatchNumber,
+    pub root_hash: H256,
+}
+
 /// Part of [`MetadataCalculator`] related to its syncing mode.
 #[derive(Debug, Clone, Copy)]
 pub enum MetadataCalculatorModeConfig<'a> {
@@ -79,7 +89,9 @@ pub struct MetadataCalculatorConfig<'a> {
     pub mode: MetadataCalculatorModeConfig<'a>,
     /// Interval between polling Postgres for updates if no progress was made by the tree.
     pub delay_interval: Duration,
-    /// Maximum number of L1 batches to get from Postgres on a single update iteration.
+    /// Maximum number of L1 batches processed by the tree on a single update iteration. Larger values
+    /// improve throughput when the tree catches up with Postgres; during normal operation, the tree
+    /// processes L1 batches one by one anyway as they get sealed. Must be positive.
     pub max_block_batch: usize,
     /// Sleep interval between tree updates if the tree has made progress. This is only applied
     /// to the tree in the lightweight mode.
@@ -108,12 +120,12 @@ pub struct MetadataCalculator {
     delayer: Delayer,
     throttler: Delayer,
     status_sender: watch::Sender<MetadataCalculatorStatus>,
+    root_info_sender: watch::Sender<Option<TreeRootInfo>>,
 }
 
 impl MetadataCalculator {
     /// Creates a calculator with the specified `config`.
     pub async fn new(config: &MetadataCalculatorConfig<'_>) -> Self {
-
         let mode = config.mode.to_mode();
         let object_store = match config.mode {
             MetadataCalculatorModeConfig::Full { store_factory } => {
@@ -128,18 +140,37 @@ impl MetadataCalculator {
             Duration::ZERO
         };
         let (status_sender, _) = watch::channel(MetadataCalculatorStatus::NotReady);
+        let (root_info_sender, _) = watch::channel(None);
         Self {
             updater,
             delayer: Delayer::new(config.delay_interval),
             throttler: Delayer::new(throttle_interval),
             status_sender,
+            root_info_sender,
         }
     }
 
+    /// Creates a calculator with the tree stored in a unique temporary directory, which is removed
+    /// once the calculator is dropped. `config.db_path` is ignored.
+    #[cfg(test)]
+    pub(crate) async fn with_temp_db(config: &MetadataCalculatorConfig<'_>) -> Self {
+        let temp_dir =
+            tempfile::TempDir::new().expect("failed creating temporary directory for RocksDB");
+        let db_path = temp_dir
+            .path()
+            .to_str()
+            .expect("non-UTF-8 temporary directory path");
+        let config = MetadataCalculatorConfig { db_path, ..*config };
+        let mut calculator = Self::new(&config).await;
+        calculator.updater.temp_dir = Some(temp_dir);
+        calculator
+    }
+
     /// Returns a health check for this calculator.
     pub fn tree_health_check(&self) -> TreeHealthCheck {
         let receiver = self.status_sender.subscribe();
-        TreeHealthCheck::new(receiver, self.updater.mode())
+        let root_info_receiver = self.root_info_sender.subscribe();
+        TreeHealthCheck::new(receiver, root_info_receiver, self.updater.mode())
     }
 
     /// Returns the tag for this calculator usable in metrics reporting.
@@ -160,6 +191,7 @@ impl MetadataCalculator {
             &prover_pool,
             stop_receiver,
             self.status_sender,
+            self.root_info_sender,
         );
         update_task.await;
     }
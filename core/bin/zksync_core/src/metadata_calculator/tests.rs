@@ -18,6 +18,7 @@ use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_health_check::{CheckHealth, CheckHealthStatus};
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_types::{
+    aggregated_operations::{AggregatedActionType, BlocksCommitOperation},
     block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
     commitment::BlockCommitment,
     proofs::PrepareBasicCircuitsJob,
@@ -26,7 +27,11 @@ use zksync_types::{
 };
 use zksync_utils::{miniblock_hash, u32_to_h256};
 
-use super::{MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig};
+use super::{
+    metrics::CatchupTracker, MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorMode,
+    MetadataCalculatorModeConfig, MetadataCalculatorStatus, TreeConsistencyHealthCheck,
+    TreeHealthCheck, TreeRootInfo,
+};
 use crate::genesis::{create_genesis_block, save_genesis_block_metadata};
 
 const RUN_TIMEOUT: Duration = Duration::from_secs(15);
@@ -80,12 +85,12 @@ async fn status_receiver_has_correct_states(pool: ConnectionPool, prover_pool: C
     let tree_health_check = calculator.tree_health_check();
     assert_matches!(
         tree_health_check.check_health().await,
-        CheckHealthStatus::NotReady(msg) if msg.contains("full")
+        CheckHealthStatus::Degraded(msg) if msg.contains("full")
     );
     let other_tree_health_check = calculator.tree_health_check();
     assert_matches!(
         other_tree_health_check.check_health().await,
-        CheckHealthStatus::NotReady(msg) if msg.contains("full")
+        CheckHealthStatus::Degraded(msg) if msg.contains("full")
     );
     reset_db_state(&pool, 1).await;
     run_calculator(calculator, pool, prover_pool).await;
@@ -99,6 +104,147 @@ async fn status_receiver_has_correct_states(pool: ConnectionPool, prover_pool: C
     );
 }
 
+#[db_test]
+async fn health_check_reports_tree_root_hash(pool: ConnectionPool, prover_pool: ConnectionPool) {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+
+    let (calculator, _) = setup_calculator(temp_dir.path(), &pool).await;
+    let tree_health_check = calculator.tree_health_check();
+    assert_eq!(tree_health_check.root_info(), None);
+    assert_eq!(tree_health_check.details(), None);
+
+    reset_db_state(&pool, 1).await;
+    let root_hash = run_calculator(calculator, pool, prover_pool).await;
+    assert_ne!(root_hash, H256::zero());
+
+    let root_info = tree_health_check.root_info().unwrap();
+    assert_eq!(root_info.l1_batch_number, L1BatchNumber(1));
+    assert_eq!(root_info.root_hash, root_hash);
+    let details = tree_health_check.details().unwrap();
+    assert_eq!(details["l1_batch_number"], 1);
+    assert_eq!(
+        details["root_hash"],
+        serde_json::to_value(root_hash).unwrap()
+    );
+}
+
+#[db_test]
+async fn tree_consistency_health_check(pool: ConnectionPool, prover_pool: ConnectionPool) {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+
+    let (calculator, _) = setup_calculator(temp_dir.path(), &pool).await;
+    let tree_health_check = calculator.tree_health_check();
+    let consistency_check = TreeConsistencyHealthCheck::new(tree_health_check, pool.clone());
+    reset_db_state(&pool, 1).await;
+    let root_hash = run_calculator(calculator, pool.clone(), prover_pool).await;
+    // The L1 batch isn't committed yet, so there's nothing to compare the tree root with.
+    assert_eq!(
+        consistency_check.check_health().await,
+        CheckHealthStatus::Ready
+    );
+
+    // A commit transaction not sent by this node has no calldata to compare the tree root with.
+    pool.access_storage()
+        .await
+        .eth_sender_dal()
+        .insert_bogus_confirmed_eth_tx(
+            L1BatchNumber(1),
+            AggregatedActionType::CommitBlocks,
+            H256::repeat_byte(1),
+            chrono::Utc::now(),
+        )
+        .await;
+    assert_eq!(
+        consistency_check.check_health().await,
+        CheckHealthStatus::Ready
+    );
+
+    commit_l1_batch(&pool, L1BatchNumber(1)).await;
+    assert_eq!(
+        consistency_check.check_health().await,
+        CheckHealthStatus::Ready
+    );
+
+    // Mock a tree that has computed a different root hash for the same L1 batch.
+    let (_status_sender, status_receiver) = watch::channel(MetadataCalculatorStatus::Ready);
+    let diverged_root_info = TreeRootInfo {
+        l1_batch_number: L1BatchNumber(1),
+        root_hash: H256::repeat_byte(0xff),
+    };
+    assert_ne!(diverged_root_info.root_hash, root_hash);
+    let (_root_info_sender, root_info_receiver) = watch::channel(Some(diverged_root_info));
+    let diverged_tree_health_check = TreeHealthCheck::new(
+        status_receiver,
+        root_info_receiver,
+        MetadataCalculatorMode::Full,
+    );
+    let consistency_check = TreeConsistencyHealthCheck::new(diverged_tree_health_check, pool);
+    assert_matches!(
+        consistency_check.check_health().await,
+        CheckHealthStatus::NotReady(message) if message.contains("L1 batch #1")
+    );
+}
+
+/// Emulates a confirmed commit transaction for the specified L1 batch sent by the ETH sender.
+async fn commit_l1_batch(pool: &ConnectionPool, number: L1BatchNumber) {
+    let mut storage = pool.access_storage().await;
+    let mut blocks = vec![];
+    for number in [number - 1, number] {
+        let storage_block = storage
+            .blocks_dal()
+            .get_storage_block(number)
+            .await
+            .unwrap();
+        let block = storage
+            .blocks_dal()
+            .get_block_with_metadata(storage_block)
+            .await
+            .unwrap();
+        blocks.push(block);
+    }
+    let block = blocks.pop().unwrap();
+    let commit_operation = BlocksCommitOperation {
+        last_committed_block: blocks.pop().unwrap(),
+        blocks: vec![block],
+    };
+    let calldata = zksync_contracts::zksync_contract()
+        .function("commitBlocks")
+        .unwrap()
+        .encode_input(&commit_operation.get_eth_tx_args())
+        .unwrap();
+
+    let eth_tx = storage
+        .eth_sender_dal()
+        .save_eth_tx(
+            0,
+            calldata,
+            None,
+            AggregatedActionType::CommitBlocks,
+            Address::zero(),
+            0,
+        )
+        .await;
+    let tx_hash = H256::repeat_byte(2);
+    storage
+        .eth_sender_dal()
+        .insert_tx_history(eth_tx.id, 0, 0, tx_hash, vec![])
+        .await
+        .unwrap();
+    storage
+        .eth_sender_dal()
+        .confirm_tx(tx_hash, 0.into(), None)
+        .await;
+    storage
+        .blocks_dal()
+        .set_eth_tx_id(
+            number,
+            number,
+            eth_tx.id,
+            AggregatedActionType::CommitBlocks,
+        )
+        .await;
+}
+
 #[db_test]
 async fn multi_block_workflow(pool: ConnectionPool, prover_pool: ConnectionPool) {
     // Run all transactions as a single block
@@ -220,6 +366,82 @@ async fn throttling_tree(pool: ConnectionPool, prover_pool: ConnectionPool) {
     delay_rx.try_recv().unwrap_err();
 }
 
+#[db_test]
+async fn processing_multiple_l1_batches_per_iteration(
+    pool: ConnectionPool,
+    prover_pool: ConnectionPool,
+) {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (mut db_config, operation_config, eth) = create_config(temp_dir.path());
+    db_config.new_merkle_tree_throttle_ms = 10;
+    db_config.max_block_batch = 2;
+    let mut calculator = setup_calculator_with_options(
+        &db_config,
+        &operation_config,
+        &eth,
+        &pool,
+        MetadataCalculatorModeConfig::Lightweight,
+    )
+    .await;
+    let (delay_sx, mut delay_rx) = mpsc::unbounded_channel();
+    calculator.throttler.delay_notifier = delay_sx;
+    reset_db_state(&pool, 5).await;
+    run_calculator(calculator, pool, prover_pool).await;
+
+    // The tree is throttled after each iteration that made progress, i.e., after processing
+    // L1 batches #1-2, #3-4 and #5.
+    let mut block_counts = vec![];
+    while let Ok((block_count, _)) = delay_rx.try_recv() {
+        block_counts.push(block_count);
+    }
+    assert_eq!(block_counts, [3, 5, 6]);
+}
+
+#[test]
+fn catchup_tracker_computes_throughput_over_window() {
+    let start = Instant::now();
+    let mut tracker = CatchupTracker::new(Duration::from_secs(10));
+    let progress = tracker.observe(start, 0, 100);
+    assert_eq!(progress.blocks_behind, 100);
+    assert_eq!(progress.blocks_per_sec, 0.0);
+    assert_eq!(progress.eta, None);
+
+    // Process 10 L1 batches per second.
+    for i in 1..=10 {
+        let now = start + Duration::from_secs(i);
+        let progress = tracker.observe(now, i as u32 * 10, 100);
+        assert_eq!(progress.blocks_behind, 100 - i as u32 * 10);
+        assert!(
+            (progress.blocks_per_sec - 10.0).abs() < 1e-6,
+            "{progress:?}"
+        );
+    }
+
+    // A spike in throughput is smoothed by the window.
+    let progress = tracker.observe(start + Duration::from_secs(11), 150, 200);
+    assert_eq!(progress.blocks_behind, 50);
+    assert!(
+        (progress.blocks_per_sec - 14.0).abs() < 1e-6,
+        "{progress:?}"
+    );
+    let eta = progress.eta.unwrap().as_secs_f64();
+    assert!((eta - 50.0 / 14.0).abs() < 1e-3, "{eta}");
+
+    // Once the tree stalls for the entire window, the throughput drops to zero.
+    let progress = tracker.observe(start + Duration::from_secs(15), 150, 200);
+    assert!(
+        (progress.blocks_per_sec - 10.0).abs() < 1e-6,
+        "{progress:?}"
+    );
+    let progress = tracker.observe(start + Duration::from_secs(30), 150, 200);
+    assert_eq!(progress.blocks_per_sec, 0.0);
+    assert_eq!(progress.eta, None);
+
+    let progress = tracker.observe(start + Duration::from_secs(31), 200, 200);
+    assert_eq!(progress.blocks_behind, 0);
+    assert_eq!(progress.eta, Some(Duration::ZERO));
+}
+
 #[db_test]
 async fn shutting_down_calculator(pool: ConnectionPool, prover_pool: ConnectionPool) {
     let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
@@ -247,6 +469,29 @@ async fn shutting_down_calculator(pool: ConnectionPool, prover_pool: ConnectionP
         .unwrap();
 }
 
+#[db_test]
+async fn calculators_with_temp_db_do_not_share_state(
+    pool: ConnectionPool,
+    prover_pool: ConnectionPool,
+) {
+    let (db_config, operation_config, eth) = create_config(Path::new("unused"));
+    let mode = MetadataCalculatorModeConfig::Lightweight;
+    let calculator_config =
+        MetadataCalculatorConfig::for_main_node(&db_config, &operation_config, mode);
+    // Both calculators are alive at the same time, which would fail if they shared a RocksDB instance.
+    let calculator = MetadataCalculator::with_temp_db(&calculator_config).await;
+    let other_calculator = MetadataCalculator::with_temp_db(&calculator_config).await;
+    create_genesis_if_needed(&calculator, &eth, &pool).await;
+    reset_db_state(&pool, 1).await;
+
+    let root_hash = run_calculator(calculator, pool, prover_pool).await;
+    assert_ne!(root_hash, H256::zero());
+    assert_eq!(other_calculator.updater.tree().block_number(), 0);
+
+    let new_calculator = MetadataCalculator::with_temp_db(&calculator_config).await;
+    assert_eq!(new_calculator.updater.tree().block_number(), 0);
+}
+
 async fn setup_calculator(
     db_path: &Path,
     pool: &ConnectionPool,
@@ -285,7 +530,15 @@ async fn setup_calculator_with_options(
     let calculator_config =
         MetadataCalculatorConfig::for_main_node(db_config, operation_config, mode);
     let metadata_calculator = MetadataCalculator::new(&calculator_config).await;
+    create_genesis_if_needed(&metadata_calculator, eth, pool).await;
+    metadata_calculator
+}
 
+async fn create_genesis_if_needed(
+    metadata_calculator: &MetadataCalculator,
+    eth: &NetworkConfig,
+    pool: &ConnectionPool,
+) {
     let mut storage = pool.access_storage_tagged("metadata_calculator").await;
     if storage.blocks_dal().is_genesis_needed().await {
         let chain_id = L2ChainId(eth.zksync_network_id);
@@ -310,7 +563,6 @@ async fn setup_calculator_with_options(
         )
         .await;
     }
-    metadata_calculator
 }
 
 fn path_to_string(path: &Path) -> String {
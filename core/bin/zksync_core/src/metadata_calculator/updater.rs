@@ -13,8 +13,8 @@ use zksync_types::{block::WitnessBlockWithLogs, L1BatchNumber};
 use super::{
     get_logs_for_l1_batch,
     helpers::{AsyncTree, Delayer},
-    metrics::TreeUpdateStage,
-    MetadataCalculator, MetadataCalculatorMode, MetadataCalculatorStatus,
+    metrics::{CatchupTracker, TreeUpdateStage},
+    MetadataCalculator, MetadataCalculatorMode, MetadataCalculatorStatus, TreeRootInfo,
 };
 
 #[derive(Debug)]
@@ -23,6 +23,11 @@ pub(super) struct TreeUpdater {
     tree: AsyncTree,
     max_block_batch: usize,
     object_store: Option<Box<dyn ObjectStore>>,
+    catchup_tracker: CatchupTracker,
+    /// Temporary directory containing the tree RocksDB. Declared last so that it's removed
+    /// only after the tree is dropped.
+    #[cfg(test)]
+    pub(super) temp_dir: Option<tempfile::TempDir>,
 }
 
 impl TreeUpdater {
@@ -48,6 +53,9 @@ impl TreeUpdater {
             tree,
             max_block_batch,
             object_store,
+            catchup_tracker: CatchupTracker::new(CatchupTracker::DEFAULT_WINDOW),
+            #[cfg(test)]
+            temp_dir: None,
         }
     }
 
@@ -71,6 +79,13 @@ impl TreeUpdater {
         self.mode
     }
 
+    fn root_info(&self) -> TreeRootInfo {
+        TreeRootInfo {
+            l1_batch_number: L1BatchNumber(self.tree.block_number() - 1),
+            root_hash: self.tree.root_hash(),
+        }
+    }
+
     async fn process_multiple_blocks(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -193,6 +208,12 @@ impl TreeUpdater {
             self.process_multiple_blocks(&mut storage, &mut prover_storage, new_blocks)
                 .await;
         }
+
+        let last_processed_block = next_block_to_seal.0.saturating_sub(1);
+        let progress =
+            self.catchup_tracker
+                .observe(Instant::now(), last_processed_block, last_sealed_block.0);
+        progress.report(self.mode);
     }
 
     /// The processing loop for this updater.
@@ -204,6 +225,7 @@ impl TreeUpdater {
         prover_pool: &ConnectionPool,
         mut stop_receiver: watch::Receiver<bool>,
         status_sender: watch::Sender<MetadataCalculatorStatus>,
+        root_info_sender: watch::Sender<Option<TreeRootInfo>>,
     ) {
         let mut storage = pool.access_storage_tagged("metadata_calculator").await;
 
@@ -211,7 +233,15 @@ impl TreeUpdater {
         let tree = &mut self.tree;
         if tree.is_empty() {
             let Some(logs) = get_logs_for_l1_batch(&mut storage, L1BatchNumber(0)).await else {
-                panic!("Missing storage logs for the genesis block");
+                // The storage may have been recovered from a snapshot (see `snapshot_importer`),
+                // in which case there is no genesis block. The tree cannot be recovered from
+                // a snapshot, so we fail loudly instead of waiting for the genesis block forever.
+                let sealed_block_number = storage.blocks_dal().get_sealed_block_number().await;
+                panic!(
+                    "Missing storage logs for the genesis block, while Postgres has sealed L1 batch \
+                     #{sealed_block_number}. If the storage was recovered from a snapshot, note that \
+                     Merkle tree recovery from snapshots is not supported"
+                );
             };
             tree.process_block(logs.storage_logs).await;
             tree.save().await;
@@ -237,6 +267,7 @@ impl TreeUpdater {
             (last_block_number_with_metadata - *next_block_to_seal).0 as f64
         );
         status_sender.send_replace(MetadataCalculatorStatus::Ready);
+        root_info_sender.send_replace(Some(self.root_info()));
 
         loop {
             if *stop_receiver.borrow_and_update() {
@@ -262,6 +293,7 @@ impl TreeUpdater {
                     "Metadata calculator (next L1 batch: #{next_block_to_seal}) \
                      made progress from #{next_block_snapshot}; throttling it using {throttler:?}"
                 );
+                root_info_sender.send_replace(Some(self.root_info()));
                 throttler.wait(&self.tree)
             };
 
@@ -1,13 +1,13 @@
-use std::time::Duration;
+use std::{future::Future, time::Duration};
 use zksync_web3_decl::{
     jsonrpsee::core::Error as RpcError,
     jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
-    namespaces::ZksNamespaceClient,
+    namespaces::{EthNamespaceClient, ZksNamespaceClient},
     RpcResult,
 };
 
 use zksync_dal::ConnectionPool;
-use zksync_types::L1BatchNumber;
+use zksync_types::{api::BlockNumber, L1BatchNumber, MiniblockNumber, H256};
 
 const SLEEP_INTERVAL: Duration = Duration::from_secs(5);
 
@@ -22,13 +22,18 @@ const SLEEP_INTERVAL: Duration = Duration::from_secs(5);
 /// We then perform a binary search to find the latest correct block
 /// and revert all batches after it, to keep being consistent with the main node.
 ///
-/// This is the only component that is expected to finish its execution
-/// in the even of reorg, since we have to restart the node after a rollback is performed,
-/// and is special-cased in the `zksync_external_node` crate.
+/// This is the only component that is expected to finish its execution in the event of reorg.
+/// It is special-cased in the `zksync_external_node` crate, which reverts the node state
+/// to the last correct batch and restarts the sync components.
+///
+/// If the maximum revert depth is set, the detector refuses to return a batch that would require
+/// reverting more batches than allowed and returns an error instead, so that the node halts
+/// and the reorg can be investigated manually.
 #[derive(Debug)]
 pub struct ReorgDetector {
     client: HttpClient,
     pool: ConnectionPool,
+    max_revert_depth: Option<u32>,
 }
 
 impl ReorgDetector {
@@ -36,7 +41,17 @@ impl ReorgDetector {
         let client = HttpClientBuilder::default()
             .build(url)
             .expect("Failed to create HTTP client");
-        Self { client, pool }
+        Self {
+            client,
+            pool,
+            max_revert_depth: None,
+        }
+    }
+
+    /// Sets the maximum number of L1 batches that may be reverted because of a detected reorg.
+    pub fn with_max_revert_depth(mut self, max_revert_depth: Option<u32>) -> Self {
+        self.max_revert_depth = max_revert_depth;
+        self
     }
 
     /// Compares root hashes of the latest local batch and of the same batch from the main node.
@@ -75,10 +90,67 @@ impl ReorgDetector {
         .map(L1BatchNumber)
     }
 
-    pub async fn run(self) -> L1BatchNumber {
+    /// Reports the depth of the detected divergence, i.e. the number of local miniblocks after
+    /// the last miniblock shared with the main node. This is best-effort: errors are logged
+    /// and don't influence the reorg handling.
+    async fn report_divergence(&self, last_correct_batch: L1BatchNumber) {
+        metrics::counter!("reorg_detector.divergences_total", 1);
+        match self.divergence_depth(last_correct_batch).await {
+            Ok((depth, sealed_miniblock)) => {
+                vlog::info!(
+                    "Divergence depth is {depth} miniblocks (local tip: #{sealed_miniblock})"
+                );
+                metrics::gauge!("reorg_detector.last_divergence_depth", depth as f64);
+            }
+            Err(err) => {
+                vlog::warn!("Failed computing divergence depth, skipping its reporting: {err:#}");
+            }
+        }
+    }
+
+    /// Returns the divergence depth together with the local sealed miniblock.
+    async fn divergence_depth(
+        &self,
+        last_correct_batch: L1BatchNumber,
+    ) -> anyhow::Result<(usize, MiniblockNumber)> {
+        let mut storage = self.pool.access_storage().await;
+        let mut blocks_dal = storage.blocks_web3_dal();
+        let first_diverged_miniblock = blocks_dal
+            .get_miniblock_range_of_l1_batch(last_correct_batch)
+            .await?
+            .map_or(MiniblockNumber(0), |(_, last_miniblock)| last_miniblock + 1);
+        let sealed_miniblock = blocks_dal.get_sealed_miniblock_number().await?;
+        let local_hashes = blocks_dal
+            .get_miniblock_hashes_in_range(first_diverged_miniblock, sealed_miniblock)
+            .await?;
+        drop(storage);
+
+        let depth = divergence_depth(&local_hashes, |number| async move {
+            let block = self
+                .client
+                .get_block_by_number(BlockNumber::Number(number.0.into()), false)
+                .await?;
+            Ok::<_, RpcError>(block.map(|block| block.hash))
+        })
+        .await?;
+        Ok((depth, sealed_miniblock))
+    }
+
+    /// Runs the detector until a reorg is detected. Returns the last correct L1 batch, or an error
+    /// if the reorg is too deep to be reverted automatically.
+    pub async fn run(self) -> anyhow::Result<L1BatchNumber> {
         loop {
             match self.run_inner().await {
-                Ok(batch_number) => return batch_number,
+                Ok(last_correct_batch) => {
+                    self.report_divergence(last_correct_batch.number).await;
+                    check_revert_depth(
+                        last_correct_batch.sealed_batch,
+                        last_correct_batch.number,
+                        self.max_revert_depth,
+                    )
+                    .map_err(anyhow::Error::msg)?;
+                    return Ok(last_correct_batch.number);
+                }
                 Err(err @ RpcError::Transport(_) | err @ RpcError::RequestTimeout) => {
                     vlog::warn!("Following transport error occurred: {}", err);
                     vlog::info!("Trying again after a delay");
@@ -121,7 +193,7 @@ impl ReorgDetector {
         Ok(main_node_batch_root_hash.is_none() && en_ahead_for <= Some(1))
     }
 
-    async fn run_inner(&self) -> RpcResult<L1BatchNumber> {
+    async fn run_inner(&self) -> RpcResult<LastCorrectBatch> {
         loop {
             let sealed_block_number = self
                 .pool
@@ -157,16 +229,71 @@ impl ReorgDetector {
                 vlog::info!("Searching for the first diverged batch");
                 let last_correct_block = self.detect_reorg(sealed_block_number).await?;
                 vlog::info!("Reorg localized: last correct batch is #{last_correct_block}",);
-                return Ok(last_correct_block);
+                return Ok(LastCorrectBatch {
+                    number: last_correct_block,
+                    sealed_batch: sealed_block_number,
+                });
             }
         }
     }
 }
 
+/// Result of localizing a reorg.
+#[derive(Debug)]
+struct LastCorrectBatch {
+    number: L1BatchNumber,
+    /// Latest local batch at the time the reorg was detected.
+    sealed_batch: L1BatchNumber,
+}
+
+/// Checks that reverting from `sealed_batch` to `last_correct_batch` doesn't exceed the maximum
+/// revert depth. Returns the number of batches to revert.
+fn check_revert_depth(
+    sealed_batch: L1BatchNumber,
+    last_correct_batch: L1BatchNumber,
+    max_revert_depth: Option<u32>,
+) -> Result<u32, String> {
+    let revert_depth = sealed_batch.0 - last_correct_batch.0;
+    match max_revert_depth {
+        Some(max_depth) if revert_depth > max_depth => Err(format!(
+            "Reorg requires reverting {revert_depth} batches (from #{sealed_batch} to \
+             #{last_correct_batch}), which exceeds the limit of {max_depth} batches; \
+             manual intervention is required"
+        )),
+        _ => Ok(revert_depth),
+    }
+}
+
+/// Computes the divergence depth, i.e. the number of miniblocks in `local_hashes` starting from
+/// the first miniblock that doesn't match the main node. `local_hashes` must be consecutive
+/// and end at the local tip. Since matching miniblocks form a prefix of `local_hashes`,
+/// the first diverged miniblock is found using binary search.
+async fn divergence_depth<F, Fut, E>(
+    local_hashes: &[(MiniblockNumber, H256)],
+    mut main_node_hash: F,
+) -> Result<usize, E>
+where
+    F: FnMut(MiniblockNumber) -> Fut,
+    Fut: Future<Output = Result<Option<H256>, E>>,
+{
+    // All miniblocks before `left` match, and all miniblocks starting from `right` are diverged.
+    let (mut left, mut right) = (0, local_hashes.len());
+    while left < right {
+        let middle = (left + right) / 2;
+        let (number, local_hash) = local_hashes[middle];
+        if main_node_hash(number).await? == Some(local_hash) {
+            left = middle + 1;
+        } else {
+            right = middle;
+        }
+    }
+    Ok(local_hashes.len() - left)
+}
+
 async fn binary_search_with<F, Fut, E>(mut left: u32, mut right: u32, mut f: F) -> Result<u32, E>
 where
     F: FnMut(u32) -> Fut,
-    Fut: std::future::Future<Output = Result<bool, E>>,
+    Fut: Future<Output = Result<bool, E>>,
 {
     while left + 1 < right {
         let middle = (left + right) / 2;
@@ -181,6 +308,8 @@ where
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     /// Tests the binary search algorithm.
     #[tokio::test]
     async fn test_binary_search() {
@@ -190,4 +319,63 @@ mod tests {
             assert_eq!(result, Ok(divergence_point - 1));
         }
     }
+
+    #[tokio::test]
+    async fn shallow_reorg_is_reverted_to_common_ancestor() {
+        let local_hashes: Vec<_> = (0..=10).map(H256::from_low_u64_be).collect();
+        // The main node has re-sealed the last 2 batches.
+        let mut main_node_hashes = local_hashes.clone();
+        main_node_hashes[9] = H256::repeat_byte(0xff);
+        main_node_hashes[10] = H256::repeat_byte(0xfe);
+
+        let sealed_batch = L1BatchNumber(10);
+        let last_correct_batch = binary_search_with(1, sealed_batch.0, |number| {
+            let hashes_match = local_hashes[number as usize] == main_node_hashes[number as usize];
+            async move { Ok::<_, ()>(hashes_match) }
+        })
+        .await
+        .map(L1BatchNumber)
+        .unwrap();
+        assert_eq!(last_correct_batch, L1BatchNumber(8));
+
+        let revert_depth = check_revert_depth(sealed_batch, last_correct_batch, Some(5));
+        assert_eq!(revert_depth, Ok(2));
+        let revert_depth = check_revert_depth(sealed_batch, last_correct_batch, None);
+        assert_eq!(revert_depth, Ok(2));
+        let err = check_revert_depth(sealed_batch, last_correct_batch, Some(1)).unwrap_err();
+        assert!(err.contains("exceeds the limit"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn divergence_depth_is_computed_from_common_ancestor() {
+        let local_hashes: Vec<_> = (11..=20)
+            .map(|number| {
+                (
+                    MiniblockNumber(number),
+                    H256::from_low_u64_be(number.into()),
+                )
+            })
+            .collect();
+        // The main node has diverged at miniblock #18 and doesn't have miniblock #20.
+        let main_node_hash = |number: MiniblockNumber| async move {
+            let hash = match number.0 {
+                0..=17 => Some(H256::from_low_u64_be(number.0.into())),
+                18 | 19 => Some(H256::repeat_byte(0xff)),
+                _ => None,
+            };
+            Ok::<_, ()>(hash)
+        };
+        let depth = divergence_depth(&local_hashes, main_node_hash).await;
+        assert_eq!(depth, Ok(3));
+
+        let main_node_hash = |number: MiniblockNumber| async move {
+            Ok::<_, ()>(Some(H256::from_low_u64_be(number.0.into())))
+        };
+        let depth = divergence_depth(&local_hashes, main_node_hash).await;
+        assert_eq!(depth, Ok(0));
+
+        let main_node_hash = |_| async { Ok::<_, ()>(None) };
+        let depth = divergence_depth(&local_hashes, main_node_hash).await;
+        assert_eq!(depth, Ok(10));
+    }
 }
@@ -0,0 +1,168 @@
+//! Reporting of the reason why the node shuts down.
+//!
+//! Each shutdown path (a stop signal, a circuit breaker, a reorg etc.) reports its reason via
+//! a [`ShutdownReasonSender`]. The binary waits for the first reported reason, logs it and chooses
+//! the process exit code based on it.
+
+use futures::channel::oneshot;
+use tokio::sync::{mpsc, watch};
+
+use std::fmt;
+
+use zksync_circuit_breaker::{CircuitBreakerChecker, CircuitBreakerError};
+use zksync_types::L1BatchNumber;
+
+/// Reason of the node shutdown.
+#[derive(Debug)]
+pub enum ShutdownReason {
+    /// Stop signal (e.g., SIGINT) was received.
+    StopSignal,
+    /// One of circuit breakers was triggered.
+    CircuitBreaker(CircuitBreakerError),
+    /// One of component tasks terminated unexpectedly (e.g., panicked).
+    ComponentTerminated,
+    /// A reorg was detected; the node state was rolled back to the specified L1 batch.
+    Reorg(L1BatchNumber),
+}
+
+impl fmt::Display for ShutdownReason {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StopSignal => formatter.write_str("stop signal received"),
+            Self::CircuitBreaker(err) => write!(formatter, "circuit breaker triggered: {err}"),
+            Self::ComponentTerminated => formatter.write_str("component terminated unexpectedly"),
+            Self::Reorg(l1_batch_number) => {
+                write!(
+                    formatter,
+                    "rolled back to L1 batch #{l1_batch_number} after a reorg"
+                )
+            }
+        }
+    }
+}
+
+impl ShutdownReason {
+    /// Returns the process exit code corresponding to this reason. Shutdowns caused
+    /// by failures have a non-zero exit code.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::StopSignal | Self::Reorg(_) => 0,
+            Self::CircuitBreaker(_) | Self::ComponentTerminated => 1,
+        }
+    }
+}
+
+/// Sending side of the channel for [`ShutdownReason`]s.
+#[derive(Debug, Clone)]
+pub struct ShutdownReasonSender(mpsc::UnboundedSender<ShutdownReason>);
+
+impl ShutdownReasonSender {
+    /// Reports the shutdown reason. Can be called from a synchronous context (e.g., a signal handler).
+    pub fn send(&self, reason: ShutdownReason) {
+        self.0.send(reason).ok();
+        // ^ The send fails if the receiver is dropped, i.e., if the node is not interested
+        // in shutdown reasons anymore.
+    }
+}
+
+/// Receiving side of the channel for [`ShutdownReason`]s.
+#[derive(Debug)]
+pub struct ShutdownReasonReceiver(mpsc::UnboundedReceiver<ShutdownReason>);
+
+impl ShutdownReasonReceiver {
+    /// Waits until a shutdown reason is reported. If all senders are dropped without reporting
+    /// a reason, waits indefinitely.
+    pub async fn recv(&mut self) -> ShutdownReason {
+        match self.0.recv().await {
+            Some(reason) => reason,
+            None => futures::future::pending().await,
+        }
+    }
+
+    /// Returns a reason if it was already reported. Useful if the node is shutting down because
+    /// one of its tasks has terminated; the task may have reported a more specific reason.
+    pub fn try_recv(&mut self) -> Option<ShutdownReason> {
+        self.0.try_recv().ok()
+    }
+}
+
+/// Creates a channel for shutdown reasons.
+pub fn shutdown_reason_channel() -> (ShutdownReasonSender, ShutdownReasonReceiver) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    (
+        ShutdownReasonSender(sender),
+        ShutdownReasonReceiver(receiver),
+    )
+}
+
+/// Runs the circuit breaker `checker` until the stop signal is received. If a circuit breaker
+/// is triggered, reports [`ShutdownReason::CircuitBreaker`] before returning.
+pub async fn run_circuit_breaker_checker(
+    checker: CircuitBreakerChecker,
+    stop_receiver: watch::Receiver<bool>,
+    shutdown_sender: ShutdownReasonSender,
+) {
+    let (cb_sender, cb_receiver) = oneshot::channel();
+    checker.run(cb_sender, stop_receiver).await;
+    // If the checker has returned without sending an error, the sender is dropped.
+    if let Ok(err) = cb_receiver.await {
+        shutdown_sender.send(ShutdownReason::CircuitBreaker(err));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use zksync_circuit_breaker::CircuitBreaker;
+    use zksync_config::configs::chain::CircuitBreakerConfig;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct TrippedCircuitBreaker;
+
+    #[async_trait]
+    impl CircuitBreaker for TrippedCircuitBreaker {
+        async fn check(&self) -> Result<(), CircuitBreakerError> {
+            Err(CircuitBreakerError::FailedL1Transaction)
+        }
+    }
+
+    #[tokio::test]
+    async fn circuit_breaker_trip_is_reported_as_shutdown_reason() {
+        let config = CircuitBreakerConfig {
+            sync_interval_ms: 10,
+            http_req_max_retry_number: 1,
+            http_req_retry_interval_sec: 1,
+        };
+        let checker = CircuitBreakerChecker::new(vec![Box::new(TrippedCircuitBreaker)], &config);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let (shutdown_sender, mut shutdown_receiver) = shutdown_reason_channel();
+
+        run_circuit_breaker_checker(checker, stop_receiver, shutdown_sender).await;
+        let reason = shutdown_receiver.try_recv().unwrap();
+        assert!(
+            matches!(
+                reason,
+                ShutdownReason::CircuitBreaker(CircuitBreakerError::FailedL1Transaction)
+            ),
+            "{reason:?}"
+        );
+        assert_eq!(reason.exit_code(), 1);
+    }
+
+    #[tokio::test]
+    async fn stopped_circuit_breaker_checker_does_not_report_reason() {
+        let config = CircuitBreakerConfig {
+            sync_interval_ms: 10,
+            http_req_max_retry_number: 1,
+            http_req_retry_interval_sec: 1,
+        };
+        let checker = CircuitBreakerChecker::new(vec![], &config);
+        let (_stop_sender, stop_receiver) = watch::channel(true);
+        let (shutdown_sender, mut shutdown_receiver) = shutdown_reason_channel();
+
+        run_circuit_breaker_checker(checker, stop_receiver, shutdown_sender).await;
+        assert!(shutdown_receiver.try_recv().is_none());
+    }
+}
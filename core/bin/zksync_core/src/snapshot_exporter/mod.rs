@@ -0,0 +1,169 @@
+//! Periodic export of state snapshots to the object store. A snapshot contains the full storage
+//! state and factory deps as of the end of the latest L1 batch with computed metadata, which allows
+//! external nodes to bootstrap without replaying the whole chain.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+
+use zksync_config::configs::SnapshotExporterConfig;
+use zksync_dal::ConnectionPool;
+use zksync_object_store::{BlobKind, ObjectStore, ObjectStoreRouter};
+use zksync_types::{
+    snapshots::{SnapshotManifest, SnapshotMetadata, StorageLogsSnapshot},
+    L1BatchNumber, MiniblockNumber, H256,
+};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug)]
+pub struct SnapshotExporter {
+    pool: ConnectionPool,
+    object_store: Box<dyn ObjectStore>,
+    export_interval: Duration,
+}
+
+impl SnapshotExporter {
+    pub async fn new(
+        config: &SnapshotExporterConfig,
+        store_router: &ObjectStoreRouter,
+        pool: ConnectionPool,
+    ) -> Self {
+        let store_factory = store_router.factory(BlobKind::Snapshots);
+        Self {
+            pool,
+            object_store: store_factory.create_store().await,
+            export_interval: config.export_interval(),
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) {
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, snapshot exporter is shutting down");
+                break;
+            }
+
+            match self.export_latest_snapshot().await {
+                Ok(Some(metadata)) => {
+                    vlog::info!(
+                        "Exported snapshot for L1 batch #{} with {} storage logs",
+                        metadata.l1_batch_number,
+                        metadata.storage_logs_count
+                    );
+                }
+                Ok(None) => {
+                    vlog::debug!("Snapshot for the latest sealed L1 batch is already exported");
+                }
+                Err(err) => {
+                    vlog::error!("Failed exporting snapshot: {err:#}");
+                    metrics::counter!("server.snapshot_exporter.errors", 1);
+                }
+            }
+            // Wake up early if the stop signal is received.
+            tokio::time::timeout(self.export_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+    }
+
+    /// Exports a snapshot for the latest L1 batch with metadata unless it's already exported.
+    /// Returns the manifest of the exported snapshot.
+    async fn export_latest_snapshot(&self) -> anyhow::Result<Option<SnapshotMetadata>> {
+        let started_at = Instant::now();
+        let mut storage = self.pool.access_storage_tagged("snapshot_exporter").await;
+        // The snapshot must contain the root hash of its L1 batch, so that the state keeper
+        // of the recovered node can start the next batch.
+        let l1_batch_number = storage
+            .blocks_dal()
+            .get_last_block_number_with_metadata()
+            .await;
+        let latest_snapshot = storage.snapshots_dal().get_latest_snapshot().await;
+        if let Some(latest_snapshot) = latest_snapshot {
+            if latest_snapshot.l1_batch_number >= l1_batch_number {
+                return Ok(None);
+            }
+        }
+
+        // The snapshot is taken at the L1 batch boundary: we include all storage logs up to
+        // the last miniblock of the batch. Since the batch is sealed, these logs cannot change.
+        let (_, miniblock_number) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await
+            .with_context(|| format!("L1 batch #{l1_batch_number} has no miniblocks"))?;
+        let l1_batch = storage
+            .blocks_dal()
+            .get_block_metadata(l1_batch_number)
+            .await
+            .with_context(|| format!("L1 batch #{l1_batch_number} or its metadata is missing"))?;
+        let previous_l1_batch_root_hash = if l1_batch_number == L1BatchNumber(0) {
+            H256::zero()
+        } else {
+            let (root_hash, _) = storage
+                .blocks_dal()
+                .get_block_state_root_and_timestamp(l1_batch_number - 1)
+                .await
+                .with_context(|| {
+                    format!("root hash of L1 batch #{} is missing", l1_batch_number - 1)
+                })?;
+            root_hash
+        };
+        let miniblock_header = storage
+            .blocks_dal()
+            .get_miniblock_header(miniblock_number)
+            .await
+            .with_context(|| format!("miniblock #{miniblock_number} is missing"))?;
+        let storage_logs = storage
+            .storage_logs_dal()
+            .get_storage_logs_for_range(MiniblockNumber(0), miniblock_number)
+            .await
+            .context("failed loading storage logs")?;
+        let factory_deps = storage
+            .storage_dal()
+            .get_factory_deps_up_to_miniblock(miniblock_number)
+            .await;
+
+        let snapshot = StorageLogsSnapshot {
+            l1_batch_number,
+            miniblock_number,
+            storage_logs,
+            factory_deps,
+        };
+        let storage_logs_url = self
+            .object_store
+            .put(l1_batch_number, &snapshot)
+            .await
+            .context("failed saving snapshot to the object store")?;
+        let manifest = SnapshotManifest {
+            l1_batch_header: l1_batch.header,
+            l1_batch_metadata: l1_batch.metadata,
+            previous_l1_batch_root_hash,
+            miniblock_header,
+            storage_logs_url: storage_logs_url.clone(),
+        };
+        self.object_store
+            .put(l1_batch_number, &manifest)
+            .await
+            .context("failed saving snapshot manifest to the object store")?;
+
+        let metadata = SnapshotMetadata {
+            l1_batch_number,
+            storage_logs_count: snapshot.storage_logs.len() as u64,
+            storage_logs_url,
+        };
+        storage.snapshots_dal().add_snapshot(&metadata).await;
+
+        metrics::histogram!(
+            "server.snapshot_exporter.export_latency",
+            started_at.elapsed()
+        );
+        metrics::gauge!(
+            "server.snapshot_exporter.l1_batch_number",
+            l1_batch_number.0 as f64
+        );
+        Ok(Some(metadata))
+    }
+}
@@ -0,0 +1,149 @@
+//! Tests for the snapshot exporter.
+
+use db_test_macro::db_test;
+
+use std::collections::HashMap;
+
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_dal::StorageProcessor;
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::{
+    block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
+    AccountTreeId, Address, L1BatchNumber, StorageKey, StorageLog, H256,
+};
+use zksync_utils::miniblock_hash;
+
+use super::*;
+use crate::state_keeper::tests::create_block_metadata;
+
+fn create_miniblock_header(number: u32) -> MiniblockHeader {
+    MiniblockHeader {
+        number: MiniblockNumber(number),
+        timestamp: number.into(),
+        hash: miniblock_hash(MiniblockNumber(number)),
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        base_fee_per_gas: 100,
+        l1_gas_price: 100,
+        l2_fair_gas_price: 100,
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+    }
+}
+
+fn storage_log(key: u64, value: u64) -> StorageLog {
+    let key = StorageKey::new(
+        AccountTreeId::new(Address::repeat_byte(1)),
+        H256::from_low_u64_be(key),
+    );
+    StorageLog::new_write_log(key, H256::from_low_u64_be(value))
+}
+
+async fn insert_l1_batch_with_metadata(storage: &mut StorageProcessor<'_>, number: u32) {
+    let mut header = L1BatchHeader::new(
+        L1BatchNumber(number),
+        number.into(),
+        Address::default(),
+        BaseSystemContractsHashes::default(),
+    );
+    header.is_finished = true;
+    storage
+        .blocks_dal()
+        .insert_l1_batch(&header, BlockGasCount::default())
+        .await;
+    storage
+        .blocks_dal()
+        .save_blocks_metadata(
+            L1BatchNumber(number),
+            &create_block_metadata(number),
+            H256::from_low_u64_be(number.saturating_sub(1).into()),
+        )
+        .await;
+}
+
+/// Prepares storage with L1 batch #0 and a sealed L1 batch #1 with metadata consisting of
+/// miniblocks #1 and #2, and a pending miniblock #3.
+async fn prepare_storage(pool: &ConnectionPool) {
+    let mut storage = pool.access_storage().await;
+    storage.blocks_dal().delete_genesis().await;
+    insert_l1_batch_with_metadata(&mut storage, 0).await;
+
+    let logs_by_miniblock = [
+        vec![storage_log(1, 1), storage_log(2, 1)],
+        vec![storage_log(1, 2)],
+        vec![storage_log(1, 3), storage_log(3, 1)],
+    ];
+    for (number, logs) in (1..).zip(logs_by_miniblock) {
+        let miniblock_number = MiniblockNumber(number);
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock_header(number))
+            .await;
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(miniblock_number, &[(H256::zero(), logs)])
+            .await;
+        let factory_dep = (H256::from_low_u64_be(number.into()), vec![number as u8; 32]);
+        storage
+            .storage_dal()
+            .insert_factory_deps(miniblock_number, &HashMap::from([factory_dep]))
+            .await;
+
+        if number == 2 {
+            insert_l1_batch_with_metadata(&mut storage, 1).await;
+            storage
+                .blocks_dal()
+                .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+                .await;
+        }
+    }
+}
+
+#[db_test]
+async fn exporting_snapshot_for_latest_batch(pool: ConnectionPool) {
+    prepare_storage(&pool).await;
+    let config = SnapshotExporterConfig {
+        export_interval_sec: 1,
+    };
+    let store_router = ObjectStoreRouter::new(ObjectStoreFactory::mock())
+        .with_factory(BlobKind::Snapshots, ObjectStoreFactory::mock());
+    let exporter = SnapshotExporter::new(&config, &store_router, pool.clone()).await;
+
+    let metadata = exporter.export_latest_snapshot().await.unwrap();
+    let metadata = metadata.expect("no snapshot exported");
+    assert_eq!(metadata.l1_batch_number, L1BatchNumber(1));
+    assert_eq!(metadata.storage_logs_count, 2);
+
+    let mut storage = pool.access_storage().await;
+    let manifest = storage.snapshots_dal().get_latest_snapshot().await;
+    assert_eq!(manifest.as_ref(), Some(&metadata));
+    drop(storage);
+
+    let object_store = store_router
+        .factory(BlobKind::Snapshots)
+        .create_store()
+        .await;
+    let snapshot: StorageLogsSnapshot = object_store.get(L1BatchNumber(1)).await.unwrap();
+    assert_eq!(snapshot.l1_batch_number, L1BatchNumber(1));
+    assert_eq!(snapshot.miniblock_number, MiniblockNumber(2));
+    let manifest: SnapshotManifest = object_store.get(L1BatchNumber(1)).await.unwrap();
+    assert_eq!(manifest.l1_batch_header.number, L1BatchNumber(1));
+    assert_eq!(manifest.l1_batch_metadata, create_block_metadata(1));
+    assert_eq!(manifest.previous_l1_batch_root_hash, H256::zero());
+    assert_eq!(manifest.miniblock_header, create_miniblock_header(2));
+    assert_eq!(manifest.storage_logs_url, metadata.storage_logs_url);
+    // Writes in the pending miniblock #3 must not be included.
+    let mut expected_logs = vec![storage_log(1, 2), storage_log(2, 1)];
+    expected_logs.sort_unstable_by_key(|log| log.key.hashed_key());
+    assert_eq!(snapshot.storage_logs, expected_logs);
+    // Factory deps from the pending miniblock #3 must not be included either.
+    let mut factory_dep_hashes: Vec<_> = snapshot.factory_deps.keys().copied().collect();
+    factory_dep_hashes.sort_unstable();
+    assert_eq!(
+        factory_dep_hashes,
+        [H256::from_low_u64_be(1), H256::from_low_u64_be(2)]
+    );
+
+    // The snapshot for the latest batch is already exported.
+    let metadata = exporter.export_latest_snapshot().await.unwrap();
+    assert!(metadata.is_none());
+}
@@ -0,0 +1,115 @@
+//! Recovery of the node state from a snapshot exported by
+//! [`SnapshotExporter`](crate::snapshot_exporter::SnapshotExporter). Allows bootstrapping a node
+//! from the snapshot's L1 batch instead of running genesis and replaying the whole chain.
+//!
+//! The importer initializes Postgres with the L1 batch header and metadata (including the root hash),
+//! the last miniblock header, storage logs and factory deps, so that the state keeper and the sync
+//! layer continue from the L1 batch / miniblock following the snapshot.
+//!
+//! The Merkle tree is **not** recovered: leaf indices of the tree are not a part of the snapshot,
+//! so the tree cannot reproduce root hashes of the following L1 batches. The metadata calculator
+//! refuses to start on an empty tree if the storage was recovered from a snapshot.
+
+use anyhow::Context as _;
+
+use zksync_dal::StorageProcessor;
+use zksync_object_store::{ObjectStore, ObjectStoreFactory};
+use zksync_types::{
+    block::BlockGasCount,
+    snapshots::{SnapshotManifest, StorageLogsSnapshot},
+    L1BatchNumber, H256,
+};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug)]
+pub struct SnapshotImporter {
+    object_store: Box<dyn ObjectStore>,
+}
+
+impl SnapshotImporter {
+    pub async fn new(store_factory: &ObjectStoreFactory) -> Self {
+        Self {
+            object_store: store_factory.create_store().await,
+        }
+    }
+
+    /// Imports the snapshot for the specified L1 batch into the storage, which must be empty.
+    /// Returns the manifest of the imported snapshot.
+    pub async fn import_snapshot(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<SnapshotManifest> {
+        if !storage.blocks_dal().is_genesis_needed().await {
+            anyhow::bail!("cannot import snapshot into non-empty storage");
+        }
+
+        let manifest: SnapshotManifest = self
+            .object_store
+            .get(l1_batch_number)
+            .await
+            .context("failed loading snapshot manifest")?;
+        let snapshot: StorageLogsSnapshot = self
+            .object_store
+            .get(l1_batch_number)
+            .await
+            .context("failed loading snapshot storage logs")?;
+        let miniblock_number = manifest.miniblock_header.number;
+        anyhow::ensure!(
+            manifest.l1_batch_header.number == l1_batch_number
+                && snapshot.l1_batch_number == l1_batch_number
+                && snapshot.miniblock_number == miniblock_number,
+            "snapshot manifest is inconsistent with storage logs"
+        );
+        anyhow::ensure!(
+            manifest.l1_batch_header.is_finished,
+            "snapshot is not taken at a sealed L1 batch"
+        );
+
+        let mut transaction = storage.start_transaction().await;
+        transaction
+            .blocks_dal()
+            .insert_l1_batch(&manifest.l1_batch_header, BlockGasCount::default())
+            .await;
+        transaction
+            .blocks_dal()
+            .save_blocks_metadata(
+                l1_batch_number,
+                &manifest.l1_batch_metadata,
+                manifest.previous_l1_batch_root_hash,
+            )
+            .await;
+        transaction
+            .blocks_dal()
+            .insert_miniblock(&manifest.miniblock_header)
+            .await;
+        transaction
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(l1_batch_number)
+            .await;
+
+        // All storage logs are attributed to the last miniblock of the snapshot's L1 batch.
+        let storage_logs = [(H256::zero(), snapshot.storage_logs)];
+        transaction
+            .storage_logs_dal()
+            .insert_storage_logs(miniblock_number, &storage_logs)
+            .await;
+        transaction
+            .storage_dal()
+            .apply_storage_logs(&storage_logs)
+            .await;
+        transaction
+            .storage_dal()
+            .insert_factory_deps(miniblock_number, &snapshot.factory_deps)
+            .await;
+        transaction.commit().await;
+
+        metrics::gauge!(
+            "server.snapshot_importer.l1_batch_number",
+            l1_batch_number.0 as f64
+        );
+        Ok(manifest)
+    }
+}
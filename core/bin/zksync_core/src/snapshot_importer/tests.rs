@@ -0,0 +1,188 @@
+//! Tests for the snapshot importer.
+
+use db_test_macro::db_test;
+
+use std::{collections::HashMap, time::Duration};
+
+use vm::vm_with_bootloader::BlockContextMode;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    block::{L1BatchHeader, MiniblockHeader},
+    AccountTreeId, Address, MiniblockNumber, StorageKey, StorageLog,
+};
+use zksync_utils::{be_words_to_bytes, h256_to_u256, miniblock_hash};
+
+use super::*;
+use crate::{
+    state_keeper::{
+        io::StateKeeperIO,
+        tests::{create_block_metadata, BASE_SYSTEM_CONTRACTS},
+    },
+    sync_layer::{sync_action::SyncAction, ActionQueue, ExternalIO, SyncState},
+};
+
+const L1_BATCH_NUMBER: L1BatchNumber = L1BatchNumber(10);
+const MINIBLOCK_NUMBER: MiniblockNumber = MiniblockNumber(42);
+
+fn storage_log(key: u64, value: u64) -> StorageLog {
+    let key = StorageKey::new(
+        AccountTreeId::new(Address::repeat_byte(1)),
+        H256::from_low_u64_be(key),
+    );
+    StorageLog::new_write_log(key, H256::from_low_u64_be(value))
+}
+
+fn base_system_contracts_deps() -> HashMap<H256, Vec<u8>> {
+    let contracts = [
+        &BASE_SYSTEM_CONTRACTS.bootloader,
+        &BASE_SYSTEM_CONTRACTS.default_aa,
+    ];
+    contracts
+        .into_iter()
+        .map(|contract| (contract.hash, be_words_to_bytes(&contract.code)))
+        .collect()
+}
+
+async fn save_snapshot(store_factory: &ObjectStoreFactory, storage_logs: Vec<StorageLog>) {
+    let mut l1_batch_header = L1BatchHeader::new(
+        L1_BATCH_NUMBER,
+        100,
+        Address::default(),
+        BASE_SYSTEM_CONTRACTS.hashes(),
+    );
+    l1_batch_header.is_finished = true;
+    let miniblock_header = MiniblockHeader {
+        number: MINIBLOCK_NUMBER,
+        timestamp: 100,
+        hash: miniblock_hash(MINIBLOCK_NUMBER),
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        base_fee_per_gas: 100,
+        l1_gas_price: 100,
+        l2_fair_gas_price: 100,
+        base_system_contracts_hashes: BASE_SYSTEM_CONTRACTS.hashes(),
+    };
+
+    let object_store = store_factory.create_store().await;
+    let snapshot = StorageLogsSnapshot {
+        l1_batch_number: L1_BATCH_NUMBER,
+        miniblock_number: MINIBLOCK_NUMBER,
+        storage_logs,
+        factory_deps: base_system_contracts_deps(),
+    };
+    let storage_logs_url = object_store.put(L1_BATCH_NUMBER, &snapshot).await.unwrap();
+    let manifest = SnapshotManifest {
+        l1_batch_header,
+        l1_batch_metadata: create_block_metadata(L1_BATCH_NUMBER.0),
+        previous_l1_batch_root_hash: H256::repeat_byte(0xff),
+        miniblock_header,
+        storage_logs_url,
+    };
+    object_store.put(L1_BATCH_NUMBER, &manifest).await.unwrap();
+}
+
+#[db_test]
+async fn importing_snapshot(pool: ConnectionPool) {
+    let store_factory = ObjectStoreFactory::mock();
+    let storage_logs = vec![storage_log(1, 10), storage_log(2, 20)];
+    save_snapshot(&store_factory, storage_logs.clone()).await;
+
+    let mut storage = pool.access_storage().await;
+    storage.blocks_dal().delete_genesis().await;
+    let importer = SnapshotImporter::new(&store_factory).await;
+    let manifest = importer
+        .import_snapshot(&mut storage, L1_BATCH_NUMBER)
+        .await
+        .unwrap();
+    assert_eq!(manifest.miniblock_header.number, MINIBLOCK_NUMBER);
+
+    let sealed_l1_batch_number = storage.blocks_web3_dal().get_sealed_l1_batch_number().await;
+    assert_eq!(sealed_l1_batch_number.unwrap(), L1_BATCH_NUMBER);
+    let sealed_miniblock_number = storage
+        .blocks_web3_dal()
+        .get_sealed_miniblock_number()
+        .await;
+    assert_eq!(sealed_miniblock_number.unwrap(), MINIBLOCK_NUMBER);
+    for log in &storage_logs {
+        let value = storage.storage_dal().get_by_key(&log.key).await;
+        assert_eq!(value, Some(log.value));
+    }
+    let (root_hash, _) = storage
+        .blocks_dal()
+        .get_block_state_root_and_timestamp(L1_BATCH_NUMBER)
+        .await
+        .expect("no root hash for the snapshot L1 batch");
+    assert_eq!(root_hash, manifest.l1_batch_metadata.root_hash);
+    for (hash, bytecode) in base_system_contracts_deps() {
+        let stored_bytecode = storage.storage_dal().get_factory_dep(hash).await;
+        assert_eq!(stored_bytecode, Some(bytecode));
+    }
+
+    // The storage is no longer empty, so the snapshot cannot be imported again.
+    let err = importer
+        .import_snapshot(&mut storage, L1_BATCH_NUMBER)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("non-empty storage"), "{err}");
+}
+
+#[db_test]
+async fn starting_state_keeper_io_after_recovery(pool: ConnectionPool) {
+    let store_factory = ObjectStoreFactory::mock();
+    save_snapshot(&store_factory, vec![storage_log(1, 10)]).await;
+
+    let mut storage = pool.access_storage().await;
+    storage.blocks_dal().delete_genesis().await;
+    let importer = SnapshotImporter::new(&store_factory).await;
+    let manifest = importer
+        .import_snapshot(&mut storage, L1_BATCH_NUMBER)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let actions = ActionQueue::new();
+    // The main node URL is not used since all base system contracts are present in the snapshot.
+    let mut io = ExternalIO::new(
+        pool,
+        actions.clone(),
+        SyncState::new(),
+        "http://localhost:1".to_owned(),
+        Address::repeat_byte(2),
+    )
+    .await;
+    assert_eq!(io.current_l1_batch_number(), L1_BATCH_NUMBER + 1);
+    assert_eq!(io.current_miniblock_number(), MINIBLOCK_NUMBER + 1);
+
+    actions.push_actions(vec![
+        SyncAction::OpenBatch {
+            number: L1_BATCH_NUMBER + 1,
+            timestamp: 101,
+            l1_gas_price: 100,
+            l2_fair_gas_price: 100,
+            base_system_contracts_hashes: BASE_SYSTEM_CONTRACTS.hashes(),
+            operator_address: Address::default(),
+        },
+        SyncAction::SealMiniblock,
+    ]);
+    // Without the root hash of the snapshot L1 batch, this call would block indefinitely.
+    let params = tokio::time::timeout(
+        Duration::from_secs(10),
+        io.wait_for_new_batch_params(Duration::from_secs(5)),
+    )
+    .await
+    .expect("timed out waiting for the previous L1 batch hash")
+    .expect("no L1 batch params");
+
+    let BlockContextMode::NewBlock(context, prev_block_hash) = params.context_mode else {
+        panic!("unexpected context mode: {:?}", params.context_mode);
+    };
+    assert_eq!(context.context.block_number, L1_BATCH_NUMBER.0 + 1);
+    assert_eq!(
+        prev_block_hash,
+        h256_to_u256(manifest.l1_batch_metadata.root_hash)
+    );
+    assert_eq!(
+        params.base_system_contracts.hashes(),
+        BASE_SYSTEM_CONTRACTS.hashes()
+    );
+}
@@ -16,7 +16,7 @@ use vm::{
 };
 use zksync_dal::ConnectionPool;
 use zksync_state::{RocksdbStorage, StorageView};
-use zksync_types::{tx::ExecutionMetrics, Transaction, U256};
+use zksync_types::{tx::ExecutionMetrics, L1BatchNumber, Transaction, U256};
 use zksync_utils::bytecode::{hash_bytecode, CompressedBytecodeInfo};
 
 #[cfg(test)]
@@ -99,18 +99,25 @@ impl MainBatchExecutorBuilder {
 #[async_trait]
 impl L1BatchExecutorBuilder for MainBatchExecutorBuilder {
     async fn init_batch(&self, l1_batch_params: L1BatchParams) -> BatchExecutorHandle {
-        let mut secondary_storage = RocksdbStorage::new(self.state_keeper_db_path.as_ref());
-        let mut conn = self.pool.access_storage_tagged("state_keeper").await;
-        secondary_storage.update_from_postgres(&mut conn).await;
-        drop(conn);
-
-        vlog::info!(
-            "Secondary storage for batch {} initialized, size is {}",
+        let l1_batch_number = L1BatchNumber(
             l1_batch_params
                 .context_mode
                 .inner_block_context()
                 .context
                 .block_number,
+        );
+        let mut secondary_storage = RocksdbStorage::new(self.state_keeper_db_path.as_ref());
+        let mut conn = self.pool.access_storage_tagged("state_keeper").await;
+        // Only apply L1 batches preceding the initialized one. Normally, this is equivalent
+        // to syncing with all sealed batches; in the shadow mode, the initialized batch
+        // is already sealed in Postgres.
+        secondary_storage
+            .update_from_postgres_until(&mut conn, l1_batch_number - 1)
+            .await;
+        drop(conn);
+
+        vlog::info!(
+            "Secondary storage for batch {l1_batch_number} initialized, size is {}",
             secondary_storage.estimated_map_size()
         );
         metrics::gauge!(
@@ -16,8 +16,9 @@ use zksync_types::{L1BatchNumber, MiniblockNumber, Transaction};
 pub(crate) mod common;
 pub(crate) mod mempool;
 pub(crate) mod seal_logic;
+mod shadow;
 
-pub(crate) use self::mempool::MempoolIO;
+pub(crate) use self::{mempool::MempoolIO, shadow::ShadowIO};
 
 use super::updates::{MiniblockSealCommand, UpdatesManager};
 
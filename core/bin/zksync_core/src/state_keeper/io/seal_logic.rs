@@ -594,6 +594,10 @@ impl MiniblockSealCommand {
             "server.state_keeper.miniblock.sealed_time",
             started_at.elapsed(),
         );
+        report_priority_op_inclusion_latencies(
+            &self.miniblock.executed_transactions,
+            millis_since_epoch(),
+        );
         metrics::gauge!(
             "server.miniblock.number",
             miniblock_number.0 as f64,
@@ -621,6 +625,36 @@ fn l1_l2_tx_count(executed_transactions: &[TransactionExecutionResult]) -> (usiz
     (l1_tx_count, l2_tx_count)
 }
 
+/// Returns latencies between observing priority operations on L1 (the eth watcher sets
+/// the received timestamp of L1 transactions to the observation time) and including them
+/// into a miniblock at `included_at_ms`.
+pub(crate) fn priority_op_inclusion_latencies(
+    executed_transactions: &[TransactionExecutionResult],
+    included_at_ms: u128,
+) -> Vec<Duration> {
+    executed_transactions
+        .iter()
+        .filter(|tx| matches!(tx.transaction.common_data, ExecuteTransactionCommon::L1(_)))
+        .map(|tx| {
+            let observed_at_ms = tx.transaction.received_timestamp_ms as u128;
+            // Saturate in case of clock skew between the eth watcher and the state keeper.
+            let latency_ms = included_at_ms.saturating_sub(observed_at_ms);
+            Duration::from_millis(latency_ms as u64)
+        })
+        .collect()
+}
+
+/// Reports latencies returned by [`priority_op_inclusion_latencies()`]
+/// as the `priority_op.inclusion_latency` histogram.
+pub(crate) fn report_priority_op_inclusion_latencies(
+    executed_transactions: &[TransactionExecutionResult],
+    included_at_ms: u128,
+) {
+    for latency in priority_op_inclusion_latencies(executed_transactions, included_at_ms) {
+        metrics::histogram!("priority_op.inclusion_latency", latency);
+    }
+}
+
 fn log_query_write_read_counts<'a>(logs: impl Iterator<Item = &'a LogQuery>) -> (usize, usize) {
     let mut reads_count = 0;
     let mut writes_count = 0;
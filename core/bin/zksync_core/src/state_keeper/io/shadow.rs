@@ -0,0 +1,313 @@
+use async_trait::async_trait;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use vm::{vm_with_bootloader::DerivedBlockContext, VmBlockResult};
+use zksync_dal::ConnectionPool;
+use zksync_types::{L1BatchNumber, MiniblockNumber, StorageKey, StorageLog, Transaction, H256};
+
+use crate::state_keeper::{
+    extractors,
+    io::{
+        common::{l1_batch_params, poll_iters},
+        L1BatchParams, PendingBatchData, StateKeeperIO,
+    },
+    seal_criteria::{SealManager, SealerFn},
+    updates::UpdatesManager,
+};
+
+/// Replay position shared between [`ShadowIO`] and the sealers created by [`ShadowIO::seal_manager()`].
+#[derive(Debug, Default)]
+struct ShadowCursor {
+    /// Transactions of the current miniblock that were not yet passed to the state keeper.
+    pending_txs: VecDeque<Transaction>,
+    /// Whether the current miniblock is the last non-fictive miniblock in the replayed L1 batch.
+    is_last_miniblock: bool,
+}
+
+/// IO for the state keeper running in the shadow mode. Re-executes L1 batches already sealed
+/// in Postgres (e.g., by the main node) and compares the execution results with the stored ones
+/// instead of persisting them. Divergences are logged and reported via the `shadow.divergence` counter.
+///
+/// The IO never writes to Postgres, so it can be used to validate a new VM version against real traffic.
+#[derive(Debug)]
+pub(crate) struct ShadowIO {
+    pool: ConnectionPool,
+    current_l1_batch_number: L1BatchNumber,
+    current_miniblock_number: MiniblockNumber,
+    /// Last non-fictive miniblock in the replayed L1 batch.
+    last_miniblock_in_batch: MiniblockNumber,
+    cursor: Arc<Mutex<ShadowCursor>>,
+    delay_interval: Duration,
+    divergence_count: usize,
+}
+
+impl ShadowIO {
+    pub(crate) fn new(
+        pool: ConnectionPool,
+        first_l1_batch_number: L1BatchNumber,
+        delay_interval: Duration,
+    ) -> Self {
+        Self {
+            pool,
+            current_l1_batch_number: first_l1_batch_number,
+            // Miniblock numbers will be set once the L1 batch is loaded.
+            current_miniblock_number: MiniblockNumber(0),
+            last_miniblock_in_batch: MiniblockNumber(0),
+            cursor: Arc::default(),
+            delay_interval,
+            divergence_count: 0,
+        }
+    }
+
+    /// Creates a seal manager that seals miniblocks and L1 batches at the same points
+    /// as in the replayed data.
+    pub(crate) fn seal_manager(&self) -> SealManager {
+        let cursor = self.cursor.clone();
+        let batch_sealer: Box<SealerFn> = Box::new(move |_: &UpdatesManager| {
+            let cursor = cursor.lock().unwrap();
+            cursor.pending_txs.is_empty() && cursor.is_last_miniblock
+        });
+        let cursor = self.cursor.clone();
+        let miniblock_sealer: Box<SealerFn> = Box::new(move |_: &UpdatesManager| {
+            let cursor = cursor.lock().unwrap();
+            cursor.pending_txs.is_empty() && !cursor.is_last_miniblock
+        });
+        SealManager::custom(None, vec![batch_sealer], vec![miniblock_sealer])
+    }
+
+    #[cfg(test)]
+    pub(crate) fn divergence_count(&self) -> usize {
+        self.divergence_count
+    }
+
+    fn report_divergence(&mut self, kind: &'static str, details: String) {
+        self.divergence_count += 1;
+        vlog::warn!(
+            "Shadow execution diverged in L1 batch #{}, miniblock #{}: {details} \
+             (total divergences: {})",
+            self.current_l1_batch_number,
+            self.current_miniblock_number,
+            self.divergence_count
+        );
+        metrics::increment_counter!("shadow.divergence", "kind" => kind);
+    }
+
+    /// Loads transactions of the current miniblock into the cursor and returns the miniblock timestamp.
+    async fn load_miniblock(&mut self) -> Option<u64> {
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await;
+        let header = storage
+            .blocks_dal()
+            .get_miniblock_header(self.current_miniblock_number)
+            .await?;
+        let txs = storage
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(self.current_miniblock_number)
+            .await
+            .unwrap();
+
+        let mut cursor = self.cursor.lock().unwrap();
+        cursor.pending_txs = txs.into();
+        cursor.is_last_miniblock = self.current_miniblock_number == self.last_miniblock_in_batch;
+        Some(header.timestamp)
+    }
+}
+
+#[async_trait]
+impl StateKeeperIO for ShadowIO {
+    fn current_l1_batch_number(&self) -> L1BatchNumber {
+        self.current_l1_batch_number
+    }
+
+    fn current_miniblock_number(&self) -> MiniblockNumber {
+        self.current_miniblock_number
+    }
+
+    async fn load_pending_batch(&mut self) -> Option<PendingBatchData> {
+        // Pending batches are not replayed; shadow execution starts from the next sealed batch.
+        None
+    }
+
+    async fn wait_for_new_batch_params(&mut self, max_wait: Duration) -> Option<L1BatchParams> {
+        for _ in 0..poll_iters(self.delay_interval, max_wait) {
+            let mut storage = self.pool.access_storage_tagged("state_keeper").await;
+            let Some(header) = storage
+                .blocks_dal()
+                .get_block_header(self.current_l1_batch_number)
+                .await
+            else {
+                drop(storage);
+                tokio::time::sleep(self.delay_interval).await;
+                continue;
+            };
+
+            let (first_miniblock, fictive_miniblock) = storage
+                .blocks_dal()
+                .get_miniblock_range_of_l1_batch(self.current_l1_batch_number)
+                .await
+                .expect("Replayed L1 batch has no miniblocks");
+            let (prev_hash, _) = extractors::wait_for_prev_l1_batch_params(
+                &mut storage,
+                self.current_l1_batch_number,
+            )
+            .await;
+            let base_system_contracts = storage
+                .storage_dal()
+                .get_base_system_contracts(
+                    header.base_system_contracts_hashes.bootloader,
+                    header.base_system_contracts_hashes.default_aa,
+                )
+                .await;
+            drop(storage);
+
+            vlog::info!(
+                "Replaying L1 batch #{} consisting of miniblocks #{first_miniblock}..=#{fictive_miniblock}",
+                self.current_l1_batch_number
+            );
+            self.current_miniblock_number = first_miniblock;
+            self.last_miniblock_in_batch = fictive_miniblock - 1;
+            self.load_miniblock().await;
+
+            return Some(l1_batch_params(
+                self.current_l1_batch_number,
+                header.fee_account_address,
+                header.timestamp,
+                prev_hash,
+                header.l1_gas_price,
+                header.l2_fair_gas_price,
+                base_system_contracts,
+            ));
+        }
+        None
+    }
+
+    async fn wait_for_new_miniblock_params(&mut self, _max_wait: Duration) -> Option<u64> {
+        self.load_miniblock().await
+    }
+
+    async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction> {
+        let tx = self.cursor.lock().unwrap().pending_txs.pop_front();
+        if tx.is_none() {
+            // Normally, the sealers fire once all transactions are executed; sleep to avoid a busy loop otherwise.
+            tokio::time::sleep(self.delay_interval.min(max_wait)).await;
+        }
+        tx
+    }
+
+    async fn rollback(&mut self, tx: Transaction) {
+        self.report_divergence(
+            "rollback",
+            format!("transaction {} was rolled back", tx.hash()),
+        );
+    }
+
+    async fn reject(&mut self, tx: &Transaction, error: &str) {
+        self.report_divergence(
+            "rejection",
+            format!("transaction {} was rejected: {error}", tx.hash()),
+        );
+    }
+
+    async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) {
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await;
+        let stored_results = storage
+            .transactions_dal()
+            .get_miniblock_execution_results(self.current_miniblock_number)
+            .await;
+        drop(storage);
+
+        let executed_txs = &updates_manager.miniblock.executed_transactions;
+        if executed_txs.len() != stored_results.len() {
+            self.report_divergence(
+                "tx_count",
+                format!(
+                    "{} transactions were executed, while {} are stored",
+                    executed_txs.len(),
+                    stored_results.len()
+                ),
+            );
+        }
+        for (executed, (hash, status, refunded_gas)) in executed_txs.iter().zip(stored_results) {
+            if executed.hash != hash {
+                self.report_divergence(
+                    "tx_hash",
+                    format!("executed transaction {} instead of {hash}", executed.hash),
+                );
+            } else if executed.execution_status != status {
+                self.report_divergence(
+                    "tx_status",
+                    format!(
+                        "transaction {hash} has status {:?}, while stored status is {status:?}",
+                        executed.execution_status
+                    ),
+                );
+            } else if executed.refunded_gas != refunded_gas {
+                self.report_divergence(
+                    "gas",
+                    format!(
+                        "transaction {hash} has {} refunded gas, while {refunded_gas} is stored",
+                        executed.refunded_gas
+                    ),
+                );
+            }
+        }
+        self.current_miniblock_number += 1;
+    }
+
+    async fn seal_l1_batch(
+        &mut self,
+        block_result: VmBlockResult,
+        _updates_manager: UpdatesManager,
+        _block_context: DerivedBlockContext,
+    ) {
+        // Final values of the touched storage slots are what determines the state root of the batch.
+        let executed_writes: HashMap<StorageKey, H256> = block_result
+            .full_result
+            .storage_log_queries
+            .iter()
+            .filter(|query| query.log_query.rw_flag)
+            .map(|query| {
+                let log = StorageLog::from_log_query(query);
+                (log.key, log.value)
+            })
+            .collect();
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await;
+        let stored_writes = storage
+            .storage_logs_dal()
+            .get_touched_slots_for_l1_batch(self.current_l1_batch_number)
+            .await;
+        drop(storage);
+
+        if executed_writes != stored_writes {
+            let diverged_slots = executed_writes
+                .iter()
+                .filter(|(key, value)| stored_writes.get(key) != Some(value))
+                .count()
+                + stored_writes
+                    .keys()
+                    .filter(|key| !executed_writes.contains_key(key))
+                    .count();
+            self.report_divergence(
+                "state",
+                format!("{diverged_slots} storage slots have diverged final values"),
+            );
+        } else {
+            vlog::info!(
+                "Replayed L1 batch #{} matches the stored one",
+                self.current_l1_batch_number
+            );
+        }
+
+        metrics::gauge!(
+            "shadow.l1_batch_number",
+            self.current_l1_batch_number.0 as f64
+        );
+        // Skip the fictive miniblock.
+        self.current_miniblock_number += 1;
+        self.current_l1_batch_number += 1;
+    }
+}
@@ -4,23 +4,35 @@ use std::time::Duration;
 
 use db_test_macro::db_test;
 use vm::vm_with_bootloader::{derive_base_fee_and_gas_per_pubdata, BlockContextMode};
-use zksync_contracts::BaseSystemContractsHashes;
+use zksync_config::configs::chain::MempoolOrderingStrategy;
+use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
 use zksync_dal::ConnectionPool;
 use zksync_mempool::L2TxFilter;
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, AccountTreeId, Address, L1BatchNumber,
-    MiniblockNumber, StorageKey, VmEvent, H256, U256,
+    block::BlockGasCount,
+    fee::TransactionExecutionMetrics,
+    l1::{L1Tx, OpProcessingType, PriorityQueueType},
+    tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics},
+    AccountTreeId, Address, Execute, L1BatchNumber, L1BlockNumber, L1TxCommonData, MiniblockNumber,
+    PriorityOpId, StorageKey, VmEvent, H256, U256,
 };
 use zksync_utils::time::millis_since_epoch;
 
-use crate::state_keeper::{
-    io::{MiniblockSealer, StateKeeperIO},
-    mempool_actor::l2_tx_filter,
-    tests::{
-        create_block_metadata, create_execution_result, create_transaction, create_updates_manager,
-        default_block_context, default_vm_block_result, Query,
+use crate::{
+    state_keeper::{
+        io::{
+            seal_logic::{priority_op_inclusion_latencies, report_priority_op_inclusion_latencies},
+            MiniblockSealer, ShadowIO, StateKeeperIO,
+        },
+        mempool_actor::l2_tx_filter,
+        tests::{
+            create_block_metadata, create_execution_result, create_l2_transaction,
+            create_transaction, create_updates_manager, default_block_context,
+            default_vm_block_result, Query,
+        },
+        updates::{MiniblockSealCommand, MiniblockUpdates, UpdatesManager},
     },
-    updates::{MiniblockSealCommand, MiniblockUpdates, UpdatesManager},
+    testonly::counter_value,
 };
 
 mod tester;
@@ -441,3 +453,199 @@ async fn miniblock_sealer_handle_parallel_processing(pool: ConnectionPool) {
 
     sealer_handle.wait_for_all_commands().await;
 }
+
+#[db_test]
+async fn shadow_io_detects_divergences(pool: ConnectionPool) {
+    let tester = Tester::new();
+    tester.genesis(&pool).await;
+    let mut conn = pool.access_storage_tagged("state_keeper").await;
+    let block_metadata = create_block_metadata(0);
+    conn.blocks_dal()
+        .save_blocks_metadata(L1BatchNumber(0), &block_metadata, H256::zero())
+        .await;
+    let tx = create_l2_transaction(10, 100);
+    conn.transactions_dal()
+        .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+        .await;
+    drop(conn);
+
+    // Seal L1 batch #1 with a single transaction as the main node would do.
+    let (mut mempool, _) = tester.create_test_mempool_io(pool.clone(), 0).await;
+    let mut block_context = default_block_context();
+    block_context.context.block_timestamp = 100;
+    let block_context_mode = BlockContextMode::NewBlock(block_context, 0.into());
+    let base_system_contracts_hashes = BaseSystemContracts::load_from_disk().hashes();
+    let mut updates = UpdatesManager::new(&block_context_mode, base_system_contracts_hashes);
+    updates.extend_from_executed_transaction(
+        tx.clone().into(),
+        create_execution_result(0, []),
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+    );
+    mempool.seal_miniblock(&updates).await;
+    updates.push_miniblock(1);
+    mempool
+        .seal_l1_batch(default_vm_block_result(), updates, block_context)
+        .await;
+
+    let mut shadow_io = ShadowIO::new(pool.clone(), L1BatchNumber(1), Duration::from_millis(10));
+    let sealer = shadow_io.seal_manager();
+    let params = shadow_io
+        .wait_for_new_batch_params(Duration::from_secs(1))
+        .await
+        .expect("L1 batch #1 is not replayed");
+    let replayed_context = params.context_mode.inner_block_context().context;
+    assert_eq!(replayed_context.block_number, 1);
+    assert_eq!(replayed_context.block_timestamp, 100);
+    assert_eq!(shadow_io.current_miniblock_number(), MiniblockNumber(1));
+
+    let replayed_tx = shadow_io
+        .wait_for_next_tx(Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(replayed_tx.hash(), tx.hash());
+    assert!(shadow_io
+        .wait_for_next_tx(Duration::from_millis(10))
+        .await
+        .is_none());
+
+    // Inject a gas divergence: the stored transaction has no refunded gas.
+    let mut tx_result = create_execution_result(0, []);
+    tx_result.gas_refunded = 10;
+    let mut updates = UpdatesManager::new(&block_context_mode, base_system_contracts_hashes);
+    updates.extend_from_executed_transaction(
+        replayed_tx,
+        tx_result,
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+    );
+    assert!(sealer.should_seal_l1_batch_unconditionally(&updates));
+    shadow_io.seal_miniblock(&updates).await;
+    assert_eq!(shadow_io.divergence_count(), 1);
+
+    let fictive_timestamp = shadow_io
+        .wait_for_new_miniblock_params(Duration::from_millis(10))
+        .await
+        .unwrap();
+    updates.push_miniblock(fictive_timestamp);
+    // Inject a state divergence: no storage slots are touched in the stored L1 batch.
+    let mut block_result = default_vm_block_result();
+    block_result.full_result.storage_log_queries =
+        create_execution_result(0, [(U256::from(1), Query::InitialWrite(U256::from(1)))])
+            .result
+            .logs
+            .storage_logs;
+    shadow_io
+        .seal_l1_batch(block_result, updates, block_context)
+        .await;
+    assert_eq!(shadow_io.divergence_count(), 2);
+    assert_eq!(shadow_io.current_l1_batch_number(), L1BatchNumber(2));
+    assert_eq!(shadow_io.current_miniblock_number(), MiniblockNumber(3));
+
+    // Check that the stored data is not mutated.
+    let mut conn = pool.access_storage_tagged("state_keeper").await;
+    assert_eq!(
+        conn.blocks_dal().get_sealed_block_number().await,
+        L1BatchNumber(1)
+    );
+    assert_eq!(
+        conn.blocks_dal().get_sealed_miniblock_number().await,
+        MiniblockNumber(2)
+    );
+    let stored_results = conn
+        .transactions_dal()
+        .get_miniblock_execution_results(MiniblockNumber(1))
+        .await;
+    assert_eq!(stored_results, [(tx.hash(), TxExecutionStatus::Success, 0)]);
+    let touched_slots = conn
+        .storage_logs_dal()
+        .get_touched_slots_for_l1_batch(L1BatchNumber(1))
+        .await;
+    assert!(touched_slots.is_empty(), "{touched_slots:?}");
+}
+
+#[db_test]
+async fn priority_op_inclusion_latency(pool: ConnectionPool) {
+    const OBSERVATION_LAG_MS: u64 = 5_000;
+
+    let observed_at_ms = millis_since_epoch() as u64 - OBSERVATION_LAG_MS;
+    let priority_op = L1Tx {
+        common_data: L1TxCommonData {
+            sender: Address::repeat_byte(1),
+            serial_id: PriorityOpId(0),
+            deadline_block: 100_000,
+            layer_2_tip_fee: U256::zero(),
+            full_fee: U256::zero(),
+            max_fee_per_gas: U256::one(),
+            gas_limit: U256::from(100_100),
+            gas_per_pubdata_limit: U256::from(800),
+            op_processing_type: OpProcessingType::Common,
+            priority_queue_type: PriorityQueueType::Deque,
+            eth_hash: H256::random(),
+            eth_block: 1,
+            canonical_tx_hash: H256::repeat_byte(0x11),
+            to_mint: U256::zero(),
+            refund_recipient: Address::repeat_byte(1),
+        },
+        execute: Execute {
+            contract_address: Address::repeat_byte(2),
+            calldata: vec![],
+            value: U256::zero(),
+            factory_deps: None,
+        },
+        received_timestamp_ms: observed_at_ms,
+    };
+
+    // Emulate the eth watcher observing the priority op and the state keeper loading it.
+    let mut storage = pool.access_storage_tagged("state_keeper").await;
+    storage
+        .transactions_dal()
+        .insert_transaction_l1(priority_op, L1BlockNumber(1))
+        .await;
+    let l2_tx = create_l2_transaction(10, 100);
+    storage
+        .transactions_dal()
+        .insert_transaction_l2(l2_tx, TransactionExecutionMetrics::default())
+        .await;
+    let (txs, _) = storage
+        .transactions_dal()
+        .sync_mempool(vec![], vec![], 0, 0, 10, MempoolOrderingStrategy::Fifo)
+        .await;
+    drop(storage);
+    assert_eq!(txs.len(), 2);
+
+    let mut updates = create_updates_manager();
+    for tx in txs {
+        updates.extend_from_executed_transaction(
+            tx,
+            create_execution_result(0, []),
+            vec![],
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+        );
+    }
+    let included_at_ms = millis_since_epoch();
+    let latencies =
+        priority_op_inclusion_latencies(&updates.miniblock.executed_transactions, included_at_ms);
+
+    // Only the priority op should be reported.
+    assert_eq!(latencies.len(), 1);
+    let latency = latencies[0];
+    assert!(
+        latency >= Duration::from_millis(OBSERVATION_LAG_MS) && latency < Duration::from_secs(60),
+        "{latency:?}"
+    );
+
+    // Check that the latency is recorded to the histogram.
+    let recorded_count = counter_value("priority_op_inclusion_latency_count");
+    report_priority_op_inclusion_latencies(
+        &updates.miniblock.executed_transactions,
+        included_at_ms,
+    );
+    assert_eq!(
+        counter_value("priority_op_inclusion_latency_count"),
+        recorded_count + 1
+    );
+}
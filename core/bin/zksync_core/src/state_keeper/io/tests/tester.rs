@@ -2,7 +2,7 @@
 
 use std::{sync::Arc, time::Duration};
 
-use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_config::configs::chain::{MempoolOrderingStrategy, StateKeeperConfig};
 use zksync_config::GasAdjusterConfig;
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::ConnectionPool;
@@ -46,6 +46,8 @@ impl Tester {
             internal_enforced_l1_gas_price: None,
             poll_period: 10,
             max_l1_gas_price: None,
+            max_l1_gas_price_for_estimation: None,
+            use_legacy_gas_price: false,
         };
 
         GasAdjuster::new(eth_client, gas_adjuster_config)
@@ -64,7 +66,7 @@ impl Tester {
         miniblock_sealer_capacity: usize,
     ) -> (MempoolIO<GasAdjuster<MockEthereum>>, MempoolGuard) {
         let gas_adjuster = Arc::new(self.create_gas_adjuster().await);
-        let mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let mempool = MempoolGuard::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
         let (miniblock_sealer, miniblock_sealer_handle) =
             MiniblockSealer::new(pool.clone(), miniblock_sealer_capacity);
         tokio::spawn(miniblock_sealer.run());
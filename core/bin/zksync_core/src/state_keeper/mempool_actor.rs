@@ -5,7 +5,7 @@ use std::time::Duration;
 use std::time::Instant;
 use tokio::sync::watch;
 use vm::vm_with_bootloader::derive_base_fee_and_gas_per_pubdata;
-use zksync_config::configs::chain::MempoolConfig;
+use zksync_config::configs::chain::{MempoolConfig, MempoolOrderingStrategy};
 
 use zksync_dal::ConnectionPool;
 use zksync_mempool::L2TxFilter;
@@ -34,6 +34,7 @@ pub struct MempoolFetcher<G> {
     l1_gas_price_provider: Arc<G>,
     sync_interval: Duration,
     sync_batch_size: usize,
+    ordering_strategy: MempoolOrderingStrategy,
 }
 
 impl<G: L1GasPriceProvider> MempoolFetcher<G> {
@@ -47,6 +48,7 @@ impl<G: L1GasPriceProvider> MempoolFetcher<G> {
             l1_gas_price_provider,
             sync_interval: config.sync_interval(),
             sync_batch_size: config.sync_batch_size,
+            ordering_strategy: config.ordering_strategy(),
         }
     }
 
@@ -88,6 +90,7 @@ impl<G: L1GasPriceProvider> MempoolFetcher<G> {
                     l2_tx_filter.gas_per_pubdata,
                     l2_tx_filter.fee_per_gas,
                     self.sync_batch_size,
+                    self.ordering_strategy,
                 )
                 .await;
             let all_transactions_loaded = transactions.len() < self.sync_batch_size;
@@ -16,7 +16,7 @@ mod keeper;
 mod mempool_actor;
 pub(crate) mod seal_criteria;
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
 mod types;
 pub(crate) mod updates;
 
@@ -25,7 +25,7 @@ pub use self::{
 };
 pub(crate) use self::{io::MiniblockSealer, mempool_actor::MempoolFetcher, types::MempoolGuard};
 
-use self::io::{MempoolIO, MiniblockSealerHandle};
+use self::io::{MempoolIO, MiniblockSealerHandle, ShadowIO};
 use crate::l1_gas_price::L1GasPriceProvider;
 
 #[allow(clippy::too_many_arguments)]
@@ -58,6 +58,25 @@ where
         state_keeper_config.validation_computational_gas_limit,
     );
 
+    if state_keeper_config.shadow_mode() {
+        let mut storage = pool.access_storage_tagged("state_keeper").await;
+        let first_l1_batch_number = storage.blocks_dal().get_sealed_block_number().await + 1;
+        drop(storage);
+        vlog::warn!(
+            "State keeper runs in the shadow mode; L1 batches starting from #{first_l1_batch_number} \
+             will be re-executed and compared with stored ones, no new batches will be sealed"
+        );
+
+        let io = ShadowIO::new(pool, first_l1_batch_number, mempool_config.delay_interval());
+        let sealer = io.seal_manager();
+        return ZkSyncStateKeeper::new(
+            stop_receiver,
+            Box::new(io),
+            Box::new(batch_executor_base),
+            sealer,
+        );
+    }
+
     let io = MempoolIO::new(
         mempool,
         miniblock_sealer_handle,
@@ -1,6 +1,7 @@
 use once_cell::sync::Lazy;
 
 use std::{
+    collections::HashMap,
     sync::{
         atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
@@ -13,7 +14,10 @@ use vm::{
     vm_with_bootloader::{BlockContext, BlockContextMode, DerivedBlockContext},
     VmBlockResult, VmExecutionResult,
 };
-use zksync_config::{configs::chain::StateKeeperConfig, constants::ZKPORTER_IS_AVAILABLE};
+use zksync_config::{
+    configs::chain::{MempoolOrderingStrategy, StateKeeperConfig},
+    constants::ZKPORTER_IS_AVAILABLE,
+};
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
 use zksync_types::{
     block::BlockGasCount,
@@ -25,8 +29,8 @@ use zksync_types::{
     vm_trace::{VmExecutionTrace, VmTrace},
     zk_evm::aux_structures::{LogQuery, Timestamp},
     zk_evm::block_properties::BlockProperties,
-    Address, L2ChainId, MiniblockNumber, Nonce, StorageLogQuery, StorageLogQueryType, Transaction,
-    H256, U256,
+    Address, L2ChainId, MiniblockNumber, Nonce, PriorityOpId, StorageLogQuery, StorageLogQueryType,
+    Transaction, H256, U256,
 };
 use zksync_utils::h256_to_u256;
 
@@ -43,13 +47,14 @@ use crate::state_keeper::{
         criteria::{GasCriterion, SlotsCriterion},
         ConditionalSealer, SealManager,
     },
-    types::ExecutionMetricsForCriteria,
+    types::{ExecutionMetricsForCriteria, MempoolGuard},
     updates::UpdatesManager,
 };
+use crate::testonly::counter_value;
 
 mod tester;
 
-pub(super) static BASE_SYSTEM_CONTRACTS: Lazy<BaseSystemContracts> =
+pub(crate) static BASE_SYSTEM_CONTRACTS: Lazy<BaseSystemContracts> =
     Lazy::new(BaseSystemContracts::load_from_disk);
 
 pub(super) fn default_block_properties() -> BlockProperties {
@@ -59,7 +64,7 @@ pub(super) fn default_block_properties() -> BlockProperties {
     }
 }
 
-pub(super) fn create_block_metadata(number: u32) -> BlockMetadata {
+pub(crate) fn create_block_metadata(number: u32) -> BlockMetadata {
     BlockMetadata {
         root_hash: H256::from_low_u64_be(number.into()),
         rollup_last_leaf_index: u64::from(number) + 20,
@@ -733,3 +738,23 @@ async fn time_is_monotonic() {
         .run(sealer)
         .await;
 }
+
+#[test]
+fn mempool_guard_reports_transactions_rejected_when_full() {
+    let mut mempool = MempoolGuard::new(PriorityOpId(0), 3, MempoolOrderingStrategy::Fifo);
+    let transactions = (0..3).map(|_| create_transaction(10, 100)).collect();
+    mempool.insert(transactions, HashMap::new());
+    let rejected_count = counter_value("mempool_rejected_full");
+
+    // Transactions that cannot be executed yet are dropped once the mempool is full.
+    let transactions = (0..2)
+        .map(|_| {
+            let mut tx = create_l2_transaction(10, 100);
+            tx.common_data.nonce = Nonce(1);
+            tx.into()
+        })
+        .collect();
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(mempool.get_mempool_info().purged_accounts.len(), 2);
+    assert_eq!(counter_value("mempool_rejected_full"), rejected_count + 2);
+}
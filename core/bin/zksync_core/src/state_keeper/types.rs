@@ -3,6 +3,7 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use zksync_config::configs::chain::MempoolOrderingStrategy;
 use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
 use zksync_types::{
     block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
@@ -12,8 +13,12 @@ use zksync_types::{
 pub struct MempoolGuard(Arc<Mutex<MempoolStore>>);
 
 impl MempoolGuard {
-    pub fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
-        let store = MempoolStore::new(next_priority_id, capacity);
+    pub fn new(
+        next_priority_id: PriorityOpId,
+        capacity: u64,
+        ordering: MempoolOrderingStrategy,
+    ) -> Self {
+        let store = MempoolStore::new(next_priority_id, capacity, ordering);
         Self(Arc::new(Mutex::new(store)))
     }
 
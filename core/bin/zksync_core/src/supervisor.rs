@@ -0,0 +1,192 @@
+//! Supervisor relaunching non-critical components if their tasks terminate unexpectedly.
+//!
+//! Without supervision, a component task that terminates (e.g., because of a panic) shuts down
+//! the whole server. A supervised component is relaunched instead, unless it needs too many relaunches
+//! within a short period of time; in this case, the supervisor gives up and terminates itself,
+//! which shuts down the server as before.
+
+use futures::future;
+use tokio::{sync::watch, task::JoinHandle};
+
+use std::{
+    collections::VecDeque,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use zksync_utils::panic_extractor::try_extract_panic_message;
+
+use crate::spawn_named;
+
+/// Limits the number of relaunches of a supervised component.
+#[derive(Debug, Clone, Copy)]
+pub struct RestartPolicy {
+    /// Max number of relaunches within `window`.
+    pub max_restarts: u32,
+    /// Sliding window for `max_restarts`.
+    pub window: Duration,
+}
+
+/// Relaunch history of a single component.
+#[derive(Debug)]
+struct RestartHistory {
+    policy: RestartPolicy,
+    restarts: VecDeque<Instant>,
+}
+
+impl RestartHistory {
+    fn new(policy: RestartPolicy) -> Self {
+        Self {
+            policy,
+            restarts: VecDeque::new(),
+        }
+    }
+
+    /// Records a relaunch at `now`. Returns `false` if the relaunch is not allowed by the policy.
+    fn record_restart(&mut self, now: Instant) -> bool {
+        while let Some(&restarted_at) = self.restarts.front() {
+            if now.duration_since(restarted_at) < self.policy.window {
+                break;
+            }
+            self.restarts.pop_front();
+        }
+        if self.restarts.len() >= self.policy.max_restarts as usize {
+            return false;
+        }
+        self.restarts.push_back(now);
+        true
+    }
+}
+
+/// Spawns a supervisor for the `component` launched by `start`. `start` returns handles
+/// for all tasks of the component; if any of them terminates before the stop signal is received,
+/// the remaining tasks are aborted, and the component is relaunched according to `policy`.
+///
+/// The returned handle completes once the stop signal is received and the component terminates,
+/// or if the supervisor gives up relaunching the component.
+pub fn spawn_supervised<F, Fut>(
+    component: &'static str,
+    policy: RestartPolicy,
+    stop_receiver: watch::Receiver<bool>,
+    mut start: F,
+) -> JoinHandle<()>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = Vec<JoinHandle<()>>> + Send,
+{
+    spawn_named(component, async move {
+        let mut history = RestartHistory::new(policy);
+        loop {
+            let handles = start().await;
+            if handles.is_empty() {
+                vlog::info!("Component {component} has no tasks to supervise");
+                return;
+            }
+            let (result, _, remaining_handles) = future::select_all(handles).await;
+            for handle in remaining_handles {
+                handle.abort();
+            }
+            if *stop_receiver.borrow() {
+                return;
+            }
+
+            match result {
+                Ok(()) => vlog::warn!("Component {component} terminated unexpectedly"),
+                Err(err) => {
+                    let panic_message = try_extract_panic_message(err);
+                    vlog::error!("Component {component} panicked: {panic_message}");
+                }
+            }
+            if !history.record_restart(Instant::now()) {
+                vlog::error!(
+                    "Component {component} was relaunched {} times within {:?}; giving up",
+                    policy.max_restarts,
+                    policy.window
+                );
+                return;
+            }
+            metrics::counter!("server.supervisor.restarts", 1, "component" => component);
+            vlog::info!("Relaunching component {component}");
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+
+    const POLICY: RestartPolicy = RestartPolicy {
+        max_restarts: 2,
+        window: Duration::from_secs(60),
+    };
+
+    #[test]
+    fn restart_history_uses_sliding_window() {
+        let mut history = RestartHistory::new(POLICY);
+        let start = Instant::now();
+        assert!(history.record_restart(start));
+        assert!(history.record_restart(start + Duration::from_secs(10)));
+        assert!(!history.record_restart(start + Duration::from_secs(20)));
+        // The first restart is out of the window.
+        assert!(history.record_restart(start + Duration::from_secs(60)));
+        assert!(!history.record_restart(start + Duration::from_secs(65)));
+    }
+
+    #[tokio::test]
+    async fn component_is_relaunched_after_panic() {
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let launch_count = Arc::new(AtomicUsize::new(0));
+        let supervisor = spawn_supervised("test", POLICY, stop_receiver.clone(), {
+            let launch_count = launch_count.clone();
+            move || {
+                let launch_number = launch_count.fetch_add(1, Ordering::SeqCst);
+                let mut stop_receiver = stop_receiver.clone();
+                async move {
+                    let handle = tokio::spawn(async move {
+                        assert!(launch_number > 0, "first launch panics");
+                        while !*stop_receiver.borrow() {
+                            stop_receiver.changed().await.unwrap();
+                        }
+                    });
+                    vec![handle]
+                }
+            }
+        });
+
+        while launch_count.load(Ordering::SeqCst) < 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(!supervisor.is_finished());
+        stop_sender.send_replace(true);
+        tokio::time::timeout(Duration::from_secs(5), supervisor)
+            .await
+            .expect("supervisor did not stop")
+            .unwrap();
+        assert_eq!(launch_count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn supervisor_gives_up_after_too_many_restarts() {
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let launch_count = Arc::new(AtomicUsize::new(0));
+        let supervisor = spawn_supervised("test", POLICY, stop_receiver, {
+            let launch_count = launch_count.clone();
+            move || {
+                launch_count.fetch_add(1, Ordering::SeqCst);
+                async { vec![tokio::spawn(async { panic!("component failure") })] }
+            }
+        });
+
+        tokio::time::timeout(Duration::from_secs(5), supervisor)
+            .await
+            .expect("supervisor did not give up")
+            .unwrap();
+        // The initial launch + `max_restarts` relaunches.
+        assert_eq!(launch_count.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,214 @@
+//! Client abstraction for the main node API used by the sync layer.
+
+use async_trait::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
+
+use zksync_types::{api::en::SyncBlock, MiniblockNumber};
+use zksync_web3_decl::{
+    jsonrpsee::{core::Error as RpcError, http_client::HttpClient},
+    namespaces::EnNamespaceClient,
+    RpcResult,
+};
+
+/// Max number of miniblocks requested from the main node concurrently
+/// in [`MainNodeClient::fetch_miniblock_range()`].
+const MAX_CONCURRENT_MINIBLOCK_REQUESTS: usize = 10;
+
+/// Main node API methods required by the sync layer.
+#[async_trait]
+pub trait MainNodeClient: Send + Sync {
+    async fn sync_l2_block(
+        &self,
+        number: MiniblockNumber,
+        with_transactions: bool,
+    ) -> RpcResult<Option<SyncBlock>>;
+
+    /// Fetches full payloads (including transactions) of miniblocks in the `from..=to` range.
+    /// The returned range may be shorter than requested if the main node doesn't have
+    /// the latest miniblocks yet, but it always starts at `from` and has no gaps.
+    async fn fetch_miniblock_range(
+        &self,
+        from: MiniblockNumber,
+        to: MiniblockNumber,
+    ) -> RpcResult<Vec<SyncBlock>> {
+        let block_futures =
+            (from.0..=to.0).map(|number| self.sync_l2_block(MiniblockNumber(number), true));
+        // `buffered()` returns blocks in the order of requests.
+        let blocks: Vec<_> = stream::iter(block_futures)
+            .buffered(MAX_CONCURRENT_MINIBLOCK_REQUESTS)
+            .try_collect()
+            .await?;
+        validate_miniblock_range(from, blocks)
+    }
+}
+
+#[async_trait]
+impl MainNodeClient for HttpClient {
+    async fn sync_l2_block(
+        &self,
+        number: MiniblockNumber,
+        with_transactions: bool,
+    ) -> RpcResult<Option<SyncBlock>> {
+        <Self as EnNamespaceClient>::sync_l2_block(self, number, with_transactions).await
+    }
+}
+
+fn validate_miniblock_range(
+    from: MiniblockNumber,
+    blocks: Vec<Option<SyncBlock>>,
+) -> RpcResult<Vec<SyncBlock>> {
+    let mut blocks = blocks.into_iter();
+    let mut range = Vec::with_capacity(blocks.len());
+    let mut expected_number = from;
+    for block in blocks.by_ref() {
+        let Some(block) = block else {
+            break;
+        };
+        if block.number != expected_number {
+            return Err(RpcError::Custom(format!(
+                "Main node returned miniblock #{} instead of #{expected_number}",
+                block.number
+            )));
+        }
+        range.push(block);
+        expected_number += 1;
+    }
+
+    // Missing miniblocks are only allowed at the end of the range.
+    if let Some(block) = blocks.flatten().next() {
+        return Err(RpcError::Custom(format!(
+            "Gap in the miniblock range returned by the main node: miniblock #{expected_number} \
+             is missing, but #{} is present",
+            block.number
+        )));
+    }
+    Ok(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use zksync_types::L1BatchNumber;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct MockMainNodeClient {
+        blocks: HashMap<MiniblockNumber, SyncBlock>,
+        in_flight_requests: AtomicUsize,
+        max_in_flight_requests: AtomicUsize,
+    }
+
+    impl MockMainNodeClient {
+        fn insert_block(&mut self, requested_number: u32, actual_number: u32) {
+            let block = SyncBlock {
+                number: MiniblockNumber(actual_number),
+                l1_batch_number: L1BatchNumber(1),
+                last_in_batch: false,
+                timestamp: actual_number.into(),
+                root_hash: None,
+                commit_tx_hash: None,
+                committed_at: None,
+                prove_tx_hash: None,
+                proven_at: None,
+                execute_tx_hash: None,
+                executed_at: None,
+                l1_gas_price: 1,
+                l2_fair_gas_price: 1,
+                base_system_contracts_hashes: Default::default(),
+                operator_address: Default::default(),
+                transactions: Some(vec![]),
+            };
+            self.blocks.insert(MiniblockNumber(requested_number), block);
+        }
+    }
+
+    #[async_trait]
+    impl MainNodeClient for MockMainNodeClient {
+        async fn sync_l2_block(
+            &self,
+            number: MiniblockNumber,
+            with_transactions: bool,
+        ) -> RpcResult<Option<SyncBlock>> {
+            assert!(with_transactions);
+            let in_flight_requests = self.in_flight_requests.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight_requests
+                .fetch_max(in_flight_requests, Ordering::SeqCst);
+            // Emulate network latency so that concurrent requests overlap.
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            self.in_flight_requests.fetch_sub(1, Ordering::SeqCst);
+            Ok(self.blocks.get(&number).cloned())
+        }
+    }
+
+    fn block_numbers(blocks: &[SyncBlock]) -> Vec<u32> {
+        blocks.iter().map(|block| block.number.0).collect()
+    }
+
+    #[tokio::test]
+    async fn fetching_miniblock_range() {
+        let mut client = MockMainNodeClient::default();
+        for number in 1..=5 {
+            client.insert_block(number, number);
+        }
+
+        let blocks = client
+            .fetch_miniblock_range(MiniblockNumber(2), MiniblockNumber(4))
+            .await
+            .unwrap();
+        assert_eq!(block_numbers(&blocks), [2, 3, 4]);
+
+        // Miniblocks missing on the main node are truncated from the end of the range.
+        let blocks = client
+            .fetch_miniblock_range(MiniblockNumber(4), MiniblockNumber(10))
+            .await
+            .unwrap();
+        assert_eq!(block_numbers(&blocks), [4, 5]);
+    }
+
+    #[tokio::test]
+    async fn fetching_miniblock_range_has_bounded_concurrency() {
+        let mut client = MockMainNodeClient::default();
+        for number in 1..=50 {
+            client.insert_block(number, number);
+        }
+
+        let blocks = client
+            .fetch_miniblock_range(MiniblockNumber(1), MiniblockNumber(50))
+            .await
+            .unwrap();
+        assert_eq!(block_numbers(&blocks), (1..=50).collect::<Vec<_>>());
+        assert_eq!(
+            client.max_in_flight_requests.load(Ordering::SeqCst),
+            MAX_CONCURRENT_MINIBLOCK_REQUESTS
+        );
+    }
+
+    #[tokio::test]
+    async fn miniblock_range_with_gap_is_rejected() {
+        let mut client = MockMainNodeClient::default();
+        for number in [1, 2, 4, 5] {
+            client.insert_block(number, number);
+        }
+        let err = client
+            .fetch_miniblock_range(MiniblockNumber(1), MiniblockNumber(5))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Gap"), "{err}");
+
+        // Miniblock with an unexpected number.
+        let mut client = MockMainNodeClient::default();
+        client.insert_block(1, 1);
+        client.insert_block(2, 3);
+        let err = client
+            .fetch_miniblock_range(MiniblockNumber(1), MiniblockNumber(2))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("instead of #2"), "{err}");
+    }
+}
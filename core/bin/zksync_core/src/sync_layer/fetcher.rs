@@ -109,20 +109,26 @@ impl MainNodeFetcher {
             self.client
                 .populate_miniblocks_cache(self.current_miniblock, last_main_node_block)
                 .await;
-            let has_action_capacity = self.actions.has_action_capacity();
+            // Wait for the state keeper to process queued actions instead of buffering fetched data
+            // indefinitely. The wait is bounded so that the stop signal is checked periodically.
+            let has_action_capacity =
+                tokio::time::timeout(DELAY_INTERVAL, self.actions.wait_for_capacity())
+                    .await
+                    .is_ok();
             if has_action_capacity {
                 progressed |= self.fetch_next_miniblock().await?;
             }
 
             if !progressed {
-                // We didn't fetch any updated on this iteration, so to prevent a busy loop we wait a bit.
-                let log_message = if has_action_capacity {
-                    "No updates to discover, waiting for new blocks on the main node"
+                if has_action_capacity {
+                    // We didn't fetch any updated on this iteration, so to prevent a busy loop we wait a bit.
+                    vlog::debug!("No updates to discover, waiting for new blocks on the main node");
+                    tokio::time::sleep(DELAY_INTERVAL).await;
                 } else {
-                    "Local action queue is full, waiting for state keeper to process the queue"
-                };
-                vlog::debug!("{log_message}");
-                tokio::time::sleep(DELAY_INTERVAL).await;
+                    vlog::debug!(
+                        "Local action queue is full, waiting for state keeper to process the queue"
+                    );
+                }
             }
         }
     }
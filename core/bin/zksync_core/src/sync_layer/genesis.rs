@@ -1,7 +1,13 @@
-use crate::genesis::{ensure_genesis_state, GenesisParams};
+use anyhow::Context as _;
+
+use crate::{
+    genesis::{ensure_genesis_state, insert_base_system_contracts_to_factory_deps, GenesisParams},
+    snapshot_importer::SnapshotImporter,
+};
 
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes, SystemContractCode};
 use zksync_dal::StorageProcessor;
+use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{L1BatchNumber, L2ChainId, H256};
 use zksync_web3_decl::{
     jsonrpsee::{core::error::Error, http_client::HttpClientBuilder},
@@ -30,6 +36,42 @@ pub async fn perform_genesis_if_needed(
     transaction.commit().await;
 }
 
+/// Initializes the storage from the snapshot for the specified L1 batch instead of performing genesis.
+/// Base system contracts used by the snapshot's L1 batch are fetched from the main node.
+pub async fn recover_from_snapshot(
+    storage: &mut StorageProcessor<'_>,
+    store_factory: &ObjectStoreFactory,
+    l1_batch_number: L1BatchNumber,
+    main_node_url: &str,
+) -> anyhow::Result<()> {
+    let mut transaction = storage.start_transaction().await;
+
+    let importer = SnapshotImporter::new(store_factory).await;
+    let manifest = importer
+        .import_snapshot(&mut transaction, l1_batch_number)
+        .await?;
+    let base_system_contracts = fetch_base_system_contracts(
+        main_node_url,
+        manifest.l1_batch_header.base_system_contracts_hashes,
+    )
+    .await
+    .context("failed fetching base system contracts from the main node")?;
+    insert_base_system_contracts_to_factory_deps(
+        &mut transaction,
+        manifest.miniblock_header.number,
+        base_system_contracts,
+    )
+    .await;
+
+    transaction.commit().await;
+    vlog::info!(
+        "Recovered storage from snapshot for L1 batch #{}, miniblock #{}",
+        l1_batch_number,
+        manifest.miniblock_header.number
+    );
+    Ok(())
+}
+
 // When running an external node, we want to make sure we have the same
 // genesis root hash as the main node.
 async fn validate_genesis_state(main_node_url: &str, root_hash: H256) {
@@ -1,5 +1,6 @@
 pub mod batch_status_updater;
 mod cached_main_node_client;
+pub mod client;
 pub mod external_io;
 pub mod fetcher;
 pub mod genesis;
@@ -7,6 +8,7 @@ pub(crate) mod sync_action;
 mod sync_state;
 
 pub use self::{
+    client::MainNodeClient,
     external_io::{ExternalIO, ExternalNodeSealer},
     sync_action::ActionQueue,
     sync_state::SyncState,
@@ -4,27 +4,55 @@ use std::{
     time::Instant,
 };
 
+use tokio::sync::Notify;
+
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{Address, L1BatchNumber, MiniblockNumber, Transaction};
 
 /// Action queue is used to communicate between the fetcher and the rest of the external node
 /// by collecting the fetched data in memory until it gets processed by the different entities.
-#[derive(Debug, Clone, Default)]
+///
+/// The queue has limited capacity to avoid memory exhaustion if the fetcher is faster than
+/// the state keeper; see [`Self::wait_for_capacity()`].
+#[derive(Debug, Clone)]
 pub struct ActionQueue {
     inner: Arc<RwLock<ActionQueueInner>>,
+    capacity: usize,
+    /// Notifies the fetcher that actions were removed from the queue.
+    capacity_notify: Arc<Notify>,
+}
+
+impl Default for ActionQueue {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
 }
 
 impl ActionQueue {
+    pub const DEFAULT_CAPACITY: usize = 32_768;
+
     pub fn new() -> Self {
         Self::default()
     }
 
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "Action queue capacity must be positive");
+        Self {
+            inner: Arc::default(),
+            capacity,
+            capacity_notify: Arc::default(),
+        }
+    }
+
     /// Removes the first action from the queue.
     pub(crate) fn pop_action(&self) -> Option<SyncAction> {
-        self.write_lock().actions.pop_front().map(|action| {
-            metrics::decrement_gauge!("external_node.action_queue.action_queue_size", 1_f64);
-            action
-        })
+        let mut inner = self.write_lock();
+        let action = inner.actions.pop_front()?;
+        Self::report_queue_size(inner.actions.len());
+        drop(inner);
+
+        self.capacity_notify.notify_waiters();
+        Some(action)
     }
 
     /// Returns the first action from the queue without removing it.
@@ -35,14 +63,29 @@ impl ActionQueue {
     /// Returns true if the queue has capacity for a new action.
     /// Capacity is limited to avoid memory exhaustion.
     pub(crate) fn has_action_capacity(&self) -> bool {
-        const ACTION_CAPACITY: usize = 32_768;
-
         // Since the capacity is read before the action is pushed,
         // it is possible that the capacity will be exceeded, since the fetcher will
         // decompose received data into a sequence of actions.
         // This is not a problem, since the size of decomposed action is much smaller
         // than the configured capacity.
-        self.read_lock().actions.len() < ACTION_CAPACITY
+        self.read_lock().actions.len() < self.capacity
+    }
+
+    /// Waits until the queue has capacity for new actions.
+    ///
+    /// A complete action sequence for a miniblock is always pushed once there is *some* capacity,
+    /// even if it exceeds the remaining capacity. Otherwise, the state keeper (which is the only
+    /// consumer) could wait for the rest of the sequence, while the fetcher waits for capacity.
+    pub(crate) async fn wait_for_capacity(&self) {
+        loop {
+            // The future must be created before checking capacity so that we don't miss
+            // a notification sent in between.
+            let notified = self.capacity_notify.notified();
+            if self.has_action_capacity() {
+                return;
+            }
+            notified.await;
+        }
     }
 
     /// Pushes a set of actions to the queue.
@@ -53,12 +96,14 @@ impl ActionQueue {
     pub(crate) fn push_actions(&self, actions: Vec<SyncAction>) {
         // We need to enforce the ordering of actions to make sure that they can be processed.
         Self::check_action_sequence(&actions).expect("Invalid sequence of actions.");
-        metrics::increment_gauge!(
-            "external_node.action_queue.action_queue_size",
-            actions.len() as f64
-        );
 
-        self.write_lock().actions.extend(actions);
+        let mut inner = self.write_lock();
+        inner.actions.extend(actions);
+        Self::report_queue_size(inner.actions.len());
+    }
+
+    fn report_queue_size(size: usize) {
+        metrics::gauge!("external_node.action_queue.action_queue_size", size as f64);
     }
 
     /// Checks whether the action sequence is valid.
@@ -153,6 +198,8 @@ impl From<Transaction> for SyncAction {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use zksync_types::{l2::L2Tx, H256};
 
     use super::*;
@@ -273,4 +320,38 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn fetcher_is_blocked_by_slow_consumer() {
+        const BLOCK_COUNT: usize = 20;
+
+        let queue = ActionQueue::with_capacity(4);
+        let producer_queue = queue.clone();
+        let producer = tokio::spawn(async move {
+            let mut max_queue_size = 0;
+            for _ in 0..BLOCK_COUNT {
+                producer_queue.wait_for_capacity().await;
+                producer_queue.push_actions(vec![miniblock(), tx(), seal_miniblock()]);
+                max_queue_size = max_queue_size.max(producer_queue.read_lock().actions.len());
+            }
+            max_queue_size
+        });
+
+        // The producer should block once the queue is full rather than buffer all actions.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!producer.is_finished());
+        assert_eq!(queue.read_lock().actions.len(), 6);
+
+        let mut popped_actions = 0;
+        while popped_actions < BLOCK_COUNT * 3 {
+            if queue.pop_action().is_some() {
+                popped_actions += 1;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        let max_queue_size = producer.await.unwrap();
+        // The queue may only exceed its capacity by a single action sequence.
+        assert!(max_queue_size <= 6, "{max_queue_size}");
+        assert!(queue.pop_action().is_none());
+    }
 }
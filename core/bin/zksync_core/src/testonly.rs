@@ -0,0 +1,25 @@
+//! Test utilities shared by unit tests in this crate.
+
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use once_cell::sync::Lazy;
+
+static METRICS_HANDLE: Lazy<PrometheusHandle> = Lazy::new(|| {
+    PrometheusBuilder::new()
+        .install_recorder()
+        .expect("failed installing global metrics recorder")
+});
+
+/// Returns the current value of a counter reported via the global `metrics` recorder. The recorder
+/// is installed on the first call, so this should be called before the tested code runs.
+/// The name must be specified in the Prometheus format (e.g., `api_ws_slow_consumer_dropped`).
+/// The number of values recorded to a histogram can be obtained using the `_count` suffix.
+///
+/// The recorder is shared by all tests, so tests should compare counter values before and after
+/// the tested action rather than check absolute values.
+pub(crate) fn counter_value(name: &str) -> u64 {
+    let rendered = METRICS_HANDLE.render();
+    rendered
+        .lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' ')?.parse().ok())
+        .unwrap_or(0)
+}
@@ -5,7 +5,7 @@ use async_trait::async_trait;
 
 use zksync_config::configs::{chain::CircuitBreakerConfig, ContractsConfig};
 use zksync_eth_client::{
-    types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
+    types::{BlobTxParams, Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
     BoundEthInterface, EthInterface,
 };
 use zksync_types::web3::types::Block;
@@ -147,6 +147,10 @@ impl EthInterface for ETHDirectClientMock {
         Ok(Default::default())
     }
 
+    async fn get_blob_base_fee(&self, _: &'static str) -> Result<U256, Error> {
+        Ok(Default::default())
+    }
+
     async fn base_fee_history(
         &self,
         _from_block: usize,
@@ -238,6 +242,23 @@ impl BoundEthInterface for ETHDirectClientMock {
         })
     }
 
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        _data: Vec<u8>,
+        _contract_addr: H160,
+        _options: Options,
+        _blob_params: BlobTxParams,
+        _component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        Ok(SignedCallResult {
+            raw_tx: vec![],
+            max_priority_fee_per_gas: U256::zero(),
+            max_fee_per_gas: U256::zero(),
+            nonce: U256::zero(),
+            hash: H256::zero(),
+        })
+    }
+
     async fn allowance_on_account(
         &self,
         _token_address: Address,
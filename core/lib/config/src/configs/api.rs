@@ -4,7 +4,7 @@ use serde::Deserialize;
 use std::net::SocketAddr;
 use std::time::Duration;
 // Local uses
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 pub use crate::configs::PrometheusConfig;
 use zksync_basic_types::H256;
 
@@ -78,12 +78,64 @@ pub struct Web3JsonRpcConfig {
     pub vm_concurrency_limit: Option<usize>,
     /// Smart contract cache size in MBs
     pub factory_deps_cache_size_mb: Option<usize>,
+    /// Whether to back the smart contract cache with an additional tier stored in the object store.
+    /// If set, bytecodes missing from the in-memory cache are looked up in the object store
+    /// before querying Postgres.
+    pub factory_deps_cache_object_store_tier: Option<bool>,
+    /// Whether to persist hashes of cached smart contracts on shutdown and to prewarm the smart contract
+    /// cache from them on startup.
+    pub factory_deps_cache_persist_keys: Option<bool>,
+    /// Path to the file with persisted hashes of cached smart contracts.
+    pub factory_deps_cache_dump_path: Option<String>,
     /// Override value for the amount of threads used for HTTP RPC server.
     /// If not set, the value from `threads_per_server` is used.
     pub http_threads: Option<u32>,
     /// Override value for the amount of threads used for WebSocket RPC server.
     /// If not set, the value from `threads_per_server` is used.
     pub ws_threads: Option<u32>,
+    /// Total amount of threads for the HTTP and WebSocket RPC servers. If set, threads are distributed
+    /// between the servers automatically, overriding `http_threads` and `ws_threads`. Must be at least 2
+    /// if both servers are run, so that each server gets a thread.
+    pub total_api_threads: Option<u32>,
+    /// Interval between pings sent to WebSocket clients (in s). Clients not responding to a ping until
+    /// the next one is due are disconnected. If not set, pings are not sent.
+    pub ws_ping_interval: Option<u64>,
+    /// Max number of `newHeads` subscriptions per WebSocket connection. If not set, not limited.
+    pub new_heads_subscriptions_limit: Option<usize>,
+    /// Max number of `newPendingTransactions` subscriptions per WebSocket connection. If not set, not limited.
+    pub pending_txs_subscriptions_limit: Option<usize>,
+    /// Max number of `logs` subscriptions per WebSocket connection. If not set, not limited.
+    pub logs_subscriptions_limit: Option<usize>,
+    /// Max number of notifications sent to a WebSocket client, but not acknowledged by it (acknowledgements
+    /// are piggybacked on pings). If a client doesn't read notifications fast enough and exceeds the limit,
+    /// the connection is closed. If not set, not limited.
+    pub ws_max_buffered_notifications: Option<usize>,
+    /// Max number of block hashes returned by a single `eth_getFilterChanges` call for a block filter.
+    /// Caps `req_entities_limit` for block filters. If not set, defaults to 1024.
+    pub max_block_hash_batch: Option<usize>,
+    /// Max number of blocks that may be scanned by a single `eth_getLogs`-style query (after resolving
+    /// block tags). Queries spanning a wider range are rejected. If not set, defaults to 100000.
+    pub max_getlogs_block_range: Option<u32>,
+    /// Origins allowed to access the HTTP API from browsers (via CORS); `*` allows any origin.
+    /// If not set, any origin is allowed.
+    pub cors_origins: Option<Vec<String>>,
+    /// Min base fee per gas returned for blocks by the API (e.g., by `eth_getBlockByNumber`). Lower base fees,
+    /// which may occur right after genesis, are raised to this value; the stored values are not affected.
+    /// If not set, base fees are returned as is.
+    pub min_base_fee_per_gas: Option<u64>,
+    /// Max number of entries in the cache mapping miniblocks to L1 batches including them.
+    /// If not set, defaults to 100000.
+    pub l1_batch_number_cache_size: Option<u64>,
+    /// Whether to compress HTTP responses (the encoding is negotiated via the `Accept-Encoding` header).
+    /// If not set, responses are not compressed.
+    pub enable_response_compression: Option<bool>,
+    /// Min size of HTTP responses (in bytes) to be compressed. If not set, defaults to 1024.
+    pub response_compression_min_size: Option<usize>,
+    /// Whether to assemble a provisional block from mempool transactions when the `pending` block
+    /// is requested (e.g., by `eth_getBlockByNumber`). This is more expensive than loading a sealed block.
+    /// Transactions are ordered according to the mempool ordering strategy.
+    /// If not set, `null` is returned for the pending block.
+    pub assemble_pending_block: Option<bool>,
 }
 
 impl Web3JsonRpcConfig {
@@ -91,6 +143,12 @@ impl Web3JsonRpcConfig {
         envy_load("web3_json_rpc", "API_WEB3_JSON_RPC_")
     }
 
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("API_WEB3_JSON_RPC_", vars)
+    }
+
     pub fn http_bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.http_port)
     }
@@ -107,6 +165,30 @@ impl Web3JsonRpcConfig {
         self.filters_limit.unwrap_or(10000) as usize
     }
 
+    pub fn max_block_hash_batch(&self) -> usize {
+        self.max_block_hash_batch.unwrap_or(1024)
+    }
+
+    pub fn max_getlogs_block_range(&self) -> u32 {
+        self.max_getlogs_block_range.unwrap_or(100_000)
+    }
+
+    pub fn l1_batch_number_cache_size(&self) -> u64 {
+        self.l1_batch_number_cache_size.unwrap_or(100_000)
+    }
+
+    pub fn enable_response_compression(&self) -> bool {
+        self.enable_response_compression.unwrap_or(false)
+    }
+
+    pub fn response_compression_min_size(&self) -> usize {
+        self.response_compression_min_size.unwrap_or(1_024)
+    }
+
+    pub fn assemble_pending_block(&self) -> bool {
+        self.assemble_pending_block.unwrap_or(false)
+    }
+
     pub fn subscriptions_limit(&self) -> usize {
         self.subscriptions_limit.unwrap_or(10000) as usize
     }
@@ -128,6 +210,24 @@ impl Web3JsonRpcConfig {
         self.factory_deps_cache_size_mb.unwrap_or(128)
     }
 
+    pub fn factory_deps_cache_object_store_tier(&self) -> bool {
+        self.factory_deps_cache_object_store_tier.unwrap_or(false)
+    }
+
+    pub fn factory_deps_cache_persist_keys(&self) -> bool {
+        self.factory_deps_cache_persist_keys.unwrap_or(false)
+    }
+
+    pub fn factory_deps_cache_dump_path(&self) -> &str {
+        self.factory_deps_cache_dump_path
+            .as_deref()
+            .unwrap_or("./db/factory_deps_cache_keys")
+    }
+
+    pub fn ws_ping_interval(&self) -> Option<Duration> {
+        self.ws_ping_interval.map(Duration::from_secs)
+    }
+
     pub fn http_server_threads(&self) -> usize {
         self.http_threads.unwrap_or(self.threads_per_server) as usize
     }
@@ -135,12 +235,49 @@ impl Web3JsonRpcConfig {
     pub fn ws_server_threads(&self) -> usize {
         self.ws_threads.unwrap_or(self.threads_per_server) as usize
     }
+
+    /// Distributes `total_api_threads` (if set) between the HTTP and WebSocket RPC servers.
+    /// If both servers are run, threads are split evenly between them; otherwise,
+    /// the single running server receives all threads. The distributed threads never exceed the total.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `total_api_threads` is too small for each running server to get a thread.
+    pub fn distribute_api_threads(&mut self, run_http: bool, run_ws: bool) {
+        let Some(total_threads) = self.total_api_threads else {
+            return;
+        };
+        if run_http && run_ws {
+            assert!(
+                total_threads >= 2,
+                "`total_api_threads` must be at least 2 to run both HTTP and WS servers, got {}",
+                total_threads
+            );
+            let http_threads = (total_threads + 1) / 2;
+            self.http_threads = Some(http_threads);
+            self.ws_threads = Some(total_threads - http_threads);
+        } else {
+            assert!(total_threads > 0, "`total_api_threads` must be positive");
+            self.http_threads = Some(total_threads);
+            self.ws_threads = Some(total_threads);
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct HealthCheckConfig {
     /// Port to which the REST server is listening.
     pub port: u16,
+    /// URLs of health endpoints of remote components, which are polled by the health check aggregator.
+    pub remote_urls: Option<Vec<String>>,
+    /// If set, the health check server is co-located with the API servers: it's only started if an API server
+    /// or the health check aggregator is running, and only health checks relevant to the running components
+    /// are registered (e.g., the replica connection pool is only checked if an API server is running).
+    /// Default is `false`.
+    pub scope_to_components: Option<bool>,
+    /// If set, degraded health checks (e.g., a lagging Merkle tree) fail the `/live` endpoint in addition
+    /// to the `/health` one. Default is `false`.
+    pub degraded_fails_liveness: Option<bool>,
 }
 
 impl HealthCheckConfig {
@@ -148,9 +285,27 @@ impl HealthCheckConfig {
         envy_load("healthcheck", "API_HEALTHCHECK_")
     }
 
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("API_HEALTHCHECK_", vars)
+    }
+
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    pub fn remote_urls(&self) -> Vec<String> {
+        self.remote_urls.clone().unwrap_or_default()
+    }
+
+    pub fn scope_to_components(&self) -> bool {
+        self.scope_to_components.unwrap_or(false)
+    }
+
+    pub fn degraded_fails_liveness(&self) -> bool {
+        self.degraded_fails_liveness.unwrap_or(false)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -167,6 +322,9 @@ pub struct ExplorerApiConfig {
     pub offset_limit: Option<u32>,
     /// number of threads per server
     pub threads_per_server: u32,
+    /// Max number of requests concurrently processed by the server. Requests exceeding this limit
+    /// are rejected with 503 Service Unavailable. If not set, the number of requests is not limited.
+    pub max_concurrent_requests: Option<usize>,
 }
 
 impl ExplorerApiConfig {
@@ -189,12 +347,18 @@ impl ExplorerApiConfig {
     pub fn from_env() -> Self {
         envy_load("explorer", "API_EXPLORER_")
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("API_EXPLORER_", vars)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::configs::test_utils::set_env;
+    use crate::configs::{test_utils::set_env, utils::PrometheusExporterMode};
     use std::net::IpAddr;
     use std::str::FromStr;
 
@@ -230,8 +394,28 @@ mod tests {
                 vm_execution_cache_misses_limit: None,
                 vm_concurrency_limit: Some(512),
                 factory_deps_cache_size_mb: Some(128),
+                factory_deps_cache_object_store_tier: Some(true),
+                factory_deps_cache_persist_keys: Some(true),
+                factory_deps_cache_dump_path: Some("/db/factory_deps_cache_keys".into()),
                 http_threads: Some(128),
                 ws_threads: Some(256),
+                total_api_threads: None,
+                ws_ping_interval: Some(30),
+                new_heads_subscriptions_limit: Some(10),
+                pending_txs_subscriptions_limit: Some(10),
+                logs_subscriptions_limit: Some(5),
+                ws_max_buffered_notifications: Some(1024),
+                max_block_hash_batch: Some(500),
+                max_getlogs_block_range: Some(50000),
+                cors_origins: Some(vec![
+                    "https://app.example.com".into(),
+                    "https://wallet.example.com".into(),
+                ]),
+                min_base_fee_per_gas: Some(100_000_000),
+                l1_batch_number_cache_size: Some(50_000),
+                enable_response_compression: Some(true),
+                response_compression_min_size: Some(2_048),
+                assemble_pending_block: Some(true),
             },
             explorer: ExplorerApiConfig {
                 port: 3070,
@@ -240,13 +424,24 @@ mod tests {
                 req_entities_limit: Some(100),
                 offset_limit: Some(10000),
                 threads_per_server: 128,
+                max_concurrent_requests: Some(64),
             },
             prometheus: PrometheusConfig {
                 listener_port: 3312,
                 pushgateway_url: "http://127.0.0.1:9091".into(),
                 push_interval_ms: Some(100),
+                exporter_mode: PrometheusExporterMode::Push,
+                collect_runtime_metrics: Some(true),
+            },
+            healthcheck: HealthCheckConfig {
+                port: 8081,
+                remote_urls: Some(vec![
+                    "http://127.0.0.1:3081/health".into(),
+                    "http://127.0.0.1:3082/health".into(),
+                ]),
+                scope_to_components: Some(true),
+                degraded_fails_liveness: Some(true),
             },
-            healthcheck: HealthCheckConfig { port: 8081 },
         }
     }
 
@@ -272,18 +467,40 @@ API_WEB3_JSON_RPC_ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION=1000
 API_WEB3_JSON_RPC_MAX_TX_SIZE=1000000
 API_WEB3_JSON_RPC_VM_CONCURRENCY_LIMIT=512
 API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_SIZE_MB=128
+API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_OBJECT_STORE_TIER=true
+API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_PERSIST_KEYS=true
+API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_DUMP_PATH=/db/factory_deps_cache_keys
 API_WEB3_JSON_RPC_HTTP_THREADS=128
 API_WEB3_JSON_RPC_WS_THREADS=256
+API_WEB3_JSON_RPC_WS_PING_INTERVAL=30
+API_WEB3_JSON_RPC_NEW_HEADS_SUBSCRIPTIONS_LIMIT=10
+API_WEB3_JSON_RPC_PENDING_TXS_SUBSCRIPTIONS_LIMIT=10
+API_WEB3_JSON_RPC_LOGS_SUBSCRIPTIONS_LIMIT=5
+API_WEB3_JSON_RPC_WS_MAX_BUFFERED_NOTIFICATIONS=1024
+API_WEB3_JSON_RPC_MAX_BLOCK_HASH_BATCH=500
+API_WEB3_JSON_RPC_MAX_GETLOGS_BLOCK_RANGE=50000
+API_WEB3_JSON_RPC_CORS_ORIGINS=https://app.example.com,https://wallet.example.com
+API_WEB3_JSON_RPC_MIN_BASE_FEE_PER_GAS=100000000
+API_WEB3_JSON_RPC_L1_BATCH_NUMBER_CACHE_SIZE=50000
+API_WEB3_JSON_RPC_ENABLE_RESPONSE_COMPRESSION=true
+API_WEB3_JSON_RPC_RESPONSE_COMPRESSION_MIN_SIZE=2048
+API_WEB3_JSON_RPC_ASSEMBLE_PENDING_BLOCK=true
 API_EXPLORER_PORT="3070"
 API_EXPLORER_URL="http://127.0.0.1:3070"
 API_EXPLORER_NETWORK_STATS_POLLING_INTERVAL="1000"
 API_EXPLORER_REQ_ENTITIES_LIMIT=100
 API_EXPLORER_OFFSET_LIMIT=10000
 API_EXPLORER_THREADS_PER_SERVER=128
+API_EXPLORER_MAX_CONCURRENT_REQUESTS=64
 API_PROMETHEUS_LISTENER_PORT="3312"
 API_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
 API_PROMETHEUS_PUSH_INTERVAL_MS=100
+API_PROMETHEUS_EXPORTER_MODE="Push"
+API_PROMETHEUS_COLLECT_RUNTIME_METRICS=true
 API_HEALTHCHECK_PORT=8081
+API_HEALTHCHECK_REMOTE_URLS=http://127.0.0.1:3081/health,http://127.0.0.1:3082/health
+API_HEALTHCHECK_SCOPE_TO_COMPONENTS=true
+API_HEALTHCHECK_DEGRADED_FAILS_LIVENESS=true
         "#;
         set_env(config);
 
@@ -301,9 +518,46 @@ API_HEALTHCHECK_PORT=8081
             config.web3_json_rpc.pubsub_interval(),
             Duration::from_millis(200)
         );
+        assert_eq!(
+            config.web3_json_rpc.ws_ping_interval(),
+            Some(Duration::from_secs(30))
+        );
         assert_eq!(
             config.explorer.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.explorer.port)
         );
     }
+
+    #[test]
+    fn distributing_api_threads() {
+        let mut config = expected_config().web3_json_rpc;
+        config.distribute_api_threads(true, true);
+        assert_eq!(config.http_server_threads(), 128);
+        assert_eq!(config.ws_server_threads(), 256);
+
+        config.total_api_threads = Some(15);
+        config.distribute_api_threads(true, true);
+        assert_eq!(config.http_server_threads(), 8);
+        assert_eq!(config.ws_server_threads(), 7);
+
+        config.distribute_api_threads(false, true);
+        assert_eq!(config.ws_server_threads(), 15);
+
+        config.total_api_threads = Some(2);
+        config.distribute_api_threads(true, true);
+        assert_eq!(config.http_server_threads(), 1);
+        assert_eq!(config.ws_server_threads(), 1);
+
+        config.total_api_threads = Some(1);
+        config.distribute_api_threads(true, false);
+        assert_eq!(config.http_server_threads(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be at least 2")]
+    fn distributing_too_few_api_threads() {
+        let mut config = expected_config().web3_json_rpc;
+        config.total_api_threads = Some(1);
+        config.distribute_api_threads(true, true);
+    }
 }
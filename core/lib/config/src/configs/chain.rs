@@ -7,7 +7,7 @@ use zksync_basic_types::network::Network;
 use zksync_basic_types::{Address, H256};
 use zksync_contracts::BaseSystemContractsHashes;
 
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ChainConfig {
@@ -51,6 +51,12 @@ impl NetworkConfig {
     pub fn from_env() -> Self {
         envy_load("network", "CHAIN_ETH_")
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("CHAIN_ETH_", vars)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq, Default)]
@@ -99,6 +105,9 @@ pub struct StateKeeperConfig {
     /// Max number of computational gas that validation step is allowed to take.
     pub validation_computational_gas_limit: u32,
     pub save_call_traces: bool,
+    /// Whether the state keeper runs in the shadow mode, i.e., re-executes L1 batches sealed
+    /// by another node and compares results instead of sealing new batches.
+    pub shadow_mode: Option<bool>,
 }
 
 impl StateKeeperConfig {
@@ -106,12 +115,22 @@ impl StateKeeperConfig {
         envy_load("state_keeper", "CHAIN_STATE_KEEPER_")
     }
 
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("CHAIN_STATE_KEEPER_", vars)
+    }
+
     pub fn base_system_contracts_hashes(&self) -> BaseSystemContractsHashes {
         BaseSystemContractsHashes {
             bootloader: self.bootloader_hash,
             default_aa: self.default_aa_hash,
         }
     }
+
+    pub fn shadow_mode(&self) -> bool {
+        self.shadow_mode.unwrap_or(false)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -125,6 +144,12 @@ impl OperationsManagerConfig {
         envy_load("operations_manager", "CHAIN_OPERATIONS_MANAGER_")
     }
 
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("CHAIN_OPERATIONS_MANAGER_", vars)
+    }
+
     pub fn delay_interval(&self) -> Duration {
         Duration::from_millis(self.delay_interval)
     }
@@ -142,6 +167,12 @@ impl CircuitBreakerConfig {
         envy_load("circuit_breaker", "CHAIN_CIRCUIT_BREAKER_")
     }
 
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("CHAIN_CIRCUIT_BREAKER_", vars)
+    }
+
     pub fn sync_interval(&self) -> Duration {
         Duration::from_millis(self.sync_interval_ms)
     }
@@ -151,6 +182,16 @@ impl CircuitBreakerConfig {
     }
 }
 
+/// Order in which pending L2 transactions are selected from Postgres into the mempool.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MempoolOrderingStrategy {
+    /// Transactions are selected in the order they were received.
+    Fifo,
+    /// Transactions are selected by their effective priority fee (descending). Transactions
+    /// from the same account are still selected in the nonce order.
+    PriorityFee,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct MempoolConfig {
     pub sync_interval_ms: u64,
@@ -159,6 +200,8 @@ pub struct MempoolConfig {
     pub stuck_tx_timeout: u64,
     pub remove_stuck_txs: bool,
     pub delay_interval: u64,
+    /// Order of selecting pending L2 transactions into the mempool. Defaults to [`MempoolOrderingStrategy::Fifo`].
+    pub ordering_strategy: Option<MempoolOrderingStrategy>,
 }
 
 impl MempoolConfig {
@@ -174,9 +217,20 @@ impl MempoolConfig {
         Duration::from_millis(self.delay_interval)
     }
 
+    pub fn ordering_strategy(&self) -> MempoolOrderingStrategy {
+        self.ordering_strategy
+            .unwrap_or(MempoolOrderingStrategy::Fifo)
+    }
+
     pub fn from_env() -> Self {
         envy_load("mempool", "CHAIN_MEMPOOL_")
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("CHAIN_MEMPOOL_", vars)
+    }
 }
 
 #[cfg(test)]
@@ -210,6 +264,7 @@ mod tests {
                 default_aa_hash: H256::from(&[254; 32]),
                 validation_computational_gas_limit: 10_000_000,
                 save_call_traces: false,
+                shadow_mode: Some(true),
             },
             operations_manager: OperationsManagerConfig {
                 delay_interval: 100,
@@ -221,6 +276,7 @@ mod tests {
                 stuck_tx_timeout: 10,
                 remove_stuck_txs: true,
                 delay_interval: 100,
+                ordering_strategy: Some(MempoolOrderingStrategy::PriorityFee),
             },
             circuit_breaker: CircuitBreakerConfig {
                 sync_interval_ms: 1000,
@@ -254,6 +310,7 @@ CHAIN_STATE_KEEPER_BOOTLOADER_HASH="0xfefefefefefefefefefefefefefefefefefefefefe
 CHAIN_STATE_KEEPER_DEFAULT_AA_HASH="0xfefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefe"
 CHAIN_STATE_KEEPER_VALIDATION_COMPUTATIONAL_GAS_LIMIT="10000000"
 CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
+CHAIN_STATE_KEEPER_SHADOW_MODE="true"
 CHAIN_OPERATIONS_MANAGER_DELAY_INTERVAL="100"
 CHAIN_MEMPOOL_SYNC_INTERVAL_MS="10"
 CHAIN_MEMPOOL_SYNC_BATCH_SIZE="1000"
@@ -261,6 +318,7 @@ CHAIN_MEMPOOL_STUCK_TX_TIMEOUT="10"
 CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
 CHAIN_MEMPOOL_DELAY_INTERVAL="100"
 CHAIN_MEMPOOL_CAPACITY="1000000"
+CHAIN_MEMPOOL_ORDERING_STRATEGY="PriorityFee"
 CHAIN_CIRCUIT_BREAKER_SYNC_INTERVAL_MS="1000"
 CHAIN_CIRCUIT_BREAKER_HTTP_REQ_MAX_RETRY_NUMBER="5"
 CHAIN_CIRCUIT_BREAKER_HTTP_REQ_RETRY_INTERVAL_SEC="2"
@@ -3,7 +3,7 @@ use serde::Deserialize;
 // Workspace uses
 use zksync_basic_types::{Address, H256};
 // Local uses
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 
 /// Data about deployed contracts.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -32,6 +32,12 @@ impl ContractsConfig {
     pub fn from_env() -> Self {
         envy_load("contracts", "CONTRACTS_")
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("CONTRACTS_", vars)
+    }
 }
 
 #[cfg(test)]
@@ -20,6 +20,8 @@ pub struct DBConfig {
     pub backup_interval_ms: u64,
     /// Maximum number of blocks to be processed by the Merkle tree at a time.
     pub max_block_batch: usize,
+    /// DAL requests taking longer than this threshold (in milliseconds) are logged.
+    pub slow_query_threshold_ms: u64,
 }
 
 impl Default for DBConfig {
@@ -32,6 +34,7 @@ impl Default for DBConfig {
             backup_count: 5,
             backup_interval_ms: 60_000,
             max_block_batch: 100,
+            slow_query_threshold_ms: 5_000,
         }
     }
 }
@@ -60,6 +63,9 @@ impl DBConfig {
         if let Some(size) = Self::parse_env_var("DATABASE_MAX_BLOCK_BATCH") {
             config.max_block_batch = size;
         }
+        if let Some(threshold) = Self::parse_env_var("DATABASE_SLOW_QUERY_THRESHOLD_MS") {
+            config.slow_query_threshold_ms = threshold;
+        }
         config
     }
 
@@ -95,6 +101,11 @@ impl DBConfig {
     pub fn max_block_batch(&self) -> usize {
         self.max_block_batch
     }
+
+    /// Threshold for logging slow DAL requests.
+    pub fn slow_query_threshold(&self) -> Duration {
+        Duration::from_millis(self.slow_query_threshold_ms)
+    }
 }
 
 #[cfg(test)]
@@ -112,6 +123,7 @@ DATABASE_NEW_MERKLE_TREE_THROTTLE_MS=0
 DATABASE_BACKUP_COUNT=5
 DATABASE_BACKUP_INTERVAL_MS=60000
 DATABASE_MAX_BLOCK_BATCH=100
+DATABASE_SLOW_QUERY_THRESHOLD_MS=5000
         "#;
         set_env(config);
 
@@ -134,5 +146,6 @@ DATABASE_MAX_BLOCK_BATCH=100
         );
         assert_eq!(db_config.backup_count(), db_config.backup_count);
         assert_eq!(db_config.backup_interval().as_secs(), 60);
+        assert_eq!(db_config.slow_query_threshold().as_secs(), 5);
     }
 }
@@ -1,7 +1,7 @@
 // External uses
 use serde::Deserialize;
 // Local uses
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 
 /// Configuration for the Ethereum gateways.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -23,6 +23,19 @@ impl ETHClientConfig {
         }
         config
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        let config: Self = envy_try_load_from("ETH_CLIENT_", vars)?;
+        if config.web3_url.contains(',') {
+            return Err(envy::Error::Custom(format!(
+                "Multiple web3 URLs aren't supported anymore. Provided invalid value: {}",
+                config.web3_url
+            )));
+        }
+        Ok(config)
+    }
 }
 
 #[cfg(test)]
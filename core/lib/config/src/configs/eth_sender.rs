@@ -5,7 +5,7 @@ use serde::Deserialize;
 // Workspace uses
 use zksync_basic_types::H256;
 // Local uses
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 
 /// Configuration for the Ethereum sender crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -23,6 +23,15 @@ impl ETHSenderConfig {
             gas_adjuster: GasAdjusterConfig::from_env(),
         }
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        Ok(Self {
+            sender: SenderConfig::try_from_vars(vars)?,
+            gas_adjuster: GasAdjusterConfig::try_from_vars(vars)?,
+        })
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
@@ -55,6 +64,15 @@ pub struct SenderConfig {
     pub aggregated_block_prove_deadline: u64,
     pub aggregated_block_execute_deadline: u64,
     pub timestamp_criteria_max_allowed_lag: usize,
+    /// Maximum time in seconds an L1 batch may wait to be committed. Once the oldest batch is older,
+    /// all ready batches are committed regardless of other criteria. If not set, the deadline isn't enforced.
+    #[serde(default)]
+    pub max_batch_wait: Option<u64>,
+    /// Maximum size of the commit transaction calldata in bytes. Once committing the next
+    /// L1 batch would exceed it, the already collected batches are committed. Can only lower
+    /// `max_eth_tx_data_size`; if not set, only `max_eth_tx_data_size` limits the commit data.
+    #[serde(default)]
+    pub max_commit_size_bytes: Option<usize>,
 
     /// L1 batches will only be executed on L1 contract after they are at least this number of seconds old.
     /// Note that this number must be slightly higher than the one set on the contract,
@@ -62,9 +80,25 @@ pub struct SenderConfig {
     pub l1_batch_min_age_before_execute_seconds: Option<u64>,
     // Max acceptable fee for sending tx it acts as a safeguard to prevent sending tx with very high fees.
     pub max_acceptable_priority_fee_in_gwei: u64,
+    /// Whether operations listed in `blob_operations` are sent as EIP-4844 transactions with their pubdata
+    /// posted in blobs. Requires the L1 network to support blobs.
+    #[serde(default)]
+    pub use_blobs: Option<bool>,
+    /// Types of aggregated operations (e.g., `CommitBlocks`) sent as blob transactions if `use_blobs` is set.
+    /// Defaults to commit operations only.
+    #[serde(default)]
+    pub blob_operations: Option<Vec<String>>,
+    /// Percentage by which the priority fee is raised when a stuck transaction is resent.
+    /// Values below [`SenderConfig::MIN_RESEND_FEE_BUMP_PERCENT`] are raised to it.
+    #[serde(default)]
+    pub resend_fee_bump_percent: Option<u64>,
 }
 
 impl SenderConfig {
+    /// Minimum fee bump accepted by L1 nodes for replacement transactions.
+    pub const MIN_RESEND_FEE_BUMP_PERCENT: u64 = 10;
+    const DEFAULT_RESEND_FEE_BUMP_PERCENT: u64 = 20;
+
     /// Converts `self.tx_poll_period` into `Duration`.
     pub fn tx_poll_period(&self) -> Duration {
         Duration::from_secs(self.tx_poll_period)
@@ -74,6 +108,38 @@ impl SenderConfig {
         Duration::from_secs(self.aggregate_tx_poll_period)
     }
 
+    /// Returns the limit on the commit transaction calldata size in bytes.
+    pub fn commit_data_size_limit(&self) -> usize {
+        match self.max_commit_size_bytes {
+            Some(limit) => limit.min(self.max_eth_tx_data_size),
+            None => self.max_eth_tx_data_size,
+        }
+    }
+
+    /// Returns whether blob transactions are used; defaults to `false`.
+    pub fn use_blobs(&self) -> bool {
+        self.use_blobs.unwrap_or(false)
+    }
+
+    /// Returns types of aggregated operations sent as blob transactions. Empty if blobs aren't used.
+    pub fn blob_operations(&self) -> Vec<&str> {
+        if !self.use_blobs() {
+            return vec![];
+        }
+        match &self.blob_operations {
+            Some(operations) => operations.iter().map(String::as_str).collect(),
+            None => vec!["CommitBlocks"],
+        }
+    }
+
+    /// Returns the priority fee bump for resent transactions, in percent. Never lower than
+    /// [`Self::MIN_RESEND_FEE_BUMP_PERCENT`] so that the replacement isn't rejected as underpriced.
+    pub fn resend_fee_bump_percent(&self) -> u64 {
+        self.resend_fee_bump_percent
+            .unwrap_or(Self::DEFAULT_RESEND_FEE_BUMP_PERCENT)
+            .max(Self::MIN_RESEND_FEE_BUMP_PERCENT)
+    }
+
     // Don't load private key, if it's not required.
     pub fn private_key(&self) -> Option<H256> {
         std::env::var("ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY")
@@ -84,6 +150,12 @@ impl SenderConfig {
     pub fn from_env() -> Self {
         envy_load("eth_sender", "ETH_SENDER_SENDER_")
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("ETH_SENDER_SENDER_", vars)
+    }
 }
 
 #[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
@@ -104,6 +176,12 @@ pub struct GasAdjusterConfig {
     pub poll_period: u64,
     /// Max number of l1 gas price that is allowed to be used in state keeper.
     pub max_l1_gas_price: Option<u64>,
+    /// Max l1 gas price that is used to estimate fees for API users. Doesn't affect
+    /// the fees paid by the eth sender.
+    pub max_l1_gas_price_for_estimation: Option<u64>,
+    /// If set, L1 transactions are sent with a single (legacy) gas price instead of EIP1559 fees.
+    #[serde(default)]
+    pub use_legacy_gas_price: bool,
 }
 
 impl GasAdjusterConfig {
@@ -119,6 +197,12 @@ impl GasAdjusterConfig {
     pub fn from_env() -> Self {
         envy_load("eth_sender.gas_adjuster", "ETH_SENDER_GAS_ADJUSTER_")
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("ETH_SENDER_GAS_ADJUSTER_", vars)
+    }
 }
 
 #[cfg(test)]
@@ -146,6 +230,11 @@ mod tests {
                 proof_sending_mode: ProofSendingMode::SkipEveryProof,
                 l1_batch_min_age_before_execute_seconds: Some(1000),
                 max_acceptable_priority_fee_in_gwei: 100_000_000_000,
+                max_batch_wait: Some(600),
+                max_commit_size_bytes: Some(100_000),
+                use_blobs: Some(true),
+                blob_operations: Some(vec!["CommitBlocks".to_owned()]),
+                resend_fee_bump_percent: Some(25),
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 20000000000,
@@ -156,6 +245,8 @@ mod tests {
                 internal_enforced_l1_gas_price: None,
                 poll_period: 15,
                 max_l1_gas_price: Some(100000000),
+                max_l1_gas_price_for_estimation: Some(200000000),
+                use_legacy_gas_price: true,
             },
         }
     }
@@ -176,6 +267,8 @@ ETH_SENDER_GAS_ADJUSTER_PRICING_FORMULA_PARAMETER_B="1.0005"
 ETH_SENDER_GAS_ADJUSTER_INTERNAL_L1_PRICING_MULTIPLIER="0.8"
 ETH_SENDER_GAS_ADJUSTER_POLL_PERIOD="15"
 ETH_SENDER_GAS_ADJUSTER_MAX_L1_GAS_PRICE="100000000"
+ETH_SENDER_GAS_ADJUSTER_MAX_L1_GAS_PRICE_FOR_ESTIMATION="200000000"
+ETH_SENDER_GAS_ADJUSTER_USE_LEGACY_GAS_PRICE="true"
 ETH_SENDER_WAIT_FOR_PROOFS="false"
 ETH_SENDER_SENDER_AGGREGATED_PROOF_SIZES="1,5"
 ETH_SENDER_SENDER_MAX_AGGREGATED_BLOCKS_TO_COMMIT="3"
@@ -184,10 +277,15 @@ ETH_SENDER_SENDER_AGGREGATED_BLOCK_COMMIT_DEADLINE="30"
 ETH_SENDER_SENDER_AGGREGATED_BLOCK_PROVE_DEADLINE="3000"
 ETH_SENDER_SENDER_AGGREGATED_BLOCK_EXECUTE_DEADLINE="4000"
 ETH_SENDER_SENDER_TIMESTAMP_CRITERIA_MAX_ALLOWED_LAG="30"
+ETH_SENDER_SENDER_MAX_BATCH_WAIT="600"
+ETH_SENDER_SENDER_MAX_COMMIT_SIZE_BYTES="100000"
 ETH_SENDER_SENDER_MAX_AGGREGATED_TX_GAS="4000000"
 ETH_SENDER_SENDER_MAX_ETH_TX_DATA_SIZE="120000"
 ETH_SENDER_SENDER_L1_BATCH_MIN_AGE_BEFORE_EXECUTE_SECONDS="1000"
 ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
+ETH_SENDER_SENDER_USE_BLOBS="true"
+ETH_SENDER_SENDER_BLOB_OPERATIONS="CommitBlocks"
+ETH_SENDER_SENDER_RESEND_FEE_BUMP_PERCENT="25"
         "#;
         set_env(config);
 
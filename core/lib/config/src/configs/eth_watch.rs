@@ -3,7 +3,7 @@ use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Local uses
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 
 /// Configuration for the Ethereum sender crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -14,6 +14,9 @@ pub struct ETHWatchConfig {
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
+    /// Max number of concurrent requests to the Ethereum node issued by the watcher, e.g. when fetching
+    /// a large range of L1 blocks in chunks. If not specified, requests are issued one at a time.
+    pub max_concurrent_l1_requests: Option<usize>,
 }
 
 impl ETHWatchConfig {
@@ -21,6 +24,12 @@ impl ETHWatchConfig {
         envy_load("eth_watch", "ETH_WATCH_")
     }
 
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("ETH_WATCH_", vars)
+    }
+
     /// Converts `self.eth_node_poll_interval` into `Duration`.
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
@@ -36,6 +45,7 @@ mod tests {
         ETHWatchConfig {
             confirmations_for_eth_event: Some(0),
             eth_node_poll_interval: 300,
+            max_concurrent_l1_requests: Some(4),
         }
     }
 
@@ -44,6 +54,7 @@ mod tests {
         let config = r#"
 ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT="0"
 ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
+ETH_WATCH_MAX_CONCURRENT_L1_REQUESTS="4"
         "#;
         set_env(config);
 
@@ -0,0 +1,56 @@
+// External uses
+use serde::Deserialize;
+// Workspace uses
+use zksync_basic_types::Address;
+// Local uses
+use super::envy_load;
+
+/// Configuration of the fee policy, which allows to reduce fees for certain transactions.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct FeePolicyConfig {
+    /// Contracts calls to which have their fees sponsored.
+    #[serde(default)]
+    pub sponsored_contracts: Vec<Address>,
+    /// Multiplier applied to fees of transactions calling sponsored contracts.
+    /// `0.0` makes such transactions free; defaults to `1.0`, i.e. fees are unchanged.
+    #[serde(default = "FeePolicyConfig::default_sponsored_fee_multiplier")]
+    pub sponsored_fee_multiplier: f64,
+}
+
+impl FeePolicyConfig {
+    fn default_sponsored_fee_multiplier() -> f64 {
+        1.0
+    }
+
+    pub fn from_env() -> Self {
+        envy_load("fee_policy", "FEE_POLICY_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::{addr, set_env};
+
+    fn expected_config() -> FeePolicyConfig {
+        FeePolicyConfig {
+            sponsored_contracts: vec![
+                addr("0000000000000000000000000000000000000001"),
+                addr("0000000000000000000000000000000000000002"),
+            ],
+            sponsored_fee_multiplier: 0.5,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+FEE_POLICY_SPONSORED_CONTRACTS="0x0000000000000000000000000000000000000001,0x0000000000000000000000000000000000000002"
+FEE_POLICY_SPONSORED_FEE_MULTIPLIER="0.5"
+        "#;
+        set_env(config);
+
+        let actual = FeePolicyConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+}
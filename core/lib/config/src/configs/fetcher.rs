@@ -1,11 +1,11 @@
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 
 // Built-in uses
 // External uses
 use serde::Deserialize;
 // Workspace uses
 // Local uses
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 
 #[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
 pub enum TokenListSource {
@@ -26,6 +26,18 @@ pub enum TokenTradingVolumeSource {
     Mock,
 }
 
+/// Kind of the data fetcher run by the server.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataFetcherKind {
+    TokenList,
+    TokenPrice,
+    TokenTradingVolume,
+}
+
+impl DataFetcherKind {
+    pub const ALL: [Self; 3] = [Self::TokenList, Self::TokenPrice, Self::TokenTradingVolume];
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct SingleFetcherConfig<TYPE> {
     /// Indicator of the API to be used for getting information.
@@ -42,17 +54,44 @@ impl<TYPE> SingleFetcherConfig<TYPE> {
     }
 }
 
+/// Settings shared by all the data fetchers.
+#[derive(Debug, Deserialize)]
+struct CommonFetcherConfig {
+    #[serde(default = "CommonFetcherConfig::default_max_feed_staleness")]
+    max_feed_staleness: u64,
+    #[serde(default = "CommonFetcherConfig::default_enabled_fetchers")]
+    enabled_fetchers: HashSet<DataFetcherKind>,
+}
+
+impl CommonFetcherConfig {
+    fn default_max_feed_staleness() -> u64 {
+        300
+    }
+
+    fn default_enabled_fetchers() -> HashSet<DataFetcherKind> {
+        DataFetcherKind::ALL.iter().copied().collect()
+    }
+}
+
 /// Configuration for the third-party API data fetcher.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct FetcherConfig {
     pub token_list: SingleFetcherConfig<TokenListSource>,
     pub token_price: SingleFetcherConfig<TokenPriceSource>,
     pub token_trading_volume: SingleFetcherConfig<TokenTradingVolumeSource>,
+    /// Maximum time in seconds since the last successful update of any data feed
+    /// for the data fetchers to be considered healthy. By default, 5 minutes.
+    pub max_feed_staleness: u64,
+    /// Data fetchers to be run. By default, all the fetchers are enabled.
+    pub enabled_fetchers: HashSet<DataFetcherKind>,
 }
 
 impl FetcherConfig {
     pub fn from_env() -> Self {
+        let common: CommonFetcherConfig = envy_load("fetcher", "FETCHER_");
         Self {
+            max_feed_staleness: common.max_feed_staleness,
+            enabled_fetchers: common.enabled_fetchers,
             token_list: envy_load("token_list", "FETCHER_TOKEN_LIST_"),
             token_price: envy_load("token_price", "FETCHER_TOKEN_PRICE_"),
             token_trading_volume: envy_load(
@@ -61,6 +100,27 @@ impl FetcherConfig {
             ),
         }
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        let common: CommonFetcherConfig = envy_try_load_from("FETCHER_", vars)?;
+        Ok(Self {
+            max_feed_staleness: common.max_feed_staleness,
+            enabled_fetchers: common.enabled_fetchers,
+            token_list: envy_try_load_from("FETCHER_TOKEN_LIST_", vars)?,
+            token_price: envy_try_load_from("FETCHER_TOKEN_PRICE_", vars)?,
+            token_trading_volume: envy_try_load_from("FETCHER_TOKEN_TRADING_VOLUME_", vars)?,
+        })
+    }
+
+    pub fn max_feed_staleness(&self) -> Duration {
+        Duration::from_secs(self.max_feed_staleness)
+    }
+
+    pub fn is_fetcher_enabled(&self, kind: DataFetcherKind) -> bool {
+        self.enabled_fetchers.contains(&kind)
+    }
 }
 
 #[cfg(test)]
@@ -85,6 +145,8 @@ mod tests {
                 url: "http://127.0.0.1:9975/graphql".to_string(),
                 fetching_interval: 5,
             },
+            max_feed_staleness: 120,
+            enabled_fetchers: HashSet::from([DataFetcherKind::TokenPrice]),
         }
     }
 
@@ -100,10 +162,35 @@ FETCHER_TOKEN_PRICE_FETCHING_INTERVAL="7"
 FETCHER_TOKEN_TRADING_VOLUME_SOURCE="Uniswap"
 FETCHER_TOKEN_TRADING_VOLUME_URL="http://127.0.0.1:9975/graphql"
 FETCHER_TOKEN_TRADING_VOLUME_FETCHING_INTERVAL="5"
+FETCHER_MAX_FEED_STALENESS="120"
+FETCHER_ENABLED_FETCHERS="TokenPrice"
         "#;
         set_env(config);
 
         let actual = FetcherConfig::from_env();
         assert_eq!(actual, expected_config());
     }
+
+    #[test]
+    fn all_fetchers_are_enabled_by_default() {
+        let enabled_fetchers = CommonFetcherConfig::default_enabled_fetchers();
+        for kind in DataFetcherKind::ALL {
+            assert!(enabled_fetchers.contains(&kind), "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn common_config_has_defaults() {
+        let config: CommonFetcherConfig = envy::prefixed("FETCHER_")
+            .from_iter(Vec::<(String, String)>::new())
+            .unwrap();
+        assert_eq!(
+            config.max_feed_staleness,
+            CommonFetcherConfig::default_max_feed_staleness()
+        );
+        assert_eq!(
+            config.enabled_fetchers,
+            CommonFetcherConfig::default_enabled_fetchers()
+        );
+    }
 }
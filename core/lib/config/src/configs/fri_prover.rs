@@ -1,4 +1,4 @@
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 use serde::Deserialize;
 use std::time::Duration;
 
@@ -26,6 +26,12 @@ impl FriProverConfig {
         envy_load("fri_prover", "FRI_PROVER_")
     }
 
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("FRI_PROVER_", vars)
+    }
+
     pub fn proof_generation_timeout(&self) -> Duration {
         Duration::from_secs(self.generation_timeout_in_secs as u64)
     }
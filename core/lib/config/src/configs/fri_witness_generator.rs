@@ -5,7 +5,7 @@ use std::time::Duration;
 use serde::Deserialize;
 
 // Local uses
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 
 /// Configuration for the fri witness generation
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -31,6 +31,12 @@ impl FriWitnessGeneratorConfig {
         envy_load("fri_witness", "FRI_WITNESS_")
     }
 
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("FRI_WITNESS_", vars)
+    }
+
     pub fn witness_generation_timeout(&self) -> Duration {
         Duration::from_secs(self.generation_timeout_in_secs as u64)
     }
@@ -1,6 +1,6 @@
 use serde::Deserialize;
 
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 
 /// Configuration for the house keeper.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -17,12 +17,39 @@ pub struct HouseKeeperConfig {
     pub fri_witness_generator_job_retrying_interval_ms: u64,
     pub prover_db_pool_size: u32,
     pub fri_prover_stats_reporting_interval_ms: u64,
+    /// Whether to periodically prune call traces of old transactions.
+    pub call_traces_pruning_enabled: Option<bool>,
+    /// Number of latest miniblocks to retain call traces for.
+    pub call_traces_retention_miniblocks: Option<u32>,
+    /// Interval between call trace pruning runs (in ms).
+    pub call_traces_pruning_interval_ms: Option<u64>,
+    /// Number of consecutive failed ticks of a periodic job after which the house keeper health check
+    /// fails. If not set, failing jobs don't affect the health check.
+    pub job_max_consecutive_failures: Option<u32>,
 }
 
 impl HouseKeeperConfig {
     pub fn from_env() -> Self {
         envy_load("house_keeper", "HOUSE_KEEPER_")
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("HOUSE_KEEPER_", vars)
+    }
+
+    pub fn call_traces_pruning_enabled(&self) -> bool {
+        self.call_traces_pruning_enabled.unwrap_or(false)
+    }
+
+    pub fn call_traces_retention_miniblocks(&self) -> u32 {
+        self.call_traces_retention_miniblocks.unwrap_or(1_000_000)
+    }
+
+    pub fn call_traces_pruning_interval_ms(&self) -> u64 {
+        self.call_traces_pruning_interval_ms.unwrap_or(60_000)
+    }
 }
 
 #[cfg(test)]
@@ -45,6 +72,10 @@ mod tests {
             fri_witness_generator_job_retrying_interval_ms: 30_000,
             prover_db_pool_size: 2,
             fri_prover_stats_reporting_interval_ms: 30_000,
+            call_traces_pruning_enabled: Some(true),
+            call_traces_retention_miniblocks: Some(100_000),
+            call_traces_pruning_interval_ms: None,
+            job_max_consecutive_failures: Some(5),
         }
     }
 
@@ -63,6 +94,9 @@ HOUSE_KEEPER_FRI_PROVER_JOB_RETRYING_INTERVAL_MS="30000"
 HOUSE_KEEPER_FRI_WITNESS_GENERATOR_JOB_RETRYING_INTERVAL_MS="30000"
 HOUSE_KEEPER_PROVER_DB_POOL_SIZE="2"
 HOUSE_KEEPER_FRI_PROVER_STATS_REPORTING_INTERVAL_MS="30000"
+HOUSE_KEEPER_CALL_TRACES_PRUNING_ENABLED="true"
+HOUSE_KEEPER_CALL_TRACES_RETENTION_MINIBLOCKS="100000"
+HOUSE_KEEPER_JOB_MAX_CONSECUTIVE_FAILURES="5"
         "#;
         set_env(config);
         let actual = HouseKeeperConfig::from_env();
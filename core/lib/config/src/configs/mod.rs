@@ -4,10 +4,11 @@ pub use self::{
     circuit_synthesizer::CircuitSynthesizerConfig, contract_verifier::ContractVerifierConfig,
     contracts::ContractsConfig, database::DBConfig, eth_client::ETHClientConfig,
     eth_sender::ETHSenderConfig, eth_sender::GasAdjusterConfig, eth_watch::ETHWatchConfig,
-    fetcher::FetcherConfig, fri_prover::FriProverConfig,
+    fee_policy::FeePolicyConfig, fetcher::FetcherConfig, fri_prover::FriProverConfig,
     fri_witness_generator::FriWitnessGeneratorConfig, nfs::NfsConfig,
     object_store::ObjectStoreConfig, prover::ProverConfig, prover::ProverConfigs,
-    prover_group::ProverGroupConfig, utils::PrometheusConfig,
+    prover_group::ProverGroupConfig, snapshot_exporter::SnapshotExporterConfig,
+    supervisor::SupervisorConfig, utils::PrometheusConfig, utils::PrometheusExporterMode,
     witness_generator::WitnessGeneratorConfig,
 };
 
@@ -23,6 +24,7 @@ pub mod database;
 pub mod eth_client;
 pub mod eth_sender;
 pub mod eth_watch;
+pub mod fee_policy;
 pub mod fetcher;
 pub mod fri_prover;
 pub mod fri_prover_group;
@@ -32,6 +34,8 @@ pub mod nfs;
 pub mod object_store;
 pub mod prover;
 pub mod prover_group;
+pub mod snapshot_exporter;
+pub mod supervisor;
 pub mod utils;
 pub mod witness_generator;
 
@@ -48,3 +52,12 @@ pub fn envy_load<T: DeserializeOwned>(name: &str, prefix: &str) -> T {
 pub fn envy_try_load<T: DeserializeOwned>(prefix: &str) -> Result<T, envy::Error> {
     envy::prefixed(prefix).from_env()
 }
+
+/// Same as [`envy_try_load()`], but loads the structure from the provided environment variables
+/// rather than from the process environment.
+pub fn envy_try_load_from<T: DeserializeOwned>(
+    prefix: &str,
+    vars: &[(String, String)],
+) -> Result<T, envy::Error> {
+    envy::prefixed(prefix).from_iter(vars.iter().cloned())
+}
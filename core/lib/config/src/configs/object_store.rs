@@ -1,4 +1,4 @@
-use super::envy_load;
+use super::{envy_load, envy_try_load, envy_try_load_from};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Clone, Copy)]
@@ -16,16 +16,59 @@ pub struct ObjectStoreConfig {
     pub file_backed_base_path: String,
     pub gcs_credential_file_path: String,
     pub max_retries: u16,
+    /// Blobs with size greater or equal to this threshold are compressed using zstd before being stored.
+    /// If not set (the default), blobs are never compressed. Reading compressed blobs is supported
+    /// regardless of this setting.
+    pub compression_threshold_bytes: Option<usize>,
+    /// zstd compression level. If not set, [`Self::DEFAULT_COMPRESSION_LEVEL`] is used.
+    pub compression_level: Option<i32>,
 }
 
 impl ObjectStoreConfig {
+    /// Default zstd compression level.
+    pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
     pub fn from_env() -> Self {
         envy_load("object_store", "OBJECT_STORE_")
     }
 
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("OBJECT_STORE_", vars)
+    }
+
+    /// Same as [`Self::from_env()`], but returns an error instead of panicking if the configuration
+    /// is missing or malformed.
+    pub fn try_from_env() -> Result<Self, envy::Error> {
+        envy_try_load("OBJECT_STORE_")
+    }
+
     pub fn public_from_env() -> Self {
         envy_load("public_object_store", "PUBLIC_OBJECT_STORE_")
     }
+
+    /// Loads a backend configuration overriding the default object store for a specific kind of blobs
+    /// (e.g., with the `WITNESS_INPUTS_OBJECT_STORE_` prefix). Returns `None` if the override
+    /// is not configured, i.e., the `MODE` variable for the prefix is not set.
+    pub fn override_from_env(name: &str, prefix: &str) -> Option<Self> {
+        Self::try_override_from_env(prefix)
+            .unwrap_or_else(|err| panic!("Cannot load config <{}>: {}", name, err))
+    }
+
+    /// Same as [`Self::override_from_env()`], but returns an error instead of panicking if the override
+    /// is malformed.
+    pub fn try_override_from_env(prefix: &str) -> Result<Option<Self>, envy::Error> {
+        if std::env::var_os(format!("{prefix}MODE")).is_none() {
+            return Ok(None);
+        }
+        envy_try_load(prefix).map(Some)
+    }
+
+    pub fn compression_level(&self) -> i32 {
+        self.compression_level
+            .unwrap_or(Self::DEFAULT_COMPRESSION_LEVEL)
+    }
 }
 
 #[cfg(test)]
@@ -40,6 +83,8 @@ mod tests {
             file_backed_base_path: "artifacts".to_string(),
             gcs_credential_file_path: "/path/to/credentials.json".to_string(),
             max_retries: 5,
+            compression_threshold_bytes: Some(1_048_576),
+            compression_level: Some(5),
         }
     }
 
@@ -51,6 +96,8 @@ OBJECT_STORE_MODE="FileBacked"
 OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
 OBJECT_STORE_GCS_CREDENTIAL_FILE_PATH="/path/to/credentials.json"
 OBJECT_STORE_MAX_RETRIES="5"
+OBJECT_STORE_COMPRESSION_THRESHOLD_BYTES="1048576"
+OBJECT_STORE_COMPRESSION_LEVEL="5"
         "#;
         set_env(config);
         let actual = ObjectStoreConfig::from_env();
@@ -65,9 +112,44 @@ PUBLIC_OBJECT_STORE_MODE="FileBacked"
 PUBLIC_OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
 PUBLIC_OBJECT_STORE_GCS_CREDENTIAL_FILE_PATH="/path/to/credentials.json"
 PUBLIC_OBJECT_STORE_MAX_RETRIES="5"
+PUBLIC_OBJECT_STORE_COMPRESSION_THRESHOLD_BYTES="1048576"
+PUBLIC_OBJECT_STORE_COMPRESSION_LEVEL="5"
         "#;
         set_env(config);
         let actual = ObjectStoreConfig::public_from_env();
         assert_eq!(actual, expected_config("/public_base_url"));
     }
+
+    #[test]
+    fn override_config_from_env() {
+        let config = r#"
+TEST_OVERRIDE_OBJECT_STORE_BUCKET_BASE_URL="/override_base_url"
+TEST_OVERRIDE_OBJECT_STORE_MODE="FileBacked"
+TEST_OVERRIDE_OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
+TEST_OVERRIDE_OBJECT_STORE_GCS_CREDENTIAL_FILE_PATH="/path/to/credentials.json"
+TEST_OVERRIDE_OBJECT_STORE_MAX_RETRIES="5"
+TEST_OVERRIDE_OBJECT_STORE_COMPRESSION_THRESHOLD_BYTES="1048576"
+TEST_OVERRIDE_OBJECT_STORE_COMPRESSION_LEVEL="5"
+        "#;
+        set_env(config);
+        let actual =
+            ObjectStoreConfig::override_from_env("test_override", "TEST_OVERRIDE_OBJECT_STORE_");
+        assert_eq!(actual, Some(expected_config("/override_base_url")));
+
+        let missing =
+            ObjectStoreConfig::override_from_env("test_missing", "TEST_MISSING_OBJECT_STORE_");
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn malformed_override_config_is_reported() {
+        let config = r#"
+TEST_BROKEN_OBJECT_STORE_BUCKET_BASE_URL="/broken_base_url"
+TEST_BROKEN_OBJECT_STORE_MODE="Unknown"
+        "#;
+        set_env(config);
+        let err =
+            ObjectStoreConfig::try_override_from_env("TEST_BROKEN_OBJECT_STORE_").unwrap_err();
+        assert!(err.to_string().contains("Unknown"), "{err}");
+    }
 }
@@ -5,7 +5,7 @@ use std::time::Duration;
 use serde::Deserialize;
 
 // Local uses
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 
 /// Configuration for the prover application
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -84,6 +84,18 @@ impl ProverConfigs {
             ),
         }
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        Ok(Self {
+            non_gpu: envy_try_load_from("PROVER_NON_GPU_", vars)?,
+            two_gpu_forty_gb_mem: envy_try_load_from("PROVER_TWO_GPU_FORTY_GB_MEM_", vars)?,
+            one_gpu_eighty_gb_mem: envy_try_load_from("PROVER_ONE_GPU_EIGHTY_GB_MEM_", vars)?,
+            two_gpu_eighty_gb_mem: envy_try_load_from("PROVER_TWO_GPU_EIGHTY_GB_MEM_", vars)?,
+            four_gpu_eighty_gb_mem: envy_try_load_from("PROVER_FOUR_GPU_EIGHTY_GB_MEM_", vars)?,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 use serde::Deserialize;
 
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 
 /// Configuration for the grouping of specialized provers.
 /// This config would be used by circuit-synthesizer and provers.
@@ -30,6 +30,12 @@ impl ProverGroupConfig {
         envy_load("prover_group", "PROVER_GROUP_")
     }
 
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("PROVER_GROUP_", vars)
+    }
+
     pub fn get_circuit_ids_for_group_id(&self, group_id: u8) -> Option<Vec<u8>> {
         match group_id {
             0 => Some(self.group_0_circuit_ids.clone()),
@@ -0,0 +1,54 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use super::{envy_load, envy_try_load_from};
+
+/// Configuration for the state snapshot exporter.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct SnapshotExporterConfig {
+    /// How often a snapshot of the latest sealed L1 batch is exported.
+    /// Value in seconds.
+    pub export_interval_sec: u64,
+}
+
+impl SnapshotExporterConfig {
+    pub fn from_env() -> Self {
+        envy_load("snapshot_exporter", "SNAPSHOT_EXPORTER_")
+    }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("SNAPSHOT_EXPORTER_", vars)
+    }
+
+    /// Converts `self.export_interval_sec` into `Duration`.
+    pub fn export_interval(&self) -> Duration {
+        Duration::from_secs(self.export_interval_sec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> SnapshotExporterConfig {
+        SnapshotExporterConfig {
+            export_interval_sec: 3600,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+SNAPSHOT_EXPORTER_EXPORT_INTERVAL_SEC="3600"
+        "#;
+        set_env(config);
+
+        let actual = SnapshotExporterConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+}
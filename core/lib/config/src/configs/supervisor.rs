@@ -0,0 +1,68 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use super::{envy_load, envy_try_load_from};
+
+/// Configuration for the supervisor relaunching non-critical components if they terminate unexpectedly.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct SupervisorConfig {
+    /// Components that are relaunched if their tasks terminate unexpectedly or panic, specified
+    /// using the same tokens as the `--components` server argument (e.g., `eth_watcher,data_fetcher`).
+    /// Only the ETH watcher and data fetchers can be relaunched; other components are ignored. Empty by default.
+    #[serde(default)]
+    pub restartable_components: Vec<String>,
+    /// Max number of relaunches of a component within `restart_window_sec`. If a component needs
+    /// more relaunches, the supervisor gives up and the server shuts down. Defaults to 3.
+    pub max_restarts: Option<u32>,
+    /// Sliding window for `max_restarts`. Value in seconds; defaults to 600.
+    pub restart_window_sec: Option<u64>,
+}
+
+impl SupervisorConfig {
+    pub fn from_env() -> Self {
+        envy_load("supervisor", "SUPERVISOR_")
+    }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("SUPERVISOR_", vars)
+    }
+
+    pub fn max_restarts(&self) -> u32 {
+        self.max_restarts.unwrap_or(3)
+    }
+
+    pub fn restart_window(&self) -> Duration {
+        Duration::from_secs(self.restart_window_sec.unwrap_or(600))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> SupervisorConfig {
+        SupervisorConfig {
+            restartable_components: vec!["eth_watcher".to_owned(), "data_fetcher".to_owned()],
+            max_restarts: Some(5),
+            restart_window_sec: Some(300),
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+SUPERVISOR_RESTARTABLE_COMPONENTS="eth_watcher,data_fetcher"
+SUPERVISOR_MAX_RESTARTS="5"
+SUPERVISOR_RESTART_WINDOW_SEC="300"
+        "#;
+        set_env(config);
+
+        let actual = SupervisorConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+}
@@ -1,7 +1,19 @@
-use crate::configs::envy_load;
+use crate::configs::{envy_load, envy_try_load_from};
 use serde::Deserialize;
 use std::time::Duration;
 
+/// Way the Prometheus exporter makes metrics available.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrometheusExporterMode {
+    /// Metrics are served via an HTTP listener and scraped by Prometheus.
+    #[default]
+    Pull,
+    /// Metrics are periodically pushed to the Pushgateway.
+    Push,
+    /// Metrics are both served via an HTTP listener and pushed to the Pushgateway.
+    Both,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct PrometheusConfig {
     /// Port to which the Prometheus exporter server is listening.
@@ -10,13 +22,29 @@ pub struct PrometheusConfig {
     pub pushgateway_url: String,
     /// Push interval in ms.
     pub push_interval_ms: Option<u64>,
+    /// Whether the metrics are pulled, pushed or both. Defaults to pull.
+    #[serde(default)]
+    pub exporter_mode: PrometheusExporterMode,
+    /// Whether to export metrics of the Tokio runtime (worker count, busy duration, etc.). Runtime metrics
+    /// are only collected if the binary is built with `RUSTFLAGS="--cfg tokio_unstable"`.
+    pub collect_runtime_metrics: Option<bool>,
 }
 
 impl PrometheusConfig {
     pub fn from_env() -> Self {
         envy_load("prometheus", "API_PROMETHEUS_")
     }
+
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("API_PROMETHEUS_", vars)
+    }
     pub fn push_interval(&self) -> Duration {
         Duration::from_millis(self.push_interval_ms.unwrap_or(100))
     }
+
+    pub fn collect_runtime_metrics(&self) -> bool {
+        self.collect_runtime_metrics.unwrap_or(false)
+    }
 }
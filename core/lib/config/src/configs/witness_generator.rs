@@ -5,7 +5,7 @@ use std::time::Duration;
 use serde::Deserialize;
 
 // Local uses
-use super::envy_load;
+use super::{envy_load, envy_try_load_from};
 
 /// Configuration for the witness generation
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -26,6 +26,12 @@ pub struct WitnessGeneratorConfig {
     // This parameter is used in case of performing circuit upgrades(VK/Setup keys),
     // to not let witness-generator pick new job and finish all the existing jobs with old circuit.
     pub last_l1_batch_to_process: Option<u32>,
+    // Number of concurrent workers for each aggregation round. All workers for a round
+    // pull jobs from the same queue. If not set, a single worker is spawned for the round.
+    pub basic_circuits_concurrency: Option<usize>,
+    pub leaf_aggregation_concurrency: Option<usize>,
+    pub node_aggregation_concurrency: Option<usize>,
+    pub scheduler_concurrency: Option<usize>,
 }
 
 impl WitnessGeneratorConfig {
@@ -33,6 +39,12 @@ impl WitnessGeneratorConfig {
         envy_load("witness", "WITNESS_")
     }
 
+    /// Same as [`Self::from_env()`], but loads the config from the provided environment variables
+    /// and returns an error instead of panicking.
+    pub fn try_from_vars(vars: &[(String, String)]) -> Result<Self, envy::Error> {
+        envy_try_load_from("WITNESS_", vars)
+    }
+
     pub fn witness_generation_timeout(&self) -> Duration {
         Duration::from_secs(self.generation_timeout_in_secs as u64)
     }
@@ -57,6 +69,10 @@ mod tests {
             blocks_proving_percentage: Some(30),
             dump_arguments_for_blocks: vec![2, 3],
             last_l1_batch_to_process: None,
+            basic_circuits_concurrency: Some(4),
+            leaf_aggregation_concurrency: Some(2),
+            node_aggregation_concurrency: None,
+            scheduler_concurrency: None,
         }
     }
 
@@ -69,6 +85,8 @@ mod tests {
         WITNESS_MAX_ATTEMPTS=4
         WITNESS_DUMP_ARGUMENTS_FOR_BLOCKS="2,3"
         WITNESS_BLOCKS_PROVING_PERCENTAGE="30"
+        WITNESS_BASIC_CIRCUITS_CONCURRENCY=4
+        WITNESS_LEAF_AGGREGATION_CONCURRENCY=2
         "#;
         set_env(config);
         let actual = WitnessGeneratorConfig::from_env();
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS snapshots (
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    storage_logs_count BIGINT NOT NULL,
+    storage_logs_url TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
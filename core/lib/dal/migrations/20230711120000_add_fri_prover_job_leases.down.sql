@@ -0,0 +1,2 @@
+ALTER TABLE prover_jobs_fri DROP COLUMN IF EXISTS picked_by;
+ALTER TABLE prover_jobs_fri DROP COLUMN IF EXISTS lease_expires_at;
@@ -0,0 +1,2 @@
+ALTER TABLE prover_jobs_fri ADD COLUMN IF NOT EXISTS picked_by TEXT;
+ALTER TABLE prover_jobs_fri ADD COLUMN IF NOT EXISTS lease_expires_at TIMESTAMP;
@@ -0,0 +1 @@
+ALTER TABLE eth_txs_history ADD COLUMN IF NOT EXISTS confirmed_at_block INT;
@@ -0,0 +1 @@
+ALTER TABLE eth_txs ADD COLUMN IF NOT EXISTS blob_data BYTEA;
@@ -7,6 +7,7 @@ use std::{
 use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
 use sqlx::Row;
 
+use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
@@ -34,6 +35,27 @@ impl BlocksDal<'_, '_> {
         count == 0
     }
 
+    /// Acquires a transaction-scoped advisory lock serializing genesis among concurrent processes.
+    /// The lock is released once the current transaction is committed or rolled back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a transaction.
+    pub async fn lock_genesis(&mut self) {
+        /// Arbitrary advisory lock ID ("genesis" in ASCII).
+        const GENESIS_LOCK_ID: i64 = 0x0067_656e_6573_6973;
+
+        assert!(
+            self.storage.in_transaction(),
+            "Genesis lock must be acquired in a transaction"
+        );
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(GENESIS_LOCK_ID)
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+    }
+
     pub async fn get_sealed_block_number(&mut self) -> L1BatchNumber {
         let started_at = Instant::now();
         let number = sqlx::query!(
@@ -45,7 +67,7 @@ impl BlocksDal<'_, '_> {
         .number
         .expect("DAL invocation before genesis");
 
-        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_sealed_block_number");
+        StorageProcessor::report_request("get_sealed_block_number", started_at);
         L1BatchNumber(number as u32)
     }
 
@@ -58,7 +80,7 @@ impl BlocksDal<'_, '_> {
             .number
             .unwrap_or(0);
 
-        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_sealed_miniblock_number");
+        StorageProcessor::report_request("get_sealed_miniblock_number", started_at);
         MiniblockNumber(number as u32)
     }
 
@@ -72,7 +94,7 @@ impl BlocksDal<'_, '_> {
                 .number
                 .expect("DAL invocation before genesis");
 
-        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_last_block_number_with_metadata");
+        StorageProcessor::report_request("get_last_block_number_with_metadata", started_at);
         L1BatchNumber(number as u32)
     }
 
@@ -254,6 +276,75 @@ impl BlocksDal<'_, '_> {
         .unwrap();
     }
 
+    /// Inserts multiple miniblock headers with a single statement, so either all or none of
+    /// the headers are inserted. Column values are the same as in [`Self::insert_miniblock()`].
+    pub async fn insert_miniblocks(&mut self, miniblock_headers: &[MiniblockHeader]) {
+        if miniblock_headers.is_empty() {
+            return;
+        }
+
+        let mut numbers = Vec::with_capacity(miniblock_headers.len());
+        let mut timestamps = Vec::with_capacity(miniblock_headers.len());
+        let mut hashes = Vec::with_capacity(miniblock_headers.len());
+        let mut l1_tx_counts = Vec::with_capacity(miniblock_headers.len());
+        let mut l2_tx_counts = Vec::with_capacity(miniblock_headers.len());
+        let mut base_fees_per_gas = Vec::with_capacity(miniblock_headers.len());
+        let mut l1_gas_prices = Vec::with_capacity(miniblock_headers.len());
+        let mut l2_fair_gas_prices = Vec::with_capacity(miniblock_headers.len());
+        let mut bootloader_code_hashes = Vec::with_capacity(miniblock_headers.len());
+        let mut default_aa_code_hashes = Vec::with_capacity(miniblock_headers.len());
+        for header in miniblock_headers {
+            numbers.push(header.number.0 as i64);
+            timestamps.push(header.timestamp as i64);
+            hashes.push(header.hash.as_bytes());
+            l1_tx_counts.push(header.l1_tx_count as i32);
+            l2_tx_counts.push(header.l2_tx_count as i32);
+            base_fees_per_gas.push(
+                BigDecimal::from_u64(header.base_fee_per_gas)
+                    .expect("base_fee_per_gas should fit in u64"),
+            );
+            l1_gas_prices.push(header.l1_gas_price as i64);
+            l2_fair_gas_prices.push(header.l2_fair_gas_price as i64);
+            bootloader_code_hashes.push(header.base_system_contracts_hashes.bootloader.as_bytes());
+            default_aa_code_hashes.push(header.base_system_contracts_hashes.default_aa.as_bytes());
+        }
+
+        sqlx::query!(
+            "INSERT INTO miniblocks (\
+                number, timestamp, hash, l1_tx_count, l2_tx_count, \
+                base_fee_per_gas, l1_gas_price, l2_fair_gas_price, gas_per_pubdata_limit, \
+                bootloader_code_hash, default_aa_code_hash, \
+                created_at, updated_at\
+            ) \
+            SELECT u.number, u.timestamp, u.hash, u.l1_tx_count, u.l2_tx_count, \
+                u.base_fee_per_gas, u.l1_gas_price, u.l2_fair_gas_price, $11, \
+                u.bootloader_code_hash, u.default_aa_code_hash, \
+                now(), now() \
+            FROM UNNEST(\
+                $1::bigint[], $2::bigint[], $3::bytea[], $4::int[], $5::int[], \
+                $6::numeric[], $7::bigint[], $8::bigint[], $9::bytea[], $10::bytea[]\
+            ) AS u(\
+                number, timestamp, hash, l1_tx_count, l2_tx_count, \
+                base_fee_per_gas, l1_gas_price, l2_fair_gas_price, \
+                bootloader_code_hash, default_aa_code_hash\
+            )",
+            &numbers,
+            &timestamps,
+            &hashes as &[&[u8]],
+            &l1_tx_counts,
+            &l2_tx_counts,
+            &base_fees_per_gas,
+            &l1_gas_prices,
+            &l2_fair_gas_prices,
+            &bootloader_code_hashes as &[&[u8]],
+            &default_aa_code_hashes as &[&[u8]],
+            MAX_GAS_PER_PUBDATA_BYTE as i64,
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
     pub async fn get_last_sealed_miniblock_header(&mut self) -> Option<MiniblockHeader> {
         sqlx::query_as!(
             StorageMiniblockHeader,
@@ -412,7 +503,7 @@ impl BlocksDal<'_, '_> {
                 block_metadata.l2_l1_merkle_root
             );
         }
-        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "save_blocks_metadata");
+        StorageProcessor::report_request("save_blocks_metadata", started_at);
     }
 
     pub async fn get_last_committed_to_eth_block(&mut self) -> Option<BlockWithMetadata> {
@@ -691,6 +782,25 @@ impl BlocksDal<'_, '_> {
         .map(|hash| H256::from_slice(&hash))
     }
 
+    /// Returns calldata of the confirmed L1 transaction committing the specified L1 batch, or `None`
+    /// if the batch is not committed yet. For commit transactions not sent by this node
+    /// (e.g., ones tracked by the external node), the calldata is a placeholder.
+    pub async fn get_confirmed_commit_tx_calldata(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> Option<Vec<u8>> {
+        sqlx::query!(
+            "SELECT eth_txs.raw_tx FROM l1_batches \
+            JOIN eth_txs ON (l1_batches.eth_commit_tx_id = eth_txs.id) \
+            WHERE l1_batches.number = $1 AND eth_txs.confirmed_eth_tx_history_id IS NOT NULL",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()
+        .map(|row| row.raw_tx)
+    }
+
     pub async fn get_block_state_root_and_timestamp(
         &mut self,
         number: L1BatchNumber,
@@ -718,6 +828,46 @@ impl BlocksDal<'_, '_> {
         last_block.into()
     }
 
+    /// Returns the number of the latest sealed L1 batch together with the hashes
+    /// of the base system contracts (bootloader and default account) it was executed with.
+    pub async fn get_newest_block_base_system_contracts_hashes(
+        &mut self,
+    ) -> Option<(L1BatchNumber, BaseSystemContractsHashes)> {
+        let row = sqlx::query!(
+            "SELECT number, bootloader_code_hash, default_aa_code_hash FROM l1_batches \
+            ORDER BY number DESC LIMIT 1"
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?;
+
+        let hashes = BaseSystemContractsHashes {
+            bootloader: H256::from_slice(&row.bootloader_code_hash?),
+            default_aa: H256::from_slice(&row.default_aa_code_hash?),
+        };
+        Some((L1BatchNumber(row.number as u32), hashes))
+    }
+
+    /// Returns the number of distinct sets of base system contracts used by L1 batches up to and including
+    /// `last_l1_batch`. Each protocol upgrade changing the bootloader or the default account increases this number.
+    pub async fn get_base_system_contracts_version_count(
+        &mut self,
+        last_l1_batch: L1BatchNumber,
+    ) -> u32 {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) as \"count!\" FROM (\
+                SELECT DISTINCT bootloader_code_hash, default_aa_code_hash \
+                FROM l1_batches WHERE number <= $1\
+            ) AS versions",
+            last_l1_batch.0 as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .unwrap()
+        .count;
+        count as u32
+    }
+
     pub async fn get_block_metadata(&mut self, number: L1BatchNumber) -> Option<BlockWithMetadata> {
         let l1_batch: Option<StorageBlock> = sqlx::query_as!(
             StorageBlock,
@@ -783,12 +933,157 @@ impl BlocksDal<'_, '_> {
             .unwrap();
     }
 
+    /// Returns the number of L1 batches that would be deleted by [`Self::delete_l1_batches()`].
+    pub async fn get_l1_batch_count_after(&mut self, l1_batch_number: L1BatchNumber) -> usize {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) as \"count!\" FROM l1_batches WHERE number > $1",
+            l1_batch_number.0 as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .unwrap()
+        .count;
+        count as usize
+    }
+
+    /// Returns the number of miniblocks that would be deleted by [`Self::delete_miniblocks()`].
+    pub async fn get_miniblock_count_after(&mut self, miniblock_number: MiniblockNumber) -> usize {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) as \"count!\" FROM miniblocks WHERE number > $1",
+            miniblock_number.0 as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .unwrap()
+        .count;
+        count as usize
+    }
+
     /// Deletes all miniblocks from the storage so that the specified miniblock number is the last one left.
     pub async fn delete_miniblocks(&mut self, last_miniblock_to_keep: MiniblockNumber) {
         self.delete_miniblocks_inner(Some(last_miniblock_to_keep))
             .await
     }
 
+    /// Deletes miniblocks in the inclusive range `[from, to]` together with events, L2-to-L1 logs,
+    /// storage logs and factory deps produced in them. Storage values modified in the range are reverted
+    /// to the latest values among the remaining storage logs, and initial writes of keys first written
+    /// in the range are removed. Transactions included into the deleted miniblocks
+    /// are returned to the pending state, and their call traces are removed. All changes are applied
+    /// in a single DB transaction, so they are rolled back if any of the queries fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from > to`.
+    pub async fn delete_miniblocks_in_range(&mut self, from: MiniblockNumber, to: MiniblockNumber) {
+        assert!(
+            from <= to,
+            "Invalid miniblock range to delete: {} > {}",
+            from.0,
+            to.0
+        );
+        let (from, to) = (from.0 as i64, to.0 as i64);
+        let mut transaction = self.storage.start_transaction().await;
+
+        let tx_hashes = sqlx::query!(
+            "UPDATE transactions \
+            SET l1_batch_number = NULL, miniblock_number = NULL, error = NULL, index_in_block = NULL, \
+                execution_info = '{}' \
+            WHERE miniblock_number BETWEEN $1 AND $2 \
+            RETURNING hash",
+            from,
+            to
+        )
+        .fetch_all(transaction.conn())
+        .await
+        .unwrap();
+        let tx_hashes: Vec<_> = tx_hashes.into_iter().map(|row| row.hash).collect();
+        sqlx::query!(
+            "DELETE FROM call_traces WHERE tx_hash = ANY($1)",
+            &tx_hashes
+        )
+        .execute(transaction.conn())
+        .await
+        .unwrap();
+
+        sqlx::query!(
+            "DELETE FROM events WHERE miniblock_number BETWEEN $1 AND $2",
+            from,
+            to
+        )
+        .execute(transaction.conn())
+        .await
+        .unwrap();
+        sqlx::query!(
+            "DELETE FROM l2_to_l1_logs WHERE miniblock_number BETWEEN $1 AND $2",
+            from,
+            to
+        )
+        .execute(transaction.conn())
+        .await
+        .unwrap();
+        let modified_keys = sqlx::query!(
+            "DELETE FROM storage_logs WHERE miniblock_number BETWEEN $1 AND $2 RETURNING hashed_key",
+            from,
+            to
+        )
+        .fetch_all(transaction.conn())
+        .await
+        .unwrap();
+        let modified_keys: Vec<_> = modified_keys
+            .into_iter()
+            .map(|row| row.hashed_key)
+            .collect();
+        sqlx::query!(
+            "DELETE FROM storage WHERE hashed_key = ANY($1) \
+            AND NOT EXISTS (SELECT 1 FROM storage_logs WHERE storage_logs.hashed_key = storage.hashed_key)",
+            &modified_keys
+        )
+        .execute(transaction.conn())
+        .await
+        .unwrap();
+        // Keys without remaining storage logs were initially written in the deleted miniblocks.
+        sqlx::query!(
+            "DELETE FROM initial_writes WHERE hashed_key = ANY($1) \
+            AND NOT EXISTS (SELECT 1 FROM storage_logs WHERE storage_logs.hashed_key = initial_writes.hashed_key)",
+            &modified_keys
+        )
+        .execute(transaction.conn())
+        .await
+        .unwrap();
+        sqlx::query!(
+            "UPDATE storage SET value = latest.value \
+            FROM (\
+                SELECT DISTINCT ON (hashed_key) hashed_key, value FROM storage_logs \
+                WHERE hashed_key = ANY($1) \
+                ORDER BY hashed_key, miniblock_number DESC, operation_number DESC\
+            ) AS latest \
+            WHERE storage.hashed_key = latest.hashed_key",
+            &modified_keys
+        )
+        .execute(transaction.conn())
+        .await
+        .unwrap();
+        sqlx::query!(
+            "DELETE FROM factory_deps WHERE miniblock_number BETWEEN $1 AND $2",
+            from,
+            to
+        )
+        .execute(transaction.conn())
+        .await
+        .unwrap();
+        sqlx::query!(
+            "DELETE FROM miniblocks WHERE number BETWEEN $1 AND $2",
+            from,
+            to
+        )
+        .execute(transaction.conn())
+        .await
+        .unwrap();
+
+        transaction.commit().await;
+    }
+
     async fn delete_miniblocks_inner(&mut self, last_miniblock_to_keep: Option<MiniblockNumber>) {
         let block_number = last_miniblock_to_keep.map_or(-1, |number| number.0 as i64);
         sqlx::query!("DELETE FROM miniblocks WHERE number > $1", block_number)
@@ -1004,11 +1299,15 @@ impl BlocksDal<'_, '_> {
 #[cfg(test)]
 mod tests {
     use db_test_macro::db_test;
-    use zksync_contracts::BaseSystemContractsHashes;
-    use zksync_types::Address;
+    use zksync_types::{AccountTreeId, Address, StorageKey, StorageLog};
+
+    use std::time::Duration;
 
     use super::*;
-    use crate::ConnectionPool;
+    use crate::{
+        tests::{create_miniblock_header, write_log_to_query},
+        ConnectionPool,
+    };
 
     #[db_test(dal_crate)]
     async fn getting_predicted_gas(pool: ConnectionPool) {
@@ -1062,4 +1361,174 @@ mod tests {
             assert_eq!(gas, 3 * expected_gas);
         }
     }
+
+    #[db_test(dal_crate)]
+    async fn getting_miniblock_header(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        let header = MiniblockHeader {
+            l1_tx_count: 3,
+            l2_tx_count: 5,
+            base_fee_per_gas: 250,
+            l1_gas_price: 1_000,
+            l2_fair_gas_price: 500,
+            ..create_miniblock_header(1)
+        };
+        conn.blocks_dal().insert_miniblock(&header).await;
+
+        let loaded_header = conn
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(1))
+            .await
+            .expect("no miniblock header");
+        assert_eq!(loaded_header, header);
+
+        let missing_header = conn
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(2))
+            .await;
+        assert!(missing_header.is_none());
+    }
+
+    #[db_test(dal_crate)]
+    async fn deleting_miniblocks_in_range(pool: ConnectionPool) {
+        let mut conn = pool.access_test_storage().await;
+        conn.blocks_dal().delete_miniblocks_inner(None).await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+        let headers: Vec<_> = (0..=5).map(create_miniblock_header).collect();
+        conn.blocks_dal().insert_miniblocks(&headers).await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            0,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        conn.blocks_dal()
+            .insert_l1_batch(&header, BlockGasCount::default())
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let key = StorageKey::new(account, H256::zero());
+        let new_key = StorageKey::new(account, H256::repeat_byte(1));
+        let logs_by_miniblock = [
+            (
+                1,
+                vec![StorageLog::new_write_log(key, H256::repeat_byte(1))],
+            ),
+            (
+                3,
+                vec![
+                    StorageLog::new_write_log(key, H256::repeat_byte(3)),
+                    StorageLog::new_write_log(new_key, H256::repeat_byte(3)),
+                ],
+            ),
+        ];
+        for (number, logs) in logs_by_miniblock {
+            let logs = [(H256::zero(), logs)];
+            conn.storage_logs_dal()
+                .insert_storage_logs(MiniblockNumber(number), &logs)
+                .await;
+            conn.storage_dal().apply_storage_logs(&logs).await;
+        }
+        let initial_writes = [
+            StorageLog::new_write_log(key, H256::repeat_byte(1)),
+            StorageLog::new_write_log(new_key, H256::repeat_byte(3)),
+        ];
+        let initial_writes: Vec<_> = initial_writes.iter().map(write_log_to_query).collect();
+        conn.storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(1), &initial_writes)
+            .await;
+
+        conn.blocks_dal()
+            .delete_miniblocks_in_range(MiniblockNumber(2), MiniblockNumber(3))
+            .await;
+
+        let mut remaining_numbers = vec![];
+        for number in 0..=5 {
+            let number = MiniblockNumber(number);
+            let header = conn.blocks_dal().get_miniblock_header(number).await;
+            if header.is_some() {
+                remaining_numbers.push(number.0);
+            }
+        }
+        assert_eq!(remaining_numbers, [0, 1, 4, 5]);
+
+        // Storage changes made in the deleted miniblocks must be reverted.
+        let value = conn.storage_dal().get_by_key(&key).await;
+        assert_eq!(value, Some(H256::repeat_byte(1)));
+        assert_eq!(conn.storage_dal().get_by_key(&new_key).await, None);
+        // ...as well as initial writes.
+        let initial_write_batch = conn
+            .storage_web3_dal()
+            .get_l1_batch_number_for_initial_write(&key)
+            .await
+            .unwrap();
+        assert_eq!(initial_write_batch, Some(L1BatchNumber(1)));
+        let initial_write_batch = conn
+            .storage_web3_dal()
+            .get_l1_batch_number_for_initial_write(&new_key)
+            .await
+            .unwrap();
+        assert_eq!(initial_write_batch, None);
+    }
+
+    #[db_test(dal_crate)]
+    async fn inserting_miniblocks_in_bulk(pool: ConnectionPool) {
+        let mut conn = pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+
+        let headers: Vec<_> = (1..=100).map(create_miniblock_header).collect();
+        conn.blocks_dal().insert_miniblocks(&headers).await;
+
+        let (hashes, last_miniblock_number, _) = conn
+            .blocks_web3_dal()
+            .get_block_hashes_after(MiniblockNumber(0), 1_000)
+            .await
+            .unwrap();
+        let expected_hashes: Vec<_> = headers.iter().map(|header| header.hash).collect();
+        assert_eq!(hashes, expected_hashes);
+        assert_eq!(last_miniblock_number, Some(MiniblockNumber(100)));
+        let last_header = conn
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await
+            .unwrap();
+        assert_eq!(last_header.base_fee_per_gas, headers[99].base_fee_per_gas);
+        assert_eq!(last_header.l1_gas_price, headers[99].l1_gas_price);
+    }
+
+    /// Uses a real connection pool since test pools have a single connection.
+    #[tokio::test]
+    async fn genesis_lock_serializes_transactions() {
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .max_connections(2)
+            .connect(&crate::get_test_database_url())
+            .await
+            .unwrap();
+        let pool = ConnectionPool::Real(pool);
+        let mut first_conn = pool.access_storage().await;
+        let mut first_transaction = first_conn.start_transaction().await;
+        first_transaction.blocks_dal().lock_genesis().await;
+
+        let mut second_conn = pool.access_storage().await;
+        let mut second_transaction = second_conn.start_transaction().await;
+        let release_first_lock = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let released_at = Instant::now();
+            first_transaction.commit().await;
+            released_at
+        };
+        let acquire_second_lock = async {
+            second_transaction.blocks_dal().lock_genesis().await;
+            Instant::now()
+        };
+        let (released_at, acquired_at) = tokio::join!(release_first_lock, acquire_second_lock);
+        // The second transaction must wait until the first one releases the lock.
+        assert!(acquired_at > released_at);
+        second_transaction.commit().await;
+    }
 }
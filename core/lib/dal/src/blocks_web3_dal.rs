@@ -1,9 +1,13 @@
-use bigdecimal::BigDecimal;
-use sqlx::Row;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use sqlx::{postgres::PgRow, Row};
 
-use std::time::Instant;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
-use zksync_config::constants::EMPTY_UNCLES_HASH;
+use zksync_config::{configs::chain::MempoolOrderingStrategy, constants::EMPTY_UNCLES_HASH};
 use zksync_types::{
     api,
     l2_to_l1_log::L2ToL1Log,
@@ -22,45 +26,394 @@ use crate::{SqlxError, StorageProcessor};
 
 const BLOCK_GAS_LIMIT: u32 = system_params::VM_INITIAL_FRAME_ERGS;
 
+/// Errors returned by [`BlocksWeb3Dal`] methods.
+#[derive(Debug, thiserror::Error)]
+pub enum BlocksWeb3DalError {
+    /// Requested entity is not present in the storage.
+    #[error("Requested entity is not found")]
+    NotFound,
+    /// Storage doesn't contain the genesis block yet.
+    #[error("DAL invocation before genesis")]
+    BeforeGenesis,
+    /// Method is called with an invalid range of blocks.
+    #[error("Invalid range: {0}")]
+    InvalidRange(String),
+    /// Read replica lags behind the master database more than allowed.
+    #[error("Replica is lagging: its last miniblock is #{replica}, while master has #{master}")]
+    ReplicaLagging {
+        replica: MiniblockNumber,
+        master: MiniblockNumber,
+    },
+    /// Block number read from the storage doesn't fit into `u32`, which indicates data corruption.
+    #[error("Block number {0} read from the database is out of range")]
+    BlockNumberOverflow(i64),
+    #[error("Database error: {0}")]
+    Db(#[from] SqlxError),
+}
+
+impl BlocksWeb3DalError {
+    /// Converts this error into a raw [`SqlxError`]. Used by DAL methods outside [`BlocksWeb3Dal`]
+    /// that resolve block IDs, so that the block-specific error type doesn't leak into their signatures.
+    pub(crate) fn into_sqlx_error(self) -> SqlxError {
+        match self {
+            Self::Db(err) => err,
+            Self::NotFound => SqlxError::RowNotFound,
+            err => SqlxError::Protocol(err.to_string()),
+        }
+    }
+}
+
+/// Tolerance of a read replica lagging behind the master database, used in
+/// [`BlocksWeb3Dal::resolve_block_id_on_replica()`].
+#[derive(Debug, Clone, Copy)]
+pub struct ReplicaLagTolerance {
+    /// Max number of miniblocks the replica is allowed to lag behind the master.
+    pub max_lag: u32,
+    /// Max time to wait for a lagging replica to catch up. If zero, a lagging replica
+    /// is reported immediately.
+    pub max_wait: Duration,
+}
+
+/// Result of resolving the `pending` block tag returned by [`BlocksWeb3Dal::resolve_pending_block()`].
+/// Both numbers are read in a single query, so they are consistent with each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedPendingBlock {
+    /// Number of the pending miniblock, i.e., the miniblock to be sealed next.
+    pub pending: MiniblockNumber,
+    /// Number of the latest sealed miniblock at the time of resolution. If the latest sealed miniblock
+    /// at the time of a subsequent read differs from this value, the pending miniblock has been sealed
+    /// in the meantime, and the read may observe its data.
+    pub latest: MiniblockNumber,
+}
+
+/// Numbers of L1 blocks in which transactions for the stages of an L1 batch were mined, returned by
+/// [`BlocksWeb3Dal::get_l1_batch_stage_l1_blocks()`]. A stage is `None` if its transaction
+/// is not mined yet, or if the L1 block number was not recorded when it was mined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchStageL1Blocks {
+    pub commit: Option<u32>,
+    pub prove: Option<u32>,
+    pub execute: Option<u32>,
+}
+
+/// Converts a miniblock or L1 batch number read from the storage. Unlike an `as` cast, doesn't silently
+/// truncate out-of-range values.
+pub(crate) fn block_number_from_db<T: From<u32>>(number: i64) -> Result<T, BlocksWeb3DalError> {
+    u32::try_from(number)
+        .map(T::from)
+        .map_err(|_| BlocksWeb3DalError::BlockNumberOverflow(number))
+}
+
+type BlockWithBatchMetadata = (
+    api::Block<api::TransactionVariant>,
+    Option<api::L1BatchMetadata>,
+);
+
 #[derive(Debug)]
 pub struct BlocksWeb3Dal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
 }
 
 impl BlocksWeb3Dal<'_, '_> {
-    pub async fn get_sealed_miniblock_number(&mut self) -> Result<MiniblockNumber, SqlxError> {
+    pub async fn get_sealed_miniblock_number(
+        &mut self,
+    ) -> Result<MiniblockNumber, BlocksWeb3DalError> {
         let started_at = Instant::now();
         let number: i64 = sqlx::query!("SELECT MAX(number) as \"number\" FROM miniblocks")
             .fetch_one(self.storage.conn())
             .await?
             .number
-            .expect("DAL invocation before genesis");
-        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_sealed_block_number");
-        Ok(MiniblockNumber(number as u32))
+            .ok_or(BlocksWeb3DalError::BeforeGenesis)?;
+        StorageProcessor::report_request("get_sealed_block_number", started_at);
+        block_number_from_db(number)
+    }
+
+    /// Returns the number, hash and timestamp of the latest sealed miniblock in a single query,
+    /// or `None` if there are no miniblocks (i.e., before genesis).
+    pub async fn get_latest_sealed_miniblock(
+        &mut self,
+    ) -> Result<Option<(MiniblockNumber, H256, u64)>, BlocksWeb3DalError> {
+        let started_at = Instant::now();
+        let row = sqlx::query!(
+            "SELECT number, hash, timestamp FROM miniblocks ORDER BY number DESC LIMIT 1"
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        StorageProcessor::report_request("get_latest_sealed_miniblock", started_at);
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let number = block_number_from_db(row.number)?;
+        Ok(Some((
+            number,
+            H256::from_slice(&row.hash),
+            row.timestamp as u64,
+        )))
     }
 
-    pub async fn get_sealed_l1_batch_number(&mut self) -> Result<L1BatchNumber, SqlxError> {
+    pub async fn get_sealed_l1_batch_number(
+        &mut self,
+    ) -> Result<L1BatchNumber, BlocksWeb3DalError> {
         let started_at = Instant::now();
         let number: i64 = sqlx::query!("SELECT MAX(number) as \"number\" FROM l1_batches")
             .fetch_one(self.storage.conn())
             .await?
             .number
-            .expect("DAL invocation before genesis");
-        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_sealed_block_number");
-        Ok(L1BatchNumber(number as u32))
+            .ok_or(BlocksWeb3DalError::BeforeGenesis)?;
+        StorageProcessor::report_request("get_sealed_block_number", started_at);
+        block_number_from_db(number)
+    }
+
+    /// Returns the number of the earliest miniblock stored in the database. This number may be
+    /// non-zero if old miniblocks were pruned.
+    pub async fn get_earliest_available_miniblock(
+        &mut self,
+    ) -> Result<MiniblockNumber, BlocksWeb3DalError> {
+        let number: i64 = sqlx::query!("SELECT MIN(number) as \"number\" FROM miniblocks")
+            .fetch_one(self.storage.conn())
+            .await?
+            .number
+            .ok_or(BlocksWeb3DalError::BeforeGenesis)?;
+        block_number_from_db(number)
+    }
+
+    /// Returns the number of the last miniblock included into an L1 batch executed on L1,
+    /// or `None` if no L1 batches are executed yet.
+    pub async fn get_last_executed_miniblock(
+        &mut self,
+    ) -> Result<Option<MiniblockNumber>, BlocksWeb3DalError> {
+        let number = sqlx::query!(
+            "SELECT MAX(miniblocks.number) AS \"number\" FROM miniblocks \
+            JOIN l1_batches ON l1_batches.number = miniblocks.l1_batch_number \
+            JOIN eth_txs ON l1_batches.eth_execute_tx_id = eth_txs.id \
+            WHERE eth_txs.confirmed_eth_tx_history_id IS NOT NULL"
+        )
+        .fetch_one(self.storage.conn())
+        .await?
+        .number;
+        number.map(block_number_from_db).transpose()
     }
 
+    /// Returns the block with the specified ID. If `min_base_fee_per_gas` is specified, the returned
+    /// base fee is raised to it if necessary; the stored base fee is not affected.
     pub async fn get_block_by_web3_block_id(
         &mut self,
         block_id: api::BlockId,
         include_full_transactions: bool,
         chain_id: L2ChainId,
-    ) -> Result<Option<api::Block<api::TransactionVariant>>, SqlxError> {
+        min_base_fee_per_gas: Option<U256>,
+    ) -> Result<Option<api::Block<api::TransactionVariant>>, BlocksWeb3DalError> {
+        let block = self
+            .get_block_inner(block_id, include_full_transactions, false, chain_id)
+            .await?;
+        Ok(block.map(|(mut block, _)| {
+            if let Some(min_base_fee_per_gas) = min_base_fee_per_gas {
+                apply_base_fee_floor(&mut block, min_base_fee_per_gas);
+            }
+            block
+        }))
+    }
+
+    /// Returns the block (with transaction hashes) together with the metadata of the L1 batch
+    /// including it, such as hashes of the L1 transactions for the batch. Both are loaded
+    /// in a single query. The batch metadata is `None` if the block isn't included into
+    /// a sealed L1 batch yet.
+    pub async fn get_block_with_batch_metadata(
+        &mut self,
+        block_id: api::BlockId,
+    ) -> Result<Option<BlockWithBatchMetadata>, BlocksWeb3DalError> {
+        // The chain ID is only used to serialize full transactions, which are not loaded here.
+        self.get_block_inner(block_id, false, true, L2ChainId::default())
+            .await
+    }
+
+    /// Returns blocks with the specified hashes loaded in a single query. Blocks are returned
+    /// in the order of `hashes`; unknown hashes are omitted.
+    pub async fn get_blocks_by_hashes(
+        &mut self,
+        hashes: &[H256],
+        include_full_transactions: bool,
+        chain_id: L2ChainId,
+    ) -> Result<Vec<api::Block<api::TransactionVariant>>, BlocksWeb3DalError> {
+        let transactions_sql = if include_full_transactions {
+            web3_transaction_select_sql()
+        } else {
+            "transactions.hash as tx_hash"
+        };
+        let query = format!(
+            "SELECT
+                miniblocks.hash as block_hash,
+                miniblocks.number,
+                miniblocks.l1_batch_number,
+                miniblocks.timestamp,
+                miniblocks.base_fee_per_gas,
+                l1_batches.timestamp as l1_batch_timestamp,
+                transactions.gas_limit as gas_limit,
+                transactions.refunded_gas as refunded_gas,
+                {}
+            FROM miniblocks
+            LEFT JOIN l1_batches
+                ON l1_batches.number = miniblocks.l1_batch_number
+            LEFT JOIN transactions
+                ON transactions.miniblock_number = miniblocks.number
+            WHERE miniblocks.hash = ANY($1)
+            ORDER BY miniblocks.number, transactions.index_in_block",
+            transactions_sql
+        );
+        let hash_bytes: Vec<_> = hashes.iter().map(|hash| hash.as_bytes().to_vec()).collect();
+        let rows = sqlx::query(&query)
+            .bind(hash_bytes)
+            .fetch_all(self.storage.conn())
+            .await?;
+
+        // Rows are ordered by the block number, so rows for each block are contiguous.
+        let mut blocks_by_hash = HashMap::new();
+        let mut current_block: Option<BlockWithBatchMetadata> = None;
+        for db_row in rows {
+            let hash = H256::from_slice(db_row.get("block_hash"));
+            if let Some((block, _)) = &current_block {
+                if block.hash != hash {
+                    let (block, _) = current_block.take().unwrap();
+                    blocks_by_hash.insert(block.hash, block);
+                }
+            }
+            current_block = Some(fold_block_row(
+                current_block,
+                db_row,
+                include_full_transactions,
+                false,
+                chain_id,
+            ));
+        }
+        if let Some((block, _)) = current_block {
+            blocks_by_hash.insert(block.hash, block);
+        }
+
+        Ok(hashes
+            .iter()
+            .filter_map(|hash| blocks_by_hash.get(hash).cloned())
+            .collect())
+    }
+
+    /// Assembles a provisional pending block, i.e., the block following the latest sealed miniblock.
+    /// The block has a zero hash and includes at most `max_transactions` transactions currently
+    /// in the mempool, in the order they are likely to be executed by the state keeper using
+    /// the `ordering` strategy. Since the block is not executed yet, its gas used is zero,
+    /// and its timestamp and base fee are copied from the latest sealed miniblock.
+    /// Returns `None` before genesis.
+    ///
+    /// Only transactions loaded into the state keeper mempool are included. Hence, the block
+    /// never has transactions on nodes without a mempool, such as the external node.
+    ///
+    /// This is noticeably more expensive than loading a sealed block, since the mempool
+    /// may be large.
+    pub async fn get_pending_block(
+        &mut self,
+        include_full_transactions: bool,
+        chain_id: L2ChainId,
+        max_transactions: usize,
+        ordering: MempoolOrderingStrategy,
+        min_base_fee_per_gas: Option<U256>,
+    ) -> Result<Option<api::Block<api::TransactionVariant>>, BlocksWeb3DalError> {
+        let latest_row = sqlx::query!(
+            "SELECT number, timestamp, base_fee_per_gas FROM miniblocks \
+            ORDER BY number DESC LIMIT 1"
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        let Some(latest_row) = latest_row else {
+            return Ok(None);
+        };
+        let latest_number: MiniblockNumber = block_number_from_db(latest_row.number)?;
+
+        let transactions_sql = if include_full_transactions {
+            web3_transaction_select_sql()
+        } else {
+            "transactions.hash as tx_hash"
+        };
+        // Mirrors the ordering of the in-memory mempool: with the priority fee ordering, the fee key
+        // of a transaction is the minimum priority fee among the transactions of the same account
+        // with lower or equal nonces, so that transactions from an account stay in the nonce order.
+        let (fee_key_sql, order_by_sql) = match ordering {
+            MempoolOrderingStrategy::Fifo => ("", "transactions.received_at"),
+            MempoolOrderingStrategy::PriorityFee => (
+                ", MIN(transactions.max_priority_fee_per_gas) OVER (
+                    PARTITION BY transactions.initiator_address ORDER BY transactions.nonce
+                ) AS fee_key",
+                "fee_key DESC, transactions.received_at, transactions.nonce",
+            ),
+        };
+        // `miniblocks` are joined so that `web3_transaction_select_sql()` is valid; the joined columns
+        // are always `NULL` for pending transactions.
+        let query = format!(
+            "SELECT {transactions_sql}{fee_key_sql}
+            FROM transactions
+            LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+            WHERE transactions.miniblock_number IS NULL AND transactions.in_mempool = TRUE
+            ORDER BY transactions.is_priority DESC, transactions.priority_op_id, {order_by_sql}
+            LIMIT $1"
+        );
+        let rows = sqlx::query(&query)
+            .bind(max_transactions as i64)
+            .fetch_all(self.storage.conn())
+            .await?;
+        let transactions = rows
+            .into_iter()
+            .map(|db_row| {
+                if include_full_transactions {
+                    api::TransactionVariant::Full(extract_web3_transaction(db_row, chain_id))
+                } else {
+                    api::TransactionVariant::Hash(H256::from_slice(db_row.get("tx_hash")))
+                }
+            })
+            .collect();
+
+        let mut block = api::Block {
+            hash: H256::zero(),
+            parent_hash: miniblock_hash(latest_number),
+            uncles_hash: EMPTY_UNCLES_HASH,
+            number: U64::from(latest_number.0 + 1),
+            gas_limit: BLOCK_GAS_LIMIT.into(),
+            base_fee_per_gas: bigdecimal_to_u256(latest_row.base_fee_per_gas),
+            timestamp: latest_row.timestamp.into(),
+            transactions,
+            ..api::Block::default()
+        };
+        if let Some(min_base_fee_per_gas) = min_base_fee_per_gas {
+            apply_base_fee_floor(&mut block, min_base_fee_per_gas);
+        }
+        Ok(Some(block))
+    }
+
+    async fn get_block_inner(
+        &mut self,
+        block_id: api::BlockId,
+        include_full_transactions: bool,
+        include_batch_metadata: bool,
+        chain_id: L2ChainId,
+    ) -> Result<Option<BlockWithBatchMetadata>, BlocksWeb3DalError> {
         let transactions_sql = if include_full_transactions {
             web3_transaction_select_sql()
         } else {
             "transactions.hash as tx_hash"
         };
+        let (batch_metadata_sql, batch_metadata_joins_sql) = if include_batch_metadata {
+            (
+                "l1_batches.number as batch_number,
+                commit_tx.tx_hash as commit_tx_hash,
+                prove_tx.tx_hash as prove_tx_hash,
+                execute_tx.tx_hash as execute_tx_hash,",
+                "LEFT JOIN eth_txs_history as commit_tx
+                    ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id AND commit_tx.confirmed_at IS NOT NULL)
+                LEFT JOIN eth_txs_history as prove_tx
+                    ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id AND prove_tx.confirmed_at IS NOT NULL)
+                LEFT JOIN eth_txs_history as execute_tx
+                    ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id AND execute_tx.confirmed_at IS NOT NULL)",
+            )
+        } else {
+            ("", "")
+        };
 
         let query = format!(
             "SELECT
@@ -70,16 +423,20 @@ impl BlocksWeb3Dal<'_, '_> {
                 miniblocks.timestamp,
                 miniblocks.base_fee_per_gas,
                 l1_batches.timestamp as l1_batch_timestamp,
+                {}
                 transactions.gas_limit as gas_limit,
                 transactions.refunded_gas as refunded_gas,
                 {}
             FROM miniblocks
             LEFT JOIN l1_batches
                 ON l1_batches.number = miniblocks.l1_batch_number
+            {}
             LEFT JOIN transactions
                 ON transactions.miniblock_number = miniblocks.number
             WHERE {}",
+            batch_metadata_sql,
             transactions_sql,
+            batch_metadata_joins_sql,
             web3_block_where_sql(block_id, 1)
         );
 
@@ -87,54 +444,13 @@ impl BlocksWeb3Dal<'_, '_> {
         let rows = query.fetch_all(self.storage.conn()).await?.into_iter();
 
         let block = rows.fold(None, |prev_block, db_row| {
-            let mut block = prev_block.unwrap_or_else(|| {
-                // This code will be only executed for the first row in the DB response.
-                // All other rows will only be used to extract relevant transactions.
-                let hash = db_row
-                    .try_get("block_hash")
-                    .map_or_else(|_| H256::zero(), H256::from_slice);
-                let number = U64::from(db_row.get::<i64, &str>("number"));
-                let l1_batch_number = db_row
-                    .try_get::<i64, &str>("l1_batch_number")
-                    .map(U64::from)
-                    .ok();
-                let l1_batch_timestamp = db_row
-                    .try_get::<i64, &str>("l1_batch_timestamp")
-                    .map(U256::from)
-                    .ok();
-                let parent_hash = match number.as_u32() {
-                    0 => H256::zero(),
-                    number => miniblock_hash(MiniblockNumber(number - 1)),
-                };
-                let base_fee_per_gas = db_row.get::<BigDecimal, &str>("base_fee_per_gas");
-
-                api::Block {
-                    hash,
-                    parent_hash,
-                    uncles_hash: EMPTY_UNCLES_HASH,
-                    number,
-                    l1_batch_number,
-                    gas_limit: BLOCK_GAS_LIMIT.into(),
-                    base_fee_per_gas: bigdecimal_to_u256(base_fee_per_gas),
-                    timestamp: db_row.get::<i64, &str>("timestamp").into(),
-                    l1_batch_timestamp,
-                    ..api::Block::default()
-                }
-            });
-            if db_row.try_get::<&[u8], &str>("tx_hash").is_ok() {
-                let tx_gas_limit = bigdecimal_to_u256(db_row.get::<BigDecimal, &str>("gas_limit"));
-                let tx_refunded_gas = U256::from((db_row.get::<i64, &str>("refunded_gas")) as u32);
-
-                block.gas_used += tx_gas_limit - tx_refunded_gas;
-                let tx = if include_full_transactions {
-                    let tx = extract_web3_transaction(db_row, chain_id);
-                    api::TransactionVariant::Full(tx)
-                } else {
-                    api::TransactionVariant::Hash(H256::from_slice(db_row.get("tx_hash")))
-                };
-                block.transactions.push(tx);
-            }
-            Some(block)
+            Some(fold_block_row(
+                prev_block,
+                db_row,
+                include_full_transactions,
+                include_batch_metadata,
+                chain_id,
+            ))
         });
         Ok(block)
     }
@@ -142,7 +458,7 @@ impl BlocksWeb3Dal<'_, '_> {
     pub async fn get_block_tx_count(
         &mut self,
         block_id: api::BlockId,
-    ) -> Result<Option<U256>, SqlxError> {
+    ) -> Result<Option<U256>, BlocksWeb3DalError> {
         let query = format!(
             "SELECT l1_tx_count + l2_tx_count as tx_count FROM miniblocks WHERE {}",
             web3_block_where_sql(block_id, 1)
@@ -156,33 +472,58 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(tx_count.map(|t| (t as u32).into()))
     }
 
-    /// Returns hashes of blocks with numbers greater than `from_block` and the number of the last block.
+    /// Returns the total number of transactions in all miniblocks of the specified L1 batch,
+    /// or `None` if the batch is unknown.
+    pub async fn get_l1_batch_tx_count(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<U256>, BlocksWeb3DalError> {
+        let tx_count = sqlx::query!(
+            "SELECT SUM(l1_tx_count + l2_tx_count) AS \"tx_count?\" \
+            FROM miniblocks WHERE l1_batch_number = $1",
+            l1_batch_number.0 as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await?
+        .tx_count;
+        Ok(tx_count.map(|count| (count as u64).into()))
+    }
+
+    /// Returns hashes of at most `limit` blocks with numbers greater than `from_block`, the number
+    /// of the last returned block, and a flag indicating whether the result was truncated
+    /// (i.e., there are more blocks after the last returned one).
     pub async fn get_block_hashes_after(
         &mut self,
         from_block: MiniblockNumber,
         limit: usize,
-    ) -> Result<(Vec<H256>, Option<MiniblockNumber>), SqlxError> {
-        let rows = sqlx::query!(
+    ) -> Result<(Vec<H256>, Option<MiniblockNumber>, bool), BlocksWeb3DalError> {
+        // Fetch an extra row to determine whether the result is truncated.
+        let mut rows = sqlx::query!(
             "SELECT number, hash FROM miniblocks \
             WHERE number > $1 \
             ORDER BY number ASC \
             LIMIT $2",
             from_block.0 as i64,
-            limit as i32
+            i64::try_from(limit).unwrap_or(i64::MAX).saturating_add(1)
         )
         .fetch_all(self.storage.conn())
         .await?;
 
-        let last_block_number = rows.last().map(|row| MiniblockNumber(row.number as u32));
+        let is_truncated = rows.len() > limit;
+        rows.truncate(limit);
+        let last_block_number = rows
+            .last()
+            .map(|row| block_number_from_db(row.number))
+            .transpose()?;
         let hashes = rows.iter().map(|row| H256::from_slice(&row.hash)).collect();
-        Ok((hashes, last_block_number))
+        Ok((hashes, last_block_number, is_truncated))
     }
 
     /// Returns hashes of blocks with numbers greater than `from_block` and the number of the last block.
     pub async fn get_block_headers_after(
         &mut self,
         from_block: MiniblockNumber,
-    ) -> Result<Vec<BlockHeader>, SqlxError> {
+    ) -> Result<Vec<BlockHeader>, BlocksWeb3DalError> {
         let rows = sqlx::query!(
             "SELECT hash, number, timestamp \
             FROM miniblocks \
@@ -215,10 +556,16 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(blocks.collect())
     }
 
+    /// Resolves the block ID to a miniblock number. Returns `None` if the block doesn't exist.
+    ///
+    /// The `pending` tag is resolved to the number following the latest sealed miniblock, i.e.,
+    /// to a miniblock that doesn't exist at the time of resolution. Since miniblocks are sealed
+    /// concurrently, this miniblock may be sealed before a subsequent read; use
+    /// [`Self::resolve_pending_block()`] to detect such races.
     pub async fn resolve_block_id(
         &mut self,
         block_id: api::BlockId,
-    ) -> Result<Option<MiniblockNumber>, SqlxError> {
+    ) -> Result<Option<MiniblockNumber>, BlocksWeb3DalError> {
         let query_string = match block_id {
             api::BlockId::Hash(_) => "SELECT number FROM miniblocks WHERE hash = $1".to_owned(),
             api::BlockId::Number(api::BlockNumber::Number(_)) => {
@@ -229,7 +576,11 @@ impl BlocksWeb3Dal<'_, '_> {
                 "SELECT number FROM miniblocks WHERE number = $1".to_owned()
             }
             api::BlockId::Number(api::BlockNumber::Earliest) => {
-                return Ok(Some(MiniblockNumber(0)));
+                return match self.get_earliest_available_miniblock().await {
+                    Ok(number) => Ok(Some(number)),
+                    Err(BlocksWeb3DalError::BeforeGenesis) => Ok(None),
+                    Err(err) => Err(err),
+                };
             }
             api::BlockId::Number(block_number) => web3_block_number_to_sql(block_number),
         };
@@ -239,14 +590,182 @@ impl BlocksWeb3Dal<'_, '_> {
 
         let block_number = row
             .and_then(|row| row.get::<Option<i64>, &str>("number"))
-            .map(|n| MiniblockNumber(n as u32));
+            .map(block_number_from_db)
+            .transpose()?;
+
+        if let (api::BlockId::Hash(hash), Some(number)) = (block_id, block_number) {
+            check_miniblock_hash(hash, number);
+        }
         Ok(block_number)
     }
 
+    /// Same as [`Self::resolve_block_id()`], but additionally checks that the storage this DAL
+    /// is used with (presumably, a read replica) doesn't lag behind `master` by more than allowed
+    /// by `tolerance`. If the replica lags, it's polled until it catches up or `tolerance.max_wait`
+    /// elapses; in the latter case, [`BlocksWeb3DalError::ReplicaLagging`] is returned.
+    pub async fn resolve_block_id_on_replica(
+        &mut self,
+        block_id: api::BlockId,
+        master: &mut StorageProcessor<'_>,
+        tolerance: ReplicaLagTolerance,
+    ) -> Result<Option<MiniblockNumber>, BlocksWeb3DalError> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+        let started_at = Instant::now();
+        let master_number = master
+            .blocks_web3_dal()
+            .get_sealed_miniblock_number()
+            .await?;
+        loop {
+            let replica_number = self.get_sealed_miniblock_number().await?;
+            if master_number.0.saturating_sub(replica_number.0) <= tolerance.max_lag {
+                break;
+            }
+
+            let elapsed = started_at.elapsed();
+            if elapsed >= tolerance.max_wait {
+                return Err(BlocksWeb3DalError::ReplicaLagging {
+                    replica: replica_number,
+                    master: master_number,
+                });
+            }
+            tokio::time::sleep(POLL_INTERVAL.min(tolerance.max_wait - elapsed)).await;
+        }
+        self.resolve_block_id(block_id).await
+    }
+
+    /// Resolves the `pending` block tag in the same way as [`Self::resolve_block_id()`], additionally
+    /// returning the latest sealed miniblock at the time of resolution. Returns `None` before genesis.
+    pub async fn resolve_pending_block(
+        &mut self,
+    ) -> Result<Option<ResolvedPendingBlock>, BlocksWeb3DalError> {
+        let latest = sqlx::query!("SELECT MAX(number) as \"number\" FROM miniblocks")
+            .fetch_one(self.storage.conn())
+            .await?
+            .number;
+        let Some(latest) = latest else {
+            return Ok(None);
+        };
+        let latest: MiniblockNumber = block_number_from_db(latest)?;
+        let pending = block_number_from_db(i64::from(latest.0) + 1)?;
+        Ok(Some(ResolvedPendingBlock { pending, latest }))
+    }
+
+    /// Resolves multiple block IDs at once. The returned vector is aligned with `block_ids`.
+    ///
+    /// Unlike calling [`Self::resolve_block_id()`] for each ID, this method issues a bounded number
+    /// of queries: explicit block numbers and hashes are resolved with a single query each,
+    /// and `latest` / `pending` IDs share a single query for the last sealed miniblock.
+    pub async fn resolve_block_ids(
+        &mut self,
+        block_ids: &[api::BlockId],
+    ) -> Result<Vec<Option<MiniblockNumber>>, BlocksWeb3DalError> {
+        let mut numbers = vec![];
+        let mut hashes = vec![];
+        let mut needs_last_miniblock = false;
+        for block_id in block_ids {
+            match block_id {
+                api::BlockId::Hash(hash) => hashes.push(hash.as_bytes().to_vec()),
+                api::BlockId::Number(api::BlockNumber::Number(number)) => {
+                    numbers.push(number.as_u64() as i64);
+                }
+                api::BlockId::Number(
+                    api::BlockNumber::Latest
+                    | api::BlockNumber::Committed
+                    | api::BlockNumber::Pending,
+                ) => {
+                    needs_last_miniblock = true;
+                }
+                api::BlockId::Number(api::BlockNumber::Earliest | api::BlockNumber::Finalized) => { /* resolved separately */
+                }
+            }
+        }
+
+        let existing_numbers: HashSet<u64> = if numbers.is_empty() {
+            HashSet::new()
+        } else {
+            sqlx::query!(
+                "SELECT number FROM miniblocks WHERE number = ANY($1)",
+                &numbers
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| row.number as u64)
+            .collect()
+        };
+        let numbers_by_hash: HashMap<H256, MiniblockNumber> = if hashes.is_empty() {
+            HashMap::new()
+        } else {
+            sqlx::query!(
+                "SELECT number, hash FROM miniblocks WHERE hash = ANY($1)",
+                &hashes
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| {
+                let hash = H256::from_slice(&row.hash);
+                block_number_from_db(row.number).map(|number| (hash, number))
+            })
+            .collect::<Result<_, _>>()?
+        };
+        let last_miniblock_number = if needs_last_miniblock {
+            sqlx::query!("SELECT MAX(number) as \"number\" FROM miniblocks")
+                .fetch_one(self.storage.conn())
+                .await?
+                .number
+                .map(block_number_from_db)
+                .transpose()?
+        } else {
+            None
+        };
+
+        // Other tags are resolved at most once each.
+        let mut resolved_tags: Vec<(api::BlockNumber, Option<MiniblockNumber>)> = vec![];
+        let mut resolved = Vec::with_capacity(block_ids.len());
+        for &block_id in block_ids {
+            let block_number = match block_id {
+                api::BlockId::Hash(hash) => {
+                    let number = numbers_by_hash.get(&hash).copied();
+                    if let Some(number) = number {
+                        check_miniblock_hash(hash, number);
+                    }
+                    number
+                }
+                api::BlockId::Number(api::BlockNumber::Number(number)) => existing_numbers
+                    .contains(&number.as_u64())
+                    .then(|| MiniblockNumber(number.as_u32())),
+                api::BlockId::Number(api::BlockNumber::Latest | api::BlockNumber::Committed) => {
+                    last_miniblock_number
+                }
+                api::BlockId::Number(api::BlockNumber::Pending) => {
+                    last_miniblock_number.map(|number| number + 1)
+                }
+                api::BlockId::Number(
+                    tag @ (api::BlockNumber::Earliest | api::BlockNumber::Finalized),
+                ) => {
+                    let cached = resolved_tags
+                        .iter()
+                        .find(|(cached_tag, _)| *cached_tag == tag);
+                    if let Some(&(_, number)) = cached {
+                        number
+                    } else {
+                        let number = self.resolve_block_id(block_id).await?;
+                        resolved_tags.push((tag, number));
+                        number
+                    }
+                }
+            };
+            resolved.push(block_number);
+        }
+        Ok(resolved)
+    }
+
     pub async fn get_block_timestamp(
         &mut self,
         block_number: MiniblockNumber,
-    ) -> Result<Option<u64>, SqlxError> {
+    ) -> Result<Option<u64>, BlocksWeb3DalError> {
         let timestamp = sqlx::query!(
             "SELECT timestamp FROM miniblocks WHERE number = $1",
             block_number.0 as i64
@@ -257,10 +776,116 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(timestamp)
     }
 
+    /// Returns the number of the last miniblock with timestamp not exceeding `timestamp`,
+    /// or `None` if the timestamp predates the genesis miniblock.
+    ///
+    /// Miniblock timestamps are non-decreasing, but not strictly increasing: several consecutive
+    /// miniblocks may share the same timestamp, in which case the latest of them is returned.
+    pub async fn get_miniblock_at_or_before_timestamp(
+        &mut self,
+        timestamp: u64,
+    ) -> Result<Option<MiniblockNumber>, BlocksWeb3DalError> {
+        let row = sqlx::query!(
+            "SELECT number FROM miniblocks WHERE timestamp <= $1 ORDER BY number DESC LIMIT 1",
+            timestamp as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        row.map(|row| block_number_from_db(row.number)).transpose()
+    }
+
+    /// Counts miniblocks with timestamps within `[from_timestamp, to_timestamp]` (both bounds inclusive).
+    /// Returns 0 if the window is empty.
+    pub async fn count_miniblocks_in_time_window(
+        &mut self,
+        from_timestamp: u64,
+        to_timestamp: u64,
+    ) -> Result<u32, BlocksWeb3DalError> {
+        if from_timestamp > to_timestamp {
+            return Ok(0);
+        }
+        let count = sqlx::query!(
+            "SELECT COUNT(*) AS \"count!\" FROM miniblocks WHERE timestamp BETWEEN $1 AND $2",
+            from_timestamp as i64,
+            to_timestamp as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await?
+        .count;
+        Ok(count as u32)
+    }
+
+    /// Returns base fees and gas used ratios of the most recent `block_count` miniblocks up to
+    /// and including `to_block`, ordered by miniblock number ascending. If there are fewer miniblocks
+    /// available, returns the history for all of them.
+    ///
+    /// Gas used ratios are computed relative to the block gas limit and are clamped to `[0, 1]`.
+    pub async fn get_base_fee_history(
+        &mut self,
+        to_block: MiniblockNumber,
+        block_count: usize,
+    ) -> Result<(Vec<U256>, Vec<f64>), BlocksWeb3DalError> {
+        let rows = sqlx::query!(
+            "SELECT base_fee_per_gas, \
+                (SELECT SUM(gas_limit - refunded_gas) FROM transactions \
+                    WHERE miniblock_number = miniblocks.number) AS \"gas_used?\" \
+            FROM miniblocks \
+            WHERE number <= $1 \
+            ORDER BY number DESC \
+            LIMIT $2",
+            to_block.0 as i64,
+            block_count as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let (mut base_fees, mut gas_used_ratios): (Vec<_>, Vec<_>) = rows
+            .into_iter()
+            .map(|row| {
+                let base_fee = bigdecimal_to_u256(row.base_fee_per_gas);
+                let gas_used = row
+                    .gas_used
+                    .and_then(|gas_used| gas_used.to_f64())
+                    .unwrap_or(0.0);
+                let gas_used_ratio = (gas_used / f64::from(BLOCK_GAS_LIMIT)).clamp(0.0, 1.0);
+                (base_fee, gas_used_ratio)
+            })
+            .unzip();
+        base_fees.reverse();
+        gas_used_ratios.reverse();
+        Ok((base_fees, gas_used_ratios))
+    }
+
+    /// Returns the total fee collected for transactions in the inclusive miniblock range `from..=to`.
+    /// The fee of a transaction is its gas used multiplied by its effective gas price; gas used
+    /// is computed in the same way as in [`Self::get_block_by_web3_block_id()`]. Returns zero
+    /// for an empty range.
+    pub async fn get_total_fees_collected(
+        &mut self,
+        from: MiniblockNumber,
+        to: MiniblockNumber,
+    ) -> Result<U256, BlocksWeb3DalError> {
+        if from > to {
+            return Ok(U256::zero());
+        }
+        let row = sqlx::query!(
+            "SELECT SUM(GREATEST(gas_limit - refunded_gas, 0) * effective_gas_price) AS \"fees?\" \
+            FROM transactions \
+            WHERE miniblock_number BETWEEN $1 AND $2",
+            from.0 as i64,
+            to.0 as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row.fees.map_or_else(U256::zero, bigdecimal_to_u256))
+    }
+
+    /// Returns L2-to-L1 logs emitted in the specified L1 batch. Returns an empty list
+    /// if the batch is unknown.
     pub async fn get_l2_to_l1_logs(
         &mut self,
         block_number: L1BatchNumber,
-    ) -> Result<Vec<L2ToL1Log>, SqlxError> {
+    ) -> Result<Vec<L2ToL1Log>, BlocksWeb3DalError> {
         let raw_logs = sqlx::query!(
             "SELECT l2_to_l1_logs FROM l1_batches WHERE number = $1",
             block_number.0 as i64
@@ -279,7 +904,7 @@ impl BlocksWeb3Dal<'_, '_> {
     pub async fn get_l1_batch_number_of_miniblock(
         &mut self,
         miniblock_number: MiniblockNumber,
-    ) -> Result<Option<L1BatchNumber>, SqlxError> {
+    ) -> Result<Option<L1BatchNumber>, BlocksWeb3DalError> {
         let number: Option<i64> = sqlx::query!(
             "SELECT l1_batch_number FROM miniblocks WHERE number = $1",
             miniblock_number.0 as i64
@@ -288,13 +913,13 @@ impl BlocksWeb3Dal<'_, '_> {
         .await?
         .and_then(|row| row.l1_batch_number);
 
-        Ok(number.map(|number| L1BatchNumber(number as u32)))
+        number.map(block_number_from_db).transpose()
     }
 
     pub async fn get_miniblock_range_of_l1_batch(
         &mut self,
         l1_batch_number: L1BatchNumber,
-    ) -> Result<Option<(MiniblockNumber, MiniblockNumber)>, SqlxError> {
+    ) -> Result<Option<(MiniblockNumber, MiniblockNumber)>, BlocksWeb3DalError> {
         let row = sqlx::query!(
             "SELECT MIN(miniblocks.number) as \"min?\", MAX(miniblocks.number) as \"max?\" \
             FROM miniblocks \
@@ -306,17 +931,92 @@ impl BlocksWeb3Dal<'_, '_> {
 
         Ok(match (row.min, row.max) {
             (Some(min), Some(max)) => {
-                Some((MiniblockNumber(min as u32), MiniblockNumber(max as u32)))
+                Some((block_number_from_db(min)?, block_number_from_db(max)?))
             }
             (None, None) => None,
             _ => unreachable!(),
         })
     }
 
+    /// Returns the number of miniblocks in the specified L1 batch. Returns 0 if the batch doesn't exist
+    /// or has no miniblocks assigned yet.
+    pub async fn get_miniblock_count_in_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<u32, BlocksWeb3DalError> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) as \"count!\" FROM miniblocks WHERE l1_batch_number = $1",
+            l1_batch_number.0 as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await?
+        .count;
+
+        Ok(count as u32)
+    }
+
+    /// Returns numbers of L1 blocks in which commit, prove and execute transactions for the specified
+    /// L1 batch were mined, or `None` if the batch doesn't exist. Used to compute the finality depth
+    /// of the batch stages.
+    pub async fn get_l1_batch_stage_l1_blocks(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<BatchStageL1Blocks>, BlocksWeb3DalError> {
+        let row = sqlx::query!(
+            "SELECT commit_tx.confirmed_at_block as commit_block, \
+                prove_tx.confirmed_at_block as prove_block, \
+                execute_tx.confirmed_at_block as execute_block \
+            FROM l1_batches \
+            LEFT JOIN eth_txs_history as commit_tx \
+                ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id AND commit_tx.confirmed_at IS NOT NULL) \
+            LEFT JOIN eth_txs_history as prove_tx \
+                ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id AND prove_tx.confirmed_at IS NOT NULL) \
+            LEFT JOIN eth_txs_history as execute_tx \
+                ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id AND execute_tx.confirmed_at IS NOT NULL) \
+            WHERE l1_batches.number = $1",
+            l1_batch_number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        Ok(Some(BatchStageL1Blocks {
+            commit: row.commit_block.map(block_number_from_db).transpose()?,
+            prove: row.prove_block.map(block_number_from_db).transpose()?,
+            execute: row.execute_block.map(block_number_from_db).transpose()?,
+        }))
+    }
+
+    /// Returns numbers and hashes of miniblocks in the specified inclusive range, ordered by number.
+    pub async fn get_miniblock_hashes_in_range(
+        &mut self,
+        from_miniblock: MiniblockNumber,
+        to_miniblock: MiniblockNumber,
+    ) -> Result<Vec<(MiniblockNumber, H256)>, BlocksWeb3DalError> {
+        let rows = sqlx::query!(
+            "SELECT number, hash FROM miniblocks \
+            WHERE number BETWEEN $1 AND $2 \
+            ORDER BY number",
+            from_miniblock.0 as i64,
+            to_miniblock.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let hash = H256::from_slice(&row.hash);
+                block_number_from_db(row.number).map(|number| (number, hash))
+            })
+            .collect()
+    }
+
     pub async fn get_l1_batch_info_for_tx(
         &mut self,
         tx_hash: H256,
-    ) -> Result<Option<(L1BatchNumber, u16)>, SqlxError> {
+    ) -> Result<Option<(L1BatchNumber, u16)>, BlocksWeb3DalError> {
         let row = sqlx::query!(
             "SELECT l1_batch_number, l1_batch_tx_index \
             FROM transactions \
@@ -327,38 +1027,400 @@ impl BlocksWeb3Dal<'_, '_> {
         .await?;
 
         let result = row.and_then(|row| match (row.l1_batch_number, row.l1_batch_tx_index) {
-            (Some(l1_batch_number), Some(l1_batch_tx_index)) => Some((
-                L1BatchNumber(l1_batch_number as u32),
-                l1_batch_tx_index as u16,
-            )),
+            (Some(l1_batch_number), Some(l1_batch_tx_index)) => Some(
+                block_number_from_db(l1_batch_number)
+                    .map(|number| (number, l1_batch_tx_index as u16)),
+            ),
             _ => None,
         });
-        Ok(result)
+        result.transpose()
     }
 
-    pub async fn get_trace_for_miniblock(&mut self, block_number: MiniblockNumber) -> Vec<Call> {
-        sqlx::query_as!(
-            CallTrace,
-            "SELECT * FROM call_traces WHERE tx_hash IN \
-                (SELECT hash FROM transactions WHERE miniblock_number = $1)",
-            block_number.0 as i64
+    /// Returns the number of the miniblock including the specified transaction, or `None`
+    /// if the transaction is unknown or not included into a miniblock yet.
+    pub async fn get_miniblock_number_for_tx(
+        &mut self,
+        tx_hash: H256,
+    ) -> Result<Option<MiniblockNumber>, BlocksWeb3DalError> {
+        let number: Option<i64> = sqlx::query!(
+            "SELECT miniblock_number FROM transactions WHERE hash = $1",
+            tx_hash.as_bytes()
         )
-        .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
+        .fetch_optional(self.storage.conn())
+        .await?
+        .and_then(|row| row.miniblock_number);
+
+        number.map(block_number_from_db).transpose()
+    }
+
+    /// Returns contiguous ranges of miniblock numbers within `[from, to]` (both bounds inclusive)
+    /// that are missing from the storage. Ranges are ordered by their start and have inclusive bounds.
+    /// Note that miniblocks after the last sealed one are reported as missing as well.
+    pub async fn find_miniblock_gaps(
+        &mut self,
+        from: MiniblockNumber,
+        to: MiniblockNumber,
+    ) -> Result<Vec<(MiniblockNumber, MiniblockNumber)>, BlocksWeb3DalError> {
+        if from > to {
+            return Err(BlocksWeb3DalError::InvalidRange(format!(
+                "start miniblock #{from} is greater than end miniblock #{to}"
+            )));
+        }
+        // Sentinel numbers just outside the range allow to detect gaps at the range boundaries.
+        let rows = sqlx::query!(
+            "SELECT number + 1 AS \"gap_start!\", next_number - 1 AS \"gap_end!\" \
+            FROM ( \
+                SELECT number, LEAD(number) OVER (ORDER BY number) AS next_number \
+                FROM ( \
+                    SELECT number FROM miniblocks WHERE number BETWEEN $1 AND $2 \
+                    UNION ALL SELECT $1 - 1 \
+                    UNION ALL SELECT $2 + 1 \
+                ) AS numbers \
+            ) AS numbers_with_next \
+            WHERE next_number > number + 1 \
+            ORDER BY number",
+            from.0 as i64,
+            to.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok((
+                    block_number_from_db(row.gap_start)?,
+                    block_number_from_db(row.gap_end)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns numbers of miniblocks within `[from, to]` (both bounds inclusive) whose stored hash
+    /// differs from the deterministic [`miniblock_hash()`]. Missing miniblocks are not reported.
+    pub async fn verify_miniblock_hashes(
+        &mut self,
+        from: MiniblockNumber,
+        to: MiniblockNumber,
+    ) -> Result<Vec<MiniblockNumber>, BlocksWeb3DalError> {
+        if from > to {
+            return Err(BlocksWeb3DalError::InvalidRange(format!(
+                "start miniblock #{from} is greater than end miniblock #{to}"
+            )));
+        }
+
+        let rows = sqlx::query!(
+            "SELECT number, hash FROM miniblocks \
+            WHERE number BETWEEN $1 AND $2 \
+            ORDER BY number",
+            from.0 as i64,
+            to.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        rows.into_iter()
+            .filter_map(|row| {
+                let number = match block_number_from_db(row.number) {
+                    Ok(number) => number,
+                    Err(err) => return Some(Err(err)),
+                };
+                (row.hash != miniblock_hash(number).as_bytes()).then_some(Ok(number))
+            })
+            .collect()
+    }
+
+    /// Returns numbers of miniblocks within `[from, to]` (both bounds inclusive) whose parent link
+    /// is broken, i.e., whose parent hash returned by the API (which is computed deterministically)
+    /// differs from the stored hash of the previous miniblock. Unlike [`Self::find_miniblock_gaps()`],
+    /// this detects corrupted data rather than missing miniblocks; miniblocks with a missing
+    /// predecessor are not reported.
+    pub async fn check_block_chain_integrity(
+        &mut self,
+        from: MiniblockNumber,
+        to: MiniblockNumber,
+    ) -> Result<Vec<MiniblockNumber>, BlocksWeb3DalError> {
+        if from > to {
+            return Err(BlocksWeb3DalError::InvalidRange(format!(
+                "start miniblock #{from} is greater than end miniblock #{to}"
+            )));
+        }
+        if to.0 == 0 {
+            // The genesis miniblock has no parent.
+            return Ok(vec![]);
+        }
+
+        // The parent link of a miniblock is broken iff the stored hash of its parent is corrupted.
+        let parents_from = MiniblockNumber(from.0.saturating_sub(1));
+        let corrupted_parents = self.verify_miniblock_hashes(parents_from, to - 1).await?;
+        if corrupted_parents.is_empty() {
+            return Ok(vec![]);
+        }
+        let children: Vec<_> = corrupted_parents
+            .iter()
+            .map(|&parent| (parent.0 + 1) as i64)
+            .collect();
+        let rows = sqlx::query!(
+            "SELECT number FROM miniblocks WHERE number = ANY($1)",
+            &children
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let mut broken_links = rows
+            .into_iter()
+            .map(|row| block_number_from_db(row.number))
+            .collect::<Result<Vec<MiniblockNumber>, _>>()?;
+        broken_links.sort_unstable();
+        Ok(broken_links)
+    }
+
+    pub async fn get_trace_for_miniblock(&mut self, block_number: MiniblockNumber) -> Vec<Call> {
+        sqlx::query_as!(
+            CallTrace,
+            "SELECT * FROM call_traces WHERE tx_hash IN \
+                (SELECT hash FROM transactions WHERE miniblock_number = $1)",
+            block_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
         .into_iter()
         .map(Call::from)
         .collect()
     }
 }
 
+fn check_miniblock_hash(hash: H256, number: MiniblockNumber) {
+    // Miniblock hashes are deterministic, so a mismatch means that the stored data is corrupted.
+    let expected_hash = miniblock_hash(number);
+    if hash != expected_hash {
+        vlog::warn!(
+            "Hash {hash:?} of miniblock #{number} stored in Postgres differs from \
+             the expected hash {expected_hash:?}; the stored data may be corrupted"
+        );
+    }
+}
+
+/// Raises the base fee of a block returned by the API to `min_base_fee_per_gas`. Base fees may be
+/// too low (e.g., zero) right after genesis, which some wallets reject.
+fn apply_base_fee_floor<T>(block: &mut api::Block<T>, min_base_fee_per_gas: U256) {
+    if block.base_fee_per_gas < min_base_fee_per_gas {
+        vlog::debug!(
+            "Raising base fee per gas {} for miniblock #{} to the configured minimum {min_base_fee_per_gas}",
+            block.base_fee_per_gas,
+            block.number
+        );
+        block.base_fee_per_gas = min_base_fee_per_gas;
+    }
+}
+
+/// Folds a row returned by a block query into the block. The first row of the block initializes it
+/// (`prev_block` is `None` in this case); each row also contributes a transaction of the block, if any.
+fn fold_block_row(
+    prev_block: Option<BlockWithBatchMetadata>,
+    db_row: PgRow,
+    include_full_transactions: bool,
+    include_batch_metadata: bool,
+    chain_id: L2ChainId,
+) -> BlockWithBatchMetadata {
+    let (mut block, batch_metadata) = prev_block.unwrap_or_else(|| {
+        // This code will be only executed for the first row of the block.
+        // All other rows will only be used to extract relevant transactions.
+        let hash = db_row
+            .try_get("block_hash")
+            .map_or_else(|_| H256::zero(), H256::from_slice);
+        let number = U64::from(db_row.get::<i64, &str>("number"));
+        let l1_batch_number = db_row
+            .try_get::<i64, &str>("l1_batch_number")
+            .map(U64::from)
+            .ok();
+        let l1_batch_timestamp = db_row
+            .try_get::<i64, &str>("l1_batch_timestamp")
+            .map(U256::from)
+            .ok();
+        let parent_hash = match number.as_u32() {
+            0 => H256::zero(),
+            number => miniblock_hash(MiniblockNumber(number - 1)),
+        };
+        let base_fee_per_gas = db_row.get::<BigDecimal, &str>("base_fee_per_gas");
+        let batch_metadata = if include_batch_metadata {
+            extract_l1_batch_metadata(&db_row)
+        } else {
+            None
+        };
+
+        let block = api::Block {
+            hash,
+            parent_hash,
+            uncles_hash: EMPTY_UNCLES_HASH,
+            number,
+            l1_batch_number,
+            gas_limit: BLOCK_GAS_LIMIT.into(),
+            base_fee_per_gas: bigdecimal_to_u256(base_fee_per_gas),
+            timestamp: db_row.get::<i64, &str>("timestamp").into(),
+            l1_batch_timestamp,
+            ..api::Block::default()
+        };
+        (block, batch_metadata)
+    });
+    if db_row.try_get::<&[u8], &str>("tx_hash").is_ok() {
+        let tx_gas_limit = bigdecimal_to_u256(db_row.get::<BigDecimal, &str>("gas_limit"));
+        let tx_refunded_gas = U256::from((db_row.get::<i64, &str>("refunded_gas")) as u32);
+        if tx_refunded_gas > tx_gas_limit {
+            let tx_hash = H256::from_slice(db_row.get("tx_hash"));
+            vlog::warn!(
+                "Refunded gas {tx_refunded_gas} for transaction {tx_hash:?} exceeds \
+                 its gas limit {tx_gas_limit}; the stored data may be corrupted"
+            );
+        }
+
+        let tx_gas_used = tx_gas_limit.saturating_sub(tx_refunded_gas);
+        block.gas_used = block.gas_used.saturating_add(tx_gas_used);
+        let tx = if include_full_transactions {
+            let tx = extract_web3_transaction(db_row, chain_id);
+            api::TransactionVariant::Full(tx)
+        } else {
+            api::TransactionVariant::Hash(H256::from_slice(db_row.get("tx_hash")))
+        };
+        block.transactions.push(tx);
+    }
+    (block, batch_metadata)
+}
+
+fn extract_l1_batch_metadata(db_row: &PgRow) -> Option<api::L1BatchMetadata> {
+    let number = db_row.get::<Option<i64>, &str>("batch_number")?;
+    let parse_tx_hash = |column: &str| {
+        let hash = db_row.get::<Option<String>, &str>(column)?;
+        Some(H256::from_str(&hash).expect("Incorrect L1 tx hash"))
+    };
+
+    Some(api::L1BatchMetadata {
+        number: L1BatchNumber(number as u32),
+        timestamp: db_row.get::<i64, &str>("l1_batch_timestamp") as u64,
+        commit_tx_hash: parse_tx_hash("commit_tx_hash"),
+        prove_tx_hash: parse_tx_hash("prove_tx_hash"),
+        execute_tx_hash: parse_tx_hash("execute_tx_hash"),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use db_test_macro::db_test;
-    use zksync_types::{block::MiniblockHeader, MiniblockNumber};
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        aggregated_operations::AggregatedActionType,
+        block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
+        fee::TransactionExecutionMetrics,
+        tx::TransactionExecutionResult,
+        Address, MiniblockNumber,
+    };
 
     use super::*;
-    use crate::{tests::create_miniblock_header, ConnectionPool};
+    use crate::{
+        connection::TestPool,
+        tests::{
+            create_miniblock_header, mock_execution_result, mock_l2_transaction,
+            mock_l2_transaction_with_fee,
+        },
+        ConnectionPool,
+    };
+
+    #[test]
+    fn converting_block_number_from_db() {
+        let number: MiniblockNumber = block_number_from_db(42).unwrap();
+        assert_eq!(number, MiniblockNumber(42));
+        let number: L1BatchNumber = block_number_from_db(i64::from(u32::MAX)).unwrap();
+        assert_eq!(number, L1BatchNumber(u32::MAX));
+
+        for overflowing_number in [-1, i64::from(u32::MAX) + 1, i64::MAX] {
+            let err = block_number_from_db::<MiniblockNumber>(overflowing_number).unwrap_err();
+            assert!(
+                matches!(err, BlocksWeb3DalError::BlockNumberOverflow(number) if number == overflowing_number),
+                "{err:?}"
+            );
+        }
+    }
+
+    #[db_test(dal_crate)]
+    async fn min_base_fee_per_gas_is_applied_to_web3_block(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        let header = MiniblockHeader {
+            base_fee_per_gas: 0,
+            ..create_miniblock_header(0)
+        };
+        conn.blocks_dal().insert_miniblock(&header).await;
+
+        let block_id = api::BlockId::Number(api::BlockNumber::Number(0.into()));
+        let block = conn
+            .blocks_web3_dal()
+            .get_block_by_web3_block_id(block_id, false, L2ChainId(270), None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.base_fee_per_gas, U256::zero());
+
+        let min_base_fee_per_gas = U256::from(100_000_000);
+        let block = conn
+            .blocks_web3_dal()
+            .get_block_by_web3_block_id(block_id, false, L2ChainId(270), Some(min_base_fee_per_gas))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.base_fee_per_gas, min_base_fee_per_gas);
+
+        // The stored base fee must not be affected.
+        let stored_header = conn
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(stored_header.base_fee_per_gas, 0);
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_latest_sealed_miniblock(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal().delete_genesis().await;
+        let latest_miniblock = conn.blocks_web3_dal().get_latest_sealed_miniblock().await;
+        assert_eq!(latest_miniblock.unwrap(), None);
+
+        for number in 0..3 {
+            let header = MiniblockHeader {
+                timestamp: 100 + u64::from(number),
+                ..create_miniblock_header(number)
+            };
+            conn.blocks_dal().insert_miniblock(&header).await;
+        }
+
+        let (number, hash, timestamp) = conn
+            .blocks_web3_dal()
+            .get_latest_sealed_miniblock()
+            .await
+            .unwrap()
+            .expect("no sealed miniblock");
+        let sealed_number = conn
+            .blocks_web3_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .unwrap();
+        assert_eq!(number, sealed_number);
+        let header = conn
+            .blocks_dal()
+            .get_miniblock_header(sealed_number)
+            .await
+            .unwrap();
+        assert_eq!(hash, header.hash);
+        let sealed_timestamp = conn
+            .blocks_web3_dal()
+            .get_block_timestamp(sealed_number)
+            .await
+            .unwrap();
+        assert_eq!(Some(timestamp), sealed_timestamp);
+        assert_eq!(timestamp, 102);
+    }
 
     #[db_test(dal_crate)]
     async fn getting_web3_block_and_tx_count(connection_pool: ConnectionPool) {
@@ -382,7 +1444,7 @@ mod tests {
         for block_id in block_ids {
             let block = conn
                 .blocks_web3_dal()
-                .get_block_by_web3_block_id(block_id, false, L2ChainId(270))
+                .get_block_by_web3_block_id(block_id, false, L2ChainId(270), None)
                 .await;
             let block = block.unwrap().unwrap();
             assert!(block.transactions.is_empty());
@@ -401,7 +1463,7 @@ mod tests {
         for block_id in non_existing_block_ids {
             let block = conn
                 .blocks_web3_dal()
-                .get_block_by_web3_block_id(block_id, false, L2ChainId(270))
+                .get_block_by_web3_block_id(block_id, false, L2ChainId(270), None)
                 .await;
             assert!(block.unwrap().is_none());
 
@@ -410,6 +1472,109 @@ mod tests {
         }
     }
 
+    #[db_test(dal_crate)]
+    async fn getting_web3_block_with_excessive_refund(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await;
+
+        let txs = [mock_l2_transaction(), mock_l2_transaction()];
+        let tx_gas_limit = txs[0].common_data.fee.gas_limit.as_u32();
+        let tx_results: Vec<_> = txs
+            .iter()
+            .zip([tx_gas_limit / 4, tx_gas_limit * 2])
+            .map(|(tx, refunded_gas)| TransactionExecutionResult {
+                refunded_gas,
+                ..mock_execution_result(tx.clone())
+            })
+            .collect();
+        for tx in &txs {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+        }
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &tx_results, U256::one())
+            .await;
+
+        let block = conn
+            .blocks_web3_dal()
+            .get_block_by_web3_block_id(
+                api::BlockId::Number(api::BlockNumber::Number(1.into())),
+                false,
+                L2ChainId(270),
+                None,
+            )
+            .await;
+        let block = block.unwrap().unwrap();
+        assert_eq!(block.transactions.len(), 2);
+        // The transaction with the refund exceeding its gas limit contributes nothing.
+        let expected_gas_used = tx_gas_limit - tx_gas_limit / 4;
+        assert_eq!(block.gas_used, expected_gas_used.into());
+    }
+
+    #[db_test(dal_crate)]
+    async fn resolving_block_id_on_lagging_replica(connection_pool: ConnectionPool) {
+        // Separate test pools don't see each other's changes, which allows to emulate
+        // a replica lagging behind the master. Miniblock numbers in pools must not overlap;
+        // otherwise, inserting a miniblock would block on a concurrent uncommitted insertion.
+        let master_pool = ConnectionPool::Test(TestPool::new().await);
+        let mut master = master_pool.access_test_storage().await;
+        for number in 14..=15 {
+            master
+                .blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+        }
+
+        let mut replica = connection_pool.access_test_storage().await;
+        replica
+            .blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 10..=13 {
+            replica
+                .blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+        }
+
+        let latest_block_id = api::BlockId::Number(api::BlockNumber::Latest);
+        let tolerance = ReplicaLagTolerance {
+            max_lag: 2,
+            max_wait: Duration::ZERO,
+        };
+        let number = replica
+            .blocks_web3_dal()
+            .resolve_block_id_on_replica(latest_block_id, &mut master, tolerance)
+            .await;
+        assert_eq!(number.unwrap(), Some(MiniblockNumber(13)));
+
+        let tolerance = ReplicaLagTolerance {
+            max_lag: 1,
+            max_wait: Duration::from_millis(100),
+        };
+        let started_at = Instant::now();
+        let err = replica
+            .blocks_web3_dal()
+            .resolve_block_id_on_replica(latest_block_id, &mut master, tolerance)
+            .await
+            .unwrap_err();
+        assert!(started_at.elapsed() >= tolerance.max_wait);
+        assert!(
+            matches!(
+                err,
+                BlocksWeb3DalError::ReplicaLagging { replica, master }
+                    if replica == MiniblockNumber(13) && master == MiniblockNumber(15)
+            ),
+            "{err:?}"
+        );
+    }
+
     #[db_test(dal_crate)]
     async fn resolving_earliest_block_id(connection_pool: ConnectionPool) {
         let mut conn = connection_pool.access_test_storage().await;
@@ -424,6 +1589,43 @@ mod tests {
         assert_eq!(miniblock_number.unwrap(), Some(MiniblockNumber(0)));
     }
 
+    #[db_test(dal_crate)]
+    async fn resolving_earliest_block_id_after_pruning(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 1..10 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+        }
+        // Emulate pruning of the old miniblocks.
+        sqlx::query("DELETE FROM miniblocks WHERE number < 5")
+            .execute(conn.conn())
+            .await
+            .unwrap();
+
+        let earliest = conn
+            .blocks_web3_dal()
+            .get_earliest_available_miniblock()
+            .await;
+        assert_eq!(earliest.unwrap(), MiniblockNumber(5));
+        let block_id = api::BlockId::Number(api::BlockNumber::Earliest);
+        let miniblock_number = conn.blocks_web3_dal().resolve_block_id(block_id).await;
+        assert_eq!(miniblock_number.unwrap(), Some(MiniblockNumber(5)));
+        let miniblock_numbers = conn.blocks_web3_dal().resolve_block_ids(&[block_id]).await;
+        assert_eq!(miniblock_numbers.unwrap(), [Some(MiniblockNumber(5))]);
+
+        let block = conn
+            .blocks_web3_dal()
+            .get_block_by_web3_block_id(block_id, false, L2ChainId(270), None)
+            .await
+            .unwrap()
+            .expect("no earliest block");
+        assert_eq!(block.number, U64::from(5));
+    }
+
     #[db_test(dal_crate)]
     async fn resolving_latest_block_id(connection_pool: ConnectionPool) {
         let mut conn = connection_pool.access_test_storage().await;
@@ -473,6 +1675,252 @@ mod tests {
         assert_eq!(miniblock_number.unwrap(), Some(MiniblockNumber(1)));
     }
 
+    #[db_test(dal_crate)]
+    async fn assembling_pending_block(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal().delete_genesis().await;
+        let block = conn
+            .blocks_web3_dal()
+            .get_pending_block(
+                false,
+                L2ChainId::default(),
+                10,
+                MempoolOrderingStrategy::Fifo,
+                None,
+            )
+            .await;
+        assert!(block.unwrap().is_none());
+
+        let header = MiniblockHeader {
+            timestamp: 100,
+            ..create_miniblock_header(0)
+        };
+        conn.blocks_dal().insert_miniblock(&header).await;
+        let txs: Vec<_> = (1..=3)
+            .map(|i| {
+                let mut tx = mock_l2_transaction();
+                tx.received_timestamp_ms = i * 1_000;
+                tx
+            })
+            .collect();
+        for tx in &txs {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+        }
+        // Only transactions loaded into the mempool are included into the pending block.
+        let (mempool_txs, _) = conn
+            .transactions_dal()
+            .sync_mempool(vec![], vec![], 0, 0, 1_000, MempoolOrderingStrategy::Fifo)
+            .await;
+        assert_eq!(mempool_txs.len(), 3);
+
+        let block = conn
+            .blocks_web3_dal()
+            .get_pending_block(
+                false,
+                L2ChainId::default(),
+                2,
+                MempoolOrderingStrategy::Fifo,
+                None,
+            )
+            .await
+            .unwrap()
+            .expect("no pending block");
+        assert_eq!(block.number, U64::from(1));
+        assert_eq!(block.hash, H256::zero());
+        assert_eq!(block.parent_hash, miniblock_hash(MiniblockNumber(0)));
+        assert_eq!(block.timestamp, U256::from(100));
+        assert_eq!(block.gas_used, U256::zero());
+        let expected_txs: Vec<_> = txs[..2]
+            .iter()
+            .map(|tx| api::TransactionVariant::Hash(tx.hash()))
+            .collect();
+        assert_eq!(block.transactions, expected_txs);
+
+        let block = conn
+            .blocks_web3_dal()
+            .get_pending_block(
+                true,
+                L2ChainId::default(),
+                10,
+                MempoolOrderingStrategy::Fifo,
+                None,
+            )
+            .await
+            .unwrap()
+            .expect("no pending block");
+        let tx_hashes: Vec<_> = block
+            .transactions
+            .iter()
+            .map(|tx| match tx {
+                api::TransactionVariant::Full(tx) => {
+                    assert_eq!(tx.block_number, None);
+                    tx.hash
+                }
+                api::TransactionVariant::Hash(_) => panic!("unexpected tx hash: {tx:?}"),
+            })
+            .collect();
+        let expected_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+        assert_eq!(tx_hashes, expected_hashes);
+    }
+
+    #[db_test(dal_crate)]
+    async fn assembling_pending_block_with_priority_fee_ordering(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal().delete_genesis().await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(0))
+            .await;
+
+        let (alice, bob) = (H256::repeat_byte(1), H256::repeat_byte(2));
+        // Bob's second transaction has the highest fee, but it must not precede his first, cheap one.
+        let txs = [
+            mock_l2_transaction_with_fee(&bob, 0, 1),
+            mock_l2_transaction_with_fee(&bob, 1, 20),
+            mock_l2_transaction_with_fee(&alice, 0, 10),
+        ];
+        for tx in &txs {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+        }
+        conn.transactions_dal()
+            .sync_mempool(vec![], vec![], 0, 0, 1_000, MempoolOrderingStrategy::Fifo)
+            .await;
+
+        let block = conn
+            .blocks_web3_dal()
+            .get_pending_block(
+                false,
+                L2ChainId::default(),
+                10,
+                MempoolOrderingStrategy::PriorityFee,
+                None,
+            )
+            .await
+            .unwrap()
+            .expect("no pending block");
+        let expected_txs: Vec<_> = [&txs[2], &txs[0], &txs[1]]
+            .into_iter()
+            .map(|tx| api::TransactionVariant::Hash(tx.hash()))
+            .collect();
+        assert_eq!(block.transactions, expected_txs);
+    }
+
+    #[db_test(dal_crate)]
+    async fn resolving_pending_block(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal().delete_genesis().await;
+        let resolved = conn.blocks_web3_dal().resolve_pending_block().await;
+        assert_eq!(resolved.unwrap(), None);
+
+        for number in 0..2 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+        }
+        let first_resolved = conn
+            .blocks_web3_dal()
+            .resolve_pending_block()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            first_resolved,
+            ResolvedPendingBlock {
+                pending: MiniblockNumber(2),
+                latest: MiniblockNumber(1),
+            }
+        );
+        let pending_number = conn
+            .blocks_web3_dal()
+            .resolve_block_id(api::BlockId::Number(api::BlockNumber::Pending))
+            .await
+            .unwrap();
+        assert_eq!(pending_number, Some(first_resolved.pending));
+
+        // Emulate sealing the pending miniblock between resolutions.
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(2))
+            .await;
+        let second_resolved = conn
+            .blocks_web3_dal()
+            .resolve_pending_block()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second_resolved.latest, first_resolved.pending);
+        assert_eq!(second_resolved.pending, MiniblockNumber(3));
+        // The race is detectable by comparing the latest sealed miniblock with the one at resolution time.
+        let sealed_number = conn
+            .blocks_web3_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .unwrap();
+        assert_ne!(sealed_number, first_resolved.latest);
+        assert_eq!(sealed_number, second_resolved.latest);
+    }
+
+    #[db_test(dal_crate)]
+    async fn resolving_multiple_block_ids(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 0..3 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+        }
+
+        let block_ids = [
+            api::BlockId::Hash(miniblock_hash(MiniblockNumber(1))),
+            api::BlockId::Number(api::BlockNumber::Pending),
+            api::BlockId::Number(api::BlockNumber::Number(2.into())),
+            api::BlockId::Number(api::BlockNumber::Latest),
+            api::BlockId::Hash(miniblock_hash(MiniblockNumber(5))),
+            api::BlockId::Number(api::BlockNumber::Number(5.into())),
+            api::BlockId::Number(api::BlockNumber::Earliest),
+            api::BlockId::Number(api::BlockNumber::Number(0.into())),
+            api::BlockId::Hash(miniblock_hash(MiniblockNumber(1))),
+        ];
+        let resolved = conn
+            .blocks_web3_dal()
+            .resolve_block_ids(&block_ids)
+            .await
+            .unwrap();
+        let expected = [
+            Some(1),
+            Some(3),
+            Some(2),
+            Some(2),
+            None,
+            None,
+            Some(0),
+            Some(0),
+            Some(1),
+        ];
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|number| number.map(MiniblockNumber))
+            .collect();
+        assert_eq!(resolved, expected);
+
+        // Check consistency with resolving IDs one by one.
+        for (&block_id, expected) in block_ids.iter().zip(&expected) {
+            let resolved = conn
+                .blocks_web3_dal()
+                .resolve_block_id(block_id)
+                .await
+                .unwrap();
+            assert_eq!(resolved, *expected, "{block_id:?}");
+        }
+
+        let resolved = conn.blocks_web3_dal().resolve_block_ids(&[]).await;
+        assert!(resolved.unwrap().is_empty());
+    }
+
     #[db_test(dal_crate)]
     async fn resolving_block_by_hash(connection_pool: ConnectionPool) {
         let mut conn = connection_pool.access_test_storage().await;
@@ -497,4 +1945,888 @@ mod tests {
             .await;
         assert_eq!(miniblock_number.unwrap(), None);
     }
+
+    #[db_test(dal_crate)]
+    async fn resolving_block_with_corrupted_hash(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        let corrupted_hash = H256::repeat_byte(1);
+        let header = MiniblockHeader {
+            hash: corrupted_hash,
+            ..create_miniblock_header(0)
+        };
+        conn.blocks_dal().insert_miniblock(&header).await;
+
+        // The block is resolved by the stored hash (with a warning logged)...
+        let miniblock_number = conn
+            .blocks_web3_dal()
+            .resolve_block_id(api::BlockId::Hash(corrupted_hash))
+            .await;
+        assert_eq!(miniblock_number.unwrap(), Some(MiniblockNumber(0)));
+
+        // ...but not by the expected one.
+        let hash = miniblock_hash(MiniblockNumber(0));
+        let miniblock_number = conn
+            .blocks_web3_dal()
+            .resolve_block_id(api::BlockId::Hash(hash))
+            .await;
+        assert_eq!(miniblock_number.unwrap(), None);
+    }
+
+    #[db_test(dal_crate)]
+    async fn verifying_miniblock_hashes(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 1..5 {
+            let mut header = create_miniblock_header(number);
+            if number == 3 {
+                header.hash = H256::repeat_byte(1);
+            }
+            conn.blocks_dal().insert_miniblock(&header).await;
+        }
+
+        let corrupted_numbers = conn
+            .blocks_web3_dal()
+            .verify_miniblock_hashes(MiniblockNumber(0), MiniblockNumber(2))
+            .await;
+        assert_eq!(corrupted_numbers.unwrap(), []);
+        let corrupted_numbers = conn
+            .blocks_web3_dal()
+            .verify_miniblock_hashes(MiniblockNumber(0), MiniblockNumber(10))
+            .await;
+        assert_eq!(corrupted_numbers.unwrap(), [MiniblockNumber(3)]);
+    }
+
+    #[db_test(dal_crate)]
+    async fn checking_block_chain_integrity(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 0..5 {
+            let mut header = create_miniblock_header(number);
+            if number == 2 {
+                header.hash = H256::repeat_byte(1);
+            }
+            conn.blocks_dal().insert_miniblock(&header).await;
+        }
+
+        let broken_links = conn
+            .blocks_web3_dal()
+            .check_block_chain_integrity(MiniblockNumber(0), MiniblockNumber(2))
+            .await;
+        assert_eq!(broken_links.unwrap(), []);
+        let broken_links = conn
+            .blocks_web3_dal()
+            .check_block_chain_integrity(MiniblockNumber(0), MiniblockNumber(10))
+            .await;
+        assert_eq!(broken_links.unwrap(), [MiniblockNumber(3)]);
+        // The parent link of the first miniblock in the range must be checked as well.
+        let broken_links = conn
+            .blocks_web3_dal()
+            .check_block_chain_integrity(MiniblockNumber(3), MiniblockNumber(4))
+            .await;
+        assert_eq!(broken_links.unwrap(), [MiniblockNumber(3)]);
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_fee_history(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 0..5 {
+            let header = MiniblockHeader {
+                base_fee_per_gas: 100 + u64::from(number) * 10,
+                ..create_miniblock_header(number)
+            };
+            conn.blocks_dal().insert_miniblock(&header).await;
+        }
+        // Include a single transaction into miniblock #2.
+        let tx = mock_l2_transaction();
+        let tx_gas_limit = tx.common_data.fee.gas_limit.as_u32();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+        let tx_result = TransactionExecutionResult {
+            refunded_gas: tx_gas_limit / 4,
+            ..mock_execution_result(tx)
+        };
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(2), &[tx_result], U256::one())
+            .await;
+
+        let (base_fees, gas_used_ratios) = conn
+            .blocks_web3_dal()
+            .get_base_fee_history(MiniblockNumber(3), 3)
+            .await
+            .unwrap();
+        assert_eq!(
+            base_fees,
+            [U256::from(110), U256::from(120), U256::from(130)]
+        );
+        let expected_ratio =
+            f64::from(tx_gas_limit - tx_gas_limit / 4) / f64::from(BLOCK_GAS_LIMIT);
+        assert_eq!(gas_used_ratios, [0.0, expected_ratio, 0.0]);
+
+        let base_fees = conn
+            .blocks_web3_dal()
+            .get_base_fee_history(MiniblockNumber(3), 2)
+            .await
+            .unwrap()
+            .0;
+        assert_eq!(base_fees, [U256::from(120), U256::from(130)]);
+
+        // Requesting more blocks than available returns the entire history.
+        let base_fees = conn
+            .blocks_web3_dal()
+            .get_base_fee_history(MiniblockNumber(4), 10)
+            .await
+            .unwrap()
+            .0;
+        let expected_base_fees: Vec<_> = (0_u64..5).map(|i| U256::from(100 + i * 10)).collect();
+        assert_eq!(base_fees, expected_base_fees);
+
+        let (base_fees, gas_used_ratios) = conn
+            .blocks_web3_dal()
+            .get_base_fee_history(MiniblockNumber(4), 0)
+            .await
+            .unwrap();
+        assert!(base_fees.is_empty());
+        assert!(gas_used_ratios.is_empty());
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_total_fees_collected(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 0..4 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+        }
+
+        // Miniblock #1 contains a transaction with a regular refund and one with the refund
+        // exceeding its gas limit; miniblock #2 contains a single transaction with no refund.
+        let txs = [
+            mock_l2_transaction(),
+            mock_l2_transaction(),
+            mock_l2_transaction(),
+        ];
+        let tx_gas_limit = txs[0].common_data.fee.gas_limit.as_u64();
+        for tx in &txs {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+        }
+        let tx_results: Vec<_> = txs[..2]
+            .iter()
+            .zip([tx_gas_limit / 4, tx_gas_limit * 2])
+            .map(|(tx, refunded_gas)| TransactionExecutionResult {
+                refunded_gas: refunded_gas as u32,
+                ..mock_execution_result(tx.clone())
+            })
+            .collect();
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &tx_results, 10.into())
+            .await;
+        let tx_result = mock_execution_result(txs[2].clone());
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(2), &[tx_result], 20.into())
+            .await;
+
+        let miniblock1_fees = U256::from(tx_gas_limit - tx_gas_limit / 4) * 10;
+        let miniblock2_fees = U256::from(tx_gas_limit) * 20;
+        let ranges_and_fees = [
+            (1, 1, miniblock1_fees),
+            (2, 3, miniblock2_fees),
+            (0, 3, miniblock1_fees + miniblock2_fees),
+            (3, 3, U256::zero()),
+            // Empty range
+            (2, 1, U256::zero()),
+        ];
+        for (from, to, expected_fees) in ranges_and_fees {
+            let fees = conn
+                .blocks_web3_dal()
+                .get_total_fees_collected(MiniblockNumber(from), MiniblockNumber(to))
+                .await
+                .unwrap();
+            assert_eq!(fees, expected_fees, "range {from}..={to}");
+        }
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_miniblock_number_for_tx(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await;
+
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), Default::default())
+            .await;
+        let miniblock_number = conn
+            .blocks_web3_dal()
+            .get_miniblock_number_for_tx(tx_hash)
+            .await;
+        assert_eq!(miniblock_number.unwrap(), None);
+
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(
+                MiniblockNumber(1),
+                &[mock_execution_result(tx)],
+                1.into(),
+            )
+            .await;
+        let miniblock_number = conn
+            .blocks_web3_dal()
+            .get_miniblock_number_for_tx(tx_hash)
+            .await;
+        assert_eq!(miniblock_number.unwrap(), Some(MiniblockNumber(1)));
+
+        let miniblock_number = conn
+            .blocks_web3_dal()
+            .get_miniblock_number_for_tx(H256::random())
+            .await;
+        assert_eq!(miniblock_number.unwrap(), None);
+    }
+
+    #[db_test(dal_crate)]
+    async fn finding_miniblock_gaps(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 0..=5 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+        }
+
+        let gaps = conn
+            .blocks_web3_dal()
+            .find_miniblock_gaps(MiniblockNumber(0), MiniblockNumber(5))
+            .await
+            .unwrap();
+        assert!(gaps.is_empty(), "{gaps:?}");
+
+        sqlx::query!("DELETE FROM miniblocks WHERE number = $1", 3_i64)
+            .execute(conn.conn())
+            .await
+            .unwrap();
+        let gaps = conn
+            .blocks_web3_dal()
+            .find_miniblock_gaps(MiniblockNumber(0), MiniblockNumber(5))
+            .await
+            .unwrap();
+        assert_eq!(gaps, [(MiniblockNumber(3), MiniblockNumber(3))]);
+
+        // Gaps at the range boundaries are reported as well.
+        let gaps = conn
+            .blocks_web3_dal()
+            .find_miniblock_gaps(MiniblockNumber(3), MiniblockNumber(7))
+            .await
+            .unwrap();
+        assert_eq!(
+            gaps,
+            [
+                (MiniblockNumber(3), MiniblockNumber(3)),
+                (MiniblockNumber(6), MiniblockNumber(7))
+            ]
+        );
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_miniblock_at_or_before_timestamp(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        // Miniblocks #1 and #2 share the same timestamp.
+        let timestamps = [100, 110, 110, 120];
+        for (number, &timestamp) in timestamps.iter().enumerate() {
+            let header = MiniblockHeader {
+                timestamp,
+                ..create_miniblock_header(number as u32)
+            };
+            conn.blocks_dal().insert_miniblock(&header).await;
+        }
+
+        let expected_numbers = [
+            (99, None),
+            (100, Some(0)),
+            (109, Some(0)),
+            (110, Some(2)),
+            (119, Some(2)),
+            (120, Some(3)),
+            (1_000, Some(3)),
+        ];
+        for (timestamp, expected_number) in expected_numbers {
+            let number = conn
+                .blocks_web3_dal()
+                .get_miniblock_at_or_before_timestamp(timestamp)
+                .await
+                .unwrap();
+            assert_eq!(number, expected_number.map(MiniblockNumber), "{timestamp}");
+        }
+    }
+
+    #[db_test(dal_crate)]
+    async fn counting_miniblocks_in_time_window(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        let timestamps = [100, 110, 110, 120, 180];
+        for (number, &timestamp) in timestamps.iter().enumerate() {
+            let header = MiniblockHeader {
+                timestamp,
+                ..create_miniblock_header(number as u32)
+            };
+            conn.blocks_dal().insert_miniblock(&header).await;
+        }
+
+        let expected_counts = [
+            ((0, 99), 0),
+            ((100, 100), 1),
+            ((100, 119), 3),
+            ((110, 180), 4),
+            ((121, 179), 0),
+            ((0, 1_000), 5),
+            ((120, 110), 0),
+        ];
+        for ((from, to), expected_count) in expected_counts {
+            let count = conn
+                .blocks_web3_dal()
+                .count_miniblocks_in_time_window(from, to)
+                .await
+                .unwrap();
+            assert_eq!(count, expected_count, "[{from}, {to}]");
+        }
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_l1_batch_tx_count(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+
+        let mut l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            0,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        l1_batch_header.is_finished = true;
+        conn.blocks_dal()
+            .insert_l1_batch(&l1_batch_header, BlockGasCount::default())
+            .await;
+        // The batch spans two miniblocks with different transaction counts.
+        let miniblock_tx_counts = [(1, 2), (0, 5)];
+        for (number, (l1_tx_count, l2_tx_count)) in (1..).zip(miniblock_tx_counts) {
+            let header = MiniblockHeader {
+                l1_tx_count,
+                l2_tx_count,
+                ..create_miniblock_header(number)
+            };
+            conn.blocks_dal().insert_miniblock(&header).await;
+        }
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await;
+
+        let tx_count = conn
+            .blocks_web3_dal()
+            .get_l1_batch_tx_count(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(tx_count, Some(U256::from(8)));
+
+        let tx_count = conn
+            .blocks_web3_dal()
+            .get_l1_batch_tx_count(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(tx_count, None);
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_block_with_batch_metadata(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+
+        let mut l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        l1_batch_header.is_finished = true;
+        conn.blocks_dal()
+            .insert_l1_batch(&l1_batch_header, BlockGasCount::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await;
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await;
+        // This miniblock isn't included into a sealed L1 batch yet.
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(2))
+            .await;
+
+        let block_id = api::BlockId::Number(api::BlockNumber::Number(1.into()));
+        let (block, batch_metadata) = conn
+            .blocks_web3_dal()
+            .get_block_with_batch_metadata(block_id)
+            .await
+            .unwrap()
+            .expect("no block");
+        assert_eq!(block.number, U64::one());
+        assert_eq!(block.l1_batch_number, Some(U64::one()));
+        let expected_metadata = api::L1BatchMetadata {
+            number: L1BatchNumber(1),
+            timestamp: 100,
+            commit_tx_hash: None,
+            prove_tx_hash: None,
+            execute_tx_hash: None,
+        };
+        assert_eq!(batch_metadata, Some(expected_metadata.clone()));
+
+        // Commit the L1 batch.
+        let eth_tx = conn
+            .eth_sender_dal()
+            .save_eth_tx(
+                0,
+                vec![],
+                None,
+                AggregatedActionType::CommitBlocks,
+                Address::default(),
+                0,
+            )
+            .await;
+        let commit_tx_hash = H256::repeat_byte(1);
+        conn.eth_sender_dal()
+            .insert_tx_history(eth_tx.id, 0, 0, commit_tx_hash, vec![])
+            .await
+            .unwrap();
+        conn.eth_sender_dal()
+            .confirm_tx(commit_tx_hash, U256::zero(), Some(10))
+            .await;
+        conn.blocks_dal()
+            .set_eth_tx_id(
+                L1BatchNumber(1),
+                L1BatchNumber(1),
+                eth_tx.id,
+                AggregatedActionType::CommitBlocks,
+            )
+            .await;
+
+        let (_, batch_metadata) = conn
+            .blocks_web3_dal()
+            .get_block_with_batch_metadata(block_id)
+            .await
+            .unwrap()
+            .expect("no block");
+        let expected_metadata = api::L1BatchMetadata {
+            commit_tx_hash: Some(commit_tx_hash),
+            ..expected_metadata
+        };
+        assert_eq!(batch_metadata, Some(expected_metadata));
+
+        let block_id = api::BlockId::Number(api::BlockNumber::Number(2.into()));
+        let (block, batch_metadata) = conn
+            .blocks_web3_dal()
+            .get_block_with_batch_metadata(block_id)
+            .await
+            .unwrap()
+            .expect("no block");
+        assert_eq!(block.number, U64::from(2));
+        assert_eq!(block.l1_batch_number, None);
+        assert_eq!(batch_metadata, None);
+
+        let block_id = api::BlockId::Number(api::BlockNumber::Number(3.into()));
+        let block = conn
+            .blocks_web3_dal()
+            .get_block_with_batch_metadata(block_id)
+            .await
+            .unwrap();
+        assert!(block.is_none());
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_l1_batch_stage_l1_blocks(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        conn.blocks_dal()
+            .insert_l1_batch(&l1_batch_header, BlockGasCount::default())
+            .await;
+
+        let stage_blocks = conn
+            .blocks_web3_dal()
+            .get_l1_batch_stage_l1_blocks(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let expected_blocks = BatchStageL1Blocks {
+            commit: None,
+            prove: None,
+            execute: None,
+        };
+        assert_eq!(stage_blocks, Some(expected_blocks));
+
+        let stages = [
+            (AggregatedActionType::CommitBlocks, H256::repeat_byte(1)),
+            (
+                AggregatedActionType::PublishProofBlocksOnchain,
+                H256::repeat_byte(2),
+            ),
+        ];
+        for (nonce, (action_type, tx_hash)) in stages.iter().enumerate() {
+            let eth_tx = conn
+                .eth_sender_dal()
+                .save_eth_tx(
+                    nonce as u64,
+                    vec![],
+                    None,
+                    *action_type,
+                    Address::default(),
+                    0,
+                )
+                .await;
+            conn.eth_sender_dal()
+                .insert_tx_history(eth_tx.id, 0, 0, *tx_hash, vec![])
+                .await
+                .unwrap();
+            conn.blocks_dal()
+                .set_eth_tx_id(L1BatchNumber(1), L1BatchNumber(1), eth_tx.id, *action_type)
+                .await;
+        }
+        // Only the commit transaction is mined.
+        conn.eth_sender_dal()
+            .confirm_tx(H256::repeat_byte(1), U256::zero(), Some(10))
+            .await;
+
+        let stage_blocks = conn
+            .blocks_web3_dal()
+            .get_l1_batch_stage_l1_blocks(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let expected_blocks = BatchStageL1Blocks {
+            commit: Some(10),
+            ..expected_blocks
+        };
+        assert_eq!(stage_blocks, Some(expected_blocks));
+
+        conn.eth_sender_dal()
+            .confirm_tx(H256::repeat_byte(2), U256::zero(), Some(12))
+            .await;
+        let stage_blocks = conn
+            .blocks_web3_dal()
+            .get_l1_batch_stage_l1_blocks(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let expected_blocks = BatchStageL1Blocks {
+            prove: Some(12),
+            ..expected_blocks
+        };
+        assert_eq!(stage_blocks, Some(expected_blocks));
+
+        let stage_blocks = conn
+            .blocks_web3_dal()
+            .get_l1_batch_stage_l1_blocks(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(stage_blocks, None);
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_blocks_by_hashes(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 0..3 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+        }
+        let tx = mock_l2_transaction();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(
+                MiniblockNumber(1),
+                &[mock_execution_result(tx.clone())],
+                U256::from(1),
+            )
+            .await;
+
+        let unknown_hash = H256::repeat_byte(0xff);
+        let hashes = [
+            miniblock_hash(MiniblockNumber(2)),
+            unknown_hash,
+            miniblock_hash(MiniblockNumber(0)),
+            miniblock_hash(MiniblockNumber(1)),
+        ];
+        let blocks = conn
+            .blocks_web3_dal()
+            .get_blocks_by_hashes(&hashes, false, L2ChainId(270))
+            .await
+            .unwrap();
+        let block_numbers: Vec<_> = blocks.iter().map(|block| block.number.as_u32()).collect();
+        assert_eq!(block_numbers, [2, 0, 1]);
+        for block in &blocks {
+            assert_eq!(
+                block.hash,
+                miniblock_hash(MiniblockNumber(block.number.as_u32()))
+            );
+        }
+        assert!(blocks[0].transactions.is_empty());
+        assert_eq!(
+            blocks[2].transactions,
+            [api::TransactionVariant::Hash(tx.hash())]
+        );
+
+        let blocks = conn
+            .blocks_web3_dal()
+            .get_blocks_by_hashes(&hashes[..2], true, L2ChainId(270))
+            .await
+            .unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].number, U64::from(2));
+
+        let blocks = conn
+            .blocks_web3_dal()
+            .get_blocks_by_hashes(&[unknown_hash], false, L2ChainId(270))
+            .await
+            .unwrap();
+        assert!(blocks.is_empty());
+    }
+
+    #[db_test(dal_crate)]
+    async fn blocks_web3_dal_errors(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+
+        let err = conn
+            .blocks_web3_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlocksWeb3DalError::BeforeGenesis), "{err:?}");
+        let err = conn
+            .blocks_web3_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlocksWeb3DalError::BeforeGenesis), "{err:?}");
+
+        // Unknown L1 batches have no logs rather than being an error.
+        let logs = conn
+            .blocks_web3_dal()
+            .get_l2_to_l1_logs(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(logs.is_empty(), "{logs:?}");
+
+        let err = conn
+            .blocks_web3_dal()
+            .find_miniblock_gaps(MiniblockNumber(5), MiniblockNumber(3))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, BlocksWeb3DalError::InvalidRange(_)),
+            "{err:?}"
+        );
+
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(0))
+            .await;
+        let number = conn.blocks_web3_dal().get_sealed_miniblock_number().await;
+        assert_eq!(number.unwrap(), MiniblockNumber(0));
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_last_executed_miniblock(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+
+        // L1 batch #N contains miniblocks #(2N - 1) and #2N; miniblock #7 is not sealed into a batch.
+        for batch_number in 1..=3 {
+            let mut l1_batch_header = L1BatchHeader::new(
+                L1BatchNumber(batch_number),
+                100,
+                Address::default(),
+                BaseSystemContractsHashes::default(),
+            );
+            l1_batch_header.is_finished = true;
+            conn.blocks_dal()
+                .insert_l1_batch(&l1_batch_header, BlockGasCount::default())
+                .await;
+            for miniblock_number in [2 * batch_number - 1, 2 * batch_number] {
+                conn.blocks_dal()
+                    .insert_miniblock(&create_miniblock_header(miniblock_number))
+                    .await;
+            }
+            conn.blocks_dal()
+                .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(batch_number))
+                .await;
+        }
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(7))
+            .await;
+
+        let number = conn.blocks_web3_dal().get_last_executed_miniblock().await;
+        assert_eq!(number.unwrap(), None);
+
+        let confirmed_at = sqlx::types::chrono::Utc::now();
+        let eth_txs = [
+            (1, AggregatedActionType::ExecuteBlocks),
+            (2, AggregatedActionType::ExecuteBlocks),
+            (3, AggregatedActionType::CommitBlocks),
+        ];
+        for (batch_number, tx_type) in eth_txs {
+            conn.eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(
+                    L1BatchNumber(batch_number),
+                    tx_type,
+                    H256::from_low_u64_be(batch_number.into()),
+                    confirmed_at,
+                )
+                .await;
+        }
+        // L1 batch #3 is committed, but not executed.
+        let number = conn.blocks_web3_dal().get_last_executed_miniblock().await;
+        assert_eq!(number.unwrap(), Some(MiniblockNumber(4)));
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_block_hashes_after_is_capped_by_limit(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        let headers: Vec<_> = (1..=10).map(create_miniblock_header).collect();
+        conn.blocks_dal().insert_miniblocks(&headers).await;
+        let all_hashes: Vec<_> = headers.iter().map(|header| header.hash).collect();
+
+        let (hashes, last_block_number, is_truncated) = conn
+            .blocks_web3_dal()
+            .get_block_hashes_after(MiniblockNumber(0), 4)
+            .await
+            .unwrap();
+        assert_eq!(hashes, all_hashes[..4]);
+        assert_eq!(last_block_number, Some(MiniblockNumber(4)));
+        assert!(is_truncated);
+
+        // The remaining blocks fit into the limit exactly.
+        let (hashes, last_block_number, is_truncated) = conn
+            .blocks_web3_dal()
+            .get_block_hashes_after(MiniblockNumber(4), 6)
+            .await
+            .unwrap();
+        assert_eq!(hashes, all_hashes[4..]);
+        assert_eq!(last_block_number, Some(MiniblockNumber(10)));
+        assert!(!is_truncated);
+
+        let (hashes, last_block_number, is_truncated) = conn
+            .blocks_web3_dal()
+            .get_block_hashes_after(MiniblockNumber(10), 4)
+            .await
+            .unwrap();
+        assert!(hashes.is_empty());
+        assert_eq!(last_block_number, None);
+        assert!(!is_truncated);
+
+        // The limit must not overflow when converted for the query.
+        let (hashes, last_block_number, is_truncated) = conn
+            .blocks_web3_dal()
+            .get_block_hashes_after(MiniblockNumber(0), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(hashes, all_hashes);
+        assert_eq!(last_block_number, Some(MiniblockNumber(10)));
+        assert!(!is_truncated);
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_miniblock_count_in_l1_batch(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for batch_number in 1..=2 {
+            let l1_batch_header = L1BatchHeader::new(
+                L1BatchNumber(batch_number),
+                0,
+                Address::default(),
+                BaseSystemContractsHashes::default(),
+            );
+            conn.blocks_dal()
+                .insert_l1_batch(&l1_batch_header, BlockGasCount::default())
+                .await;
+        }
+        let headers: Vec<_> = (1..=4).map(create_miniblock_header).collect();
+        conn.blocks_dal().insert_miniblocks(&headers).await;
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await;
+
+        let (first, last) = conn
+            .blocks_web3_dal()
+            .get_miniblock_range_of_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .expect("no miniblocks in L1 batch #1");
+        let count = conn
+            .blocks_web3_dal()
+            .get_miniblock_count_in_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(count, last.0 - first.0 + 1);
+
+        // L1 batch #2 exists, but has no miniblocks; L1 batch #3 doesn't exist.
+        for batch_number in [2, 3] {
+            let count = conn
+                .blocks_web3_dal()
+                .get_miniblock_count_in_l1_batch(L1BatchNumber(batch_number))
+                .await
+                .unwrap();
+            assert_eq!(count, 0);
+        }
+    }
 }
@@ -14,6 +14,14 @@ pub use self::test_pool::TestPool;
 pub mod holder;
 pub mod test_pool;
 
+/// Error returned by [`ConnectionPool::access_storage_with_timeout()`] if a connection
+/// cannot be acquired before the deadline (e.g., because the pool is exhausted).
+#[derive(Debug, thiserror::Error)]
+#[error("timed out after {timeout:?} waiting for a connection from the pool")]
+pub struct PoolTimeout {
+    pub timeout: Duration,
+}
+
 #[derive(Clone, Debug)]
 pub enum ConnectionPool {
     Real(PgPool),
@@ -65,6 +73,21 @@ impl ConnectionPool {
         self.access_storage_inner(Some(requester)).await
     }
 
+    /// A version of `access_storage` that gives up waiting for a connection after `timeout`
+    /// instead of blocking indefinitely if the pool is exhausted.
+    pub async fn access_storage_with_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<StorageProcessor<'_>, PoolTimeout> {
+        let start = Instant::now();
+        let storage = tokio::time::timeout(timeout, self.access_storage_inner(None)).await;
+        metrics::histogram!("dal.pool.acquire_latency", start.elapsed());
+        storage.map_err(|_| {
+            metrics::counter!("dal.pool.timeouts", 1);
+            PoolTimeout { timeout }
+        })
+    }
+
     async fn access_storage_inner(&self, requester: Option<&'static str>) -> StorageProcessor<'_> {
         match self {
             ConnectionPool::Real(real_pool) => {
@@ -113,3 +136,23 @@ impl ConnectionPool {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+
+    use super::*;
+
+    #[db_test(dal_crate)]
+    async fn accessing_storage_with_timeout(pool: ConnectionPool) {
+        const TIMEOUT: Duration = Duration::from_millis(100);
+
+        // The test pool holds a single connection, so it's exhausted as long as `storage` is alive.
+        let storage = pool.access_storage_with_timeout(TIMEOUT).await.unwrap();
+        let err = pool.access_storage_with_timeout(TIMEOUT).await.unwrap_err();
+        assert_eq!(err.timeout, TIMEOUT);
+
+        drop(storage);
+        pool.access_storage_with_timeout(TIMEOUT).await.unwrap();
+    }
+}
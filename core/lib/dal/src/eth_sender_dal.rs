@@ -140,6 +140,7 @@ impl EthSenderDal<'_, '_> {
         &mut self,
         nonce: u64,
         raw_tx: Vec<u8>,
+        blob_data: Option<Vec<u8>>,
         tx_type: AggregatedActionType,
         contract_address: Address,
         predicted_gas_cost: u32,
@@ -148,10 +149,11 @@ impl EthSenderDal<'_, '_> {
             let address = format!("{:#x}", contract_address);
             let eth_tx = sqlx::query_as!(
             StorageEthTx,
-            "INSERT INTO eth_txs (raw_tx, nonce, tx_type, contract_address, predicted_gas_cost, created_at, updated_at)
-               VALUES ($1, $2, $3, $4, $5, now(), now())
+            "INSERT INTO eth_txs (raw_tx, blob_data, nonce, tx_type, contract_address, predicted_gas_cost, created_at, updated_at)
+               VALUES ($1, $2, $3, $4, $5, $6, now(), now())
                RETURNING *",
             raw_tx,
+            blob_data,
             nonce as i64,
             tx_type.to_string(),
             address,
@@ -225,17 +227,25 @@ impl EthSenderDal<'_, '_> {
         }
     }
 
-    pub async fn confirm_tx(&mut self, tx_hash: H256, gas_used: U256) {
+    /// Marks the specified L1 transaction as mined. `confirmed_at_block` is the number
+    /// of the L1 block the transaction was mined in, if known.
+    pub async fn confirm_tx(
+        &mut self,
+        tx_hash: H256,
+        gas_used: U256,
+        confirmed_at_block: Option<u32>,
+    ) {
         {
             let mut transaction = self.storage.start_transaction().await;
             let gas_used = i64::try_from(gas_used).expect("Can't convert U256 to i64");
             let tx_hash = format!("{:#x}", tx_hash);
             let ids = sqlx::query!(
                 "UPDATE eth_txs_history
-                SET updated_at = now(), confirmed_at = now()
+                SET updated_at = now(), confirmed_at = now(), confirmed_at_block = $2
                 WHERE tx_hash = $1
                 RETURNING id, eth_tx_id",
                 tx_hash,
+                confirmed_at_block.map(|number| number as i32),
             )
             .fetch_one(transaction.conn())
             .await
@@ -5,7 +5,7 @@ use sqlx::Row;
 use crate::models::storage_block::web3_block_number_to_sql;
 use zksync_types::{
     api::{GetLogsFilter, Log},
-    MiniblockNumber,
+    Address, MiniblockNumber,
 };
 
 use crate::models::storage_event::StorageWeb3Log;
@@ -57,22 +57,54 @@ impl EventsWeb3Dal<'_, '_> {
             query = query.bind(offset as i32);
             let log = query.fetch_optional(self.storage.conn()).await?;
 
-            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_log_block_number");
+            StorageProcessor::report_request("get_log_block_number", started_at);
 
             Ok(log.map(|row| MiniblockNumber(row.get::<i64, &str>("miniblock_number") as u32)))
         }
     }
 
     /// Returns logs for given filter.
-    #[allow(clippy::type_complexity)]
     pub async fn get_logs(
         &mut self,
         filter: GetLogsFilter,
         limit: usize,
+    ) -> Result<Vec<Log>, SqlxError> {
+        let started_at = Instant::now();
+        let logs = self.query_logs(&filter, None, limit).await?;
+        StorageProcessor::report_request("get_logs", started_at);
+        Ok(logs)
+    }
+
+    /// Returns up to `limit` logs for given filter that are located strictly after the specified
+    /// `(miniblock_number, event_index_in_block)` position. Logs are ordered by their position.
+    async fn get_logs_chunk(
+        &mut self,
+        filter: &GetLogsFilter,
+        after: Option<(MiniblockNumber, u32)>,
+        limit: usize,
+    ) -> Result<Vec<Log>, SqlxError> {
+        let started_at = Instant::now();
+        let logs = self.query_logs(filter, after, limit).await?;
+        StorageProcessor::report_request("get_logs_chunk", started_at);
+        Ok(logs)
+    }
+
+    async fn query_logs(
+        &mut self,
+        filter: &GetLogsFilter,
+        after: Option<(MiniblockNumber, u32)>,
+        limit: usize,
     ) -> Result<Vec<Log>, SqlxError> {
         {
-            let started_at = Instant::now();
-            let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter);
+            let (mut where_sql, mut arg_index) = self.build_get_logs_where_clause(filter);
+            if after.is_some() {
+                where_sql += &format!(
+                    " AND ((miniblock_number, event_index_in_block) > (${}, ${}))",
+                    arg_index,
+                    arg_index + 1
+                );
+                arg_index += 2;
+            }
 
             let query = format!(
                 r#"
@@ -98,20 +130,24 @@ impl EventsWeb3Dal<'_, '_> {
             if !filter.addresses.is_empty() {
                 let addresses: Vec<_> = filter
                     .addresses
-                    .into_iter()
+                    .iter()
                     .map(|address| address.0.to_vec())
                     .collect();
                 query = query.bind(addresses);
             }
-            for (_, topics) in filter.topics {
-                let topics: Vec<_> = topics.into_iter().map(|topic| topic.0.to_vec()).collect();
+            for (_, topics) in &filter.topics {
+                let topics: Vec<_> = topics.iter().map(|topic| topic.0.to_vec()).collect();
                 query = query.bind(topics);
             }
+            if let Some((miniblock_number, event_index)) = after {
+                query = query
+                    .bind(miniblock_number.0 as i64)
+                    .bind(event_index as i32);
+            }
             query = query.bind(limit as i32);
 
             let db_logs: Vec<StorageWeb3Log> = query.fetch_all(self.storage.conn()).await?;
             let logs = db_logs.into_iter().map(Into::into).collect();
-            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_logs");
             Ok(logs)
         }
     }
@@ -170,16 +206,238 @@ impl EventsWeb3Dal<'_, '_> {
             Ok(logs)
         }
     }
+
+    /// Returns distinct addresses of contracts that emitted at least one log in the specified
+    /// miniblock, ordered by address. Can be used to skip miniblocks when filtering logs
+    /// by address. Returns an empty list if the miniblock has no logs (or doesn't exist).
+    pub async fn get_log_addresses_in_block(
+        &mut self,
+        block: MiniblockNumber,
+    ) -> Result<Vec<Address>, SqlxError> {
+        let started_at = Instant::now();
+        let rows = sqlx::query!(
+            "SELECT DISTINCT address FROM events WHERE miniblock_number = $1 ORDER BY address",
+            block.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+        StorageProcessor::report_request("get_log_addresses_in_block", started_at);
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Address::from_slice(&row.address))
+            .collect())
+    }
+}
+
+/// Cursor reading logs matching a filter in chunks of bounded size, so that wide block ranges
+/// can be processed without loading all matching logs into memory.
+///
+/// Logs are ordered by `(miniblock_number, event_index_in_block)`, i.e., in the same order as returned
+/// by [`EventsWeb3Dal::get_logs()`]. Each chunk starts right after the last log of the previous chunk,
+/// so concatenating all chunks yields every matching log exactly once. Chunk boundaries do not
+/// necessarily align with miniblock boundaries.
+#[derive(Debug)]
+pub struct LogsCursor {
+    filter: GetLogsFilter,
+    chunk_size: usize,
+    last_position: Option<(MiniblockNumber, u32)>,
+    is_exhausted: bool,
+}
+
+impl LogsCursor {
+    pub fn new(filter: GetLogsFilter, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "Logs chunk size must be positive");
+        Self {
+            filter,
+            chunk_size,
+            last_position: None,
+            is_exhausted: false,
+        }
+    }
+
+    /// Returns the next chunk of at most `chunk_size` logs, or `None` if all matching logs
+    /// have already been returned.
+    pub async fn next_chunk(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<Option<Vec<Log>>, SqlxError> {
+        if self.is_exhausted {
+            return Ok(None);
+        }
+
+        let logs = storage
+            .events_web3_dal()
+            .get_logs_chunk(&self.filter, self.last_position, self.chunk_size)
+            .await?;
+        self.is_exhausted = logs.len() < self.chunk_size;
+        let Some(last_log) = logs.last() else {
+            return Ok(None);
+        };
+        let block_number = last_log.block_number.expect("log without block number");
+        let log_index = last_log.log_index.expect("log without index");
+        self.last_position = Some((MiniblockNumber(block_number.as_u32()), log_index.as_u32()));
+        Ok(Some(logs))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use db_test_macro::db_test;
     use zksync_types::api::BlockNumber;
-    use zksync_types::{Address, H256};
+    use zksync_types::{tx::IncludedTxLocation, L1BatchNumber, VmEvent, H256};
 
     use super::*;
-    use crate::connection::ConnectionPool;
+    use crate::{connection::ConnectionPool, tests::create_miniblock_header};
+
+    #[db_test(dal_crate)]
+    async fn reading_logs_in_chunks(connection_pool: ConnectionPool) {
+        let storage = &mut connection_pool.access_test_storage().await;
+        storage
+            .events_dal()
+            .rollback_events(MiniblockNumber(0))
+            .await;
+        storage
+            .blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+
+        // Seed 5 miniblocks with 4 events each; events with odd indices have a different address.
+        for number in 1..=5 {
+            storage
+                .blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+            let events: Vec<_> = (0..4_u8)
+                .map(|index| VmEvent {
+                    location: (L1BatchNumber(1), u32::from(index)),
+                    address: Address::repeat_byte(index % 2),
+                    indexed_topics: vec![H256::repeat_byte(index)],
+                    value: vec![index],
+                })
+                .collect();
+            let location = IncludedTxLocation {
+                tx_hash: H256::from_low_u64_be(number.into()),
+                tx_index_in_miniblock: 0,
+                tx_initiator_address: Address::default(),
+            };
+            storage
+                .events_dal()
+                .save_events(
+                    MiniblockNumber(number),
+                    &[(location, events.iter().collect())],
+                )
+                .await;
+        }
+
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(1),
+            to_block: Some(BlockNumber::Number(4.into())),
+            addresses: vec![],
+            topics: vec![],
+        };
+        let all_logs = storage
+            .events_web3_dal()
+            .get_logs(filter.clone(), 100)
+            .await
+            .unwrap();
+        assert_eq!(all_logs.len(), 16);
+
+        let mut cursor = LogsCursor::new(filter, 3);
+        let mut chunks = vec![];
+        while let Some(chunk) = cursor.next_chunk(storage).await.unwrap() {
+            chunks.push(chunk);
+        }
+        let chunk_lengths: Vec<_> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(chunk_lengths, [3, 3, 3, 3, 3, 1]);
+        // The second chunk starts in the middle of miniblock #1 and ends in miniblock #2.
+        let chunk_positions: Vec<_> = chunks[1]
+            .iter()
+            .map(|log| {
+                (
+                    log.block_number.unwrap().as_u32(),
+                    log.log_index.unwrap().as_u32(),
+                )
+            })
+            .collect();
+        assert_eq!(chunk_positions, [(1, 3), (2, 0), (2, 1)]);
+
+        let chunked_logs: Vec<_> = chunks.into_iter().flatten().collect();
+        assert_eq!(chunked_logs, all_logs);
+
+        // Check chunking with a filter by address and a chunk size dividing the number of logs.
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(2),
+            to_block: None,
+            addresses: vec![Address::repeat_byte(1)],
+            topics: vec![],
+        };
+        let mut cursor = LogsCursor::new(filter, 2);
+        let mut chunk_count = 0;
+        while let Some(chunk) = cursor.next_chunk(storage).await.unwrap() {
+            chunk_count += 1;
+            assert_eq!(chunk.len(), 2);
+            for log in &chunk {
+                assert_eq!(log.address, Address::repeat_byte(1));
+            }
+        }
+        assert_eq!(chunk_count, 4);
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_log_addresses_in_block(connection_pool: ConnectionPool) {
+        let storage = &mut connection_pool.access_test_storage().await;
+        storage
+            .events_dal()
+            .rollback_events(MiniblockNumber(0))
+            .await;
+        storage
+            .blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in [1, 2] {
+            storage
+                .blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+        }
+
+        let events: Vec<_> = [2_u8, 1, 2]
+            .iter()
+            .enumerate()
+            .map(|(index, &address_byte)| VmEvent {
+                location: (L1BatchNumber(1), index as u32),
+                address: Address::repeat_byte(address_byte),
+                indexed_topics: vec![],
+                value: vec![],
+            })
+            .collect();
+        let location = IncludedTxLocation {
+            tx_hash: H256::repeat_byte(1),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::default(),
+        };
+        storage
+            .events_dal()
+            .save_events(MiniblockNumber(1), &[(location, events.iter().collect())])
+            .await;
+
+        let addresses = storage
+            .events_web3_dal()
+            .get_log_addresses_in_block(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            addresses,
+            [Address::repeat_byte(1), Address::repeat_byte(2)]
+        );
+        let addresses = storage
+            .events_web3_dal()
+            .get_log_addresses_in_block(MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert!(addresses.is_empty());
+    }
 
     #[db_test(dal_crate)]
     async fn test_build_get_logs_where_clause(connection_pool: ConnectionPool) {
@@ -10,7 +10,7 @@ use zksync_types::{
     U256,
 };
 
-use crate::{SqlxError, StorageProcessor};
+use crate::{blocks_web3_dal::BlocksWeb3DalError, SqlxError, StorageProcessor};
 
 #[derive(Debug)]
 pub struct ExplorerAccountsDal<'a, 'c> {
@@ -98,7 +98,8 @@ impl ExplorerAccountsDal<'_, '_> {
             .storage
             .blocks_web3_dal()
             .resolve_block_id(api::BlockId::Number(api::BlockNumber::Latest))
-            .await?
+            .await
+            .map_err(BlocksWeb3DalError::into_sqlx_error)?
             .unwrap();
         let sealed_nonce = self
             .storage
@@ -111,7 +112,8 @@ impl ExplorerAccountsDal<'_, '_> {
             .storage
             .blocks_web3_dal()
             .resolve_block_id(api::BlockId::Number(api::BlockNumber::Finalized))
-            .await?
+            .await
+            .map_err(BlocksWeb3DalError::into_sqlx_error)?
             .unwrap(); // Safe: we always have at least the genesis miniblock finalized
         let verified_nonce = self
             .storage
@@ -1,14 +1,16 @@
+use std::str::FromStr;
 use std::time::Instant;
 
+use sqlx::types::chrono::{DateTime, Utc};
 use zksync_types::explorer_api::{
-    BlockDetails, BlockPageItem, BlocksQuery, L1BatchDetails, L1BatchPageItem, L1BatchesQuery,
-    PaginationDirection,
+    BatchStageTimestamps, BlockDetails, BlockListItem, BlockPageItem, BlocksQuery, L1BatchDetails,
+    L1BatchPageItem, L1BatchesQuery, L1TxHashes, PaginationDirection,
 };
-use zksync_types::{Address, L1BatchNumber, MiniblockNumber};
+use zksync_types::{Address, L1BatchNumber, MiniblockNumber, H256};
 
 use crate::models::storage_block::{
     block_page_item_from_storage, l1_batch_page_item_from_storage, StorageBlockDetails,
-    StorageL1BatchDetails,
+    StorageBlockListItem, StorageL1BatchDetails,
 };
 use crate::SqlxError;
 use crate::StorageProcessor;
@@ -60,6 +62,31 @@ impl ExplorerBlocksDal<'_, '_> {
         }
     }
 
+    /// Returns summaries of up to `limit` miniblocks with numbers not exceeding `from`,
+    /// ordered by number descending. Unlike [`Self::get_block_details()`], this method
+    /// only touches the `miniblocks` table, so it's cheap enough to be used for list pages.
+    pub async fn get_block_range_details(
+        &mut self,
+        from: MiniblockNumber,
+        limit: usize,
+    ) -> Result<Vec<BlockListItem>, SqlxError> {
+        let started_at = Instant::now();
+        let items = sqlx::query_as!(
+            StorageBlockListItem,
+            "SELECT number, hash, timestamp, l1_tx_count, l2_tx_count, l1_batch_number \
+            FROM miniblocks \
+            WHERE number <= $1 \
+            ORDER BY number DESC \
+            LIMIT $2",
+            from.0 as i64,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+        StorageProcessor::report_request("explorer_get_block_range_details", started_at);
+        Ok(items.into_iter().map(BlockListItem::from).collect())
+    }
+
     pub async fn get_block_details(
         &mut self,
         block_number: MiniblockNumber,
@@ -98,7 +125,7 @@ impl ExplorerBlocksDal<'_, '_> {
             )
             .fetch_optional(self.storage.conn())
             .await?;
-            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "explorer_get_block_details");
+            StorageProcessor::report_request("explorer_get_block_details", started_at);
             Ok(storage_block_details.map(|storage_block_details| {
                 storage_block_details.into_block_details(current_operator_address)
             }))
@@ -180,8 +207,279 @@ impl ExplorerBlocksDal<'_, '_> {
             )
                 .fetch_optional(self.storage.conn())
                 .await?;
-            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "explorer_get_l1_batch_details");
+            StorageProcessor::report_request("explorer_get_l1_batch_details", started_at);
             Ok(l1_batch_details.map(L1BatchDetails::from))
         }
     }
+
+    /// Returns hashes of the confirmed L1 transactions for the L1 batch containing the specified miniblock.
+    /// Returns `None` if the miniblock doesn't exist or isn't included into an L1 batch yet.
+    pub async fn get_l1_tx_hashes_for_miniblock(
+        &mut self,
+        miniblock: MiniblockNumber,
+    ) -> Result<Option<L1TxHashes>, SqlxError> {
+        let started_at = Instant::now();
+        let row = sqlx::query!(
+            r#"
+                SELECT commit_tx.tx_hash as "commit_tx_hash?",
+                    prove_tx.tx_hash as "prove_tx_hash?",
+                    execute_tx.tx_hash as "execute_tx_hash?"
+                FROM miniblocks
+                JOIN l1_batches ON l1_batches.number = miniblocks.l1_batch_number
+                LEFT JOIN eth_txs_history as commit_tx ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id AND commit_tx.confirmed_at IS NOT NULL)
+                LEFT JOIN eth_txs_history as prove_tx ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id AND prove_tx.confirmed_at IS NOT NULL)
+                LEFT JOIN eth_txs_history as execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id AND execute_tx.confirmed_at IS NOT NULL)
+                WHERE miniblocks.number = $1
+            "#,
+            miniblock.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        StorageProcessor::report_request("explorer_get_l1_tx_hashes_for_miniblock", started_at);
+
+        let parse_hash = |hash: Option<String>| {
+            hash.map(|hash| H256::from_str(&hash).expect("Incorrect L1 tx hash"))
+        };
+        Ok(row.map(|row| L1TxHashes {
+            commit: parse_hash(row.commit_tx_hash),
+            prove: parse_hash(row.prove_tx_hash),
+            execute: parse_hash(row.execute_tx_hash),
+        }))
+    }
+
+    /// Returns L1 confirmation timestamps of the commit, prove and execute transactions for the specified
+    /// L1 batch. Returns `None` if the L1 batch doesn't exist.
+    pub async fn get_l1_batch_stage_timestamps(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<BatchStageTimestamps>, SqlxError> {
+        let started_at = Instant::now();
+        let row = sqlx::query!(
+            r#"
+                SELECT commit_tx.confirmed_at as "committed_at?",
+                    prove_tx.confirmed_at as "proven_at?",
+                    execute_tx.confirmed_at as "executed_at?"
+                FROM l1_batches
+                LEFT JOIN eth_txs_history as commit_tx ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id AND commit_tx.confirmed_at IS NOT NULL)
+                LEFT JOIN eth_txs_history as prove_tx ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id AND prove_tx.confirmed_at IS NOT NULL)
+                LEFT JOIN eth_txs_history as execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id AND execute_tx.confirmed_at IS NOT NULL)
+                WHERE l1_batches.number = $1
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        StorageProcessor::report_request("explorer_get_l1_batch_stage_timestamps", started_at);
+
+        let to_utc = |timestamp: Option<_>| timestamp.map(|ts| DateTime::<Utc>::from_utc(ts, Utc));
+        Ok(row.map(|row| BatchStageTimestamps {
+            committed_at: to_utc(row.committed_at),
+            proven_at: to_utc(row.proven_at),
+            executed_at: to_utc(row.executed_at),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use sqlx::types::chrono::{Duration, NaiveDateTime};
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        aggregated_operations::AggregatedActionType,
+        block::{L1BatchHeader, MiniblockHeader},
+    };
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    #[db_test(dal_crate)]
+    async fn getting_block_range_details(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 0..4 {
+            let header = MiniblockHeader {
+                timestamp: u64::from(number),
+                l1_tx_count: 1,
+                l2_tx_count: number as u16,
+                ..create_miniblock_header(number)
+            };
+            conn.blocks_dal().insert_miniblock(&header).await;
+        }
+
+        let items = conn
+            .explorer()
+            .blocks_dal()
+            .get_block_range_details(MiniblockNumber(2), 10)
+            .await
+            .unwrap();
+        let numbers: Vec<_> = items.iter().map(|item| item.number.0).collect();
+        assert_eq!(numbers, [2, 1, 0]);
+        for item in &items {
+            assert_eq!(item.timestamp, u64::from(item.number.0));
+            assert_eq!(item.l1_tx_count, 1);
+            assert_eq!(item.l2_tx_count, item.number.0 as usize);
+            assert_eq!(item.l1_batch_number, None);
+        }
+
+        let items = conn
+            .explorer()
+            .blocks_dal()
+            .get_block_range_details(MiniblockNumber(3), 2)
+            .await
+            .unwrap();
+        let numbers: Vec<_> = items.iter().map(|item| item.number.0).collect();
+        assert_eq!(numbers, [3, 2]);
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_l1_tx_hashes_for_miniblock(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await;
+
+        let hashes = conn
+            .explorer()
+            .blocks_dal()
+            .get_l1_tx_hashes_for_miniblock(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(hashes, None);
+
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            0,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        conn.blocks_dal()
+            .insert_l1_batch(&l1_batch_header, Default::default())
+            .await;
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await;
+        let commit_hash = H256::repeat_byte(1);
+        conn.eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::CommitBlocks,
+                commit_hash,
+                Utc::now(),
+            )
+            .await;
+
+        let hashes = conn
+            .explorer()
+            .blocks_dal()
+            .get_l1_tx_hashes_for_miniblock(MiniblockNumber(1))
+            .await
+            .unwrap();
+        let expected_hashes = L1TxHashes {
+            commit: Some(commit_hash),
+            prove: None,
+            execute: None,
+        };
+        assert_eq!(hashes, Some(expected_hashes));
+
+        let prove_hash = H256::repeat_byte(2);
+        let execute_hash = H256::repeat_byte(3);
+        for (tx_type, hash) in [
+            (AggregatedActionType::PublishProofBlocksOnchain, prove_hash),
+            (AggregatedActionType::ExecuteBlocks, execute_hash),
+        ] {
+            conn.eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(L1BatchNumber(1), tx_type, hash, Utc::now())
+                .await;
+        }
+
+        let hashes = conn
+            .explorer()
+            .blocks_dal()
+            .get_l1_tx_hashes_for_miniblock(MiniblockNumber(1))
+            .await
+            .unwrap();
+        let expected_hashes = L1TxHashes {
+            commit: Some(commit_hash),
+            prove: Some(prove_hash),
+            execute: Some(execute_hash),
+        };
+        assert_eq!(hashes, Some(expected_hashes));
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_l1_batch_stage_timestamps(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let timestamps = conn
+            .explorer()
+            .blocks_dal()
+            .get_l1_batch_stage_timestamps(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(timestamps, None);
+
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            0,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        conn.blocks_dal()
+            .insert_l1_batch(&l1_batch_header, Default::default())
+            .await;
+        let timestamps = conn
+            .explorer()
+            .blocks_dal()
+            .get_l1_batch_stage_timestamps(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .expect("no timestamps for existing L1 batch");
+        assert_eq!(timestamps.committed_at, None);
+        assert_eq!(timestamps.proven_at, None);
+        assert_eq!(timestamps.executed_at, None);
+
+        // Postgres stores timestamps with microsecond precision.
+        let committed_at = DateTime::<Utc>::from_utc(
+            NaiveDateTime::from_timestamp_opt(1_000_000, 0).unwrap(),
+            Utc,
+        );
+        let proven_at = committed_at + Duration::minutes(30);
+        let executed_at = proven_at + Duration::minutes(10);
+        let stages = [
+            (AggregatedActionType::CommitBlocks, committed_at),
+            (AggregatedActionType::PublishProofBlocksOnchain, proven_at),
+            (AggregatedActionType::ExecuteBlocks, executed_at),
+        ];
+        for (i, &(tx_type, confirmed_at)) in stages.iter().enumerate() {
+            conn.eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(
+                    L1BatchNumber(1),
+                    tx_type,
+                    H256::repeat_byte(i as u8 + 1),
+                    confirmed_at,
+                )
+                .await;
+        }
+
+        let timestamps = conn
+            .explorer()
+            .blocks_dal()
+            .get_l1_batch_stage_timestamps(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .expect("no timestamps for existing L1 batch");
+        let expected = BatchStageTimestamps {
+            committed_at: Some(committed_at),
+            proven_at: Some(proven_at),
+            executed_at: Some(executed_at),
+        };
+        assert_eq!(timestamps, expected);
+        assert!(timestamps.committed_at < timestamps.proven_at);
+        assert!(timestamps.proven_at < timestamps.executed_at);
+    }
 }
@@ -336,7 +336,7 @@ impl ExplorerTransactionsDal<'_, '_> {
                     })
                     .collect()
             };
-            metrics::histogram!("dal.request", started_at_stage.elapsed(), "method" => "get_hashes_transfer_from");
+            StorageProcessor::report_request("get_hashes_transfer_from", started_at_stage);
 
             started_at_stage = Instant::now();
             let hashes_transfer_to: Vec<(Vec<u8>, i64, i32)> = {
@@ -366,7 +366,7 @@ impl ExplorerTransactionsDal<'_, '_> {
                     })
                     .collect()
             };
-            metrics::histogram!("dal.request", started_at_stage.elapsed(), "method" => "get_hashes_transfer_to");
+            StorageProcessor::report_request("get_hashes_transfer_to", started_at_stage);
 
             started_at_stage = Instant::now();
             let hashes_initiated: Vec<(Vec<u8>, i64, i32)> = {
@@ -397,7 +397,7 @@ impl ExplorerTransactionsDal<'_, '_> {
                     })
                     .collect()
             };
-            metrics::histogram!("dal.request", started_at_stage.elapsed(), "method" => "get_hashes_initiated");
+            StorageProcessor::report_request("get_hashes_initiated", started_at_stage);
 
             let mut merged: Vec<_> = hashes_transfer_from
                 .into_iter()
@@ -418,7 +418,7 @@ impl ExplorerTransactionsDal<'_, '_> {
                 .take(pagination.limit)
                 .collect();
 
-            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_account_transactions_hashes_page");
+            StorageProcessor::report_request("get_account_transactions_hashes_page", started_at);
 
             Ok((result, total))
         }
@@ -7,7 +7,7 @@ use zksync_types::proofs::{AggregationRound, FriProverJobMetadata, JobCountStati
 use zksync_types::L1BatchNumber;
 
 use crate::time_utils::{duration_to_naive_time, pg_interval_from_duration};
-use crate::StorageProcessor;
+use crate::{SqlxError, StorageProcessor};
 
 #[derive(Debug)]
 pub struct FriProverDal<'a, 'c> {
@@ -37,7 +37,7 @@ impl FriProverDal<'_, '_> {
             )
             .await;
         }
-        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "save_fri_prover_jobs");
+        StorageProcessor::report_request("save_fri_prover_jobs", started_at);
     }
 
     pub async fn get_next_job(&mut self) -> Option<FriProverJobMetadata> {
@@ -75,6 +75,97 @@ impl FriProverDal<'_, '_> {
         result
     }
 
+    /// Atomically leases up to `count` jobs for the specified `aggregation_round` to the worker
+    /// with the specified ID. Besides queued jobs, picks up in-progress jobs with an expired lease,
+    /// unless they were already attempted `max_attempts` times. Jobs locked by concurrent callers
+    /// are skipped, so the same job is never leased twice.
+    pub async fn lease_next_fri_jobs(
+        &mut self,
+        aggregation_round: AggregationRound,
+        worker_id: &str,
+        count: usize,
+        lease_duration: Duration,
+        max_attempts: u32,
+    ) -> Result<Vec<FriProverJobMetadata>, SqlxError> {
+        let started_at = Instant::now();
+        let lease_duration = pg_interval_from_duration(lease_duration);
+        // Attempts are stored as `smallint`, so larger limits are equivalent to `i16::MAX`.
+        let max_attempts = i16::try_from(max_attempts).unwrap_or(i16::MAX);
+        let rows = sqlx::query!(
+            "
+                UPDATE prover_jobs_fri
+                SET status = 'in_progress', attempts = attempts + 1,
+                    updated_at = now(), processing_started_at = now(),
+                    picked_by = $2, lease_expires_at = now() + $4::interval
+                WHERE id IN (
+                    SELECT id
+                    FROM prover_jobs_fri
+                    WHERE aggregation_round = $1
+                    AND (
+                        status = 'queued'
+                        OR (status = 'in_progress' AND lease_expires_at < now() AND attempts < $5)
+                    )
+                    ORDER BY l1_batch_number ASC, id ASC
+                    LIMIT $3
+                    FOR UPDATE
+                    SKIP LOCKED
+                )
+                RETURNING prover_jobs_fri.id, prover_jobs_fri.l1_batch_number, prover_jobs_fri.circuit_id,
+                prover_jobs_fri.aggregation_round, prover_jobs_fri.sequence_number, prover_jobs_fri.depth,
+                prover_jobs_fri.is_node_final_proof
+                ",
+            aggregation_round as i16,
+            worker_id,
+            count as i64,
+            &lease_duration,
+            max_attempts,
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let jobs = rows
+            .into_iter()
+            .map(|row| FriProverJobMetadata {
+                id: row.id as u32,
+                block_number: L1BatchNumber(row.l1_batch_number as u32),
+                circuit_id: row.circuit_id as u8,
+                aggregation_round: AggregationRound::try_from(row.aggregation_round as i32)
+                    .unwrap(),
+                sequence_number: row.sequence_number as usize,
+                depth: row.depth as u16,
+                is_node_final_proof: row.is_node_final_proof,
+            })
+            .collect();
+        StorageProcessor::report_request("lease_next_fri_jobs", started_at);
+        Ok(jobs)
+    }
+
+    /// Extends the lease of a job previously leased by the worker with the specified ID
+    /// using [`Self::lease_next_fri_jobs()`]. Returns `false` if the job is no longer leased
+    /// by the worker (e.g., the lease has expired, and the job was leased by another worker).
+    pub async fn renew_lease(
+        &mut self,
+        id: u32,
+        worker_id: &str,
+        lease_duration: Duration,
+    ) -> Result<bool, SqlxError> {
+        let lease_duration = pg_interval_from_duration(lease_duration);
+        let row = sqlx::query!(
+            "
+                UPDATE prover_jobs_fri
+                SET lease_expires_at = now() + $3::interval, updated_at = now()
+                WHERE id = $1 AND status = 'in_progress' AND picked_by = $2
+                RETURNING id
+                ",
+            id as i64,
+            worker_id,
+            &lease_duration,
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.is_some())
+    }
+
     pub async fn get_next_job_for_circuit_id_round(
         &mut self,
         circuits_to_pick: &[CircuitIdRoundTuple],
@@ -177,10 +268,13 @@ impl FriProverDal<'_, '_> {
                 })
                 .unwrap();
 
-        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "save_fri_proof");
+        StorageProcessor::report_request("save_fri_proof", started_at);
         result
     }
 
+    /// Re-queues failed jobs and jobs stuck in progress for longer than `processing_timeout`,
+    /// unless they were already attempted `max_attempts` times. Returned jobs have the status
+    /// they had before being re-queued.
     pub async fn requeue_stuck_jobs(
         &mut self,
         processing_timeout: Duration,
@@ -189,13 +283,19 @@ impl FriProverDal<'_, '_> {
         let processing_timeout = pg_interval_from_duration(processing_timeout);
         {
             sqlx::query!(
-                "
+                r#"
                 UPDATE prover_jobs_fri
                 SET status = 'queued', attempts = attempts + 1, updated_at = now(), processing_started_at = now()
-                WHERE (status = 'in_progress' AND  processing_started_at <= now() - $1::interval AND attempts < $2)
-                OR (status = 'failed' AND attempts < $2)
-                RETURNING id, status, attempts
-                ",
+                FROM (
+                    SELECT id, status
+                    FROM prover_jobs_fri
+                    WHERE (status = 'in_progress' AND  processing_started_at <= now() - $1::interval AND attempts < $2)
+                    OR (status = 'failed' AND attempts < $2)
+                    FOR UPDATE
+                ) AS stuck
+                WHERE prover_jobs_fri.id = stuck.id
+                RETURNING prover_jobs_fri.id, stuck.status AS "status!", prover_jobs_fri.attempts
+                "#,
                 &processing_timeout,
                 max_attempts as i32,
             )
@@ -295,4 +395,27 @@ impl FriProverDal<'_, '_> {
             .collect()
         }
     }
+
+    /// Returns the age of the oldest job waiting to be picked up by a prover for each aggregation round
+    /// that has such jobs.
+    pub async fn oldest_unpicked_job_age(&mut self) -> HashMap<u8, Duration> {
+        sqlx::query!(
+            r#"
+                SELECT aggregation_round,
+                    EXTRACT(EPOCH FROM (now()::timestamp - MIN(created_at)))::float8 AS "age_seconds!"
+                FROM prover_jobs_fri
+                WHERE status = 'queued'
+                GROUP BY aggregation_round
+            "#
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| {
+            let age = Duration::from_secs_f64(row.age_seconds.max(0.0));
+            (row.aggregation_round as u8, age)
+        })
+        .collect()
+    }
 }
@@ -1,3 +1,4 @@
+use sqlx::types::chrono::NaiveDateTime;
 use sqlx::Row;
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
@@ -250,7 +251,7 @@ impl FriWitnessGeneratorDal<'_, '_> {
             .await
             .unwrap();
 
-            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "create_aggregation_jobs_fri");
+            StorageProcessor::report_request("create_aggregation_jobs_fri", started_at);
         }
     }
 
@@ -709,6 +710,41 @@ impl FriWitnessGeneratorDal<'_, '_> {
         }
     }
 
+    /// Returns the number of witness generation jobs for `aggregation_round` that have successfully
+    /// completed in the half-open interval `[since, until)`, where `until` is the current DB time,
+    /// together with `until`. The returned timestamp should be passed as `since` to the following call
+    /// so that each job is counted exactly once. If `since` is not specified, only `until` is determined,
+    /// and the returned count is always 0.
+    pub async fn get_witness_jobs_completed_since(
+        &mut self,
+        aggregation_round: AggregationRound,
+        since: Option<NaiveDateTime>,
+    ) -> (usize, NaiveDateTime) {
+        let table_name = Self::input_table_name_for(aggregation_round);
+        // `clock_timestamp()` is used instead of `now()` since the latter returns the start time
+        // of the current transaction.
+        let sql = format!(
+            r#"
+                WITH bounds AS (SELECT clock_timestamp()::timestamp AS "until")
+                SELECT COUNT(jobs.status) as "count", bounds.until as "until"
+                FROM bounds
+                LEFT JOIN {} AS jobs
+                    ON jobs.status = 'successful'
+                    AND jobs.updated_at >= COALESCE($1, bounds.until)
+                    AND jobs.updated_at < bounds.until
+                GROUP BY bounds.until
+                "#,
+            table_name
+        );
+        let row = sqlx::query(&sql)
+            .bind(since)
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap();
+        let count: i64 = row.get("count");
+        (count as usize, row.get("until"))
+    }
+
     fn input_table_name_for(aggregation_round: AggregationRound) -> &'static str {
         match aggregation_round {
             AggregationRound::BasicCircuits => "witness_inputs_fri",
@@ -23,4 +23,8 @@ impl CheckHealth for ConnectionPoolHealthCheck {
         let _ = self.connection_pool.access_storage().await;
         CheckHealthStatus::Ready
     }
+
+    fn affects_liveness(&self) -> bool {
+        false
+    }
 }
@@ -1,6 +1,13 @@
 #![allow(clippy::derive_partial_eq_without_eq, clippy::format_push_string)]
 
-use std::env;
+use std::{
+    env,
+    future::Future,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
 
 // Built-in deps
 pub use sqlx::Error as SqlxError;
@@ -10,6 +17,8 @@ use sqlx::pool::PoolConnection;
 pub use sqlx::types::BigDecimal;
 
 // Local imports
+use zksync_config::configs::DBConfig;
+
 use crate::blocks_dal::BlocksDal;
 use crate::blocks_web3_dal::BlocksWeb3Dal;
 pub use crate::connection::ConnectionPool;
@@ -23,6 +32,7 @@ use crate::fri_scheduler_dependency_tracker_dal::FriSchedulerDependencyTrackerDa
 use crate::fri_witness_generator_dal::FriWitnessGeneratorDal;
 use crate::gpu_prover_queue_dal::GpuProverQueueDal;
 use crate::prover_dal::ProverDal;
+use crate::snapshots_dal::SnapshotsDal;
 use crate::storage_dal::StorageDal;
 use crate::storage_logs_dal::StorageLogsDal;
 use crate::storage_logs_dedup_dal::StorageLogsDedupDal;
@@ -50,6 +60,7 @@ pub mod gpu_prover_queue_dal;
 pub mod healthcheck;
 mod models;
 pub mod prover_dal;
+pub mod snapshots_dal;
 pub mod storage_dal;
 pub mod storage_logs_dal;
 pub mod storage_logs_dedup_dal;
@@ -85,6 +96,20 @@ pub fn get_test_database_url() -> String {
     env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must be set")
 }
 
+/// SQLSTATE codes of errors after which a database transaction can be safely retried:
+/// serialization failure and detected deadlock.
+const RETRIABLE_SQLSTATE_CODES: [&str; 2] = ["40001", "40P01"];
+/// Delay before the first retry in [`StorageProcessor::transaction_with_retry()`]; it's doubled
+/// with each subsequent retry.
+const TRANSACTION_RETRY_BASE_BACKOFF: Duration = Duration::from_millis(50);
+
+/// DAL requests taking longer than this threshold are logged.
+static SLOW_QUERY_THRESHOLD: Lazy<Duration> =
+    Lazy::new(|| DBConfig::from_env().slow_query_threshold());
+
+/// Future returned by closures passed to [`StorageProcessor::transaction_with_retry()`].
+pub type TransactionFuture<'t, T> = Pin<Box<dyn Future<Output = Result<T, SqlxError>> + Send + 't>>;
+
 /// Storage processor is the main storage interaction point.
 /// It holds down the connection (either direct or pooled) to the database
 /// and provide methods to obtain different storage schemas.
@@ -117,6 +142,59 @@ impl<'a> StorageProcessor<'a> {
         processor
     }
 
+    /// Runs `f` inside a database transaction, retrying the transaction up to `max_retries` times
+    /// if it fails because of a serialization failure or a deadlock. Other errors are returned
+    /// immediately; if all retries are exhausted, the last error is returned.
+    pub async fn transaction_with_retry<T, F>(
+        &mut self,
+        mut f: F,
+        max_retries: usize,
+    ) -> Result<T, SqlxError>
+    where
+        F: for<'t, 'c> FnMut(&'t mut StorageProcessor<'c>) -> TransactionFuture<'t, T>,
+    {
+        let mut retry = 0;
+        loop {
+            let err = match self.try_transaction(&mut f).await {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+            if retry >= max_retries || !is_retriable_error(&err) {
+                return Err(err);
+            }
+
+            retry += 1;
+            let backoff = TRANSACTION_RETRY_BASE_BACKOFF * (1 << (retry - 1).min(10));
+            vlog::warn!(
+                "Database transaction failed with a retriable error: {err}; \
+                 retrying in {backoff:?} (retry {retry}/{max_retries})"
+            );
+            metrics::increment_counter!("dal.transaction.retries");
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    async fn try_transaction<T, F>(&mut self, f: &mut F) -> Result<T, SqlxError>
+    where
+        F: for<'t, 'c> FnMut(&'t mut StorageProcessor<'c>) -> TransactionFuture<'t, T>,
+    {
+        let transaction = self.conn().begin().await?;
+        let mut processor = StorageProcessor::from_transaction(transaction);
+        let value = f(&mut processor).await?;
+        if let ConnectionHolder::Transaction(transaction) = processor.conn {
+            transaction.commit().await?;
+        }
+        Ok(value)
+    }
+
+    /// Reports the latency of a DAL request labeled with `method`. Requests exceeding
+    /// the slow query threshold (`DATABASE_SLOW_QUERY_THRESHOLD_MS`) are additionally logged.
+    pub(crate) fn report_request(method: &'static str, started_at: Instant) {
+        let elapsed = started_at.elapsed();
+        metrics::histogram!("dal.request", elapsed, "method" => method);
+        log_slow_request(method, elapsed, *SLOW_QUERY_THRESHOLD);
+    }
+
     /// Checks if the `StorageProcessor` is currently within database transaction.
     pub fn in_transaction(&self) -> bool {
         self.in_transaction
@@ -243,9 +321,29 @@ impl<'a> StorageProcessor<'a> {
         SyncDal { storage: self }
     }
 
+    pub fn snapshots_dal(&mut self) -> SnapshotsDal<'_, 'a> {
+        SnapshotsDal { storage: self }
+    }
+
     pub fn fri_scheduler_dependency_tracker_dal(
         &mut self,
     ) -> FriSchedulerDependencyTrackerDal<'_, 'a> {
         FriSchedulerDependencyTrackerDal { storage: self }
     }
 }
+
+/// Logs a DAL request if it took longer than `threshold`. Returns whether the request was logged.
+fn log_slow_request(method: &'static str, elapsed: Duration, threshold: Duration) -> bool {
+    if elapsed <= threshold {
+        return false;
+    }
+    vlog::warn!("Slow DAL request `{method}` took {elapsed:?} (threshold: {threshold:?})");
+    metrics::increment_counter!("dal.request.slow", "method" => method);
+    true
+}
+
+fn is_retriable_error(err: &SqlxError) -> bool {
+    err.as_database_error()
+        .and_then(|err| err.code())
+        .map_or(false, |code| RETRIABLE_SQLSTATE_CODES.contains(&&*code))
+}
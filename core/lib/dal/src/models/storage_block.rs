@@ -12,7 +12,7 @@ use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::api;
 use zksync_types::block::MiniblockHeader;
 use zksync_types::commitment::{BlockMetaParameters, BlockMetadata};
-use zksync_types::explorer_api::{BlockDetails, L1BatchDetails, L1BatchPageItem};
+use zksync_types::explorer_api::{BlockDetails, BlockListItem, L1BatchDetails, L1BatchPageItem};
 use zksync_types::{
     block::L1BatchHeader,
     explorer_api::{BlockPageItem, BlockStatus},
@@ -216,6 +216,31 @@ pub struct StorageBlockPageItem {
     pub timestamp: i64,
 }
 
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorageBlockListItem {
+    pub number: i64,
+    pub hash: Vec<u8>,
+    pub timestamp: i64,
+    pub l1_tx_count: i32,
+    pub l2_tx_count: i32,
+    pub l1_batch_number: Option<i64>,
+}
+
+impl From<StorageBlockListItem> for BlockListItem {
+    fn from(storage: StorageBlockListItem) -> Self {
+        BlockListItem {
+            number: MiniblockNumber(storage.number as u32),
+            hash: H256::from_slice(&storage.hash),
+            timestamp: storage.timestamp as u64,
+            l1_tx_count: storage.l1_tx_count as usize,
+            l2_tx_count: storage.l2_tx_count as usize,
+            l1_batch_number: storage
+                .l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
+        }
+    }
+}
+
 // At the moment it has the same fields as `StorageBlockPageItem`
 // but there are no guarantees it won't change in the future.
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -269,7 +294,8 @@ pub fn l1_batch_page_item_from_storage(
 pub fn web3_block_number_to_sql(block_number: api::BlockNumber) -> String {
     match block_number {
         api::BlockNumber::Number(number) => number.to_string(),
-        api::BlockNumber::Earliest => 0.to_string(),
+        // The earliest miniblock may be non-zero if old miniblocks are pruned.
+        api::BlockNumber::Earliest => "(SELECT MIN(number) as number FROM miniblocks)".to_string(),
         api::BlockNumber::Pending => {
             "(SELECT (MAX(number) + 1) as number FROM miniblocks)".to_string()
         }
@@ -524,7 +550,10 @@ mod tests {
     #[test]
     fn test_web3_block_number_to_sql_earliest() {
         let sql = web3_block_number_to_sql(api::BlockNumber::Earliest);
-        assert_eq!(sql, 0.to_string());
+        assert_eq!(
+            sql,
+            "(SELECT MIN(number) as number FROM miniblocks)".to_string()
+        );
     }
 
     #[test]
@@ -18,6 +18,7 @@ pub struct StorageEthTx {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub sent_at_block: Option<i32>,
+    pub blob_data: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Default)]
@@ -60,6 +61,7 @@ impl From<StorageEthTx> for EthTx {
             contract_address: Address::from_str(&tx.contract_address)
                 .expect("Incorrect address in db"),
             raw_tx: tx.raw_tx.clone(),
+            blob_data: tx.blob_data,
             tx_type: AggregatedActionType::from_str(&tx.tx_type).expect("Wrong agg type"),
             created_at_timestamp: tx.created_at.timestamp() as u64,
             predicted_gas_cost: tx.predicted_gas_cost as u64,
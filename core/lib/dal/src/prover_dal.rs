@@ -143,7 +143,7 @@ impl ProverDal<'_, '_> {
                 .await
                 .unwrap();
 
-                metrics::histogram!("dal.request", started_at.elapsed(), "method" => "save_witness");
+                StorageProcessor::report_request("save_witness", started_at);
             }
         }
     }
@@ -172,7 +172,7 @@ impl ProverDal<'_, '_> {
                 .await
                 .unwrap();
 
-            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "save_proof");
+            StorageProcessor::report_request("save_proof", started_at);
         }
     }
 
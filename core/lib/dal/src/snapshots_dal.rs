@@ -0,0 +1,86 @@
+use zksync_types::{snapshots::SnapshotMetadata, L1BatchNumber};
+
+use crate::StorageProcessor;
+
+/// DAL for the manifests of state snapshots exported to the object store.
+#[derive(Debug)]
+pub struct SnapshotsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl SnapshotsDal<'_, '_> {
+    pub async fn add_snapshot(&mut self, metadata: &SnapshotMetadata) {
+        sqlx::query!(
+            "INSERT INTO snapshots \
+                (l1_batch_number, storage_logs_count, storage_logs_url, created_at) \
+            VALUES ($1, $2, $3, now())",
+            metadata.l1_batch_number.0 as i64,
+            metadata.storage_logs_count as i64,
+            metadata.storage_logs_url
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns the manifest of the snapshot for the latest L1 batch, or `None` if no snapshots
+    /// were exported yet.
+    pub async fn get_latest_snapshot(&mut self) -> Option<SnapshotMetadata> {
+        let row = sqlx::query!(
+            "SELECT l1_batch_number, storage_logs_count, storage_logs_url FROM snapshots \
+            ORDER BY l1_batch_number DESC LIMIT 1"
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?;
+
+        Some(SnapshotMetadata {
+            l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            storage_logs_count: row.storage_logs_count as u64,
+            storage_logs_url: row.storage_logs_url,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        Address,
+    };
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[db_test(dal_crate)]
+    async fn adding_and_getting_snapshots(pool: ConnectionPool) {
+        let mut conn = pool.access_test_storage().await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+        assert_eq!(conn.snapshots_dal().get_latest_snapshot().await, None);
+
+        for number in [1, 2] {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100 + u64::from(number),
+                Address::default(),
+                BaseSystemContractsHashes::default(),
+            );
+            conn.blocks_dal()
+                .insert_l1_batch(&header, BlockGasCount::default())
+                .await;
+            let metadata = SnapshotMetadata {
+                l1_batch_number: L1BatchNumber(number),
+                storage_logs_count: 10 * u64::from(number),
+                storage_logs_url: format!("snapshot_{number}.bin"),
+            };
+            conn.snapshots_dal().add_snapshot(&metadata).await;
+        }
+
+        let latest_snapshot = conn.snapshots_dal().get_latest_snapshot().await.unwrap();
+        assert_eq!(latest_snapshot.l1_batch_number, L1BatchNumber(2));
+        assert_eq!(latest_snapshot.storage_logs_count, 20);
+        assert_eq!(latest_snapshot.storage_logs_url, "snapshot_2.bin");
+    }
+}
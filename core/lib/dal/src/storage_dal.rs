@@ -110,6 +110,23 @@ impl StorageDal<'_, '_> {
         .collect()
     }
 
+    /// Returns raw bytecodes for factory deps with the specified `hashes`. Hashes not present
+    /// in the database are omitted from the returned map.
+    pub async fn get_factory_dep_bytecodes(&mut self, hashes: &[H256]) -> HashMap<H256, Vec<u8>> {
+        let hashes_as_bytes: Vec<_> = hashes.iter().map(H256::as_bytes).collect();
+
+        sqlx::query!(
+            "SELECT bytecode, bytecode_hash FROM factory_deps WHERE bytecode_hash = ANY($1)",
+            &hashes_as_bytes as &[&[u8]],
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
+        .collect()
+    }
+
     /// Returns bytecode hashes for factory deps from miniblocks with number strictly greater
     /// than `block_number`.
     pub async fn get_factory_deps_for_revert(
@@ -128,6 +145,24 @@ impl StorageDal<'_, '_> {
         .collect()
     }
 
+    /// Returns bytecodes of all factory deps from miniblocks with number less than or equal
+    /// to `block_number`, keyed by the bytecode hash.
+    pub async fn get_factory_deps_up_to_miniblock(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> HashMap<H256, Vec<u8>> {
+        sqlx::query!(
+            "SELECT bytecode_hash, bytecode FROM factory_deps WHERE miniblock_number <= $1",
+            block_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
+        .collect()
+    }
+
     /// Applies the specified storage logs for a miniblock. Returns the map of unique storage updates.
     // We likely don't need `storage` table at all, as we have `storage_logs` table
     pub async fn apply_storage_logs(
@@ -194,7 +229,7 @@ impl StorageDal<'_, '_> {
         .unwrap()
         .map(|row| H256::from_slice(&row.value));
 
-        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_by_key");
+        StorageProcessor::report_request("get_by_key", started_at);
         result
     }
 
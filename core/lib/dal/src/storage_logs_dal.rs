@@ -2,7 +2,7 @@ use sqlx::types::chrono::Utc;
 
 use std::{collections::HashMap, time::Instant};
 
-use crate::StorageProcessor;
+use crate::{SqlxError, StorageProcessor};
 use zksync_types::{
     get_code_key, AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey, StorageLog,
     FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256,
@@ -186,6 +186,19 @@ impl StorageLogsDal<'_, '_> {
         .unwrap();
     }
 
+    /// Returns the number of storage logs that would be deleted by [`Self::rollback_storage_logs()`].
+    pub async fn get_storage_logs_count_after(&mut self, block_number: MiniblockNumber) -> usize {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) as \"count!\" FROM storage_logs WHERE miniblock_number > $1",
+            block_number.0 as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .unwrap()
+        .count;
+        count as usize
+    }
+
     pub async fn is_contract_deployed_at_address(&mut self, address: Address) -> bool {
         let hashed_key = get_code_key(&address).hashed_key();
         let row = sqlx::query!(
@@ -236,6 +249,38 @@ impl StorageLogsDal<'_, '_> {
         touched_slots.collect()
     }
 
+    /// Returns storage writes applied in the specified inclusive range of miniblocks, ordered by the hashed key.
+    ///
+    /// The writes are de-duplicated with last-write-wins semantics: if a key is written to several times
+    /// in the range, only the final write (i.e., the one with the greatest miniblock number and, within
+    /// the miniblock, the greatest operation number) is returned. Thus, applying the returned logs
+    /// to the storage state before `from` yields the storage state after `to`.
+    pub async fn get_storage_logs_for_range(
+        &mut self,
+        from: MiniblockNumber,
+        to: MiniblockNumber,
+    ) -> Result<Vec<StorageLog>, SqlxError> {
+        let rows = sqlx::query!(
+            "SELECT DISTINCT ON (hashed_key) address, key, value \
+            FROM storage_logs \
+            WHERE miniblock_number BETWEEN $1 AND $2 \
+            ORDER BY hashed_key, miniblock_number DESC, operation_number DESC",
+            from.0 as i64,
+            to.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let logs = rows.into_iter().map(|row| {
+            let key = StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            );
+            StorageLog::new_write_log(key, H256::from_slice(&row.value))
+        });
+        Ok(logs.collect())
+    }
+
     /// Returns (hashed) storage keys and the corresponding values that need to be applied to a storage
     /// in order to revert it to the specified L1 batch. Deduplication is taken into account.
     pub async fn get_storage_logs_for_revert(
@@ -396,14 +441,13 @@ impl StorageLogsDal<'_, '_> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{tests::create_miniblock_header, ConnectionPool};
+    use crate::{
+        tests::{create_miniblock_header, write_log_to_query},
+        ConnectionPool,
+    };
     use db_test_macro::db_test;
     use zksync_contracts::BaseSystemContractsHashes;
-    use zksync_types::{
-        block::{BlockGasCount, L1BatchHeader},
-        zk_evm::aux_structures::{LogQuery, Timestamp},
-        U256,
-    };
+    use zksync_types::block::{BlockGasCount, L1BatchHeader};
 
     async fn insert_miniblock(conn: &mut StorageProcessor<'_>, number: u32, logs: Vec<StorageLog>) {
         let mut header = L1BatchHeader::new(
@@ -473,6 +517,51 @@ mod tests {
         test_rollback(&mut conn, first_key, second_key).await;
     }
 
+    #[db_test(dal_crate)]
+    async fn getting_storage_logs_for_range(pool: ConnectionPool) {
+        let mut conn = pool.access_storage().await;
+
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let first_key = StorageKey::new(account, H256::zero());
+        let second_key = StorageKey::new(account, H256::from_low_u64_be(1));
+        let third_key = StorageKey::new(account, H256::from_low_u64_be(2));
+        let first_log = StorageLog::new_write_log(first_key, H256::repeat_byte(1));
+        let second_log = StorageLog::new_write_log(second_key, H256::repeat_byte(2));
+        insert_miniblock(&mut conn, 1, vec![first_log, second_log]).await;
+        let overwriting_log = StorageLog::new_write_log(first_key, H256::repeat_byte(3));
+        insert_miniblock(&mut conn, 2, vec![overwriting_log]).await;
+        let third_log = StorageLog::new_write_log(third_key, H256::repeat_byte(4));
+        insert_miniblock(&mut conn, 3, vec![third_log]).await;
+
+        let mut expected_logs = vec![overwriting_log, second_log];
+        expected_logs.sort_unstable_by_key(|log| log.key.hashed_key());
+        let logs = conn
+            .storage_logs_dal()
+            .get_storage_logs_for_range(MiniblockNumber(1), MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(logs, expected_logs);
+
+        let logs = conn
+            .storage_logs_dal()
+            .get_storage_logs_for_range(MiniblockNumber(3), MiniblockNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(logs, [third_log]);
+
+        let logs = conn
+            .storage_logs_dal()
+            .get_storage_logs_for_range(MiniblockNumber(4), MiniblockNumber(10))
+            .await
+            .unwrap();
+        assert!(logs.is_empty());
+    }
+
     async fn test_rollback(
         conn: &mut StorageProcessor<'_>,
         key: StorageKey,
@@ -563,22 +652,6 @@ mod tests {
         }
     }
 
-    fn write_log_to_query(log: &StorageLog) -> LogQuery {
-        LogQuery {
-            timestamp: Timestamp(0),
-            tx_number_in_block: 0,
-            aux_byte: 0,
-            shard_id: 0,
-            address: *log.key.address(),
-            key: U256::from_big_endian(log.key.key().as_bytes()),
-            read_value: U256::zero(),
-            written_value: U256::from_big_endian(log.value.as_bytes()),
-            rw_flag: true,
-            rollback: false,
-            is_service: false,
-        }
-    }
-
     #[db_test(dal_crate)]
     async fn reverting_keys_without_initial_write(pool: ConnectionPool) {
         let mut conn = pool.access_storage().await;
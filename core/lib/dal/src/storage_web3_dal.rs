@@ -42,6 +42,20 @@ impl StorageWeb3Dal<'_, '_> {
         Ok(h256_to_u256(balance))
     }
 
+    /// Returns the value of the storage slot as of the end of the `at_block` miniblock, i.e.,
+    /// the last value written to `key` in a miniblock at or before `at_block`. If the slot was never
+    /// written to, returns zero.
+    ///
+    /// The block is not checked to exist; callers should resolve it beforehand
+    /// (e.g., via `BlocksWeb3Dal::resolve_block_id()`).
+    pub async fn get_historical_storage_value(
+        &mut self,
+        key: StorageKey,
+        at_block: MiniblockNumber,
+    ) -> Result<H256, SqlxError> {
+        self.get_historical_value_unchecked(&key, at_block).await
+    }
+
     /// This method does not check if a block with this number exists in the database.
     /// It will return the current value if the block is in the future.
     pub async fn get_historical_value_unchecked(
@@ -72,7 +86,7 @@ impl StorageWeb3Dal<'_, '_> {
                     .map(|row| H256::from_slice(&row.value))
                     .unwrap_or_else(H256::zero)
             });
-            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_historical_value_unchecked");
+            StorageProcessor::report_request("get_historical_value_unchecked", started_at);
 
             result
         }
@@ -112,11 +126,7 @@ impl StorageWeb3Dal<'_, '_> {
         .await?;
 
         let l1_batch_number = row.map(|record| L1BatchNumber(record.l1_batch_number as u32));
-        metrics::histogram!(
-            "dal.request",
-            started_at.elapsed(),
-            "method" => "get_l1_batch_number_for_initial_write"
-        );
+        StorageProcessor::report_request("get_l1_batch_number_for_initial_write", started_at);
         Ok(l1_batch_number)
     }
 
@@ -172,3 +182,50 @@ impl StorageWeb3Dal<'_, '_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use zksync_types::StorageLog;
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    #[db_test(dal_crate)]
+    async fn getting_historical_storage_value(pool: ConnectionPool) {
+        let mut conn = pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+
+        let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+        for (number, value) in [(1, H256::repeat_byte(1)), (3, H256::repeat_byte(3))] {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+            let logs = [(H256::zero(), vec![StorageLog::new_write_log(key, value)])];
+            conn.storage_logs_dal()
+                .insert_storage_logs(MiniblockNumber(number), &logs)
+                .await;
+        }
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(2))
+            .await;
+
+        let mut dal = conn.storage_web3_dal();
+        let expected_values = [
+            (0, H256::zero()),
+            (1, H256::repeat_byte(1)),
+            (2, H256::repeat_byte(1)),
+            (3, H256::repeat_byte(3)),
+            (4, H256::repeat_byte(3)),
+        ];
+        for (number, expected_value) in expected_values {
+            let value = dal
+                .get_historical_storage_value(key, MiniblockNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(value, expected_value, "miniblock #{number}");
+        }
+    }
+}
@@ -74,7 +74,7 @@ impl SyncDal<'_, '_> {
             None
         };
 
-        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "sync_dal_sync_block");
+        StorageProcessor::report_request("sync_dal_sync_block", started_at);
         Ok(res)
     }
 }
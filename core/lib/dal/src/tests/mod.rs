@@ -1,7 +1,9 @@
 use std::fs;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use db_test_macro::db_test;
+use sqlx::{types::chrono::NaiveDateTime, Row};
+use zksync_config::configs::chain::MempoolOrderingStrategy;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
@@ -10,19 +12,25 @@ use zksync_types::{
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
     l2::L2Tx,
     proofs::AggregationRound,
-    tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
+    tx::{
+        tx_execution_info::TxExecutionStatus, ExecutionMetrics, IncludedTxLocation,
+        TransactionExecutionResult,
+    },
+    zk_evm::aux_structures::{LogQuery, Timestamp},
     Address, Execute, L1BatchNumber, L1BlockNumber, L1TxCommonData, L2ChainId, MiniblockNumber,
-    PriorityOpId, H160, H256, MAX_GAS_PER_PUBDATA_BYTE, U256,
+    PriorityOpId, StorageLog, VmEvent, H160, H256, MAX_GAS_PER_PUBDATA_BYTE, U256,
 };
 use zksync_utils::miniblock_hash;
 
 use crate::blocks_dal::BlocksDal;
 use crate::connection::ConnectionPool;
+use crate::events_dal::EventsDal;
 use crate::prover_dal::{GetProverJobsParams, ProverDal};
 use crate::transactions_dal::L2TxSubmissionResult;
 use crate::transactions_dal::TransactionsDal;
 use crate::transactions_web3_dal::TransactionsWeb3Dal;
 use crate::witness_generator_dal::WitnessGeneratorDal;
+use crate::{log_slow_request, SqlxError, StorageProcessor};
 
 const DEFAULT_GAS_PER_PUBDATA: u32 = 100;
 
@@ -44,6 +52,22 @@ pub(crate) fn create_miniblock_header(number: u32) -> MiniblockHeader {
     }
 }
 
+pub(crate) fn write_log_to_query(log: &StorageLog) -> LogQuery {
+    LogQuery {
+        timestamp: Timestamp(0),
+        tx_number_in_block: 0,
+        aux_byte: 0,
+        shard_id: 0,
+        address: *log.key.address(),
+        key: U256::from_big_endian(log.key.key().as_bytes()),
+        read_value: U256::zero(),
+        written_value: U256::from_big_endian(log.value.as_bytes()),
+        rw_flag: true,
+        rollback: false,
+        is_service: false,
+    }
+}
+
 pub(crate) fn mock_l2_transaction() -> L2Tx {
     let fee = Fee {
         gas_limit: U256::from(1_000_000u32),
@@ -196,7 +220,7 @@ async fn remove_stuck_txs(connection_pool: ConnectionPool) {
     // Get all txs
     transactions_dal.reset_mempool().await;
     let txs = transactions_dal
-        .sync_mempool(vec![], vec![], 0, 0, 1000)
+        .sync_mempool(vec![], vec![], 0, 0, 1000, MempoolOrderingStrategy::Fifo)
         .await
         .0;
     assert_eq!(txs.len(), 4);
@@ -218,7 +242,7 @@ async fn remove_stuck_txs(connection_pool: ConnectionPool) {
     // Get all txs
     transactions_dal.reset_mempool().await;
     let txs = transactions_dal
-        .sync_mempool(vec![], vec![], 0, 0, 1000)
+        .sync_mempool(vec![], vec![], 0, 0, 1000, MempoolOrderingStrategy::Fifo)
         .await
         .0;
     assert_eq!(txs.len(), 3);
@@ -230,7 +254,7 @@ async fn remove_stuck_txs(connection_pool: ConnectionPool) {
     assert_eq!(removed_txs, 1);
     transactions_dal.reset_mempool().await;
     let txs = transactions_dal
-        .sync_mempool(vec![], vec![], 0, 0, 1000)
+        .sync_mempool(vec![], vec![], 0, 0, 1000, MempoolOrderingStrategy::Fifo)
         .await
         .0;
     assert_eq!(txs.len(), 2);
@@ -245,6 +269,164 @@ async fn remove_stuck_txs(connection_pool: ConnectionPool) {
         .unwrap();
 }
 
+pub(crate) fn mock_l2_transaction_with_fee(
+    private_key: &H256,
+    nonce: u32,
+    priority_fee: u64,
+) -> L2Tx {
+    let fee = Fee {
+        gas_limit: U256::from(1_000_000u32),
+        max_fee_per_gas: U256::from(250_000_000u32),
+        max_priority_fee_per_gas: U256::from(priority_fee),
+        gas_per_pubdata_limit: U256::from(DEFAULT_GAS_PER_PUBDATA),
+    };
+    let mut l2_tx = L2Tx::new_signed(
+        Address::random(),
+        vec![],
+        zksync_types::Nonce(nonce),
+        fee,
+        Default::default(),
+        L2ChainId(270),
+        private_key,
+        None,
+        Default::default(),
+    )
+    .unwrap();
+
+    l2_tx.set_input(H256::random().0.to_vec(), H256::random());
+    l2_tx
+}
+
+#[db_test(dal_crate)]
+async fn mempool_sync_with_priority_fee_ordering(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut transactions_dal = TransactionsDal { storage };
+
+    let (alice, bob, carol) = (
+        H256::repeat_byte(1),
+        H256::repeat_byte(2),
+        H256::repeat_byte(3),
+    );
+    // (account, nonce, priority fee); Bob's second transaction has the highest fee,
+    // but it must not be selected before his first, cheap one.
+    let txs = [
+        (bob, 0, 1),
+        (bob, 1, 20),
+        (carol, 0, 5),
+        (alice, 0, 10),
+        (alice, 1, 10),
+    ];
+    let txs: Vec<_> = txs
+        .iter()
+        .map(|&(key, nonce, fee)| mock_l2_transaction_with_fee(&key, nonce, fee))
+        .collect();
+    for tx in &txs {
+        transactions_dal
+            .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics())
+            .await;
+    }
+
+    let selected = transactions_dal
+        .sync_mempool(
+            vec![],
+            vec![],
+            0,
+            0,
+            3,
+            MempoolOrderingStrategy::PriorityFee,
+        )
+        .await
+        .0;
+    let mut selected: Vec<_> = selected.iter().map(|tx| tx.hash()).collect();
+    selected.sort_unstable();
+    let mut expected = vec![txs[3].hash(), txs[4].hash(), txs[2].hash()];
+    expected.sort_unstable();
+    assert_eq!(selected, expected);
+
+    let selected = transactions_dal
+        .sync_mempool(
+            vec![],
+            vec![],
+            0,
+            0,
+            1,
+            MempoolOrderingStrategy::PriorityFee,
+        )
+        .await
+        .0;
+    assert_eq!(selected.len(), 1);
+    assert_eq!(selected[0].hash(), txs[0].hash());
+
+    // With FIFO ordering, the remaining transaction is selected regardless of its fee.
+    let selected = transactions_dal
+        .sync_mempool(vec![], vec![], 0, 0, 10, MempoolOrderingStrategy::Fifo)
+        .await
+        .0;
+    assert_eq!(selected.len(), 1);
+    assert_eq!(selected[0].hash(), txs[1].hash());
+}
+
+#[db_test(dal_crate)]
+async fn getting_pending_priority_txs(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    BlocksDal { storage }
+        .delete_miniblocks(MiniblockNumber(0))
+        .await;
+    let mut transactions_dal = TransactionsDal { storage };
+    assert!(transactions_dal
+        .get_pending_priority_txs(10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let txs: Vec<_> = (0..4)
+        .map(|serial_id| {
+            let mut tx = mock_l1_execute();
+            tx.common_data.serial_id = PriorityOpId(serial_id);
+            tx.common_data.canonical_tx_hash = H256::from_low_u64_be(serial_id + 1);
+            tx.common_data.layer_2_tip_fee = U256::from(serial_id);
+            tx
+        })
+        .collect();
+    // Insert transactions out of order to check that the output is sorted.
+    for tx in txs.iter().rev() {
+        transactions_dal
+            .insert_transaction_l1(tx.clone(), L1BlockNumber(1))
+            .await;
+    }
+
+    let storage = transactions_dal.storage;
+    BlocksDal { storage }
+        .insert_miniblock(&create_miniblock_header(1))
+        .await;
+    let included_tx = txs[1].clone();
+    let tx_result = TransactionExecutionResult {
+        hash: included_tx.common_data.canonical_tx_hash,
+        transaction: included_tx.into(),
+        execution_info: ExecutionMetrics::default(),
+        execution_status: TxExecutionStatus::Success,
+        refunded_gas: 0,
+        operator_suggested_refund: 0,
+        compressed_bytecodes: vec![],
+        call_traces: vec![],
+        revert_reason: None,
+    };
+    let mut transactions_dal = TransactionsDal { storage };
+    transactions_dal
+        .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], U256::from(1))
+        .await;
+
+    let pending_txs = transactions_dal.get_pending_priority_txs(10).await.unwrap();
+    let expected_txs: Vec<_> = [&txs[0], &txs[2], &txs[3]]
+        .iter()
+        .map(|tx| tx.common_data.onchain_data())
+        .collect();
+    assert_eq!(pending_txs, expected_txs);
+
+    let pending_txs = transactions_dal.get_pending_priority_txs(2).await.unwrap();
+    assert_eq!(pending_txs, expected_txs[..2]);
+}
+
 fn create_circuits() -> Vec<(&'static str, String)> {
     vec![
         ("Main VM", "1_0_Main VM_BasicCircuits.bin".to_owned()),
@@ -260,6 +442,121 @@ fn create_circuits() -> Vec<(&'static str, String)> {
     ]
 }
 
+#[db_test(dal_crate)]
+async fn finding_duplicate_tx_hashes(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    BlocksDal { storage }
+        .delete_miniblocks(MiniblockNumber(0))
+        .await;
+    for number in 1..=3 {
+        BlocksDal { storage }
+            .insert_miniblock(&create_miniblock_header(number))
+            .await;
+    }
+
+    let mut transactions_dal = TransactionsDal { storage };
+    let txs = [mock_l2_transaction(), mock_l2_transaction()];
+    for (i, tx) in txs.iter().enumerate() {
+        transactions_dal
+            .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics())
+            .await;
+        transactions_dal
+            .mark_txs_as_executed_in_miniblock(
+                MiniblockNumber(i as u32 + 1),
+                &[mock_execution_result(tx.clone())],
+                U256::one(),
+            )
+            .await;
+    }
+    let duplicates = transactions_dal
+        .find_duplicate_tx_hashes(MiniblockNumber(0), MiniblockNumber(3))
+        .await
+        .unwrap();
+    assert!(duplicates.is_empty(), "{duplicates:?}");
+
+    // Attribute an event of the first transaction to miniblock #3.
+    let duplicate_hash = txs[0].hash();
+    let tx_location = IncludedTxLocation {
+        tx_hash: duplicate_hash,
+        tx_index_in_miniblock: 0,
+        tx_initiator_address: Address::default(),
+    };
+    let event = VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: Address::repeat_byte(1),
+        indexed_topics: vec![],
+        value: vec![],
+    };
+    EventsDal { storage }
+        .save_events(MiniblockNumber(3), &[(tx_location, vec![&event])])
+        .await;
+
+    let duplicates = TransactionsDal { storage }
+        .find_duplicate_tx_hashes(MiniblockNumber(0), MiniblockNumber(3))
+        .await
+        .unwrap();
+    assert_eq!(
+        duplicates,
+        [(duplicate_hash, vec![MiniblockNumber(1), MiniblockNumber(3)])]
+    );
+    // The duplicate is not detected if the range doesn't cover both miniblocks.
+    let duplicates = TransactionsDal { storage }
+        .find_duplicate_tx_hashes(MiniblockNumber(0), MiniblockNumber(2))
+        .await
+        .unwrap();
+    assert!(duplicates.is_empty(), "{duplicates:?}");
+}
+
+#[db_test(dal_crate)]
+async fn marking_txs_included_in_bulk(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    BlocksDal { storage }
+        .delete_miniblocks(MiniblockNumber(0))
+        .await;
+    BlocksDal { storage }
+        .insert_miniblock(&create_miniblock_header(1))
+        .await;
+
+    let txs: Vec<_> = (0..4).map(|_| mock_l2_transaction()).collect();
+    let mut transactions_dal = TransactionsDal { storage };
+    for tx in &txs {
+        transactions_dal
+            .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics())
+            .await;
+    }
+    // Leave the last transaction pending.
+    let included_hashes: Vec<_> = txs[..3].iter().map(L2Tx::hash).collect();
+    transactions_dal
+        .mark_txs_included(&included_hashes, MiniblockNumber(1), &[2, 0, 1])
+        .await;
+
+    let mut locations = vec![];
+    for tx in &txs {
+        let row = sqlx::query(
+            "SELECT miniblock_number, index_in_block, in_mempool FROM transactions WHERE hash = $1",
+        )
+        .bind(tx.hash().as_bytes())
+        .fetch_one(storage.conn())
+        .await
+        .unwrap();
+        let miniblock_number: Option<i64> = row.get("miniblock_number");
+        let index_in_block: Option<i32> = row.get("index_in_block");
+        locations.push((miniblock_number, index_in_block));
+        if miniblock_number.is_some() {
+            assert!(!row.get::<bool, _>("in_mempool"));
+        }
+    }
+    assert_eq!(
+        locations,
+        [
+            (Some(1), Some(2)),
+            (Some(1), Some(0)),
+            (Some(1), Some(1)),
+            (None, None)
+        ]
+    );
+}
+
 #[db_test(dal_crate)]
 async fn test_duplicate_insert_prover_jobs(connection_pool: ConnectionPool) {
     let storage = &mut connection_pool.access_test_storage().await;
@@ -350,6 +647,364 @@ async fn test_requeue_prover_jobs(connection_pool: ConnectionPool) {
     }
 }
 
+#[db_test(dal_crate)]
+async fn getting_oldest_unpicked_fri_prover_job_age(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    for number in 1..=3 {
+        let header = L1BatchHeader::new(
+            L1BatchNumber(number),
+            0,
+            Default::default(),
+            Default::default(),
+        );
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&header, Default::default())
+            .await;
+    }
+
+    let jobs = [
+        (1, AggregationRound::BasicCircuits, 300),
+        (2, AggregationRound::BasicCircuits, 100),
+        (3, AggregationRound::BasicCircuits, 10),
+        (1, AggregationRound::LeafAggregation, 50),
+    ];
+    for (l1_batch_number, aggregation_round, age_seconds) in jobs {
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        storage
+            .fri_prover_jobs_dal()
+            .insert_prover_jobs(
+                l1_batch_number,
+                vec![(1, "circuit".to_owned())],
+                aggregation_round,
+                0,
+            )
+            .await;
+        sqlx::query(
+            "UPDATE prover_jobs_fri SET created_at = now() - make_interval(secs => $1) \
+             WHERE l1_batch_number = $2 AND aggregation_round = $3",
+        )
+        .bind(age_seconds as f64)
+        .bind(l1_batch_number.0 as i64)
+        .bind(aggregation_round as i16)
+        .execute(storage.conn())
+        .await
+        .unwrap();
+    }
+    // Pick the oldest basic circuits job; it should not be taken into account anymore.
+    let job = storage.fri_prover_jobs_dal().get_next_job().await.unwrap();
+    assert_eq!(job.block_number, L1BatchNumber(1));
+    assert_eq!(job.aggregation_round, AggregationRound::LeafAggregation);
+    let job = storage.fri_prover_jobs_dal().get_next_job().await.unwrap();
+    assert_eq!(job.block_number, L1BatchNumber(1));
+    assert_eq!(job.aggregation_round, AggregationRound::BasicCircuits);
+
+    let ages = storage
+        .fri_prover_jobs_dal()
+        .oldest_unpicked_job_age()
+        .await;
+    assert_eq!(ages.len(), 1, "{ages:?}");
+    let age = ages[&(AggregationRound::BasicCircuits as u8)];
+    assert!(
+        age >= Duration::from_secs(100) && age < Duration::from_secs(150),
+        "{age:?}"
+    );
+}
+
+#[db_test(dal_crate)]
+async fn requeuing_stuck_in_progress_fri_jobs(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let header = L1BatchHeader::new(L1BatchNumber(1), 0, Default::default(), Default::default());
+    storage
+        .blocks_dal()
+        .insert_l1_batch(&header, Default::default())
+        .await;
+    storage
+        .fri_prover_jobs_dal()
+        .insert_prover_jobs(
+            L1BatchNumber(1),
+            vec![(1, "circuit_1".to_owned()), (2, "circuit_2".to_owned())],
+            AggregationRound::BasicCircuits,
+            0,
+        )
+        .await;
+
+    let stale_job = storage.fri_prover_jobs_dal().get_next_job().await.unwrap();
+    let fresh_job = storage.fri_prover_jobs_dal().get_next_job().await.unwrap();
+    sqlx::query(
+        "UPDATE prover_jobs_fri SET processing_started_at = now() - interval '1 hour' WHERE id = $1",
+    )
+    .bind(stale_job.id as i64)
+    .execute(storage.conn())
+    .await
+    .unwrap();
+
+    let requeued_jobs = storage
+        .fri_prover_jobs_dal()
+        .requeue_stuck_jobs(Duration::from_secs(600), 3)
+        .await;
+    assert_eq!(requeued_jobs.len(), 1, "{requeued_jobs:?}");
+    assert_eq!(requeued_jobs[0].id, u64::from(stale_job.id));
+    assert_eq!(requeued_jobs[0].status, "in_progress");
+    assert_eq!(requeued_jobs[0].attempts, 2);
+
+    // The stale job should be picked up again, while the fresh one should stay in progress.
+    let job = storage.fri_prover_jobs_dal().get_next_job().await.unwrap();
+    assert_eq!(job.id, stale_job.id);
+    assert_ne!(job.id, fresh_job.id);
+    assert!(storage.fri_prover_jobs_dal().get_next_job().await.is_none());
+
+    // Once the job runs out of attempts, it's no longer re-queued.
+    sqlx::query(
+        "UPDATE prover_jobs_fri SET processing_started_at = now() - interval '1 hour' WHERE id = $1",
+    )
+    .bind(stale_job.id as i64)
+    .execute(storage.conn())
+    .await
+    .unwrap();
+    let requeued_jobs = storage
+        .fri_prover_jobs_dal()
+        .requeue_stuck_jobs(Duration::from_secs(600), 3)
+        .await;
+    assert!(requeued_jobs.is_empty(), "{requeued_jobs:?}");
+}
+
+/// Unlike other tests, this one needs several connections that see each other's changes, so it uses
+/// a real connection pool and commits its data. To not interfere with tests running concurrently,
+/// it uses an L1 batch number far beyond ones used elsewhere and cleans up after itself.
+#[tokio::test]
+async fn leasing_fri_jobs_concurrently() {
+    const L1_BATCH_NUMBER: L1BatchNumber = L1BatchNumber(1_000_000);
+    const LEASE_DURATION: Duration = Duration::from_secs(600);
+    const MAX_ATTEMPTS: u32 = 2;
+
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&crate::get_test_database_url())
+        .await
+        .unwrap();
+    let connection_pool = ConnectionPool::Real(pool);
+    let mut storage = connection_pool.access_storage().await;
+    remove_l1_batch(&mut storage, L1_BATCH_NUMBER).await;
+    let header = L1BatchHeader::new(L1_BATCH_NUMBER, 0, Default::default(), Default::default());
+    storage
+        .blocks_dal()
+        .insert_l1_batch(&header, Default::default())
+        .await;
+    let circuits = (1..=5).map(|id| (id, format!("circuit_{id}"))).collect();
+    storage
+        .fri_prover_jobs_dal()
+        .insert_prover_jobs(
+            L1_BATCH_NUMBER,
+            circuits,
+            AggregationRound::BasicCircuits,
+            0,
+        )
+        .await;
+
+    // Lease jobs in a transaction that is kept open while the second worker leases jobs
+    // over another connection, so that the first worker's jobs remain locked.
+    let mut other_storage = connection_pool.access_storage().await;
+    let mut transaction = storage.start_transaction().await;
+    let first_jobs = transaction
+        .fri_prover_jobs_dal()
+        .lease_next_fri_jobs(
+            AggregationRound::BasicCircuits,
+            "worker_1",
+            3,
+            LEASE_DURATION,
+            MAX_ATTEMPTS,
+        )
+        .await
+        .unwrap();
+    let second_jobs = other_storage
+        .fri_prover_jobs_dal()
+        .lease_next_fri_jobs(
+            AggregationRound::BasicCircuits,
+            "worker_2",
+            3,
+            LEASE_DURATION,
+            MAX_ATTEMPTS,
+        )
+        .await
+        .unwrap();
+    transaction.commit().await;
+
+    assert_eq!(first_jobs.len(), 3, "{first_jobs:?}");
+    assert_eq!(second_jobs.len(), 2, "{second_jobs:?}");
+    let mut job_ids: Vec<_> = first_jobs
+        .iter()
+        .chain(&second_jobs)
+        .map(|job| job.id)
+        .collect();
+    job_ids.sort_unstable();
+    job_ids.dedup();
+    assert_eq!(job_ids.len(), 5, "{first_jobs:?} {second_jobs:?}");
+
+    let jobs = storage
+        .fri_prover_jobs_dal()
+        .lease_next_fri_jobs(
+            AggregationRound::BasicCircuits,
+            "worker_3",
+            3,
+            LEASE_DURATION,
+            MAX_ATTEMPTS,
+        )
+        .await
+        .unwrap();
+    assert!(jobs.is_empty(), "{jobs:?}");
+
+    // Only the worker holding the lease can renew it.
+    let first_job_id = first_jobs[0].id;
+    let renewed = storage
+        .fri_prover_jobs_dal()
+        .renew_lease(first_job_id, "worker_1", LEASE_DURATION)
+        .await
+        .unwrap();
+    assert!(renewed);
+    let renewed = storage
+        .fri_prover_jobs_dal()
+        .renew_lease(first_job_id, "worker_2", LEASE_DURATION)
+        .await
+        .unwrap();
+    assert!(!renewed);
+
+    // Expired leases are picked up again until jobs run out of attempts.
+    expire_fri_job_leases(&mut storage, L1_BATCH_NUMBER).await;
+    let jobs = storage
+        .fri_prover_jobs_dal()
+        .lease_next_fri_jobs(
+            AggregationRound::BasicCircuits,
+            "worker_3",
+            5,
+            LEASE_DURATION,
+            MAX_ATTEMPTS,
+        )
+        .await
+        .unwrap();
+    assert_eq!(jobs.len(), 5, "{jobs:?}");
+    let renewed = storage
+        .fri_prover_jobs_dal()
+        .renew_lease(first_job_id, "worker_1", LEASE_DURATION)
+        .await
+        .unwrap();
+    assert!(!renewed);
+
+    expire_fri_job_leases(&mut storage, L1_BATCH_NUMBER).await;
+    let jobs = storage
+        .fri_prover_jobs_dal()
+        .lease_next_fri_jobs(
+            AggregationRound::BasicCircuits,
+            "worker_4",
+            5,
+            LEASE_DURATION,
+            MAX_ATTEMPTS,
+        )
+        .await
+        .unwrap();
+    assert!(jobs.is_empty(), "{jobs:?}");
+
+    let picked_by: Vec<String> =
+        sqlx::query("SELECT DISTINCT picked_by FROM prover_jobs_fri WHERE l1_batch_number = $1")
+            .bind(L1_BATCH_NUMBER.0 as i64)
+            .fetch_all(storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.get("picked_by"))
+            .collect();
+    assert_eq!(picked_by, ["worker_3"]);
+
+    remove_l1_batch(&mut storage, L1_BATCH_NUMBER).await;
+}
+
+async fn expire_fri_job_leases(storage: &mut StorageProcessor<'_>, l1_batch_number: L1BatchNumber) {
+    sqlx::query(
+        "UPDATE prover_jobs_fri SET lease_expires_at = now() - interval '1 second' \
+         WHERE l1_batch_number = $1",
+    )
+    .bind(l1_batch_number.0 as i64)
+    .execute(storage.conn())
+    .await
+    .unwrap();
+}
+
+/// Removes an L1 batch together with FRI prover jobs for it (the latter are removed by cascading).
+async fn remove_l1_batch(storage: &mut StorageProcessor<'_>, l1_batch_number: L1BatchNumber) {
+    sqlx::query("DELETE FROM l1_batches WHERE number = $1")
+        .bind(l1_batch_number.0 as i64)
+        .execute(storage.conn())
+        .await
+        .unwrap();
+}
+
+#[db_test(dal_crate)]
+async fn getting_completed_fri_witness_jobs_per_round(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut dal = storage.fri_witness_generator_dal();
+    for number in 1..=3 {
+        dal.save_witness_inputs(L1BatchNumber(number), "witness_inputs")
+            .await;
+    }
+    let closed_form_inputs = vec![
+        (1, "closed_form_1".to_owned(), 1),
+        (2, "closed_form_2".to_owned(), 1),
+    ];
+    dal.create_aggregation_jobs(L1BatchNumber(1), &closed_form_inputs, "scheduler", |id| id)
+        .await;
+
+    dal.mark_witness_job_as_successful(L1BatchNumber(1), Duration::from_secs(1))
+        .await;
+    dal.mark_witness_job_as_successful(L1BatchNumber(2), Duration::from_secs(1))
+        .await;
+    dal.mark_scheduler_job_as_successful(L1BatchNumber(1), Duration::from_secs(1))
+        .await;
+    let leaf_job_id: i64 = sqlx::query(
+        "SELECT id FROM leaf_aggregation_witness_jobs_fri WHERE l1_batch_number = 1 AND circuit_id = 1",
+    )
+    .fetch_one(dal.storage.conn())
+    .await
+    .unwrap()
+    .get("id");
+    dal.mark_leaf_aggregation_as_successful(leaf_job_id as u32, Duration::from_secs(1))
+        .await;
+    // Emulate a basic circuits job completed a long time ago.
+    sqlx::query(
+        "UPDATE witness_inputs_fri SET updated_at = now() - interval '1 hour' WHERE l1_batch_number = 2",
+    )
+    .execute(dal.storage.conn())
+    .await
+    .unwrap();
+
+    let since: NaiveDateTime =
+        sqlx::query("SELECT (now() - interval '10 minutes')::timestamp AS since")
+            .fetch_one(dal.storage.conn())
+            .await
+            .unwrap()
+            .get("since");
+    let expected_counts = [
+        (AggregationRound::BasicCircuits, 1),
+        (AggregationRound::LeafAggregation, 1),
+        (AggregationRound::NodeAggregation, 0),
+        (AggregationRound::Scheduler, 1),
+    ];
+    for (round, expected_count) in expected_counts {
+        let (count, until) = dal
+            .get_witness_jobs_completed_since(round, Some(since))
+            .await;
+        assert_eq!(count, expected_count, "{round:?}");
+        // The interval is half-open, so jobs must not be counted again in the following call.
+        let (count, next_until) = dal
+            .get_witness_jobs_completed_since(round, Some(until))
+            .await;
+        assert_eq!(count, 0, "{round:?}");
+        assert!(next_until >= until);
+
+        let (count, _) = dal.get_witness_jobs_completed_since(round, None).await;
+        assert_eq!(count, 0, "{round:?}");
+    }
+}
+
 #[db_test(dal_crate)]
 async fn test_move_leaf_aggregation_jobs_from_waiting_to_queued(connection_pool: ConnectionPool) {
     let storage = &mut connection_pool.access_test_storage().await;
@@ -545,6 +1200,72 @@ async fn test_move_scheduler_jobs_from_waiting_to_queued(connection_pool: Connec
     assert_eq!(l1_batch_number, job.unwrap().block_number);
 }
 
+/// Fails the current database transaction with a serialization failure.
+async fn inject_serialization_failure(storage: &mut StorageProcessor<'_>) -> Result<(), SqlxError> {
+    sqlx::query(
+        "DO $$ BEGIN RAISE EXCEPTION 'injected failure' USING ERRCODE = 'serialization_failure'; END $$",
+    )
+    .execute(storage.conn())
+    .await?;
+    Ok(())
+}
+
+#[db_test(dal_crate)]
+async fn transaction_is_retried_after_serialization_failure(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    storage
+        .blocks_dal()
+        .delete_miniblocks(MiniblockNumber(0))
+        .await;
+
+    let mut attempts = 0;
+    let result = storage
+        .transaction_with_retry(
+            |storage| {
+                attempts += 1;
+                let should_fail = attempts == 1;
+                Box::pin(async move {
+                    // Changes made by the failed attempt must be rolled back; otherwise,
+                    // the second attempt would fail on inserting a duplicate miniblock.
+                    storage
+                        .blocks_dal()
+                        .insert_miniblock(&create_miniblock_header(1))
+                        .await;
+                    if should_fail {
+                        inject_serialization_failure(storage).await?;
+                    }
+                    Ok(())
+                })
+            },
+            3,
+        )
+        .await;
+    result.unwrap();
+    assert_eq!(attempts, 2);
+    let (hashes, ..) = storage
+        .blocks_web3_dal()
+        .get_block_hashes_after(MiniblockNumber(0), 10)
+        .await
+        .unwrap();
+    assert_eq!(hashes, [miniblock_hash(MiniblockNumber(1))]);
+
+    // If retries are exhausted, the last error is returned.
+    let mut attempts = 0;
+    let err = storage
+        .transaction_with_retry(
+            |storage| {
+                attempts += 1;
+                Box::pin(inject_serialization_failure(storage))
+            },
+            2,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(attempts, 3);
+    let err_code = err.as_database_error().and_then(|err| err.code());
+    assert_eq!(err_code.as_deref(), Some("40001"));
+}
+
 fn get_default_prover_jobs_params(l1_batch_number: L1BatchNumber) -> GetProverJobsParams {
     GetProverJobsParams {
         statuses: None,
@@ -563,3 +1284,19 @@ fn get_sample_proof() -> Vec<u8> {
     fs::read(format!("{}/etc/prover-test-data/proof.bin", zksync_home))
         .expect("Failed reading test proof file")
 }
+
+#[db_test(dal_crate)]
+async fn slow_requests_are_logged(connection_pool: ConnectionPool) {
+    let mut storage = connection_pool.access_test_storage().await;
+    let started_at = Instant::now();
+    sqlx::query("SELECT pg_sleep(0.1)")
+        .execute(storage.conn())
+        .await
+        .unwrap();
+    let elapsed = started_at.elapsed();
+
+    assert!(log_slow_request("test", elapsed, Duration::from_millis(50)));
+    assert!(!log_slow_request("test", elapsed, Duration::from_secs(60)));
+    // Check that the public reporting hook doesn't panic.
+    StorageProcessor::report_request("test", started_at);
+}
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::fmt::{self, Debug};
 use std::iter::FromIterator;
 use std::time::{Duration, Instant};
+use zksync_config::configs::chain::MempoolOrderingStrategy;
 use zksync_types::fee::TransactionExecutionMetrics;
 
 use itertools::Itertools;
@@ -13,15 +14,15 @@ use zksync_types::tx::tx_execution_info::TxExecutionStatus;
 use zksync_types::vm_trace::Call;
 use zksync_types::{get_nonce_key, U256};
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, tx::TransactionExecutionResult, vm_trace::VmExecutionTrace, Address,
-    ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, Nonce, PriorityOpId,
-    Transaction, H256,
+    l1::L1Tx, l2::L2Tx, priority_op_onchain_data::PriorityOpOnchainData,
+    tx::TransactionExecutionResult, vm_trace::VmExecutionTrace, Address, ExecuteTransactionCommon,
+    L1BatchNumber, L1BlockNumber, MiniblockNumber, Nonce, PriorityOpId, Transaction, H256,
 };
-use zksync_utils::{h256_to_u32, u256_to_big_decimal};
+use zksync_utils::{bigdecimal_to_u256, h256_to_u32, u256_to_big_decimal};
 
 use crate::models::storage_transaction::{CallTrace, StorageTransaction};
 use crate::time_utils::pg_interval_from_duration;
-use crate::StorageProcessor;
+use crate::{SqlxError, StorageProcessor};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum L2TxSubmissionResult {
@@ -326,6 +327,43 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Marks transactions with the specified hashes as included into `miniblock_number`, setting
+    /// their indices in the miniblock to `indices`. All transactions are updated with a single statement,
+    /// so the update is atomic even if the storage processor is not in a transaction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tx_hashes` and `indices` have different lengths.
+    pub async fn mark_txs_included(
+        &mut self,
+        tx_hashes: &[H256],
+        miniblock_number: MiniblockNumber,
+        indices: &[u16],
+    ) {
+        assert_eq!(
+            tx_hashes.len(),
+            indices.len(),
+            "Mismatch between number of transaction hashes and indices"
+        );
+        let hashes: Vec<_> = tx_hashes.iter().map(H256::as_bytes).collect();
+        let indices: Vec<_> = indices.iter().copied().map(i32::from).collect();
+        sqlx::query!(
+            "UPDATE transactions \
+             SET miniblock_number = $1, index_in_block = data_table.index_in_block, \
+                 in_mempool = FALSE, updated_at = now() \
+             FROM ( \
+                 SELECT UNNEST($2::bytea[]) AS hash, UNNEST($3::integer[]) AS index_in_block \
+             ) AS data_table \
+             WHERE transactions.hash = data_table.hash",
+            miniblock_number.0 as i64,
+            &hashes as &[&[u8]],
+            &indices
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
     pub async fn mark_txs_as_executed_in_miniblock(
         &mut self,
         miniblock_number: MiniblockNumber,
@@ -334,8 +372,10 @@ impl TransactionsDal<'_, '_> {
     ) {
         {
             let mut transaction = self.storage.start_transaction().await;
+            let mut included_hashes = Vec::with_capacity(transactions.len());
+            let mut included_indices = Vec::with_capacity(transactions.len());
+
             let mut l1_hashes = Vec::with_capacity(transactions.len());
-            let mut l1_indices_in_block = Vec::with_capacity(transactions.len());
             let mut l1_errors = Vec::with_capacity(transactions.len());
             let mut l1_execution_infos = Vec::with_capacity(transactions.len());
             let mut l1_refunded_gas = Vec::with_capacity(transactions.len());
@@ -346,7 +386,6 @@ impl TransactionsDal<'_, '_> {
             let mut l2_contract_addresses = Vec::with_capacity(transactions.len());
             let mut l2_paymaster = Vec::with_capacity(transactions.len());
             let mut l2_paymaster_input = Vec::with_capacity(transactions.len());
-            let mut l2_indices_in_block = Vec::with_capacity(transactions.len());
             let mut l2_initiators = Vec::with_capacity(transactions.len());
             let mut l2_nonces = Vec::with_capacity(transactions.len());
             let mut l2_signatures = Vec::with_capacity(transactions.len());
@@ -386,6 +425,11 @@ impl TransactionsDal<'_, '_> {
                         TxExecutionStatus::Failure => Some("Bootloader-based tx failed".to_owned()),
                     };
 
+                    included_hashes.push(*hash);
+                    included_indices.push(
+                        u16::try_from(index_in_block).expect("Miniblock has too many transactions"),
+                    );
+
                     if let Some(call_trace) = tx_res.call_trace() {
                         let started_at = Instant::now();
                         bytea_call_traces.push(bincode::serialize(&call_trace).unwrap());
@@ -399,7 +443,6 @@ impl TransactionsDal<'_, '_> {
                     match &transaction.common_data {
                         ExecuteTransactionCommon::L1(common_data) => {
                             l1_hashes.push(hash.0.to_vec());
-                            l1_indices_in_block.push(index_in_block as i32);
                             l1_errors.push(error.unwrap_or_default());
                             l1_execution_infos.push(serde_json::to_value(execution_info).unwrap());
                             l1_refunded_gas.push(*refunded_gas as i64);
@@ -416,7 +459,6 @@ impl TransactionsDal<'_, '_> {
                             l2_paymaster
                                 .push(common_data.paymaster_params.paymaster.as_bytes().to_vec());
                             l2_hashes.push(hash.0.to_vec());
-                            l2_indices_in_block.push(index_in_block as i32);
                             l2_initiators.push(transaction.initiator_account().0.to_vec());
                             l2_nonces.push(common_data.nonce.0 as i32);
                             l2_signatures.push(common_data.signature.clone());
@@ -463,8 +505,6 @@ impl TransactionsDal<'_, '_> {
                                 input = data_table.input,
                                 data = data_table.data,
                                 tx_format = data_table.tx_format,
-                                miniblock_number = $21,
-                                index_in_block = data_table.index_in_block,
                                 error = NULLIF(data_table.error, ''),
                                 effective_gas_price = data_table.effective_gas_price,
                                 execution_info = data_table.new_execution_info,
@@ -473,7 +513,6 @@ impl TransactionsDal<'_, '_> {
                                 contract_address = data_table.contract_address,
                                 paymaster = data_table.paymaster,
                                 paymaster_input = data_table.paymaster_input,
-                                in_mempool = FALSE,
                                 updated_at = now()
                         FROM
                             (
@@ -487,17 +526,16 @@ impl TransactionsDal<'_, '_> {
                                     UNNEST($7::numeric[]) AS max_priority_fee_per_gas,
                                     UNNEST($8::numeric[]) AS gas_per_pubdata_limit,
                                     UNNEST($9::int[]) AS tx_format,
-                                    UNNEST($10::integer[]) AS index_in_block,
-                                    UNNEST($11::varchar[]) AS error,
-                                    UNNEST($12::numeric[]) AS effective_gas_price,
-                                    UNNEST($13::jsonb[]) AS new_execution_info,
-                                    UNNEST($14::bytea[]) AS input,
-                                    UNNEST($15::jsonb[]) AS data,
-                                    UNNEST($16::bigint[]) as refunded_gas,
-                                    UNNEST($17::numeric[]) as value,
-                                    UNNEST($18::bytea[]) as contract_address,
-                                    UNNEST($19::bytea[]) as paymaster,
-                                    UNNEST($20::bytea[]) as paymaster_input
+                                    UNNEST($10::varchar[]) AS error,
+                                    UNNEST($11::numeric[]) AS effective_gas_price,
+                                    UNNEST($12::jsonb[]) AS new_execution_info,
+                                    UNNEST($13::bytea[]) AS input,
+                                    UNNEST($14::jsonb[]) AS data,
+                                    UNNEST($15::bigint[]) as refunded_gas,
+                                    UNNEST($16::numeric[]) as value,
+                                    UNNEST($17::bytea[]) as contract_address,
+                                    UNNEST($18::bytea[]) as paymaster,
+                                    UNNEST($19::bytea[]) as paymaster_input
                             ) AS data_table
                         WHERE transactions.initiator_address=data_table.initiator_address 
                         AND transactions.nonce=data_table.nonce
@@ -511,7 +549,6 @@ impl TransactionsDal<'_, '_> {
                     &l2_max_priority_fees_per_gas,
                     &l2_gas_per_pubdata_limit,
                     &l2_tx_formats,
-                    &l2_indices_in_block,
                     &l2_errors,
                     &l2_effective_gas_prices,
                     &l2_execution_infos,
@@ -522,7 +559,6 @@ impl TransactionsDal<'_, '_> {
                     &l2_contract_addresses,
                     &l2_paymaster,
                     &l2_paymaster_input,
-                    miniblock_number.0 as i32,
                 )
                 .execute(transaction.conn())
                 .await
@@ -535,10 +571,7 @@ impl TransactionsDal<'_, '_> {
                     r#"
                         UPDATE transactions
                             SET
-                                miniblock_number = $1,
-                                index_in_block = data_table.index_in_block,
                                 error = NULLIF(data_table.error, ''),
-                                in_mempool=FALSE,
                                 execution_info = execution_info || data_table.new_execution_info,
                                 refunded_gas = data_table.refunded_gas,
                                 effective_gas_price = data_table.effective_gas_price,
@@ -546,18 +579,15 @@ impl TransactionsDal<'_, '_> {
                         FROM
                             (
                                 SELECT
-                                    UNNEST($2::bytea[]) AS hash,
-                                    UNNEST($3::integer[]) AS index_in_block,
-                                    UNNEST($4::varchar[]) AS error,
-                                    UNNEST($5::jsonb[]) AS new_execution_info,
-                                    UNNEST($6::bigint[]) as refunded_gas,
-                                    UNNEST($7::numeric[]) as effective_gas_price
+                                    UNNEST($1::bytea[]) AS hash,
+                                    UNNEST($2::varchar[]) AS error,
+                                    UNNEST($3::jsonb[]) AS new_execution_info,
+                                    UNNEST($4::bigint[]) as refunded_gas,
+                                    UNNEST($5::numeric[]) as effective_gas_price
                             ) AS data_table
                         WHERE transactions.hash = data_table.hash
                     "#,
-                    miniblock_number.0 as i32,
                     &l1_hashes,
-                    &l1_indices_in_block,
                     &l1_errors,
                     &l1_execution_infos,
                     &l1_refunded_gas,
@@ -585,6 +615,13 @@ impl TransactionsDal<'_, '_> {
                 .unwrap();
                 metrics::histogram!("dal.transactions.insert_call_tracer", started_at.elapsed());
             }
+
+            // Replaced L2 transactions have their hashes updated above, so all transactions
+            // can be located by their hashes.
+            transaction
+                .transactions_dal()
+                .mark_txs_included(&included_hashes, miniblock_number, &included_indices)
+                .await;
             transaction.commit().await;
         }
     }
@@ -654,6 +691,9 @@ impl TransactionsDal<'_, '_> {
     /// Fetches new updates for mempool
     /// Returns new transactions and current nonces for related accounts
     /// Latter is only used to bootstrap mempool for given account
+    ///
+    /// L2 transactions are selected according to the `ordering` strategy; priority operations
+    /// are always selected first.
     pub async fn sync_mempool(
         &mut self,
         stashed_accounts: Vec<Address>,
@@ -661,6 +701,7 @@ impl TransactionsDal<'_, '_> {
         gas_per_pubdata: u32,
         fee_per_gas: u64,
         limit: usize,
+        ordering: MempoolOrderingStrategy,
     ) -> (Vec<Transaction>, HashMap<Address, Nonce>) {
         {
             let stashed_addresses: Vec<_> =
@@ -686,28 +727,67 @@ impl TransactionsDal<'_, '_> {
             .await
             .unwrap();
 
-            let transactions = sqlx::query_as!(
-                StorageTransaction,
-                "UPDATE transactions
-                SET in_mempool = TRUE
-                FROM (
-                    SELECT hash
-                    FROM transactions
-                    WHERE miniblock_number IS NULL AND in_mempool = FALSE AND error IS NULL
-                        AND (is_priority = TRUE OR (max_fee_per_gas >= $2 and gas_per_pubdata_limit >= $3))
-                    ORDER BY is_priority DESC, priority_op_id, received_at
-                    LIMIT $1
-                    FOR UPDATE
-                ) as subquery
-                WHERE transactions.hash = subquery.hash
-                RETURNING transactions.*",
-                limit as i32,
-                BigDecimal::from(fee_per_gas),
-                BigDecimal::from(gas_per_pubdata),
-            )
-            .fetch_all(self.storage.conn())
-            .await
-            .unwrap();
+            let transactions = match ordering {
+                MempoolOrderingStrategy::Fifo => sqlx::query_as!(
+                    StorageTransaction,
+                    "UPDATE transactions
+                    SET in_mempool = TRUE
+                    FROM (
+                        SELECT hash
+                        FROM transactions
+                        WHERE miniblock_number IS NULL AND in_mempool = FALSE AND error IS NULL
+                            AND (is_priority = TRUE OR (max_fee_per_gas >= $2 and gas_per_pubdata_limit >= $3))
+                        ORDER BY is_priority DESC, priority_op_id, received_at
+                        LIMIT $1
+                        FOR UPDATE
+                    ) as subquery
+                    WHERE transactions.hash = subquery.hash
+                    RETURNING transactions.*",
+                    limit as i32,
+                    BigDecimal::from(fee_per_gas),
+                    BigDecimal::from(gas_per_pubdata),
+                )
+                .fetch_all(self.storage.conn())
+                .await
+                .unwrap(),
+
+                // The ordering key of a transaction is the minimum effective priority fee among
+                // the transactions of the same account with lower or equal nonces. Thus, the key
+                // is non-increasing with the nonce, and a transaction is never selected before
+                // its predecessors from the same account. Window functions cannot be used
+                // together with `FOR UPDATE`, hence the additional subquery locking the rows.
+                MempoolOrderingStrategy::PriorityFee => sqlx::query_as!(
+                    StorageTransaction,
+                    "UPDATE transactions
+                    SET in_mempool = TRUE
+                    FROM (
+                        SELECT hash
+                        FROM transactions
+                        WHERE hash IN (
+                            SELECT hash
+                            FROM (
+                                SELECT hash, is_priority, priority_op_id, initiator_address, nonce,
+                                    MIN(LEAST(max_priority_fee_per_gas, max_fee_per_gas - $2))
+                                        OVER (PARTITION BY initiator_address ORDER BY nonce) AS fee_key
+                                FROM transactions
+                                WHERE miniblock_number IS NULL AND in_mempool = FALSE AND error IS NULL
+                                    AND (is_priority = TRUE OR (max_fee_per_gas >= $2 and gas_per_pubdata_limit >= $3))
+                            ) AS pending
+                            ORDER BY is_priority DESC, priority_op_id, fee_key DESC, initiator_address, nonce
+                            LIMIT $1
+                        )
+                        FOR UPDATE
+                    ) as subquery
+                    WHERE transactions.hash = subquery.hash
+                    RETURNING transactions.*",
+                    limit as i32,
+                    BigDecimal::from(fee_per_gas),
+                    BigDecimal::from(gas_per_pubdata),
+                )
+                .fetch_all(self.storage.conn())
+                .await
+                .unwrap(),
+            };
 
             let nonce_keys: HashMap<_, _> = transactions
                 .iter()
@@ -793,6 +873,34 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns on-chain data for at most `limit` priority operations that are not included
+    /// into a miniblock yet, ordered by the priority operation ID.
+    pub async fn get_pending_priority_txs(
+        &mut self,
+        limit: usize,
+    ) -> Result<Vec<PriorityOpOnchainData>, SqlxError> {
+        let rows = sqlx::query!(
+            "SELECT hash, layer_2_tip_fee FROM transactions \
+            WHERE is_priority = TRUE AND miniblock_number IS NULL \
+            ORDER BY priority_op_id \
+            LIMIT $1",
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PriorityOpOnchainData {
+                layer_2_tip_fee: row
+                    .layer_2_tip_fee
+                    .map(bigdecimal_to_u256)
+                    .unwrap_or_default(),
+                onchain_data_hash: H256::from_slice(&row.hash),
+            })
+            .collect())
+    }
+
     pub async fn insert_trace(&mut self, hash: H256, trace: VmExecutionTrace) {
         {
             sqlx::query!(
@@ -856,6 +964,31 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns hashes, execution statuses and refunded gas of transactions executed in the specified
+    /// miniblock, in the order of their execution.
+    pub async fn get_miniblock_execution_results(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> Vec<(H256, TxExecutionStatus, u32)> {
+        sqlx::query!(
+            "SELECT hash, error, refunded_gas FROM transactions \
+            WHERE miniblock_number = $1 \
+            ORDER BY index_in_block",
+            miniblock_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| {
+            // Successful L1 transactions are stored with an empty error.
+            let has_failed = row.error.map_or(false, |error| !error.is_empty());
+            let status = TxExecutionStatus::from_has_failed(has_failed);
+            (H256::from_slice(&row.hash), status, row.refunded_gas as u32)
+        })
+        .collect()
+    }
+
     pub async fn get_tx_locations(&mut self, l1_batch_number: L1BatchNumber) -> TxLocations {
         {
             sqlx::query!(
@@ -885,6 +1018,69 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Finds transaction hashes referenced from more than one miniblock in the `from..=to` range.
+    /// Since `transactions.hash` is a primary key, a duplicate can only manifest itself
+    /// via events of a transaction being attributed to another miniblock. Returns offending hashes
+    /// together with all miniblocks they are referenced from, in ascending order.
+    pub async fn find_duplicate_tx_hashes(
+        &mut self,
+        from: MiniblockNumber,
+        to: MiniblockNumber,
+    ) -> Result<Vec<(H256, Vec<MiniblockNumber>)>, SqlxError> {
+        let rows = sqlx::query!(
+            r#"
+                SELECT hash as "hash!", array_agg(miniblock_number ORDER BY miniblock_number) as "miniblock_numbers!"
+                FROM (
+                    SELECT hash, miniblock_number FROM transactions
+                    WHERE miniblock_number BETWEEN $1 AND $2
+                    UNION
+                    SELECT tx_hash as hash, miniblock_number FROM events
+                    WHERE miniblock_number BETWEEN $1 AND $2
+                ) AS tx_locations
+                GROUP BY hash
+                HAVING COUNT(*) > 1
+                ORDER BY hash
+            "#,
+            from.0 as i64,
+            to.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let miniblock_numbers = row
+                    .miniblock_numbers
+                    .into_iter()
+                    .map(|number| MiniblockNumber(number as u32))
+                    .collect();
+                (H256::from_slice(&row.hash), miniblock_numbers)
+            })
+            .collect())
+    }
+
+    /// Removes call traces of transactions included into miniblocks preceding `before`.
+    /// At most `limit` traces are removed per call to keep locks short. Returns the number
+    /// of removed traces.
+    pub async fn prune_call_traces(&mut self, before: MiniblockNumber, limit: usize) -> usize {
+        sqlx::query!(
+            "DELETE FROM call_traces \
+            WHERE tx_hash IN ( \
+                SELECT call_traces.tx_hash FROM call_traces \
+                INNER JOIN transactions ON transactions.hash = call_traces.tx_hash \
+                WHERE transactions.miniblock_number < $1 \
+                LIMIT $2 \
+            )",
+            before.0 as i64,
+            limit as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap()
+        .rows_affected() as usize
+    }
+
     pub async fn get_call_trace(&mut self, tx_hash: H256) -> Option<Call> {
         {
             sqlx::query_as!(
@@ -1,10 +1,10 @@
 use sqlx::types::chrono::NaiveDateTime;
 
 use zksync_types::{
-    api, Address, L2ChainId, MiniblockNumber, Transaction, ACCOUNT_CODE_STORAGE_ADDRESS,
-    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H160, H256, U256, U64,
+    api, Address, Bytes, L1BatchNumber, L2ChainId, MiniblockNumber, Transaction,
+    ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H160, H256, U256, U64,
 };
-use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
+use zksync_utils::{bigdecimal_to_u256, bytecode::hash_bytecode, h256_to_account_address};
 
 use crate::models::{
     storage_block::{bind_block_where_sql_params, web3_block_where_sql},
@@ -14,7 +14,18 @@ use crate::models::{
         StorageTransactionDetails,
     },
 };
-use crate::{SqlxError, StorageProcessor};
+use crate::{blocks_web3_dal::BlocksWeb3DalError, SqlxError, StorageProcessor};
+
+/// Numbers of transactions in the `transactions` table broken down by their inclusion status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TxStatusCounts {
+    /// Transactions not yet included into a miniblock.
+    pub pending: u64,
+    /// Transactions included into a miniblock (including ones reverted during execution).
+    pub included: u64,
+    /// Transactions rejected by the state keeper and never included into a miniblock.
+    pub failed: u64,
+}
 
 #[derive(Debug)]
 pub struct TransactionsWeb3Dal<'a, 'c> {
@@ -211,6 +222,94 @@ impl TransactionsWeb3Dal<'_, '_> {
         Ok(tx)
     }
 
+    /// Returns up to `limit` transactions initiated by `initiator` that were included into miniblocks
+    /// starting from `from_block`, ordered by their position in the chain. The query is served
+    /// by the `(initiator_address, miniblock_number)` index on the `transactions` table.
+    pub async fn get_transactions_by_initiator(
+        &mut self,
+        initiator: H160,
+        from_block: MiniblockNumber,
+        limit: usize,
+        chain_id: L2ChainId,
+    ) -> Result<Vec<api::Transaction>, SqlxError> {
+        let query = format!(
+            "SELECT {}
+            FROM transactions
+            LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+            WHERE transactions.initiator_address = $1 AND transactions.miniblock_number >= $2
+            ORDER BY transactions.miniblock_number, transactions.index_in_block
+            LIMIT $3",
+            web3_transaction_select_sql()
+        );
+        let transactions = sqlx::query(&query)
+            .bind(initiator.as_bytes())
+            .bind(from_block.0 as i64)
+            .bind(limit as i64)
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| extract_web3_transaction(row, chain_id))
+            .collect();
+        Ok(transactions)
+    }
+
+    /// Returns up to `limit` transactions included into the specified miniblock, skipping the first
+    /// `offset` ones. Transactions are ordered by their index in the miniblock, which is consistent
+    /// with their `l1_batch_tx_index` order, so the paging windows are stable across calls.
+    pub async fn get_transactions_in_miniblock_paged(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        offset: usize,
+        limit: usize,
+        chain_id: L2ChainId,
+    ) -> Result<Vec<api::Transaction>, SqlxError> {
+        let query = format!(
+            "SELECT {}
+            FROM transactions
+            LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+            WHERE transactions.miniblock_number = $1
+            ORDER BY transactions.index_in_block
+            OFFSET $2
+            LIMIT $3",
+            web3_transaction_select_sql()
+        );
+        let transactions = sqlx::query(&query)
+            .bind(miniblock_number.0 as i64)
+            .bind(offset as i64)
+            .bind(limit as i64)
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| extract_web3_transaction(row, chain_id))
+            .collect();
+        Ok(transactions)
+    }
+
+    /// Returns the transaction with the specified index in the L1 batch, i.e., performs the reverse
+    /// lookup to [`BlocksWeb3Dal::get_l1_batch_info_for_tx()`](crate::blocks_web3_dal::BlocksWeb3Dal::get_l1_batch_info_for_tx()).
+    /// Returns `None` if the batch is unknown or the index is out of range.
+    pub async fn get_tx_by_batch_and_index(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        index: u16,
+        chain_id: L2ChainId,
+    ) -> Result<Option<api::Transaction>, SqlxError> {
+        let query = format!(
+            "SELECT {}
+            FROM transactions
+            LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+            WHERE transactions.l1_batch_number = $1 AND transactions.l1_batch_tx_index = $2",
+            web3_transaction_select_sql()
+        );
+        let tx = sqlx::query(&query)
+            .bind(l1_batch_number.0 as i64)
+            .bind(i32::from(index))
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| extract_web3_transaction(row, chain_id));
+        Ok(tx)
+    }
+
     pub async fn get_transaction_details(
         &mut self,
         hash: H256,
@@ -244,6 +343,41 @@ impl TransactionsWeb3Dal<'_, '_> {
         }
     }
 
+    /// Returns raw bytes of the transaction with the specified hash as it was received from the user
+    /// (i.e., signed and serialized). Returns `None` if the transaction is unknown or has no raw
+    /// representation (e.g., L1 transactions).
+    pub async fn get_raw_transaction(&mut self, hash: H256) -> Result<Option<Bytes>, SqlxError> {
+        let row = sqlx::query!(
+            "SELECT input FROM transactions WHERE hash = $1",
+            hash.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.and_then(|row| row.input).map(Bytes))
+    }
+
+    /// Counts transactions by their inclusion status. To avoid scanning the entire `transactions` table,
+    /// only non-included transactions are counted directly (which is served by the index on `miniblock_number`);
+    /// the number of included transactions is taken from miniblock headers.
+    pub async fn count_transactions_by_status(&mut self) -> Result<TxStatusCounts, SqlxError> {
+        let row = sqlx::query!(
+            "SELECT \
+                COUNT(*) FILTER (WHERE error IS NULL) AS \"pending!\", \
+                COUNT(*) FILTER (WHERE error IS NOT NULL) AS \"failed!\", \
+                (SELECT COALESCE(SUM(l1_tx_count + l2_tx_count), 0) FROM miniblocks)::bigint AS \"included!\" \
+            FROM transactions \
+            WHERE miniblock_number IS NULL"
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+
+        Ok(TxStatusCounts {
+            pending: row.pending as u64,
+            included: row.included as u64,
+            failed: row.failed as u64,
+        })
+    }
+
     /// Returns hashes of txs which were received after `from_timestamp` and the time of receiving the last tx.
     pub async fn get_pending_txs_hashes_after(
         &mut self,
@@ -279,7 +413,8 @@ impl TransactionsWeb3Dal<'_, '_> {
             .storage
             .blocks_web3_dal()
             .resolve_block_id(api::BlockId::Number(api::BlockNumber::Latest))
-            .await?
+            .await
+            .map_err(BlocksWeb3DalError::into_sqlx_error)?
             .expect("Failed to get `latest` nonce");
         let latest_nonce = self
             .storage
@@ -339,12 +474,62 @@ impl TransactionsWeb3Dal<'_, '_> {
 
         Ok(rows.into_iter().map(Into::into).collect())
     }
+
+    /// Returns hashes of factory dependencies (i.e., contract bytecodes) referenced by the transaction
+    /// with the specified hash. Returns an empty list if the transaction has no factory dependencies
+    /// or doesn't exist.
+    pub async fn get_factory_deps_for_tx(&mut self, tx_hash: H256) -> Result<Vec<H256>, SqlxError> {
+        let row = sqlx::query!(
+            "SELECT data->'factoryDeps' AS factory_deps FROM transactions WHERE hash = $1",
+            tx_hash.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        let Some(factory_deps) = row.and_then(|row| row.factory_deps) else {
+            return Ok(vec![]);
+        };
+
+        let factory_deps: Option<Vec<Vec<u8>>> = serde_json::from_value(factory_deps)
+            .unwrap_or_else(|err| {
+                panic!("cannot deserialize factory deps for tx {tx_hash:?}: {err}")
+            });
+        let hashes = factory_deps
+            .unwrap_or_default()
+            .iter()
+            .map(|bytecode| hash_bytecode(bytecode))
+            .collect();
+        Ok(hashes)
+    }
+
+    /// Returns the time (in milliseconds since UNIX epoch) when the mempool received the pending
+    /// transaction with the specified hash. Returns `None` if the transaction is unknown,
+    /// was rejected, or is already included into a miniblock.
+    pub async fn get_transaction_received_at(
+        &mut self,
+        tx_hash: H256,
+    ) -> Result<Option<u64>, SqlxError> {
+        let row = sqlx::query!(
+            "SELECT received_at FROM transactions \
+            WHERE hash = $1 AND miniblock_number IS NULL AND error IS NULL",
+            tx_hash.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.map(|row| row.received_at.timestamp_millis() as u64))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use db_test_macro::db_test;
-    use zksync_types::{fee::TransactionExecutionMetrics, l2::L2Tx};
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        fee::TransactionExecutionMetrics,
+        helpers::unix_timestamp_ms,
+        l2::L2Tx,
+        Nonce,
+    };
     use zksync_utils::miniblock_hash;
 
     use super::*;
@@ -373,6 +558,108 @@ mod tests {
             .await;
     }
 
+    #[db_test(dal_crate)]
+    async fn getting_factory_deps_for_tx(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let factory_deps = vec![vec![1_u8; 32], vec![2_u8; 96]];
+        let expected_hashes: Vec<_> = factory_deps
+            .iter()
+            .map(|bytecode| hash_bytecode(bytecode))
+            .collect();
+        let mut deploy_tx = mock_l2_transaction();
+        deploy_tx.execute.factory_deps = Some(factory_deps);
+        let deploy_tx_hash = deploy_tx.hash();
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        for tx in [deploy_tx, tx] {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                .await;
+        }
+
+        let hashes = conn
+            .transactions_web3_dal()
+            .get_factory_deps_for_tx(deploy_tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(hashes, expected_hashes);
+        let hashes = conn
+            .transactions_web3_dal()
+            .get_factory_deps_for_tx(tx_hash)
+            .await
+            .unwrap();
+        assert!(hashes.is_empty(), "{hashes:?}");
+        let hashes = conn
+            .transactions_web3_dal()
+            .get_factory_deps_for_tx(H256::repeat_byte(0xff))
+            .await
+            .unwrap();
+        assert!(hashes.is_empty(), "{hashes:?}");
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_transaction_received_at(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let mut tx = mock_l2_transaction();
+        let received_at = unix_timestamp_ms() - 10_000;
+        tx.received_timestamp_ms = received_at;
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+
+        let stored_received_at = conn
+            .transactions_web3_dal()
+            .get_transaction_received_at(tx_hash)
+            .await
+            .unwrap()
+            .expect("no pending transaction");
+        assert_eq!(stored_received_at, received_at);
+        let age_ms = unix_timestamp_ms() - stored_received_at;
+        assert!((10_000..60_000).contains(&age_ms), "{age_ms}");
+
+        let received_at = conn
+            .transactions_web3_dal()
+            .get_transaction_received_at(H256::repeat_byte(0xff))
+            .await
+            .unwrap();
+        assert_eq!(received_at, None);
+
+        // Rejected transactions are not pending either.
+        let rejected_tx = mock_l2_transaction();
+        let rejected_tx_hash = rejected_tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(rejected_tx, TransactionExecutionMetrics::default())
+            .await;
+        conn.transactions_dal()
+            .mark_tx_as_rejected(rejected_tx_hash, "rejected: out of gas")
+            .await;
+        let received_at = conn
+            .transactions_web3_dal()
+            .get_transaction_received_at(rejected_tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(received_at, None);
+
+        // Once the transaction is included into a miniblock, it's no longer pending.
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await;
+        let tx_results = [mock_execution_result(tx)];
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &tx_results, U256::from(1))
+            .await;
+        let received_at = conn
+            .transactions_web3_dal()
+            .get_transaction_received_at(tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(received_at, None);
+    }
+
     #[db_test(dal_crate)]
     async fn getting_transaction(connection_pool: ConnectionPool) {
         let mut conn = connection_pool.access_test_storage().await;
@@ -430,6 +717,109 @@ mod tests {
         }
     }
 
+    #[db_test(dal_crate)]
+    async fn transaction_chain_id_is_taken_from_argument(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        prepare_transaction(&mut conn, tx).await;
+
+        for chain_id in [L2ChainId(270), L2ChainId(271)] {
+            let web3_tx = conn
+                .transactions_web3_dal()
+                .get_transaction(api::TransactionId::Hash(tx_hash), chain_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(web3_tx.chain_id, U256::from(chain_id.0));
+
+            let block_id = api::BlockId::Number(api::BlockNumber::Number(1.into()));
+            let block = conn
+                .blocks_web3_dal()
+                .get_block_by_web3_block_id(block_id, true, chain_id, None)
+                .await
+                .unwrap()
+                .unwrap();
+            let block_txs: Vec<_> = block
+                .transactions
+                .into_iter()
+                .map(|tx| match tx {
+                    api::TransactionVariant::Full(tx) => tx,
+                    api::TransactionVariant::Hash(_) => panic!("expected full transaction"),
+                })
+                .collect();
+            assert_eq!(block_txs.len(), 1);
+            assert_eq!(block_txs[0].hash, tx_hash);
+            assert_eq!(block_txs[0].chain_id, U256::from(chain_id.0));
+        }
+    }
+
+    #[db_test(dal_crate)]
+    async fn counting_transactions_by_status(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let counts = conn
+            .transactions_web3_dal()
+            .count_transactions_by_status()
+            .await
+            .unwrap();
+        assert_eq!(counts, TxStatusCounts::default());
+
+        let included_tx = mock_l2_transaction();
+        prepare_transaction(&mut conn, included_tx).await;
+        let pending_tx = mock_l2_transaction();
+        conn.transactions_dal()
+            .insert_transaction_l2(pending_tx, TransactionExecutionMetrics::default())
+            .await;
+        let failed_tx = mock_l2_transaction();
+        let failed_tx_hash = failed_tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(failed_tx, TransactionExecutionMetrics::default())
+            .await;
+        conn.transactions_dal()
+            .mark_tx_as_rejected(failed_tx_hash, "rejected: test")
+            .await;
+
+        let counts = conn
+            .transactions_web3_dal()
+            .count_transactions_by_status()
+            .await
+            .unwrap();
+        assert_eq!(
+            counts,
+            TxStatusCounts {
+                pending: 1,
+                included: 1,
+                failed: 1,
+            }
+        );
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_raw_transaction(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let mut tx = mock_l2_transaction();
+        let raw_tx = vec![0x02, 0xf8, 0x6c, 0x82, 0x01, 0x0e];
+        let tx_hash = H256::repeat_byte(0x42);
+        tx.set_input(raw_tx.clone(), tx_hash);
+        conn.transactions_dal()
+            .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+            .await;
+
+        let loaded_raw_tx = conn
+            .transactions_web3_dal()
+            .get_raw_transaction(tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(loaded_raw_tx, Some(Bytes(raw_tx)));
+
+        let missing_raw_tx = conn
+            .transactions_web3_dal()
+            .get_raw_transaction(H256::zero())
+            .await
+            .unwrap();
+        assert_eq!(missing_raw_tx, None);
+    }
+
     #[db_test(dal_crate)]
     async fn getting_miniblock_transactions(connection_pool: ConnectionPool) {
         let mut conn = connection_pool.access_test_storage().await;
@@ -452,4 +842,208 @@ mod tests {
         assert_eq!(raw_txs.len(), 1);
         assert_eq!(raw_txs[0].hash(), tx_hash);
     }
+
+    #[db_test(dal_crate)]
+    async fn getting_transactions_in_miniblock_paged(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(0))
+            .await;
+
+        let txs: Vec<_> = (0..5).map(|_| mock_l2_transaction()).collect();
+        for tx in &txs {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+        }
+        let mut miniblock_header = create_miniblock_header(1);
+        miniblock_header.l2_tx_count = txs.len() as u16;
+        conn.blocks_dal().insert_miniblock(&miniblock_header).await;
+        let tx_results: Vec<_> = txs.iter().cloned().map(mock_execution_result).collect();
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &tx_results, U256::from(1))
+            .await;
+        let tx_hashes: Vec<_> = txs.iter().map(L2Tx::hash).collect();
+
+        let windows = [
+            (0, 2, 0..2),
+            (2, 2, 2..4),
+            (4, 2, 4..5),
+            (5, 2, 5..5),
+            (1, 10, 1..5),
+        ];
+        for (offset, limit, expected_range) in windows {
+            let page = conn
+                .transactions_web3_dal()
+                .get_transactions_in_miniblock_paged(
+                    MiniblockNumber(1),
+                    offset,
+                    limit,
+                    L2ChainId(270),
+                )
+                .await
+                .unwrap();
+            let page_hashes: Vec<_> = page.iter().map(|tx| tx.hash).collect();
+            assert_eq!(
+                page_hashes, tx_hashes[expected_range],
+                "offset={offset}, limit={limit}"
+            );
+            for tx in &page {
+                assert_eq!(tx.block_number, Some(U64::one()));
+            }
+        }
+
+        let page = conn
+            .transactions_web3_dal()
+            .get_transactions_in_miniblock_paged(MiniblockNumber(2), 0, 10, L2ChainId(270))
+            .await
+            .unwrap();
+        assert!(page.is_empty());
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_transactions_by_initiator(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(0))
+            .await;
+
+        let alice = H160::repeat_byte(0xa);
+        let bob = H160::repeat_byte(0xb);
+        let mut txs = vec![];
+        for (nonce, &initiator) in [alice, bob, alice].iter().enumerate() {
+            let mut tx = mock_l2_transaction();
+            tx.common_data.initiator_address = initiator;
+            tx.common_data.nonce = Nonce(nonce as u32);
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+            txs.push(tx);
+        }
+
+        // Alice's first transaction and Bob's transaction go to miniblock #1,
+        // Alice's second transaction goes to miniblock #2.
+        let miniblocks = [(1, &txs[..2]), (2, &txs[2..])];
+        for (number, miniblock_txs) in miniblocks {
+            let mut miniblock_header = create_miniblock_header(number);
+            miniblock_header.l2_tx_count = miniblock_txs.len() as u16;
+            conn.blocks_dal().insert_miniblock(&miniblock_header).await;
+
+            let tx_results: Vec<_> = miniblock_txs
+                .iter()
+                .cloned()
+                .map(mock_execution_result)
+                .collect();
+            conn.transactions_dal()
+                .mark_txs_as_executed_in_miniblock(
+                    MiniblockNumber(number),
+                    &tx_results,
+                    U256::from(1),
+                )
+                .await;
+        }
+
+        let get_hashes = |txs: Vec<api::Transaction>| -> Vec<H256> {
+            txs.into_iter().map(|tx| tx.hash).collect()
+        };
+        let mut dal = conn.transactions_web3_dal();
+        let alice_txs = dal
+            .get_transactions_by_initiator(alice, MiniblockNumber(0), 10, L2ChainId(270))
+            .await
+            .unwrap();
+        assert_eq!(get_hashes(alice_txs), [txs[0].hash(), txs[2].hash()]);
+
+        let alice_txs = dal
+            .get_transactions_by_initiator(alice, MiniblockNumber(0), 1, L2ChainId(270))
+            .await
+            .unwrap();
+        assert_eq!(get_hashes(alice_txs), [txs[0].hash()]);
+
+        let alice_txs = dal
+            .get_transactions_by_initiator(alice, MiniblockNumber(2), 10, L2ChainId(270))
+            .await
+            .unwrap();
+        assert_eq!(get_hashes(alice_txs), [txs[2].hash()]);
+
+        let bob_txs = dal
+            .get_transactions_by_initiator(bob, MiniblockNumber(0), 10, L2ChainId(270))
+            .await
+            .unwrap();
+        assert_eq!(get_hashes(bob_txs), [txs[1].hash()]);
+
+        let bob_txs = dal
+            .get_transactions_by_initiator(bob, MiniblockNumber(2), 10, L2ChainId(270))
+            .await
+            .unwrap();
+        assert!(bob_txs.is_empty());
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_transactions_by_batch_and_index(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        conn.blocks_dal()
+            .insert_l1_batch(&l1_batch_header, BlockGasCount::default())
+            .await;
+        let mut miniblock_header = create_miniblock_header(1);
+        miniblock_header.l2_tx_count = 2;
+        conn.blocks_dal().insert_miniblock(&miniblock_header).await;
+
+        let txs = [mock_l2_transaction(), mock_l2_transaction()];
+        for tx in &txs {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+        }
+        let tx_results: Vec<_> = txs.iter().cloned().map(mock_execution_result).collect();
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &tx_results, U256::from(1))
+            .await;
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(L1BatchNumber(1), &tx_results)
+            .await;
+
+        for tx in &txs {
+            let (l1_batch_number, index) = conn
+                .blocks_web3_dal()
+                .get_l1_batch_info_for_tx(tx.hash())
+                .await
+                .unwrap()
+                .expect("no L1 batch info for tx");
+            let web3_tx = conn
+                .transactions_web3_dal()
+                .get_tx_by_batch_and_index(l1_batch_number, index, L2ChainId(270))
+                .await
+                .unwrap()
+                .expect("no transaction");
+            assert_eq!(web3_tx.hash, tx.hash());
+            assert_eq!(web3_tx.l1_batch_number, Some(1.into()));
+            assert_eq!(web3_tx.l1_batch_tx_index, Some(index.into()));
+        }
+
+        let missing_tx_ids = [(L1BatchNumber(1), 2), (L1BatchNumber(2), 0)];
+        for (l1_batch_number, index) in missing_tx_ids {
+            let web3_tx = conn
+                .transactions_web3_dal()
+                .get_tx_by_batch_and_index(l1_batch_number, index, L2ChainId(270))
+                .await;
+            assert!(web3_tx.unwrap().is_none());
+        }
+    }
 }
@@ -499,7 +499,7 @@ impl WitnessGeneratorDal<'_, '_> {
             .await
             .unwrap();
 
-            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "create_aggregation_jobs");
+            StorageProcessor::report_request("create_aggregation_jobs", started_at);
         }
     }
 
@@ -536,11 +536,7 @@ impl WitnessGeneratorDal<'_, '_> {
             .await
             .unwrap();
 
-            metrics::histogram!(
-                "dal.request",
-                started_at.elapsed(),
-                "method" => "save_leaf_aggregation_artifacts"
-            );
+            StorageProcessor::report_request("save_leaf_aggregation_artifacts", started_at);
         }
     }
 
@@ -570,11 +566,7 @@ impl WitnessGeneratorDal<'_, '_> {
             .await
             .unwrap();
 
-            metrics::histogram!(
-                "dal.request",
-                started_at.elapsed(),
-                "method" => "save_node_aggregation_artifacts",
-            );
+            StorageProcessor::report_request("save_node_aggregation_artifacts", started_at);
         }
     }
 
@@ -82,6 +82,15 @@ impl EthInterface for QueryClient {
         Ok(network_gas_price)
     }
 
+    async fn get_blob_base_fee(&self, component: &'static str) -> Result<U256, Error> {
+        metrics::counter!("server.ethereum_gateway.call", 1, "component" => component, "method" => "get_blob_base_fee");
+        let start = Instant::now();
+        let blob_base_fee =
+            CallFuture::new(self.web3.transport().execute("eth_blobBaseFee", vec![])).await?;
+        metrics::histogram!("eth_client.direct.get_blob_base_fee", start.elapsed());
+        Ok(blob_base_fee)
+    }
+
     async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, Error> {
         let start = Instant::now();
         let tx = self.web3.eth().send_raw_transaction(Bytes(tx)).await?;
@@ -21,12 +21,14 @@ use zksync_types::web3::{
         U256, U64,
     },
 };
-use zksync_types::{L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE};
+use zksync_types::{
+    L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE, EIP_4844_TX_TYPE, LEGACY_TX_TYPE,
+};
 
 // Loal uses
 use super::query::QueryClient;
 use crate::{
-    types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
+    types::{BlobTxParams, Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
     BoundEthInterface, EthInterface,
 };
 
@@ -149,6 +151,10 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
             .await
     }
 
+    async fn get_blob_base_fee(&self, component: &'static str) -> Result<U256, Error> {
+        self.query_client.get_blob_base_fee(component).await
+    }
+
     async fn get_tx_status(
         &self,
         hash: H256,
@@ -248,19 +254,101 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
         contract_addr: H160,
         options: Options,
         component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        self.sign_tx(data, contract_addr, options, None, component)
+            .await
+    }
+
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        blob_params: BlobTxParams,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        self.sign_tx(data, contract_addr, options, Some(blob_params), component)
+            .await
+    }
+
+    async fn allowance_on_account(
+        &self,
+        token_address: Address,
+        address: Address,
+        erc20_abi: ethabi::Contract,
+    ) -> Result<U256, Error> {
+        let start = Instant::now();
+        let res = self
+            .call_contract_function(
+                "allowance",
+                (self.inner.sender_account, address),
+                None,
+                Options::default(),
+                None,
+                token_address,
+                erc20_abi,
+            )
+            .await?;
+        metrics::histogram!("eth_client.direct.allowance", start.elapsed());
+        Ok(res)
+    }
+}
+
+impl<S: EthereumSigner> SigningClient<S> {
+    pub fn new(
+        transport: Http,
+        contract: ethabi::Contract,
+        operator_eth_addr: H160,
+        eth_signer: S,
+        contract_eth_addr: H160,
+        default_priority_fee_per_gas: U256,
+        chain_id: L1ChainId,
+    ) -> Self {
+        Self {
+            inner: Arc::new(ETHDirectClientInner {
+                sender_account: operator_eth_addr,
+                eth_signer,
+                contract_addr: contract_eth_addr,
+                chain_id,
+                contract,
+                default_priority_fee_per_gas,
+            }),
+            query_client: transport.into(),
+        }
+    }
+
+    async fn sign_tx(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        blob_params: Option<BlobTxParams>,
+        component: &'static str,
     ) -> Result<SignedCallResult, Error> {
         let start = Instant::now();
 
+        // Blobs can only be attached to EIP-4844 transactions. Otherwise, if the gas price is specified,
+        // a legacy transaction is sent. The signer uses `max_fee_per_gas` as the gas price in this case.
+        let transaction_type = if blob_params.is_some() {
+            EIP_4844_TX_TYPE
+        } else if options.gas_price.is_some() {
+            LEGACY_TX_TYPE
+        } else {
+            EIP_1559_TX_TYPE
+        };
+
         // fetch current max priority fee per gas
-        let max_priority_fee_per_gas = match options.max_priority_fee_per_gas {
-            Some(max_priority_fee_per_gas) => max_priority_fee_per_gas,
-            None => self.inner.default_priority_fee_per_gas,
+        let max_priority_fee_per_gas = match (options.gas_price, options.max_priority_fee_per_gas) {
+            (Some(gas_price), _) => gas_price,
+            (None, Some(max_priority_fee_per_gas)) => max_priority_fee_per_gas,
+            (None, None) => self.inner.default_priority_fee_per_gas,
         };
 
         // fetch current base fee and add max_priority_fee_per_gas
-        let max_fee_per_gas = match options.max_fee_per_gas {
-            Some(max_fee_per_gas) => max_fee_per_gas,
-            None => {
+        let max_fee_per_gas = match (options.gas_price, options.max_fee_per_gas) {
+            (Some(gas_price), _) => gas_price,
+            (None, Some(max_fee_per_gas)) => max_fee_per_gas,
+            (None, None) => {
                 self.get_pending_block_base_fee_per_gas(component).await? + max_priority_fee_per_gas
             }
         };
@@ -298,13 +386,24 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
             chain_id: self.inner.chain_id.0,
             max_priority_fee_per_gas,
             gas_price: None,
-            transaction_type: Some(EIP_1559_TX_TYPE.into()),
+            transaction_type: Some(transaction_type.into()),
             access_list: None,
             max_fee_per_gas,
+            max_fee_per_blob_gas: blob_params
+                .as_ref()
+                .map(|params| params.max_fee_per_blob_gas),
+            blob_versioned_hashes: blob_params
+                .as_ref()
+                .map(|params| params.sidecar.versioned_hashes()),
         };
 
         let signed_tx = self.inner.eth_signer.sign_transaction(tx).await?;
+        // The hash of a blob transaction doesn't cover its sidecar.
         let hash = zksync_types::web3::signing::keccak256(&signed_tx).into();
+        let signed_tx = match &blob_params {
+            Some(params) => params.sidecar.wrap_signed_tx(&signed_tx),
+            None => signed_tx,
+        };
 
         metrics::histogram!(
             "eth_client.direct.sign_prepared_tx_for_addr",
@@ -318,50 +417,4 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
             hash,
         })
     }
-
-    async fn allowance_on_account(
-        &self,
-        token_address: Address,
-        address: Address,
-        erc20_abi: ethabi::Contract,
-    ) -> Result<U256, Error> {
-        let start = Instant::now();
-        let res = self
-            .call_contract_function(
-                "allowance",
-                (self.inner.sender_account, address),
-                None,
-                Options::default(),
-                None,
-                token_address,
-                erc20_abi,
-            )
-            .await?;
-        metrics::histogram!("eth_client.direct.allowance", start.elapsed());
-        Ok(res)
-    }
-}
-
-impl<S: EthereumSigner> SigningClient<S> {
-    pub fn new(
-        transport: Http,
-        contract: ethabi::Contract,
-        operator_eth_addr: H160,
-        eth_signer: S,
-        contract_eth_addr: H160,
-        default_priority_fee_per_gas: U256,
-        chain_id: L1ChainId,
-    ) -> Self {
-        Self {
-            inner: Arc::new(ETHDirectClientInner {
-                sender_account: operator_eth_addr,
-                eth_signer,
-                contract_addr: contract_eth_addr,
-                chain_id,
-                contract,
-                default_priority_fee_per_gas,
-            }),
-            query_client: transport.into(),
-        }
-    }
 }
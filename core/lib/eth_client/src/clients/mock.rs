@@ -18,7 +18,7 @@ use zksync_types::{Address, L1ChainId};
 use zksync_types::{web3::types::TransactionReceipt, H160, H256, U256};
 
 use crate::{
-    types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
+    types::{BlobTxParams, Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
     BoundEthInterface, EthInterface,
 };
 
@@ -59,8 +59,11 @@ pub struct MockEthereum {
     pub max_fee_per_gas: U256,
     pub base_fee_history: RwLock<Vec<u64>>,
     pub max_priority_fee_per_gas: U256,
+    pub blob_base_fee: U256,
     pub tx_statuses: RwLock<HashMap<H256, ExecutedTxStatus>>,
     pub sent_txs: RwLock<HashMap<H256, MockTx>>,
+    /// Blob parameters of signed EIP-4844 transactions, keyed by the transaction hash.
+    pub signed_blob_txs: RwLock<HashMap<H256, BlobTxParams>>,
     pub current_nonce: AtomicU64,
     pub pending_nonce: AtomicU64,
     pub nonces: RwLock<BTreeMap<u64, u64>>,
@@ -74,10 +77,12 @@ impl Default for MockEthereum {
         Self {
             max_fee_per_gas: 100.into(),
             max_priority_fee_per_gas: 10.into(),
+            blob_base_fee: 1.into(),
             block_number: Default::default(),
             base_fee_history: Default::default(),
             tx_statuses: Default::default(),
             sent_txs: Default::default(),
+            signed_blob_txs: Default::default(),
             current_nonce: Default::default(),
             pending_nonce: Default::default(),
             nonces: RwLock::new([(0, 0)].into()),
@@ -144,10 +149,16 @@ impl MockEthereum {
         mut raw_tx: Vec<u8>,
         options: Options,
     ) -> Result<SignedCallResult, Error> {
-        let max_fee_per_gas = options.max_fee_per_gas.unwrap_or(self.max_fee_per_gas);
-        let max_priority_fee_per_gas = options
-            .max_priority_fee_per_gas
-            .unwrap_or(self.max_priority_fee_per_gas);
+        // Legacy transactions are modeled as ones with zero priority fee.
+        let (max_fee_per_gas, max_priority_fee_per_gas) = match options.gas_price {
+            Some(gas_price) => (gas_price, U256::zero()),
+            None => (
+                options.max_fee_per_gas.unwrap_or(self.max_fee_per_gas),
+                options
+                    .max_priority_fee_per_gas
+                    .unwrap_or(self.max_priority_fee_per_gas),
+            ),
+        };
         let nonce = options.nonce.expect("Nonce must be set for every tx");
 
         // Nonce and gas_price are appended to distinguish the same transactions
@@ -202,6 +213,10 @@ impl EthInterface for MockEthereum {
         Ok(self.block_number.load(Ordering::SeqCst).into())
     }
 
+    async fn get_blob_base_fee(&self, _: &'static str) -> Result<U256, Error> {
+        Ok(self.blob_base_fee)
+    }
+
     async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, Error> {
         let mock_tx = MockTx::from(tx);
 
@@ -351,6 +366,22 @@ impl BoundEthInterface for MockEthereum {
         self.sign_prepared_tx(data, options)
     }
 
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        _contract_addr: H160,
+        options: Options,
+        blob_params: BlobTxParams,
+        _component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        let signed_tx = self.sign_prepared_tx(data, options)?;
+        self.signed_blob_txs
+            .write()
+            .unwrap()
+            .insert(signed_tx.hash, blob_params);
+        Ok(signed_tx)
+    }
+
     async fn allowance_on_account(
         &self,
         _token_address: Address,
@@ -426,6 +457,10 @@ impl<T: AsRef<MockEthereum> + Send + Sync> EthInterface for T {
         self.as_ref().block_number(component).await
     }
 
+    async fn get_blob_base_fee(&self, component: &'static str) -> Result<U256, Error> {
+        self.as_ref().get_blob_base_fee(component).await
+    }
+
     async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, Error> {
         self.as_ref().send_raw_tx(tx).await
     }
@@ -535,6 +570,19 @@ impl<T: AsRef<MockEthereum> + Send + Sync> BoundEthInterface for T {
             .await
     }
 
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        blob_params: BlobTxParams,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        self.as_ref()
+            .sign_prepared_blob_tx_for_addr(data, contract_addr, options, blob_params, component)
+            .await
+    }
+
     async fn allowance_on_account(
         &self,
         token_address: Address,
@@ -3,7 +3,7 @@
 pub mod clients;
 pub mod types;
 
-use crate::types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult};
+use crate::types::{BlobTxParams, Error, ExecutedTxStatus, FailureInfo, SignedCallResult};
 use async_trait::async_trait;
 use zksync_types::{
     web3::{
@@ -63,6 +63,9 @@ pub trait EthInterface: Sync + Send {
         component: &'static str,
     ) -> Result<U256, Error>;
 
+    /// Returns the base fee per blob gas (EIP-4844) for the next L1 block.
+    async fn get_blob_base_fee(&self, component: &'static str) -> Result<U256, Error>;
+
     /// Returns the current gas price.
     async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error>;
 
@@ -182,6 +185,17 @@ pub trait BoundEthInterface: EthInterface {
         component: &'static str,
     ) -> Result<SignedCallResult, Error>;
 
+    /// Same as [`Self::sign_prepared_tx_for_addr()`], but signs an EIP-4844 transaction with the provided blobs.
+    /// The returned raw transaction is in the network representation, i.e., includes the blob sidecar.
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        blob_params: BlobTxParams,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error>;
+
     /// Returns the nonce of the `Self::sender_account()` at the specified block.
     async fn nonce_at(&self, block: BlockNumber, component: &'static str) -> Result<U256, Error> {
         self.nonce_at_for_account(self.sender_account(), block, component)
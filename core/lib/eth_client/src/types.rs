@@ -1,4 +1,5 @@
 // External uses
+use zksync_eth_signer::BlobTxSidecar;
 use zksync_types::web3::{
     ethabi,
     types::{TransactionReceipt, H256, U256},
@@ -39,6 +40,15 @@ pub struct SignedCallResult {
     pub hash: H256,
 }
 
+/// Blob-related parameters of an EIP-4844 transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlobTxParams {
+    /// `max_fee_per_blob_gas` field of the transaction.
+    pub max_fee_per_blob_gas: U256,
+    /// Blobs attached to the transaction.
+    pub sidecar: BlobTxSidecar,
+}
+
 /// State of the executed Ethereum transaction.
 #[derive(Debug, Clone)]
 pub struct ExecutedTxStatus {
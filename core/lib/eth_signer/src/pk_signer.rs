@@ -77,6 +77,8 @@ impl EthereumSigner for PrivateKeySigner {
             transaction_type: raw_tx.transaction_type,
             access_list: raw_tx.access_list.unwrap_or_default(),
             max_priority_fee_per_gas,
+            max_fee_per_blob_gas: raw_tx.max_fee_per_blob_gas.unwrap_or_default(),
+            blob_versioned_hashes: raw_tx.blob_versioned_hashes.unwrap_or_default(),
         };
 
         let signed = tx.sign(&key, raw_tx.chain_id);
@@ -107,6 +109,8 @@ mod test {
             chain_id: 270,
             transaction_type: Some(U64::from(1u32)),
             access_list: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
         };
         let raw_tx = signer
             .sign_transaction(raw_transaction.clone())
@@ -9,15 +9,20 @@
 //! In the case where it will be possible to use only the web3 library without copy-paste, the changes will be small and simple
 //! Link to @Deniallugo's PR to web3: https://github.com/tomusdrw/rust-web3/pull/630
 use rlp::RlpStream;
+use sha2::{Digest, Sha256};
 use zksync_types::web3::{
     signing::{self, Signature},
     types::{AccessList, SignedTransaction},
 };
-use zksync_types::{ethabi::Address, U256, U64};
+use zksync_types::{ethabi::Address, H256, U256, U64};
 
 const LEGACY_TX_ID: u64 = 0;
 const ACCESSLISTS_TX_ID: u64 = 1;
 const EIP1559_TX_ID: u64 = 2;
+const EIP4844_TX_ID: u64 = 3;
+
+/// Version byte of blob versioned hashes, as defined in EIP-4844.
+const VERSIONED_HASH_VERSION_KZG: u8 = 1;
 
 #[derive(Clone, Debug, PartialEq, Default)]
 pub struct TransactionParameters {
@@ -43,6 +48,59 @@ pub struct TransactionParameters {
     pub max_fee_per_gas: U256,
     /// miner bribe
     pub max_priority_fee_per_gas: U256,
+    /// Max fee per blob gas (EIP-4844 transactions only)
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// Versioned hashes of the attached blobs (EIP-4844 transactions only)
+    pub blob_versioned_hashes: Option<Vec<H256>>,
+}
+
+/// Blobs together with their KZG commitments and proofs, which are attached to an EIP-4844 transaction
+/// when it's sent to the network. The sidecar is not covered by the transaction signature; the transaction
+/// commits to it via [versioned hashes](Self::versioned_hashes()).
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct BlobTxSidecar {
+    pub blobs: Vec<Vec<u8>>,
+    pub commitments: Vec<[u8; 48]>,
+    pub proofs: Vec<[u8; 48]>,
+}
+
+impl BlobTxSidecar {
+    /// Returns versioned hashes of the blob commitments.
+    pub fn versioned_hashes(&self) -> Vec<H256> {
+        self.commitments
+            .iter()
+            .map(|commitment| {
+                let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+                hash[0] = VERSIONED_HASH_VERSION_KZG;
+                H256(hash)
+            })
+            .collect()
+    }
+
+    /// Wraps a signed EIP-4844 transaction into the network representation that includes this sidecar.
+    /// The hash of the transaction is still computed from `signed_tx`.
+    pub fn wrap_signed_tx(&self, signed_tx: &[u8]) -> Vec<u8> {
+        assert_eq!(
+            signed_tx.first(),
+            Some(&(EIP4844_TX_ID as u8)),
+            "Sidecar can only be attached to EIP-4844 transactions"
+        );
+        let mut stream = RlpStream::new_list(4);
+        stream.append_raw(&signed_tx[1..], 1);
+        stream.begin_list(self.blobs.len());
+        for blob in &self.blobs {
+            stream.append(blob);
+        }
+        stream.begin_list(self.commitments.len());
+        for commitment in &self.commitments {
+            stream.append(&commitment.to_vec());
+        }
+        stream.begin_list(self.proofs.len());
+        for proof in &self.proofs {
+            stream.append(&proof.to_vec());
+        }
+        [&[EIP4844_TX_ID as u8], stream.as_raw()].concat()
+    }
 }
 
 /// A transaction used for RLP encoding, hashing and signing.
@@ -57,6 +115,8 @@ pub struct Transaction {
     pub transaction_type: Option<U64>,
     pub access_list: AccessList,
     pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_blob_gas: U256,
+    pub blob_versioned_hashes: Vec<H256>,
 }
 
 impl Transaction {
@@ -143,6 +203,38 @@ impl Transaction {
         stream
     }
 
+    fn encode_eip4844_payload(&self, chain_id: u64, signature: Option<&Signature>) -> RlpStream {
+        let mut stream = RlpStream::new();
+
+        let list_size = if signature.is_some() { 14 } else { 11 };
+        stream.begin_list(list_size);
+
+        stream.append(&chain_id);
+
+        stream.append(&self.nonce);
+        stream.append(&self.max_priority_fee_per_gas);
+        stream.append(&self.gas_price);
+        stream.append(&self.gas);
+        // EIP-4844 transactions cannot create contracts, so the recipient is mandatory.
+        let to = self
+            .to
+            .expect("EIP-4844 transactions must have a recipient");
+        stream.append(&to);
+        stream.append(&self.value);
+        stream.append(&self.data);
+
+        self.rlp_append_access_list(&mut stream);
+
+        stream.append(&self.max_fee_per_blob_gas);
+        stream.append_list(&self.blob_versioned_hashes);
+
+        if let Some(signature) = signature {
+            self.rlp_append_signature(&mut stream, signature);
+        }
+
+        stream
+    }
+
     fn rlp_append_signature(&self, stream: &mut RlpStream, signature: &Signature) {
         stream.append(&signature.v);
         stream.append(&U256::from_big_endian(signature.r.as_bytes()));
@@ -180,6 +272,12 @@ impl Transaction {
                 [&[tx_id], stream.as_raw()].concat()
             }
 
+            Some(EIP4844_TX_ID) => {
+                let tx_id: u8 = EIP4844_TX_ID as u8;
+                let stream = self.encode_eip4844_payload(chain_id, signature);
+                [&[tx_id], stream.as_raw()].concat()
+            }
+
             _ => {
                 panic!("Unsupported transaction type");
             }
@@ -6,14 +6,30 @@ pub use async_trait::async_trait;
 #[async_trait]
 pub trait CheckHealth: Send + Sync + 'static {
     async fn check_health(&self) -> CheckHealthStatus;
+
+    /// Returns additional information about the component to be included into the /healthcheck response.
+    /// This method must not block, since it's invoked on each request to the endpoint.
+    fn details(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Checks whether this check is taken into account by the /live endpoint. Liveness probes are
+    /// issued frequently, so checks that access external resources (e.g., the database or remote
+    /// components) should opt out.
+    fn affects_liveness(&self) -> bool {
+        true
+    }
 }
 
 /// Used to return health status when checked.
 /// States:
 ///     Ready => move forward
+///     Degraded => component works, but cannot serve its purpose yet (e.g., is lagging); fails readiness,
+///         but not necessarily liveness
 ///     NotReady => check fails with message String -- to be passed to /healthcheck caller
 #[derive(Debug, PartialEq)]
 pub enum CheckHealthStatus {
     Ready,
+    Degraded(String),
     NotReady(String),
 }
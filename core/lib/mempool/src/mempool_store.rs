@@ -2,11 +2,12 @@ use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
 use std::collections::hash_map::Entry;
 use std::collections::{BTreeSet, HashMap, HashSet};
 
+use zksync_config::configs::chain::MempoolOrderingStrategy;
 use zksync_types::{
     l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
 };
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct MempoolStore {
     /// Pending L1 transactions
     l1_transactions: HashMap<PriorityOpId, L1Tx>,
@@ -20,6 +21,7 @@ pub struct MempoolStore {
     /// number of l2 transactions in the mempool
     size: u64,
     capacity: u64,
+    ordering: MempoolOrderingStrategy,
 }
 
 #[derive(Debug)]
@@ -29,7 +31,11 @@ pub struct MempoolInfo {
 }
 
 impl MempoolStore {
-    pub fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
+    pub fn new(
+        next_priority_id: PriorityOpId,
+        capacity: u64,
+        ordering: MempoolOrderingStrategy,
+    ) -> Self {
         Self {
             l1_transactions: HashMap::new(),
             l2_transactions_per_account: HashMap::new(),
@@ -38,6 +44,7 @@ impl MempoolStore {
             stashed_accounts: vec![],
             size: 0,
             capacity,
+            ordering,
         }
     }
 
@@ -96,7 +103,7 @@ impl MempoolStore {
             Entry::Vacant(entry) => {
                 let account_nonce = initial_nonces.get(&account).cloned().unwrap_or(Nonce(0));
                 entry
-                    .insert(AccountTransactions::new(account_nonce))
+                    .insert(AccountTransactions::new(account_nonce, self.ordering))
                     .insert(transaction)
             }
         };
@@ -228,10 +235,14 @@ impl MempoolStore {
                 .into_iter()
                 .partition(|(address, _)| index.contains(address));
             self.l2_transactions_per_account = kept;
-            self.size = self
+            let new_size = self
                 .l2_transactions_per_account
                 .iter()
                 .fold(0, |agg, (_, tnxs)| agg + tnxs.len() as u64);
+            // Transactions of the drained accounts are dropped because the mempool is full.
+            metrics::counter!("mempool.rejected_full", self.size - new_size);
+            self.size = new_size;
+            self.collect_stats();
             return drained.into_keys().collect();
         }
         vec![]
@@ -1,6 +1,7 @@
 use crate::{mempool_store::MempoolStore, types::L2TxFilter};
 use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
+use zksync_config::configs::chain::MempoolOrderingStrategy;
 use zksync_types::fee::Fee;
 use zksync_types::helpers::unix_timestamp_ms;
 use zksync_types::l1::{OpProcessingType, PriorityQueueType};
@@ -10,7 +11,7 @@ use zksync_types::{Execute, Nonce, Transaction};
 
 #[test]
 fn basic_flow() {
-    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
     let account0 = Address::random();
     let account1 = Address::random();
     let transactions = vec![
@@ -57,7 +58,7 @@ fn basic_flow() {
 
 #[test]
 fn missing_txns() {
-    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
     let account = Address::random();
     let transactions = vec![
         gen_l2_tx(account, Nonce(6)),
@@ -97,7 +98,7 @@ fn missing_txns() {
 
 #[test]
 fn prioritize_l1_txns() {
-    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
     let account = Address::random();
     let transactions = vec![
         gen_l2_tx(account, Nonce(0)),
@@ -113,7 +114,7 @@ fn prioritize_l1_txns() {
 
 #[test]
 fn l1_txns_priority_id() {
-    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
     let transactions = vec![
         gen_l1_tx(PriorityOpId(1)),
         gen_l1_tx(PriorityOpId(2)),
@@ -138,7 +139,7 @@ fn l1_txns_priority_id() {
 
 #[test]
 fn rejected_tx() {
-    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
     let account = Address::random();
     let transactions = vec![
         gen_l2_tx(account, Nonce(0)),
@@ -178,7 +179,7 @@ fn rejected_tx() {
 
 #[test]
 fn replace_tx() {
-    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
     let account = Address::random();
     mempool.insert(vec![gen_l2_tx(account, Nonce(0))], HashMap::new());
     // replace it
@@ -196,7 +197,7 @@ fn replace_tx() {
 
 #[test]
 fn two_ready_txs() {
-    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
     let account0 = Address::random();
     let account1 = Address::random();
     let transactions = vec![gen_l2_tx(account0, Nonce(0)), gen_l2_tx(account1, Nonce(0))];
@@ -212,7 +213,7 @@ fn two_ready_txs() {
 
 #[test]
 fn mempool_size() {
-    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
     let account0 = Address::random();
     let account1 = Address::random();
     let transactions = vec![
@@ -249,7 +250,7 @@ fn filtering() {
         gas_per_pubdata: 0u32,
     };
 
-    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
     let account0 = Address::random();
     let account1 = Address::random();
 
@@ -292,7 +293,7 @@ fn stashed_accounts() {
         fee_per_gas: 0u64,
         gas_per_pubdata: 0u32,
     };
-    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
     let account0 = Address::random();
     let account1 = Address::random();
 
@@ -315,7 +316,7 @@ fn stashed_accounts() {
 
 #[test]
 fn mempool_capacity() {
-    let mut mempool = MempoolStore::new(PriorityOpId(0), 5);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 5, MempoolOrderingStrategy::Fifo);
     let account0 = Address::random();
     let account1 = Address::random();
     let account2 = Address::random();
@@ -359,6 +360,70 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn mempool_size_plateaus_at_capacity() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 3, MempoolOrderingStrategy::Fifo);
+    let accounts: Vec<_> = (0..3).map(|_| Address::random()).collect();
+    let transactions = accounts
+        .iter()
+        .map(|&account| gen_l2_tx(account, Nonce(0)))
+        .collect();
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(mempool.size(), 3);
+
+    for _ in 0..2 {
+        // Transactions that cannot be executed yet are purged once the mempool is full.
+        let new_accounts: Vec<_> = (0..2).map(|_| Address::random()).collect();
+        let transactions = new_accounts
+            .iter()
+            .map(|&account| gen_l2_tx(account, Nonce(1)))
+            .collect();
+        mempool.insert(transactions, HashMap::new());
+        assert_eq!(mempool.size(), 5);
+
+        let purged_accounts = mempool.get_mempool_info().purged_accounts;
+        assert_eq!(
+            HashSet::<_>::from_iter(purged_accounts),
+            HashSet::<_>::from_iter(new_accounts)
+        );
+        assert_eq!(mempool.size(), 3);
+    }
+}
+
+#[test]
+fn priority_fee_ordering() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::PriorityFee);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(account0, Nonce(0), 0, 1),
+        gen_l2_tx_with_priority_fee(account0, Nonce(1), 0, 100),
+        gen_l2_tx_with_priority_fee(account1, Nonce(0), 1, 10),
+        gen_l2_tx_with_priority_fee(account2, Nonce(0), 2, 10),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    // Transactions with the same priority fee are ordered by the received timestamp.
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account2, 0));
+    // The transaction with the higher priority fee cannot be selected before its predecessor.
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 1));
+    assert_eq!(mempool.next_transaction(&filter), None);
+
+    // With the FIFO ordering, priority fees are ignored.
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100, MempoolOrderingStrategy::Fifo);
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(account0, Nonce(0), 0, 1),
+        gen_l2_tx_with_priority_fee(account1, Nonce(0), 1, 10),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
@@ -378,6 +443,22 @@ fn gen_l2_tx_with_timestamp(address: Address, nonce: Nonce, received_at_ms: u64)
     txn.into()
 }
 
+fn gen_l2_tx_with_priority_fee(
+    address: Address,
+    nonce: Nonce,
+    received_at_ms: u64,
+    max_priority_fee_per_gas: u64,
+) -> Transaction {
+    let mut tx = gen_l2_tx_with_timestamp(address, nonce, received_at_ms);
+    match &mut tx.common_data {
+        ExecuteTransactionCommon::L2(data) => {
+            data.fee.max_priority_fee_per_gas = U256::from(max_priority_fee_per_gas)
+        }
+        _ => unreachable!(),
+    };
+    tx
+}
+
 fn gen_l1_tx(priority_id: PriorityOpId) -> Transaction {
     let execute = Execute {
         contract_address: Address::repeat_byte(0x11),
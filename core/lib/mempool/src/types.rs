@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use zksync_config::configs::chain::MempoolOrderingStrategy;
 use zksync_types::fee::Fee;
 use zksync_types::l2::L2Tx;
 use zksync_types::{Address, Nonce, Transaction, U256};
@@ -12,13 +13,16 @@ pub(crate) struct AccountTransactions {
     /// account nonce in mempool
     /// equals to committed nonce in db + number of transactions sent to state keeper
     nonce: Nonce,
+    /// ordering strategy used for transaction scores
+    ordering: MempoolOrderingStrategy,
 }
 
 impl AccountTransactions {
-    pub fn new(nonce: Nonce) -> Self {
+    pub fn new(nonce: Nonce, ordering: MempoolOrderingStrategy) -> Self {
         Self {
             transactions: HashMap::new(),
             nonce,
+            ordering,
         }
     }
 
@@ -30,11 +34,11 @@ impl AccountTransactions {
         if nonce < self.nonce {
             return metadata;
         }
-        let new_score = Self::score_for_transaction(&transaction);
+        let new_score = self.score_for_transaction(&transaction);
         let previous_score = self
             .transactions
             .insert(nonce, transaction)
-            .map(|tx| Self::score_for_transaction(&tx));
+            .map(|tx| self.score_for_transaction(&tx));
         metadata.is_new = previous_score.is_none();
         if nonce == self.nonce {
             metadata.new_score = Some(new_score);
@@ -54,7 +58,7 @@ impl AccountTransactions {
         let score = self
             .transactions
             .get(&self.nonce)
-            .map(Self::score_for_transaction);
+            .map(|tx| self.score_for_transaction(tx));
         (transaction, score)
     }
 
@@ -67,32 +71,37 @@ impl AccountTransactions {
         self.nonce = self.nonce.min(tx_nonce);
         self.transactions
             .get(&(tx_nonce + 1))
-            .map(Self::score_for_transaction)
+            .map(|tx| self.score_for_transaction(tx))
     }
 
     pub fn len(&self) -> usize {
         self.transactions.len()
     }
 
-    fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
+    fn score_for_transaction(&self, transaction: &L2Tx) -> MempoolScore {
         MempoolScore {
             account: transaction.initiator_account(),
             received_at_ms: transaction.received_timestamp_ms,
             fee_data: transaction.common_data.fee.clone(),
+            ordering: self.ordering,
         }
     }
 }
 
-/// Mempool score of transaction. Used to prioritize L2 transactions in mempool
-/// Currently trivial ordering is used based on received at timestamp
+/// Mempool score of transaction. Used to prioritize L2 transactions in mempool.
+/// With the FIFO ordering, transactions are ordered by the received at timestamp. With the priority fee
+/// ordering, transactions are ordered by `max_priority_fee_per_gas` first (the effective priority fee
+/// depends on the base fee, which changes over time, so it cannot be used as a score).
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
 pub struct MempoolScore {
     pub account: Address,
     pub received_at_ms: u64,
-    // Not used for actual scoring, but state keeper would request
+    // Used for scoring only with the priority fee ordering, but state keeper would request
     // transactions that have acceptable fee values (so transactions
     // with fee too low would be ignored until prices go down).
     pub fee_data: Fee,
+    /// Ordering strategy of the mempool. All scores in a mempool have the same strategy.
+    pub ordering: MempoolOrderingStrategy,
 }
 
 impl MempoolScore {
@@ -105,6 +114,13 @@ impl MempoolScore {
 
 impl Ord for MempoolScore {
     fn cmp(&self, other: &MempoolScore) -> Ordering {
+        if self.ordering == MempoolOrderingStrategy::PriorityFee {
+            let self_fee = self.fee_data.max_priority_fee_per_gas;
+            match self_fee.cmp(&other.fee_data.max_priority_fee_per_gas) {
+                Ordering::Equal => {}
+                ordering => return ordering,
+            }
+        }
         match self.received_at_ms.cmp(&other.received_at_ms).reverse() {
             Ordering::Equal => {}
             ordering => return ordering,
@@ -166,6 +182,7 @@ mod tests {
                 max_priority_fee_per_gas: U256::from(MAX_PRIORITY_FEE_PER_GAS),
                 gas_per_pubdata_limit: U256::from(GAS_PER_PUBDATA_LIMIT),
             },
+            ordering: MempoolOrderingStrategy::Fifo, // Not important
         };
 
         let noop_filter = filter(0, 0, 0);
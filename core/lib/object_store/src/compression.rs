@@ -0,0 +1,231 @@
+//! Transparent compression of stored blobs.
+
+use async_trait::async_trait;
+
+use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
+
+/// Prefix of the header of blobs written with compression enabled. Blobs stored before compression
+/// was enabled don't have a header, and are read as is.
+const HEADER_MAGIC: [u8; 4] = *b"zkob";
+/// Format byte following [`HEADER_MAGIC`] for blobs stored without compression.
+const FORMAT_RAW: u8 = 0;
+/// Format byte following [`HEADER_MAGIC`] for blobs compressed with zstd.
+const FORMAT_ZSTD: u8 = 1;
+const HEADER_LEN: usize = HEADER_MAGIC.len() + 1;
+
+/// [`ObjectStore`] wrapper compressing blobs larger than the configured threshold with zstd.
+/// If compression is enabled, each written blob is prefixed with a header containing a format byte
+/// that indicates whether the blob is compressed. Blobs without a header are read as is, so that
+/// reading legacy blobs and blobs written with compression disabled is supported.
+#[derive(Debug)]
+pub(crate) struct CompressingObjectStore<S> {
+    inner: S,
+    /// `None` if compression on write is disabled.
+    threshold: Option<usize>,
+    level: i32,
+}
+
+impl<S: ObjectStore> CompressingObjectStore<S> {
+    pub fn new(inner: S, threshold: Option<usize>, level: i32) -> Self {
+        Self {
+            inner,
+            threshold,
+            level,
+        }
+    }
+
+    fn with_header(format: u8, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+        bytes.extend_from_slice(&HEADER_MAGIC);
+        bytes.push(format);
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    async fn compress(&self, bucket: Bucket, value: Vec<u8>) -> Result<Vec<u8>, ObjectStoreError> {
+        let Some(threshold) = self.threshold else {
+            return Ok(value);
+        };
+        if value.len() < threshold {
+            return Ok(Self::with_header(FORMAT_RAW, &value));
+        }
+
+        let level = self.level;
+        let (value, compressed) = tokio::task::spawn_blocking(move || {
+            let compressed = zstd::encode_all(value.as_slice(), level);
+            (value, compressed)
+        })
+        .await
+        .unwrap();
+        let compressed = compressed.map_err(|err| ObjectStoreError::Serialization(err.into()))?;
+
+        if compressed.len() >= value.len() {
+            // Incompressible data; it's cheaper to store it as is.
+            return Ok(Self::with_header(FORMAT_RAW, &value));
+        }
+        let bytes_saved = (value.len() - compressed.len()) as u64;
+        metrics::counter!(
+            "object_store.bytes_saved",
+            bytes_saved,
+            "bucket" => bucket.as_str()
+        );
+        Ok(Self::with_header(FORMAT_ZSTD, &compressed))
+    }
+
+    async fn decompress(mut bytes: Vec<u8>) -> Result<Vec<u8>, ObjectStoreError> {
+        if bytes.len() < HEADER_LEN || !bytes.starts_with(&HEADER_MAGIC) {
+            return Ok(bytes);
+        }
+        match bytes[HEADER_MAGIC.len()] {
+            FORMAT_RAW => {
+                bytes.drain(..HEADER_LEN);
+                Ok(bytes)
+            }
+            FORMAT_ZSTD => {
+                tokio::task::spawn_blocking(move || zstd::decode_all(&bytes[HEADER_LEN..]))
+                    .await
+                    .unwrap()
+                    .map_err(|err| ObjectStoreError::Serialization(err.into()))
+            }
+            format => {
+                let err = format!("unknown blob format: {format}");
+                Err(ObjectStoreError::Serialization(err.into()))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S: ObjectStore> ObjectStore for CompressingObjectStore<S> {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let bytes = self.inner.get_raw(bucket, key).await?;
+        Self::decompress(bytes).await
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let value = self.compress(bucket, value).await?;
+        self.inner.put_raw(bucket, key, value).await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.inner.remove_raw(bucket, key).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::mock::MockStore;
+
+    const THRESHOLD: usize = 1_024;
+
+    fn create_store() -> (CompressingObjectStore<Arc<MockStore>>, Arc<MockStore>) {
+        let inner = Arc::new(MockStore::default());
+        let store = CompressingObjectStore::new(inner.clone(), Some(THRESHOLD), 3);
+        (store, inner)
+    }
+
+    #[tokio::test]
+    async fn large_blob_is_compressed() {
+        let (store, inner) = create_store();
+        let blob: Vec<u8> = (0..1_000_000).map(|i| b"abcdefg"[i % 7]).collect();
+        store
+            .put_raw(Bucket::WitnessInput, "large", blob.clone())
+            .await
+            .unwrap();
+
+        let stored_blob = inner.get_raw(Bucket::WitnessInput, "large").await.unwrap();
+        assert!(stored_blob.starts_with(&HEADER_MAGIC));
+        assert_eq!(stored_blob[HEADER_MAGIC.len()], FORMAT_ZSTD);
+        assert!(stored_blob.len() < blob.len() / 10, "{}", stored_blob.len());
+
+        let fetched_blob = store.get_raw(Bucket::WitnessInput, "large").await.unwrap();
+        assert_eq!(fetched_blob, blob);
+    }
+
+    #[tokio::test]
+    async fn small_blob_is_not_compressed() {
+        let (store, inner) = create_store();
+        let blob = vec![0_u8; THRESHOLD - 1];
+        store
+            .put_raw(Bucket::WitnessInput, "small", blob.clone())
+            .await
+            .unwrap();
+
+        let stored_blob = inner.get_raw(Bucket::WitnessInput, "small").await.unwrap();
+        assert!(stored_blob.starts_with(&HEADER_MAGIC));
+        assert_eq!(stored_blob[HEADER_MAGIC.len()], FORMAT_RAW);
+        assert_eq!(stored_blob[HEADER_LEN..], blob);
+        let fetched_blob = store.get_raw(Bucket::WitnessInput, "small").await.unwrap();
+        assert_eq!(fetched_blob, blob);
+    }
+
+    #[tokio::test]
+    async fn legacy_uncompressed_blob_can_be_read() {
+        let (store, inner) = create_store();
+        let blob = vec![1_u8; THRESHOLD * 10];
+        inner
+            .put_raw(Bucket::WitnessInput, "legacy", blob.clone())
+            .await
+            .unwrap();
+
+        let fetched_blob = store.get_raw(Bucket::WitnessInput, "legacy").await.unwrap();
+        assert_eq!(fetched_blob, blob);
+    }
+
+    #[tokio::test]
+    async fn blobs_are_stored_as_is_if_compression_is_disabled() {
+        let inner = Arc::new(MockStore::default());
+        let store = CompressingObjectStore::new(inner.clone(), None, 3);
+        let blob = vec![1_u8; THRESHOLD * 10];
+        store
+            .put_raw(Bucket::WitnessInput, "blob", blob.clone())
+            .await
+            .unwrap();
+        let stored_blob = inner.get_raw(Bucket::WitnessInput, "blob").await.unwrap();
+        assert_eq!(stored_blob, blob);
+
+        // Blobs compressed earlier can still be read.
+        let (compressing_store, compressing_inner) = create_store();
+        compressing_store
+            .put_raw(Bucket::WitnessInput, "compressed", blob.clone())
+            .await
+            .unwrap();
+        let compressed_blob = compressing_inner
+            .get_raw(Bucket::WitnessInput, "compressed")
+            .await
+            .unwrap();
+        inner
+            .put_raw(Bucket::WitnessInput, "compressed", compressed_blob)
+            .await
+            .unwrap();
+        let fetched_blob = store
+            .get_raw(Bucket::WitnessInput, "compressed")
+            .await
+            .unwrap();
+        assert_eq!(fetched_blob, blob);
+    }
+
+    #[tokio::test]
+    async fn blob_with_unknown_format_is_rejected() {
+        let (store, inner) = create_store();
+        let blob = [&HEADER_MAGIC[..], &[0xff, 1, 2, 3]].concat();
+        inner
+            .put_raw(Bucket::WitnessInput, "unknown", blob)
+            .await
+            .unwrap();
+
+        let err = store
+            .get_raw(Bucket::WitnessInput, "unknown")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown blob format"), "{err}");
+    }
+}
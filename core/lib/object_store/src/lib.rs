@@ -8,6 +8,8 @@
 //! can be constructed using an [`ObjectStoreFactory`] based on the configuration.
 //! The configuration can be provided explicitly (see [`ObjectStoreFactory::new()`])
 //! or obtained from the environment (see [`ObjectStoreFactory::from_env()`]).
+//! Different kinds of blobs may be stored in different backends; see
+//! [`ObjectStoreFactory::from_env_multi()`].
 //!
 //! Besides the lower-level storage abstraction, the crate provides high-level
 //! typesafe `<dyn ObjectStore>::get()` and `<dyn ObjectStore>::put()` methods
@@ -23,6 +25,7 @@
     clippy::doc_markdown
 )]
 
+mod compression;
 mod file;
 mod gcs;
 mod mock;
@@ -39,5 +42,5 @@ pub mod _reexports {
 
 pub use self::{
     objects::{AggregationsKey, CircuitKey, ClosedFormInputKey, FriCircuitKey, StoredObject},
-    raw::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory},
+    raw::{BlobKind, Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory, ObjectStoreRouter},
 };
@@ -2,6 +2,7 @@
 
 use zksync_types::{
     proofs::{AggregationRound, PrepareBasicCircuitsJob},
+    snapshots::{SnapshotManifest, StorageLogsSnapshot},
     zkevm_test_harness::{
         abstract_zksync_circuit::concrete_circuits::ZkSyncCircuit,
         bellman::bn256::Bn256,
@@ -138,6 +139,28 @@ impl StoredObject for Vec<QueueSimulator<Bn256, RecursionRequest<Bn256>, 2, 2>>
     serialize_using_bincode!();
 }
 
+impl StoredObject for StorageLogsSnapshot {
+    const BUCKET: Bucket = Bucket::StorageSnapshots;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("snapshot_l1_batch_{key}_storage_logs.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
+impl StoredObject for SnapshotManifest {
+    const BUCKET: Bucket = Bucket::StorageSnapshots;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("snapshot_l1_batch_{key}_manifest.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
 /// Storage key for a [AggregationWrapper`].
 #[derive(Debug, Clone, Copy)]
 pub struct AggregationsKey {
@@ -1,8 +1,11 @@
 use async_trait::async_trait;
 
-use std::{error, fmt, sync::Arc};
+use std::{collections::HashMap, error, fmt, sync::Arc};
 
-use crate::{file::FileBackedObjectStore, gcs::GoogleCloudStorage, mock::MockStore};
+use crate::{
+    compression::CompressingObjectStore, file::FileBackedObjectStore, gcs::GoogleCloudStorage,
+    mock::MockStore,
+};
 use zksync_config::configs::object_store::ObjectStoreMode;
 use zksync_config::ObjectStoreConfig;
 
@@ -20,6 +23,8 @@ pub enum Bucket {
     NodeAggregationWitnessJobsFri,
     SchedulerWitnessJobsFri,
     ProofsFri,
+    FactoryDeps,
+    StorageSnapshots,
 }
 
 impl Bucket {
@@ -35,6 +40,8 @@ impl Bucket {
             Self::NodeAggregationWitnessJobsFri => "node_aggregation_witness_jobs_fri",
             Self::SchedulerWitnessJobsFri => "scheduler_witness_jobs_fri",
             Self::ProofsFri => "proofs_fri",
+            Self::FactoryDeps => "factory_deps",
+            Self::StorageSnapshots => "storage_logs_snapshots",
         }
     }
 }
@@ -45,6 +52,35 @@ impl fmt::Display for Bucket {
     }
 }
 
+/// Kind of blobs for which a dedicated object store backend can be configured
+/// (see [`ObjectStoreFactory::from_env_multi()`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BlobKind {
+    /// Witness inputs and other blobs produced and consumed during witness generation.
+    WitnessInputs,
+    /// State snapshots exported for external nodes.
+    Snapshots,
+}
+
+impl BlobKind {
+    const ALL: [Self; 2] = [Self::WitnessInputs, Self::Snapshots];
+
+    fn config_name(self) -> &'static str {
+        match self {
+            Self::WitnessInputs => "witness_inputs_object_store",
+            Self::Snapshots => "snapshots_object_store",
+        }
+    }
+
+    fn env_prefix(self) -> &'static str {
+        match self {
+            Self::WitnessInputs => "WITNESS_INPUTS_OBJECT_STORE_",
+            Self::Snapshots => "SNAPSHOTS_OBJECT_STORE_",
+        }
+    }
+}
+
 /// Thread-safe boxed error.
 pub type BoxedError = Box<dyn error::Error + Send + Sync>;
 
@@ -59,6 +95,12 @@ pub enum ObjectStoreError {
     Other(BoxedError),
 }
 
+impl ObjectStoreError {
+    fn config(name: &str, err: impl fmt::Display) -> Self {
+        Self::Other(format!("cannot load config <{name}>: {err}").into())
+    }
+}
+
 impl fmt::Display for ObjectStoreError {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -162,9 +204,54 @@ impl ObjectStoreFactory {
     }
 
     /// Creates an object store factory with the configuration taken from the environment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is missing or malformed. Use [`Self::try_from_env()`]
+    /// to handle this case gracefully.
     pub fn from_env() -> Self {
-        let config = ObjectStoreConfig::from_env();
-        Self::new(config)
+        Self::try_from_env().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Creates an object store factory with the configuration taken from the environment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is missing or malformed.
+    pub fn try_from_env() -> Result<Self, ObjectStoreError> {
+        let config = ObjectStoreConfig::try_from_env()
+            .map_err(|err| ObjectStoreError::config("object_store", err))?;
+        Ok(Self::new(config))
+    }
+
+    /// Creates a router of object store factories with the configuration taken from the environment.
+    /// Each [`BlobKind`] may have a dedicated backend configured (e.g., using `WITNESS_INPUTS_OBJECT_STORE_*`
+    /// env variables); kinds without such a configuration use the store returned by [`Self::from_env()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the configurations is malformed. Use [`Self::try_from_env_multi()`]
+    /// to handle this case gracefully.
+    pub fn from_env_multi() -> ObjectStoreRouter {
+        Self::try_from_env_multi().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Self::from_env_multi()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the configurations is missing or malformed.
+    pub fn try_from_env_multi() -> Result<ObjectStoreRouter, ObjectStoreError> {
+        let mut router = ObjectStoreRouter::new(Self::try_from_env()?);
+        for kind in BlobKind::ALL {
+            let config = ObjectStoreConfig::try_override_from_env(kind.env_prefix())
+                .map_err(|err| ObjectStoreError::config(kind.config_name(), err))?;
+            if let Some(config) = config {
+                vlog::info!("Using dedicated object store for {kind:?} blobs: {config:?}");
+                router = router.with_factory(kind, Self::new(config));
+            }
+        }
+        Ok(router)
     }
 
     /// Creates an object store factory with a mock in-memory store.
@@ -185,6 +272,20 @@ impl ObjectStoreFactory {
     }
 
     async fn create_from_config(config: &ObjectStoreConfig) -> Box<dyn ObjectStore> {
+        let store = Self::create_uncompressed_store(config).await;
+        let threshold = config.compression_threshold_bytes;
+        let level = config.compression_level();
+        if let Some(threshold) = threshold {
+            vlog::trace!(
+                "Enabled compression for object store blobs with at least {threshold} bytes (level: {level})"
+            );
+        }
+        // Stores are always wrapped, so that compressed blobs can be read even if compression is disabled.
+        let store = Arc::<dyn ObjectStore>::from(store);
+        Box::new(CompressingObjectStore::new(store, threshold, level))
+    }
+
+    async fn create_uncompressed_store(config: &ObjectStoreConfig) -> Box<dyn ObjectStore> {
         let gcs_credential_file_path = match config.mode {
             ObjectStoreMode::GCSWithCredentialFile => Some(config.gcs_credential_file_path.clone()),
             _ => None,
@@ -218,3 +319,103 @@ impl ObjectStoreFactory {
         }
     }
 }
+
+/// Router of [`ObjectStoreFactory`]s keyed by [`BlobKind`]. Blob kinds without a dedicated factory
+/// are served by the default one.
+#[derive(Debug)]
+pub struct ObjectStoreRouter {
+    default_factory: ObjectStoreFactory,
+    factories: HashMap<BlobKind, ObjectStoreFactory>,
+}
+
+impl ObjectStoreRouter {
+    /// Creates a router serving all blob kinds with the specified factory.
+    pub fn new(default_factory: ObjectStoreFactory) -> Self {
+        Self {
+            default_factory,
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Sets a dedicated factory for the specified blob `kind`.
+    #[must_use]
+    pub fn with_factory(mut self, kind: BlobKind, factory: ObjectStoreFactory) -> Self {
+        self.factories.insert(kind, factory);
+        self
+    }
+
+    /// Returns the factory used for blobs not covered by [`BlobKind`]s.
+    pub fn default_factory(&self) -> &ObjectStoreFactory {
+        &self.default_factory
+    }
+
+    /// Returns the factory for the specified blob `kind`.
+    pub fn factory(&self, kind: BlobKind) -> &ObjectStoreFactory {
+        self.factories.get(&kind).unwrap_or(&self.default_factory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn file_backed_config(dir: &TempDir) -> ObjectStoreConfig {
+        ObjectStoreConfig {
+            bucket_base_url: String::new(),
+            mode: ObjectStoreMode::FileBacked,
+            file_backed_base_path: dir.path().to_str().unwrap().to_owned(),
+            gcs_credential_file_path: String::new(),
+            max_retries: 1,
+            compression_threshold_bytes: None,
+            compression_level: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn blob_kinds_are_routed_to_configured_backends() {
+        let witness_dir = TempDir::new("witness-inputs").unwrap();
+        let snapshots_dir = TempDir::new("snapshots").unwrap();
+        let router =
+            ObjectStoreRouter::new(ObjectStoreFactory::new(file_backed_config(&witness_dir)))
+                .with_factory(
+                    BlobKind::Snapshots,
+                    ObjectStoreFactory::new(file_backed_config(&snapshots_dir)),
+                );
+
+        let witness_store = router.factory(BlobKind::WitnessInputs).create_store().await;
+        let snapshots_store = router.factory(BlobKind::Snapshots).create_store().await;
+        witness_store
+            .put_raw(Bucket::WitnessInput, "1.bin", vec![1])
+            .await
+            .unwrap();
+        snapshots_store
+            .put_raw(Bucket::StorageSnapshots, "1.bin", vec![2])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            witness_store
+                .get_raw(Bucket::WitnessInput, "1.bin")
+                .await
+                .unwrap(),
+            [1]
+        );
+        assert!(witness_store
+            .get_raw(Bucket::StorageSnapshots, "1.bin")
+            .await
+            .is_err());
+        assert_eq!(
+            snapshots_store
+                .get_raw(Bucket::StorageSnapshots, "1.bin")
+                .await
+                .unwrap(),
+            [2]
+        );
+        assert!(snapshots_store
+            .get_raw(Bucket::WitnessInput, "1.bin")
+            .await
+            .is_err());
+    }
+}
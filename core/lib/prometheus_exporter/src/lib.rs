@@ -1,11 +1,158 @@
-use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
 use std::time::Duration;
+#[cfg(tokio_unstable)]
+use tokio::runtime::Handle;
 use tokio::task::JoinHandle;
+use zksync_config::configs::{PrometheusConfig, PrometheusExporterMode};
 
-pub fn run_prometheus_exporter(
-    port: u16,
-    pushgateway_config: Option<(String, Duration)>,
-) -> JoinHandle<()> {
+/// Way the metrics are exported to Prometheus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrometheusTransport {
+    /// Metrics are served on the specified port and scraped by Prometheus.
+    Pull { port: u16 },
+    /// Metrics are pushed to the Pushgateway at `url` every `interval`.
+    Push { url: String, interval: Duration },
+    /// Metrics are served on the specified port *and* pushed to the Pushgateway.
+    Both {
+        port: u16,
+        url: String,
+        interval: Duration,
+    },
+}
+
+impl PrometheusTransport {
+    pub fn from_config(config: &PrometheusConfig) -> Self {
+        match config.exporter_mode {
+            PrometheusExporterMode::Pull => Self::Pull {
+                port: config.listener_port,
+            },
+            PrometheusExporterMode::Push => Self::Push {
+                url: config.pushgateway_url.clone(),
+                interval: config.push_interval(),
+            },
+            PrometheusExporterMode::Both => Self::Both {
+                port: config.listener_port,
+                url: config.pushgateway_url.clone(),
+                interval: config.push_interval(),
+            },
+        }
+    }
+}
+
+fn pushgateway_endpoint(pushgateway_url: &str) -> String {
+    let job_id = "zksync-pushgateway";
+    let namespace = std::env::var("POD_NAMESPACE").unwrap_or_else(|_| {
+        vlog::warn!("Missing POD_NAMESPACE env");
+        "UNKNOWN_NAMESPACE".to_string()
+    });
+    let pod = std::env::var("POD_NAME").unwrap_or_else(|_| {
+        vlog::warn!("Missing POD_NAME env");
+        "UNKNOWN_POD".to_string()
+    });
+    format!(
+        "{}/metrics/job/{}/namespace/{}/pod/{}",
+        pushgateway_url, job_id, namespace, pod
+    )
+}
+
+/// Periodically pushes metrics rendered by `handle` to the Pushgateway. Used in the `Both` mode,
+/// since a single `PrometheusBuilder` can either serve or push metrics, but not both.
+async fn push_metrics(handle: PrometheusHandle, endpoint: String, interval: Duration) {
+    let client = reqwest::Client::new();
+    loop {
+        tokio::time::sleep(interval).await;
+        let response = client.put(&endpoint).body(handle.render()).send().await;
+        match response {
+            Ok(response) if !response.status().is_success() => {
+                vlog::warn!(
+                    "Pushgateway responded with status {} to metrics push",
+                    response.status()
+                );
+            }
+            Ok(_) => {}
+            Err(err) => vlog::warn!("Failed pushing metrics to Pushgateway: {err}"),
+        }
+    }
+}
+
+/// Interval between collecting Tokio runtime metrics.
+const RUNTIME_METRICS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Reports metrics of the Tokio runtime `handle` belongs to into `recorder`. Allows to detect
+/// worker starvation and task queue buildup.
+///
+/// Tokio runtime metrics are unstable, so they are only available if the binary is built
+/// with `RUSTFLAGS="--cfg tokio_unstable"`.
+#[cfg(tokio_unstable)]
+fn report_runtime_metrics(handle: &Handle, recorder: &dyn metrics::Recorder) {
+    let runtime_metrics = handle.metrics();
+    let worker_count = runtime_metrics.num_workers();
+    let busy_duration: Duration = (0..worker_count)
+        .map(|worker| runtime_metrics.worker_total_busy_duration(worker))
+        .sum();
+
+    let set_gauge = |name: &'static str, value: f64| {
+        recorder
+            .register_gauge(&metrics::Key::from_static_name(name))
+            .set(value);
+    };
+    set_gauge("tokio.runtime.workers", worker_count as f64);
+    set_gauge("tokio.runtime.busy_duration", busy_duration.as_secs_f64());
+    set_gauge(
+        "tokio.runtime.global_queue_depth",
+        runtime_metrics.injection_queue_depth() as f64,
+    );
+}
+
+#[cfg(tokio_unstable)]
+async fn collect_runtime_metrics(interval: Duration) {
+    let handle = Handle::current();
+    loop {
+        report_runtime_metrics(&handle, metrics::recorder());
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(not(tokio_unstable))]
+async fn collect_runtime_metrics(_interval: Duration) {
+    vlog::warn!(
+        "Tokio runtime metrics are enabled, but the binary is built without `--cfg tokio_unstable`; \
+         runtime metrics will not be collected"
+    );
+}
+
+/// Runs the Prometheus exporter. In the `Both` mode, the returned handle also covers the task
+/// pushing metrics to the Pushgateway.
+pub fn run_prometheus_exporter(transport: PrometheusTransport) -> JoinHandle<()> {
+    let (exporter_task, push_task) = run_prometheus_exporter_inner(transport, false);
+    match push_task {
+        Some(push_task) => tokio::spawn(async move {
+            tokio::select! {
+                _ = exporter_task => {}
+                _ = push_task => {}
+            }
+        }),
+        None => exporter_task,
+    }
+}
+
+/// Runs the Prometheus exporter configured from `config`. Unlike [`run_prometheus_exporter()`],
+/// this also exports Tokio runtime metrics if they are enabled in the config, and returns
+/// a separate handle for the task pushing metrics to the Pushgateway in the `Both` mode.
+pub fn run_prometheus_exporter_from_config(config: &PrometheusConfig) -> Vec<JoinHandle<()>> {
+    let (exporter_task, push_task) = run_prometheus_exporter_inner(
+        PrometheusTransport::from_config(config),
+        config.collect_runtime_metrics(),
+    );
+    std::iter::once(exporter_task).chain(push_task).collect()
+}
+
+/// Returns handles for the exporter task and, in the `Both` mode, for the task pushing metrics
+/// to the Pushgateway.
+fn run_prometheus_exporter_inner(
+    transport: PrometheusTransport,
+    collect_runtime: bool,
+) -> (JoinHandle<()>, Option<JoinHandle<()>>) {
     // in seconds
     let default_latency_buckets = [0.001, 0.005, 0.025, 0.1, 0.25, 1.0, 5.0, 30.0, 120.0];
     let slow_latency_buckets = [
@@ -40,26 +187,22 @@ pub fn run_prometheus_exporter(
         0.1, 0.25, 0.5, 0.75, 1., 1.5, 2., 3., 4., 5., 10., 20., 50., 100., 1000.,
     ];
 
-    let builder = if let Some((pushgateway_url, push_interval)) = pushgateway_config {
-        let job_id = "zksync-pushgateway";
-        let namespace = std::env::var("POD_NAMESPACE").unwrap_or_else(|_| {
-            vlog::warn!("Missing POD_NAMESPACE env");
-            "UNKNOWN_NAMESPACE".to_string()
-        });
-        let pod = std::env::var("POD_NAME").unwrap_or_else(|_| {
-            vlog::warn!("Missing POD_NAME env");
-            "UNKNOWN_POD".to_string()
-        });
-        let endpoint = format!(
-            "{}/metrics/job/{}/namespace/{}/pod/{}",
-            pushgateway_url, job_id, namespace, pod
-        );
-        PrometheusBuilder::new()
-            .with_push_gateway(endpoint.as_str(), push_interval)
-            .unwrap()
-    } else {
-        let addr = ([0, 0, 0, 0], port);
-        PrometheusBuilder::new().with_http_listener(addr)
+    let mut push_task = None;
+    let builder = match transport {
+        PrometheusTransport::Pull { port } => {
+            PrometheusBuilder::new().with_http_listener(([0, 0, 0, 0], port))
+        }
+        PrometheusTransport::Push { url, interval } => PrometheusBuilder::new()
+            .with_push_gateway(pushgateway_endpoint(&url).as_str(), interval)
+            .unwrap(),
+        PrometheusTransport::Both {
+            port,
+            url,
+            interval,
+        } => {
+            push_task = Some((pushgateway_endpoint(&url), interval));
+            PrometheusBuilder::new().with_http_listener(([0, 0, 0, 0], port))
+        }
     };
 
     let (recorder, exporter) = builder
@@ -97,14 +240,84 @@ pub fn run_prometheus_exporter(
         .build()
         .expect("failed to install Prometheus recorder");
 
+    let handle = recorder.handle();
     metrics::set_boxed_recorder(Box::new(recorder)).expect("failed to set metrics recorder");
 
-    tokio::spawn(async move {
+    let push_task = push_task
+        .map(|(endpoint, interval)| tokio::spawn(push_metrics(handle, endpoint, interval)));
+    let exporter_task = tokio::spawn(async move {
+        if collect_runtime {
+            tokio::spawn(collect_runtime_metrics(RUNTIME_METRICS_INTERVAL));
+        }
         tokio::pin!(exporter);
         loop {
             tokio::select! {
                 _ = &mut exporter => {}
             }
         }
-    })
+    });
+    (exporter_task, push_task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(exporter_mode: PrometheusExporterMode) -> PrometheusConfig {
+        PrometheusConfig {
+            listener_port: 3312,
+            pushgateway_url: "http://127.0.0.1:9091".into(),
+            push_interval_ms: Some(500),
+            exporter_mode,
+            collect_runtime_metrics: None,
+        }
+    }
+
+    #[test]
+    fn pull_transport_from_config() {
+        let transport = PrometheusTransport::from_config(&config(PrometheusExporterMode::Pull));
+        assert_eq!(transport, PrometheusTransport::Pull { port: 3312 });
+    }
+
+    #[test]
+    fn push_transport_from_config() {
+        let transport = PrometheusTransport::from_config(&config(PrometheusExporterMode::Push));
+        assert_eq!(
+            transport,
+            PrometheusTransport::Push {
+                url: "http://127.0.0.1:9091".into(),
+                interval: Duration::from_millis(500),
+            }
+        );
+    }
+
+    #[test]
+    fn both_transport_from_config() {
+        let transport = PrometheusTransport::from_config(&config(PrometheusExporterMode::Both));
+        assert_eq!(
+            transport,
+            PrometheusTransport::Both {
+                port: 3312,
+                url: "http://127.0.0.1:9091".into(),
+                interval: Duration::from_millis(500),
+            }
+        );
+    }
+
+    #[cfg(tokio_unstable)]
+    #[tokio::test]
+    async fn runtime_metrics_are_reported() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+
+        report_runtime_metrics(&Handle::current(), &recorder);
+        let rendered = handle.render();
+        for gauge in [
+            "tokio_runtime_workers",
+            "tokio_runtime_busy_duration",
+            "tokio_runtime_global_queue_depth",
+        ] {
+            assert!(rendered.contains(gauge), "{gauge} is missing: {rendered}");
+        }
+    }
 }
@@ -8,6 +8,16 @@ use tokio::time::sleep;
 
 use zksync_utils::panic_extractor::try_extract_panic_message;
 
+/// Resolves once the stop signal is received. If the stop sender is dropped without sending the signal,
+/// never resolves.
+async fn wait_for_stop_signal(mut stop_receiver: watch::Receiver<bool>) {
+    while !*stop_receiver.borrow() {
+        if stop_receiver.changed().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
 #[async_trait]
 pub trait JobProcessor: Sync + Send {
     type Job: Send + 'static;
@@ -17,6 +27,9 @@ pub trait JobProcessor: Sync + Send {
     const POLLING_INTERVAL_MS: u64 = 1000;
     const MAX_BACKOFF_MS: u64 = 60_000;
     const BACKOFF_MULTIPLIER: u64 = 2;
+    /// Maximum time to wait for the in-flight job to finish after the stop signal is received.
+    /// If the job doesn't finish in time, it is abandoned (and will be picked up by retry logic).
+    const MAX_DRAIN_TIME_MS: u64 = 5 * 60 * 1_000;
     const SERVICE_NAME: &'static str;
 
     /// Returns None when there is no pending job
@@ -39,6 +52,10 @@ pub trait JobProcessor: Sync + Send {
     /// To run indefinitely, pass `None`,
     /// To process one job, pass `Some(1)`,
     /// To process a batch, pass `Some(batch_size)`.
+    ///
+    /// Once the stop signal is received, the processor stops picking new jobs. If a job is being
+    /// processed at this moment, the processor drains: it waits for the job to finish and persists
+    /// its outcome before returning, but no longer than [`Self::MAX_DRAIN_TIME_MS`].
     async fn run(self, stop_receiver: watch::Receiver<bool>, mut iterations_left: Option<usize>)
     where
         Self: Sized,
@@ -62,9 +79,30 @@ pub trait JobProcessor: Sync + Send {
                     Self::SERVICE_NAME,
                     job_id
                 );
+                let job_id_string = format!("{job_id:?}");
                 let task = self.process_job(job, started_at).await;
 
-                self.wait_for_task(job_id, started_at, task).await
+                let drain_deadline = async {
+                    wait_for_stop_signal(stop_receiver.clone()).await;
+                    vlog::info!(
+                        "Stop signal received, finishing {} job {} before shutting down",
+                        Self::SERVICE_NAME,
+                        job_id_string
+                    );
+                    sleep(Duration::from_millis(Self::MAX_DRAIN_TIME_MS)).await;
+                };
+                tokio::select! {
+                    () = self.wait_for_task(job_id, started_at, task) => {}
+                    () = drain_deadline => {
+                        vlog::warn!(
+                            "{} job {} didn't finish in {} ms after stop signal; abandoning it",
+                            Self::SERVICE_NAME,
+                            job_id_string,
+                            Self::MAX_DRAIN_TIME_MS
+                        );
+                        return;
+                    }
+                }
             } else if iterations_left.is_some() {
                 vlog::info!("No more jobs to process. Server can stop now.");
                 return;
@@ -127,3 +165,91 @@ pub trait JobProcessor: Sync + Send {
         artifacts: Self::JobArtifacts,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct MockState {
+        picked_jobs: Mutex<Vec<u32>>,
+        saved_jobs: Mutex<Vec<u32>>,
+    }
+
+    #[derive(Debug)]
+    struct MockProcessor {
+        job_duration: Duration,
+        state: Arc<MockState>,
+    }
+
+    #[async_trait]
+    impl JobProcessor for MockProcessor {
+        type Job = u32;
+        type JobId = u32;
+        type JobArtifacts = u32;
+
+        const POLLING_INTERVAL_MS: u64 = 10;
+        const MAX_DRAIN_TIME_MS: u64 = 500;
+        const SERVICE_NAME: &'static str = "mock_processor";
+
+        async fn get_next_job(&self) -> Option<(u32, u32)> {
+            let mut picked_jobs = self.state.picked_jobs.lock().unwrap();
+            let job_id = picked_jobs.len() as u32;
+            picked_jobs.push(job_id);
+            Some((job_id, job_id))
+        }
+
+        async fn save_failure(&self, job_id: u32, _started_at: Instant, error: String) {
+            panic!("job {job_id} failed: {error}");
+        }
+
+        async fn process_job(&self, job: u32, _started_at: Instant) -> JoinHandle<u32> {
+            let job_duration = self.job_duration;
+            tokio::spawn(async move {
+                sleep(job_duration).await;
+                job
+            })
+        }
+
+        async fn save_result(&self, job_id: u32, _started_at: Instant, artifacts: u32) {
+            assert_eq!(job_id, artifacts);
+            self.state.saved_jobs.lock().unwrap().push(job_id);
+        }
+    }
+
+    async fn run_until_stopped_mid_job(job_duration: Duration) -> Arc<MockState> {
+        let state = Arc::<MockState>::default();
+        let processor = MockProcessor {
+            job_duration,
+            state: state.clone(),
+        };
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let processor_task = tokio::spawn(processor.run(stop_receiver, None));
+
+        while state.picked_jobs.lock().unwrap().is_empty() {
+            sleep(Duration::from_millis(10)).await;
+        }
+        stop_sender.send_replace(true);
+        tokio::time::timeout(Duration::from_secs(5), processor_task)
+            .await
+            .expect("processor did not stop")
+            .unwrap();
+        state
+    }
+
+    #[tokio::test]
+    async fn processor_finishes_in_flight_job_on_stop() {
+        let state = run_until_stopped_mid_job(Duration::from_millis(100)).await;
+        assert_eq!(*state.picked_jobs.lock().unwrap(), [0]);
+        assert_eq!(*state.saved_jobs.lock().unwrap(), [0]);
+    }
+
+    #[tokio::test]
+    async fn processor_abandons_in_flight_job_after_drain_timeout() {
+        let state = run_until_stopped_mid_job(Duration::from_secs(3_600)).await;
+        assert_eq!(*state.picked_jobs.lock().unwrap(), [0]);
+        assert!(state.saved_jobs.lock().unwrap().is_empty());
+    }
+}
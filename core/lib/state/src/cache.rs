@@ -5,7 +5,8 @@ use std::{hash::Hash, time::Instant};
 
 type MokaBase<K> = mini_moka::sync::Cache<K, Vec<u8>>;
 
-/// [`Cache`] implementation that uses LRU eviction policy.
+/// [`Cache`] implementation that uses LRU eviction policy. Clones of the cache share
+/// the underlying storage.
 #[derive(Debug, Clone)]
 pub struct Cache<K: Hash + Eq + Send + Sync> {
     name: &'static str,
@@ -58,6 +59,11 @@ impl<K: Hash + Eq + Send + Sync + 'static> Cache<K> {
             "method" => "insert"
         );
 
+        self.report_size();
+    }
+
+    /// Reports the number of entries and the total size of values in the cache.
+    pub fn report_size(&self) {
         metrics::gauge!("server.state_cache.len", self.cache.entry_count() as f64, "name" => self.name);
         metrics::gauge!(
             "server.state_cache.used_memory",
@@ -65,4 +71,18 @@ impl<K: Hash + Eq + Send + Sync + 'static> Cache<K> {
             "name" => self.name,
         );
     }
+
+    /// Returns keys of all entries currently present in the cache.
+    pub fn keys(&self) -> Vec<K>
+    where
+        K: Clone,
+    {
+        self.cache.iter().map(|entry| entry.key().clone()).collect()
+    }
+
+    /// Removes an entry from the cache, emulating its eviction.
+    #[cfg(test)]
+    pub(crate) fn remove(&self, key: &K) {
+        self.cache.invalidate(key);
+    }
 }
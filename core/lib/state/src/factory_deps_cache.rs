@@ -0,0 +1,241 @@
+//! Two-tier cache for smart contract bytecodes (aka factory dependencies).
+
+use std::{fs, io, path::Path, str::FromStr, sync::Arc, time::Instant};
+
+use crate::cache::Cache;
+use zksync_dal::StorageProcessor;
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError};
+use zksync_types::H256;
+
+/// Max number of bytecodes loaded from Postgres in a single query when prewarming the cache.
+const PREWARM_BATCH_SIZE: usize = 1_000;
+
+/// Cache for smart contract bytecodes.
+///
+/// The first tier is an in-memory LRU cache. Optionally, the cache can have a second, cold tier
+/// backed by the object store, which is consulted on misses in the memory tier before
+/// falling back to Postgres. Since the in-memory cache does not provide eviction hooks, bytecodes
+/// are written to the object store tier once they are loaded from Postgres; this is safe
+/// because bytecodes are immutable for a given hash.
+///
+/// Clones of the cache share the underlying storage. Total memory usage and the number of entries
+/// in the in-memory tier are reported as `server.state_cache.used_memory` and `server.state_cache.len`
+/// gauges labeled with the cache name; hence, a single cache instance shared by multiple consumers
+/// (e.g., the HTTP and WS API servers) produces a single set of gauges. The gauges are updated on inserts
+/// and by [`Self::report_size()`]; the latter should be called periodically so that evictions are reflected
+/// as well.
+#[derive(Debug, Clone)]
+pub struct FactoryDepsCache {
+    memory: Cache<H256>,
+    object_store: Option<Arc<dyn ObjectStore>>,
+}
+
+impl FactoryDepsCache {
+    /// Creates a new cache with the in-memory tier only.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an invalid cache capacity (usize) is provided.
+    pub fn new(name: &'static str, capacity_mb: usize) -> Self {
+        Self {
+            memory: Cache::new(name, capacity_mb),
+            object_store: None,
+        }
+    }
+
+    /// Enables the object store tier of the cache.
+    #[must_use]
+    pub fn with_object_store_tier(self, object_store: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            object_store: Some(object_store),
+            ..self
+        }
+    }
+
+    fn object_key(hash: H256) -> String {
+        format!("factory_dep_{hash:?}.bin")
+    }
+
+    /// Gets a bytecode from the in-memory tier.
+    pub fn get(&self, hash: &H256) -> Option<Vec<u8>> {
+        let entry = self.memory.get(hash);
+        Self::report_request("memory", entry.is_some());
+        entry
+    }
+
+    /// Gets a bytecode from the object store tier, if this tier is enabled. If the bytecode
+    /// is found, it is promoted to the in-memory tier.
+    pub async fn get_from_object_store(&self, hash: H256) -> Option<Vec<u8>> {
+        let object_store = self.object_store.as_deref()?;
+        let key = Self::object_key(hash);
+        let entry = match object_store.get_raw(Bucket::FactoryDeps, &key).await {
+            Ok(bytecode) => Some(bytecode),
+            Err(ObjectStoreError::KeyNotFound(_)) => None,
+            Err(err) => {
+                vlog::warn!("Failed getting factory dep {hash:?} from object store: {err}");
+                None
+            }
+        };
+        Self::report_request("object_store", entry.is_some());
+
+        if let Some(bytecode) = &entry {
+            self.memory.insert(hash, bytecode.clone());
+        }
+        entry
+    }
+
+    /// Inserts a bytecode into the in-memory tier.
+    pub fn insert(&self, hash: H256, bytecode: Vec<u8>) {
+        self.memory.insert(hash, bytecode);
+    }
+
+    /// Inserts a bytecode loaded from Postgres into all enabled tiers of the cache.
+    pub async fn insert_into_all_tiers(&self, hash: H256, bytecode: Vec<u8>) {
+        if let Some(object_store) = self.object_store.as_deref() {
+            let key = Self::object_key(hash);
+            let put_result = object_store
+                .put_raw(Bucket::FactoryDeps, &key, bytecode.clone())
+                .await;
+            if let Err(err) = put_result {
+                vlog::warn!("Failed putting factory dep {hash:?} to object store: {err}");
+            }
+        }
+        self.memory.insert(hash, bytecode);
+    }
+
+    /// Writes hashes of all bytecodes in the in-memory tier to the file at `path`, one hash per line.
+    /// Bytecodes themselves are not persisted; they are reloaded from Postgres
+    /// by [`Self::prewarm_from_file()`]. Returns the number of written hashes.
+    ///
+    /// # Errors
+    ///
+    /// Propagates I/O errors.
+    pub fn dump_keys(&self, path: &Path) -> io::Result<usize> {
+        let hashes = self.memory.keys();
+        let contents: String = hashes.iter().map(|hash| format!("{hash:?}\n")).collect();
+        fs::write(path, contents)?;
+        Ok(hashes.len())
+    }
+
+    /// Loads bytecodes for hashes previously written by [`Self::dump_keys()`] from Postgres
+    /// into the in-memory tier. Hashes missing from Postgres (e.g., after a revert) are skipped.
+    /// If the file does not exist (e.g., on the first start), the cache is left empty.
+    /// Returns the number of loaded bytecodes.
+    ///
+    /// Prewarming latency is reported as the `server.factory_deps_cache.prewarm_latency` histogram.
+    ///
+    /// # Errors
+    ///
+    /// Propagates I/O errors, and returns an error if the file contains an invalid hash.
+    pub async fn prewarm_from_file(
+        &self,
+        path: &Path,
+        storage: &mut StorageProcessor<'_>,
+    ) -> io::Result<usize> {
+        let started_at = Instant::now();
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+        let hashes = contents
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                H256::from_str(line).map_err(|err| {
+                    let message = format!("invalid factory dep hash {line:?}: {err}");
+                    io::Error::new(io::ErrorKind::InvalidData, message)
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut loaded_count = 0;
+        for chunk in hashes.chunks(PREWARM_BATCH_SIZE) {
+            let bytecodes = storage.storage_dal().get_factory_dep_bytecodes(chunk).await;
+            loaded_count += bytecodes.len();
+            for (hash, bytecode) in bytecodes {
+                self.memory.insert(hash, bytecode);
+            }
+        }
+        metrics::histogram!(
+            "server.factory_deps_cache.prewarm_latency",
+            started_at.elapsed()
+        );
+        Ok(loaded_count)
+    }
+
+    /// Reports memory usage and the number of entries in the in-memory tier of the cache.
+    pub fn report_size(&self) {
+        self.memory.report_size();
+    }
+
+    fn report_request(tier: &'static str, is_hit: bool) {
+        metrics::increment_counter!(
+            "server.factory_deps_cache.requests",
+            "tier" => tier,
+            "kind" => if is_hit { "hit" } else { "miss" }
+        );
+    }
+
+    #[cfg(test)]
+    pub(crate) fn evict_from_memory(&self, hash: &H256) {
+        self.memory.remove(hash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use db_test_macro::db_test;
+    use zksync_dal::ConnectionPool;
+    use zksync_types::MiniblockNumber;
+
+    use super::*;
+    use crate::test_utils::{create_miniblock, prepare_postgres};
+
+    #[db_test]
+    async fn factory_deps_cache_is_prewarmed_from_dumped_keys(pool: ConnectionPool) {
+        let mut storage = pool.access_storage().await;
+        prepare_postgres(&mut storage).await;
+        create_miniblock(&mut storage, MiniblockNumber(1), vec![]).await;
+        let contracts: HashMap<_, _> = (1..=3)
+            .map(|byte| (H256::repeat_byte(byte), vec![byte; 32]))
+            .collect();
+        storage
+            .storage_dal()
+            .insert_factory_deps(MiniblockNumber(1), &contracts)
+            .await;
+
+        let cache = FactoryDepsCache::new("test_factory_deps_cache", 128);
+        for (&hash, bytecode) in contracts.iter().take(2) {
+            cache.insert(hash, bytecode.clone());
+        }
+        // The hash not present in Postgres should be skipped during prewarming.
+        cache.insert(H256::repeat_byte(0xff), vec![0xff; 32]);
+        let dump_dir = tempfile::TempDir::new().expect("failed creating temp dir");
+        let dump_path = dump_dir.path().join("factory_deps_cache_keys");
+        assert_eq!(cache.dump_keys(&dump_path).unwrap(), 3);
+
+        // Emulate restarting the node.
+        let cache = FactoryDepsCache::new("test_factory_deps_cache", 128);
+        let loaded_count = cache
+            .prewarm_from_file(&dump_path, &mut storage)
+            .await
+            .unwrap();
+        assert_eq!(loaded_count, 2);
+        let cached_count = contracts
+            .iter()
+            .filter(|&(hash, bytecode)| cache.get(hash).as_ref() == Some(bytecode))
+            .count();
+        assert_eq!(cached_count, 2);
+        assert_eq!(cache.get(&H256::repeat_byte(0xff)), None);
+
+        let missing_path = dump_dir.path().join("missing");
+        let loaded_count = cache
+            .prewarm_from_file(&missing_path, &mut storage)
+            .await
+            .unwrap();
+        assert_eq!(loaded_count, 0);
+    }
+}
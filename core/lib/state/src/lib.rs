@@ -18,6 +18,7 @@ use zksync_types::{
 };
 
 mod cache;
+mod factory_deps_cache;
 mod in_memory;
 mod postgres;
 mod rocksdb;
@@ -26,8 +27,8 @@ mod storage_view;
 mod test_utils;
 
 pub use self::{
+    factory_deps_cache::FactoryDepsCache,
     in_memory::InMemoryStorage,
-    postgres::FactoryDepsCache,
     postgres::PostgresStorage,
     rocksdb::RocksdbStorage,
     storage_view::{StorageView, StorageViewMetrics},
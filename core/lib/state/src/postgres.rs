@@ -1,13 +1,9 @@
 use tokio::{runtime::Handle, time::Instant};
 
-use crate::cache::Cache;
-use crate::ReadStorage;
+use crate::{FactoryDepsCache, ReadStorage};
 use zksync_dal::StorageProcessor;
 use zksync_types::{L1BatchNumber, MiniblockNumber, StorageKey, StorageValue, H256};
 
-/// [`FactoryDepsCache`] type alias for smart contract source code cache
-pub type FactoryDepsCache = Cache<H256>;
-
 /// [`ReadStorage`] implementation backed by the Postgres database.
 #[derive(Debug)]
 pub struct PostgresStorage<'a> {
@@ -107,27 +103,33 @@ impl ReadStorage for PostgresStorage<'_> {
             .as_ref()
             .and_then(|cache| cache.get(&hash));
         let result = cached_value.or_else(|| {
+            let cache = self.factory_deps_cache.as_ref();
             let mut dal = self.connection.storage_web3_dal();
-            let value = self
-                .rt_handle
-                .block_on(async {
-                    metrics::histogram!(
-                        "state.postgres_storage.enter_context",
-                        started_at.elapsed(),
-                        "method" => "load_factory_dep"
-                    );
-                    dal.get_factory_dep_unchecked(hash, self.block_number).await
-                })
-                .unwrap();
-
-            if let Some(cache) = &self.factory_deps_cache {
-                // If we receive None, we won't cache it.
-                if let Some(dep) = value.clone() {
-                    cache.insert(hash, dep);
+            let block_number = self.block_number;
+            self.rt_handle.block_on(async {
+                metrics::histogram!(
+                    "state.postgres_storage.enter_context",
+                    started_at.elapsed(),
+                    "method" => "load_factory_dep"
+                );
+                if let Some(cache) = cache {
+                    if let Some(dep) = cache.get_from_object_store(hash).await {
+                        return Some(dep);
+                    }
                 }
-            };
 
-            value
+                let value = dal
+                    .get_factory_dep_unchecked(hash, block_number)
+                    .await
+                    .unwrap();
+                if let Some(cache) = cache {
+                    // If we receive None, we won't cache it.
+                    if let Some(dep) = value.clone() {
+                        cache.insert_into_all_tiers(hash, dep).await;
+                    }
+                }
+                value
+            })
         });
 
         metrics::histogram!(
@@ -142,10 +144,11 @@ impl ReadStorage for PostgresStorage<'_> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, sync::Arc};
 
     use db_test_macro::db_test;
     use zksync_dal::ConnectionPool;
+    use zksync_object_store::{Bucket, ObjectStore, ObjectStoreFactory};
 
     use super::*;
     use crate::test_utils::{
@@ -365,4 +368,116 @@ mod tests {
         .await
         .unwrap();
     }
+
+    fn test_factory_deps_cache_shared_between_storages(pool: &ConnectionPool, rt_handle: &Handle) {
+        let mut connection = rt_handle.block_on(pool.access_storage());
+        rt_handle.block_on(prepare_postgres(&mut connection));
+        rt_handle.block_on(create_miniblock(
+            &mut connection,
+            MiniblockNumber(1),
+            vec![],
+        ));
+        let hash = H256::repeat_byte(1);
+        let contracts = HashMap::from([(hash, vec![1, 2, 3])]);
+        rt_handle.block_on(
+            connection
+                .storage_dal()
+                .insert_factory_deps(MiniblockNumber(1), &contracts),
+        );
+
+        // Emulate the HTTP and WS API servers, each of which receives a clone of the same cache.
+        let cache = FactoryDepsCache::new("test_factory_deps_cache", 128);
+        let http_cache = cache.clone();
+        let ws_cache = cache;
+
+        let mut http_storage =
+            PostgresStorage::new(rt_handle.clone(), connection, MiniblockNumber(1), true)
+                .with_factory_deps_cache(http_cache);
+        assert_eq!(http_storage.load_factory_dep(hash), Some(vec![1, 2, 3]));
+        // Remove the dependency from Postgres, so that it can only be served by the cache.
+        rt_handle.block_on(
+            http_storage
+                .connection
+                .storage_dal()
+                .rollback_factory_deps(MiniblockNumber(0)),
+        );
+        drop(http_storage);
+
+        let connection = rt_handle.block_on(pool.access_storage());
+        let mut ws_storage =
+            PostgresStorage::new(rt_handle.clone(), connection, MiniblockNumber(1), true)
+                .with_factory_deps_cache(ws_cache);
+        assert_eq!(ws_storage.load_factory_dep(hash), Some(vec![1, 2, 3]));
+    }
+
+    #[db_test]
+    async fn factory_deps_cache_shared_between_storages(pool: ConnectionPool) {
+        let handle = Handle::current();
+        tokio::task::spawn_blocking(move || {
+            test_factory_deps_cache_shared_between_storages(&pool, &handle);
+        })
+        .await
+        .unwrap();
+    }
+
+    fn test_factory_deps_cache_object_store_tier(
+        pool: &ConnectionPool,
+        rt_handle: &Handle,
+        object_store: Arc<dyn ObjectStore>,
+    ) {
+        let mut connection = rt_handle.block_on(pool.access_storage());
+        rt_handle.block_on(prepare_postgres(&mut connection));
+        rt_handle.block_on(create_miniblock(
+            &mut connection,
+            MiniblockNumber(1),
+            vec![],
+        ));
+        let hash = H256::repeat_byte(1);
+        let contracts = HashMap::from([(hash, vec![1, 2, 3])]);
+        rt_handle.block_on(
+            connection
+                .storage_dal()
+                .insert_factory_deps(MiniblockNumber(1), &contracts),
+        );
+
+        let cache = FactoryDepsCache::new("test_factory_deps_cache", 128)
+            .with_object_store_tier(object_store.clone());
+        let mut storage =
+            PostgresStorage::new(rt_handle.clone(), connection, MiniblockNumber(1), true)
+                .with_factory_deps_cache(cache.clone());
+        // The dependency is loaded from Postgres and is put to both cache tiers.
+        assert_eq!(storage.load_factory_dep(hash), Some(vec![1, 2, 3]));
+        let stored_dep = rt_handle
+            .block_on(
+                object_store.get_raw(Bucket::FactoryDeps, &format!("factory_dep_{hash:?}.bin")),
+            )
+            .unwrap();
+        assert_eq!(stored_dep, [1, 2, 3]);
+
+        // Remove the dependency from Postgres, so that it can only be served by the cache.
+        rt_handle.block_on(
+            storage
+                .connection
+                .storage_dal()
+                .rollback_factory_deps(MiniblockNumber(0)),
+        );
+        cache.evict_from_memory(&hash);
+        assert_eq!(cache.get(&hash), None);
+
+        assert_eq!(storage.load_factory_dep(hash), Some(vec![1, 2, 3]));
+        // The dependency should be promoted to the in-memory tier.
+        assert_eq!(cache.get(&hash), Some(vec![1, 2, 3]));
+    }
+
+    #[db_test]
+    async fn factory_deps_cache_object_store_tier(pool: ConnectionPool) {
+        let handle = Handle::current();
+        let object_store: Arc<dyn ObjectStore> =
+            ObjectStoreFactory::mock().create_store().await.into();
+        tokio::task::spawn_blocking(move || {
+            test_factory_deps_cache_object_store_tier(&pool, &handle, object_store);
+        })
+        .await
+        .unwrap();
+    }
 }
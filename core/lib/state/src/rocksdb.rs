@@ -77,21 +77,44 @@ impl RocksdbStorage {
     /// Panics if the local L1 batch number is greater than the last sealed L1 batch number
     /// in Postgres.
     pub async fn update_from_postgres(&mut self, conn: &mut StorageProcessor<'_>) {
-        let stage_started_at: Instant = Instant::now();
         let latest_l1_batch_number = conn.blocks_dal().get_sealed_block_number().await;
+        self.update_from_postgres_until(conn, latest_l1_batch_number)
+            .await;
+    }
+
+    /// Synchronizes this storage with Postgres using the provided connection, applying L1 batches
+    /// up to and including `last_l1_batch_number`. This allows to use the storage for re-executing
+    /// L1 batches that are already sealed in Postgres.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the local L1 batch number is greater than `last_l1_batch_number + 1`, or
+    /// if `last_l1_batch_number` is not sealed in Postgres.
+    pub async fn update_from_postgres_until(
+        &mut self,
+        conn: &mut StorageProcessor<'_>,
+        last_l1_batch_number: L1BatchNumber,
+    ) {
+        let stage_started_at: Instant = Instant::now();
+        let sealed_l1_batch_number = conn.blocks_dal().get_sealed_block_number().await;
+        assert!(
+            last_l1_batch_number <= sealed_l1_batch_number,
+            "Requested L1 batch {last_l1_batch_number} is not sealed in Postgres; \
+             the last sealed L1 batch is {sealed_l1_batch_number}"
+        );
         vlog::debug!(
             "loading storage for l1 batch number {}",
-            latest_l1_batch_number.0
+            last_l1_batch_number.0
         );
 
         let mut current_l1_batch_number = self.l1_batch_number().0;
         assert!(
-            current_l1_batch_number <= latest_l1_batch_number.0 + 1,
+            current_l1_batch_number <= last_l1_batch_number.0 + 1,
             "L1 batch number in state keeper cache ({current_l1_batch_number}) is greater than \
-             the last sealed L1 batch number in Postgres ({latest_l1_batch_number})"
+             the requested L1 batch number ({last_l1_batch_number})"
         );
 
-        while current_l1_batch_number <= latest_l1_batch_number.0 {
+        while current_l1_batch_number <= last_l1_batch_number.0 {
             vlog::debug!("loading state changes for l1 batch {current_l1_batch_number}");
             let storage_logs = conn
                 .storage_logs_dal()
@@ -346,6 +369,42 @@ mod tests {
         }
     }
 
+    #[db_test]
+    async fn rocksdb_storage_syncing_with_postgres_until_l1_batch(pool: ConnectionPool) {
+        let mut conn = pool.access_storage().await;
+        prepare_postgres(&mut conn).await;
+        let storage_logs = gen_storage_logs(20..40);
+        create_miniblock(&mut conn, MiniblockNumber(1), storage_logs.clone()).await;
+        create_l1_batch(&mut conn, L1BatchNumber(1), &storage_logs).await;
+        let new_storage_logs = gen_storage_logs(50..60);
+        create_miniblock(&mut conn, MiniblockNumber(2), new_storage_logs.clone()).await;
+        create_l1_batch(&mut conn, L1BatchNumber(2), &new_storage_logs).await;
+
+        let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
+        let mut storage = RocksdbStorage::new(dir.path());
+        storage
+            .update_from_postgres_until(&mut conn, L1BatchNumber(1))
+            .await;
+
+        {
+            let mut storage = &storage;
+            assert_eq!(storage.l1_batch_number(), L1BatchNumber(2));
+            for log in &storage_logs {
+                assert_eq!(storage.read_value(&log.key), log.value);
+            }
+            for log in &new_storage_logs {
+                assert_eq!(storage.read_value(&log.key), H256::zero());
+            }
+        }
+
+        storage.update_from_postgres(&mut conn).await;
+        let mut storage = &storage;
+        assert_eq!(storage.l1_batch_number(), L1BatchNumber(3));
+        for log in &new_storage_logs {
+            assert_eq!(storage.read_value(&log.key), log.value);
+        }
+    }
+
     async fn insert_factory_deps(
         conn: &mut StorageProcessor<'_>,
         miniblock_number: MiniblockNumber,
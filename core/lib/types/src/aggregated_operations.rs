@@ -26,6 +26,25 @@ impl BlocksCommitOperation {
         vec![stored_block_info, Token::Array(blocks_to_commit)]
     }
 
+    /// Same as [`Self::get_eth_tx_args()`], but with the pubdata of the committed blocks left out.
+    /// The pubdata is returned by [`Self::pubdata()`].
+    pub fn get_eth_tx_args_without_pubdata(&self) -> Vec<Token> {
+        let stored_block_info = self.last_committed_block.l1_header_data();
+        let blocks_to_commit = self
+            .blocks
+            .iter()
+            .map(|block| block.l1_commit_data_without_pubdata())
+            .collect();
+
+        vec![stored_block_info, Token::Array(blocks_to_commit)]
+    }
+
+    /// Returns ABI-encoded pubdata of the committed blocks.
+    pub fn pubdata(&self) -> Vec<u8> {
+        let pubdata = self.blocks.iter().map(|block| block.l1_pubdata()).collect();
+        crate::ethabi::encode(&[Token::Array(pubdata)])
+    }
+
     pub fn block_range(&self) -> (L1BatchNumber, L1BatchNumber) {
         let BlocksCommitOperation { blocks, .. } = self;
         (
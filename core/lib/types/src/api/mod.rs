@@ -6,7 +6,7 @@ pub use crate::transaction_request::{
 };
 use crate::vm_trace::{Call, CallType};
 use crate::web3::types::{AccessList, Index, H2048};
-use crate::{Address, MiniblockNumber};
+use crate::{Address, L1BatchNumber, MiniblockNumber};
 use chrono::{DateTime, Utc};
 pub use zksync_basic_types::web3::{
     self, ethabi,
@@ -523,6 +523,30 @@ pub struct TransactionDetails {
     pub eth_execute_tx_hash: Option<H256>,
 }
 
+/// Metadata of the L1 batch that includes a certain miniblock.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchMetadata {
+    pub number: L1BatchNumber,
+    pub timestamp: u64,
+    /// Hashes of the L1 transactions are only set once the corresponding transaction is confirmed.
+    pub commit_tx_hash: Option<H256>,
+    pub prove_tx_hash: Option<H256>,
+    pub execute_tx_hash: Option<H256>,
+}
+
+/// Protocol version information as of the latest sealed L1 batch.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolVersionInfo {
+    /// Version ID, i.e., the number of distinct sets of base system contracts used by L1 batches
+    /// up to and including `l1_batch_number`, starting from 1 for genesis.
+    pub version_id: u32,
+    pub l1_batch_number: L1BatchNumber,
+    pub bootloader_code_hash: H256,
+    pub default_account_code_hash: H256,
+}
+
 #[derive(Debug, Clone)]
 pub struct GetLogsFilter {
     pub from_block: MiniblockNumber,
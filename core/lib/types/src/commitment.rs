@@ -125,7 +125,33 @@ impl BlockWithMetadata {
     }
 
     pub fn l1_commit_data(&self) -> Token {
-        Token::Tuple(vec![
+        let mut fields = self.l1_commit_data_fields();
+        fields.extend(self.l1_pubdata_fields());
+        Token::Tuple(fields)
+    }
+
+    /// Same as [`Self::l1_commit_data()`], but with all pubdata fields left empty. Used when pubdata
+    /// is posted to L1 separately (e.g., in EIP-4844 blobs).
+    pub fn l1_commit_data_without_pubdata(&self) -> Token {
+        let mut fields = self.l1_commit_data_fields();
+        fields.extend([
+            Token::Bytes(vec![]),
+            Token::Bytes(vec![]),
+            Token::Bytes(vec![]),
+            Token::Array(vec![]),
+            Token::Array(vec![]),
+        ]);
+        Token::Tuple(fields)
+    }
+
+    /// Returns pubdata of the block, i.e. the fields of [`Self::l1_commit_data()`] that are left empty
+    /// by [`Self::l1_commit_data_without_pubdata()`].
+    pub fn l1_pubdata(&self) -> Token {
+        Token::Tuple(self.l1_pubdata_fields())
+    }
+
+    fn l1_commit_data_fields(&self) -> Vec<Token> {
+        vec![
             Token::Uint(U256::from(self.header.number.0)),
             Token::Uint(U256::from(self.header.timestamp)),
             Token::Uint(U256::from(self.metadata.rollup_last_leaf_index)),
@@ -138,6 +164,11 @@ impl BlockWithMetadata {
                     .as_bytes()
                     .to_vec(),
             ),
+        ]
+    }
+
+    fn l1_pubdata_fields(&self) -> Vec<Token> {
+        vec![
             Token::Bytes(self.metadata.initial_writes_compressed.clone()),
             Token::Bytes(self.metadata.repeated_writes_compressed.clone()),
             Token::Bytes(self.metadata.l2_l1_messages_compressed.clone()),
@@ -154,12 +185,18 @@ impl BlockWithMetadata {
                     .map(|bytecode| Token::Bytes(bytecode.to_vec()))
                     .collect(),
             ),
-        ])
+        ]
     }
 
     pub fn l1_commit_data_size(&self) -> usize {
         crate::ethabi::encode(&[Token::Array(vec![self.l1_commit_data()])]).len()
     }
+
+    /// Same as [`Self::l1_commit_data_size()`], but for [`Self::l1_commit_data_without_pubdata()`].
+    pub fn l1_commit_data_without_pubdata_size(&self) -> usize {
+        let commit_data = self.l1_commit_data_without_pubdata();
+        crate::ethabi::encode(&[Token::Array(vec![commit_data])]).len()
+    }
 }
 
 impl SerializeCommitment for L2ToL1Log {
@@ -7,6 +7,8 @@ pub struct EthTx {
     pub nonce: Nonce,
     pub contract_address: Address,
     pub raw_tx: Vec<u8>,
+    /// Data posted in EIP-4844 blobs alongside `raw_tx`. If set, the transaction is sent as a blob transaction.
+    pub blob_data: Option<Vec<u8>>,
     pub tx_type: AggregatedActionType,
     pub created_at_timestamp: u64,
     pub predicted_gas_cost: u64,
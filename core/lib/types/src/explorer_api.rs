@@ -134,6 +134,19 @@ pub struct BlockPageItem {
     pub timestamp: u64,
 }
 
+/// Lightweight miniblock summary used to render explorer block lists.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockListItem {
+    pub number: MiniblockNumber,
+    pub hash: H256,
+    pub timestamp: u64,
+    pub l1_tx_count: usize,
+    pub l2_tx_count: usize,
+    /// `None` if the miniblock is not yet included into an L1 batch.
+    pub l1_batch_number: Option<L1BatchNumber>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionResponse {
@@ -309,6 +322,26 @@ pub struct L1BatchDetails {
     pub base_system_contracts_hashes: BaseSystemContractsHashes,
 }
 
+/// Hashes of the L1 transactions processing an L1 batch. Each hash is `None` until the corresponding
+/// transaction is confirmed on L1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1TxHashes {
+    pub commit: Option<H256>,
+    pub prove: Option<H256>,
+    pub execute: Option<H256>,
+}
+
+/// L1 confirmation timestamps of the transactions processing an L1 batch. Each timestamp is `None`
+/// until the corresponding transaction is confirmed on L1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchStageTimestamps {
+    pub committed_at: Option<DateTime<Utc>>,
+    pub proven_at: Option<DateTime<Utc>>,
+    pub executed_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct L1BatchPageItem {
@@ -50,6 +50,20 @@ impl Fee {
     }
 }
 
+/// Breakdown of the estimated transaction fee into its components. All values are denominated
+/// in wei; the components sum up to [`Fee::max_total_fee()`] of the corresponding estimate.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeBreakdown {
+    /// Cost of the gas spent on executing the transaction body on L2.
+    pub l2_gas_cost: U256,
+    /// Cost of publishing the data of the transaction (e.g., its storage writes, L2-to-L1 messages
+    /// and factory deps) on L1.
+    pub l1_pubdata_cost: U256,
+    /// Share of the batch overhead paid by the transaction.
+    pub overhead: U256,
+}
+
 /// Returns how many slots would ABI-encoding of the transaction with such parameters take
 pub fn encoding_len(
     data_len: u64,
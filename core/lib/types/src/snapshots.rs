@@ -0,0 +1,48 @@
+//! Types related to state snapshots used to bootstrap nodes without replaying the whole chain.
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+use crate::{
+    block::{L1BatchHeader, MiniblockHeader},
+    commitment::BlockMetadata,
+    L1BatchNumber, MiniblockNumber, StorageLog, H256,
+};
+
+/// Storage logs comprising the full storage state as of the end of an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageLogsSnapshot {
+    pub l1_batch_number: L1BatchNumber,
+    /// Last miniblock included into the L1 batch.
+    pub miniblock_number: MiniblockNumber,
+    /// Latest value for each storage slot written to before or at `miniblock_number`,
+    /// ordered by the hashed key.
+    pub storage_logs: Vec<StorageLog>,
+    /// Bytecodes of all factory deps deployed before or at `miniblock_number`, keyed by the bytecode hash.
+    pub factory_deps: HashMap<H256, Vec<u8>>,
+}
+
+/// Manifest of a state snapshot stored in the object store alongside [`StorageLogsSnapshot`].
+/// Contains the headers necessary to continue syncing from the snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub l1_batch_header: L1BatchHeader,
+    /// Metadata of the L1 batch, including its root hash, as computed by the Merkle tree.
+    pub l1_batch_metadata: BlockMetadata,
+    /// Root hash of the previous L1 batch.
+    pub previous_l1_batch_root_hash: H256,
+    /// Header of the last miniblock in the L1 batch.
+    pub miniblock_header: MiniblockHeader,
+    /// Object store key of the [`StorageLogsSnapshot`].
+    pub storage_logs_url: String,
+}
+
+/// Metadata of a state snapshot recorded in Postgres.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotMetadata {
+    pub l1_batch_number: L1BatchNumber,
+    pub storage_logs_count: u64,
+    /// Object store key of the [`StorageLogsSnapshot`].
+    pub storage_logs_url: String,
+}
@@ -1,16 +1,22 @@
+use std::any::Any;
+
 use tokio::task::JoinError;
 
 pub fn try_extract_panic_message(err: JoinError) -> String {
     if err.is_panic() {
-        let panic = err.into_panic();
-        if let Some(panic_string) = panic.downcast_ref::<&'static str>() {
-            panic_string.to_string()
-        } else if let Some(panic_string) = panic.downcast_ref::<String>() {
-            panic_string.to_string()
-        } else {
-            "Unknown panic".to_string()
-        }
+        extract_panic_message(err.into_panic())
     } else {
         "Cancelled task".to_string()
     }
 }
+
+/// Extracts the message from a panic payload, e.g. one returned by [`std::panic::catch_unwind()`].
+pub fn extract_panic_message(panic: Box<dyn Any + Send>) -> String {
+    if let Some(panic_string) = panic.downcast_ref::<&'static str>() {
+        panic_string.to_string()
+    } else if let Some(panic_string) = panic.downcast_ref::<String>() {
+        panic_string.to_string()
+    } else {
+        "Unknown panic".to_string()
+    }
+}
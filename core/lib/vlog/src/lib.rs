@@ -12,7 +12,7 @@
 //! https://docs.sentry.io/platforms/rust/
 //!
 
-use std::{borrow::Cow, str::FromStr};
+use std::{borrow::Cow, str::FromStr, sync::Mutex};
 
 use opentelemetry::sdk::{resource::Resource, trace::Sampler};
 use opentelemetry::trace::{TraceContextExt, TraceId};
@@ -22,7 +22,8 @@ use sentry::protocol::Event;
 use sentry::{types::Dsn, ClientInitGuard, ClientOptions};
 use std::backtrace::Backtrace;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::filter::{EnvFilter, ParseError};
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, Registry};
 
 pub use chrono as __chrono;
 pub use sentry as __sentry;
@@ -167,6 +168,38 @@ fn get_sampling_ratio() -> f64 {
         .unwrap_or(DEFAULT_SAMPLING_RATIO)
 }
 
+/// Handle allowing to replace the log filter installed by [`init()`].
+static FILTER_HANDLE: Mutex<Option<reload::Handle<EnvFilter, Registry>>> = Mutex::new(None);
+
+/// Adds log filter `directives` (e.g., `zksync_core::eth_watch=trace`) on top of `filter`.
+/// More specific directives take precedence, so the added directives override the level
+/// for their targets only.
+fn with_directives<'a>(
+    filter: EnvFilter,
+    directives: impl IntoIterator<Item = &'a str>,
+) -> Result<EnvFilter, ParseError> {
+    directives
+        .into_iter()
+        .try_fold(filter, |filter, directive| {
+            Ok(filter.add_directive(directive.parse()?))
+        })
+}
+
+/// Layers log filter `directives` (e.g., `zksync_core::eth_watch=trace`) on top of the global filter
+/// specified by the `RUST_LOG` env variable. Replaces directives set by the previous call, if any.
+/// Has no effect if logging was not initialized with [`init()`].
+pub fn set_log_directives<'a>(
+    directives: impl IntoIterator<Item = &'a str>,
+) -> Result<(), ParseError> {
+    let filter = with_directives(EnvFilter::from_default_env(), directives)?;
+    if let Some(handle) = &*FILTER_HANDLE.lock().unwrap() {
+        handle
+            .reload(filter)
+            .expect("global subscriber is never dropped");
+    }
+    Ok(())
+}
+
 /// Initialize logging with tracing and set up log format
 pub fn init() {
     let log_format = std::env::var("MISC_LOG_FORMAT").unwrap_or_else(|_| "plain".to_string());
@@ -204,18 +237,20 @@ pub fn init() {
             .unwrap();
         tracing_opentelemetry::layer().with_tracer(tracer)
     });
+    let (filter, filter_handle) = reload::Layer::new(EnvFilter::from_default_env());
+    *FILTER_HANDLE.lock().unwrap() = Some(filter_handle);
     match log_format.as_str() {
         "plain" => {
             if let Some(opentelemetry) = opentelemetry {
                 tracing_subscriber::registry()
+                    .with(filter)
                     .with(opentelemetry)
                     .with(fmt::Layer::default())
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
             } else {
                 tracing_subscriber::registry()
+                    .with(filter)
                     .with(fmt::Layer::default())
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
             }
         }
@@ -225,14 +260,14 @@ pub fn init() {
             install_pretty_panic_hook();
             if let Some(opentelemetry) = opentelemetry {
                 tracing_subscriber::registry()
+                    .with(filter)
                     .with(opentelemetry)
                     .with(fmt::Layer::default().with_timer(timer).json())
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
             } else {
                 tracing_subscriber::registry()
+                    .with(filter)
                     .with(fmt::Layer::default().with_timer(timer).json())
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
             }
         }
@@ -325,3 +360,62 @@ impl sentry::Integration for TraceIdToSentry {
         Some(event)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tracing::{Event, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer};
+
+    use std::sync::Arc;
+
+    use super::*;
+
+    /// Layer recording targets and levels of all events passing through the filter.
+    #[derive(Debug, Default, Clone)]
+    struct EventRecorder(Arc<Mutex<Vec<(String, tracing::Level)>>>);
+
+    impl<S: Subscriber> Layer<S> for EventRecorder {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let metadata = event.metadata();
+            let record = (metadata.target().to_owned(), *metadata.level());
+            self.0.lock().unwrap().push(record);
+        }
+    }
+
+    #[test]
+    fn directives_override_global_level_for_their_targets() {
+        let filter = with_directives(
+            EnvFilter::new("info"),
+            ["zksync_core::metadata_calculator=debug"],
+        )
+        .unwrap();
+        let recorder = EventRecorder::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(filter)
+            .with(recorder.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(target: "zksync_core::metadata_calculator::updater", "tree");
+            tracing::trace!(target: "zksync_core::metadata_calculator", "tree");
+            tracing::debug!(target: "zksync_core::eth_watch", "eth_watch");
+            tracing::info!(target: "zksync_core::eth_watch", "eth_watch");
+        });
+
+        let records = recorder.0.lock().unwrap();
+        assert_eq!(
+            *records,
+            [
+                (
+                    "zksync_core::metadata_calculator::updater".to_owned(),
+                    tracing::Level::DEBUG
+                ),
+                ("zksync_core::eth_watch".to_owned(), tracing::Level::INFO),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_directive_is_rejected() {
+        with_directives(EnvFilter::new("info"), ["zksync_core=not_a_level"]).unwrap_err();
+    }
+}
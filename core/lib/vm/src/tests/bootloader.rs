@@ -2140,6 +2140,8 @@ async fn test_require_eip712() {
             access_list: None,
             max_fee_per_gas: U256::from(1000000000),
             max_priority_fee_per_gas: U256::from(1000000000),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
         };
         let txn = pk_signer.sign_transaction(raw_tx).await.unwrap();
 
@@ -2170,6 +2172,8 @@ async fn test_require_eip712() {
             access_list: None,
             max_fee_per_gas: U256::from(1000000000),
             max_priority_fee_per_gas: U256::from(1000000000),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
         };
 
         let aa_txn = pk_signer.sign_transaction(aa_raw_tx).await.unwrap();
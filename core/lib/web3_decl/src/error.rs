@@ -33,6 +33,8 @@ pub enum Web3Error {
     NotImplemented,
     #[error("Query returned more than {0} results. Try with this block range [{1:#x}, {2:#x}].")]
     LogsLimitExceeded(usize, u32, u32),
+    #[error("Block range is too wide; query at most {0} blocks at once")]
+    LogsBlockRangeTooWide(u32),
     #[error("invalid filter: if blockHash is supplied fromBlock and toBlock must not be")]
     InvalidFilterBlockHash,
 }
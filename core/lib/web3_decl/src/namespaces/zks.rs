@@ -2,12 +2,12 @@ use crate::types::Token;
 use bigdecimal::BigDecimal;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use std::collections::HashMap;
-use zksync_types::api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails};
+use zksync_types::api::{BridgeAddresses, L2ToL1LogProof, ProtocolVersionInfo, TransactionDetails};
 use zksync_types::transaction_request::CallRequest;
 use zksync_types::{
     api::U64,
     explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
+    fee::{Fee, FeeBreakdown},
     Address, H256, U256,
 };
 use zksync_types::{L1BatchNumber, MiniblockNumber};
@@ -28,6 +28,9 @@ pub trait ZksNamespace {
     #[method(name = "estimateFee")]
     async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee>;
 
+    #[method(name = "estimateFeeDetailed")]
+    async fn estimate_fee_detailed(&self, req: CallRequest) -> RpcResult<FeeBreakdown>;
+
     #[method(name = "estimateGasL1ToL2")]
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256>;
 
@@ -83,6 +86,9 @@ pub trait ZksNamespace {
     #[method(name = "getTransactionDetails")]
     async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>>;
 
+    #[method(name = "getTransactionAge")]
+    async fn get_transaction_age(&self, hash: H256) -> RpcResult<Option<U64>>;
+
     #[method(name = "getRawBlockTransactions")]
     async fn get_raw_block_transactions(
         &self,
@@ -98,4 +104,7 @@ pub trait ZksNamespace {
 
     #[method(name = "getL1GasPrice")]
     async fn get_l1_gas_price(&self) -> RpcResult<U64>;
+
+    #[method(name = "getProtocolVersion")]
+    async fn get_protocol_version(&self) -> RpcResult<ProtocolVersionInfo>;
 }
@@ -40,11 +40,10 @@ async fn main() -> anyhow::Result<()> {
     if let Some(prometheus_config) = prometheus_config {
         vlog::info!("Starting prometheus exporter with config {prometheus_config:?}");
         tokio::spawn(prometheus_exporter::run_prometheus_exporter(
-            prometheus_config.listener_port,
-            Some((
-                prometheus_config.pushgateway_url.clone(),
-                prometheus_config.push_interval(),
-            )),
+            prometheus_exporter::PrometheusTransport::Push {
+                url: prometheus_config.pushgateway_url.clone(),
+                interval: prometheus_config.push_interval(),
+            },
         ));
     } else {
         vlog::info!("Starting without prometheus exporter");
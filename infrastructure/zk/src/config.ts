@@ -20,6 +20,7 @@ const CONFIG_FILES = [
     'rust.toml',
     'private.toml',
     'fetcher.toml',
+    'fee_policy.toml',
     'witness_generator.toml',
     'circuit_synthesizer.toml',
     'prover_group.toml',